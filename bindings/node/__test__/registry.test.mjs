@@ -0,0 +1,34 @@
+import assert from "node:assert/strict";
+import { createRequire } from "node:module";
+import { test } from "node:test";
+
+const { Registry } = createRequire(import.meta.url)("../index.js");
+
+const registry = Registry.fromBundle(
+  JSON.stringify({
+    username: { type: "string", minLength: 3 },
+    age: { type: "integer" },
+  }),
+);
+
+test("validate returns the errors", () => {
+  assert.deepEqual(registry.validate("username", "ada"), []);
+  const [error] = registry.validate("username", "ab");
+  assert.equal(error.code, "min_length");
+  assert.equal(error.path, "");
+  assert.throws(() => registry.validate("missing", 1), /not found/);
+});
+
+test("validateAsync validates on a worker thread", async () => {
+  const errors = await registry.validateAsync("age", '"seven"');
+  assert.deepEqual(
+    errors.map((e) => e.code),
+    ["invalid_type"],
+  );
+  await assert.rejects(registry.validateAsync("age", "{"), /not valid JSON/);
+});
+
+test("loadBundle reports invalid schemas", () => {
+  assert.throws(() => registry.loadBundle('{"broken": {}}'), /Missing 'type' field/);
+  assert.deepEqual(registry.schemaNames().sort(), ["age", "username"]);
+});
@@ -0,0 +1,51 @@
+/* tslint:disable */
+/* eslint-disable */
+
+/* auto-generated by NAPI-RS */
+
+/** A validation error, as seen from JavaScript. */
+export interface ValidationError {
+  /**
+   * The path of the invalid value, e.g. `items[0].sku`, or `""` for the
+   * document itself.
+   */
+  path: string
+  /** A stable code for the kind of error, e.g. `min_length`. */
+  code: string
+  /** A message describing the error. */
+  message: string
+  /** What was expected, if known. */
+  expected?: string
+  /** What was found, if known. */
+  got?: string
+}
+/** A registry of named schemas. */
+export declare class Registry {
+  /** Creates an empty registry. */
+  constructor()
+  /** Creates a registry from a bundle of JSON Schemas. */
+  static fromBundle(bundle: string): Registry
+  /**
+   * Adds the schemas of a bundle to the registry.
+   *
+   * Throws if any schema is invalid or already registered; the valid
+   * schemas are registered anyway.
+   */
+  loadBundle(bundle: string): void
+  /** Returns the names of the registered schemas. */
+  schemaNames(): Array<string>
+  /**
+   * Validates a JavaScript value against the schema named `schema`.
+   *
+   * Throws if the schema does not exist.
+   */
+  validate(schema: string, value: any): Array<ValidationError>
+  /**
+   * Validates a JSON document against the schema named `schema` on a
+   * worker thread, so a large document does not block the event loop.
+   *
+   * Rejects if the schema does not exist or the document is not valid
+   * JSON.
+   */
+  validateAsync(schema: string, json: string): Promise<Array<ValidationError>>
+}
@@ -0,0 +1,25 @@
+// Loads the native module built by `napi build`, which names it after the
+// platform when built with `--platform`.
+const { existsSync } = require("node:fs");
+const { join } = require("node:path");
+
+function platformSuffix() {
+  const { platform, arch } = process;
+  if (platform === "linux") {
+    const report = process.report && process.report.getReport();
+    const glibc = report && report.header && report.header.glibcVersionRuntime;
+    return `linux-${arch}-${glibc ? "gnu" : "musl"}`;
+  }
+  if (platform === "win32") {
+    return `win32-${arch}-msvc`;
+  }
+  return `${platform}-${arch}`;
+}
+
+const candidates = [`postmortem.${platformSuffix()}.node`, "postmortem.node"];
+const binary = candidates.map((name) => join(__dirname, name)).find(existsSync);
+if (!binary) {
+  throw new Error(`postmortem: no native module found, tried ${candidates.join(", ")}`);
+}
+
+module.exports = require(binary);
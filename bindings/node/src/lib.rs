@@ -0,0 +1,168 @@
+//! Node.js bindings for postmortem.
+//!
+//! TypeScript services load the same JSON Schema bundles as the Rust
+//! services and get the same errors back:
+//!
+//! ```typescript
+//! import { Registry } from "postmortem";
+//!
+//! const registry = Registry.fromBundle(
+//!   JSON.stringify({ username: { type: "string", minLength: 3 } }),
+//! );
+//!
+//! registry.validate("username", "ab");
+//! // [{ path: "", code: "min_length", message: "length must be at least 3, got 2", ... }]
+//!
+//! // Parses and validates on a worker thread
+//! const errors = await registry.validateAsync("username", largeDocument);
+//! ```
+//!
+//! A bundle is a JSON object mapping schema names to schemas, as accepted
+//! by [`SchemaRegistry::load_bundle`]. Validation returns the errors as an
+//! array, empty if the value is valid.
+
+use napi::bindgen_prelude::{AsyncTask, Error, Result, Status};
+use napi::{Env, Task};
+use napi_derive::napi;
+use postmortem::{SchemaErrors, SchemaRegistry};
+use serde_json::Value;
+use stillwater::Validation;
+
+/// A validation error, as seen from JavaScript.
+#[napi(object)]
+pub struct ValidationError {
+    /// The path of the invalid value, e.g. `items[0].sku`, or `""` for the
+    /// document itself.
+    pub path: String,
+    /// A stable code for the kind of error, e.g. `min_length`.
+    pub code: String,
+    /// A message describing the error.
+    pub message: String,
+    /// What was expected, if known.
+    pub expected: Option<String>,
+    /// What was found, if known.
+    pub got: Option<String>,
+}
+
+/// A registry of named schemas.
+#[napi]
+pub struct Registry {
+    registry: SchemaRegistry,
+}
+
+#[napi]
+impl Registry {
+    /// Creates an empty registry.
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self {
+            registry: SchemaRegistry::new(),
+        }
+    }
+
+    /// Creates a registry from a bundle of JSON Schemas.
+    #[napi(factory)]
+    pub fn from_bundle(bundle: String) -> Result<Self> {
+        let registry = Self::new();
+        registry.load_bundle(bundle)?;
+        Ok(registry)
+    }
+
+    /// Adds the schemas of a bundle to the registry.
+    ///
+    /// Throws if any schema is invalid or already registered; the valid
+    /// schemas are registered anyway.
+    #[napi]
+    pub fn load_bundle(&self, bundle: String) -> Result<()> {
+        self.registry
+            .load_bundle("<bundle>", &bundle)
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+    }
+
+    /// Returns the names of the registered schemas.
+    #[napi]
+    pub fn schema_names(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    /// Validates a JavaScript value against the schema named `schema`.
+    ///
+    /// Throws if the schema does not exist.
+    #[napi]
+    pub fn validate(&self, schema: String, value: Value) -> Result<Vec<ValidationError>> {
+        validate(&self.registry, &schema, &value)
+    }
+
+    /// Validates a JSON document against the schema named `schema` on a
+    /// worker thread, so a large document does not block the event loop.
+    ///
+    /// Rejects if the schema does not exist or the document is not valid
+    /// JSON.
+    #[napi(ts_return_type = "Promise<Array<ValidationError>>")]
+    pub fn validate_async(&self, schema: String, json: String) -> AsyncTask<ValidateTask> {
+        AsyncTask::new(ValidateTask {
+            registry: self.registry.clone(),
+            schema,
+            json,
+        })
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses and validates a document off the main thread.
+pub struct ValidateTask {
+    registry: SchemaRegistry,
+    schema: String,
+    json: String,
+}
+
+impl Task for ValidateTask {
+    type Output = Vec<ValidationError>;
+    type JsValue = Vec<ValidationError>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let value: Value = serde_json::from_str(&self.json).map_err(|e| {
+            Error::new(
+                Status::InvalidArg,
+                format!("document is not valid JSON: {}", e),
+            )
+        })?;
+        validate(&self.registry, &self.schema, &value)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+fn validate(
+    registry: &SchemaRegistry,
+    schema: &str,
+    value: &Value,
+) -> Result<Vec<ValidationError>> {
+    let result = registry
+        .validate(schema, value)
+        .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))?;
+    Ok(match result {
+        Validation::Success(_) => Vec::new(),
+        Validation::Failure(errors) => to_js(errors),
+    })
+}
+
+fn to_js(errors: SchemaErrors) -> Vec<ValidationError> {
+    errors
+        .into_iter()
+        .map(|error| ValidationError {
+            path: error.path.to_string(),
+            code: error.code,
+            message: error.message,
+            expected: error.expected,
+            got: error.got,
+        })
+        .collect()
+}
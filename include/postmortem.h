@@ -0,0 +1,86 @@
+#ifndef POSTMORTEM_H
+#define POSTMORTEM_H
+
+/* Generated by cbindgen; do not edit. Regenerate with `just capi-header`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The value is valid.
+#define PM_VALID 0
+
+// The value is invalid; the errors are reported.
+#define PM_INVALID 1
+
+// The value could not be validated, e.g. because an argument is null or
+// the schema does not exist; a message is reported.
+#define PM_ERROR -1
+
+// A compiled registry of schemas, opaque to C.
+typedef struct PmRegistry PmRegistry;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Compiles a bundle of JSON Schemas into a registry.
+//
+// `bundle` is a NUL-terminated JSON object mapping schema names to
+// schemas. Returns null if the bundle is not valid, and then stores an
+// error message in `*error_out` unless `error_out` is null. Free the
+// registry with [`pm_registry_free`] and the message with
+// [`pm_string_free`].
+//
+// # Safety
+//
+// `bundle` must be null or a NUL-terminated string, and `error_out` null
+// or valid for writes.
+PmRegistry *pm_registry_compile(const char *bundle, char **error_out);
+
+// Validates a JSON document against the schema named `schema`.
+//
+// `json` points to `len` bytes of UTF-8 JSON, which need not be
+// NUL-terminated. Returns [`PM_VALID`], [`PM_INVALID`], or [`PM_ERROR`].
+// Unless `errors_out` is null, `*errors_out` is set to null if the value
+// is valid, to the errors as a JSON string if it is invalid (see
+// [`FailureReport::to_json`]), and to a message if it could not be
+// validated. A document that is not valid JSON is invalid, with a single
+// error coded `invalid_json`. Free the string with [`pm_string_free`].
+//
+// # Safety
+//
+// `registry` must be null or a handle returned by
+// [`pm_registry_compile`] and not yet freed, `schema` null or a
+// NUL-terminated string, `json` valid for reads of `len` bytes, and
+// `errors_out` null or valid for writes.
+int pm_validate(const PmRegistry *registry,
+                const char *schema,
+                const uint8_t *json,
+                size_t len,
+                char **errors_out);
+
+// Frees a string returned by this library. Does nothing if `string` is
+// null.
+//
+// # Safety
+//
+// `string` must be null or a string returned by this library and not yet
+// freed.
+void pm_string_free(char *string);
+
+// Frees a registry. Does nothing if `registry` is null.
+//
+// # Safety
+//
+// `registry` must be null or a handle returned by [`pm_registry_compile`]
+// and not yet freed, and no other thread may be using it.
+void pm_registry_free(PmRegistry *registry);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POSTMORTEM_H */
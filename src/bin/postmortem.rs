@@ -0,0 +1,731 @@
+//! The `postmortem` command-line tool.
+//!
+//! `postmortem repl [bundle.json]` starts an interactive session for
+//! authoring schemas: load a bundle of JSON Schemas, pick a schema, type
+//! JSON documents to see every error they produce, explain the errors in
+//! detail, and describe the schema at a path. Type `:help` in the session
+//! for the commands.
+//!
+//! `postmortem serve --lsp bundle.json` runs a language server on stdin and
+//! stdout, so editors check JSON and YAML documents against the bundle as
+//! they are typed: errors are reported on the offending values, and
+//! completion offers enum values and the properties an object is missing.
+//! Documents name their schema with a top-level `"$schema"` member; a
+//! bundle of one schema needs none. Documents are YAML if the editor says
+//! so or their name ends in `.yaml` or `.yml`. See [`postmortem::editor`].
+//!
+//! Requires the `cli` feature: `cargo run --features cli -- repl schemas.json`.
+
+use postmortem::editor::{schema_at, CompletionKind, DocumentChecker, Position};
+use postmortem::{JsonPath, SchemaErrors, SchemaRegistry};
+use serde_json::json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Read, Write};
+use std::process::ExitCode;
+use stillwater::Validation;
+
+const USAGE: &str = "\
+usage: postmortem repl [bundle.json]
+       postmortem serve --lsp <bundle.json>";
+
+const HELP: &str = "\
+Type a JSON document, over several lines if needed, to validate it against
+the current schema.
+
+Commands:
+  :load <file>       load a bundle of JSON Schemas mapping names to schemas
+  :schemas           list the loaded schemas
+  :use <name>        validate against the named schema
+  :explain           show every detail of the last errors
+  :describe [path]   show the current schema at a path, e.g. items[0].sku
+  :help              show this help
+  :quit              leave";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("repl") if args.len() <= 2 => {
+            let mut repl = Repl::new();
+            if let Some(file) = args.get(1) {
+                println!("{}", repl.eval(&format!(":load {}", file)));
+            }
+            match run(&mut repl) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("postmortem: {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Some("serve") if args.len() == 3 && args[1] == "--lsp" => match serve(&args[2]) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("postmortem: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Reads lines from stdin and evaluates them until `:quit` or end of
+/// input.
+fn run(repl: &mut Repl) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}", repl.prompt());
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(());
+        };
+        if line.trim() == ":quit" {
+            return Ok(());
+        }
+        let output = repl.eval(&line);
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+    }
+}
+
+/// The state of a session.
+struct Repl {
+    registry: SchemaRegistry,
+    schema: Option<String>,
+    /// Lines of a document that is not complete yet.
+    pending: String,
+    last_errors: Option<SchemaErrors>,
+}
+
+impl Repl {
+    fn new() -> Self {
+        Self {
+            registry: SchemaRegistry::new(),
+            schema: None,
+            pending: String::new(),
+            last_errors: None,
+        }
+    }
+
+    fn prompt(&self) -> String {
+        match (&self.schema, self.pending.is_empty()) {
+            (_, false) => "...> ".to_string(),
+            (Some(schema), true) => format!("{}> ", schema),
+            (None, true) => "> ".to_string(),
+        }
+    }
+
+    /// Evaluates a line and returns what to print.
+    fn eval(&mut self, line: &str) -> String {
+        let trimmed = line.trim();
+        if self.pending.is_empty() {
+            if trimmed.is_empty() {
+                return String::new();
+            }
+            if let Some(command) = trimmed.strip_prefix(':') {
+                let (name, argument) = match command.split_once(char::is_whitespace) {
+                    Some((name, argument)) => (name, argument.trim()),
+                    None => (command, ""),
+                };
+                return self.command(name, argument);
+            }
+        }
+
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        let value: Value = match serde_json::from_str(&self.pending) {
+            Ok(value) => value,
+            // Wait for the rest of the document
+            Err(e) if e.is_eof() => return String::new(),
+            Err(e) => {
+                self.pending.clear();
+                return format!("invalid JSON: {}", e);
+            }
+        };
+        self.pending.clear();
+        self.validate(&value)
+    }
+
+    fn command(&mut self, name: &str, argument: &str) -> String {
+        match name {
+            "load" if !argument.is_empty() => self.load(argument),
+            "schemas" => {
+                let names = self.registry.names();
+                if names.is_empty() {
+                    "no schemas loaded; use :load <file>".to_string()
+                } else {
+                    names.join("\n")
+                }
+            }
+            "use" if !argument.is_empty() => {
+                if self.registry.get(argument).is_none() {
+                    return format!("no schema named '{}'", argument);
+                }
+                self.schema = Some(argument.to_string());
+                self.last_errors = None;
+                format!("validating against {}", argument)
+            }
+            "explain" => match &self.last_errors {
+                Some(errors) => explain(errors),
+                None => "nothing to explain; the last document was valid".to_string(),
+            },
+            "describe" => self.describe(argument),
+            "help" => HELP.to_string(),
+            _ => format!("unknown command ':{}'; type :help for the commands", name),
+        }
+    }
+
+    fn load(&mut self, file: &str) -> String {
+        let content = match std::fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => return format!("cannot read {}: {}", file, e),
+        };
+        // Replace the registry, so a reloaded bundle does not conflict with
+        // the schemas it loaded before
+        let registry = SchemaRegistry::new();
+        let loaded = registry.load_bundle(file, &content);
+        self.registry = registry;
+        let names = self.registry.names();
+        if self.schema.as_ref().is_none_or(|s| !names.contains(s)) {
+            self.schema = names.first().cloned();
+        }
+        self.last_errors = None;
+
+        let mut output = format!("loaded {} schema(s) from {}", names.len(), file);
+        if let Err(e) = loaded {
+            output.push_str(&format!("\n{}", e));
+        }
+        output
+    }
+
+    fn validate(&mut self, value: &Value) -> String {
+        let Some(schema) = &self.schema else {
+            return "no schema selected; use :load <file> and :use <name>".to_string();
+        };
+        match self.registry.validate(schema, value) {
+            Ok(Validation::Success(_)) => {
+                self.last_errors = None;
+                "valid".to_string()
+            }
+            Ok(Validation::Failure(errors)) => {
+                let mut output = format!("{} error(s):", errors.len());
+                for error in errors.iter() {
+                    output.push_str(&format!("\n  {} [{}]", error, error.code));
+                }
+                self.last_errors = Some(errors);
+                output
+            }
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn describe(&self, path: &str) -> String {
+        let Some(schema) = &self.schema else {
+            return "no schema selected; use :load <file> and :use <name>".to_string();
+        };
+        let Some(path) = JsonPath::parse(path) else {
+            return format!("invalid path '{}'", path);
+        };
+        let export = self.registry.to_json_schema();
+        match schema_at(&export, schema, &path) {
+            Some(current) => serde_json::to_string_pretty(current).unwrap_or_default(),
+            None => format!("{} has no schema at {}", schema, path),
+        }
+    }
+}
+
+/// Serves the Language Server Protocol on stdin and stdout until the
+/// client exits.
+fn serve(bundle: &str) -> io::Result<ExitCode> {
+    let content = std::fs::read_to_string(bundle)
+        .map_err(|e| io::Error::new(e.kind(), format!("cannot read {}: {}", bundle, e)))?;
+    let registry = SchemaRegistry::new();
+    registry
+        .load_bundle(bundle, &content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    let names = registry.names();
+    let mut checker = DocumentChecker::new(registry);
+    if let [name] = names.as_slice() {
+        checker = checker.default_schema(name.clone());
+    }
+
+    let mut server = Server::new(checker);
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    loop {
+        let message = match read_message(&mut input)? {
+            Incoming::Message(message) => message,
+            Incoming::Malformed(reason) => {
+                write_message(&mut output, &parse_error(reason))?;
+                continue;
+            }
+            Incoming::End => break,
+        };
+        for reply in server.handle(&message) {
+            write_message(&mut output, &reply)?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    // Exiting without a shutdown request is an error
+    Ok(if server.shut_down {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// The largest message body the server reads, in bytes.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// What the client sent.
+#[derive(Debug, PartialEq)]
+enum Incoming {
+    Message(Value),
+    /// A message that could not be read, and why. The server answers it
+    /// with a parse error and reads on.
+    Malformed(String),
+    /// The end of input.
+    End,
+}
+
+/// Reads a message framed by a `Content-Length` header.
+///
+/// Bodies longer than [`MAX_MESSAGE_BYTES`] are skipped without being
+/// stored.
+fn read_message(input: &mut impl BufRead) -> io::Result<Incoming> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(Incoming::End);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let Some(length) = length else {
+        return Ok(Incoming::Malformed(
+            "message without a valid Content-Length header".to_string(),
+        ));
+    };
+    if length > MAX_MESSAGE_BYTES {
+        io::copy(&mut Read::take(&mut *input, length as u64), &mut io::sink())?;
+        return Ok(Incoming::Malformed(format!(
+            "message of {} bytes exceeds the limit of {} bytes",
+            length, MAX_MESSAGE_BYTES
+        )));
+    }
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(match serde_json::from_slice(&body) {
+        Ok(message) => Incoming::Message(message),
+        Err(e) => Incoming::Malformed(e.to_string()),
+    })
+}
+
+/// Returns the JSON-RPC error for a message that could not be parsed.
+fn parse_error(reason: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {"code": -32700, "message": reason},
+    })
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+/// The language of an open document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Json,
+    Yaml,
+}
+
+impl Language {
+    /// Returns the language of a document opened with the editor's
+    /// `languageId`, or from its URI's extension.
+    fn of(language_id: &str, uri: &str) -> Self {
+        let yaml = language_id == "yaml" || uri.ends_with(".yaml") || uri.ends_with(".yml");
+        if yaml {
+            Language::Yaml
+        } else {
+            Language::Json
+        }
+    }
+}
+
+/// The state of a language server session.
+struct Server {
+    checker: DocumentChecker,
+    /// The text and language of each open document, by URI.
+    documents: HashMap<String, (String, Language)>,
+    shut_down: bool,
+    exited: bool,
+}
+
+impl Server {
+    fn new(checker: DocumentChecker) -> Self {
+        Self {
+            checker,
+            documents: HashMap::new(),
+            shut_down: false,
+            exited: false,
+        }
+    }
+
+    /// Handles a request or notification and returns the messages to send.
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match message["method"].as_str().unwrap_or_default() {
+            "initialize" => reply(
+                id,
+                json!({
+                    "capabilities": {
+                        // Full text on each change
+                        "textDocumentSync": 1,
+                        "completionProvider": {"triggerCharacters": ["\"", ":"]},
+                    },
+                    "serverInfo": {"name": "postmortem", "version": env!("CARGO_PKG_VERSION")},
+                }),
+            ),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                let language_id = params["textDocument"]["languageId"]
+                    .as_str()
+                    .unwrap_or_default();
+                let language = Language::of(language_id, uri);
+                self.documents
+                    .insert(uri.to_string(), (text.to_string(), language));
+                vec![self.publish(uri)]
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                else {
+                    return Vec::new();
+                };
+                let language = self
+                    .documents
+                    .get(uri)
+                    .map_or_else(|| Language::of("", uri), |(_, language)| *language);
+                self.documents
+                    .insert(uri.to_string(), (text.to_string(), language));
+                vec![self.publish(uri)]
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                vec![diagnostics_notification(uri, Vec::new())]
+            }
+            "textDocument/completion" => {
+                let (text, language) = self.document(uri);
+                let position = Position::new(
+                    params["position"]["line"].as_u64().unwrap_or(0) as u32,
+                    params["position"]["character"].as_u64().unwrap_or(0) as u32,
+                );
+                let completions = match language {
+                    Language::Json => self.checker.completions(text, position),
+                    Language::Yaml => self.checker.yaml_completions(text, position),
+                };
+                let items: Vec<Value> = completions
+                    .into_iter()
+                    .map(|completion| {
+                        json!({
+                            "label": completion.label,
+                            // Property, Value, or Text, in the protocol's numbering
+                            "kind": match completion.kind {
+                                CompletionKind::Property => 10,
+                                CompletionKind::Value => 12,
+                                _ => 1,
+                            },
+                            "detail": completion.detail,
+                            "textEdit": {
+                                "range": range(completion.range),
+                                "newText": completion.insert_text,
+                            },
+                        })
+                    })
+                    .collect();
+                reply(id, json!(items))
+            }
+            "shutdown" => {
+                self.shut_down = true;
+                reply(id, Value::Null)
+            }
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            method => match id {
+                Some(id) => vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": -32601, "message": format!("unsupported method '{}'", method)},
+                })],
+                // Notifications need no answer
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Returns the text and language of an open document.
+    fn document(&self, uri: &str) -> (&str, Language) {
+        match self.documents.get(uri) {
+            Some((text, language)) => (text, *language),
+            None => ("", Language::of("", uri)),
+        }
+    }
+
+    fn publish(&self, uri: &str) -> Value {
+        let (text, language) = self.document(uri);
+        let diagnostics = match language {
+            Language::Json => self.checker.diagnostics(text),
+            Language::Yaml => self.checker.yaml_diagnostics(text),
+        };
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                json!({
+                    "range": range(diagnostic.range),
+                    "severity": 1,
+                    "code": diagnostic.code,
+                    "source": "postmortem",
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        diagnostics_notification(uri, diagnostics)
+    }
+}
+
+fn reply(id: Option<Value>, result: Value) -> Vec<Value> {
+    vec![json!({"jsonrpc": "2.0", "id": id, "result": result})]
+}
+
+fn diagnostics_notification(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics},
+    })
+}
+
+fn range(range: postmortem::editor::Range) -> Value {
+    json!({
+        "start": {"line": range.start.line, "character": range.start.character},
+        "end": {"line": range.end.line, "character": range.end.character},
+    })
+}
+
+/// Describes each error with every detail it carries.
+fn explain(errors: &SchemaErrors) -> String {
+    let mut output = Vec::new();
+    for (i, error) in errors.iter().enumerate() {
+        let path = if error.path.is_root() {
+            "(root)".to_string()
+        } else {
+            error.path.to_string()
+        };
+        output.push(format!("{}. {}", i + 1, path));
+        output.push(format!("   message:  {}", error.message));
+        output.push(format!("   code:     {}", error.code));
+        if let Some(expected) = &error.expected {
+            output.push(format!("   expected: {}", expected));
+        }
+        if let Some(got) = &error.got {
+            output.push(format!("   got:      {}", got));
+        }
+        if let Some(provenance) = &error.provenance {
+            output.push(format!("   rule:     defined in {}", provenance));
+        }
+        if let Some(repair) = &error.suggestion {
+            let fix = match &repair.value {
+                Some(value) => format!("{} to {}", repair.kind.as_str(), value),
+                None => repair.kind.as_str().to_string(),
+            };
+            output.push(format!("   fix:      {}", fix));
+        }
+    }
+    output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl() -> Repl {
+        let path =
+            std::env::temp_dir().join(format!("postmortem-repl-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"username": {"type": "string", "minLength": 3}, "age": {"type": "integer"}}"#,
+        )
+        .unwrap();
+        let mut repl = Repl::new();
+        let output = repl.eval(&format!(":load {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(output.starts_with("loaded 2 schema(s)"), "{}", output);
+        repl
+    }
+
+    #[test]
+    fn test_validate_and_explain() {
+        let mut repl = repl();
+        assert_eq!(repl.prompt(), "age> ");
+        assert_eq!(repl.eval(":use username"), "validating against username");
+        assert_eq!(repl.eval("\"ada\""), "valid");
+
+        let output = repl.eval("\"ab\"");
+        assert!(output.starts_with("1 error(s):"), "{}", output);
+        assert!(output.contains("[min_length]"), "{}", output);
+
+        let explained = repl.eval(":explain");
+        assert!(explained.contains("code:     min_length"), "{}", explained);
+        assert!(
+            explained.contains("expected: at least 3 characters"),
+            "{}",
+            explained
+        );
+        assert!(explained.contains("rule:     defined in"), "{}", explained);
+    }
+
+    #[test]
+    fn test_multiline_documents() {
+        let mut repl = repl();
+        assert_eq!(repl.eval("["), "");
+        assert_eq!(repl.prompt(), "...> ");
+        assert_eq!(repl.eval("1,"), "");
+        let output = repl.eval("2]");
+        assert!(output.contains("[invalid_type]"), "{}", output);
+        assert!(repl.eval("{]").starts_with("invalid JSON"));
+        assert_eq!(repl.prompt(), "age> ");
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut repl = repl();
+        repl.eval(":use username");
+        let output = repl.eval(":describe");
+        assert!(output.contains("\"minLength\": 3"), "{}", output);
+        assert_eq!(
+            repl.eval(":describe items[0]"),
+            "username has no schema at items[0]"
+        );
+        assert!(repl.eval(":use missing").starts_with("no schema named"));
+    }
+
+    #[test]
+    fn test_language_server() {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Order",
+                postmortem::Schema::object().field(
+                    "status",
+                    postmortem::Schema::string().one_of(["open", "closed"]),
+                ),
+            )
+            .unwrap();
+        let mut server = Server::new(DocumentChecker::new(registry).default_schema("Order"));
+        let request = |method: &str, params: Value| json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+
+        let init = server.handle(&request("initialize", json!({})));
+        assert_eq!(init[0]["result"]["capabilities"]["textDocumentSync"], 1);
+
+        let opened = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": "file:///a.json", "text": "{\"status\": \"new\"}"}},
+        }));
+        let diagnostics = &opened[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics[0]["code"], "invalid_enum");
+        assert_eq!(diagnostics[0]["range"]["start"]["character"], 11);
+
+        let completion = server.handle(&request(
+            "textDocument/completion",
+            json!({"textDocument": {"uri": "file:///a.json"}, "position": {"line": 0, "character": 12}}),
+        ));
+        let items = completion[0]["result"].as_array().unwrap();
+        assert_eq!(items[0]["label"], "open");
+        assert_eq!(items[0]["textEdit"]["newText"], "\"open\"");
+
+        let opened = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": "file:///a.yaml", "languageId": "yaml", "text": "status: new\n"}},
+        }));
+        let diagnostics = &opened[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics[0]["code"], "invalid_enum");
+        assert_eq!(diagnostics[0]["range"]["start"]["character"], 8);
+        let completion = server.handle(&request(
+            "textDocument/completion",
+            json!({"textDocument": {"uri": "file:///a.yaml"}, "position": {"line": 0, "character": 9}}),
+        ));
+        assert_eq!(completion[0]["result"][0]["textEdit"]["newText"], "open");
+
+        let unknown = server.handle(&request("textDocument/hover", json!({})));
+        assert_eq!(unknown[0]["error"]["code"], -32601);
+
+        server.handle(&request("shutdown", Value::Null));
+        server.handle(&json!({"jsonrpc": "2.0", "method": "exit"}));
+        assert!(server.shut_down && server.exited);
+    }
+
+    #[test]
+    fn test_message_framing() {
+        let mut output = Vec::new();
+        write_message(&mut output, &json!({"id": 1})).unwrap();
+        assert_eq!(output, b"Content-Length: 8\r\n\r\n{\"id\":1}");
+
+        let mut input = io::Cursor::new(output);
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            Incoming::Message(json!({"id": 1}))
+        );
+        assert_eq!(read_message(&mut input).unwrap(), Incoming::End);
+    }
+
+    #[test]
+    fn test_malformed_messages_are_skipped() {
+        let oversized = MAX_MESSAGE_BYTES + 1;
+        let mut stream = b"Content-Length: 5\r\n\r\n{oops".to_vec();
+        stream.extend(b"Content-Type: x\r\n\r\n");
+        stream.extend(format!("Content-Length: {}\r\n\r\n", oversized).bytes());
+        stream.extend(std::iter::repeat_n(b' ', oversized));
+        write_message(&mut stream, &json!({"id": 2})).unwrap();
+
+        let mut input = io::Cursor::new(stream);
+        for _ in 0..3 {
+            assert!(matches!(
+                read_message(&mut input).unwrap(),
+                Incoming::Malformed(_)
+            ));
+        }
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            Incoming::Message(json!({"id": 2}))
+        );
+        assert_eq!(parse_error("bad".to_string())["error"]["code"], -32700);
+    }
+}
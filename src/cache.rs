@@ -0,0 +1,421 @@
+//! Caching of validation outcomes.
+//!
+//! Webhook senders retry deliveries they consider failed, often resending
+//! the identical payload many times. An [`OutcomeCache`] attached to a
+//! [`SchemaRegistry`](crate::SchemaRegistry) remembers the outcome of
+//! validating each payload, keyed by a hash of the schema (its registry and
+//! name), the validation mode, the generation of the
+//! [tunable limits](crate::tunable), and the canonicalized value, so a retry
+//! costs a hash and a lookup instead of a full validation. One cache can be
+//! shared by several registries.
+//!
+//! Warnings a validation recorded, such as for
+//! [deprecated fields](crate::ObjectSchema::deprecated_field), are cached
+//! with its outcome and recorded again on every hit. Outcomes with a
+//! [computed default](crate::ObjectSchema::default_with) are not cached, so
+//! every retry gets a fresh value.
+//!
+//! Entries are evicted oldest first once the cache is full, and expire after
+//! an optional time to live. [`OutcomeCache::stats`] reports the hit rate.
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use stillwater::Validation;
+
+use crate::error::{ErrorSettings, SchemaErrors};
+use crate::suppression::SuppressedError;
+use crate::tunable;
+use crate::validation::{ValidationContext, ValidationMode};
+
+/// The outcome of a validation, as cached.
+pub(crate) type Outcome = Validation<Value, SchemaErrors>;
+
+/// What a value was validated against: a schema, identified by its
+/// registry and name, and the parts of the context that change the outcome.
+#[derive(Debug, Clone, Copy, Hash)]
+pub(crate) struct Subject<'a> {
+    registry: u64,
+    schema: &'a str,
+    mode: Option<ValidationMode>,
+    lenient: bool,
+    errors: ErrorSettings,
+    tunables: (u64, u64),
+}
+
+impl<'a> Subject<'a> {
+    /// Describes validating against the schema named `schema` in the
+    /// registry with id `registry`, with `context`.
+    pub(crate) fn new(registry: u64, schema: &'a str, context: &ValidationContext) -> Self {
+        Self {
+            registry,
+            schema,
+            mode: context.mode(),
+            lenient: context.is_lenient(),
+            errors: context.error_settings(),
+            tunables: tunable::generation(),
+        }
+    }
+}
+
+/// A 128-bit key made of two independent 64-bit hashes, so that a collision
+/// between different payloads is vanishingly unlikely.
+type Key = (u64, u64);
+
+/// A bounded cache of validation outcomes, shared by every clone.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{OutcomeCache, Schema, SchemaRegistry};
+/// use serde_json::json;
+/// use std::time::Duration;
+///
+/// let cache = OutcomeCache::new(10_000).with_ttl(Duration::from_secs(300));
+/// let registry = SchemaRegistry::new().with_outcome_cache(cache.clone());
+/// registry.register("Event", Schema::object().field("id", Schema::integer())).unwrap();
+///
+/// let payload = json!({"id": 7});
+/// for _ in 0..3 {
+///     assert!(registry.validate("Event", &payload).unwrap().is_success());
+/// }
+///
+/// let stats = cache.stats();
+/// assert_eq!((stats.hits, stats.misses), (2, 1));
+/// assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+/// ```
+#[derive(Clone)]
+pub struct OutcomeCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    capacity: usize,
+    ttl: Option<Duration>,
+    state: Mutex<State>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<Key, Entry>,
+    /// Keys in insertion order, with the sequence number of the insertion
+    /// so a stale position does not evict a newer entry for the same key.
+    order: VecDeque<(Key, u64)>,
+    next_seq: u64,
+}
+
+struct Entry {
+    outcome: Outcome,
+    warnings: Vec<SuppressedError>,
+    inserted: Instant,
+    seq: u64,
+}
+
+/// Counters describing how well an [`OutcomeCache`] is working.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that had to validate.
+    pub misses: u64,
+    /// Entries removed to make room for new ones.
+    pub evictions: u64,
+    /// Entries removed because they outlived the time to live.
+    pub expirations: u64,
+    /// Entries currently cached.
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Returns the fraction of lookups answered from the cache, or 0 if
+    /// there have been none.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl OutcomeCache {
+    /// Creates a cache holding at most `capacity` outcomes.
+    ///
+    /// When the cache is full, the oldest entry is evicted. A capacity of
+    /// zero caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                ttl: None,
+                state: Mutex::new(State::default()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+                expirations: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Expires entries `ttl` after they were cached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cache has already been cloned, e.g. attached to a
+    /// registry; set the time to live first.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        Arc::get_mut(&mut self.inner)
+            .expect("with_ttl() must be called before the cache is shared")
+            .ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the hit, miss, and eviction counters.
+    pub fn stats(&self) -> CacheStats {
+        let inner = &self.inner;
+        CacheStats {
+            hits: inner.hits.load(Ordering::Relaxed),
+            misses: inner.misses.load(Ordering::Relaxed),
+            evictions: inner.evictions.load(Ordering::Relaxed),
+            expirations: inner.expirations.load(Ordering::Relaxed),
+            entries: inner.state.lock().entries.len(),
+        }
+    }
+
+    /// Removes every entry, keeping the counters.
+    pub fn clear(&self) {
+        let mut state = self.inner.state.lock();
+        state.entries.clear();
+        state.order.clear();
+    }
+
+    /// Returns the cached outcome of validating `value` against `subject`,
+    /// validating with `validate` on a miss.
+    ///
+    /// The warnings of the validation are recorded on `context`, whether
+    /// they were cached or `validate` just recorded them.
+    pub(crate) fn get_or_validate(
+        &self,
+        subject: Subject<'_>,
+        value: &Value,
+        context: &ValidationContext,
+        validate: impl FnOnce(&ValidationContext) -> Outcome,
+    ) -> Outcome {
+        let key = key(subject, value);
+        if let Some((outcome, warnings)) = self.get(key) {
+            self.inner.hits.fetch_add(1, Ordering::Relaxed);
+            context.warn(warnings);
+            return outcome;
+        }
+        self.inner.misses.fetch_add(1, Ordering::Relaxed);
+        let detached = context.detached();
+        let outcome = validate(&detached);
+        if !detached.has_computed() {
+            let warnings = detached.take_warnings();
+            self.insert(key, outcome.clone(), warnings.clone());
+            context.warn(warnings);
+        }
+        context.adopt(&detached);
+        outcome
+    }
+
+    fn get(&self, key: Key) -> Option<(Outcome, Vec<SuppressedError>)> {
+        let mut state = self.inner.state.lock();
+        let entry = state.entries.get(&key)?;
+        if self
+            .inner
+            .ttl
+            .is_some_and(|ttl| entry.inserted.elapsed() >= ttl)
+        {
+            state.entries.remove(&key);
+            self.inner.expirations.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        Some((entry.outcome.clone(), entry.warnings.clone()))
+    }
+
+    fn insert(&self, key: Key, outcome: Outcome, warnings: Vec<SuppressedError>) {
+        if self.inner.capacity == 0 {
+            return;
+        }
+        let mut state = self.inner.state.lock();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.entries.insert(
+            key,
+            Entry {
+                outcome,
+                warnings,
+                inserted: Instant::now(),
+                seq,
+            },
+        );
+        state.order.push_back((key, seq));
+
+        while state.entries.len() > self.inner.capacity {
+            let Some((oldest, seq)) = state.order.pop_front() else {
+                break;
+            };
+            if state.entries.get(&oldest).is_some_and(|e| e.seq == seq) {
+                state.entries.remove(&oldest);
+                self.inner.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        // Drop positions whose entries are gone, so the queue stays bounded
+        if state.order.len() > 2 * self.inner.capacity {
+            let State { entries, order, .. } = &mut *state;
+            order.retain(|(key, seq)| entries.get(key).is_some_and(|e| e.seq == *seq));
+        }
+    }
+}
+
+/// Hashes a subject and the canonical form of a value.
+fn key(subject: Subject<'_>, value: &Value) -> Key {
+    let hash = |seed: u8| {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        subject.hash(&mut hasher);
+        hash_canonical(value, &mut hasher);
+        hasher.finish()
+    };
+    (hash(0), hash(1))
+}
+
+/// Hashes a value with object keys in sorted order, so equal documents hash
+/// equally regardless of the order their keys were sent in.
+fn hash_canonical(value: &Value, hasher: &mut DefaultHasher) {
+    match value {
+        Value::Null => 0u8.hash(hasher),
+        Value::Bool(b) => (1u8, b).hash(hasher),
+        Value::Number(n) => (2u8, n.to_string()).hash(hasher),
+        Value::String(s) => (3u8, s).hash(hasher),
+        Value::Array(items) => {
+            (4u8, items.len()).hash(hasher);
+            for item in items {
+                hash_canonical(item, hasher);
+            }
+        }
+        Value::Object(map) => {
+            (5u8, map.len()).hash(hasher);
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            for (key, value) in entries {
+                key.hash(hasher);
+                hash_canonical(value, hasher);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn outcome(n: i64) -> Outcome {
+        Validation::Success(json!(n))
+    }
+
+    fn named(schema: &str) -> Subject<'_> {
+        Subject {
+            registry: 0,
+            schema,
+            mode: None,
+            lenient: false,
+            errors: ErrorSettings::DEFAULT,
+            tunables: (0, 0),
+        }
+    }
+
+    #[test]
+    fn test_key_ignores_key_order() {
+        let a: Value = serde_json::from_str(r#"{"a": 1, "b": [1, 2]}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"b": [1, 2], "a": 1}"#).unwrap();
+        assert_eq!(key(named("Event"), &a), key(named("Event"), &b));
+        assert_ne!(key(named("Event"), &a), key(named("Other"), &a));
+        let create = Subject {
+            mode: Some(ValidationMode::Create),
+            ..named("Event")
+        };
+        assert_ne!(key(named("Event"), &a), key(create, &a));
+        let other_registry = Subject {
+            registry: 1,
+            ..named("Event")
+        };
+        assert_ne!(key(named("Event"), &a), key(other_registry, &a));
+        let omitted = Subject {
+            errors: ErrorSettings {
+                got_limit: crate::GotLimit::Omit,
+            },
+            ..named("Event")
+        };
+        assert_ne!(key(named("Event"), &a), key(omitted, &a));
+        assert_ne!(
+            key(named("Event"), &a),
+            key(named("Event"), &json!({"a": 1, "b": [2, 1]}))
+        );
+    }
+
+    #[test]
+    fn test_evicts_oldest_when_full() {
+        let cache = OutcomeCache::new(2);
+        let context = crate::SchemaRegistry::new().context();
+        for n in 0..3 {
+            cache.get_or_validate(named("S"), &json!(n), &context, |_| outcome(n));
+        }
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.evictions, stats.misses), (2, 1, 3));
+
+        // 0 was evicted; 2 is still cached
+        cache.get_or_validate(named("S"), &json!(2), &context, |_| unreachable!());
+        let mut validated = false;
+        cache.get_or_validate(named("S"), &json!(0), &context, |_| {
+            validated = true;
+            outcome(0)
+        });
+        assert!(validated);
+        assert_eq!(cache.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = OutcomeCache::new(10).with_ttl(Duration::ZERO);
+        let context = crate::SchemaRegistry::new().context();
+        cache.get_or_validate(named("S"), &json!(1), &context, |_| outcome(1));
+        cache.get_or_validate(named("S"), &json!(1), &context, |_| outcome(1));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.expirations), (0, 2, 1));
+        assert_eq!(stats.hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_stale_positions_do_not_evict_newer_entries() {
+        let cache = OutcomeCache::new(2);
+        let (one, two, three) = (
+            key(named("S"), &json!(1)),
+            key(named("S"), &json!(2)),
+            key(named("S"), &json!(3)),
+        );
+        cache.insert(two, outcome(2), Vec::new());
+        cache.insert(one, outcome(1), Vec::new());
+        // Re-inserting, as after an expiry, leaves a stale position for `two`
+        cache.insert(two, outcome(2), Vec::new());
+        cache.insert(three, outcome(3), Vec::new());
+
+        assert_eq!(cache.stats().evictions, 1);
+        assert!(cache.get(one).is_none());
+        assert!(cache.get(two).is_some());
+        assert!(cache.get(three).is_some());
+    }
+}
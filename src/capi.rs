@@ -0,0 +1,334 @@
+//! C API for validating from non-Rust programs.
+//!
+//! A service written in another language can enforce the same schemas as
+//! the Rust services by linking postmortem as a C library. The API is
+//! deliberately small:
+//!
+//! - [`pm_registry_compile`] compiles a bundle of JSON Schemas, as accepted
+//!   by [`SchemaRegistry::load_bundle`], into a registry handle
+//! - [`pm_validate`] validates a UTF-8 JSON buffer against a named schema,
+//!   and reports the errors as a JSON string
+//! - [`pm_string_free`] and [`pm_registry_free`] release what the library
+//!   allocated
+//!
+//! The header `include/postmortem.h` is generated with
+//! [cbindgen](https://github.com/mozilla/cbindgen) (`just capi-header`), and
+//! `just capi` builds the shared and static libraries.
+//!
+//! A registry handle may be used from several threads at once. No function
+//! unwinds into the caller: a panic is reported as [`PM_ERROR`].
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `capi` feature is enabled.
+//!
+//! # Example
+//!
+//! ```c
+//! #include "postmortem.h"
+//!
+//! char *message = NULL;
+//! PmRegistry *registry = pm_registry_compile(
+//!     "{\"username\": {\"type\": \"string\", \"minLength\": 3}}", &message);
+//! if (registry == NULL) {
+//!     fprintf(stderr, "%s\n", message);
+//!     pm_string_free(message);
+//!     return 1;
+//! }
+//!
+//! const char *payload = "\"ab\"";
+//! char *errors = NULL;
+//! if (pm_validate(registry, "username", (const uint8_t *)payload,
+//!                 strlen(payload), &errors) == PM_INVALID) {
+//!     // {"schema":"username","correlation_id":null,"errors":[...]}
+//!     puts(errors);
+//! }
+//! pm_string_free(errors);
+//! pm_registry_free(registry);
+//! ```
+
+use serde_json::{json, Value};
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use stillwater::Validation;
+
+use crate::registry::SchemaRegistry;
+use crate::validation::FailureReport;
+
+/// The value is valid.
+pub const PM_VALID: c_int = 0;
+
+/// The value is invalid; the errors are reported.
+pub const PM_INVALID: c_int = 1;
+
+/// The value could not be validated, e.g. because an argument is null or
+/// the schema does not exist; a message is reported.
+pub const PM_ERROR: c_int = -1;
+
+/// A compiled registry of schemas, opaque to C.
+pub struct PmRegistry {
+    registry: SchemaRegistry,
+}
+
+/// Compiles a bundle of JSON Schemas into a registry.
+///
+/// `bundle` is a NUL-terminated JSON object mapping schema names to
+/// schemas. Returns null if the bundle is not valid, and then stores an
+/// error message in `*error_out` unless `error_out` is null. Free the
+/// registry with [`pm_registry_free`] and the message with
+/// [`pm_string_free`].
+///
+/// # Safety
+///
+/// `bundle` must be null or a NUL-terminated string, and `error_out` null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pm_registry_compile(
+    bundle: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut PmRegistry {
+    set_string(error_out, None);
+    if bundle.is_null() {
+        set_string(error_out, Some("bundle is null".to_string()));
+        return ptr::null_mut();
+    }
+    let bundle = CStr::from_ptr(bundle);
+
+    let compiled = catch_unwind(|| {
+        let bundle = bundle
+            .to_str()
+            .map_err(|_| "bundle is not valid UTF-8".to_string())?;
+        let registry = SchemaRegistry::new();
+        registry
+            .load_bundle("<bundle>", bundle)
+            .map_err(|e| e.to_string())?;
+        Ok(registry)
+    });
+    match compiled {
+        Ok(Ok(registry)) => Box::into_raw(Box::new(PmRegistry { registry })),
+        Ok(Err(message)) => {
+            set_string(error_out, Some(message));
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_string(
+                error_out,
+                Some("panic while compiling the bundle".to_string()),
+            );
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Validates a JSON document against the schema named `schema`.
+///
+/// `json` points to `len` bytes of UTF-8 JSON, which need not be
+/// NUL-terminated. Returns [`PM_VALID`], [`PM_INVALID`], or [`PM_ERROR`].
+/// Unless `errors_out` is null, `*errors_out` is set to null if the value
+/// is valid, to the errors as a JSON string if it is invalid (see
+/// [`FailureReport::to_json`]), and to a message if it could not be
+/// validated. A document that is not valid JSON is invalid, with a single
+/// error coded `invalid_json`. Free the string with [`pm_string_free`].
+///
+/// # Safety
+///
+/// `registry` must be null or a handle returned by
+/// [`pm_registry_compile`] and not yet freed, `schema` null or a
+/// NUL-terminated string, `json` valid for reads of `len` bytes, and
+/// `errors_out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pm_validate(
+    registry: *const PmRegistry,
+    schema: *const c_char,
+    json: *const u8,
+    len: usize,
+    errors_out: *mut *mut c_char,
+) -> c_int {
+    set_string(errors_out, None);
+    if registry.is_null() || schema.is_null() || (json.is_null() && len > 0) {
+        set_string(errors_out, Some("an argument is null".to_string()));
+        return PM_ERROR;
+    }
+    let registry = &(*registry).registry;
+    let schema = CStr::from_ptr(schema);
+    let input = if len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(json, len)
+    };
+
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        let schema = schema
+            .to_str()
+            .map_err(|_| "schema name is not valid UTF-8".to_string())?;
+        let value: Value = match serde_json::from_slice(input) {
+            Ok(value) => value,
+            Err(e) => return Ok(Some(invalid_json(schema, &e))),
+        };
+        match registry
+            .validate(schema, &value)
+            .map_err(|e| e.to_string())?
+        {
+            Validation::Success(_) => Ok(None),
+            Validation::Failure(errors) => Ok(Some(
+                FailureReport {
+                    schema,
+                    correlation_id: None,
+                    errors: &errors,
+                }
+                .to_json(),
+            )),
+        }
+    }));
+    match outcome {
+        Ok(Ok(None)) => PM_VALID,
+        Ok(Ok(Some(report))) => {
+            set_string(errors_out, Some(report.to_string()));
+            PM_INVALID
+        }
+        Ok(Err(message)) => {
+            set_string(errors_out, Some(message));
+            PM_ERROR
+        }
+        Err(_) => {
+            set_string(errors_out, Some("panic while validating".to_string()));
+            PM_ERROR
+        }
+    }
+}
+
+/// Frees a string returned by this library. Does nothing if `string` is
+/// null.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this library and not yet
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn pm_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Frees a registry. Does nothing if `registry` is null.
+///
+/// # Safety
+///
+/// `registry` must be null or a handle returned by [`pm_registry_compile`]
+/// and not yet freed, and no other thread may be using it.
+#[no_mangle]
+pub unsafe extern "C" fn pm_registry_free(registry: *mut PmRegistry) {
+    if !registry.is_null() {
+        drop(Box::from_raw(registry));
+    }
+}
+
+/// The report for a document that is not valid JSON, in the shape of
+/// [`FailureReport::to_json`].
+fn invalid_json(schema: &str, error: &serde_json::Error) -> Value {
+    json!({
+        "schema": schema,
+        "correlation_id": null,
+        "errors": [{
+            "path": "",
+            "code": "invalid_json",
+            "message": format!("document is not valid JSON: {}", error),
+        }],
+    })
+}
+
+/// Stores `string` in `*out` as a C string, or null if `string` is `None`.
+///
+/// Does nothing if `out` is null. Interior NULs, which cannot be
+/// represented, are replaced by spaces.
+unsafe fn set_string(out: *mut *mut c_char, string: Option<String>) {
+    if out.is_null() {
+        return;
+    }
+    *out = match string {
+        Some(string) => CString::new(string.replace('\0', " "))
+            .map(CString::into_raw)
+            .unwrap_or(ptr::null_mut()),
+        None => ptr::null_mut(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes ownership of a string returned by the library.
+    unsafe fn take(string: *mut c_char) -> Option<String> {
+        if string.is_null() {
+            return None;
+        }
+        let owned = CStr::from_ptr(string).to_string_lossy().into_owned();
+        pm_string_free(string);
+        Some(owned)
+    }
+
+    unsafe fn validate(registry: *const PmRegistry, schema: &str, json: &str) -> (c_int, Value) {
+        let schema = CString::new(schema).unwrap();
+        let mut errors = ptr::null_mut();
+        let status = pm_validate(
+            registry,
+            schema.as_ptr(),
+            json.as_ptr(),
+            json.len(),
+            &mut errors,
+        );
+        let errors = take(errors).map_or(Value::Null, |s| {
+            serde_json::from_str(&s).unwrap_or(Value::String(s))
+        });
+        (status, errors)
+    }
+
+    #[test]
+    fn test_compile_and_validate() {
+        let bundle = CString::new(r#"{"username": {"type": "string", "minLength": 3}}"#).unwrap();
+        unsafe {
+            let mut message = ptr::null_mut();
+            let registry = pm_registry_compile(bundle.as_ptr(), &mut message);
+            assert!(!registry.is_null());
+            assert!(message.is_null());
+
+            assert_eq!(
+                validate(registry, "username", "\"ada\""),
+                (PM_VALID, Value::Null)
+            );
+
+            let (status, report) = validate(registry, "username", "\"ab\"");
+            assert_eq!(status, PM_INVALID);
+            assert_eq!(report["schema"], "username");
+            assert_eq!(report["errors"][0]["code"], "min_length");
+
+            let (status, report) = validate(registry, "username", "\"ab");
+            assert_eq!(status, PM_INVALID);
+            assert_eq!(report["errors"][0]["code"], "invalid_json");
+
+            let (status, message) = validate(registry, "missing", "1");
+            assert_eq!(status, PM_ERROR);
+            assert!(message.as_str().unwrap().contains("missing"));
+
+            pm_registry_free(registry);
+        }
+    }
+
+    #[test]
+    fn test_compile_reports_errors() {
+        unsafe {
+            let bundle = CString::new(r#"{"age": {"minimum": 0}}"#).unwrap();
+            let mut message = ptr::null_mut();
+            assert!(pm_registry_compile(bundle.as_ptr(), &mut message).is_null());
+            assert!(take(message).unwrap().contains("Missing 'type' field"));
+
+            assert!(pm_registry_compile(ptr::null(), ptr::null_mut()).is_null());
+            assert_eq!(
+                pm_validate(ptr::null(), ptr::null(), ptr::null(), 0, ptr::null_mut()),
+                PM_ERROR
+            );
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! Conversions between postmortem and the `garde` crate.
+//!
+//! This module is only available when the `garde` feature is enabled.
+//!
+//! - [`GardeSchema`] runs a `garde::Validate` type as a postmortem schema, so
+//!   existing garde rule sets can be registered or nested like any other schema.
+//! - [`garde_rule`] goes the other way and wraps a postmortem schema as a
+//!   function usable with `#[garde(custom(...))]`.
+//! - [`from_report`] and [`to_report`] convert between the two error types.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use garde::Validate;
+//! use postmortem::compat::garde::GardeSchema;
+//! use postmortem::{JsonPath, Schema};
+//!
+//! #[derive(serde::Deserialize, Validate)]
+//! struct Address {
+//!     #[garde(length(min = 1))]
+//!     city: String,
+//! }
+//!
+//! let schema = Schema::object()
+//!     .field("name", Schema::string().min_len(1))
+//!     .field("address", GardeSchema::<Address>::new());
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use ::garde::error::Kind;
+use ::garde::{Path, Report, Validate};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::{JsonPath, PathSegment};
+use crate::schema::{SchemaLike, ValueValidator};
+
+use super::{deserialize_value, serialize_value};
+
+/// A schema that deserializes a value into `T` and runs its garde rules.
+///
+/// Deserialization failures are reported with code `invalid_type`; garde
+/// failures are reported with code `garde` at the path garde reported,
+/// relative to the position of this schema.
+pub struct GardeSchema<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> GardeSchema<T>
+where
+    T: DeserializeOwned + Validate,
+    T::Context: Default,
+{
+    /// Creates a schema validating values with `T`'s garde rules.
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for GardeSchema<T>
+where
+    T: DeserializeOwned + Validate,
+    T::Context: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for GardeSchema<T> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for GardeSchema<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GardeSchema")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> SchemaLike for GardeSchema<T>
+where
+    T: DeserializeOwned + Validate,
+    T::Context: Default,
+{
+    type Output = T;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<T, SchemaErrors> {
+        let typed: T = match deserialize_value(value, path) {
+            Validation::Success(typed) => typed,
+            Validation::Failure(errors) => return Validation::Failure(errors),
+        };
+
+        match typed.validate() {
+            Ok(()) => Validation::Success(typed),
+            Err(report) => match from_report(&report, path) {
+                Some(errors) => Validation::Failure(errors),
+                None => Validation::Success(typed),
+            },
+        }
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path).map(|_| value.clone())
+    }
+}
+
+impl<T> ToJsonSchema for GardeSchema<T> {
+    fn to_json_schema(&self) -> Value {
+        // garde rules are opaque, so only the presence of a value is described
+        json!({})
+    }
+}
+
+/// Converts a garde report into postmortem errors rooted at `base`.
+///
+/// Returns `None` if the report is empty.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use postmortem::compat::garde::from_report;
+/// use postmortem::JsonPath;
+///
+/// if let Err(report) = form.validate() {
+///     let errors = from_report(&report, &JsonPath::root()).unwrap();
+/// }
+/// ```
+pub fn from_report(report: &Report, base: &JsonPath) -> Option<SchemaErrors> {
+    let errors: Vec<SchemaError> = report
+        .iter()
+        .map(|(path, error)| {
+            SchemaError::new(to_json_path(path, base), error.message()).with_code("garde")
+        })
+        .collect();
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(SchemaErrors::from_vec(errors))
+    }
+}
+
+/// Converts postmortem errors into a garde report.
+///
+/// Each error keeps its path; the postmortem error code is lost because garde
+/// errors only carry a message.
+pub fn to_report(errors: &SchemaErrors) -> Report {
+    let mut report = Report::new();
+    for error in errors.iter() {
+        report.append(
+            to_garde_path(&error.path),
+            ::garde::Error::new(&error.message),
+        );
+    }
+    report
+}
+
+/// Wraps a postmortem schema as a garde custom rule.
+///
+/// The returned function serializes the field value to JSON, validates it
+/// against `schema`, and folds any failures into a single garde error, since
+/// a garde custom rule reports one error per field.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use postmortem::compat::garde::garde_rule;
+/// use postmortem::Schema;
+///
+/// fn username(value: &str, ctx: &()) -> garde::Result {
+///     garde_rule(Schema::string().not_one_of(["admin"]))(&value, ctx)
+/// }
+///
+/// #[derive(garde::Validate)]
+/// struct Signup {
+///     #[garde(custom(username))]
+///     username: String,
+/// }
+/// ```
+pub fn garde_rule<S, T, C>(schema: S) -> impl Fn(&T, &C) -> ::garde::Result
+where
+    S: ValueValidator,
+    T: Serialize + ?Sized,
+{
+    move |value, _ctx| {
+        let json = serialize_value(value).map_err(::garde::Error::new)?;
+        match schema.validate_value(&json, &JsonPath::root()) {
+            Validation::Success(_) => Ok(()),
+            Validation::Failure(errors) => Err(::garde::Error::new(join_messages(&errors))),
+        }
+    }
+}
+
+/// Joins error messages, prefixing nested errors with their path.
+fn join_messages(errors: &SchemaErrors) -> String {
+    errors
+        .iter()
+        .map(|e| {
+            if e.path.is_root() {
+                e.message.clone()
+            } else {
+                format!("{}: {}", e.path, e.message)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Converts a garde path into a [`JsonPath`] appended to `base`.
+fn to_json_path(path: &Path, base: &JsonPath) -> JsonPath {
+    // `__iter` is hidden from garde's documented API, and `Display` joins
+    // keys with dots, so keys containing one would be split. Cargo.toml pins
+    // the garde version this was checked against. garde stores components
+    // innermost first
+    path.__iter()
+        .rev()
+        .fold(base.clone(), |acc, (kind, component)| match kind {
+            Kind::Index => match component.parse() {
+                Ok(index) => acc.push_index(index),
+                Err(_) => acc.push_field(component.as_str()),
+            },
+            Kind::Key => acc.push_field(component.as_str()),
+            Kind::None => acc,
+        })
+}
+
+/// Converts a [`JsonPath`] into a garde path.
+fn to_garde_path(path: &JsonPath) -> Path {
+    path.segments()
+        .fold(Path::empty(), |acc, segment| match segment {
+            PathSegment::Field(name) => acc.join(name.as_str()),
+            PathSegment::Index(index) => acc.join(*index),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Serialize, Validate)]
+    struct Address {
+        #[garde(length(min = 1))]
+        city: String,
+        #[garde(length(min = 1), inner(length(min = 2)))]
+        lines: Vec<String>,
+    }
+
+    #[test]
+    fn test_garde_schema_accepts_valid_value() {
+        let schema = GardeSchema::<Address>::new();
+        let result = schema.validate(
+            &json!({"city": "Berlin", "lines": ["Unter den Linden 1"]}),
+            &JsonPath::root(),
+        );
+        assert!(result.is_success());
+    }
+
+    #[test]
+    fn test_garde_schema_maps_report_paths() {
+        let schema = Schema::object().field("address", GardeSchema::<Address>::new());
+        let result = schema.validate(
+            &json!({"address": {"city": "", "lines": ["ok", "x"]}}),
+            &JsonPath::root(),
+        );
+
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.code == "garde"));
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert!(paths.contains(&"address.city".to_string()));
+        assert!(paths.contains(&"address.lines[1]".to_string()));
+    }
+
+    #[test]
+    fn test_garde_schema_reports_deserialization_errors() {
+        let schema = GardeSchema::<Address>::new();
+        let result = schema.validate(&json!({"city": 5}), &JsonPath::root());
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.first().code, "invalid_type");
+    }
+
+    #[test]
+    fn test_to_report_round_trip() {
+        let errors = SchemaErrors::from_vec(vec![
+            SchemaError::new(
+                JsonPath::root().push_field("tags").push_index(2),
+                "too short",
+            ),
+            SchemaError::new(JsonPath::root().push_field("name"), "required"),
+        ]);
+
+        let report = to_report(&errors);
+        let rendered: Vec<String> = report.iter().map(|(p, _)| p.to_string()).collect();
+        assert_eq!(rendered, vec!["tags[2]", "name"]);
+
+        let back = from_report(&report, &JsonPath::root()).unwrap();
+        assert_eq!(back.first().path, errors.first().path);
+        assert_eq!(back.first().message, "too short");
+    }
+
+    #[test]
+    fn test_from_empty_report() {
+        assert!(from_report(&Report::new(), &JsonPath::root()).is_none());
+    }
+
+    #[test]
+    fn test_garde_rule() {
+        let rule = garde_rule(Schema::string().not_one_of(["admin"]));
+
+        assert!(rule("alice", &()).is_ok());
+        let error = rule("admin", &()).unwrap_err();
+        assert!(error.message().contains("must not be one of"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Compatibility layers for other validation crates.
+//!
+//! This module helps projects migrating from other Rust validation libraries
+//! reuse their existing rule declarations to build postmortem schemas.
+//!
+//! - [`validator`]: builds schemas from `validator` crate `#[validate(...)]` attributes
+//! - `garde` (feature `garde`): runs garde rule sets as schemas and converts reports
+//! - `validify` (feature `validify`): runs validify rule sets as schemas and converts errors
+
+#[cfg(feature = "garde")]
+pub mod garde;
+pub mod validator;
+#[cfg(feature = "validify")]
+pub mod validify;
+
+#[cfg(any(feature = "garde", feature = "validify"))]
+use serde_json::Value;
+#[cfg(any(feature = "garde", feature = "validify"))]
+use stillwater::Validation;
+
+#[cfg(any(feature = "garde", feature = "validify"))]
+use crate::error::SchemaErrors;
+#[cfg(any(feature = "garde", feature = "validify"))]
+use crate::path::JsonPath;
+
+/// Deserializes a JSON value into the typed struct another crate validates.
+#[cfg(any(feature = "garde", feature = "validify"))]
+fn deserialize_value<T: serde::de::DeserializeOwned>(
+    value: &Value,
+    path: &JsonPath,
+) -> Validation<T, SchemaErrors> {
+    match crate::error::deserialize(value) {
+        Validation::Success(typed) => Validation::Success(typed),
+        Validation::Failure(errors) => Validation::Failure(SchemaErrors::from_vec(
+            errors
+                .into_iter()
+                .map(|mut e| {
+                    e.path = path.join(&e.path);
+                    e
+                })
+                .collect(),
+        )),
+    }
+}
+
+/// Serializes a typed field value so a postmortem schema can validate it.
+#[cfg(any(feature = "garde", feature = "validify"))]
+fn serialize_value<T: serde::Serialize + ?Sized>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("could not serialize value: {}", e))
+}
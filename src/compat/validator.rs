@@ -0,0 +1,1043 @@
+//! Schema generation from `validator` crate attributes.
+//!
+//! Projects built on the [`validator`](https://docs.rs/validator) crate declare
+//! their rules as `#[validate(...)]` attributes on struct fields. Those rules
+//! only run on typed structs after deserialization and stop at the Rust type
+//! system, so type mismatches never reach the accumulated error report.
+//!
+//! The [`validator_schema!`](crate::validator_schema) macro wraps an existing
+//! struct definition unchanged and additionally generates a
+//! `postmortem_schema()` constructor that mirrors the field rules, so the raw
+//! JSON payload can be validated with full error accumulation before it is
+//! deserialized.
+//!
+//! # Supported Rules
+//!
+//! | `validator` rule | postmortem equivalent |
+//! |------------------|-----------------------|
+//! | `email` | [`StringSchema::email`] |
+//! | `url` | [`StringSchema::url`] |
+//! | `ip` | [`StringSchema::ip`] |
+//! | `length(min, max, equal)` | `min_len`/`max_len` on strings and arrays |
+//! | `range(min, max, exclusive_min, exclusive_max)` | `min`/`max` on integers and numbers |
+//! | `contains(pattern)` / `contains = ".."` | [`StringSchema::contains`] |
+//! | `does_not_contain(pattern)` | custom check with code `forbidden_substring` |
+//! | `regex(path)` | [`StringSchema::pattern`] |
+//! | `must_match(other)` / `must_match = ".."` | [`ObjectSchema::equal_fields`] |
+//! | `required` | makes an `Option` field required and non-null |
+//! | `nested` | nested structs are validated through their own schema |
+//!
+//! A `message = ".."` argument becomes the custom error message of the rule,
+//! and a `code = ".."` argument replaces the error code of its constraints.
+//! Rules and arguments the macro does not know are rejected at compile time
+//! rather than silently dropped. A known rule that does not apply to the
+//! field's type, such as `email` on an integer, can only be detected once
+//! the type is known: `postmortem_schema()` then returns a [`CompatError`].
+//!
+//! Field types map to schemas through [`CompatType`]: `String`, all primitive
+//! integers, `f32`/`f64`, `bool`, `Option<T>`, `Vec<T>`, and any struct that is
+//! itself declared with `validator_schema!`.
+//!
+//! # Serde Attributes
+//!
+//! `serde` attributes that change which JSON a struct accepts are mirrored
+//! in the schema:
+//!
+//! | `serde` attribute | effect on the schema |
+//! |-------------------|----------------------|
+//! | `rename = ".."` on a field | the field is read under the new name |
+//! | `default` on a field | the field may be absent |
+//! | `skip` / `skip_deserializing` on a field | the field is left out |
+//! | `rename_all = ".."` on the struct | every field not renamed itself is renamed |
+//! | `default` on the struct | every field may be absent |
+//! | `deny_unknown_fields` on the struct | unknown fields are rejected |
+//!
+//! Attributes that only affect serialization, such as `skip_serializing_if`,
+//! are ignored. Any other `serde` attribute, e.g. `alias` or `flatten`, is
+//! rejected at compile time.
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+use crate::schema::primitive::{BooleanSchema, NumberBound, NumberSchema};
+use crate::schema::{
+    ArraySchema, CombinatorSchema, IntegerSchema, KeyCase, ObjectSchema, Schema, SchemaLike,
+    StringSchema,
+};
+use crate::validation::ValidationContext;
+
+/// Maps a Rust field type to the rules used to build its schema.
+///
+/// Implemented for the primitive types supported by
+/// [`validator_schema!`](crate::validator_schema), and generated for every
+/// struct declared with the macro so that nested structs compose.
+pub trait CompatType {
+    /// Returns the base rules for a field of this type.
+    fn rules() -> FieldRules;
+}
+
+/// An error building a schema with [`validator_schema!`](crate::validator_schema).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum CompatError {
+    /// A rule does not apply to the type of its field, e.g. `email` on an
+    /// integer.
+    #[error("validator rule `{rule}` on field '{field}' is not supported for {kind} fields")]
+    #[non_exhaustive]
+    UnsupportedRule {
+        /// The field the rule is declared on.
+        field: String,
+        /// The name of the `validator` rule.
+        rule: &'static str,
+        /// The kind of field, e.g. `integer`.
+        kind: &'static str,
+    },
+}
+
+/// A single rule collected from a `#[validate(...)]` attribute.
+enum Rule {
+    Email,
+    Url,
+    Ip,
+    MinLength(usize),
+    MaxLength(usize),
+    Min(f64),
+    Max(f64),
+    ExclusiveMin(f64),
+    ExclusiveMax(f64),
+    Contains(String),
+    DoesNotContain(String),
+    Pattern(String),
+}
+
+impl Rule {
+    /// Returns the `validator` name of the rule.
+    fn name(&self) -> &'static str {
+        match self {
+            Rule::Email => "email",
+            Rule::Url => "url",
+            Rule::Ip => "ip",
+            Rule::MinLength(_) | Rule::MaxLength(_) => "length",
+            Rule::Min(_) | Rule::Max(_) | Rule::ExclusiveMin(_) | Rule::ExclusiveMax(_) => "range",
+            Rule::Contains(_) => "contains",
+            Rule::DoesNotContain(_) => "does_not_contain",
+            Rule::Pattern(_) => "regex",
+        }
+    }
+
+    /// Returns the code of the errors the rule's constraint reports.
+    fn code(&self) -> &'static str {
+        match self {
+            Rule::Email => "invalid_email",
+            Rule::Url => "invalid_url",
+            Rule::Ip => "invalid_ip",
+            Rule::MinLength(_) => "min_length",
+            Rule::MaxLength(_) => "max_length",
+            Rule::Min(_) | Rule::ExclusiveMin(_) => "min_value",
+            Rule::Max(_) | Rule::ExclusiveMax(_) => "max_value",
+            Rule::Contains(_) => "invalid_substring",
+            Rule::DoesNotContain(_) => "forbidden_substring",
+            Rule::Pattern(_) => "pattern",
+        }
+    }
+}
+
+/// A rule with the `message` and `code` arguments it was given.
+struct RuleSpec {
+    rule: Rule,
+    message: Option<String>,
+    code: Option<String>,
+}
+
+/// The base schema kind derived from a field's Rust type.
+enum Kind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object(Box<ObjectSchema>),
+    Array(Box<FieldRules>),
+    /// A nested struct whose own schema could not be built.
+    Invalid(CompatError),
+}
+
+/// Rules for one struct field, accumulated from its type and attributes.
+///
+/// Built by [`validator_schema!`](crate::validator_schema); the builder
+/// methods mirror the `validator` rule names.
+pub struct FieldRules {
+    kind: Kind,
+    rules: Vec<RuleSpec>,
+    group_start: usize,
+    required: bool,
+    nullable: bool,
+    skip: bool,
+    rename: Option<String>,
+    must_match: Vec<String>,
+}
+
+impl FieldRules {
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            rules: Vec::new(),
+            group_start: 0,
+            required: true,
+            nullable: false,
+            skip: false,
+            rename: None,
+            must_match: Vec::new(),
+        }
+    }
+
+    /// Rules for a nested struct field validated by `schema`, or failing
+    /// with the error that kept the nested schema from being built.
+    pub fn object(schema: Result<ObjectSchema, CompatError>) -> Self {
+        match schema {
+            Ok(schema) => Self::new(Kind::Object(Box::new(schema))),
+            Err(error) => Self::new(Kind::Invalid(error)),
+        }
+    }
+
+    /// Rules for a `Vec<T>` field whose items follow `items`.
+    pub fn array(items: FieldRules) -> Self {
+        Self::new(Kind::Array(Box::new(items)))
+    }
+
+    /// Marks the field as an `Option`: it may be absent or null.
+    pub fn nullable(mut self) -> Self {
+        self.required = false;
+        self.nullable = true;
+        self
+    }
+
+    /// Starts a new `validator` rule; a following `message` applies to it.
+    pub fn begin(mut self) -> Self {
+        self.group_start = self.rules.len();
+        self
+    }
+
+    /// Sets the error message for every constraint of the current rule.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        let message = message.into();
+        for spec in &mut self.rules[self.group_start..] {
+            spec.message = Some(message.clone());
+        }
+        self
+    }
+
+    /// Sets the error code for every constraint of the current rule.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        let code = code.into();
+        for spec in &mut self.rules[self.group_start..] {
+            spec.code = Some(code.clone());
+        }
+        self
+    }
+
+    /// Uses `name` as the schema field name instead of the struct field name.
+    pub fn rename(mut self, name: impl Into<String>) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    /// `serde(default)`: the field may be absent.
+    pub fn default(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// `serde(skip)`: the field is not read from the payload.
+    pub fn skip(mut self) -> Self {
+        self.skip = true;
+        self
+    }
+
+    /// `required`: the field must be present and non-null.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self.nullable = false;
+        self
+    }
+
+    /// `email`
+    pub fn email(self) -> Self {
+        self.push(Rule::Email)
+    }
+
+    /// `url`
+    pub fn url(self) -> Self {
+        self.push(Rule::Url)
+    }
+
+    /// `ip`
+    pub fn ip(self) -> Self {
+        self.push(Rule::Ip)
+    }
+
+    /// `length(min = ..)`
+    pub fn min_length(self, min: usize) -> Self {
+        self.push(Rule::MinLength(min))
+    }
+
+    /// `length(max = ..)`
+    pub fn max_length(self, max: usize) -> Self {
+        self.push(Rule::MaxLength(max))
+    }
+
+    /// `range(min = ..)`
+    pub fn min(self, min: f64) -> Self {
+        self.push(Rule::Min(min))
+    }
+
+    /// `range(max = ..)`
+    pub fn max(self, max: f64) -> Self {
+        self.push(Rule::Max(max))
+    }
+
+    /// `range(exclusive_min = ..)`
+    pub fn exclusive_min(self, min: f64) -> Self {
+        self.push(Rule::ExclusiveMin(min))
+    }
+
+    /// `range(exclusive_max = ..)`
+    pub fn exclusive_max(self, max: f64) -> Self {
+        self.push(Rule::ExclusiveMax(max))
+    }
+
+    /// `contains(pattern = ..)`
+    pub fn contains(self, pattern: impl Into<String>) -> Self {
+        self.push(Rule::Contains(pattern.into()))
+    }
+
+    /// `does_not_contain(pattern = ..)`
+    pub fn does_not_contain(self, pattern: impl Into<String>) -> Self {
+        self.push(Rule::DoesNotContain(pattern.into()))
+    }
+
+    /// `regex(path = ..)`
+    pub fn regex(self, regex: &Regex) -> Self {
+        self.push(Rule::Pattern(regex.as_str().to_string()))
+    }
+
+    /// `must_match(other = ..)`: this field must equal `other`.
+    pub fn must_match(mut self, other: impl Into<String>) -> Self {
+        self.must_match.push(other.into());
+        self
+    }
+
+    fn push(mut self, rule: Rule) -> Self {
+        self.rules.push(RuleSpec {
+            rule,
+            message: None,
+            code: None,
+        });
+        self
+    }
+
+    /// Adds this field to `schema` under the name `fields` gives it, or its
+    /// own `serde` rename.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rule does not apply to the field's type.
+    pub fn apply(
+        mut self,
+        schema: ObjectSchema,
+        name: &str,
+        fields: &StructRules,
+    ) -> Result<ObjectSchema, CompatError> {
+        if self.skip {
+            return Ok(schema);
+        }
+        let name = self
+            .rename
+            .take()
+            .unwrap_or_else(|| fields.field_name(name));
+        let required = self.required && !fields.default;
+        let must_match = std::mem::take(&mut self.must_match);
+        let field = self.build(&name)?;
+
+        let mut schema = if required {
+            schema.field(name.clone(), field)
+        } else {
+            schema.optional(name.clone(), field)
+        };
+        for other in must_match {
+            schema = schema.equal_fields(fields.serde_name(&other), name.clone());
+        }
+        Ok(schema)
+    }
+
+    /// Builds the schema for this field, failing on rules that do not apply
+    /// to the field's type (e.g. `email` on an integer).
+    fn build(self, name: &str) -> Result<FieldSchema, CompatError> {
+        let codes: Vec<(&'static str, String)> = self
+            .rules
+            .iter()
+            .filter_map(|spec| Some((spec.rule.code(), spec.code.clone()?)))
+            .collect();
+        let schema = match self.kind {
+            Kind::String => {
+                let mut schema = Schema::string();
+                for RuleSpec { rule, message, .. } in self.rules {
+                    if let Rule::DoesNotContain(pattern) = rule {
+                        schema = schema.custom(does_not_contain(pattern, message));
+                        continue;
+                    }
+                    schema = match rule {
+                        Rule::Email => schema.email(),
+                        Rule::Url => schema.url(),
+                        Rule::Ip => schema.ip(),
+                        Rule::MinLength(min) => schema.min_len(min),
+                        Rule::MaxLength(max) => schema.max_len(max),
+                        Rule::Contains(pattern) => schema.contains(pattern),
+                        Rule::Pattern(pattern) => schema
+                            .pattern(&pattern)
+                            .expect("pattern was already compiled by the regex crate"),
+                        rule => return Err(unsupported(name, &rule, "string")),
+                    };
+                    if let Some(message) = message {
+                        schema = schema.error(message);
+                    }
+                }
+                FieldSchema::String(schema)
+            }
+            Kind::Integer => {
+                let mut schema = Schema::integer();
+                for RuleSpec { rule, message, .. } in self.rules {
+                    schema = match rule {
+                        Rule::Min(min) => schema.min(min.ceil() as i64),
+                        Rule::Max(max) => schema.max(max.floor() as i64),
+                        Rule::ExclusiveMin(min) => {
+                            schema.min((min.floor() as i64).saturating_add(1))
+                        }
+                        Rule::ExclusiveMax(max) => {
+                            schema.max((max.ceil() as i64).saturating_sub(1))
+                        }
+                        rule => return Err(unsupported(name, &rule, "integer")),
+                    };
+                    if let Some(message) = message {
+                        schema = schema.error(message);
+                    }
+                }
+                FieldSchema::Integer(schema)
+            }
+            Kind::Number => {
+                let mut schema = NumberSchema::new();
+                for RuleSpec { rule, message, .. } in self.rules {
+                    let bound = match rule {
+                        Rule::Min(v) => NumberBound::Min(v),
+                        Rule::Max(v) => NumberBound::Max(v),
+                        Rule::ExclusiveMin(v) => NumberBound::ExclusiveMin(v),
+                        Rule::ExclusiveMax(v) => NumberBound::ExclusiveMax(v),
+                        rule => return Err(unsupported(name, &rule, "number")),
+                    };
+                    schema = schema.bound(bound, message);
+                }
+                FieldSchema::Number(schema)
+            }
+            Kind::Boolean => {
+                if let Some(spec) = self.rules.first() {
+                    return Err(unsupported(name, &spec.rule, "boolean"));
+                }
+                FieldSchema::Boolean(BooleanSchema)
+            }
+            Kind::Object(schema) => {
+                if let Some(spec) = self.rules.first() {
+                    return Err(unsupported(name, &spec.rule, "nested struct"));
+                }
+                FieldSchema::Object(schema)
+            }
+            Kind::Invalid(error) => return Err(error),
+            Kind::Array(items) => {
+                let mut schema = ArraySchema::new(items.build(name)?);
+                for RuleSpec { rule, message, .. } in self.rules {
+                    schema = match rule {
+                        Rule::MinLength(min) => schema.min_len(min),
+                        Rule::MaxLength(max) => schema.max_len(max),
+                        rule => return Err(unsupported(name, &rule, "array")),
+                    };
+                    if let Some(message) = message {
+                        schema = schema.error(message);
+                    }
+                }
+                FieldSchema::Array(Box::new(schema))
+            }
+        };
+
+        let schema = if codes.is_empty() {
+            schema
+        } else {
+            FieldSchema::Recoded(Box::new(schema), codes)
+        };
+        Ok(if self.nullable {
+            FieldSchema::Nullable(Schema::optional(Box::new(schema)))
+        } else {
+            schema
+        })
+    }
+}
+
+/// Describes a rule that does not fit the field type.
+fn unsupported(field: &str, rule: &Rule, kind: &'static str) -> CompatError {
+    CompatError::UnsupportedRule {
+        field: field.to_string(),
+        rule: rule.name(),
+        kind,
+    }
+}
+
+/// Struct-level `serde` attributes that change how fields are read.
+///
+/// Built by [`validator_schema!`](crate::validator_schema).
+#[derive(Debug, Clone, Default)]
+pub struct StructRules {
+    rename_all: Option<KeyCase>,
+    default: bool,
+    deny_unknown_fields: bool,
+    /// Field renames, by struct field name.
+    renames: HashMap<String, String>,
+}
+
+impl StructRules {
+    /// Rules for a struct without `serde` attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `serde(rename_all = "..")`: renames every field to `case`.
+    pub fn rename_all(mut self, case: KeyCase) -> Self {
+        self.rename_all = Some(case);
+        self
+    }
+
+    /// `serde(default)`: every field may be absent.
+    pub fn all_default(mut self) -> Self {
+        self.default = true;
+        self
+    }
+
+    /// `serde(deny_unknown_fields)`: fields not declared are rejected.
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.deny_unknown_fields = true;
+        self
+    }
+
+    /// Records the `serde` renames of the struct's fields, so that
+    /// `must_match` compares against a renamed field under its new name.
+    pub fn fields(mut self, fields: &[(&str, FieldRules)]) -> Self {
+        for (name, rules) in fields {
+            if let Some(rename) = &rules.rename {
+                self.renames.insert(name.to_string(), rename.clone());
+            }
+        }
+        self
+    }
+
+    /// Returns the name a struct field is read under, honouring its own
+    /// rename if [`fields`](Self::fields) recorded one.
+    fn serde_name(&self, name: &str) -> String {
+        match self.renames.get(name) {
+            Some(rename) => rename.clone(),
+            None => self.field_name(name),
+        }
+    }
+
+    /// Returns the name a struct field is read under by `rename_all`.
+    fn field_name(&self, name: &str) -> String {
+        match self.rename_all {
+            Some(case) => case.apply(name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Applies the struct-level rules to the schema of the whole struct.
+    pub fn finish(&self, schema: ObjectSchema) -> ObjectSchema {
+        if self.deny_unknown_fields {
+            schema.additional_properties(false)
+        } else {
+            schema
+        }
+    }
+}
+
+/// Builds the custom string check for `does_not_contain`.
+fn does_not_contain(
+    pattern: String,
+    message: Option<String>,
+) -> impl Fn(&str, &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static {
+    move |s, path| {
+        if s.contains(&pattern) {
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| format!("must not contain '{}'", pattern));
+            Validation::Failure(SchemaErrors::single(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("forbidden_substring")
+                    .with_expected(format!("string not containing '{}'", pattern))
+                    .with_got(s),
+            ))
+        } else {
+            Validation::Success(())
+        }
+    }
+}
+
+/// The schema built for a field, dispatching to the concrete schema type.
+enum FieldSchema {
+    String(StringSchema),
+    Integer(IntegerSchema),
+    Number(NumberSchema),
+    Boolean(BooleanSchema),
+    Object(Box<ObjectSchema>),
+    Array(Box<ArraySchema<FieldSchema>>),
+    Nullable(CombinatorSchema),
+    /// A schema whose errors at the field itself take the codes given by
+    /// `code = ".."` arguments, keyed by the code they replace.
+    Recoded(Box<FieldSchema>, Vec<(&'static str, String)>),
+}
+
+/// Replaces the codes of the errors reported at `path` itself.
+fn recode(
+    result: Validation<Value, SchemaErrors>,
+    codes: &[(&'static str, String)],
+    path: &JsonPath,
+) -> Validation<Value, SchemaErrors> {
+    match result {
+        Validation::Failure(errors) => Validation::Failure(SchemaErrors::from_vec(
+            errors
+                .into_iter()
+                .map(|mut error| {
+                    if error.path == *path {
+                        if let Some((_, code)) = codes.iter().find(|(from, _)| *from == error.code)
+                        {
+                            error.code = code.clone();
+                        }
+                    }
+                    error
+                })
+                .collect(),
+        )),
+        success => success,
+    }
+}
+
+impl SchemaLike for FieldSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate_to_value(value, path)
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        match self {
+            FieldSchema::String(s) => s.validate_to_value(value, path),
+            FieldSchema::Integer(s) => s.validate_to_value(value, path),
+            FieldSchema::Number(s) => s.validate_to_value(value, path),
+            FieldSchema::Boolean(s) => s.validate_to_value(value, path),
+            FieldSchema::Object(s) => s.validate_to_value(value, path),
+            FieldSchema::Array(s) => s.validate_to_value(value, path),
+            FieldSchema::Nullable(s) => s.validate_to_value(value, path),
+            FieldSchema::Recoded(s, codes) => recode(s.validate_to_value(value, path), codes, path),
+        }
+    }
+
+    fn validate_to_value_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        match self {
+            FieldSchema::Object(s) => s.validate_to_value_with_context(value, path, context),
+            FieldSchema::Array(s) => s.validate_to_value_with_context(value, path, context),
+            FieldSchema::Nullable(s) => s.validate_to_value_with_context(value, path, context),
+            FieldSchema::Recoded(s, codes) => recode(
+                s.validate_to_value_with_context(value, path, context),
+                codes,
+                path,
+            ),
+            _ => self.validate_to_value(value, path),
+        }
+    }
+}
+
+impl ToJsonSchema for FieldSchema {
+    fn to_json_schema(&self) -> Value {
+        match self {
+            FieldSchema::String(s) => s.to_json_schema(),
+            FieldSchema::Integer(s) => s.to_json_schema(),
+            FieldSchema::Number(s) => s.to_json_schema(),
+            FieldSchema::Boolean(s) => s.to_json_schema(),
+            FieldSchema::Object(s) => s.to_json_schema(),
+            FieldSchema::Array(s) => s.to_json_schema(),
+            FieldSchema::Nullable(s) => s.to_json_schema(),
+            FieldSchema::Recoded(s, _) => s.to_json_schema(),
+        }
+    }
+}
+
+impl CompatType for String {
+    fn rules() -> FieldRules {
+        FieldRules::new(Kind::String)
+    }
+}
+
+impl CompatType for bool {
+    fn rules() -> FieldRules {
+        FieldRules::new(Kind::Boolean)
+    }
+}
+
+macro_rules! compat_types {
+    ($kind:ident: $($ty:ty),*) => {
+        $(
+            impl CompatType for $ty {
+                fn rules() -> FieldRules {
+                    FieldRules::new(Kind::$kind)
+                }
+            }
+        )*
+    };
+}
+
+compat_types!(Integer: i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+compat_types!(Number: f32, f64);
+
+impl<T: CompatType> CompatType for Option<T> {
+    fn rules() -> FieldRules {
+        T::rules().nullable()
+    }
+}
+
+impl<T: CompatType> CompatType for Vec<T> {
+    fn rules() -> FieldRules {
+        FieldRules::array(T::rules())
+    }
+}
+
+/// Declares a struct and generates a postmortem schema from its
+/// `validator` crate `#[validate(...)]` attributes.
+///
+/// The struct is emitted unchanged, so `#[derive(Validate)]` and `serde`
+/// derives keep working during a migration. The macro adds an associated
+/// `postmortem_schema()` function returning an [`ObjectSchema`](crate::ObjectSchema)
+/// and implements [`CompatType`](crate::compat::validator::CompatType) so the
+/// struct can be used as a nested field of another wrapped struct.
+///
+/// `postmortem_schema()` returns a
+/// [`CompatError`](crate::compat::validator::CompatError) if a rule does not
+/// apply to its field's type. See the
+/// [`compat::validator`](crate::compat::validator) module for the supported
+/// rules, field types, and `serde` attributes.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{validator_schema, JsonPath};
+/// use serde_json::json;
+///
+/// validator_schema! {
+///     // #[derive(Deserialize, Validate)] would normally go here
+///     pub struct SignupForm {
+///         pub username: String,
+///         pub age: u32,
+///         pub nickname: Option<String>,
+///     }
+/// }
+///
+/// let schema = SignupForm::postmortem_schema().unwrap();
+/// let result = schema.validate(&json!({"username": "alice", "age": "thirty"}), &JsonPath::root());
+/// assert!(result.is_failure());
+/// ```
+#[macro_export]
+macro_rules! validator_schema {
+    (
+        $(#[$($sattr:tt)*])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$($fattr:tt)*])*
+                $fvis:vis $field:ident : $fty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$($sattr)*])*
+        $vis struct $name {
+            $(
+                $(#[$($fattr)*])*
+                $fvis $field: $fty,
+            )*
+        }
+
+        impl $name {
+            /// Returns the postmortem schema equivalent to this struct's
+            /// `#[validate(...)]` and `#[serde(...)]` attributes.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if a rule does not apply to its field's type.
+            #[allow(dead_code)]
+            pub fn postmortem_schema(
+            ) -> ::std::result::Result<$crate::ObjectSchema, $crate::compat::validator::CompatError>
+            {
+                let rules = [
+                    $(
+                        (
+                            stringify!($field),
+                            $crate::validator_schema!(
+                                @field <$fty as $crate::compat::validator::CompatType>::rules();
+                                $([$($fattr)*])*
+                            ),
+                        ),
+                    )*
+                ];
+                let fields = $crate::validator_schema!(
+                    @struct $crate::compat::validator::StructRules::new();
+                    $([$($sattr)*])*
+                )
+                .fields(&rules);
+                let mut schema = $crate::Schema::object();
+                for (name, rules) in rules {
+                    schema = rules.apply(schema, name, &fields)?;
+                }
+                Ok(fields.finish(schema))
+            }
+        }
+
+        impl $crate::compat::validator::CompatType for $name {
+            fn rules() -> $crate::compat::validator::FieldRules {
+                $crate::compat::validator::FieldRules::object($name::postmortem_schema())
+            }
+        }
+    };
+
+    // Struct attributes
+    (@struct $s:expr;) => { $s };
+    (@struct $s:expr; [serde($($args:tt)*)] $($rest:tt)*) => {
+        $crate::validator_schema!(@struct $crate::validator_schema!(@serde_struct $s; $($args)*); $($rest)*)
+    };
+    (@struct $s:expr; [$($other:tt)*] $($rest:tt)*) => {
+        $crate::validator_schema!(@struct $s; $($rest)*)
+    };
+
+    // Comma-separated arguments of a struct's #[serde(...)]
+    (@serde_struct $s:expr;) => { $s };
+    (@serde_struct $s:expr; rename_all = "snake_case" $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @serde_struct $s.rename_all($crate::KeyCase::SnakeCase); $($($rest)*)?
+        )
+    };
+    (@serde_struct $s:expr; rename_all = "camelCase" $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @serde_struct $s.rename_all($crate::KeyCase::CamelCase); $($($rest)*)?
+        )
+    };
+    (@serde_struct $s:expr; rename_all = "PascalCase" $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @serde_struct $s.rename_all($crate::KeyCase::PascalCase); $($($rest)*)?
+        )
+    };
+    (@serde_struct $s:expr; rename_all = "kebab-case" $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @serde_struct $s.rename_all($crate::KeyCase::KebabCase); $($($rest)*)?
+        )
+    };
+    (@serde_struct $s:expr; rename_all = "SCREAMING_SNAKE_CASE" $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @serde_struct $s.rename_all($crate::KeyCase::ScreamingSnakeCase); $($($rest)*)?
+        )
+    };
+    (@serde_struct $s:expr; default $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde_struct $s.all_default(); $($($rest)*)?)
+    };
+    (@serde_struct $s:expr; deny_unknown_fields $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde_struct $s.deny_unknown_fields(); $($($rest)*)?)
+    };
+    // The struct's own name, bounds, and crate path do not affect its fields
+    (@serde_struct $s:expr; rename = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde_struct $s; $($($rest)*)?)
+    };
+    (@serde_struct $s:expr; bound = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde_struct $s; $($($rest)*)?)
+    };
+    (@serde_struct $s:expr; crate = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde_struct $s; $($($rest)*)?)
+    };
+    (@serde_struct $s:expr; $key:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "serde attribute `",
+            stringify!($key $($rest)*),
+            "` on a struct is not supported by validator_schema!"
+        ))
+    };
+
+    // Field attributes
+    (@field $f:expr;) => { $f };
+    (@field $f:expr; [validate($($rules:tt)*)] $($rest:tt)*) => {
+        $crate::validator_schema!(@field $crate::validator_schema!(@rules $f; $($rules)*); $($rest)*)
+    };
+    (@field $f:expr; [serde($($args:tt)*)] $($rest:tt)*) => {
+        $crate::validator_schema!(@field $crate::validator_schema!(@serde $f; $($args)*); $($rest)*)
+    };
+    (@field $f:expr; [$($other:tt)*] $($rest:tt)*) => {
+        $crate::validator_schema!(@field $f; $($rest)*)
+    };
+
+    // Comma-separated arguments of a field's #[serde(...)]
+    (@serde $f:expr;) => { $f };
+    (@serde $f:expr; rename = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f.rename($v); $($($rest)*)?)
+    };
+    (@serde $f:expr; default $(= $v:literal)? $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f.default(); $($($rest)*)?)
+    };
+    (@serde $f:expr; skip $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f.skip(); $($($rest)*)?)
+    };
+    (@serde $f:expr; skip_deserializing $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f.skip(); $($($rest)*)?)
+    };
+    // Serialization-only attributes do not affect the accepted JSON
+    (@serde $f:expr; skip_serializing $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f; $($($rest)*)?)
+    };
+    (@serde $f:expr; skip_serializing_if = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f; $($($rest)*)?)
+    };
+    (@serde $f:expr; serialize_with = $v:literal $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@serde $f; $($($rest)*)?)
+    };
+    (@serde $f:expr; $key:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "serde attribute `",
+            stringify!($key $($rest)*),
+            "` on a field is not supported by validator_schema!"
+        ))
+    };
+
+    // Comma-separated rules inside #[validate(...)]
+    (@rules $f:expr;) => { $f };
+    (@rules $f:expr; contains = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@rules $f.begin().contains($v); $($($rest)*)?)
+    };
+    (@rules $f:expr; must_match = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@rules $f.begin().must_match($v); $($($rest)*)?)
+    };
+    (@rules $f:expr; $rule:ident($($args:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @rules $crate::validator_schema!(@rule $f.begin(); $rule; $($args)*);
+            $($($rest)*)?
+        )
+    };
+    (@rules $f:expr; $rule:ident $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @rules $crate::validator_schema!(@rule $f.begin(); $rule;);
+            $($($rest)*)?
+        )
+    };
+
+    // Individual rules
+    (@rule $f:expr; email; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f.email(); email; $($args)*)
+    };
+    (@rule $f:expr; url; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f.url(); url; $($args)*)
+    };
+    (@rule $f:expr; ip; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f.ip(); ip; $($args)*)
+    };
+    (@rule $f:expr; required; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f.required(); required; $($args)*)
+    };
+    (@rule $f:expr; nested; $($args:tt)*) => { $f };
+    (@rule $f:expr; length; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; length; $($args)*)
+    };
+    (@rule $f:expr; range; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; range; $($args)*)
+    };
+    (@rule $f:expr; contains; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; contains; $($args)*)
+    };
+    (@rule $f:expr; does_not_contain; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; does_not_contain; $($args)*)
+    };
+    (@rule $f:expr; regex; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; regex; $($args)*)
+    };
+    (@rule $f:expr; must_match; $($args:tt)*) => {
+        $crate::validator_schema!(@args $f; must_match; $($args)*)
+    };
+    (@rule $f:expr; $other:ident; $($args:tt)*) => {
+        compile_error!(concat!(
+            "validator rule `",
+            stringify!($other),
+            "` is not supported by validator_schema!"
+        ))
+    };
+
+    // Rule arguments
+    (@args $f:expr; $rule:ident;) => { $f };
+    (@args $f:expr; $rule:ident; message = $m:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.message($m); $rule; $($($rest)*)?)
+    };
+    (@args $f:expr; required; code = $c:expr $(, $($rest:tt)*)?) => {
+        compile_error!("argument `code` of validator rule `required` is not supported by validator_schema!")
+    };
+    (@args $f:expr; must_match; code = $c:expr $(, $($rest:tt)*)?) => {
+        compile_error!("argument `code` of validator rule `must_match` is not supported by validator_schema!")
+    };
+    (@args $f:expr; $rule:ident; code = $c:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.code($c); $rule; $($($rest)*)?)
+    };
+    (@args $f:expr; length; min = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.min_length($v as usize); length; $($($rest)*)?)
+    };
+    (@args $f:expr; length; max = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.max_length($v as usize); length; $($($rest)*)?)
+    };
+    (@args $f:expr; length; equal = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(
+            @args $f.min_length($v as usize).max_length($v as usize); length; $($($rest)*)?
+        )
+    };
+    (@args $f:expr; range; min = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.min($v as f64); range; $($($rest)*)?)
+    };
+    (@args $f:expr; range; max = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.max($v as f64); range; $($($rest)*)?)
+    };
+    (@args $f:expr; range; exclusive_min = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.exclusive_min($v as f64); range; $($($rest)*)?)
+    };
+    (@args $f:expr; range; exclusive_max = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.exclusive_max($v as f64); range; $($($rest)*)?)
+    };
+    (@args $f:expr; contains; pattern = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.contains($v); contains; $($($rest)*)?)
+    };
+    (@args $f:expr; does_not_contain; pattern = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.does_not_contain($v); does_not_contain; $($($rest)*)?)
+    };
+    (@args $f:expr; regex; path = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.regex(&$v); regex; $($($rest)*)?)
+    };
+    (@args $f:expr; must_match; other = $v:expr $(, $($rest:tt)*)?) => {
+        $crate::validator_schema!(@args $f.must_match($v); must_match; $($($rest)*)?)
+    };
+    (@args $f:expr; $rule:ident; $key:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "argument `",
+            stringify!($key),
+            "` of validator rule `",
+            stringify!($rule),
+            "` is not supported by validator_schema!"
+        ))
+    };
+}
@@ -0,0 +1,314 @@
+//! Conversions between postmortem and the `validify` crate.
+//!
+//! This module is only available when the `validify` feature is enabled.
+//!
+//! - [`ValidifySchema`] runs a `validify::Validate` type as a postmortem
+//!   schema, so existing validify rule sets can be registered or nested like
+//!   any other schema.
+//! - [`validify_rule`] goes the other way and wraps a postmortem schema as a
+//!   function usable with `#[validate(custom(...))]`.
+//! - [`from_validation_errors`] and [`to_validation_errors`] convert between
+//!   the two error types.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use ::validify::{Validate, ValidationError, ValidationErrors};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::{JsonPath, PathSegment};
+use crate::schema::{SchemaLike, ValueValidator};
+
+use super::{deserialize_value, serialize_value};
+
+/// Error code used for postmortem errors converted to validify errors.
+///
+/// Validify codes must be `'static`, so the original postmortem code is kept
+/// in the `code` parameter instead.
+pub const POSTMORTEM_CODE: &str = "postmortem";
+
+/// A schema that deserializes a value into `T` and runs its validify rules.
+///
+/// Deserialization failures are reported with code `invalid_type`; validify
+/// failures keep their validify code and location, relative to the position
+/// of this schema.
+pub struct ValidifySchema<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + Validate> ValidifySchema<T> {
+    /// Creates a schema validating values with `T`'s validify rules.
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Validate> Default for ValidifySchema<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ValidifySchema<T> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for ValidifySchema<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidifySchema")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T: DeserializeOwned + Validate> SchemaLike for ValidifySchema<T> {
+    type Output = T;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<T, SchemaErrors> {
+        let typed: T = match deserialize_value(value, path) {
+            Validation::Success(typed) => typed,
+            Validation::Failure(errors) => return Validation::Failure(errors),
+        };
+
+        match typed.validate() {
+            Ok(()) => Validation::Success(typed),
+            Err(errors) => match from_validation_errors(&errors, path) {
+                Some(errors) => Validation::Failure(errors),
+                None => Validation::Success(typed),
+            },
+        }
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path).map(|_| value.clone())
+    }
+}
+
+impl<T> ToJsonSchema for ValidifySchema<T> {
+    fn to_json_schema(&self) -> Value {
+        // validify rules are opaque, so only the presence of a value is described
+        json!({})
+    }
+}
+
+/// Converts validify errors into postmortem errors rooted at `base`.
+///
+/// Validify locations (`/address/lines/0`) become paths; the validify code is
+/// kept as the error code. Returns `None` if there are no errors.
+pub fn from_validation_errors(errors: &ValidationErrors, base: &JsonPath) -> Option<SchemaErrors> {
+    let errors: Vec<SchemaError> = errors
+        .errors()
+        .iter()
+        .map(|error| {
+            let path = match (error.location(), error.field_name()) {
+                ("" | "/", Some(field)) => base.push_field(field),
+                (location, _) => parse_location(location, base),
+            };
+            let message = error.message().unwrap_or_else(|| error.code());
+            let mut converted = SchemaError::new(path, message).with_code(error.code());
+            let params = error.params();
+            if let Some(expected) = params.get("target") {
+                converted = converted.with_expected(param_to_string(expected));
+            }
+            if let Some(got) = params.get("actual") {
+                converted = converted.with_got(param_to_string(got));
+            }
+            converted
+        })
+        .collect();
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(SchemaErrors::from_vec(errors))
+    }
+}
+
+/// Converts postmortem errors into validify field errors.
+///
+/// Every error uses the [`POSTMORTEM_CODE`] code, with the original code in
+/// the `code` parameter and `expected`/`got` as `target`/`actual`.
+pub fn to_validation_errors(errors: &SchemaErrors) -> ValidationErrors {
+    let mut converted = ValidationErrors::new();
+    for error in errors.iter() {
+        converted.add(to_validation_error(error));
+    }
+    converted
+}
+
+/// Converts a single postmortem error, using its path as the location.
+fn to_validation_error(error: &SchemaError) -> ValidationError {
+    let mut converted = ValidationError::new_field(POSTMORTEM_CODE)
+        .with_message(error.message.clone())
+        .with_param("code", &error.code);
+    if let Some(expected) = &error.expected {
+        converted.add_param("target", expected);
+    }
+    if let Some(got) = &error.got {
+        converted.add_param("actual", got);
+    }
+    for segment in error.path.segments() {
+        match segment {
+            PathSegment::Field(name) => converted.append_location(name),
+            PathSegment::Index(index) => converted.append_location(index),
+        }
+    }
+    converted
+}
+
+/// Wraps a postmortem schema as a validify custom rule.
+///
+/// The returned function serializes the field value to JSON and validates it
+/// against `schema`. A validify custom rule reports one error per field, so
+/// only the first postmortem error is returned; nested paths are kept in the
+/// error location.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use postmortem::compat::validify::validify_rule;
+/// use postmortem::Schema;
+///
+/// fn username(value: &str) -> Result<(), validify::ValidationError> {
+///     validify_rule(Schema::string().not_one_of(["admin"]))(value)
+/// }
+///
+/// #[derive(validify::Validate)]
+/// struct Signup {
+///     #[validate(custom(username))]
+///     username: String,
+/// }
+/// ```
+pub fn validify_rule<S, T>(schema: S) -> impl Fn(&T) -> Result<(), ValidationError>
+where
+    S: ValueValidator,
+    T: Serialize + ?Sized,
+{
+    move |value| {
+        let json = serialize_value(value)
+            .map_err(|message| ValidationError::new_field(POSTMORTEM_CODE).with_message(message))?;
+        match schema.validate_value(&json, &JsonPath::root()) {
+            Validation::Success(_) => Ok(()),
+            Validation::Failure(errors) => Err(to_validation_error(errors.first())),
+        }
+    }
+}
+
+/// Parses a validify location into a [`JsonPath`] appended to `base`.
+fn parse_location(location: &str, base: &JsonPath) -> JsonPath {
+    location
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .fold(base.clone(), |acc, segment| match segment.parse() {
+            Ok(index) => acc.push_index(index),
+            Err(_) => acc.push_field(segment),
+        })
+}
+
+/// Renders a validify parameter, without quotes for strings.
+fn param_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Address {
+        #[validate(length(min = 1))]
+        city: String,
+        #[validate(length(max = 5))]
+        zip: String,
+    }
+
+    #[test]
+    fn test_validify_schema_accepts_valid_value() {
+        let schema = ValidifySchema::<Address>::new();
+        let result = schema.validate(
+            &json!({"city": "Berlin", "zip": "10115"}),
+            &JsonPath::root(),
+        );
+        assert!(result.is_success());
+    }
+
+    #[test]
+    fn test_validify_schema_maps_errors() {
+        let schema = Schema::object().field("address", ValidifySchema::<Address>::new());
+        let result = schema.validate(
+            &json!({"address": {"city": "", "zip": "1011500"}}),
+            &JsonPath::root(),
+        );
+
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert!(paths.contains(&"address.city".to_string()));
+        assert!(paths.contains(&"address.zip".to_string()));
+        assert!(errors.iter().all(|e| e.code == "length"));
+    }
+
+    #[test]
+    fn test_validify_schema_reports_deserialization_errors() {
+        let schema = ValidifySchema::<Address>::new();
+        let result = schema.validate(&json!("not an object"), &JsonPath::root());
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.first().code, "invalid_type");
+    }
+
+    #[test]
+    fn test_to_validation_errors_round_trip() {
+        let errors = SchemaErrors::single(
+            SchemaError::new(
+                JsonPath::root().push_field("tags").push_index(2),
+                "too short",
+            )
+            .with_code("min_length")
+            .with_expected("at least 3 characters"),
+        );
+
+        let converted = to_validation_errors(&errors);
+        let error = &converted.errors()[0];
+        assert_eq!(error.location(), "/tags/2");
+        assert_eq!(error.code(), POSTMORTEM_CODE);
+        assert_eq!(error.params()["code"], json!("min_length"));
+
+        let back = from_validation_errors(&converted, &JsonPath::root()).unwrap();
+        assert_eq!(back.first().path, errors.first().path);
+        assert_eq!(back.first().message, "too short");
+        assert_eq!(
+            back.first().expected.as_deref(),
+            Some("at least 3 characters")
+        );
+    }
+
+    #[test]
+    fn test_from_empty_validation_errors() {
+        assert!(from_validation_errors(&ValidationErrors::new(), &JsonPath::root()).is_none());
+    }
+
+    #[test]
+    fn test_validify_rule() {
+        let rule = validify_rule(Schema::string().not_one_of(["admin"]));
+
+        assert!(rule("alice").is_ok());
+        let error = rule("admin").unwrap_err();
+        assert!(error.message().unwrap().contains("must not be one of"));
+    }
+}
@@ -0,0 +1,1156 @@
+//! Schemas generated from SQL table definitions.
+//!
+//! [`TableDefinitions`] replays `CREATE TABLE` and `ALTER TABLE` statements,
+//! from migrations or a `pg_dump --schema-only` dump, and turns each table
+//! into an [`ObjectSchema`]:
+//!
+//! | SQL | Schema |
+//! |-----|--------|
+//! | `VARCHAR(n)`, `CHAR(n)`, `TEXT` | string, with `max_len(n)` when bounded |
+//! | `UUID`, `DATE`, `TIMESTAMP`, `INET` | string with the matching format |
+//! | `SMALLINT`, `INTEGER`, `BIGINT`, serials | integer, within the type's range |
+//! | `NUMERIC`, `REAL`, `DOUBLE PRECISION` | number |
+//! | `BOOLEAN` | boolean |
+//! | `T[]` | array of `T` |
+//! | anything else (`JSONB`, ...) | any value |
+//!
+//! `NOT NULL` columns without a default are required; nullable columns accept
+//! `null`. `CHECK` constraints comparing a column (or its `char_length`) to a
+//! constant, `BETWEEN` ranges, and `IN (...)` lists become range, length, and
+//! `one_of` constraints. Other checks are ignored.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Value;
+use stillwater::Validation;
+
+use crate::error::SchemaErrors;
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+use crate::registry::{RegistryError, SchemaRegistry};
+use crate::schema::primitive::{AnySchema, BooleanSchema, NumberBound, NumberSchema};
+use crate::schema::{ArraySchema, IntegerSchema, ObjectSchema, Schema, SchemaLike, StringSchema};
+use crate::validation::ValidationContext;
+
+use super::migrations::{
+    bounded_length, identifier, migration_files, split_top_level, strip_comments, ADD_COLUMN,
+    ALTER_COLUMN_TYPE, ALTER_TABLE, CREATE_TABLE, DROP_COLUMN, DROP_TABLE, MODIFY_COLUMN,
+    TABLE_CONSTRAINTS,
+};
+
+static ADD_CONSTRAINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^add\s+((?:constraint|check|primary|unique|foreign|exclude)\b.*)$").unwrap()
+});
+
+static ALTER_COLUMN_SET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^alter\s+(?:column\s+)?(\S+)\s+(set|drop)\s+(not\s+null|default)\b").unwrap()
+});
+
+static DROP_CONSTRAINT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^drop\s+constraint\s+(?:if\s+exists\s+)?(\S+)").unwrap());
+
+static RENAME_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^rename\s+(?:column\s+)?(\S+)\s+to\s+(\S+)$").unwrap());
+
+static RENAME_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^rename\s+to\s+(\S+)$").unwrap());
+
+static NAMED_CONSTRAINT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^constraint\s+(\S+)\s+(.*)$").unwrap());
+
+static PRIMARY_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^primary\s+key\s*\((.*)\)").unwrap());
+
+static CHECK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:\bconstraint\s+(\S+)\s+)?\bcheck\s*\(").unwrap());
+
+static NOT_NULL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:not\s+null|primary\s+key)\b").unwrap());
+
+static DEFAULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:default|generated|auto_increment|autoincrement)\b").unwrap()
+});
+
+static CAST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)::\s*(?:character\s+varying|double\s+precision|timestamp\s+with(?:out)?\s+time\s+zone|[a-z_]\w*)(?:\s*\(\d+(?:\s*,\s*\d+)?\))?(?:\[\])?",
+    )
+    .unwrap()
+});
+
+static WRAPPED_OPERAND: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(^|[^\w])\(\s*("?[\w.]+"?|'[^']*')\s*\)"#).unwrap());
+
+static BETWEEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\S+)\s+between\s+(-?\d+(?:\.\d+)?)\s+and\s+(-?\d+(?:\.\d+)?)").unwrap()
+});
+
+static AND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\s+and\s+").unwrap());
+
+static OR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(?:or|not)\b").unwrap());
+
+static LENGTH_CHECK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:char_length|character_length|length)\s*\(\s*([^\s()]+)\s*\)\s*(>=|<=|=|>|<)\s*(\d+)$",
+    )
+    .unwrap()
+});
+
+static COMPARISON: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^\s()<>=!']+)\s*(>=|<=|=|>|<)\s*(-?\d+(?:\.\d+)?)$").unwrap());
+
+static NOT_EMPTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^\s()<>=!']+)\s*(?:<>|!=)\s*''$").unwrap());
+
+static IN_LIST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^([^\s()]+)\s+in\s*\((.*)\)$|^([^\s()]+)\s*=\s*any\s*\(+\s*array\s*\[(.*)\]\s*\)+$",
+    )
+    .unwrap()
+});
+
+/// Keywords that end the type in a column definition.
+const COLUMN_CONSTRAINTS: &[&str] = &[
+    "not",
+    "null",
+    "default",
+    "primary",
+    "unique",
+    "check",
+    "references",
+    "constraint",
+    "generated",
+    "collate",
+    "using",
+    "auto_increment",
+    "autoincrement",
+    "comment",
+    "on",
+];
+
+/// A rule extracted from a `CHECK` constraint on a single column.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ColumnCheck {
+    /// `column >= n`
+    Min(f64),
+    /// `column <= n`
+    Max(f64),
+    /// `column > n`
+    ExclusiveMin(f64),
+    /// `column < n`
+    ExclusiveMax(f64),
+    /// `char_length(column) >= n`, or `column <> ''` for a length of 1
+    MinLength(usize),
+    /// `char_length(column) <= n`
+    MaxLength(usize),
+    /// `column IN ('a', 'b')`
+    OneOf(Vec<String>),
+}
+
+/// A column of a [`TableDefinition`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDefinition {
+    name: String,
+    sql_type: String,
+    not_null: bool,
+    has_default: bool,
+    checks: Vec<(String, ColumnCheck)>,
+}
+
+impl ColumnDefinition {
+    /// Returns the column name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the column type, lowercased, e.g. `varchar(50)`.
+    pub fn sql_type(&self) -> &str {
+        &self.sql_type
+    }
+
+    /// Returns whether the column accepts `NULL`.
+    pub fn is_nullable(&self) -> bool {
+        !self.not_null
+    }
+
+    /// Returns whether the database fills in the column when it is omitted,
+    /// through a `DEFAULT`, a serial type, or a generated/identity column.
+    pub fn has_default(&self) -> bool {
+        self.has_default
+    }
+
+    /// Returns whether the column must be present when inserting a row.
+    pub fn is_required(&self) -> bool {
+        self.not_null && !self.has_default
+    }
+
+    /// Returns the rules extracted from `CHECK` constraints on this column.
+    pub fn checks(&self) -> impl Iterator<Item = &ColumnCheck> {
+        self.checks.iter().map(|(_, check)| check)
+    }
+
+    /// Builds the schema for this column's values, ignoring nullability.
+    fn value_schema(&self) -> ColumnSchema {
+        column_schema(&self.sql_type, &self.checks)
+    }
+}
+
+/// A table reconstructed from DDL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDefinition {
+    name: String,
+    columns: Vec<ColumnDefinition>,
+}
+
+impl TableDefinition {
+    /// Returns the table name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the columns in definition order.
+    pub fn columns(&self) -> &[ColumnDefinition] {
+        &self.columns
+    }
+
+    /// Returns the column named `name`.
+    pub fn column(&self, name: &str) -> Option<&ColumnDefinition> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Builds an object schema for rows of this table.
+    ///
+    /// Required columns become required fields; columns with a default or
+    /// that accept `NULL` become optional fields, and nullable columns also
+    /// accept `null` values.
+    pub fn to_schema(&self) -> ObjectSchema {
+        self.columns
+            .iter()
+            .fold(Schema::object(), |schema, column| {
+                let value = column.value_schema();
+                match (column.is_required(), column.is_nullable()) {
+                    (true, _) => schema.field(column.name.clone(), value),
+                    (false, false) => schema.optional(column.name.clone(), value),
+                    (false, true) => {
+                        schema.optional(column.name.clone(), Schema::optional(Box::new(value)))
+                    }
+                }
+            })
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut ColumnDefinition> {
+        self.columns.iter_mut().find(|c| c.name == name)
+    }
+
+    fn set_column(&mut self, column: ColumnDefinition) {
+        match self.column_mut(&column.name) {
+            Some(existing) => *existing = column,
+            None => self.columns.push(column),
+        }
+    }
+
+    /// Applies a table constraint such as `PRIMARY KEY (id)` or
+    /// `CONSTRAINT positive_price CHECK (price > 0)`.
+    fn apply_constraint(&mut self, constraint: &str) {
+        let (name, body) = match NAMED_CONSTRAINT.captures(constraint) {
+            Some(caps) => (
+                Some(identifier(caps.get(1).unwrap().as_str())),
+                caps.get(2).unwrap().as_str(),
+            ),
+            None => (None, constraint),
+        };
+
+        if let Some(caps) = PRIMARY_KEY.captures(body) {
+            for column in caps[1].split(',') {
+                if let Some(column) = self.column_mut(&identifier(column.trim())) {
+                    column.not_null = true;
+                }
+            }
+        } else if CHECK.is_match(body) {
+            if let Some(expression) = parenthesized(body) {
+                self.add_checks(name.as_deref(), expression);
+            }
+        }
+    }
+
+    /// Attaches the rules of a `CHECK` expression to the columns they test.
+    ///
+    /// Unnamed constraints get PostgreSQL's default name, `table_column_check`,
+    /// so a later `DROP CONSTRAINT` can remove them.
+    fn add_checks(&mut self, name: Option<&str>, expression: &str) {
+        let table = self.name.clone();
+        for (column, check) in parse_check(expression) {
+            let constraint = name
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}_{}_check", table, column));
+            if let Some(column) = self.column_mut(&column) {
+                column.checks.push((constraint, check));
+            }
+        }
+    }
+}
+
+/// Table definitions replayed from SQL DDL.
+///
+/// This is a starting point for write schemas that stays in sync with the
+/// database: regenerate the schemas from the migrations directory (or a
+/// `pg_dump --schema-only` dump of a live database) and compare their
+/// [`to_json_schema`](crate::ToJsonSchema::to_json_schema) output with the
+/// hand-maintained schemas to spot drift.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::db::TableDefinitions;
+/// use postmortem::{JsonPath, SchemaLike};
+/// use serde_json::json;
+///
+/// let tables = TableDefinitions::from_sql(
+///     "CREATE TABLE products (
+///          id BIGSERIAL PRIMARY KEY,
+///          name VARCHAR(100) NOT NULL,
+///          price NUMERIC(10, 2) NOT NULL CHECK (price > 0),
+///          status TEXT NOT NULL DEFAULT 'draft' CHECK (status IN ('draft', 'live'))
+///      );",
+/// );
+///
+/// let schema = tables.schema("products").unwrap();
+/// let result = schema.validate(&json!({"name": "Lamp", "price": 0}), &JsonPath::root());
+/// assert_eq!(result.into_result().unwrap_err().first().code, "min_value");
+///
+/// let result = schema.validate(&json!({"name": "Lamp", "price": 25.5}), &JsonPath::root());
+/// assert!(result.is_success());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableDefinitions {
+    tables: HashMap<String, TableDefinition>,
+}
+
+impl TableDefinitions {
+    /// Creates an empty set of table definitions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses table definitions from SQL, applying statements in order.
+    pub fn from_sql(sql: &str) -> Self {
+        let mut tables = Self::new();
+        tables.apply_sql(sql);
+        tables
+    }
+
+    /// Parses table definitions from every `.sql` file in a migrations
+    /// directory, in the same order as
+    /// [`ColumnLimits::from_migrations_dir`](super::ColumnLimits::from_migrations_dir).
+    pub fn from_migrations_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut tables = Self::new();
+        for file in migration_files(dir.as_ref())? {
+            tables.apply_sql(&fs::read_to_string(file)?);
+        }
+        Ok(tables)
+    }
+
+    /// Returns the definition of `table`.
+    pub fn table(&self, table: &str) -> Option<&TableDefinition> {
+        self.tables.get(table)
+    }
+
+    /// Returns all table definitions, ordered by name.
+    pub fn tables(&self) -> impl Iterator<Item = &TableDefinition> {
+        let mut tables: Vec<_> = self.tables.values().collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        tables.into_iter()
+    }
+
+    /// Builds the object schema for rows of `table`.
+    pub fn schema(&self, table: &str) -> Option<ObjectSchema> {
+        self.table(table).map(TableDefinition::to_schema)
+    }
+
+    /// Registers every table's schema under the table name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::DuplicateName` if a table name is already
+    /// registered; tables registered before the conflict remain registered.
+    pub fn register_all(&self, registry: &SchemaRegistry) -> Result<(), RegistryError> {
+        for table in self.tables() {
+            registry.register(table.name.clone(), table.to_schema())?;
+        }
+        Ok(())
+    }
+
+    /// Applies the statements in `sql` on top of the current definitions.
+    pub fn apply_sql(&mut self, sql: &str) {
+        for statement in strip_comments(sql).split(';') {
+            self.apply_statement(statement.trim());
+        }
+    }
+
+    fn apply_statement(&mut self, statement: &str) {
+        if let Some(caps) = CREATE_TABLE.captures(statement) {
+            let mut table = TableDefinition {
+                name: identifier(&caps[1]),
+                columns: Vec::new(),
+            };
+            let mut constraints = Vec::new();
+            for definition in split_top_level(&caps[2]) {
+                let first = definition.split_whitespace().next().unwrap_or("");
+                if TABLE_CONSTRAINTS.contains(&first.to_lowercase().as_str()) {
+                    constraints.push(definition);
+                } else {
+                    add_column(&mut table, definition);
+                }
+            }
+            // Table constraints may precede the columns they reference
+            for constraint in constraints {
+                table.apply_constraint(constraint);
+            }
+            self.tables.insert(table.name.clone(), table);
+        } else if let Some(caps) = ALTER_TABLE.captures(statement) {
+            let name = identifier(&caps[1]);
+            if let Some(caps) = RENAME_TABLE.captures(caps[2].trim()) {
+                if let Some(mut table) = self.tables.remove(&name) {
+                    table.name = identifier(&caps[1]);
+                    self.tables.insert(table.name.clone(), table);
+                }
+                return;
+            }
+            let Some(table) = self.tables.get_mut(&name) else {
+                return;
+            };
+            for action in split_top_level(&caps[2]) {
+                apply_action(table, action);
+            }
+        } else if let Some(caps) = DROP_TABLE.captures(statement) {
+            for table in caps[1].split(',') {
+                let name = table.split_whitespace().next().unwrap_or("");
+                self.tables.remove(&identifier(name));
+            }
+        }
+    }
+}
+
+/// Applies one `ALTER TABLE` action.
+fn apply_action(table: &mut TableDefinition, action: &str) {
+    if let Some(caps) = ADD_CONSTRAINT.captures(action) {
+        table.apply_constraint(caps.get(1).unwrap().as_str());
+    } else if let Some(caps) = ADD_COLUMN.captures(action) {
+        add_column(table, &format!("{} {}", &caps[1], &caps[2]));
+    } else if let Some(caps) = ALTER_COLUMN_TYPE.captures(action) {
+        if let Some(column) = table.column_mut(&identifier(&caps[1])) {
+            column.sql_type = column_type(&caps[2]).0;
+        }
+    } else if let Some(caps) = ALTER_COLUMN_SET.captures(action) {
+        if let Some(column) = table.column_mut(&identifier(&caps[1])) {
+            let set = caps[2].eq_ignore_ascii_case("set");
+            if caps[3].to_lowercase().starts_with("not") {
+                column.not_null = set;
+            } else {
+                column.has_default = set;
+            }
+        }
+    } else if let Some(caps) = MODIFY_COLUMN.captures(action) {
+        add_column(table, &format!("{} {}", &caps[1], &caps[2]));
+    } else if let Some(caps) = DROP_CONSTRAINT.captures(action) {
+        let name = identifier(&caps[1]);
+        for column in &mut table.columns {
+            column.checks.retain(|(constraint, _)| *constraint != name);
+        }
+    } else if let Some(caps) = RENAME_COLUMN.captures(action) {
+        let to = identifier(&caps[2]);
+        if let Some(column) = table.column_mut(&identifier(&caps[1])) {
+            column.name = to;
+        }
+    } else if let Some(caps) = DROP_COLUMN.captures(action) {
+        let name = identifier(&caps[1]);
+        table.columns.retain(|c| c.name != name);
+    }
+}
+
+/// Parses a column definition (`name type constraints...`) into `table`.
+fn add_column(table: &mut TableDefinition, definition: &str) {
+    let mut parts = definition.splitn(2, char::is_whitespace);
+    let name = identifier(parts.next().unwrap_or(""));
+    let rest = parts.next().unwrap_or("").trim();
+    if name.is_empty() {
+        return;
+    }
+
+    let (sql_type, constraints) = column_type(rest);
+    let masked = mask_strings(constraints);
+    let serial = base_type(&sql_type).ends_with("serial");
+    table.set_column(ColumnDefinition {
+        name: name.clone(),
+        not_null: serial || NOT_NULL.is_match(&masked),
+        has_default: serial || DEFAULT.is_match(&masked),
+        sql_type,
+        checks: Vec::new(),
+    });
+
+    if let Some(caps) = CHECK.captures(&masked) {
+        let start = caps.get(0).unwrap().end() - 1;
+        if let Some(expression) = parenthesized(&constraints[start..]) {
+            let constraint = caps.get(1).map(|m| identifier(m.as_str()));
+            table.add_checks(constraint.as_deref(), expression);
+        }
+    }
+}
+
+/// Splits the rest of a column definition into its normalized type and the
+/// trailing constraints.
+fn column_type(rest: &str) -> (String, &str) {
+    let masked = mask_strings(rest);
+    let mut depth = 0usize;
+    let mut end = rest.len();
+    let mut word_start = None;
+    for (i, c) in masked.char_indices().chain([(masked.len(), ' ')]) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        let is_word = c.is_alphanumeric() || c == '_';
+        match (word_start, is_word) {
+            (None, true) if depth == 0 => word_start = Some(i),
+            (Some(start), false) => {
+                word_start = None;
+                let word = masked[start..i].to_lowercase();
+                if start > 0 && COLUMN_CONSTRAINTS.contains(&word.as_str()) {
+                    end = start;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let sql_type = rest[..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    (sql_type, &rest[end..])
+}
+
+/// Returns a type's name without length arguments or array brackets.
+fn base_type(sql_type: &str) -> &str {
+    sql_type.split(['(', '[']).next().unwrap_or(sql_type).trim()
+}
+
+/// Builds the schema for a column type and its checks.
+fn column_schema(sql_type: &str, checks: &[(String, ColumnCheck)]) -> ColumnSchema {
+    if let Some(element) = sql_type
+        .strip_suffix("[]")
+        .or_else(|| sql_type.strip_suffix(" array"))
+    {
+        // Checks on array columns apply to the array, not its elements
+        return ColumnSchema::Array(Box::new(ArraySchema::new(column_schema(
+            element.trim(),
+            &[],
+        ))));
+    }
+
+    let checks = checks.iter().map(|(_, check)| check);
+    match base_type(sql_type) {
+        "varchar" | "nvarchar" | "character varying" | "char" | "nchar" | "character"
+        | "bpchar" | "text" | "citext" | "tinytext" | "mediumtext" | "longtext" | "uuid"
+        | "date" | "inet" => {
+            let mut schema = match base_type(sql_type) {
+                "uuid" => StringSchema::new().uuid(),
+                "date" => StringSchema::new().date(),
+                "inet" => StringSchema::new().ip(),
+                _ => StringSchema::new(),
+            };
+            let mut min_len = None;
+            let mut max_len = bounded_length(sql_type);
+            for check in checks {
+                match check {
+                    ColumnCheck::MinLength(n) => min_len = min_len.max(Some(*n)),
+                    ColumnCheck::MaxLength(n) => max_len = Some(max_len.map_or(*n, |m| m.min(*n))),
+                    ColumnCheck::OneOf(values) => schema = schema.one_of(values.clone()),
+                    _ => {}
+                }
+            }
+            if let Some(min) = min_len {
+                schema = schema.min_len(min);
+            }
+            if let Some(max) = max_len {
+                schema = schema.max_len(max);
+            }
+            ColumnSchema::String(schema)
+        }
+        t if t.starts_with("timestamp") || t == "datetime" => {
+            ColumnSchema::String(StringSchema::new().datetime())
+        }
+        t @ ("smallint" | "int2" | "smallserial" | "serial2" | "integer" | "int" | "int4"
+        | "serial" | "serial4" | "mediumint" | "bigint" | "int8" | "bigserial" | "serial8"
+        | "tinyint") => {
+            let (mut min, mut max) = match t {
+                "tinyint" => (i8::MIN as i64, i8::MAX as i64),
+                "smallint" | "int2" | "smallserial" | "serial2" => {
+                    (i16::MIN as i64, i16::MAX as i64)
+                }
+                "bigint" | "int8" | "bigserial" | "serial8" => (i64::MIN, i64::MAX),
+                _ => (i32::MIN as i64, i32::MAX as i64),
+            };
+            for check in checks {
+                match check {
+                    ColumnCheck::Min(v) => min = min.max(v.ceil() as i64),
+                    ColumnCheck::Max(v) => max = max.min(v.floor() as i64),
+                    ColumnCheck::ExclusiveMin(v) => {
+                        min = min.max((v.floor() as i64).saturating_add(1))
+                    }
+                    ColumnCheck::ExclusiveMax(v) => {
+                        max = max.min((v.ceil() as i64).saturating_sub(1))
+                    }
+                    _ => {}
+                }
+            }
+            let mut schema = IntegerSchema::new();
+            if min > i64::MIN {
+                schema = schema.min(min);
+            }
+            if max < i64::MAX {
+                schema = schema.max(max);
+            }
+            ColumnSchema::Integer(schema)
+        }
+        "numeric" | "decimal" | "real" | "float4" | "double precision" | "float8" | "float"
+        | "double" => {
+            let schema = checks.fold(NumberSchema::new(), |schema, check| {
+                let bound = match check {
+                    ColumnCheck::Min(v) => NumberBound::Min(*v),
+                    ColumnCheck::Max(v) => NumberBound::Max(*v),
+                    ColumnCheck::ExclusiveMin(v) => NumberBound::ExclusiveMin(*v),
+                    ColumnCheck::ExclusiveMax(v) => NumberBound::ExclusiveMax(*v),
+                    _ => return schema,
+                };
+                schema.bound(bound, None)
+            });
+            ColumnSchema::Number(schema)
+        }
+        "boolean" | "bool" => ColumnSchema::Boolean(BooleanSchema),
+        _ => ColumnSchema::Any(AnySchema),
+    }
+}
+
+/// Extracts single-column rules from a `CHECK` expression.
+///
+/// Only conjunctions of simple comparisons are understood; an expression
+/// containing `OR` or `NOT` yields no rules, since any rule taken from it
+/// could reject valid rows.
+fn parse_check(expression: &str) -> Vec<(String, ColumnCheck)> {
+    // Normalize pg_dump's rendering, e.g. `((price)::numeric > (0)::numeric)`
+    let mut expression = CAST.replace_all(expression, "").into_owned();
+    loop {
+        let unwrapped = WRAPPED_OPERAND
+            .replace_all(&expression, "$1$2")
+            .into_owned();
+        if unwrapped == expression {
+            break;
+        }
+        expression = unwrapped;
+    }
+    let expression = BETWEEN.replace_all(&expression, "$1 >= $2 AND $1 <= $3");
+
+    if OR.is_match(&mask_strings(&expression)) {
+        return Vec::new();
+    }
+
+    AND.split(&expression)
+        .flat_map(|term| parse_comparison(strip_parens(term)))
+        .collect()
+}
+
+/// Parses a single comparison into rules for one column.
+fn parse_comparison(term: &str) -> Vec<(String, ColumnCheck)> {
+    if let Some(caps) = LENGTH_CHECK.captures(term) {
+        let column = identifier(&caps[1]);
+        // A length too large for usize cannot be enforced, so skip it
+        let Ok(n) = caps[3].parse::<usize>() else {
+            return Vec::new();
+        };
+        let checks = match &caps[2] {
+            ">=" => vec![ColumnCheck::MinLength(n)],
+            ">" => vec![ColumnCheck::MinLength(n.saturating_add(1))],
+            "<=" => vec![ColumnCheck::MaxLength(n)],
+            "<" => vec![ColumnCheck::MaxLength(n.saturating_sub(1))],
+            _ => vec![ColumnCheck::MinLength(n), ColumnCheck::MaxLength(n)],
+        };
+        return checks.into_iter().map(|c| (column.clone(), c)).collect();
+    }
+
+    if let Some(caps) = NOT_EMPTY.captures(term) {
+        return vec![(identifier(&caps[1]), ColumnCheck::MinLength(1))];
+    }
+
+    if let Some(caps) = COMPARISON.captures(term) {
+        let column = identifier(&caps[1]);
+        let n: f64 = caps[3].parse().unwrap_or(0.0);
+        let checks = match &caps[2] {
+            ">=" => vec![ColumnCheck::Min(n)],
+            ">" => vec![ColumnCheck::ExclusiveMin(n)],
+            "<=" => vec![ColumnCheck::Max(n)],
+            "<" => vec![ColumnCheck::ExclusiveMax(n)],
+            _ => vec![ColumnCheck::Min(n), ColumnCheck::Max(n)],
+        };
+        return checks.into_iter().map(|c| (column.clone(), c)).collect();
+    }
+
+    if let Some(caps) = IN_LIST.captures(term) {
+        let column = caps.get(1).or_else(|| caps.get(3)).unwrap().as_str();
+        let items = caps.get(2).or_else(|| caps.get(4)).unwrap().as_str();
+        let values: Option<Vec<String>> = split_top_level(items)
+            .into_iter()
+            .map(|item| {
+                item.strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .map(|s| s.replace("''", "'"))
+            })
+            .collect();
+        if let Some(values) = values.filter(|v| !v.is_empty()) {
+            return vec![(identifier(column), ColumnCheck::OneOf(values))];
+        }
+    }
+
+    Vec::new()
+}
+
+/// Returns the contents of the first balanced parenthesized group.
+fn parenthesized(text: &str) -> Option<&str> {
+    let masked = mask_strings(text);
+    let start = masked.find('(')?;
+    let mut depth = 0usize;
+    for (i, c) in masked[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(text[start + 1..start + i].trim());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Removes parentheses wrapping an entire term, and unbalanced ones left
+/// over from splitting `(a > 0 AND b > 0)` on `AND`.
+fn strip_parens(term: &str) -> &str {
+    let mut term = term.trim();
+    let masked = mask_strings(term);
+    let opens = masked.matches('(').count();
+    let closes = masked.matches(')').count();
+    let mut unbalanced = opens.abs_diff(closes);
+    let mut unbalanced_paren = |c: char, paren: char| {
+        if c == paren && unbalanced > 0 {
+            unbalanced -= 1;
+            true
+        } else {
+            c.is_whitespace()
+        }
+    };
+    if opens > closes {
+        term = term.trim_start_matches(|c| unbalanced_paren(c, '('));
+    } else if closes > opens {
+        term = term.trim_end_matches(|c| unbalanced_paren(c, ')'));
+    }
+    while term.starts_with('(') && parenthesized(term).map(str::len) == Some(term.len() - 2) {
+        term = term[1..term.len() - 1].trim();
+    }
+    term
+}
+
+/// Replaces the contents of string literals with `_` so keywords and
+/// parentheses inside them are ignored, keeping byte offsets intact.
+fn mask_strings(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut in_string = false;
+    for c in text.chars() {
+        if c == '\'' {
+            in_string = !in_string;
+            masked.push(c);
+        } else if in_string {
+            masked.extend(std::iter::repeat_n('_', c.len_utf8()));
+        } else {
+            masked.push(c);
+        }
+    }
+    masked
+}
+
+/// The schema generated for a column, dispatching to the concrete schema
+/// type.
+enum ColumnSchema {
+    String(StringSchema),
+    Integer(IntegerSchema),
+    Number(NumberSchema),
+    Boolean(BooleanSchema),
+    Array(Box<ArraySchema<ColumnSchema>>),
+    Any(AnySchema),
+}
+
+impl SchemaLike for ColumnSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate_to_value(value, path)
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        match self {
+            ColumnSchema::String(s) => s.validate_to_value(value, path),
+            ColumnSchema::Integer(s) => s.validate_to_value(value, path),
+            ColumnSchema::Number(s) => s.validate_to_value(value, path),
+            ColumnSchema::Boolean(s) => s.validate_to_value(value, path),
+            ColumnSchema::Array(s) => s.validate_to_value(value, path),
+            ColumnSchema::Any(s) => s.validate_to_value(value, path),
+        }
+    }
+
+    fn validate_to_value_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        match self {
+            ColumnSchema::Array(s) => s.validate_to_value_with_context(value, path, context),
+            _ => self.validate_to_value(value, path),
+        }
+    }
+}
+
+impl ToJsonSchema for ColumnSchema {
+    fn to_json_schema(&self) -> Value {
+        match self {
+            ColumnSchema::String(s) => s.to_json_schema(),
+            ColumnSchema::Integer(s) => s.to_json_schema(),
+            ColumnSchema::Number(s) => s.to_json_schema(),
+            ColumnSchema::Boolean(s) => s.to_json_schema(),
+            ColumnSchema::Array(s) => s.to_json_schema(),
+            ColumnSchema::Any(s) => s.to_json_schema(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors(schema: &ObjectSchema, value: Value) -> Vec<(String, String)> {
+        match schema.validate(&value, &JsonPath::root()) {
+            Validation::Success(_) => Vec::new(),
+            Validation::Failure(errors) => errors
+                .iter()
+                .map(|e| (e.path.to_string(), e.code.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_column_definitions() {
+        let tables = TableDefinitions::from_sql(
+            r#"
+            CREATE TABLE public."users" (
+                id BIGSERIAL PRIMARY KEY,
+                email CHARACTER VARYING(255) NOT NULL UNIQUE,
+                name varchar(50) NOT NULL DEFAULT 'not null, really',
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                tags TEXT[],
+                bio TEXT
+            );
+            "#,
+        );
+
+        let users = tables.table("users").unwrap();
+        let names: Vec<&str> = users.columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["id", "email", "name", "created_at", "tags", "bio"]);
+
+        let email = users.column("email").unwrap();
+        assert_eq!(email.sql_type(), "character varying(255)");
+        assert!(email.is_required());
+
+        let name = users.column("name").unwrap();
+        assert!(!name.is_nullable());
+        assert!(!name.is_required());
+
+        assert_eq!(
+            users.column("created_at").unwrap().sql_type(),
+            "timestamp with time zone"
+        );
+        assert!(!users.column("id").unwrap().is_required());
+        assert!(users.column("bio").unwrap().is_nullable());
+    }
+
+    #[test]
+    fn test_schema_from_types() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE items (
+                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                code CHAR(3) NOT NULL,
+                quantity SMALLINT NOT NULL,
+                weight REAL,
+                active BOOLEAN NOT NULL DEFAULT true,
+                tags TEXT[] NOT NULL,
+                metadata JSONB
+            );",
+        );
+        let schema = tables.schema("items").unwrap();
+
+        assert!(errors(
+            &schema,
+            json!({"code": "abc", "quantity": 1, "tags": ["a"], "weight": null, "metadata": [1]})
+        )
+        .is_empty());
+
+        let found = errors(
+            &schema,
+            json!({
+                "id": "nope",
+                "code": "abcd",
+                "quantity": 40000,
+                "weight": "heavy",
+                "active": null,
+                "tags": [1]
+            }),
+        );
+        assert_eq!(
+            found,
+            [
+                ("id".to_string(), "invalid_uuid".to_string()),
+                ("code".to_string(), "max_length".to_string()),
+                ("quantity".to_string(), "max_value".to_string()),
+                ("weight".to_string(), "invalid_type".to_string()),
+                ("active".to_string(), "invalid_type".to_string()),
+                ("tags[0]".to_string(), "invalid_type".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            errors(&schema, json!({})),
+            [
+                ("code".to_string(), "required".to_string()),
+                ("quantity".to_string(), "required".to_string()),
+                ("tags".to_string(), "required".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE orders (
+                quantity INTEGER NOT NULL CHECK (quantity > 0 AND quantity <= 100),
+                discount NUMERIC(3, 2) CHECK (discount BETWEEN 0 AND 0.5),
+                note VARCHAR(200) CHECK (char_length(note) >= 3),
+                status TEXT NOT NULL CHECK (status IN ('new', 'it''s paid')),
+                reference TEXT NOT NULL,
+                priority INTEGER CHECK (priority < 0 OR priority > 10),
+                CONSTRAINT reference_not_empty CHECK (reference <> '')
+            );",
+        );
+        let orders = tables.table("orders").unwrap();
+        let checks: Vec<_> = orders.column("quantity").unwrap().checks().collect();
+        assert_eq!(
+            checks,
+            [&ColumnCheck::ExclusiveMin(0.0), &ColumnCheck::Max(100.0)]
+        );
+        assert_eq!(orders.column("priority").unwrap().checks().count(), 0);
+
+        let schema = orders.to_schema();
+        assert!(errors(
+            &schema,
+            json!({"quantity": 1, "discount": 0.5, "status": "it's paid", "reference": "r"})
+        )
+        .is_empty());
+
+        let found = errors(
+            &schema,
+            json!({
+                "quantity": 0,
+                "discount": 0.75,
+                "note": "no",
+                "status": "lost",
+                "reference": ""
+            }),
+        );
+        let codes: Vec<&str> = found.iter().map(|(_, code)| code.as_str()).collect();
+        assert_eq!(
+            codes,
+            [
+                "min_value",
+                "max_value",
+                "min_length",
+                "invalid_enum",
+                "min_length"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pg_dump_checks() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE public.products (
+                price numeric(10,2) NOT NULL,
+                name character varying(50) NOT NULL,
+                kind character varying(10) NOT NULL,
+                CONSTRAINT products_price_check CHECK ((price > (0)::numeric)),
+                CONSTRAINT products_name_check CHECK ((char_length((name)::text) > 0)),
+                CONSTRAINT products_kind_check CHECK (((kind)::text = ANY ((ARRAY['book'::character varying, 'toy'::character varying])::text[])))
+            );",
+        );
+        let products = tables.table("products").unwrap();
+
+        let check = |name: &str| {
+            products
+                .column(name)
+                .unwrap()
+                .checks()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(check("price"), [ColumnCheck::ExclusiveMin(0.0)]);
+        assert_eq!(check("name"), [ColumnCheck::MinLength(1)]);
+        assert_eq!(
+            check("kind"),
+            [ColumnCheck::OneOf(vec![
+                "book".to_string(),
+                "toy".to_string()
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_checks() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE t (
+                é INTEGER CHECK (é > f(1 AND x)),
+                ü INTEGER CHECK ((ü >= 1 AND ü < 5)),
+                ß INTEGER CHECK (ß > 0 AND ß <= 10)
+            );",
+        );
+        let t = tables.table("t").unwrap();
+        assert_eq!(t.column("é").unwrap().checks().count(), 0);
+        let checks: Vec<_> = t.column("ü").unwrap().checks().cloned().collect();
+        assert_eq!(
+            checks,
+            [ColumnCheck::Min(1.0), ColumnCheck::ExclusiveMax(5.0)]
+        );
+        let checks: Vec<_> = t.column("ß").unwrap().checks().cloned().collect();
+        assert_eq!(
+            checks,
+            [ColumnCheck::ExclusiveMin(0.0), ColumnCheck::Max(10.0)]
+        );
+    }
+
+    #[test]
+    fn test_exclusive_bounds_at_integer_limits() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE t (
+                high BIGINT CHECK (high > 9223372036854775807),
+                low BIGINT CHECK (low < -9223372036854775808)
+            );",
+        );
+        let schema = tables.schema("t").unwrap();
+        // Bounds past the range of the column are clamped to it
+        assert!(errors(&schema, json!({"high": i64::MAX, "low": i64::MIN})).is_empty());
+        assert_eq!(
+            errors(&schema, json!({"high": i64::MAX - 1, "low": i64::MIN + 1})),
+            [
+                ("high".to_string(), "min_value".to_string()),
+                ("low".to_string(), "max_value".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_length_checks_at_usize_limits() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE t (
+                huge TEXT CHECK (char_length(huge) <= 99999999999999999999999),
+                most TEXT CHECK (char_length(most) > 18446744073709551615)
+            );",
+        );
+        let table = tables.table("t").unwrap();
+        assert_eq!(table.column("huge").unwrap().checks().count(), 0);
+        assert_eq!(
+            table.column("most").unwrap().checks().collect::<Vec<_>>(),
+            [&ColumnCheck::MinLength(usize::MAX)]
+        );
+        let schema = tables.schema("t").unwrap();
+        assert!(errors(&schema, json!({"huge": "text"})).is_empty());
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE posts (title VARCHAR(100), body TEXT, slug TEXT, views INT CHECK (views >= 0));
+             ALTER TABLE posts
+                 ALTER COLUMN title SET NOT NULL,
+                 ALTER COLUMN title TYPE VARCHAR(200) USING title::varchar(200),
+                 ADD COLUMN lang CHAR(2) NOT NULL DEFAULT 'en',
+                 ADD CONSTRAINT body_length CHECK (char_length(body) <= 5000),
+                 DROP COLUMN slug;
+             ALTER TABLE posts DROP CONSTRAINT posts_views_check;
+             ALTER TABLE posts RENAME COLUMN body TO content;
+             ALTER TABLE posts RENAME TO articles;",
+        );
+
+        assert!(tables.table("posts").is_none());
+        let articles = tables.table("articles").unwrap();
+        let title = articles.column("title").unwrap();
+        assert_eq!(title.sql_type(), "varchar(200)");
+        assert!(title.is_required());
+        assert!(articles.column("slug").is_none());
+        assert_eq!(articles.column("views").unwrap().checks().count(), 0);
+        assert!(!articles.column("lang").unwrap().is_required());
+        assert_eq!(
+            articles
+                .column("content")
+                .unwrap()
+                .checks()
+                .collect::<Vec<_>>(),
+            [&ColumnCheck::MaxLength(5000)]
+        );
+
+        let tables = TableDefinitions::from_sql(
+            "CREATE TABLE t (a INT NOT NULL); ALTER TABLE t ALTER COLUMN a DROP NOT NULL; DROP TABLE t;",
+        );
+        assert_eq!(tables.tables().count(), 0);
+    }
+
+    #[test]
+    fn test_register_all() {
+        let tables =
+            TableDefinitions::from_sql("CREATE TABLE b (x INT NOT NULL); CREATE TABLE a (y TEXT);");
+        let names: Vec<&str> = tables.tables().map(|t| t.name()).collect();
+        assert_eq!(names, ["a", "b"]);
+
+        let registry = SchemaRegistry::new();
+        tables.register_all(&registry).unwrap();
+        assert!(registry
+            .validate("b", &json!({"x": "1"}))
+            .unwrap()
+            .is_failure());
+        assert!(tables.register_all(&registry).is_err());
+    }
+
+    #[test]
+    fn test_json_schema_output() {
+        let tables =
+            TableDefinitions::from_sql("CREATE TABLE t (id INT NOT NULL, note VARCHAR(5));");
+        let schema = tables.schema("t").unwrap().to_json_schema();
+        assert_eq!(schema["properties"]["id"]["maximum"], json!(i32::MAX));
+        assert_eq!(schema["required"], json!(["id"]));
+    }
+}
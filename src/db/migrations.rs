@@ -0,0 +1,384 @@
+//! Column length limits extracted from SQL migrations.
+//!
+//! The parser understands the DDL that sqlx-style migration directories
+//! typically contain: `CREATE TABLE`, `ALTER TABLE ... ADD COLUMN`,
+//! `ALTER COLUMN ... TYPE`, `MODIFY COLUMN`, `DROP COLUMN`, and `DROP TABLE`.
+//! Only bounded character types (`VARCHAR(n)`, `CHAR(n)`, `CHARACTER
+//! VARYING(n)`, `NVARCHAR(n)`, `NCHAR(n)`) produce limits; everything else is
+//! ignored.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+pub(super) static CREATE_TABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^create\s+(?:temporary\s+|temp\s+)?table\s+(?:if\s+not\s+exists\s+)?(\S+?)\s*\((.*)\)")
+        .unwrap()
+});
+
+pub(super) static ALTER_TABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^alter\s+table\s+(?:if\s+exists\s+)?(?:only\s+)?(\S+)\s+(.*)$").unwrap()
+});
+
+pub(super) static DROP_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^drop\s+table\s+(?:if\s+exists\s+)?(.*)$").unwrap());
+
+pub(super) static ADD_COLUMN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^add\s+(?:column\s+)?(?:if\s+not\s+exists\s+)?(\S+)\s+(.*)$").unwrap()
+});
+
+pub(super) static ALTER_COLUMN_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^alter\s+(?:column\s+)?(\S+)\s+(?:set\s+data\s+)?type\s+(.*)$").unwrap()
+});
+
+pub(super) static MODIFY_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^modify\s+(?:column\s+)?(\S+)\s+(.*)$").unwrap());
+
+pub(super) static DROP_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^drop\s+(?:column\s+)?(?:if\s+exists\s+)?(\S+)").unwrap());
+
+static BOUNDED_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:varchar|nvarchar|character\s+varying|char|nchar|character)\s*\(\s*(\d+)\s*\)",
+    )
+    .unwrap()
+});
+
+/// Keywords that start a table constraint rather than a column definition.
+pub(super) const TABLE_CONSTRAINTS: &[&str] = &[
+    "constraint",
+    "primary",
+    "unique",
+    "foreign",
+    "check",
+    "index",
+    "key",
+    "exclude",
+];
+
+/// Maximum lengths of bounded character columns, per table.
+///
+/// Build it from migration SQL with [`from_sql`](Self::from_sql) or
+/// [`from_migrations_dir`](Self::from_migrations_dir), or declare limits
+/// directly with [`varchar`](Self::varchar) when migrations are not written in
+/// SQL (e.g. SeaORM migrations).
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::db::ColumnLimits;
+///
+/// let limits = ColumnLimits::from_sql(
+///     "CREATE TABLE users (id BIGSERIAL PRIMARY KEY, name VARCHAR(50) NOT NULL);
+///      ALTER TABLE users ADD COLUMN country CHAR(2);",
+/// );
+///
+/// assert_eq!(limits.get("users", "name"), Some(50));
+/// assert_eq!(limits.get("users", "country"), Some(2));
+/// assert_eq!(limits.get("users", "id"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnLimits {
+    tables: HashMap<String, HashMap<String, usize>>,
+}
+
+impl ColumnLimits {
+    /// Creates an empty set of limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a maximum length for `table.column`.
+    pub fn varchar(
+        mut self,
+        table: impl Into<String>,
+        column: impl Into<String>,
+        max: usize,
+    ) -> Self {
+        self.set(&table.into(), &column.into(), Some(max));
+        self
+    }
+
+    /// Parses limits from migration SQL, applying statements in order.
+    pub fn from_sql(sql: &str) -> Self {
+        let mut limits = Self::new();
+        limits.apply_sql(sql);
+        limits
+    }
+
+    /// Parses limits from every `.sql` file in a migrations directory.
+    ///
+    /// Files are applied in file name order, which is the version order used
+    /// by sqlx. Reversible migrations' `.down.sql` files are skipped.
+    pub fn from_migrations_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut limits = Self::new();
+        for file in migration_files(dir.as_ref())? {
+            limits.apply_sql(&fs::read_to_string(file)?);
+        }
+        Ok(limits)
+    }
+
+    /// Returns the maximum length of `table.column`, if it is bounded.
+    pub fn get(&self, table: &str, column: &str) -> Option<usize> {
+        self.tables.get(table)?.get(column).copied()
+    }
+
+    /// Returns the bounded columns of `table` and their maximum lengths.
+    pub fn columns(&self, table: &str) -> impl Iterator<Item = (&str, usize)> {
+        self.tables
+            .get(table)
+            .into_iter()
+            .flat_map(|columns| columns.iter().map(|(name, max)| (name.as_str(), *max)))
+    }
+
+    /// Applies the statements in `sql` on top of the current limits.
+    pub fn apply_sql(&mut self, sql: &str) {
+        for statement in strip_comments(sql).split(';') {
+            self.apply_statement(statement.trim());
+        }
+    }
+
+    fn apply_statement(&mut self, statement: &str) {
+        if let Some(caps) = CREATE_TABLE.captures(statement) {
+            let table = identifier(&caps[1]);
+            self.tables.remove(&table);
+            for definition in split_top_level(&caps[2]) {
+                let mut parts = definition.splitn(2, char::is_whitespace);
+                let name = parts.next().unwrap_or("");
+                let rest = parts.next().unwrap_or("").trim();
+                if name.is_empty() || TABLE_CONSTRAINTS.contains(&name.to_lowercase().as_str()) {
+                    continue;
+                }
+                self.set(&table, &identifier(name), bounded_length(rest));
+            }
+        } else if let Some(caps) = ALTER_TABLE.captures(statement) {
+            let table = identifier(&caps[1]);
+            for action in split_top_level(&caps[2]) {
+                if let Some(caps) = ADD_COLUMN.captures(action) {
+                    if !TABLE_CONSTRAINTS.contains(&caps[1].to_lowercase().as_str()) {
+                        self.set(&table, &identifier(&caps[1]), bounded_length(&caps[2]));
+                    }
+                } else if let Some(caps) = ALTER_COLUMN_TYPE
+                    .captures(action)
+                    .or_else(|| MODIFY_COLUMN.captures(action))
+                {
+                    self.set(&table, &identifier(&caps[1]), bounded_length(&caps[2]));
+                } else if let Some(caps) = DROP_COLUMN.captures(action) {
+                    self.set(&table, &identifier(&caps[1]), None);
+                }
+            }
+        } else if let Some(caps) = DROP_TABLE.captures(statement) {
+            for table in caps[1].split(',') {
+                let name = table.split_whitespace().next().unwrap_or("");
+                self.tables.remove(&identifier(name));
+            }
+        }
+    }
+
+    fn set(&mut self, table: &str, column: &str, max: Option<usize>) {
+        match max {
+            Some(max) => {
+                self.tables
+                    .entry(table.to_string())
+                    .or_default()
+                    .insert(column.to_string(), max);
+            }
+            None => {
+                if let Some(columns) = self.tables.get_mut(table) {
+                    columns.remove(column);
+                }
+            }
+        }
+    }
+}
+
+/// Lists the `.sql` files of a migrations directory in version order,
+/// skipping reversible migrations' `.down.sql` files.
+pub(super) fn migration_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    files.retain(|path| {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        name.ends_with(".sql") && !name.ends_with(".down.sql")
+    });
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the length bound of a column type, if it is a bounded string type.
+pub(super) fn bounded_length(column_type: &str) -> Option<usize> {
+    BOUNDED_TYPE
+        .captures(column_type.trim())
+        .and_then(|caps| caps[1].parse().ok())
+}
+
+/// Removes quoting and schema qualification from an identifier.
+pub(super) fn identifier(raw: &str) -> String {
+    let unqualified = raw.rsplit('.').next().unwrap_or(raw);
+    unqualified
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_string()
+}
+
+/// Removes `--` line comments and `/* */` block comments.
+pub(super) fn strip_comments(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_string = !in_string;
+                out.push(c);
+            }
+            '-' if !in_string && chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if !in_string && chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits on commas that are not nested inside parentheses or string
+/// literals.
+pub(super) fn split_top_level(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '\'' => in_string = !in_string,
+            _ if in_string => {}
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(body[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_table() {
+        let limits = ColumnLimits::from_sql(
+            r#"
+            -- users table
+            CREATE TABLE IF NOT EXISTS public."users" (
+                id BIGSERIAL PRIMARY KEY,
+                email CHARACTER VARYING(255) NOT NULL UNIQUE,
+                name varchar (50) NOT NULL DEFAULT 'a, b',
+                price NUMERIC(10, 2),
+                country CHAR(2),
+                bio TEXT,
+                CONSTRAINT users_name_check CHECK (char_length(name) > 0)
+            );
+            "#,
+        );
+
+        assert_eq!(limits.get("users", "email"), Some(255));
+        assert_eq!(limits.get("users", "name"), Some(50));
+        assert_eq!(limits.get("users", "country"), Some(2));
+        assert_eq!(limits.get("users", "price"), None);
+        assert_eq!(limits.get("users", "bio"), None);
+        assert_eq!(limits.columns("users").count(), 3);
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let limits = ColumnLimits::from_sql(
+            "CREATE TABLE `posts` (`title` VARCHAR(100), `slug` VARCHAR(100), `body` TEXT);
+             /* widen titles */
+             ALTER TABLE posts ALTER COLUMN title TYPE VARCHAR(200), ADD COLUMN lang CHAR(5);
+             ALTER TABLE posts MODIFY COLUMN body VARCHAR(5000);
+             ALTER TABLE posts DROP COLUMN slug;",
+        );
+
+        assert_eq!(limits.get("posts", "title"), Some(200));
+        assert_eq!(limits.get("posts", "lang"), Some(5));
+        assert_eq!(limits.get("posts", "body"), Some(5000));
+        assert_eq!(limits.get("posts", "slug"), None);
+    }
+
+    #[test]
+    fn test_type_change_removes_limit() {
+        let limits = ColumnLimits::from_sql(
+            "CREATE TABLE t (note VARCHAR(10));
+             ALTER TABLE t ALTER COLUMN note SET DATA TYPE TEXT;",
+        );
+        assert_eq!(limits.get("t", "note"), None);
+    }
+
+    #[test]
+    fn test_drop_table() {
+        let limits = ColumnLimits::from_sql(
+            "CREATE TABLE a (x VARCHAR(1)); CREATE TABLE b (y VARCHAR(2)); DROP TABLE IF EXISTS a;",
+        );
+        assert_eq!(limits.get("a", "x"), None);
+        assert_eq!(limits.get("b", "y"), Some(2));
+    }
+
+    #[test]
+    fn test_manual_limits() {
+        let limits = ColumnLimits::new().varchar("users", "name", 50);
+        assert_eq!(limits.get("users", "name"), Some(50));
+    }
+
+    #[test]
+    fn test_from_migrations_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("postmortem-migrations-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("20240101000000_users.up.sql"),
+            "CREATE TABLE users (name VARCHAR(20));",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("20240101000000_users.down.sql"),
+            "DROP TABLE users;",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("20240202000000_widen.sql"),
+            "ALTER TABLE users ALTER COLUMN name TYPE VARCHAR(40);",
+        )
+        .unwrap();
+
+        let limits = ColumnLimits::from_migrations_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(limits.get("users", "name"), Some(40));
+    }
+}
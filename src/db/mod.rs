@@ -0,0 +1,279 @@
+//! Validation of database write models.
+//!
+//! This module validates insert/update structs after they are built and
+//! before they are bound to a query, so constraint violations surface as
+//! accumulated [`SchemaErrors`] instead of one database error at a time. It
+//! works with any query layer (sqlx, SeaORM, diesel) because it only relies
+//! on the model being `Serialize`.
+//!
+//! - [`WriteModel`] declares a model's table and schema.
+//! - [`ColumnLimits`] imports `VARCHAR(n)`-style limits from SQL migrations.
+//! - [`TableDefinitions`] generates starting-point schemas from `CREATE
+//!   TABLE` DDL, including nullability and `CHECK` constraints.
+//! - [`WriteValidator`] checks models against both, caching each model's
+//!   schema after first use.
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `db` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use postmortem::db::{ColumnLimits, WriteModel, WriteValidator};
+//! use postmortem::{ObjectSchema, Schema};
+//!
+//! #[derive(serde::Serialize)]
+//! struct NewUser {
+//!     email: String,
+//!     name: String,
+//! }
+//!
+//! impl WriteModel for NewUser {
+//!     const TABLE: &'static str = "users";
+//!
+//!     fn schema() -> ObjectSchema {
+//!         Schema::object()
+//!             .field("email", Schema::string().email())
+//!             .field("name", Schema::string().min_len(1))
+//!     }
+//! }
+//!
+//! let validator = WriteValidator::new(ColumnLimits::from_migrations_dir("./migrations")?);
+//!
+//! let user = NewUser { email: "a@example.com".into(), name: "Alice".into() };
+//! validator.check(&user).into_result()?;
+//! sqlx::query("INSERT INTO users (email, name) VALUES ($1, $2)")
+//!     .bind(&user.email)
+//!     .bind(&user.name)
+//!     .execute(&pool)
+//!     .await?;
+//! ```
+
+mod ddl;
+mod migrations;
+
+pub use ddl::{ColumnCheck, ColumnDefinition, TableDefinition, TableDefinitions};
+pub use migrations::ColumnLimits;
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::Value;
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::path::JsonPath;
+use crate::schema::{ObjectSchema, SchemaLike, StringSchema};
+
+/// An insert or update struct that declares how it is validated.
+pub trait WriteModel: Serialize + 'static {
+    /// The table this model writes to, used to look up column limits.
+    const TABLE: &'static str;
+
+    /// Returns the schema the serialized model must satisfy.
+    fn schema() -> ObjectSchema;
+
+    /// Validates this model against its schema only, without column limits.
+    fn validate_write(&self) -> Validation<(), SchemaErrors> {
+        let value = match serialize(self) {
+            Validation::Success(value) => value,
+            Validation::Failure(errors) => return Validation::Failure(errors),
+        };
+        Self::schema()
+            .validate_to_value(&value, &JsonPath::root())
+            .map(|_| ())
+    }
+}
+
+/// Validates write models against their schemas and column limits.
+///
+/// Schemas are built once per model type and shared across threads, so a
+/// single validator can be stored alongside the connection pool.
+#[derive(Clone, Default)]
+pub struct WriteValidator {
+    limits: Arc<ColumnLimits>,
+    schemas: Arc<RwLock<HashMap<TypeId, Arc<ObjectSchema>>>>,
+}
+
+impl WriteValidator {
+    /// Creates a validator enforcing the given column limits.
+    pub fn new(limits: ColumnLimits) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            schemas: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the column limits this validator enforces.
+    pub fn limits(&self) -> &ColumnLimits {
+        &self.limits
+    }
+
+    /// Validates a model before it is written.
+    ///
+    /// The model is serialized, validated against [`WriteModel::schema`], and
+    /// every string field with a column limit on [`WriteModel::TABLE`] is
+    /// checked against that limit (code `max_length`). Errors from both
+    /// checks are accumulated.
+    pub fn check<M: WriteModel>(&self, model: &M) -> Validation<(), SchemaErrors> {
+        let value = match serialize(model) {
+            Validation::Success(value) => value,
+            Validation::Failure(errors) => return Validation::Failure(errors),
+        };
+
+        let schema = self.schema_for::<M>();
+        let mut errors = match schema.validate_to_value(&value, &JsonPath::root()) {
+            Validation::Success(_) => Vec::new(),
+            Validation::Failure(errors) => errors.into_vec(),
+        };
+
+        for (column, max) in self.limits.columns(M::TABLE) {
+            let Some(field) = value.get(column).filter(|v| v.is_string()) else {
+                continue;
+            };
+            let path = JsonPath::root().push_field(column);
+            let limit = StringSchema::new().max_len(max).error(format!(
+                "exceeds column limit of {} characters for {}.{}",
+                max,
+                M::TABLE,
+                column
+            ));
+            if let Validation::Failure(errs) = limit.validate(field, &path) {
+                // Skip duplicates of a max_len the schema already reported
+                for err in errs {
+                    if !errors
+                        .iter()
+                        .any(|e| e.path == err.path && e.code == err.code)
+                    {
+                        errors.push(err);
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Validation::Success(())
+        } else {
+            Validation::Failure(SchemaErrors::from_vec(errors))
+        }
+    }
+
+    fn schema_for<M: WriteModel>(&self) -> Arc<ObjectSchema> {
+        let id = TypeId::of::<M>();
+        if let Some(schema) = self.schemas.read().get(&id) {
+            return Arc::clone(schema);
+        }
+        Arc::clone(
+            self.schemas
+                .write()
+                .entry(id)
+                .or_insert_with(|| Arc::new(M::schema())),
+        )
+    }
+}
+
+/// Serializes a model, reporting failures as a root-level error.
+fn serialize<M: Serialize + ?Sized>(model: &M) -> Validation<Value, SchemaErrors> {
+    match serde_json::to_value(model) {
+        Ok(value) => Validation::Success(value),
+        Err(e) => Validation::Failure(SchemaErrors::single(
+            SchemaError::new(
+                JsonPath::root(),
+                format!("could not serialize model: {}", e),
+            )
+            .with_code("serialize"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+
+    #[derive(Serialize)]
+    struct NewUser {
+        email: String,
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bio: Option<String>,
+    }
+
+    impl WriteModel for NewUser {
+        const TABLE: &'static str = "users";
+
+        fn schema() -> ObjectSchema {
+            Schema::object()
+                .field("email", Schema::string().email())
+                .field("name", Schema::string().min_len(1).max_len(10))
+                .optional("bio", Schema::string())
+        }
+    }
+
+    fn validator() -> WriteValidator {
+        WriteValidator::new(ColumnLimits::from_sql(
+            "CREATE TABLE users (email VARCHAR(20), name VARCHAR(5), bio VARCHAR(8));",
+        ))
+    }
+
+    fn user(email: &str, name: &str, bio: Option<&str>) -> NewUser {
+        NewUser {
+            email: email.to_string(),
+            name: name.to_string(),
+            bio: bio.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_valid_model() {
+        let result = validator().check(&user("a@example.com", "Alice", None));
+        assert!(result.is_success());
+    }
+
+    #[test]
+    fn test_column_limits_accumulate_with_schema_errors() {
+        let result = validator().check(&user(
+            "not-an-email-address-at-all",
+            "Bob",
+            Some("too long bio"),
+        ));
+
+        let errors = result.into_result().unwrap_err();
+        let email = errors.at_path(&JsonPath::root().push_field("email"));
+        let codes: Vec<&str> = email.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, vec!["invalid_email", "max_length"]);
+
+        let bio = errors.at_path(&JsonPath::root().push_field("bio"));
+        assert_eq!(bio.len(), 1);
+        assert!(bio[0].message.contains("users.bio"));
+    }
+
+    #[test]
+    fn test_schema_limit_not_duplicated() {
+        // name violates both the schema's max_len(10) and the VARCHAR(5)
+        let result = validator().check(&user("a@example.com", "Bartholomew", None));
+
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "max_length");
+    }
+
+    #[test]
+    fn test_validate_write_ignores_limits() {
+        let model = user("a@example.com", "Bartholo", None);
+        assert!(model.validate_write().is_success());
+        assert!(validator().check(&model).is_failure());
+    }
+
+    #[test]
+    fn test_schema_is_cached() {
+        let validator = validator();
+        validator.check(&user("a@example.com", "Alice", None));
+        validator.check(&user("b@example.com", "Bob", None));
+        assert_eq!(validator.schemas.read().len(), 1);
+    }
+}
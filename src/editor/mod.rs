@@ -0,0 +1,1032 @@
+//! Live feedback for documents being edited against registered schemas.
+//!
+//! Editors can check JSON documents with the same schemas production uses.
+//! [`DocumentChecker`] turns a document's text into [`Diagnostic`]s placed
+//! on the offending values, and offers [`Completion`]s at a cursor: the
+//! allowed values of an enum, and the properties an object is still missing,
+//! required ones first. `postmortem serve --lsp` speaks the Language Server
+//! Protocol on top of it.
+//!
+//! A document picks its schema with a top-level `"$schema"` member naming
+//! it, or falls back to the checker's default schema.
+//!
+//! JSON documents are checked with [`DocumentChecker::diagnostics`] and
+//! [`DocumentChecker::completions`]. With the `yaml` feature, YAML
+//! documents are checked with `DocumentChecker::yaml_diagnostics` and
+//! `DocumentChecker::yaml_completions`; they name their schema with a
+//! top-level `$schema` key.
+//!
+//! Positions are zero-based lines and UTF-16 columns, as in the Language
+//! Server Protocol.
+//!
+//! # Example
+//!
+//! ```rust
+//! use postmortem::editor::{DocumentChecker, Position};
+//! use postmortem::{Schema, SchemaRegistry};
+//!
+//! let registry = SchemaRegistry::new();
+//! registry
+//!     .register(
+//!         "Order",
+//!         Schema::object()
+//!             .field("id", Schema::integer())
+//!             .field("status", Schema::string().one_of(["open", "closed"])),
+//!     )
+//!     .unwrap();
+//! let checker = DocumentChecker::new(registry).default_schema("Order");
+//!
+//! let text = "{\n  \"id\": 1,\n  \"status\": \"shipped\"\n}";
+//! let diagnostics = checker.diagnostics(text);
+//! assert_eq!(diagnostics.len(), 1);
+//! assert_eq!(diagnostics[0].range.start, Position::new(2, 12));
+//!
+//! let completions = checker.completions(text, Position::new(2, 13));
+//! let labels: Vec<_> = completions.iter().map(|c| c.label.as_str()).collect();
+//! assert_eq!(labels, ["open", "closed"]);
+//! ```
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::error::SchemaErrors;
+use crate::path::{JsonPath, PathSegment};
+use crate::registry::SchemaRegistry;
+use stillwater::Validation;
+
+#[cfg(feature = "yaml")]
+mod yaml;
+
+/// How many `$ref`s [`schema_at`] follows in a row before giving up.
+const MAX_REF_HOPS: usize = 16;
+
+/// A position in a document: a zero-based line, and a zero-based column in
+/// UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Position {
+    /// The line, starting at 0.
+    pub line: u32,
+    /// The column in UTF-16 code units, starting at 0.
+    pub character: u32,
+}
+
+impl Position {
+    /// Creates a position.
+    pub fn new(line: u32, character: u32) -> Self {
+        Self { line, character }
+    }
+
+    /// Returns the position of the byte `offset` in `text`.
+    pub fn at_offset(text: &str, offset: usize) -> Self {
+        let mut position = Position::default();
+        for (i, c) in text.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if c == '\n' {
+                position.line += 1;
+                position.character = 0;
+            } else {
+                position.character += c.len_utf16() as u32;
+            }
+        }
+        position
+    }
+
+    /// Returns the byte offset of this position in `text`, clamped to the
+    /// end of its line and of the text.
+    pub fn offset_in(self, text: &str) -> usize {
+        let mut current = Position::default();
+        for (i, c) in text.char_indices() {
+            if current.line == self.line && (current.character >= self.character || c == '\n') {
+                return i;
+            }
+            if c == '\n' {
+                current.line += 1;
+                current.character = 0;
+            } else {
+                current.character += c.len_utf16() as u32;
+            }
+        }
+        text.len()
+    }
+}
+
+/// A span of a document, from `start` up to but excluding `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Range {
+    /// Where the span starts.
+    pub start: Position,
+    /// Where the span ends.
+    pub end: Position,
+}
+
+impl Range {
+    fn of(text: &str, start: usize, end: usize) -> Self {
+        Self {
+            start: Position::at_offset(text, start),
+            end: Position::at_offset(text, end),
+        }
+    }
+}
+
+/// A problem found in a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The value the problem is about; for a missing property, the start of
+    /// the object missing it.
+    pub range: Range,
+    /// A description of the problem.
+    pub message: String,
+    /// The error code, e.g. `required`, or `invalid_json` for a syntax
+    /// error.
+    pub code: String,
+}
+
+/// What a [`Completion`] inserts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompletionKind {
+    /// The name of a property.
+    Property,
+    /// A value.
+    Value,
+}
+
+/// A suggestion for the text at a cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// What to show, e.g. `status` or `open`.
+    pub label: String,
+    /// Whether this is a property name or a value.
+    pub kind: CompletionKind,
+    /// Extra information, e.g. `required` for a required property.
+    pub detail: Option<String>,
+    /// The JSON text to insert, e.g. `"open"`.
+    pub insert_text: String,
+    /// The text to replace: the partial token at the cursor, if any.
+    pub range: Range,
+}
+
+/// Checks documents against the schemas of a registry.
+///
+/// See the [module documentation](self).
+pub struct DocumentChecker {
+    registry: SchemaRegistry,
+    default_schema: Option<String>,
+}
+
+impl DocumentChecker {
+    /// Creates a checker for documents that name their schema.
+    pub fn new(registry: SchemaRegistry) -> Self {
+        Self {
+            registry,
+            default_schema: None,
+        }
+    }
+
+    /// Sets the schema of documents without a `"$schema"` member.
+    pub fn default_schema(mut self, name: impl Into<String>) -> Self {
+        self.default_schema = Some(name.into());
+        self
+    }
+
+    /// Returns the registry the checker validates with.
+    pub fn registry(&self) -> &SchemaRegistry {
+        &self.registry
+    }
+
+    /// Returns every problem in `text`: a syntax error if it is not valid
+    /// JSON, and otherwise each validation error, placed on the value it is
+    /// about.
+    pub fn diagnostics(&self, text: &str) -> Vec<Diagnostic> {
+        let value: Value = match serde_json::from_str(text) {
+            Ok(value) => value,
+            Err(e) => {
+                let offset = syntax_error_offset(text, e.line(), e.column());
+                return vec![Diagnostic {
+                    range: Range::of(text, offset, offset),
+                    message: e.to_string(),
+                    code: "invalid_json".to_string(),
+                }];
+            }
+        };
+        self.validation_diagnostics(text, value, || locate_values(text))
+    }
+
+    /// Returns the validation errors of a parsed document, placed on the
+    /// values they are about by the byte spans `locate` returns.
+    fn validation_diagnostics(
+        &self,
+        text: &str,
+        mut value: Value,
+        locate: impl FnOnce() -> HashMap<JsonPath, (usize, usize)>,
+    ) -> Vec<Diagnostic> {
+        let Some(schema) = self.schema_for(&value) else {
+            let names = self.registry.names();
+            return vec![Diagnostic {
+                range: Range::default(),
+                message: format!(
+                    "no schema for this document; add \"$schema\" naming one of: {}",
+                    names.join(", ")
+                ),
+                code: "no_schema".to_string(),
+            }];
+        };
+        if let Value::Object(object) = &mut value {
+            object.remove("$schema");
+        }
+
+        let errors = match self.registry.validate(&schema, &value) {
+            Ok(Validation::Success(_)) => return Vec::new(),
+            Ok(Validation::Failure(errors)) => errors,
+            Err(e) => SchemaErrors::single(
+                crate::error::SchemaError::new(JsonPath::root(), e.to_string())
+                    .with_code("unknown_schema"),
+            ),
+        };
+        let spans = locate();
+        errors
+            .iter()
+            .map(|error| {
+                let (start, end) = span_of(&spans, &error.path);
+                Diagnostic {
+                    range: Range::of(text, start, end),
+                    message: error.message.clone(),
+                    code: error.code.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns suggestions for the text at `position`.
+    ///
+    /// Where a property name is expected, suggests the properties of the
+    /// object's schema that are not in the object yet, required ones first.
+    /// Where a value is expected, suggests the values the schema allows:
+    /// enum values, constants, booleans, and `null` if it is nullable. The
+    /// document may be incomplete.
+    pub fn completions(&self, text: &str, position: Position) -> Vec<Completion> {
+        match cursor_context(text, position.offset_in(text)) {
+            Some(cursor) => self.complete(text, cursor, Syntax::Json),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns suggestions for the slot `cursor` describes, written in
+    /// `syntax`.
+    fn complete(&self, text: &str, cursor: CursorContext, syntax: Syntax) -> Vec<Completion> {
+        let range = Range::of(text, cursor.token.0, cursor.token.1);
+
+        let root_schema = cursor
+            .schema_name
+            .filter(|name| self.registry.get(name).is_some())
+            .or_else(|| self.default_schema.clone());
+        if cursor.slot == Slot::Value && cursor.path == JsonPath::from_field("$schema") {
+            return self
+                .registry
+                .names()
+                .into_iter()
+                .map(|name| value_completion(Value::String(name), None, range, syntax))
+                .collect();
+        }
+
+        let export = self.registry.to_json_schema();
+        let schema = root_schema
+            .as_deref()
+            .and_then(|name| schema_at(&export, name, &cursor.path));
+        let defs = &export["$defs"];
+
+        // A YAML sequence item may start with a key or be a value
+        let slot = match (cursor.slot, schema) {
+            (Slot::Value, Some(schema))
+                if cursor.item_start && schema.get("properties").is_some() =>
+            {
+                Slot::Key
+            }
+            (slot, _) => slot,
+        };
+        match slot {
+            Slot::Key => {
+                let mut completions = Vec::new();
+                if cursor.path.is_root() && !cursor.present_keys.iter().any(|k| k == "$schema") {
+                    completions.push(Completion {
+                        label: "$schema".to_string(),
+                        kind: CompletionKind::Property,
+                        detail: Some("the schema to check this document against".to_string()),
+                        insert_text: syntax.key("$schema"),
+                        range,
+                    });
+                }
+                if let Some(schema) = schema {
+                    completions.extend(property_completions(
+                        defs,
+                        schema,
+                        &cursor.present_keys,
+                        range,
+                        syntax,
+                    ));
+                }
+                completions
+            }
+            Slot::Value => {
+                let mut values = Vec::new();
+                if let Some(schema) = schema {
+                    allowed_values(defs, schema, &mut values, 0);
+                }
+                values
+                    .into_iter()
+                    .map(|(value, detail)| value_completion(value, detail, range, syntax))
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the name of the schema `value` is checked against.
+    fn schema_for(&self, value: &Value) -> Option<String> {
+        value
+            .get("$schema")
+            .and_then(Value::as_str)
+            .filter(|name| self.registry.get(name).is_some())
+            .map(str::to_string)
+            .or_else(|| self.default_schema.clone())
+    }
+}
+
+/// Returns the JSON Schema at `path` within the schema `name` of a
+/// registry's export (see [`SchemaRegistry::to_json_schema`]), following
+/// `$ref`s, properties, additional properties, and items.
+pub fn schema_at<'a>(export: &'a Value, name: &str, path: &JsonPath) -> Option<&'a Value> {
+    let defs = &export["$defs"];
+    let mut current = defs.get(name)?;
+    for segment in path.segments() {
+        current = resolve_refs(defs, current);
+        current = match segment {
+            PathSegment::Field(field) => current
+                .get("properties")
+                .and_then(|properties| properties.get(field))
+                .or_else(|| {
+                    current
+                        .get("additionalProperties")
+                        .filter(|schema| schema.is_object())
+                })?,
+            PathSegment::Index(index) => current
+                .get("prefixItems")
+                .and_then(|items| items.get(index))
+                .or_else(|| current.get("items"))?,
+        };
+    }
+    Some(resolve_refs(defs, current))
+}
+
+/// Follows `$ref`s to the schemas they name.
+fn resolve_refs<'a>(defs: &'a Value, mut schema: &'a Value) -> &'a Value {
+    for _ in 0..MAX_REF_HOPS {
+        match schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix("#/$defs/"))
+            .and_then(|name| defs.get(name))
+        {
+            Some(target) => schema = target,
+            None => break,
+        }
+    }
+    schema
+}
+
+fn property_completions(
+    defs: &Value,
+    schema: &Value,
+    present: &[String],
+    range: Range,
+    syntax: Syntax,
+) -> Vec<Completion> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut completions: Vec<(bool, Completion)> = properties
+        .iter()
+        .filter(|(name, _)| !present.contains(name))
+        .map(|(name, property)| {
+            let is_required = required.contains(&name.as_str());
+            let type_name = resolve_refs(defs, property)
+                .get("type")
+                .and_then(Value::as_str);
+            let detail = match (is_required, type_name) {
+                (true, Some(type_name)) => format!("required {}", type_name),
+                (true, None) => "required".to_string(),
+                (false, Some(type_name)) => format!("optional {}", type_name),
+                (false, None) => "optional".to_string(),
+            };
+            let completion = Completion {
+                label: name.clone(),
+                kind: CompletionKind::Property,
+                detail: Some(detail),
+                insert_text: syntax.key(name),
+                range,
+            };
+            (is_required, completion)
+        })
+        .collect();
+    completions.sort_by_key(|(is_required, _)| !is_required);
+    completions
+        .into_iter()
+        .map(|(_, completion)| completion)
+        .collect()
+}
+
+/// Collects the values `schema` allows, if it allows only a few.
+fn allowed_values(
+    defs: &Value,
+    schema: &Value,
+    values: &mut Vec<(Value, Option<String>)>,
+    depth: usize,
+) {
+    if depth > MAX_REF_HOPS {
+        return;
+    }
+    let schema = resolve_refs(defs, schema);
+    let mut push = |value: Value, detail: Option<String>| {
+        if !values.iter().any(|(v, _)| *v == value) {
+            values.push((value, detail));
+        }
+    };
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        for value in options {
+            push(value.clone(), None);
+        }
+    }
+    if let Some(value) = schema.get("const") {
+        push(value.clone(), None);
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("boolean") if schema.get("enum").is_none() => {
+            push(Value::Bool(true), None);
+            push(Value::Bool(false), None);
+        }
+        Some("null") => push(Value::Null, None),
+        _ => {}
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(keyword).and_then(Value::as_array) {
+            for variant in variants {
+                allowed_values(defs, variant, values, depth + 1);
+            }
+        }
+    }
+}
+
+fn value_completion(
+    value: Value,
+    detail: Option<String>,
+    range: Range,
+    syntax: Syntax,
+) -> Completion {
+    let label = match &value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    Completion {
+        label,
+        kind: CompletionKind::Value,
+        detail,
+        insert_text: syntax.value(&value),
+        range,
+    }
+}
+
+/// Converts serde_json's one-based line and column of a syntax error to a
+/// byte offset.
+fn syntax_error_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let mut offset = (line_start + column.saturating_sub(1)).min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Returns the span of the value at `path`, or for a path that is not in
+/// the document, the first character of the deepest value containing it.
+fn span_of(spans: &HashMap<JsonPath, (usize, usize)>, path: &JsonPath) -> (usize, usize) {
+    if let Some(span) = spans.get(path) {
+        return *span;
+    }
+    let mut ancestor = path.parent();
+    while let Some(path) = ancestor {
+        if let Some((start, end)) = spans.get(&path) {
+            return (*start, (*start + 1).min(*end));
+        }
+        ancestor = path.parent();
+    }
+    (0, 0)
+}
+
+/// Returns the byte span of every value in a JSON document.
+pub(crate) fn locate_values(text: &str) -> HashMap<JsonPath, (usize, usize)> {
+    let mut spans = HashMap::new();
+    let mut scanner = Scanner::new(text);
+    scanner.value(JsonPath::root(), &mut spans);
+    spans
+}
+
+/// Where in a document a cursor is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Slot {
+    /// A property name is expected; the path is the object's.
+    Key,
+    /// A value is expected; the path is the value's.
+    Value,
+}
+
+/// The language a completion's text is written in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Syntax {
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+impl Syntax {
+    /// Returns the text of a property name.
+    fn key(self, name: &str) -> String {
+        match self {
+            Syntax::Json => Value::String(name.to_string()).to_string(),
+            #[cfg(feature = "yaml")]
+            Syntax::Yaml => yaml::scalar(name),
+        }
+    }
+
+    /// Returns the text of a value.
+    fn value(self, value: &Value) -> String {
+        match (self, value) {
+            #[cfg(feature = "yaml")]
+            (Syntax::Yaml, Value::String(s)) => yaml::scalar(s),
+            _ => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CursorContext {
+    slot: Slot,
+    path: JsonPath,
+    /// The properties of the object the cursor is in, for [`Slot::Key`].
+    present_keys: Vec<String>,
+    /// The byte span of the token under the cursor, or an empty span.
+    token: (usize, usize),
+    /// The document's `"$schema"`, if it names one before the cursor.
+    schema_name: Option<String>,
+    /// Whether the cursor starts a YAML sequence item, which may be a value
+    /// or the first key of an object.
+    item_start: bool,
+}
+
+/// An open object or array while scanning a possibly incomplete document.
+struct Frame {
+    path: JsonPath,
+    kind: FrameKind,
+    keys: Vec<String>,
+}
+
+enum FrameKind {
+    Object { key: Option<String>, state: Expect },
+    Array { index: usize, state: Expect },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Key,
+    Colon,
+    Value,
+    Comma,
+}
+
+/// Finds what is expected at byte `cursor` of a possibly incomplete
+/// document.
+fn cursor_context(text: &str, cursor: usize) -> Option<CursorContext> {
+    let bytes = text.as_bytes();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root_done = false;
+    let mut context: Option<(CursorContext, usize)> = None;
+    let mut schema_name = None;
+    let mut pos = 0;
+
+    // The slot and path a token at this point would fill
+    let slot_here = |stack: &[Frame], root_done: bool| -> Option<(Slot, JsonPath)> {
+        match stack.last() {
+            None if !root_done => Some((Slot::Value, JsonPath::root())),
+            None => None,
+            Some(frame) => match &frame.kind {
+                FrameKind::Object {
+                    state: Expect::Key, ..
+                } => Some((Slot::Key, frame.path.clone())),
+                FrameKind::Object {
+                    state: Expect::Value,
+                    key: Some(key),
+                } => Some((Slot::Value, frame.path.push_field(key.clone()))),
+                FrameKind::Array {
+                    state: Expect::Value,
+                    index,
+                } => Some((Slot::Value, frame.path.push_index(*index))),
+                _ => None,
+            },
+        }
+    };
+
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let token_end = token_end(bytes, pos);
+        if context.is_none() && (pos >= cursor || cursor <= token_end) {
+            let inside = pos < cursor && pos < bytes.len();
+            let (slot, path) = slot_here(&stack, root_done)?;
+            let token = if inside {
+                (pos, token_end)
+            } else {
+                (cursor, cursor)
+            };
+            context = Some((
+                CursorContext {
+                    slot,
+                    path,
+                    present_keys: Vec::new(),
+                    token,
+                    schema_name: schema_name.clone(),
+                    item_start: false,
+                },
+                stack.len(),
+            ));
+        }
+        if pos >= bytes.len() {
+            break;
+        }
+
+        match bytes[pos] {
+            open @ (b'{' | b'[') => {
+                let path = match slot_here(&stack, root_done) {
+                    Some((Slot::Value, path)) => path,
+                    _ => return finish(context, &stack),
+                };
+                stack.push(Frame {
+                    path,
+                    kind: if open == b'{' {
+                        FrameKind::Object {
+                            key: None,
+                            state: Expect::Key,
+                        }
+                    } else {
+                        FrameKind::Array {
+                            index: 0,
+                            state: Expect::Value,
+                        }
+                    },
+                    keys: Vec::new(),
+                });
+            }
+            b'}' | b']' => {
+                let frame = stack.pop();
+                if let (Some(frame), Some((found, depth))) = (&frame, &mut context) {
+                    if stack.len() + 1 == *depth && found.slot == Slot::Key {
+                        found.present_keys = frame.keys.clone();
+                    }
+                }
+                value_done(&mut stack, &mut root_done);
+            }
+            b':' => {
+                if let Some(Frame {
+                    kind: FrameKind::Object { state, .. },
+                    ..
+                }) = stack.last_mut()
+                {
+                    *state = Expect::Value;
+                }
+            }
+            b',' => match stack.last_mut().map(|frame| &mut frame.kind) {
+                Some(FrameKind::Object { key, state }) => {
+                    *key = None;
+                    *state = Expect::Key;
+                }
+                Some(FrameKind::Array { index, state }) => {
+                    *index += 1;
+                    *state = Expect::Value;
+                }
+                None => {}
+            },
+            _ => {
+                let token = &text[pos..token_end];
+                let expecting_key = matches!(
+                    stack.last(),
+                    Some(Frame {
+                        kind: FrameKind::Object {
+                            state: Expect::Key,
+                            ..
+                        },
+                        ..
+                    })
+                );
+                if expecting_key {
+                    let key = serde_json::from_str::<String>(token).unwrap_or_default();
+                    if let Some(frame) = stack.last_mut() {
+                        frame.keys.push(key.clone());
+                        frame.kind = FrameKind::Object {
+                            key: Some(key),
+                            state: Expect::Colon,
+                        };
+                    }
+                } else {
+                    if stack.len() == 1 {
+                        if let Some(Frame {
+                            kind: FrameKind::Object { key: Some(key), .. },
+                            ..
+                        }) = stack.last()
+                        {
+                            if key == "$schema" {
+                                schema_name = serde_json::from_str::<String>(token).ok();
+                            }
+                        }
+                    }
+                    value_done(&mut stack, &mut root_done);
+                }
+                pos = token_end;
+                continue;
+            }
+        }
+        pos += 1;
+    }
+
+    finish(context, &stack)
+}
+
+/// Fills in the keys of the object the cursor is in, if it was never
+/// closed.
+fn finish(context: Option<(CursorContext, usize)>, stack: &[Frame]) -> Option<CursorContext> {
+    let (mut context, depth) = context?;
+    if context.slot == Slot::Key && context.present_keys.is_empty() {
+        if let Some(frame) = depth.checked_sub(1).and_then(|i| stack.get(i)) {
+            context.present_keys = frame.keys.clone();
+        }
+    }
+    Some(context)
+}
+
+/// Marks the value the innermost frame was expecting as complete.
+fn value_done(stack: &mut [Frame], root_done: &mut bool) {
+    match stack.last_mut().map(|frame| &mut frame.kind) {
+        Some(FrameKind::Object { state, .. }) | Some(FrameKind::Array { state, .. }) => {
+            *state = Expect::Comma;
+        }
+        None => *root_done = true,
+    }
+}
+
+/// Returns the end of the string or scalar token starting at `pos`, or
+/// `pos` for punctuation.
+fn token_end(bytes: &[u8], pos: usize) -> usize {
+    match bytes.get(pos) {
+        None | Some(b'{' | b'}' | b'[' | b']' | b':' | b',') => pos,
+        Some(b'"') => {
+            let mut i = pos + 1;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b'"' => return i + 1,
+                    b'\n' => return i,
+                    _ => i += 1,
+                }
+            }
+            bytes.len()
+        }
+        Some(_) => {
+            let mut i = pos;
+            while i < bytes.len()
+                && !bytes[i].is_ascii_whitespace()
+                && !matches!(bytes[i], b'{' | b'}' | b'[' | b']' | b':' | b',' | b'"')
+            {
+                i += 1;
+            }
+            i
+        }
+    }
+}
+
+/// Records the spans of values in a valid JSON document.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn value(&mut self, path: JsonPath, spans: &mut HashMap<JsonPath, (usize, usize)>) {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b'}') | None => break,
+                        Some(b',') => self.pos += 1,
+                        Some(b'"') => {
+                            let end = token_end(self.text.as_bytes(), self.pos);
+                            let key = serde_json::from_str::<String>(&self.text[self.pos..end])
+                                .unwrap_or_default();
+                            self.pos = end;
+                            self.skip_whitespace();
+                            if self.peek() == Some(b':') {
+                                self.pos += 1;
+                            }
+                            self.value(path.push_field(key), spans);
+                        }
+                        Some(_) => self.pos += 1,
+                    }
+                }
+                self.pos += 1;
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') | None => break,
+                        Some(b',') => {
+                            self.pos += 1;
+                            index += 1;
+                        }
+                        Some(_) => self.value(path.push_index(index), spans),
+                    }
+                }
+                self.pos += 1;
+            }
+            Some(_) => self.pos = token_end(self.text.as_bytes(), self.pos).max(self.pos + 1),
+            None => return,
+        }
+        spans.insert(path, (start, self.pos.min(self.text.len())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+
+    fn checker() -> DocumentChecker {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Address",
+                Schema::object()
+                    .field("city", Schema::string())
+                    .optional("country", Schema::string().one_of(["FR", "DE"])),
+            )
+            .unwrap();
+        registry
+            .register(
+                "Order",
+                Schema::object()
+                    .field("id", Schema::integer())
+                    .field("status", Schema::string().one_of(["open", "closed"]))
+                    .optional("gift", crate::schema::primitive::BooleanSchema)
+                    .optional("ship_to", Schema::ref_("Address"))
+                    .optional("lines", Schema::array(Schema::ref_("Address"))),
+            )
+            .unwrap();
+        DocumentChecker::new(registry)
+    }
+
+    fn labels(completions: &[Completion]) -> Vec<&str> {
+        completions.iter().map(|c| c.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_positions() {
+        let text = "{\n  \"é😀\": 1\n}";
+        let offset = text.find('1').unwrap();
+        assert_eq!(Position::at_offset(text, offset), Position::new(1, 9));
+        assert_eq!(Position::new(1, 9).offset_in(text), offset);
+        assert_eq!(Position::new(0, 40).offset_in(text), 1);
+        assert_eq!(Position::new(9, 0).offset_in(text), text.len());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let checker = checker();
+        let text = r#"{
+  "$schema": "Order",
+  "id": "7",
+  "status": "open",
+  "ship_to": {"country": "IT"}
+}"#;
+        let diagnostics = checker.diagnostics(text);
+        let found: Vec<(u32, u32, u32, &str)> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.range.start.line,
+                    d.range.start.character,
+                    d.range.end.character,
+                    d.code.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (2, 8, 11, "invalid_type"),
+                (4, 13, 14, "required"),
+                (4, 25, 29, "invalid_enum"),
+            ]
+        );
+
+        let syntax = checker.diagnostics("{\n  \"id\": 1,\n}");
+        assert_eq!(syntax[0].code, "invalid_json");
+        assert_eq!(syntax[0].range.start.line, 2);
+
+        assert_eq!(checker.diagnostics("{}")[0].code, "no_schema");
+        let checker = checker.default_schema("Order");
+        assert!(checker
+            .diagnostics(r#"{"id": 1, "status": "closed"}"#)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_property_completions() {
+        let checker = checker().default_schema("Order");
+
+        let text = "{\"gift\": true, \"\"}";
+        let completions = checker.completions(text, Position::new(0, 16));
+        assert_eq!(
+            labels(&completions),
+            ["$schema", "id", "status", "lines", "ship_to"]
+        );
+        assert_eq!(completions[1].detail.as_deref(), Some("required integer"));
+        assert_eq!(completions[1].insert_text, "\"id\"");
+        // The empty string is replaced
+        assert_eq!(completions[1].range.start, Position::new(0, 15));
+        assert_eq!(completions[1].range.end, Position::new(0, 17));
+
+        // Keys after the cursor are present too, and nested objects use
+        // their own schema
+        let text = "{\"ship_to\": {\n  \n  \"city\": \"Paris\"}}";
+        let completions = checker.completions(text, Position::new(1, 2));
+        assert_eq!(labels(&completions), ["country"]);
+    }
+
+    #[test]
+    fn test_value_completions() {
+        let checker = checker().default_schema("Order");
+
+        let completions = checker.completions("{\"status\": ", Position::new(0, 11));
+        assert_eq!(labels(&completions), ["open", "closed"]);
+        assert_eq!(completions[0].insert_text, "\"open\"");
+
+        let text = "{\"lines\": [{\"country\": \"F\"}, {\"country\": \"D\"}]}";
+        let cursor = text.rfind("\"D").unwrap() + 2;
+        let completions = checker.completions(text, Position::at_offset(text, cursor));
+        assert_eq!(labels(&completions), ["FR", "DE"]);
+        assert_eq!(
+            completions[0].range,
+            Range::of(text, cursor - 2, cursor + 1)
+        );
+
+        let completions = checker.completions("{\"gift\": t", Position::new(0, 10));
+        assert_eq!(labels(&completions), ["true", "false"]);
+
+        let completions = checker.completions("{\"$schema\": \"", Position::new(0, 13));
+        assert_eq!(labels(&completions), ["Address", "Order"]);
+
+        // A document naming its schema completes against it
+        let text = "{\"$schema\": \"Address\", \"country\": ";
+        let completions = checker.completions(text, Position::at_offset(text, text.len()));
+        assert_eq!(labels(&completions), ["FR", "DE"]);
+    }
+}
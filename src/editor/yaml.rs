@@ -0,0 +1,661 @@
+//! YAML documents.
+//!
+//! Diagnostics parse the document into a JSON value, recording the span of
+//! every value, and validate it like a JSON document. Completions work on
+//! documents being typed, which are rarely valid YAML, so they find the
+//! cursor's place from the indentation of the lines above it. They support
+//! block mappings and sequences, the style YAML configuration is written in.
+
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+use yaml_rust2::Yaml;
+
+use super::{
+    Completion, CursorContext, Diagnostic, DocumentChecker, Position, Range, Slot, Syntax,
+};
+use crate::path::{JsonPath, PathSegment};
+
+impl DocumentChecker {
+    /// Returns every problem in the YAML document `text`: a syntax error if
+    /// it is not valid YAML, and otherwise each validation error, placed on
+    /// the value it is about.
+    ///
+    /// Only the first document of a stream is checked. Keys that are not
+    /// strings are converted to strings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::editor::{DocumentChecker, Position};
+    /// use postmortem::{Schema, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry
+    ///     .register(
+    ///         "Order",
+    ///         Schema::object()
+    ///             .field("id", Schema::integer())
+    ///             .field("status", Schema::string().one_of(["open", "closed"])),
+    ///     )
+    ///     .unwrap();
+    /// let checker = DocumentChecker::new(registry);
+    ///
+    /// let text = "$schema: Order\nid: 1\nstatus: shipped\n";
+    /// let diagnostics = checker.yaml_diagnostics(text);
+    /// assert_eq!(diagnostics.len(), 1);
+    /// assert_eq!(diagnostics[0].range.start, Position::new(2, 8));
+    ///
+    /// let completions = checker.yaml_completions(text, Position::new(2, 9));
+    /// let labels: Vec<_> = completions.iter().map(|c| c.label.as_str()).collect();
+    /// assert_eq!(labels, ["open", "closed"]);
+    /// ```
+    pub fn yaml_diagnostics(&self, text: &str) -> Vec<Diagnostic> {
+        let mut builder = Builder::new(text);
+        if let Err(e) = Parser::new_from_str(text).load(&mut builder, false) {
+            let offset = builder.byte(e.marker());
+            return vec![Diagnostic {
+                range: Range::of(text, offset, offset),
+                message: e.to_string(),
+                code: "invalid_yaml".to_string(),
+            }];
+        }
+        let spans = builder.spans;
+        let value = builder.root.unwrap_or(Value::Null);
+        self.validation_diagnostics(text, value, || spans)
+    }
+
+    /// Returns suggestions for the text at `position` in the YAML document
+    /// `text`.
+    ///
+    /// Like [`completions`](Self::completions), with keys and strings
+    /// written as plain scalars where YAML allows it, and quoted otherwise.
+    pub fn yaml_completions(&self, text: &str, position: Position) -> Vec<Completion> {
+        match cursor_context(text, position.offset_in(text)) {
+            Some(cursor) => self.complete(text, cursor, Syntax::Yaml),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Returns `s` as a YAML scalar: plain if it reads back as the same
+/// string, and double-quoted otherwise.
+///
+/// Words YAML 1.1 reads as booleans, such as `no`, are quoted too, for the
+/// tools that still follow it.
+pub(super) fn scalar(s: &str) -> String {
+    const BOOLEANS_1_1: [&str; 8] = ["y", "n", "yes", "no", "on", "off", "true", "false"];
+    let plain = !s.is_empty()
+        && !BOOLEANS_1_1.contains(&s.to_ascii_lowercase().as_str())
+        && !s.starts_with(['-', ' '])
+        && !s.ends_with(' ')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | '$' | ' '))
+        && matches!(Yaml::from_str(s), Yaml::String(_));
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
+
+/// Converts a scalar to the JSON value it stands for, with the YAML 1.2
+/// core schema for plain scalars.
+fn resolve(text: String, style: TScalarStyle, tag: Option<&Tag>) -> Value {
+    if style != TScalarStyle::Plain || tag.is_some_and(|tag| tag.suffix == "str") {
+        return Value::String(text);
+    }
+    match Yaml::from_str(&text) {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Integer(i) => Value::from(i),
+        Yaml::Real(real) => real
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map_or(Value::String(text), Value::Number),
+        _ => Value::String(text),
+    }
+}
+
+/// An open mapping or sequence.
+struct Node {
+    path: JsonPath,
+    start: usize,
+    anchor: usize,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    /// A mapping, and the key whose value comes next, if it was read.
+    Mapping(Map<String, Value>, Option<String>),
+    Sequence(Vec<Value>),
+}
+
+/// Builds the JSON value of a YAML document and the byte span of each of
+/// its values from parser events.
+struct Builder<'a> {
+    text: &'a str,
+    /// The byte offset of each character, as markers count characters.
+    offsets: Vec<usize>,
+    stack: Vec<Node>,
+    anchors: HashMap<usize, Value>,
+    spans: HashMap<JsonPath, (usize, usize)>,
+    root: Option<Value>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            offsets: text.char_indices().map(|(i, _)| i).collect(),
+            stack: Vec::new(),
+            anchors: HashMap::new(),
+            spans: HashMap::new(),
+            root: None,
+        }
+    }
+
+    /// Returns the byte offset of a marker.
+    fn byte(&self, marker: &Marker) -> usize {
+        self.offsets
+            .get(marker.index())
+            .copied()
+            .unwrap_or(self.text.len())
+    }
+
+    /// Returns the path of the next value.
+    fn next_path(&self) -> JsonPath {
+        match self.stack.last() {
+            None => JsonPath::root(),
+            Some(node) => match &node.kind {
+                NodeKind::Mapping(_, Some(key)) => node.path.push_field(key.clone()),
+                NodeKind::Mapping(_, None) => node.path.clone(),
+                NodeKind::Sequence(items) => node.path.push_index(items.len()),
+            },
+        }
+    }
+
+    /// Adds a complete value to the innermost open collection.
+    fn add(&mut self, value: Value, span: (usize, usize), anchor: usize) {
+        if anchor != 0 {
+            self.anchors.insert(anchor, value.clone());
+        }
+        let path = self.next_path();
+        let Some(node) = self.stack.last_mut() else {
+            self.spans.insert(path, span);
+            self.root = Some(value);
+            return;
+        };
+        match &mut node.kind {
+            NodeKind::Mapping(map, key @ Some(_)) => {
+                self.spans.insert(path, span);
+                map.insert(key.take().unwrap_or_default(), value);
+            }
+            // A collection used as a key
+            NodeKind::Mapping(_, key) => {
+                *key = Some(match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                });
+            }
+            NodeKind::Sequence(items) => {
+                self.spans.insert(path, span);
+                items.push(value);
+            }
+        }
+    }
+
+    /// Returns the end of the scalar starting at byte `start`.
+    fn scalar_end(&self, start: usize, style: TScalarStyle, value: &str) -> usize {
+        let rest = &self.text[start..];
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        match style {
+            TScalarStyle::DoubleQuoted => {
+                let mut escaped = false;
+                for (i, c) in rest.char_indices().skip(1) {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => return start + i + 1,
+                        _ => {}
+                    }
+                }
+                self.text.len()
+            }
+            TScalarStyle::SingleQuoted => {
+                let mut chars = rest.char_indices().skip(1).peekable();
+                while let Some((i, c)) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                            chars.next();
+                        } else {
+                            return start + i + 1;
+                        }
+                    }
+                }
+                self.text.len()
+            }
+            TScalarStyle::Plain if rest.starts_with(value) => start + value.len(),
+            _ => {
+                let line = &rest[..line_end];
+                let line = line.find(" #").map_or(line, |comment| &line[..comment]);
+                start + line.trim_end().len()
+            }
+        }
+    }
+}
+
+impl MarkedEventReceiver for Builder<'_> {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        if self.root.is_some() {
+            return;
+        }
+        let at = self.byte(&marker);
+        match event {
+            Event::Scalar(text, style, anchor, tag) => {
+                if let Some(Node {
+                    start,
+                    kind: NodeKind::Mapping(_, key @ None),
+                    ..
+                }) = self.stack.last_mut()
+                {
+                    // A block mapping's start is reported after its first key
+                    *start = (*start).min(at);
+                    *key = Some(text);
+                    return;
+                }
+                let end = if text.is_empty() && style == TScalarStyle::Plain {
+                    at
+                } else {
+                    self.scalar_end(at, style, &text)
+                };
+                let value = resolve(text, style, tag.as_ref());
+                self.add(value, (at, end), anchor);
+            }
+            Event::Alias(anchor) => {
+                let value = self.anchors.get(&anchor).cloned().unwrap_or(Value::Null);
+                let rest = &self.text[at..];
+                let end = at + rest.find(char::is_whitespace).unwrap_or(rest.len());
+                self.add(value, (at, end), 0);
+            }
+            Event::MappingStart(anchor, _) | Event::SequenceStart(anchor, _) => {
+                let kind = match event {
+                    Event::MappingStart(..) => NodeKind::Mapping(Map::new(), None),
+                    _ => NodeKind::Sequence(Vec::new()),
+                };
+                self.stack.push(Node {
+                    path: self.next_path(),
+                    start: at,
+                    anchor,
+                    kind,
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                let Some(node) = self.stack.pop() else {
+                    return;
+                };
+                // Flow collections end at their bracket, block ones before
+                // the next token
+                let end = if matches!(self.text[at..].chars().next(), Some('}' | ']')) {
+                    at + 1
+                } else {
+                    node.start + self.text[node.start..at.max(node.start)].trim_end().len()
+                };
+                let value = match node.kind {
+                    NodeKind::Mapping(map, _) => Value::Object(map),
+                    NodeKind::Sequence(items) => Value::Array(items),
+                };
+                self.add(value, (node.start, end), node.anchor);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An open block mapping or sequence on the lines above the cursor.
+#[derive(Clone)]
+struct Level {
+    /// The column of its keys or dashes.
+    column: usize,
+    segment: PathSegment,
+}
+
+/// Follows the block structure of a document line by line.
+#[derive(Default)]
+struct Outline {
+    levels: Vec<Level>,
+    /// Every key, with the path of the mapping it is in.
+    keys: Vec<(JsonPath, String)>,
+    schema_name: Option<String>,
+}
+
+impl Outline {
+    fn path(&self) -> JsonPath {
+        self.levels
+            .iter()
+            .fold(JsonPath::root(), |path, level| match &level.segment {
+                PathSegment::Field(name) => path.push_field(name.clone()),
+                PathSegment::Index(index) => path.push_index(*index),
+            })
+    }
+
+    /// Closes the levels a line starting at `column` is not inside of.
+    fn close(&mut self, column: usize, inclusive: bool) {
+        while self
+            .levels
+            .last()
+            .is_some_and(|level| level.column > column || (inclusive && level.column == column))
+        {
+            self.levels.pop();
+        }
+    }
+
+    /// Reads the dashes starting `line` at `column`, opening or advancing
+    /// a sequence item for each, and returns the column and text after
+    /// them.
+    fn dashes<'t>(&mut self, mut column: usize, mut line: &'t str) -> (usize, &'t str, bool) {
+        let mut any = false;
+        while let Some(rest) = line.strip_prefix("- ") {
+            self.close(column, false);
+            match self.levels.last_mut() {
+                Some(Level {
+                    column: c,
+                    segment: PathSegment::Index(index),
+                }) if *c == column => *index += 1,
+                _ => self.levels.push(Level {
+                    column,
+                    segment: PathSegment::Index(0),
+                }),
+            }
+            let content = rest.trim_start_matches(' ');
+            column += line.len() - content.len();
+            line = content;
+            any = true;
+        }
+        (column, line, any)
+    }
+
+    /// Reads a whole line.
+    fn line(&mut self, line: &str) {
+        let content = line.trim_start_matches(' ');
+        if content.is_empty() || content.starts_with('#') || line.starts_with("---") {
+            return;
+        }
+        let (column, content, _) = self.dashes(line.len() - content.len(), content);
+        let Some((key, value)) = split_key(content) else {
+            return;
+        };
+        self.close(column, true);
+        let value = value.split(" #").next().unwrap_or_default().trim();
+        if self.levels.is_empty() && key == "$schema" {
+            self.schema_name = Some(unquote(value));
+        }
+        self.keys.push((self.path(), key.clone()));
+        if value.is_empty() {
+            self.levels.push(Level {
+                column,
+                segment: PathSegment::Field(key),
+            });
+        }
+    }
+}
+
+/// Splits `key: value` into the key and the rest, if the line has a key.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    let key_end = match content.chars().next()? {
+        quote @ ('"' | '\'') => content[1..].find(quote)? + 2,
+        _ => content
+            .find(": ")
+            .or_else(|| content.strip_suffix(':').map(str::len))?,
+    };
+    let rest = content[key_end..].strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((unquote(content[..key_end].trim_end()), rest))
+}
+
+/// Removes the quotes around a scalar, if it has them.
+fn unquote(scalar: &str) -> String {
+    if scalar.starts_with('"') {
+        if let Ok(s) = serde_json::from_str(scalar) {
+            return s;
+        }
+    }
+    match scalar.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(s) => s.replace("''", "'"),
+        None => scalar.to_string(),
+    }
+}
+
+/// Finds what is expected at byte `cursor` of a possibly incomplete YAML
+/// document.
+fn cursor_context(text: &str, cursor: usize) -> Option<CursorContext> {
+    let mut outline = Outline::default();
+    let mut context = None;
+    let mut start = 0;
+    // A document that is empty or ends with a newline has an empty last line
+    let last = (text.is_empty() || text.ends_with('\n')).then_some("");
+    for raw in text.split_inclusive('\n').chain(last) {
+        let line = raw.trim_end_matches(['\n', '\r']);
+        if context.is_none() && cursor <= start + line.len() {
+            let mut here = Outline {
+                levels: outline.levels.clone(),
+                ..Outline::default()
+            };
+            context = Some(here.cursor(line, start, cursor - start));
+            // Only the structure of the cursor's line counts for the rest
+            let content = line.trim_start_matches(' ');
+            outline.dashes(line.len() - content.len(), content);
+        } else {
+            outline.line(line);
+        }
+        start += raw.len();
+    }
+
+    let mut context = context?;
+    if context.slot == Slot::Key || context.item_start {
+        context.present_keys = outline
+            .keys
+            .iter()
+            .filter(|(path, _)| *path == context.path)
+            .map(|(_, key)| key.clone())
+            .collect();
+    }
+    context.schema_name = outline.schema_name;
+    Some(context)
+}
+
+impl Outline {
+    /// Describes the cursor at byte `cursor` of `line`, which starts at
+    /// byte `start` of the document.
+    fn cursor(&mut self, line: &str, start: usize, cursor: usize) -> CursorContext {
+        let prefix = &line[..cursor];
+        let content = prefix.trim_start_matches(' ');
+        let (column, content, item_start) = self.dashes(prefix.len() - content.len(), content);
+        // The token runs on after the cursor up to a space or colon
+        let token_end = cursor
+            + line[cursor..]
+                .find(|c: char| c.is_whitespace() || c == ':')
+                .unwrap_or(line.len() - cursor);
+
+        if let Some((key, value)) = split_key(content) {
+            self.close(column, true);
+            let value_start = cursor - value.trim_start().len();
+            let rest = &line[value_start..];
+            let rest = rest.split(" #").next().unwrap_or_default().trim_end();
+            return CursorContext {
+                slot: Slot::Value,
+                path: self.path().push_field(key),
+                present_keys: Vec::new(),
+                token: (
+                    start + value_start,
+                    start + (value_start + rest.len()).max(cursor),
+                ),
+                schema_name: None,
+                item_start: false,
+            };
+        }
+
+        let token_start = if content.is_empty() { cursor } else { column };
+        if !item_start {
+            self.close(column, true);
+        }
+        CursorContext {
+            slot: if item_start { Slot::Value } else { Slot::Key },
+            path: self.path(),
+            present_keys: Vec::new(),
+            token: (start + token_start, start + token_end),
+            schema_name: None,
+            item_start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::SchemaRegistry;
+    use serde_json::json;
+
+    fn checker() -> DocumentChecker {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Address",
+                Schema::object()
+                    .field("city", Schema::string())
+                    .optional("country", Schema::string().one_of(["FR", "DE", "no"])),
+            )
+            .unwrap();
+        registry
+            .register(
+                "Order",
+                Schema::object()
+                    .field("id", Schema::integer())
+                    .field("status", Schema::string().one_of(["open", "closed"]))
+                    .optional("gift", crate::schema::primitive::BooleanSchema)
+                    .optional("lines", Schema::array(Schema::ref_("Address"))),
+            )
+            .unwrap();
+        DocumentChecker::new(registry).default_schema("Order")
+    }
+
+    fn labels(completions: &[Completion]) -> Vec<&str> {
+        completions.iter().map(|c| c.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_values_and_spans() {
+        let text = "id: 7\né: \"x\"\nlines:\n  - city: Paris\n    tags: [a, 'b']\nn: ~\nf: 1.5\n";
+        let mut builder = Builder::new(text);
+        Parser::new_from_str(text)
+            .load(&mut builder, false)
+            .unwrap();
+        assert_eq!(
+            builder.root,
+            Some(json!({
+                "id": 7,
+                "é": "x",
+                "lines": [{"city": "Paris", "tags": ["a", "b"]}],
+                "n": null,
+                "f": 1.5
+            }))
+        );
+        let span = |path: JsonPath| {
+            let (start, end) = builder.spans[&path];
+            &text[start..end]
+        };
+        assert_eq!(span(JsonPath::from_field("é")), "\"x\"");
+        let line = JsonPath::from_field("lines").push_index(0);
+        assert_eq!(span(line.push_field("city")), "Paris");
+        assert_eq!(span(line.push_field("tags")), "[a, 'b']");
+        assert_eq!(span(line.push_field("tags").push_index(1)), "'b'");
+        assert_eq!(span(line.clone()), "city: Paris\n    tags: [a, 'b']");
+    }
+
+    #[test]
+    fn test_yaml_diagnostics() {
+        let checker = checker();
+        let text = "id: \"7\"\nstatus: open\nlines:\n  - country: IT\n";
+        let diagnostics = checker.yaml_diagnostics(text);
+        let found: Vec<(u32, u32, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.range.start.character, d.code.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (0, 4, "invalid_type"),
+                (3, 4, "required"),
+                (3, 13, "invalid_enum")
+            ]
+        );
+
+        let syntax = checker.yaml_diagnostics("id: [1,\nstatus: open\n");
+        assert_eq!(syntax[0].code, "invalid_yaml");
+        assert_eq!(syntax[0].range.start.line, 1);
+
+        assert!(checker
+            .yaml_diagnostics("id: 1\nstatus: closed\n")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_yaml_completions() {
+        let checker = checker();
+
+        let text = "gift: true\n\nstatus: open\n";
+        let completions = checker.yaml_completions(text, Position::new(1, 0));
+        assert_eq!(labels(&completions), ["$schema", "id", "lines"]);
+        assert_eq!(completions[1].insert_text, "id");
+        assert_eq!(completions[0].insert_text, "$schema");
+
+        let completions = checker.yaml_completions("status: c", Position::new(0, 9));
+        assert_eq!(labels(&completions), ["open", "closed"]);
+        assert_eq!(completions[1].insert_text, "closed");
+        assert_eq!(completions[1].range, Range::of("status: c", 8, 9));
+
+        // Items of a sequence of objects complete their keys, and values
+        // that would read back as another type are quoted
+        let text = "lines:\n  - city: Paris\n    country: \n  - \n";
+        let completions = checker.yaml_completions(text, Position::new(2, 13));
+        assert_eq!(labels(&completions), ["FR", "DE", "no"]);
+        assert_eq!(completions[2].insert_text, "\"no\"");
+        let completions = checker.yaml_completions(text, Position::new(3, 4));
+        assert_eq!(labels(&completions), ["city", "country"]);
+        // The key under the cursor is replaced
+        let completions = checker.yaml_completions(text, Position::new(2, 4));
+        assert_eq!(labels(&completions), ["country"]);
+        assert_eq!(completions[0].range, Range::of(text, 27, 34));
+
+        let completions = checker.yaml_completions("", Position::new(0, 0));
+        assert_eq!(
+            labels(&completions),
+            ["$schema", "id", "status", "gift", "lines"]
+        );
+        let text = "gift: true\r\nstatus: o\r\n";
+        let completions = checker.yaml_completions(text, Position::new(1, 9));
+        assert_eq!(completions[0].range, Range::of(text, 20, 21));
+
+        let completions = checker.yaml_completions("$schema: ", Position::new(0, 9));
+        assert_eq!(labels(&completions), ["Address", "Order"]);
+        let text = "$schema: Address\ncountry: ";
+        let completions = checker.yaml_completions(text, Position::new(1, 9));
+        assert_eq!(labels(&completions), ["FR", "DE", "no"]);
+    }
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(scalar("open"), "open");
+        assert_eq!(scalar("$schema"), "$schema");
+        assert_eq!(scalar("true"), "\"true\"");
+        assert_eq!(scalar("Off"), "\"Off\"");
+        assert_eq!(scalar("12"), "\"12\"");
+        assert_eq!(scalar("a: b"), "\"a: b\"");
+        assert_eq!(scalar(""), "\"\"");
+    }
+}
@@ -0,0 +1,419 @@
+//! On-disk cache of compiled schema bundles.
+//!
+//! Compiling a large bundle of JSON Schemas parses the whole document and
+//! scans its text for the line of every rule. A [`CompiledSchema`] holds the
+//! result, and can be saved to a cache directory under its fingerprint, so
+//! a CLI run or a serverless cold start loading the same bundle again
+//! reads the compiled form instead.
+//!
+//! The fingerprint covers the bundle's text and path, the cache format
+//! version, and the crate version, so a changed bundle or an upgraded crate
+//! never reads a stale entry: it simply misses and compiles again.
+//!
+//! # Example
+//!
+//! ```rust
+//! use postmortem::effect::CompiledSchema;
+//! use postmortem::SchemaRegistry;
+//!
+//! let cache = std::env::temp_dir().join("postmortem-doc-cache");
+//! let bundle = r#"{"username": {"type": "string", "minLength": 3}}"#;
+//!
+//! // Compiles on the first run and reads the cache afterwards
+//! let compiled = CompiledSchema::load_or_compile(&cache, "schemas.json", bundle).unwrap();
+//!
+//! let registry = SchemaRegistry::new();
+//! compiled.register(&registry).unwrap();
+//! assert!(registry.validate("username", &serde_json::json!("ab")).unwrap().is_failure());
+//! # std::fs::remove_dir_all(&cache).ok();
+//! ```
+
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::loading::{parse_bundle, Definition, SchemaLoadError};
+use crate::error::Provenance;
+use crate::registry::SchemaRegistry;
+
+/// The version of the cache file format. Bump it whenever the format or
+/// the meaning of a compiled definition changes.
+const FORMAT_VERSION: u64 = 1;
+
+/// The extension of cache files.
+const EXTENSION: &str = "pmcache";
+
+/// A bundle of JSON Schemas compiled for registration.
+///
+/// Compile a bundle with [`compile`](Self::compile), store it with
+/// [`save`](Self::save), and read it back with [`load`](Self::load), or do
+/// all three with [`load_or_compile`](Self::load_or_compile).
+/// [`register`](Self::register) adds its schemas to a registry as
+/// [`SchemaRegistry::load_bundle`] would.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledSchema {
+    fingerprint: String,
+    source: PathBuf,
+    definitions: Vec<(String, Definition)>,
+}
+
+impl CompiledSchema {
+    /// Compiles a bundle, as accepted by [`SchemaRegistry::load_bundle`].
+    ///
+    /// Unlike `load_bundle`, compilation fails if any schema is invalid,
+    /// so a cached bundle is always complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of every invalid schema, a single error directly
+    /// and several as [`SchemaLoadError::Multiple`].
+    pub fn compile(path: impl AsRef<Path>, content: &str) -> Result<Self, SchemaLoadError> {
+        let path = path.as_ref();
+        // Registering into a scratch registry checks what parsing cannot,
+        // like patterns that are too expensive
+        let scratch = SchemaRegistry::new();
+        let mut definitions = Vec::new();
+        let mut errors = Vec::new();
+        for (name, definition) in parse_bundle(path, content)? {
+            match definition.and_then(|d| d.register(&scratch, &name, path).map(|()| d)) {
+                Ok(definition) => definitions.push((name, definition)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(Self {
+                fingerprint: Self::fingerprint_of(path, content),
+                source: path.to_path_buf(),
+                definitions,
+            }),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaLoadError::Multiple(errors)),
+        }
+    }
+
+    /// Returns the cache key of a bundle: a hex digest of its path and
+    /// text, the cache format version, and the crate version.
+    pub fn fingerprint_of(path: impl AsRef<Path>, content: &str) -> String {
+        let key = format!(
+            "{}\0{}\0{}\0{}",
+            FORMAT_VERSION,
+            env!("CARGO_PKG_VERSION"),
+            path.as_ref().display(),
+            content
+        );
+        // Two differently seeded hashes make an accidental collision
+        // between cache entries vanishingly unlikely
+        format!(
+            "{:016x}{:016x}",
+            fnv1a(0xcbf2_9ce4_8422_2325, &key),
+            fnv1a(0x6c62_272e_07bb_0142, &key)
+        )
+    }
+
+    /// Returns this bundle's cache key.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Returns the names of the compiled schemas, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        self.definitions
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Registers the compiled schemas.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of schemas that could not be registered, e.g.
+    /// because the name is taken; the others are registered anyway.
+    pub fn register(&self, registry: &SchemaRegistry) -> Result<(), SchemaLoadError> {
+        let mut errors: Vec<_> = self
+            .definitions
+            .iter()
+            .filter_map(|(name, definition)| {
+                definition.register(registry, name, &self.source).err()
+            })
+            .collect();
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaLoadError::Multiple(errors)),
+        }
+    }
+
+    /// Saves the compiled bundle in the cache directory `dir`, creating it
+    /// if needed, and returns the path of the cache file.
+    ///
+    /// The file is named after the fingerprint and written atomically, so
+    /// concurrent processes never read a partial entry.
+    pub fn save(&self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let file = entry_path(dir, &self.fingerprint);
+        let partial = dir.join(format!("{}.{}.tmp", self.fingerprint, std::process::id()));
+        fs::write(&partial, self.to_json().to_string())?;
+        fs::rename(&partial, &file)?;
+        Ok(file)
+    }
+
+    /// Loads the compiled form of a bundle from the cache directory `dir`.
+    ///
+    /// Returns `None` if there is no entry for the bundle, or the entry was
+    /// written by another format or crate version or cannot be read.
+    pub fn load(dir: impl AsRef<Path>, path: impl AsRef<Path>, content: &str) -> Option<Self> {
+        let fingerprint = Self::fingerprint_of(&path, content);
+        let text = fs::read_to_string(entry_path(dir.as_ref(), &fingerprint)).ok()?;
+        let compiled = Self::from_json(&serde_json::from_str(&text).ok()?)?;
+        (compiled.fingerprint == fingerprint).then_some(compiled)
+    }
+
+    /// Loads a bundle from the cache directory `dir`, or compiles it and
+    /// saves it there.
+    ///
+    /// A cache that cannot be written is not an error; the bundle is just
+    /// compiled again next time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`compile`](Self::compile).
+    pub fn load_or_compile(
+        dir: impl AsRef<Path>,
+        path: impl AsRef<Path>,
+        content: &str,
+    ) -> Result<Self, SchemaLoadError> {
+        if let Some(compiled) = Self::load(&dir, &path, content) {
+            return Ok(compiled);
+        }
+        let compiled = Self::compile(path, content)?;
+        let _ = compiled.save(dir);
+        Ok(compiled)
+    }
+
+    fn to_json(&self) -> Value {
+        let schemas: Vec<Value> = self
+            .definitions
+            .iter()
+            .map(|(name, definition)| {
+                let mut schema = match definition {
+                    Definition::String {
+                        defined_at,
+                        min_len,
+                        max_len,
+                        pattern,
+                    } => {
+                        let mut schema = json!({
+                            "type": "string",
+                            "defined_at": provenance_to_json(defined_at),
+                        });
+                        if let Some((min_len, defined_at)) = min_len {
+                            schema["min_len"] = json!([min_len, provenance_to_json(defined_at)]);
+                        }
+                        if let Some((max_len, defined_at)) = max_len {
+                            schema["max_len"] = json!([max_len, provenance_to_json(defined_at)]);
+                        }
+                        if let Some((pattern, defined_at)) = pattern {
+                            schema["pattern"] = json!([pattern, provenance_to_json(defined_at)]);
+                        }
+                        schema
+                    }
+                    Definition::Integer { defined_at } => json!({
+                        "type": "integer",
+                        "defined_at": provenance_to_json(defined_at),
+                    }),
+                    Definition::Object => json!({"type": "object"}),
+                    Definition::Array => json!({"type": "array"}),
+                };
+                schema["name"] = json!(name);
+                schema
+            })
+            .collect();
+
+        json!({
+            "format": FORMAT_VERSION,
+            "crate_version": env!("CARGO_PKG_VERSION"),
+            "fingerprint": self.fingerprint,
+            "source": self.source.display().to_string(),
+            "schemas": schemas,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        if value.get("format")?.as_u64()? != FORMAT_VERSION
+            || value.get("crate_version")?.as_str()? != env!("CARGO_PKG_VERSION")
+        {
+            return None;
+        }
+        let definitions = value
+            .get("schemas")?
+            .as_array()?
+            .iter()
+            .map(|schema| {
+                let name = schema.get("name")?.as_str()?.to_string();
+                let definition = match schema.get("type")?.as_str()? {
+                    "string" => Definition::String {
+                        defined_at: provenance_from_json(schema.get("defined_at")?)?,
+                        min_len: rule_from_json(schema, "min_len", |v| Some(v.as_u64()? as usize))?,
+                        max_len: rule_from_json(schema, "max_len", |v| Some(v.as_u64()? as usize))?,
+                        pattern: rule_from_json(schema, "pattern", |v| {
+                            Some(v.as_str()?.to_string())
+                        })?,
+                    },
+                    "integer" => Definition::Integer {
+                        defined_at: provenance_from_json(schema.get("defined_at")?)?,
+                    },
+                    "object" => Definition::Object,
+                    "array" => Definition::Array,
+                    _ => return None,
+                };
+                Some((name, definition))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            fingerprint: value.get("fingerprint")?.as_str()?.to_string(),
+            source: PathBuf::from(value.get("source")?.as_str()?),
+            definitions,
+        })
+    }
+}
+
+/// Returns the path of the cache entry with a fingerprint.
+fn entry_path(dir: &Path, fingerprint: &str) -> PathBuf {
+    dir.join(format!("{}.{}", fingerprint, EXTENSION))
+}
+
+fn provenance_to_json(provenance: &Provenance) -> Value {
+    json!([provenance.file, provenance.line])
+}
+
+fn provenance_from_json(value: &Value) -> Option<Provenance> {
+    let [file, line] = value.as_array()?.as_slice() else {
+        return None;
+    };
+    Some(Provenance::new(file.as_str()?, line.as_u64()? as usize))
+}
+
+/// Reads an optional `[value, provenance]` rule. The outer `None` means the
+/// rule is malformed; the inner one that it is absent.
+fn rule_from_json<T>(
+    schema: &Value,
+    key: &str,
+    value: impl Fn(&Value) -> Option<T>,
+) -> Option<Option<(T, Provenance)>> {
+    let Some(rule) = schema.get(key) else {
+        return Some(None);
+    };
+    let [rule_value, provenance] = rule.as_array()?.as_slice() else {
+        return None;
+    };
+    Some(Some((
+        value(rule_value)?,
+        provenance_from_json(provenance)?,
+    )))
+}
+
+/// 64-bit FNV-1a with a configurable offset basis.
+fn fnv1a(basis: u64, text: &str) -> u64 {
+    text.bytes().fold(basis, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLE: &str = r#"{
+        "username": {"type": "string", "minLength": 3, "pattern": "^[a-z]+$"},
+        "age": {"type": "integer"},
+        "profile": {"type": "object"}
+    }"#;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "postmortem-compiled-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = cache_dir("round-trip");
+        let compiled = CompiledSchema::compile("bundle.json", BUNDLE).unwrap();
+        assert_eq!(compiled.names(), ["age", "profile", "username"]);
+
+        let file = compiled.save(&dir).unwrap();
+        assert_eq!(
+            file.file_name().unwrap().to_str().unwrap(),
+            format!("{}.pmcache", compiled.fingerprint())
+        );
+        let loaded = CompiledSchema::load(&dir, "bundle.json", BUNDLE).unwrap();
+        assert_eq!(loaded, compiled);
+
+        // Loaded schemas behave like freshly loaded ones, provenance included
+        let registry = SchemaRegistry::new();
+        loaded.register(&registry).unwrap();
+        let errors = registry
+            .validate("username", &json!("AB"))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.first().provenance,
+            Some(Provenance::new("bundle.json", 2))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_entries_miss() {
+        let dir = cache_dir("stale");
+        let compiled = CompiledSchema::compile("bundle.json", BUNDLE).unwrap();
+        let file = compiled.save(&dir).unwrap();
+
+        // A changed bundle or path has another fingerprint
+        let changed = BUNDLE.replace("minLength\": 3", "minLength\": 4");
+        assert!(CompiledSchema::load(&dir, "bundle.json", &changed).is_none());
+        assert!(CompiledSchema::load(&dir, "other.json", BUNDLE).is_none());
+
+        // An entry from another crate version is ignored
+        let mut entry: Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        entry["crate_version"] = json!("0.0.0");
+        fs::write(&file, entry.to_string()).unwrap();
+        assert!(CompiledSchema::load(&dir, "bundle.json", BUNDLE).is_none());
+
+        // ... and so is a corrupt one, which load_or_compile replaces
+        fs::write(&file, "{").unwrap();
+        assert!(CompiledSchema::load(&dir, "bundle.json", BUNDLE).is_none());
+        let recompiled = CompiledSchema::load_or_compile(&dir, "bundle.json", BUNDLE).unwrap();
+        assert_eq!(recompiled, compiled);
+        assert!(CompiledSchema::load(&dir, "bundle.json", BUNDLE).is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_bundles() {
+        let result = CompiledSchema::compile(
+            "bundle.json",
+            r#"{"ok": {"type": "integer"}, "broken": {"minLength": 1}}"#,
+        );
+        assert!(matches!(result, Err(SchemaLoadError::Schema(_, _))));
+
+        let result = CompiledSchema::compile(
+            "bundle.json",
+            r#"{"huge": {"type": "string", "pattern": "((\\w{1,100}){1,100}){1,100}"}, "b": {}}"#,
+        );
+        assert!(matches!(result, Err(SchemaLoadError::Multiple(ref errors)) if errors.len() == 2));
+    }
+}
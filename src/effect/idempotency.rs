@@ -0,0 +1,297 @@
+//! At-most-once checks for requests carrying an idempotency key.
+//!
+//! Clients retry requests, and a retried payment must not be charged twice.
+//! A service that requires an idempotency key on each request can reject
+//! replays as part of validation: [`idempotency_key`] reads the key from a
+//! field of the request and records it in a [`ReplayStore`], and a key seen
+//! again within the replay window fails with a `duplicate_request` error.
+//!
+//! The store is asynchronous, so that it can live in Redis or a database
+//! shared by every instance of a service. [`MemoryReplayStore`] keeps keys
+//! in the process, for tests and single-instance services.
+//!
+//! # Example
+//!
+//! ```rust
+//! use postmortem::effect::{idempotency_key, MemoryReplayStore};
+//! use postmortem::{JsonPath, Schema};
+//! use serde_json::json;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let schema = Schema::object()
+//!     .field("request_id", Schema::string().min_len(1))
+//!     .field("amount", Schema::integer().positive());
+//! let replays = idempotency_key(
+//!     "request_id",
+//!     Arc::new(MemoryReplayStore::new()),
+//!     Duration::from_secs(24 * 60 * 60),
+//! );
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let request = json!({"request_id": "req-1", "amount": 10});
+//! let first = replays.validate(&schema, &request, &JsonPath::root()).await;
+//! assert!(first.is_success());
+//!
+//! let retry = replays.validate(&schema, &request, &JsonPath::root()).await;
+//! let errors = retry.into_result().unwrap_err();
+//! assert_eq!(errors.first().code, "duplicate_request");
+//! # });
+//! ```
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use stillwater::Validation;
+
+use super::store::StoreError;
+use crate::error::{SchemaError, SchemaErrors};
+use crate::path::JsonPath;
+use crate::schema::SchemaLike;
+
+/// The future returned by [`ReplayStore::record`].
+pub type ReplayFuture<'a> = Pin<Box<dyn Future<Output = Result<bool, StoreError>> + Send + 'a>>;
+
+/// Remembers the idempotency keys seen recently.
+///
+/// Implementations must be safe to share between threads and tasks.
+pub trait ReplayStore: Send + Sync {
+    /// Records `key` as seen now, unless it was already seen within
+    /// `window`.
+    ///
+    /// Resolves to `true` if the key was recorded, and `false` if it is a
+    /// replay. Checking and recording must be one atomic step, so that two
+    /// concurrent requests with the same key cannot both be recorded.
+    fn record<'a>(&'a self, key: &'a str, window: Duration) -> ReplayFuture<'a>;
+}
+
+/// A [`ReplayStore`] that keeps keys in memory.
+///
+/// Clones share the same keys. Expired keys are dropped as new keys are
+/// recorded.
+#[derive(Clone, Default)]
+pub struct MemoryReplayStore {
+    seen: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl MemoryReplayStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReplayStore for MemoryReplayStore {
+    fn record<'a>(&'a self, key: &'a str, window: Duration) -> ReplayFuture<'a> {
+        let now = Instant::now();
+        let mut seen = self.seen.lock();
+        seen.retain(|_, at| now.duration_since(*at) < window);
+        let recorded = !seen.contains_key(key);
+        if recorded {
+            seen.insert(key.to_string(), now);
+        }
+        Box::pin(std::future::ready(Ok(recorded)))
+    }
+}
+
+/// Creates a rule that rejects requests whose `field` was already seen
+/// within `window`.
+///
+/// See [`IdempotencyKey`].
+pub fn idempotency_key(
+    field: impl Into<String>,
+    store: Arc<dyn ReplayStore>,
+    window: Duration,
+) -> IdempotencyKey {
+    IdempotencyKey {
+        field: field.into(),
+        store,
+        window,
+    }
+}
+
+/// Rejects replayed requests by their idempotency key.
+///
+/// Created by [`idempotency_key`]. Clones share the same store.
+#[derive(Clone)]
+pub struct IdempotencyKey {
+    field: String,
+    store: Arc<dyn ReplayStore>,
+    window: Duration,
+}
+
+impl IdempotencyKey {
+    /// Validates `value` against `schema`, then checks its key for replays.
+    ///
+    /// The key is only recorded if the request is otherwise valid, so a
+    /// client can fix a rejected request and retry it with the same key.
+    pub async fn validate<S>(
+        &self,
+        schema: &S,
+        value: &Value,
+        path: &JsonPath,
+    ) -> Validation<S::Output, SchemaErrors>
+    where
+        S: SchemaLike,
+    {
+        match schema.validate(value, path) {
+            Validation::Success(validated) => match self.check(value, path).await {
+                Validation::Success(()) => Validation::Success(validated),
+                Validation::Failure(errors) => Validation::Failure(errors),
+            },
+            failure => failure,
+        }
+    }
+
+    /// Checks the key of `value` for replays, and records it.
+    ///
+    /// Fails with `required` or `invalid_type` if the key is missing or is
+    /// not a non-empty string, with `duplicate_request` if it was seen
+    /// within the window, and with `replay_check_failed` if the store
+    /// fails, so that a request is never accepted unchecked.
+    pub async fn check(&self, value: &Value, path: &JsonPath) -> Validation<(), SchemaErrors> {
+        let key_path = path.push_field(&self.field);
+        let key = match value.get(&self.field) {
+            None => {
+                return fail(
+                    SchemaError::new(
+                        key_path,
+                        format!("required field '{}' is missing", self.field),
+                    )
+                    .with_code("required")
+                    .with_expected("an idempotency key"),
+                )
+            }
+            Some(Value::String(key)) if !key.is_empty() => key,
+            Some(_) => {
+                return fail(
+                    SchemaError::new(key_path, "idempotency key must be a non-empty string")
+                        .with_code("invalid_type")
+                        .with_expected("non-empty string"),
+                )
+            }
+        };
+
+        match self.store.record(key, self.window).await {
+            Ok(true) => Validation::Success(()),
+            Ok(false) => fail(
+                SchemaError::new(
+                    key_path,
+                    format!(
+                        "request '{}' was already received in the last {}s",
+                        key,
+                        self.window.as_secs()
+                    ),
+                )
+                .with_code("duplicate_request")
+                .with_expected(format!(
+                    "a key not used in the last {}s",
+                    self.window.as_secs()
+                )),
+            ),
+            Err(e) => fail(
+                SchemaError::new(
+                    key_path,
+                    format!("could not check '{}' for replays: {}", self.field, e),
+                )
+                .with_code("replay_check_failed"),
+            ),
+        }
+    }
+}
+
+fn fail(error: SchemaError) -> Validation<(), SchemaErrors> {
+    Validation::Failure(SchemaErrors::single(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn code(result: Validation<(), SchemaErrors>) -> String {
+        result.into_result().unwrap_err().first().code.clone()
+    }
+
+    #[test]
+    fn test_rejects_replays_within_window() {
+        let store = MemoryReplayStore::new();
+        let rule = idempotency_key("id", Arc::new(store.clone()), Duration::from_secs(60));
+        let root = JsonPath::root();
+
+        block_on(async {
+            assert!(rule.check(&json!({"id": "a"}), &root).await.is_success());
+            assert!(rule.check(&json!({"id": "b"}), &root).await.is_success());
+            assert_eq!(
+                code(rule.check(&json!({"id": "a"}), &root).await),
+                "duplicate_request"
+            );
+            assert_eq!(code(rule.check(&json!({}), &root).await), "required");
+            assert_eq!(
+                code(rule.check(&json!({"id": ""}), &root).await),
+                "invalid_type"
+            );
+
+            // Another rule sharing the store sees the same keys
+            let other = idempotency_key("id", Arc::new(store), Duration::ZERO);
+            assert!(other.check(&json!({"id": "a"}), &root).await.is_success());
+        });
+    }
+
+    #[test]
+    fn test_invalid_requests_do_not_use_key() {
+        let schema = Schema::object()
+            .field("id", Schema::string())
+            .field("amount", Schema::integer().positive());
+        let rule = idempotency_key(
+            "id",
+            Arc::new(MemoryReplayStore::new()),
+            Duration::from_secs(60),
+        );
+        let root = JsonPath::root();
+
+        block_on(async {
+            let invalid = json!({"id": "a", "amount": -1});
+            assert!(rule.validate(&schema, &invalid, &root).await.is_failure());
+            let fixed = json!({"id": "a", "amount": 1});
+            assert!(rule.validate(&schema, &fixed, &root).await.is_success());
+            let errors = rule
+                .validate(&schema, &fixed, &root)
+                .await
+                .into_result()
+                .unwrap_err();
+            assert_eq!(errors.first().path.to_string(), "id");
+            assert_eq!(
+                errors.first().message,
+                "request 'a' was already received in the last 60s"
+            );
+        });
+    }
+
+    #[test]
+    fn test_store_errors_fail_closed() {
+        struct Unavailable;
+
+        impl ReplayStore for Unavailable {
+            fn record<'a>(&'a self, _: &'a str, _: Duration) -> ReplayFuture<'a> {
+                Box::pin(async { Err(StoreError::Backend("connection refused".into())) })
+            }
+        }
+
+        let rule = idempotency_key("id", Arc::new(Unavailable), Duration::from_secs(60));
+        let result = block_on(rule.check(&json!({"id": "a"}), &JsonPath::root()));
+        assert_eq!(code(result), "replay_check_failed");
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides Effect-based schema loading that:
 //! - Loads JSON Schema files from a directory
 //! - Accumulates parsing and validation errors
+//! - Records the file and line of each rule as its [`Provenance`]
 //! - Integrates with environment abstraction
 //!
 //! # API Design Note
@@ -21,10 +22,16 @@
 //! stillwater 0.12's capabilities and provides a clean, ergonomic API.
 
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use crate::editor::locate_values;
+use crate::error::Provenance;
+use crate::metadata::SchemaMetadata;
+use crate::path::JsonPath;
 use crate::registry::{RegistryError, SchemaRegistry};
-use crate::schema::Schema;
+use crate::schema::{PatternLimits, Schema, StringSchema, ValueValidator};
 
 /// Environment trait for schema operations.
 ///
@@ -55,6 +62,7 @@ pub trait FileSystem: Send + Sync {
 
 /// Errors that can occur during schema loading.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum SchemaLoadError {
     /// IO error reading a file
     #[error("IO error reading {0}: {1}")]
@@ -76,6 +84,10 @@ pub enum SchemaLoadError {
     #[error("Registry error: {0}")]
     Registry(RegistryError),
 
+    /// Error in a [`RegistryStore`](super::RegistryStore)
+    #[error("Store error: {0}")]
+    Store(super::StoreError),
+
     /// Multiple errors occurred
     #[error("Multiple errors: {0:?}")]
     Multiple(Vec<SchemaLoadError>),
@@ -88,6 +100,11 @@ impl SchemaRegistry {
     /// - Reads all `.json` files from the specified directory
     /// - Parses each file as JSON Schema
     /// - Registers each schema using the filename (without extension) as the name
+    /// - Records where each rule was defined, so errors read e.g.
+    ///   `name: length must be at least 1 (rule defined in schemas/name.json:3)`
+    /// - Compiles patterns within [`PatternLimits::untrusted`], so a
+    ///   pathological pattern is reported as a schema error instead of
+    ///   slowing down validation
     /// - Accumulates all errors that occur
     ///
     /// # Example
@@ -146,65 +163,295 @@ impl SchemaRegistry {
             .ok_or_else(|| SchemaLoadError::InvalidFileName(path.to_path_buf()))?;
 
         // Parse the JSON Schema and register it with appropriate type
-        parse_and_register_schema(self, name, &json, path)?;
+        parse_and_register_schema(self, name, &json, &content, path)?;
         Ok(())
     }
+
+    /// Loads a bundle of JSON Schemas from a single document.
+    ///
+    /// The bundle is a JSON object mapping schema names to schemas, e.g.
+    /// `{"username": {"type": "string", "minLength": 3}, "age": {"type":
+    /// "integer"}}`. Each schema is registered under its name, as if it
+    /// had been loaded from its own file by
+    /// [`load_dir_with_env`](Self::load_dir_with_env); `path` names the
+    /// bundle in errors and provenance. Errors are accumulated, and the
+    /// valid schemas are registered even if others fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::SchemaRegistry;
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry
+    ///     .load_bundle("schemas.json", r#"{"username": {"type": "string", "minLength": 3}}"#)
+    ///     .unwrap();
+    ///
+    /// let errors = registry
+    ///     .validate("username", &json!("ab"))
+    ///     .unwrap()
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert!(errors.first().to_string().ends_with("(rule defined in schemas.json:1)"));
+    /// ```
+    pub fn load_bundle(
+        &self,
+        path: impl AsRef<Path>,
+        content: &str,
+    ) -> Result<(), SchemaLoadError> {
+        let path = path.as_ref();
+        let mut errors = Vec::new();
+        for (name, definition) in parse_bundle(path, content)? {
+            if let Err(e) = definition.and_then(|d| d.register(self, &name, path)) {
+                errors.push(e);
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaLoadError::Multiple(errors)),
+        }
+    }
+
+    /// Loads a bundle of JSON Schemas atomically: either every schema is
+    /// registered, or none are.
+    ///
+    /// Like [`load_bundle`](Self::load_bundle), but the schemas are
+    /// registered in one [`transaction`](Self::transaction), so a bundle
+    /// with one invalid schema, a taken name, or a dangling reference
+    /// leaves the registry unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::SchemaRegistry;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// let bundle = r#"{"username": {"type": "string"}, "flag": {"type": "boolean"}}"#;
+    /// assert!(registry.load_bundle_atomic("schemas.json", bundle).is_err());
+    /// assert!(registry.get("username").is_none());
+    /// ```
+    pub fn load_bundle_atomic(
+        &self,
+        path: impl AsRef<Path>,
+        content: &str,
+    ) -> Result<(), SchemaLoadError> {
+        let path = path.as_ref();
+        let mut schemas = Vec::new();
+        let mut errors = Vec::new();
+        for (name, definition) in parse_bundle(path, content)? {
+            match definition.and_then(|d| d.build(path)) {
+                Ok(schema) => schemas.push((name, schema)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        match errors.len() {
+            0 => self
+                .transaction(|tx| {
+                    for (name, schema) in schemas {
+                        tx.register_shared(name, schema, SchemaMetadata::new());
+                    }
+                })
+                .map_err(SchemaLoadError::Registry),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaLoadError::Multiple(errors)),
+        }
+    }
 }
 
-/// Helper function to parse and register a schema with the correct type.
-///
-/// This function handles the type dispatching to ensure we register
-/// the correct concrete schema type.
+/// A schema parsed from JSON Schema, with the provenance of its rules,
+/// ready to be registered.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Definition {
+    String {
+        defined_at: Provenance,
+        min_len: Option<(usize, Provenance)>,
+        max_len: Option<(usize, Provenance)>,
+        pattern: Option<(String, Provenance)>,
+    },
+    Integer {
+        defined_at: Provenance,
+    },
+    Object,
+    Array,
+}
+
+impl Definition {
+    /// Builds the schema and registers it as `name`.
+    ///
+    /// Patterns are compiled within [`PatternLimits::untrusted`]; `path`
+    /// names the source in errors.
+    pub(crate) fn register(
+        &self,
+        registry: &SchemaRegistry,
+        name: &str,
+        path: &Path,
+    ) -> Result<(), SchemaLoadError> {
+        match self {
+            Definition::String { .. } => registry.register(name, self.string_schema(path)?),
+            Definition::Integer { defined_at } => {
+                registry.register(name, Schema::integer().defined_at(defined_at.clone()))
+            }
+            Definition::Object => registry.register(name, Schema::object()),
+            Definition::Array => registry.register(name, Schema::array(Schema::object())),
+        }
+        .map_err(SchemaLoadError::Registry)
+    }
+
+    /// Builds the schema, ready to be registered.
+    fn build(&self, path: &Path) -> Result<Arc<dyn ValueValidator>, SchemaLoadError> {
+        Ok(match self {
+            Definition::String { .. } => Arc::new(self.string_schema(path)?),
+            Definition::Integer { defined_at } => {
+                Arc::new(Schema::integer().defined_at(defined_at.clone()))
+            }
+            Definition::Object => Arc::new(Schema::object()),
+            Definition::Array => Arc::new(Schema::array(Schema::object())),
+        })
+    }
+
+    /// Builds the schema of a `Definition::String`.
+    fn string_schema(&self, path: &Path) -> Result<StringSchema, SchemaLoadError> {
+        let Definition::String {
+            defined_at,
+            min_len,
+            max_len,
+            pattern,
+        } = self
+        else {
+            unreachable!("string_schema() is only called on string definitions")
+        };
+        let mut schema = Schema::string().defined_at(defined_at.clone());
+        if let Some((min_len, defined_at)) = min_len {
+            schema = schema.min_len(*min_len).defined_at(defined_at.clone());
+        }
+        if let Some((max_len, defined_at)) = max_len {
+            schema = schema.max_len(*max_len).defined_at(defined_at.clone());
+        }
+        if let Some((pattern, defined_at)) = pattern {
+            schema = schema
+                .pattern_with_limits(pattern, PatternLimits::untrusted())
+                .map_err(|e| SchemaLoadError::Schema(path.to_path_buf(), e.to_string()))?
+                .defined_at(defined_at.clone());
+        }
+        Ok(schema)
+    }
+}
+
+/// The definition of a schema in a bundle, or why it is invalid.
+pub(crate) type ParsedSchema = Result<Definition, SchemaLoadError>;
+
+/// Parses and registers a schema.
 fn parse_and_register_schema(
     registry: &SchemaRegistry,
     name: &str,
     json: &Value,
+    content: &str,
     path: &Path,
 ) -> Result<(), SchemaLoadError> {
-    let schema_type = json.get("type").and_then(|v| v.as_str()).ok_or_else(|| {
-        SchemaLoadError::Schema(path.to_path_buf(), "Missing 'type' field".to_string())
-    })?;
+    let source = Source::new(content);
+    parse_schema(json, &source, &JsonPath::root(), path)?.register(registry, name, path)
+}
 
-    match schema_type {
-        "string" => {
-            let mut schema = Schema::string();
+/// Parses a bundle into the definition, or the error, of each schema.
+///
+/// Fails if the bundle is not a JSON object.
+pub(crate) fn parse_bundle(
+    path: &Path,
+    content: &str,
+) -> Result<Vec<(String, ParsedSchema)>, SchemaLoadError> {
+    let json: Value =
+        serde_json::from_str(content).map_err(|e| SchemaLoadError::Parse(path.to_path_buf(), e))?;
+    let Value::Object(schemas) = json else {
+        return Err(SchemaLoadError::Schema(
+            path.to_path_buf(),
+            "A bundle must be an object mapping names to schemas".to_string(),
+        ));
+    };
+
+    let source = Source::new(content);
+    Ok(schemas
+        .iter()
+        .map(|(name, schema)| {
+            let at = JsonPath::root().push_field(name);
+            let definition = parse_schema(schema, &source, &at, path);
+            (name.clone(), definition)
+        })
+        .collect())
+}
 
-            if let Some(min_len) = json.get("minLength").and_then(|v| v.as_u64()) {
-                schema = schema.min_len(min_len as usize);
-            }
+/// The text of a JSON document and where each of its values is.
+pub(crate) struct Source<'a> {
+    content: &'a str,
+    spans: HashMap<JsonPath, (usize, usize)>,
+}
 
-            if let Some(max_len) = json.get("maxLength").and_then(|v| v.as_u64()) {
-                schema = schema.max_len(max_len as usize);
-            }
+impl<'a> Source<'a> {
+    /// Locates the values of a JSON document.
+    pub(crate) fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            spans: locate_values(content),
+        }
+    }
 
-            if let Some(pattern) = json.get("pattern").and_then(|v| v.as_str()) {
-                schema = schema
-                    .pattern(pattern)
-                    .map_err(|e| SchemaLoadError::Schema(path.to_path_buf(), e.to_string()))?;
+    /// Returns the 1-based line of the value at `path`, or of its nearest
+    /// located ancestor, or the first line.
+    fn line_of(&self, path: &JsonPath) -> usize {
+        let mut at = Some(path.clone());
+        while let Some(path) = at {
+            if let Some((start, _)) = self.spans.get(&path) {
+                return self.content[..*start].matches('\n').count() + 1;
             }
-
-            registry
-                .register(name, schema)
-                .map_err(SchemaLoadError::Registry)
-        }
-        "integer" => {
-            let schema = Schema::integer();
-            registry
-                .register(name, schema)
-                .map_err(SchemaLoadError::Registry)
-        }
-        "object" => {
-            let schema = Schema::object();
-            registry
-                .register(name, schema)
-                .map_err(SchemaLoadError::Registry)
-        }
-        "array" => {
-            let schema = Schema::array(Schema::object());
-            registry
-                .register(name, schema)
-                .map_err(SchemaLoadError::Registry)
+            at = path.parent();
         }
+        1
+    }
+}
+
+/// Parses the JSON Schema at `at` in `source`, recording the line each rule
+/// was defined on.
+pub(crate) fn parse_schema(
+    json: &Value,
+    source: &Source<'_>,
+    at: &JsonPath,
+    path: &Path,
+) -> Result<Definition, SchemaLoadError> {
+    let defined_at = |key: &str| {
+        Provenance::new(
+            path.display().to_string(),
+            source.line_of(&at.push_field(key)),
+        )
+    };
+
+    let schema_type = json.get("type").and_then(|v| v.as_str()).ok_or_else(|| {
+        SchemaLoadError::Schema(path.to_path_buf(), "Missing 'type' field".to_string())
+    })?;
+
+    match schema_type {
+        "string" => Ok(Definition::String {
+            defined_at: defined_at("type"),
+            min_len: json
+                .get("minLength")
+                .and_then(|v| v.as_u64())
+                .map(|min_len| (min_len as usize, defined_at("minLength"))),
+            max_len: json
+                .get("maxLength")
+                .and_then(|v| v.as_u64())
+                .map(|max_len| (max_len as usize, defined_at("maxLength"))),
+            pattern: json
+                .get("pattern")
+                .and_then(|v| v.as_str())
+                .map(|pattern| (pattern.to_string(), defined_at("pattern"))),
+        }),
+        "integer" => Ok(Definition::Integer {
+            defined_at: defined_at("type"),
+        }),
+        "object" => Ok(Definition::Object),
+        "array" => Ok(Definition::Array),
         _ => Err(SchemaLoadError::Schema(
             path.to_path_buf(),
             format!("Unsupported schema type: {}", schema_type),
@@ -309,6 +556,138 @@ mod tests {
         assert!(registry.get("age").is_some());
     }
 
+    #[test]
+    fn test_loaded_rules_record_provenance() {
+        let mut fs = MockFileSystem::new();
+        fs.add_file(
+            "schemas/username.json",
+            r#"{
+                "type": "string",
+                "examples": ["minLength"],
+                "minLength": 3,
+                "maxLength": 20
+            }"#,
+        );
+
+        let env = TestEnv { fs };
+        let registry = SchemaRegistry::new();
+        registry.load_dir_with_env("schemas", &env).unwrap();
+
+        let errors = registry
+            .validate("username", &serde_json::json!("ab"))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(
+            errors.first().provenance,
+            Some(Provenance::new("schemas/username.json", 4))
+        );
+        assert!(errors
+            .first()
+            .to_string()
+            .ends_with("(rule defined in schemas/username.json:4)"));
+
+        let errors = registry
+            .validate("username", &serde_json::json!(42))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(
+            errors.first().provenance,
+            Some(Provenance::new("schemas/username.json", 2))
+        );
+    }
+
+    #[test]
+    fn test_load_bundle() {
+        let registry = SchemaRegistry::new();
+        let result = registry.load_bundle(
+            "bundle.json",
+            r#"{
+                "username": {"type": "string", "minLength": 3},
+                "nickname": {
+                    "type": "string",
+                    "minLength": 2
+                },
+                "broken": {"minLength": 1}
+            }"#,
+        );
+        assert!(matches!(result, Err(SchemaLoadError::Schema(_, _))));
+        assert!(registry.get("broken").is_none());
+
+        let errors = registry
+            .validate("nickname", &serde_json::json!("a"))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(
+            errors.first().provenance,
+            Some(Provenance::new("bundle.json", 5))
+        );
+
+        assert!(matches!(
+            registry.load_bundle("bundle.json", "[]"),
+            Err(SchemaLoadError::Schema(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_bundle_provenance_ignores_nested_keys() {
+        let registry = SchemaRegistry::new();
+        registry
+            .load_bundle(
+                "bundle.json",
+                r#"{
+                "form": {"type": "object", "properties": {"nickname": {"minLength": 9}}},
+                "nickname": {
+                    "type": "string",
+                    "minLength": 2
+                }
+            }"#,
+            )
+            .unwrap();
+
+        let errors = registry
+            .validate("nickname", &serde_json::json!("a"))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(
+            errors.first().provenance,
+            Some(Provenance::new("bundle.json", 5))
+        );
+    }
+
+    #[test]
+    fn test_load_bundle_atomic() {
+        let registry = SchemaRegistry::new();
+        registry.register("taken", Schema::string()).unwrap();
+        let result = registry.load_bundle_atomic(
+            "bundle.json",
+            r#"{"username": {"type": "string"}, "taken": {"type": "integer"}}"#,
+        );
+        assert!(matches!(
+            result,
+            Err(SchemaLoadError::Registry(RegistryError::DuplicateName(name))) if name == "taken"
+        ));
+        assert!(registry.get("username").is_none());
+
+        let result = registry.load_bundle_atomic(
+            "bundle.json",
+            r#"{"username": {"type": "string", "pattern": "("}}"#,
+        );
+        assert!(matches!(result, Err(SchemaLoadError::Schema(_, _))));
+        assert_eq!(registry.names(), ["taken"]);
+
+        registry
+            .load_bundle_atomic(
+                "bundle.json",
+                r#"{"username": {"type": "string"}, "age": {"type": "integer"}}"#,
+            )
+            .unwrap();
+        assert_eq!(registry.names(), ["age", "taken", "username"]);
+    }
+
     #[test]
     fn test_parse_error_accumulation() {
         let mut fs = MockFileSystem::new();
@@ -324,4 +703,28 @@ mod tests {
         // Valid schema should still be registered
         assert!(registry.get("valid").is_some());
     }
+
+    #[test]
+    fn test_loaded_patterns_are_limited() {
+        let mut fs = MockFileSystem::new();
+        fs.add_file("slug.json", r#"{"type": "string", "pattern": "^[a-z-]+$"}"#);
+        fs.add_file(
+            "huge.json",
+            r#"{"type": "string", "pattern": "((\\w{1,100}){1,100}){1,100}"}"#,
+        );
+
+        let env = TestEnv { fs };
+        let registry = SchemaRegistry::new();
+
+        let result = registry.load_dir_with_env(".", &env);
+        assert!(matches!(result, Err(SchemaLoadError::Multiple(ref errors)) if errors.len() == 1));
+        assert!(registry.get("huge").is_none());
+
+        let errors = registry
+            .validate("slug", &serde_json::json!("a".repeat(100_000)))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().code, "pattern_timeout");
+    }
 }
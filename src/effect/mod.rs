@@ -2,6 +2,9 @@
 //!
 //! This module provides Effect-based integration with stillwater for:
 //! - Schema loading from filesystem
+//! - Caching compiled schema bundles on disk
+//! - Persisting dynamically created schemas in a store
+//! - Rejecting replayed requests by their idempotency key
 //! - Async validation with dependency injection
 //! - Environment-based configuration
 //!
@@ -33,7 +36,19 @@
 //! ```
 
 pub mod async_validator;
+pub mod compiled;
+pub mod idempotency;
 pub mod loading;
+pub mod store;
 
 pub use async_validator::{AsyncStringSchema, AsyncValidator};
+pub use compiled::CompiledSchema;
+pub use idempotency::{
+    idempotency_key, IdempotencyKey, MemoryReplayStore, ReplayFuture, ReplayStore,
+};
 pub use loading::{FileSystem, SchemaEnv, SchemaLoadError};
+#[cfg(feature = "sled")]
+pub use store::SledStore;
+#[cfg(feature = "sqlite")]
+pub use store::SqliteStore;
+pub use store::{FileStore, MemoryStore, RegistryStore, StoreError, StoreListener};
@@ -0,0 +1,146 @@
+//! A store that keeps each schema in its own file.
+
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{Listeners, RegistryStore, StoreError, StoreListener};
+
+/// A [`RegistryStore`] that keeps each schema in a `<name>.json` file in a
+/// directory, the layout [`SchemaRegistry::load_dir_with_env`] reads.
+///
+/// Files are pretty-printed and written atomically, so a crash never
+/// leaves a partial schema behind. The directory is created on the first
+/// save. Names that are empty, start with `.`, or contain a path
+/// separator are rejected.
+///
+/// [`watch`](RegistryStore::watch) sees saves through this store and its
+/// clones, but not files written by other processes.
+///
+/// [`SchemaRegistry::load_dir_with_env`]: crate::SchemaRegistry::load_dir_with_env
+#[derive(Clone)]
+pub struct FileStore {
+    dir: PathBuf,
+    listeners: Listeners,
+}
+
+impl FileStore {
+    /// Creates a store over the directory `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            listeners: Listeners::default(),
+        }
+    }
+
+    /// Returns the store's directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn file(&self, name: &str) -> Result<PathBuf, StoreError> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\', '\0']) {
+            return Err(StoreError::InvalidName(name.to_string()));
+        }
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+impl RegistryStore for FileStore {
+    fn load(&self, name: &str) -> Result<Option<Value>, StoreError> {
+        let text = match fs::read_to_string(self.file(name)?) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| StoreError::Corrupt(name.to_string(), e))
+    }
+
+    fn save(&self, name: &str, schema: &Value) -> Result<(), StoreError> {
+        let file = self.file(name)?;
+        fs::create_dir_all(&self.dir)?;
+        let partial = self
+            .dir
+            .join(format!(".{}.{}.tmp", name, std::process::id()));
+        let text = serde_json::to_string_pretty(schema)
+            .map_err(|e| StoreError::Corrupt(name.to_string(), e))?;
+        fs::write(&partial, text)?;
+        fs::rename(&partial, &file)?;
+        self.listeners.notify(name, schema);
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StoreError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                if !name.starts_with('.') {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn watch(&self, listener: StoreListener) -> Result<(), StoreError> {
+        self.listeners.add(listener);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check_store, check_watch};
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("postmortem-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_file_store() {
+        let dir = temp_dir("file-store");
+        let store = FileStore::new(&dir);
+        check_store(&store);
+        check_watch(&store);
+        assert!(dir.join("username.json").exists());
+
+        // Other files in the directory are not schemas
+        fs::write(dir.join("README.md"), "schemas").unwrap();
+        assert_eq!(store.list().unwrap(), ["age", "username", "zip"]);
+
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        assert!(matches!(
+            store.load("broken"),
+            Err(StoreError::Corrupt(name, _)) if name == "broken"
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_rejects_paths() {
+        let store = FileStore::new(temp_dir("file-store-names"));
+        for name in ["", "../etc/passwd", ".hidden", "a\\b"] {
+            assert!(matches!(
+                store.save(name, &json!({"type": "string"})),
+                Err(StoreError::InvalidName(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! A store that keeps schemas in memory.
+
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::{Listeners, RegistryStore, StoreError, StoreListener};
+
+/// A [`RegistryStore`] that keeps schemas in memory, for tests and for
+/// sharing schemas between registries in one process.
+///
+/// Clones share the same schemas. [`watch`](RegistryStore::watch) sees
+/// saves through any clone.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    schemas: Arc<RwLock<BTreeMap<String, Value>>>,
+    listeners: Listeners,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RegistryStore for MemoryStore {
+    fn load(&self, name: &str) -> Result<Option<Value>, StoreError> {
+        Ok(self.schemas.read().get(name).cloned())
+    }
+
+    fn save(&self, name: &str, schema: &Value) -> Result<(), StoreError> {
+        self.schemas
+            .write()
+            .insert(name.to_string(), schema.clone());
+        self.listeners.notify(name, schema);
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StoreError> {
+        Ok(self.schemas.read().keys().cloned().collect())
+    }
+
+    fn watch(&self, listener: StoreListener) -> Result<(), StoreError> {
+        self.listeners.add(listener);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check_store, check_watch};
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::new();
+        check_store(&store);
+        check_watch(&store.clone());
+    }
+}
@@ -0,0 +1,317 @@
+//! Persistent storage for dynamically created schemas.
+//!
+//! Applications that create schemas at runtime, e.g. from an admin UI, need
+//! them back after a restart. A [`RegistryStore`] keeps each schema's JSON
+//! Schema document under its name; [`SchemaRegistry::persist`] registers a
+//! schema and saves it, and [`SchemaRegistry::load_store`] registers every
+//! saved schema on startup.
+//!
+//! Two stores are always available: [`MemoryStore`], for tests, and
+//! [`FileStore`], which keeps one `.json` file per schema in a directory.
+//! The `sled` and `sqlite` features add [`SledStore`] and [`SqliteStore`].
+//! Other backends implement the trait.
+//!
+//! # Example
+//!
+//! ```rust
+//! use postmortem::effect::FileStore;
+//! use postmortem::SchemaRegistry;
+//! use serde_json::json;
+//!
+//! let dir = std::env::temp_dir().join("postmortem-doc-store");
+//! let store = FileStore::new(&dir);
+//!
+//! let registry = SchemaRegistry::new();
+//! registry
+//!     .persist(&store, "username", &json!({"type": "string", "minLength": 3}))
+//!     .unwrap();
+//!
+//! // After a restart
+//! let registry = SchemaRegistry::new();
+//! registry.load_store(&store).unwrap();
+//! assert!(registry.validate("username", &json!("ab")).unwrap().is_failure());
+//! # std::fs::remove_dir_all(&dir).ok();
+//! ```
+
+mod file;
+mod memory;
+#[cfg(feature = "sled")]
+mod sled_store;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+
+pub use file::FileStore;
+pub use memory::MemoryStore;
+#[cfg(feature = "sled")]
+pub use sled_store::SledStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite_store::SqliteStore;
+
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::loading::{parse_schema, SchemaLoadError, Source};
+use crate::path::JsonPath;
+use crate::registry::SchemaRegistry;
+
+/// A function called with the name and document of each schema saved to a
+/// store.
+pub type StoreListener = Box<dyn Fn(&str, &Value) + Send + Sync>;
+
+/// Storage for JSON Schema documents, by name.
+///
+/// Implementations must be safe to share between threads. Names are
+/// arbitrary strings; a store that cannot hold a name returns
+/// [`StoreError::InvalidName`].
+pub trait RegistryStore: Send + Sync {
+    /// Returns the schema saved as `name`, or `None` if there is none.
+    fn load(&self, name: &str) -> Result<Option<Value>, StoreError>;
+
+    /// Saves `schema` as `name`, replacing any schema saved before.
+    fn save(&self, name: &str, schema: &Value) -> Result<(), StoreError>;
+
+    /// Returns the names of the saved schemas, in sorted order.
+    fn list(&self) -> Result<Vec<String>, StoreError>;
+
+    /// Calls `listener` with each schema saved from now on.
+    ///
+    /// Which saves a store sees is up to the store; see each
+    /// implementation. The default returns [`StoreError::WatchUnsupported`].
+    fn watch(&self, listener: StoreListener) -> Result<(), StoreError> {
+        let _ = listener;
+        Err(StoreError::WatchUnsupported)
+    }
+}
+
+/// Errors that can occur in a [`RegistryStore`] or a
+/// [`ReplayStore`](super::ReplayStore).
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum StoreError {
+    /// The store cannot hold a schema with this name.
+    #[error("invalid schema name '{0}'")]
+    InvalidName(String),
+
+    /// A saved schema is not valid JSON.
+    #[error("schema '{0}' is corrupt: {1}")]
+    Corrupt(String, serde_json::Error),
+
+    /// The store does not support [`RegistryStore::watch`].
+    #[error("this store cannot be watched")]
+    WatchUnsupported,
+
+    /// IO error in a file store
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error in the storage backend
+    #[error("store error: {0}")]
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The listeners of a store that notifies them of its own saves.
+#[derive(Clone, Default)]
+struct Listeners(Arc<RwLock<Vec<StoreListener>>>);
+
+impl Listeners {
+    fn add(&self, listener: StoreListener) {
+        self.0.write().push(listener);
+    }
+
+    fn notify(&self, name: &str, schema: &Value) {
+        for listener in self.0.read().iter() {
+            listener(name, schema);
+        }
+    }
+}
+
+impl SchemaRegistry {
+    /// Registers a JSON Schema document as `name` and saves it to `store`.
+    ///
+    /// The schema is saved only once it has been registered, so a store
+    /// never holds a schema that would fail to load.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaLoadError::Schema` if the document is not a supported
+    /// JSON Schema, `SchemaLoadError::Registry` if it cannot be registered,
+    /// and `SchemaLoadError::Store` if it cannot be saved.
+    pub fn persist(
+        &self,
+        store: &dyn RegistryStore,
+        name: &str,
+        schema: &Value,
+    ) -> Result<(), SchemaLoadError> {
+        register_document(self, name, schema)?;
+        store.save(name, schema).map_err(SchemaLoadError::Store)
+    }
+
+    /// Registers every schema saved in `store`.
+    ///
+    /// Errors are accumulated, and the valid schemas are registered even if
+    /// others fail, as with [`load_bundle`](Self::load_bundle).
+    pub fn load_store(&self, store: &dyn RegistryStore) -> Result<(), SchemaLoadError> {
+        let mut errors = Vec::new();
+        for name in store.list().map_err(SchemaLoadError::Store)? {
+            let loaded = match store.load(&name) {
+                Ok(Some(schema)) => register_document(self, &name, &schema),
+                // Removed since it was listed
+                Ok(None) => Ok(()),
+                Err(e) => Err(SchemaLoadError::Store(e)),
+            };
+            if let Err(e) = loaded {
+                errors.push(e);
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaLoadError::Multiple(errors)),
+        }
+    }
+
+    /// Registers each schema saved to `store` from now on, e.g. by another
+    /// registry sharing the store.
+    ///
+    /// A schema whose name is already registered is ignored, since a
+    /// registry never replaces a schema, and so is one that cannot be
+    /// registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if it cannot be watched.
+    pub fn watch_store(&self, store: &dyn RegistryStore) -> Result<(), StoreError> {
+        let registry = self.clone();
+        store.watch(Box::new(move |name, schema| {
+            if registry.get(name).is_none() {
+                let _ = register_document(&registry, name, schema);
+            }
+        }))
+    }
+}
+
+/// Parses and registers a stored JSON Schema document.
+///
+/// Rules are located in the document's pretty-printed form, which is how
+/// [`FileStore`] writes it.
+fn register_document(
+    registry: &SchemaRegistry,
+    name: &str,
+    schema: &Value,
+) -> Result<(), SchemaLoadError> {
+    let path = Path::new(name);
+    let content = serde_json::to_string_pretty(schema)
+        .map_err(|e| SchemaLoadError::Parse(path.to_path_buf(), e))?;
+    let source = Source::new(&content);
+    parse_schema(schema, &source, &JsonPath::root(), path)?.register(registry, name, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Checks the behavior every store shares.
+    pub(super) fn check_store(store: &dyn RegistryStore) {
+        assert_eq!(store.list().unwrap(), Vec::<String>::new());
+        assert!(store.load("age").unwrap().is_none());
+
+        store.save("username", &json!({"type": "string"})).unwrap();
+        store.save("age", &json!({"type": "integer"})).unwrap();
+        store
+            .save("username", &json!({"type": "string", "minLength": 3}))
+            .unwrap();
+        assert_eq!(store.list().unwrap(), ["age", "username"]);
+        assert_eq!(
+            store.load("username").unwrap(),
+            Some(json!({"type": "string", "minLength": 3}))
+        );
+    }
+
+    /// Checks that a store notifies its listeners of saves.
+    pub(super) fn check_watch(store: &dyn RegistryStore) {
+        let (sender, saved) = mpsc::channel();
+        let sender = parking_lot::Mutex::new(sender);
+        store
+            .watch(Box::new(move |name, schema| {
+                let _ = sender.lock().send((name.to_string(), schema.clone()));
+            }))
+            .unwrap();
+        store.save("zip", &json!({"type": "string"})).unwrap();
+        assert_eq!(
+            saved.recv_timeout(Duration::from_secs(5)).unwrap(),
+            ("zip".to_string(), json!({"type": "string"}))
+        );
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        let store = MemoryStore::new();
+        let registry = SchemaRegistry::new();
+        registry
+            .persist(
+                &store,
+                "username",
+                &json!({"type": "string", "minLength": 3}),
+            )
+            .unwrap();
+        assert!(registry
+            .validate("username", &json!("ab"))
+            .unwrap()
+            .is_failure());
+
+        // Unsupported schemas are neither registered nor saved
+        assert!(matches!(
+            registry.persist(&store, "flag", &json!({"type": "boolean"})),
+            Err(SchemaLoadError::Schema(_, _))
+        ));
+        assert_eq!(store.list().unwrap(), ["username"]);
+
+        let restarted = SchemaRegistry::new();
+        restarted.load_store(&store).unwrap();
+        assert!(restarted
+            .validate("username", &json!("ab"))
+            .unwrap()
+            .is_failure());
+        assert!(restarted
+            .validate("username", &json!("abc"))
+            .unwrap()
+            .is_success());
+    }
+
+    #[test]
+    fn test_load_store_accumulates_errors() {
+        let store = MemoryStore::new();
+        store.save("a", &json!({"type": "boolean"})).unwrap();
+        store.save("b", &json!({})).unwrap();
+        store.save("c", &json!({"type": "integer"})).unwrap();
+
+        let registry = SchemaRegistry::new();
+        match registry.load_store(&store) {
+            Err(SchemaLoadError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected two errors, got {:?}", other),
+        }
+        assert!(registry.get("c").is_some());
+    }
+
+    #[test]
+    fn test_watch_store() {
+        let store = MemoryStore::new();
+        let follower = SchemaRegistry::new();
+        follower.watch_store(&store).unwrap();
+
+        SchemaRegistry::new()
+            .persist(&store, "age", &json!({"type": "integer"}))
+            .unwrap();
+        assert!(follower
+            .validate("age", &json!("old"))
+            .unwrap()
+            .is_failure());
+    }
+}
@@ -0,0 +1,100 @@
+//! A store backed by a sled tree.
+
+use serde_json::Value;
+use std::path::Path;
+use std::thread;
+
+use super::{RegistryStore, StoreError, StoreListener};
+
+/// A [`RegistryStore`] that keeps schemas in a [`sled`] tree, keyed by
+/// name.
+///
+/// [`watch`](RegistryStore::watch) sees every save to the tree, including
+/// saves through other handles to the same database. Listeners are called
+/// on a background thread.
+#[derive(Clone)]
+pub struct SledStore {
+    tree: sled::Tree,
+}
+
+impl SledStore {
+    /// Opens the sled database at `path`, creating it if needed, and
+    /// stores schemas in its default tree.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let db = sled::open(path).map_err(backend)?;
+        Ok(Self::new((*db).clone()))
+    }
+
+    /// Creates a store over `tree`, which should hold nothing but schemas.
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+}
+
+fn backend(error: sled::Error) -> StoreError {
+    StoreError::Backend(Box::new(error))
+}
+
+fn parse(name: &str, bytes: &[u8]) -> Result<Value, StoreError> {
+    serde_json::from_slice(bytes).map_err(|e| StoreError::Corrupt(name.to_string(), e))
+}
+
+impl RegistryStore for SledStore {
+    fn load(&self, name: &str) -> Result<Option<Value>, StoreError> {
+        self.tree
+            .get(name)
+            .map_err(backend)?
+            .map(|bytes| parse(name, &bytes))
+            .transpose()
+    }
+
+    fn save(&self, name: &str, schema: &Value) -> Result<(), StoreError> {
+        let bytes =
+            serde_json::to_vec(schema).map_err(|e| StoreError::Corrupt(name.to_string(), e))?;
+        self.tree.insert(name, bytes).map_err(backend)?;
+        self.tree.flush().map_err(backend)?;
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StoreError> {
+        self.tree
+            .iter()
+            .keys()
+            .map(|key| {
+                let key = key.map_err(backend)?;
+                String::from_utf8(key.to_vec()).map_err(|e| {
+                    StoreError::InvalidName(String::from_utf8_lossy(e.as_bytes()).into())
+                })
+            })
+            .collect()
+    }
+
+    fn watch(&self, listener: StoreListener) -> Result<(), StoreError> {
+        let subscriber = self.tree.watch_prefix(Vec::new());
+        thread::spawn(move || {
+            for event in subscriber {
+                if let sled::Event::Insert { key, value } = event {
+                    let name = String::from_utf8_lossy(&key);
+                    if let Ok(schema) = parse(&name, &value) {
+                        listener(&name, &schema);
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check_store, check_watch};
+    use super::*;
+
+    #[test]
+    fn test_sled_store() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = SledStore::new(db.open_tree("schemas").unwrap());
+        check_store(&store);
+        check_watch(&store);
+    }
+}
@@ -0,0 +1,118 @@
+//! A store backed by a SQLite table.
+
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{Listeners, RegistryStore, StoreError, StoreListener};
+
+/// The table schemas are stored in.
+const TABLE: &str = "postmortem_schemas";
+
+/// A [`RegistryStore`] that keeps schemas in a `postmortem_schemas` table
+/// of a SQLite database, created if it does not exist.
+///
+/// [`watch`](RegistryStore::watch) sees saves through this store and its
+/// clones, but not rows written by other connections.
+#[derive(Clone)]
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+    listeners: Listeners,
+}
+
+impl SqliteStore {
+    /// Opens the SQLite database at `path`, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::new(Connection::open(path).map_err(backend)?)
+    }
+
+    /// Creates a store over an open connection, creating the table if it
+    /// does not exist.
+    pub fn new(connection: Connection) -> Result<Self, StoreError> {
+        connection
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} (name TEXT PRIMARY KEY, schema TEXT NOT NULL)",
+                    TABLE
+                ),
+                [],
+            )
+            .map_err(backend)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            listeners: Listeners::default(),
+        })
+    }
+}
+
+fn backend(error: rusqlite::Error) -> StoreError {
+    StoreError::Backend(Box::new(error))
+}
+
+impl RegistryStore for SqliteStore {
+    fn load(&self, name: &str) -> Result<Option<Value>, StoreError> {
+        let text: Option<String> = self
+            .connection
+            .lock()
+            .query_row(
+                &format!("SELECT schema FROM {} WHERE name = ?1", TABLE),
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(backend)?;
+        text.map(|text| {
+            serde_json::from_str(&text).map_err(|e| StoreError::Corrupt(name.to_string(), e))
+        })
+        .transpose()
+    }
+
+    fn save(&self, name: &str, schema: &Value) -> Result<(), StoreError> {
+        self.connection
+            .lock()
+            .execute(
+                &format!(
+                    "INSERT INTO {} (name, schema) VALUES (?1, ?2) \
+                     ON CONFLICT(name) DO UPDATE SET schema = excluded.schema",
+                    TABLE
+                ),
+                params![name, schema.to_string()],
+            )
+            .map_err(backend)?;
+        self.listeners.notify(name, schema);
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StoreError> {
+        let connection = self.connection.lock();
+        let mut statement = connection
+            .prepare(&format!("SELECT name FROM {} ORDER BY name", TABLE))
+            .map_err(backend)?;
+        let names = statement
+            .query_map([], |row| row.get(0))
+            .map_err(backend)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(backend)?;
+        Ok(names)
+    }
+
+    fn watch(&self, listener: StoreListener) -> Result<(), StoreError> {
+        self.listeners.add(listener);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{check_store, check_watch};
+    use super::*;
+
+    #[test]
+    fn test_sqlite_store() {
+        let store = SqliteStore::new(Connection::open_in_memory().unwrap()).unwrap();
+        check_store(&store);
+        check_watch(&store);
+    }
+}
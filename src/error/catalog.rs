@@ -0,0 +1,281 @@
+//! Translated messages for the built-in error codes.
+//!
+//! The built-in messages are English. A [`MessageCatalog`] maps error codes
+//! to message templates in another language; installed as an
+//! [error factory](super::ErrorFactory) with [`MessageCatalog::factory`],
+//! it rewrites the message of every error whose code it knows and leaves
+//! the others alone.
+//!
+//! Catalog messages can use the placeholders every message has, `{field}`,
+//! `{path}`, `{got}`, and `{expected}`, with plural and select forms chosen
+//! by the catalog's own locale. In right-to-left locales, each placeholder
+//! value is wrapped in Unicode directional isolates, so a left-to-right
+//! path like `user.email` cannot reorder the text around it.
+//!
+//! Catalogs for German, French, Japanese, and Chinese are bundled behind
+//! the `catalog-de`, `catalog-fr`, `catalog-ja`, and `catalog-zh` features;
+//! see [`MessageCatalog::bundled`]. Use
+//! [`assert_catalog_renders`](crate::testing::assert_catalog_renders) to
+//! check a catalog of your own.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::plural::PluralRules;
+use super::template::render_message_in;
+use super::{ConstraintInfo, SchemaError, SchemaErrors};
+use crate::path::{JsonPath, PathSegment};
+
+/// Starts a first-strong isolate (U+2068).
+pub(crate) const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+/// Ends the innermost isolate (U+2069).
+pub(crate) const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// Catalogs compiled in by the `catalog-*` features, by language.
+const BUNDLED: &[(&str, &str)] = &[
+    #[cfg(feature = "catalog-de")]
+    ("de", include_str!("../../catalogs/de.json")),
+    #[cfg(feature = "catalog-fr")]
+    ("fr", include_str!("../../catalogs/fr.json")),
+    #[cfg(feature = "catalog-ja")]
+    ("ja", include_str!("../../catalogs/ja.json")),
+    #[cfg(feature = "catalog-zh")]
+    ("zh", include_str!("../../catalogs/zh.json")),
+];
+
+/// Message templates for error codes, in one language.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{JsonPath, MessageCatalog, Schema};
+/// use serde_json::json;
+///
+/// let catalog = MessageCatalog::new("es")
+///     .message("required", "el campo «{field}» es obligatorio")
+///     .message("min_length", "{field} es demasiado corto");
+///
+/// let schema = Schema::object()
+///     .field("name", Schema::string().min_len(2))
+///     .field("email", Schema::string())
+///     .with_error_factory(catalog.factory());
+///
+/// let errors = schema
+///     .validate(&json!({"name": "A"}), &JsonPath::root())
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.first().message, "name es demasiado corto");
+/// assert_eq!(errors.iter().nth(1).unwrap().message, "el campo «email» es obligatorio");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageCatalog {
+    locale: String,
+    messages: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog for a BCP 47 language tag such as `de` or
+    /// `zh-Hans`.
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            messages: BTreeMap::new(),
+        }
+    }
+
+    /// Parses a catalog from a JSON object mapping error codes to message
+    /// templates, the format of the bundled catalogs.
+    pub fn from_json(locale: impl Into<String>, json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            locale: locale.into(),
+            messages: serde_json::from_str(json)?,
+        })
+    }
+
+    /// Returns the bundled catalog for a language tag, if its feature is
+    /// enabled.
+    ///
+    /// Only the language subtag is matched, so `de-AT` finds the `de`
+    /// catalog, whose locale is then `de-AT`.
+    pub fn bundled(locale: &str) -> Option<Self> {
+        let language = locale.split(['-', '_']).next()?;
+        BUNDLED
+            .iter()
+            .find(|(bundled, _)| bundled.eq_ignore_ascii_case(language))
+            .map(|(_, json)| {
+                Self::from_json(locale, json).expect("bundled catalogs are valid JSON")
+            })
+    }
+
+    /// Sets the message template for an error code.
+    pub fn message(mut self, code: impl Into<String>, template: impl Into<String>) -> Self {
+        self.messages.insert(code.into(), template.into());
+        self
+    }
+
+    /// Returns the catalog's language tag.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Returns the template for an error code.
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.messages.get(code).map(String::as_str)
+    }
+
+    /// Returns the error codes the catalog translates, in sorted order.
+    pub fn codes(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// Returns whether the catalog's language is written right to left.
+    pub fn is_rtl(&self) -> bool {
+        let language = self.locale.split(['-', '_']).next().unwrap_or("");
+        [
+            "ar", "he", "iw", "fa", "ur", "yi", "ps", "sd", "ug", "dv", "ckb",
+        ]
+        .iter()
+        .any(|rtl| rtl.eq_ignore_ascii_case(language))
+    }
+
+    /// Returns `error` with its message translated, or unchanged if the
+    /// catalog has no template for its code.
+    pub fn translate(&self, error: SchemaError) -> SchemaError {
+        self.render(error).0
+    }
+
+    /// Translates `error`, also returning whether every placeholder of its
+    /// template was rendered.
+    pub(crate) fn render(&self, error: SchemaError) -> (SchemaError, bool) {
+        let Some(template) = self.get(&error.code) else {
+            return (error, true);
+        };
+        let rtl = self.is_rtl();
+        let isolate = |value: String| {
+            if rtl {
+                format!(
+                    "{}{}{}",
+                    FIRST_STRONG_ISOLATE, value, POP_DIRECTIONAL_ISOLATE
+                )
+            } else {
+                value
+            }
+        };
+        let field = match error.path.last() {
+            Some(PathSegment::Field(name)) => name.clone(),
+            Some(PathSegment::Index(index)) => index.to_string(),
+            None => "value".to_string(),
+        };
+        let path = if error.path.is_root() {
+            "(root)".to_string()
+        } else {
+            error.path.to_string()
+        };
+        let mut params = vec![("field", isolate(field)), ("path", isolate(path))];
+        params.extend(error.got.clone().map(|got| ("got", isolate(got))));
+        params.extend(
+            error
+                .expected
+                .clone()
+                .map(|expected| ("expected", isolate(expected))),
+        );
+        let error = SchemaError {
+            message: template.to_string(),
+            ..error
+        };
+        render_message_in(error, &params, PluralRules::for_locale(&self.locale))
+    }
+
+    /// Translates every error in `errors`.
+    pub fn translate_all(&self, errors: SchemaErrors) -> SchemaErrors {
+        SchemaErrors::from_vec(errors.into_iter().map(|e| self.translate(e)).collect())
+    }
+
+    /// Returns an error factory that translates each error, for
+    /// [`ObjectSchema::with_error_factory`](crate::ObjectSchema::with_error_factory)
+    /// or [`SchemaRegistry::with_error_factory`](crate::SchemaRegistry::with_error_factory).
+    pub fn factory(
+        &self,
+    ) -> impl Fn(ConstraintInfo<'_>, &Value, &JsonPath) -> SchemaError + Send + Sync + 'static {
+        let catalog = self.clone();
+        move |info, _, path| {
+            let mut error = SchemaError::new(path.clone(), info.message).with_code(info.code);
+            error.expected = info.expected.map(str::to_string);
+            error.got = info.got.map(str::to_string);
+            catalog.translate(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        let catalog = MessageCatalog::new("fr").message(
+            "max_length",
+            "{field} : {got, plural, one {# caractère} other {# caractères}} de trop",
+        );
+        let error = SchemaError::new(JsonPath::root().push_field("nom"), "too long")
+            .with_code("max_length")
+            .with_got("1");
+        // French rules put 1 in `one`, whatever the message locale
+        assert_eq!(
+            catalog.translate(error).message,
+            "nom : 1 caractère de trop"
+        );
+
+        let untranslated =
+            SchemaError::new(JsonPath::root(), "must be positive").with_code("positive");
+        assert_eq!(catalog.translate(untranslated.clone()), untranslated);
+    }
+
+    #[test]
+    fn test_rtl_values_are_isolated() {
+        let catalog = MessageCatalog::new("ar-EG").message("required", "الحقل {path} مطلوب");
+        assert!(catalog.is_rtl());
+        let error = SchemaError::new(
+            JsonPath::root().push_field("user").push_field("email"),
+            "required field 'email' is missing",
+        )
+        .with_code("required");
+        assert_eq!(
+            catalog.translate(error).message,
+            "الحقل \u{2068}user.email\u{2069} مطلوب"
+        );
+        assert!(!MessageCatalog::new("zh-Hant").is_rtl());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let catalog = MessageCatalog::from_json("de", r#"{"required": "{field} fehlt"}"#).unwrap();
+        assert_eq!(catalog.get("required"), Some("{field} fehlt"));
+        assert_eq!(catalog.codes().collect::<Vec<_>>(), ["required"]);
+        assert!(MessageCatalog::from_json("de", "[]").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "catalog-de", feature = "catalog-ja"))]
+    fn test_bundled() {
+        let catalog = MessageCatalog::bundled("de-AT").unwrap();
+        assert_eq!(catalog.locale(), "de-AT");
+        let error = SchemaError::new(
+            JsonPath::root().push_field("name"),
+            "required field 'name' is missing",
+        )
+        .with_code("required");
+        assert_eq!(
+            catalog.translate(error.clone()).message,
+            "Das Feld „name“ ist erforderlich"
+        );
+        assert_eq!(
+            MessageCatalog::bundled("ja")
+                .unwrap()
+                .translate(error)
+                .message,
+            "「name」は必須項目です"
+        );
+        assert!(MessageCatalog::bundled("tlh").is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Conveniences for validation results.
+
+use stillwater::Validation;
+
+use super::{SchemaError, SchemaErrors};
+
+mod sealed {
+    use super::SchemaErrors;
+    use stillwater::Validation;
+
+    /// Keeps [`ErrorsExt`](super::ErrorsExt) implemented for validation
+    /// results only.
+    pub trait Sealed {}
+
+    impl<T> Sealed for Validation<T, SchemaErrors> {}
+    impl<T> Sealed for Result<T, SchemaErrors> {}
+}
+
+/// Conveniences for inspecting the errors of a validation result.
+///
+/// Implemented for `Validation<T, SchemaErrors>` and
+/// `Result<T, SchemaErrors>`, and sealed, so new methods can be added in
+/// minor releases. Bring it into scope with `use postmortem::ErrorsExt`.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{ErrorsExt, JsonPath, Schema};
+/// use serde_json::json;
+///
+/// let schema = Schema::object()
+///     .field("name", Schema::string().min_len(1))
+///     .field("age", Schema::integer().positive());
+/// let result = schema.validate(&json!({"name": ""}), &JsonPath::root());
+///
+/// assert!(result.has_code("required"));
+/// assert_eq!(result.codes(), ["min_length", "required"]);
+/// assert_eq!(result.errors_at("name").len(), 1);
+/// ```
+pub trait ErrorsExt: sealed::Sealed {
+    /// Returns the errors, if validation failed.
+    fn schema_errors(&self) -> Option<&SchemaErrors>;
+
+    /// Returns the code of each error, in order.
+    fn codes(&self) -> Vec<&str> {
+        self.schema_errors()
+            .map(|errors| errors.iter().map(|error| error.code.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether any error has `code`.
+    fn has_code(&self, code: &str) -> bool {
+        self.schema_errors()
+            .is_some_and(|errors| errors.iter().any(|error| error.code == code))
+    }
+
+    /// Returns the errors at `path`, written like `items[0].sku`.
+    fn errors_at(&self, path: &str) -> Vec<&SchemaError> {
+        self.schema_errors()
+            .map(|errors| {
+                errors
+                    .iter()
+                    .filter(|error| error.path.to_string() == path)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<T> ErrorsExt for Validation<T, SchemaErrors> {
+    fn schema_errors(&self) -> Option<&SchemaErrors> {
+        match self {
+            Validation::Success(_) => None,
+            Validation::Failure(errors) => Some(errors),
+        }
+    }
+}
+
+impl<T> ErrorsExt for Result<T, SchemaErrors> {
+    fn schema_errors(&self) -> Option<&SchemaErrors> {
+        self.as_ref().err()
+    }
+}
@@ -0,0 +1,78 @@
+//! Custom construction of validation errors.
+//!
+//! Every constraint builds its own [`SchemaError`]. An [`ErrorFactory`]
+//! rebuilds each of them, so an application can control message style,
+//! codes, and metadata in one place instead of calling `.error(...)` on
+//! every constraint. Factories can be set on an
+//! [`ObjectSchema`](crate::ObjectSchema) or a
+//! [`SchemaRegistry`](crate::SchemaRegistry).
+
+use serde_json::Value;
+use std::sync::Arc;
+
+use super::{SchemaError, SchemaErrors};
+use crate::path::{JsonPath, PathSegment};
+
+/// The error a constraint reported, as passed to an [`ErrorFactory`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConstraintInfo<'a> {
+    /// The error code, e.g. `min_length`.
+    pub code: &'a str,
+    /// The message the constraint would have used.
+    pub message: &'a str,
+    /// What the constraint expected, if it said.
+    pub expected: Option<&'a str>,
+    /// The recorded value that failed, if any, after the
+    /// [got limit](crate::validation::ValidationContext::with_got_limit) was applied.
+    pub got: Option<&'a str>,
+}
+
+/// Builds the error reported for a failed constraint.
+///
+/// The factory is given the constraint's own error, the value that failed,
+/// and its path. If the error it returns has no suggestion or provenance,
+/// those of the original error are kept.
+pub type ErrorFactory =
+    Arc<dyn Fn(ConstraintInfo<'_>, &Value, &JsonPath) -> SchemaError + Send + Sync>;
+
+/// Rebuilds `errors` with `factory`.
+///
+/// `value` is the value validated at `path`; each error's own value is
+/// found below it by the rest of the error's path, or is null if the path
+/// does not exist, e.g. for a missing field.
+pub(crate) fn rebuild_errors(
+    factory: &ErrorFactory,
+    errors: SchemaErrors,
+    value: &Value,
+    path: &JsonPath,
+) -> SchemaErrors {
+    let rebuilt = errors
+        .into_iter()
+        .map(|error| {
+            let failed = error
+                .path
+                .segments()
+                .skip(path.len())
+                .try_fold(value, |current, segment| match segment {
+                    PathSegment::Field(name) => current.get(name.as_str()),
+                    PathSegment::Index(index) => current.get(*index),
+                })
+                .unwrap_or(&Value::Null);
+            let info = ConstraintInfo {
+                code: &error.code,
+                message: &error.message,
+                expected: error.expected.as_deref(),
+                got: error.got.as_deref(),
+            };
+            let mut rebuilt = factory(info, failed, &error.path);
+            if rebuilt.suggestion.is_none() {
+                rebuilt.suggestion = error.suggestion;
+            }
+            if rebuilt.provenance.is_none() {
+                rebuilt.provenance = error.provenance;
+            }
+            rebuilt
+        })
+        .collect();
+    SchemaErrors::from_vec(rebuilt)
+}
@@ -0,0 +1,254 @@
+//! Size limits for the values recorded in errors.
+//!
+//! Errors record the value that failed in [`SchemaError::got`]. A failed
+//! pattern check on a megabyte-long string would otherwise copy the whole
+//! string into the error, and again into every log line that prints it.
+//! Values longer than the limit are truncated with an ellipsis. The limit is
+//! set per validation, with [`ValidationContext::with_got_limit`] or
+//! [`SchemaRegistry::with_got_limit`].
+//!
+//! Type errors for objects and arrays record only the type by default.
+//! With [`set_value_previews`] they record a short preview of the value
+//! instead, which makes a union mismatch much easier to diagnose from logs.
+//!
+//! [`SchemaError::got`]: super::SchemaError::got
+//! [`ValidationContext::with_got_limit`]: crate::validation::ValidationContext::with_got_limit
+//! [`SchemaRegistry::with_got_limit`]: crate::SchemaRegistry::with_got_limit
+
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::settings::ErrorSettings;
+
+/// The default limit, in characters.
+pub(super) const DEFAULT_LIMIT: usize = 256;
+
+/// How many entries of an object or array a preview shows.
+const PREVIEW_ENTRIES: usize = 3;
+
+/// How many characters of a string a preview shows.
+const PREVIEW_STRING_CHARS: usize = 24;
+
+static PREVIEWS: AtomicBool = AtomicBool::new(false);
+
+/// How much of a failing value errors record.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{GotLimit, Schema, SchemaRegistry};
+/// use serde_json::json;
+///
+/// let registry = SchemaRegistry::new().with_got_limit(GotLimit::Truncate(8));
+/// registry.register("Email", Schema::string().email()).unwrap();
+///
+/// let errors = registry
+///     .validate("Email", &json!("not an email address"))
+///     .unwrap()
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.first().got.as_deref(), Some("not an e…"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GotLimit {
+    /// Record values in full.
+    Unlimited,
+    /// Record at most this many characters, followed by `…` if the value
+    /// was longer.
+    Truncate(usize),
+    /// Record no values, e.g. when they may contain personal data.
+    Omit,
+}
+
+impl Default for GotLimit {
+    /// Truncates values to 256 characters.
+    fn default() -> Self {
+        GotLimit::Truncate(DEFAULT_LIMIT)
+    }
+}
+
+/// Returns the limit of the validation running on this thread.
+pub(crate) fn got_limit() -> GotLimit {
+    ErrorSettings::current().got_limit
+}
+
+/// Sets whether type errors for objects and arrays record a preview of the
+/// value rather than only its type.
+///
+/// A preview shows the first few entries, with nested objects and arrays
+/// and long strings elided, and the total size, e.g.
+/// `{"center":{...},"radius":5,"tags":[],...} (4 keys)`. Its cost is bounded
+/// regardless of the size of the value. `one_of` errors also record a
+/// preview of the value that matched no schema.
+///
+/// This is process-wide and meant to be set once at
+/// startup. Previews are off by default, since they may contain personal
+/// data.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{set_value_previews, JsonPath, Schema};
+/// use serde_json::json;
+///
+/// set_value_previews(true);
+///
+/// let errors = Schema::string()
+///     .validate(&json!({"type": "circle", "radius": 5}), &JsonPath::root())
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(
+///     errors.first().got.as_deref(),
+///     Some(r#"{"radius":5,"type":"circle"} (2 keys)"#)
+/// );
+/// # set_value_previews(false);
+/// ```
+pub fn set_value_previews(enabled: bool) {
+    PREVIEWS.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether previews are enabled with [`set_value_previews`].
+pub fn value_previews() -> bool {
+    PREVIEWS.load(Ordering::Relaxed)
+}
+
+/// Describes a value that failed a type check: its type, or a preview if
+/// previews are enabled and the value is an object or array.
+pub(crate) fn describe_type(value: &Value) -> Cow<'static, str> {
+    match value {
+        Value::Object(_) | Value::Array(_) if value_previews() => Cow::Owned(preview(value)),
+        Value::Null => Cow::Borrowed("null"),
+        Value::Bool(_) => Cow::Borrowed("boolean"),
+        Value::Number(_) => Cow::Borrowed("number"),
+        Value::String(_) => Cow::Borrowed("string"),
+        Value::Array(_) => Cow::Borrowed("array"),
+        Value::Object(_) => Cow::Borrowed("object"),
+    }
+}
+
+/// Renders a bounded preview of a value, with its size for objects and
+/// arrays.
+pub(crate) fn preview(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) => {
+            out.push('{');
+            for (i, (key, value)) in map.iter().take(PREVIEW_ENTRIES).enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_preview_string(&mut out, key);
+                out.push(':');
+                write_preview_entry(&mut out, value);
+            }
+            if map.len() > PREVIEW_ENTRIES {
+                out.push_str(",...");
+            }
+            let noun = if map.len() == 1 { "key" } else { "keys" };
+            let _ = write!(out, "}} ({} {})", map.len(), noun);
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().take(PREVIEW_ENTRIES).enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_preview_entry(&mut out, item);
+            }
+            if items.len() > PREVIEW_ENTRIES {
+                out.push_str(",...");
+            }
+            let noun = if items.len() == 1 { "item" } else { "items" };
+            let _ = write!(out, "] ({} {})", items.len(), noun);
+        }
+        _ => write_preview_entry(&mut out, value),
+    }
+    out
+}
+
+/// Writes an entry of a preview, eliding nested objects and arrays.
+fn write_preview_entry(out: &mut String, value: &Value) {
+    match value {
+        Value::Object(map) if map.is_empty() => out.push_str("{}"),
+        Value::Object(_) => out.push_str("{...}"),
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Array(_) => out.push_str("[...]"),
+        Value::String(s) => write_preview_string(out, s),
+        scalar => {
+            let _ = write!(out, "{}", scalar);
+        }
+    }
+}
+
+/// Writes a JSON string literal, truncated to [`PREVIEW_STRING_CHARS`].
+fn write_preview_string(out: &mut String, s: &str) {
+    match s.char_indices().nth(PREVIEW_STRING_CHARS) {
+        Some((end, _)) => {
+            let _ = write!(out, "{}", Value::from(&s[..end]));
+            out.insert(out.len() - 1, '…');
+        }
+        None => {
+            let _ = write!(out, "{}", Value::from(s));
+        }
+    }
+}
+
+/// Applies `limit` to `got`, copying only the characters that are kept.
+pub(crate) fn cap(got: &str, limit: GotLimit) -> Option<String> {
+    match limit {
+        GotLimit::Unlimited => Some(got.to_string()),
+        GotLimit::Omit => None,
+        GotLimit::Truncate(max) => match got.char_indices().nth(max) {
+            Some((end, _)) => Some(format!("{}…", &got[..end])),
+            None => Some(got.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_truncates_on_char_boundaries() {
+        assert_eq!(cap("héllo", GotLimit::Truncate(2)).unwrap(), "hé…");
+        assert_eq!(cap("héllo", GotLimit::Truncate(5)).unwrap(), "héllo");
+        assert_eq!(cap("héllo", GotLimit::Truncate(0)).unwrap(), "…");
+    }
+
+    #[test]
+    fn test_preview_is_bounded() {
+        let value = serde_json::json!({
+            "type": "circle",
+            "center": {"x": 0, "y": 0},
+            "tags": [],
+            "radius": 5,
+        });
+        assert_eq!(
+            preview(&value),
+            r#"{"center":{...},"radius":5,"tags":[],...} (4 keys)"#
+        );
+
+        let items: Vec<u32> = (0..100_000).collect();
+        assert_eq!(
+            preview(&serde_json::json!(items)),
+            "[0,1,2,...] (100000 items)"
+        );
+
+        let long = "x".repeat(1_000);
+        assert_eq!(
+            preview(&serde_json::json!([long])),
+            format!("[\"{}…\"] (1 item)", "x".repeat(24))
+        );
+    }
+
+    #[test]
+    fn test_cap_unlimited_and_omit() {
+        let long = "x".repeat(10_000);
+        assert_eq!(cap(&long, GotLimit::Unlimited).unwrap().len(), 10_000);
+        assert_eq!(cap(&long, GotLimit::Omit), None);
+    }
+}
@@ -3,6 +3,31 @@
 //! This module provides types for representing validation errors with rich context
 //! including paths, messages, and expected/actual values.
 
+mod catalog;
+mod ext;
+mod factory;
+mod got;
+mod plural;
+mod provenance;
+mod repair;
 mod schema_error;
+mod serde_errors;
+mod settings;
+mod template;
 
+pub use catalog::MessageCatalog;
+pub(crate) use catalog::{FIRST_STRONG_ISOLATE, POP_DIRECTIONAL_ISOLATE};
+pub use ext::ErrorsExt;
+pub(crate) use factory::rebuild_errors;
+pub use factory::{ConstraintInfo, ErrorFactory};
+pub(crate) use got::{cap, describe_type, got_limit, preview};
+pub use got::{set_value_previews, value_previews, GotLimit};
+pub(crate) use plural::count;
+pub use plural::{plural_category, set_message_locale, PluralCategory};
+pub use provenance::Provenance;
+pub(crate) use repair::repair_with;
+pub use repair::{Repair, RepairKind, RepairOutcome, RepairPolicy};
 pub use schema_error::{SchemaError, SchemaErrors};
+pub use serde_errors::deserialize;
+pub(crate) use settings::ErrorSettings;
+pub(crate) use template::{placeholder_names, render_message};
@@ -0,0 +1,241 @@
+//! Plural rules for message formatting.
+//!
+//! Custom messages can pick a form by count with ICU-style plural syntax,
+//! `{len, plural, one {# item} other {# items}}`. Languages split counts
+//! into different categories: English has `one` and `other`, Russian adds
+//! `few` and `many`, and Japanese has only `other`. [`set_message_locale`]
+//! selects the rules used for custom messages; the built-in messages are
+//! English and always use the English rules.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A CLDR plural category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    /// E.g. Arabic 0.
+    Zero,
+    /// E.g. English 1, French 0 and 1, Russian 21.
+    One,
+    /// E.g. Arabic 2.
+    Two,
+    /// E.g. Russian and Polish 2-4, 22-24.
+    Few,
+    /// E.g. Russian and Polish 5-20, Arabic 11-99.
+    Many,
+    /// Every other count.
+    Other,
+}
+
+impl PluralCategory {
+    /// Returns the keyword used for this category in plural branches.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// Families of languages that share cardinal plural rules for integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum PluralRules {
+    /// `one` for 1: English, German, Dutch, Scandinavian, Italian, Spanish,
+    /// and most other European languages.
+    English,
+    /// `one` for 0 and 1: French, Brazilian Portuguese, Hindi, Persian.
+    French,
+    /// `one`, `few`, and `many` by the last digits: Russian, Ukrainian,
+    /// Belarusian.
+    EastSlavic,
+    /// Like East Slavic, but `one` only for 1.
+    Polish,
+    /// `one` for 1, `few` for 2-4: Czech, Slovak.
+    Czech,
+    /// `one` and `few` by the last digits, otherwise `other`: Croatian,
+    /// Serbian, Bosnian.
+    SouthSlavic,
+    /// Six categories.
+    Arabic,
+    /// Only `other`: Chinese, Japanese, Korean, Thai, Vietnamese,
+    /// Indonesian.
+    Invariant,
+}
+
+static RULES: AtomicU8 = AtomicU8::new(PluralRules::English as u8);
+
+impl PluralRules {
+    /// Returns the rules for a BCP 47 language tag such as `ru` or `pt-BR`.
+    ///
+    /// Unknown languages use the English rules, which most languages share.
+    pub(crate) fn for_locale(locale: &str) -> Self {
+        let mut subtags = locale.split(['-', '_']);
+        let language = subtags.next().unwrap_or("").to_ascii_lowercase();
+        match language.as_str() {
+            "pt" if subtags.any(|t| t.eq_ignore_ascii_case("pt")) => PluralRules::English,
+            "fr" | "pt" | "hi" | "bn" | "fa" | "zu" | "am" | "kn" | "gu" => PluralRules::French,
+            "ru" | "uk" | "be" => PluralRules::EastSlavic,
+            "pl" => PluralRules::Polish,
+            "cs" | "sk" => PluralRules::Czech,
+            "hr" | "sr" | "bs" | "sh" => PluralRules::SouthSlavic,
+            "ar" => PluralRules::Arabic,
+            "zh" | "ja" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "km" => {
+                PluralRules::Invariant
+            }
+            _ => PluralRules::English,
+        }
+    }
+
+    /// Returns the category of a count.
+    pub(crate) fn category(self, n: u64) -> PluralCategory {
+        let (last, last_two) = (n % 10, n % 100);
+        let few = (2..=4).contains(&last) && !(12..=14).contains(&last_two);
+        match self {
+            PluralRules::English if n == 1 => PluralCategory::One,
+            PluralRules::French if n <= 1 => PluralCategory::One,
+            PluralRules::EastSlavic | PluralRules::SouthSlavic if last == 1 && last_two != 11 => {
+                PluralCategory::One
+            }
+            PluralRules::Polish | PluralRules::Czech if n == 1 => PluralCategory::One,
+            PluralRules::EastSlavic | PluralRules::Polish | PluralRules::SouthSlavic if few => {
+                PluralCategory::Few
+            }
+            PluralRules::EastSlavic | PluralRules::Polish => PluralCategory::Many,
+            PluralRules::Czech if (2..=4).contains(&n) => PluralCategory::Few,
+            PluralRules::Arabic => match (n, last_two) {
+                (0, _) => PluralCategory::Zero,
+                (1, _) => PluralCategory::One,
+                (2, _) => PluralCategory::Two,
+                (_, 3..=10) => PluralCategory::Few,
+                (_, 11..=99) => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            },
+            _ => PluralCategory::Other,
+        }
+    }
+
+    /// Returns the rules set with [`set_message_locale`].
+    pub(crate) fn current() -> Self {
+        match RULES.load(Ordering::Relaxed) {
+            1 => PluralRules::French,
+            2 => PluralRules::EastSlavic,
+            3 => PluralRules::Polish,
+            4 => PluralRules::Czech,
+            5 => PluralRules::SouthSlavic,
+            6 => PluralRules::Arabic,
+            7 => PluralRules::Invariant,
+            _ => PluralRules::English,
+        }
+    }
+}
+
+/// Renders a count with an English noun, e.g. `1 item` or `3 items`.
+pub(crate) fn count(n: usize, one: &str, other: &str) -> String {
+    match PluralRules::English.category(n as u64) {
+        PluralCategory::One => format!("{} {}", n, one),
+        _ => format!("{} {}", n, other),
+    }
+}
+
+/// Sets the language whose plural rules custom messages use, as a BCP 47
+/// tag such as `en`, `ru`, or `pt-BR`.
+///
+/// This is process-wide and meant to be set once at startup. The default
+/// is English; unknown languages also use the English rules.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{set_message_locale, JsonPath, Schema};
+/// use serde_json::json;
+///
+/// set_message_locale("ru");
+///
+/// let schema = Schema::array(Schema::string()).max_len(2).error(
+///     "не более {max, plural, one {# элемента} few {# элементов} many {# элементов} other {# элемента}}",
+/// );
+/// let errors = schema
+///     .validate(&json!(["a", "b", "c"]), &JsonPath::root())
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.first().message, "не более 2 элементов");
+/// # set_message_locale("en");
+/// ```
+pub fn set_message_locale(locale: &str) {
+    RULES.store(PluralRules::for_locale(locale) as u8, Ordering::Relaxed);
+}
+
+/// Returns the plural category of `n` under the locale set with
+/// [`set_message_locale`], e.g. for an
+/// [`ErrorFactory`](super::ErrorFactory) that formats its own messages.
+pub fn plural_category(n: u64) -> PluralCategory {
+    PluralRules::current().category(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(rules: PluralRules, counts: &[u64]) -> Vec<&'static str> {
+        counts
+            .iter()
+            .map(|&n| rules.category(n).keyword())
+            .collect()
+    }
+
+    #[test]
+    fn test_plural_rules() {
+        let counts = [0, 1, 2, 5, 11, 21, 22, 25, 101, 111];
+        assert_eq!(
+            categories(PluralRules::English, &counts),
+            [
+                "other", "one", "other", "other", "other", "other", "other", "other", "other",
+                "other"
+            ]
+        );
+        assert_eq!(
+            categories(PluralRules::French, &counts),
+            [
+                "one", "one", "other", "other", "other", "other", "other", "other", "other",
+                "other"
+            ]
+        );
+        assert_eq!(
+            categories(PluralRules::EastSlavic, &counts),
+            ["many", "one", "few", "many", "many", "one", "few", "many", "one", "many"]
+        );
+        assert_eq!(
+            categories(PluralRules::Polish, &counts),
+            ["many", "one", "few", "many", "many", "many", "few", "many", "many", "many"]
+        );
+        assert_eq!(
+            categories(PluralRules::Czech, &counts),
+            [
+                "other", "one", "few", "other", "other", "other", "other", "other", "other",
+                "other"
+            ]
+        );
+        assert_eq!(
+            categories(PluralRules::Arabic, &[0, 1, 2, 3, 10, 11, 99, 100, 103]),
+            ["zero", "one", "two", "few", "few", "many", "many", "other", "few"]
+        );
+        assert_eq!(
+            categories(PluralRules::Invariant, &[0, 1, 2]),
+            ["other", "other", "other"]
+        );
+    }
+
+    #[test]
+    fn test_rules_for_locale() {
+        assert_eq!(PluralRules::for_locale("en-US"), PluralRules::English);
+        assert_eq!(PluralRules::for_locale("pt-BR"), PluralRules::French);
+        assert_eq!(PluralRules::for_locale("pt-PT"), PluralRules::English);
+        assert_eq!(PluralRules::for_locale("RU"), PluralRules::EastSlavic);
+        assert_eq!(PluralRules::for_locale("zh_Hant"), PluralRules::Invariant);
+        assert_eq!(PluralRules::for_locale("tlh"), PluralRules::English);
+    }
+}
@@ -0,0 +1,49 @@
+//! Source locations of schema rules.
+//!
+//! Schemas loaded from files record where each rule was defined, so an
+//! error from a large loaded registry can point back at the definition:
+//! `age: must be at least 0 (rule defined in schemas/user.json:12)`.
+
+use std::fmt::{self, Display};
+
+/// The file and line where a schema rule was defined.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{JsonPath, Provenance, Schema};
+/// use serde_json::json;
+///
+/// let schema = Schema::string()
+///     .min_len(3)
+///     .defined_at(Provenance::new("schemas/user.json", 42));
+///
+/// let errors = schema.validate(&json!("ab"), &JsonPath::root()).into_result().unwrap_err();
+/// assert_eq!(
+///     errors.first().provenance.as_ref().unwrap().to_string(),
+///     "schemas/user.json:42"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    /// The file the rule was loaded from.
+    pub file: String,
+    /// The 1-based line of the rule within the file.
+    pub line: usize,
+}
+
+impl Provenance {
+    /// Creates a provenance for a rule defined at `file:line`.
+    pub fn new(file: impl Into<String>, line: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+        }
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
@@ -0,0 +1,441 @@
+//! Machine-actionable repair suggestions for validation errors.
+//!
+//! Some validation failures have an obvious fix: a numeric string where an
+//! integer was expected, a string one character too long, an enum value in
+//! the wrong case. Schemas attach a [`Repair`] to those errors so upstream
+//! tools can offer one-click fixes, and
+//! [`SchemaLike::validate_and_repair`](crate::SchemaLike::validate_and_repair)
+//! applies the ones a [`RepairPolicy`] allows.
+
+use serde_json::{json, Value};
+use stillwater::Validation;
+
+use crate::error::SchemaErrors;
+use crate::path::{JsonPath, PathSegment};
+
+/// Maximum number of validate-and-repair rounds before giving up.
+///
+/// Each round applies at most one repair per value, so a value failing
+/// several repairable constraints (too long and wrongly cased, say) needs a
+/// round per repair.
+const MAX_REPAIR_PASSES: usize = 8;
+
+/// The kind of fix a [`Repair`] makes.
+///
+/// Callers applying repairs automatically can use the kind to decide which
+/// fixes are safe for their data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RepairKind {
+    /// Converts a value to the expected type, e.g. `"42"` to `42`.
+    Coerce,
+    /// Shortens a string to its maximum length.
+    Truncate,
+    /// Moves a number to the nearest allowed bound.
+    Clamp,
+    /// Replaces a value with the allowed value it matches ignoring case.
+    Canonicalize,
+    /// Removes a field the schema does not allow.
+    RemoveUnknown,
+    /// Removes leading and trailing whitespace from a string.
+    Trim,
+}
+
+impl RepairKind {
+    /// Returns the snake_case name of this kind, e.g. `remove_unknown`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepairKind::Coerce => "coerce",
+            RepairKind::Truncate => "truncate",
+            RepairKind::Clamp => "clamp",
+            RepairKind::Canonicalize => "canonicalize",
+            RepairKind::RemoveUnknown => "remove_unknown",
+            RepairKind::Trim => "trim",
+        }
+    }
+}
+
+/// A suggested fix for a validation error.
+///
+/// A repair either replaces the value at a path or removes it. Repairs are
+/// suggestions: applying one fixes the error it is attached to, but the
+/// repaired value may still fail other constraints.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{JsonPath, RepairKind, Schema};
+/// use serde_json::json;
+///
+/// let schema = Schema::integer();
+/// let path = JsonPath::root().push_field("age");
+///
+/// let errors = schema.validate(&json!("42"), &path).into_result().unwrap_err();
+/// let repair = errors.first().suggestion.as_ref().unwrap();
+///
+/// assert_eq!(repair.kind, RepairKind::Coerce);
+/// assert_eq!(repair.value, Some(json!(42)));
+/// assert_eq!(
+///     repair.to_json_patch(),
+///     json!({"op": "replace", "path": "/age", "value": 42})
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    /// The kind of fix.
+    pub kind: RepairKind,
+    /// The path to the value being fixed.
+    pub path: JsonPath,
+    /// The replacement value, or `None` if the value should be removed.
+    pub value: Option<Value>,
+}
+
+impl Repair {
+    /// Creates a repair replacing the value at `path`.
+    pub fn replace(kind: RepairKind, path: JsonPath, value: impl Into<Value>) -> Self {
+        Self {
+            kind,
+            path,
+            value: Some(value.into()),
+        }
+    }
+
+    /// Creates a repair removing the value at `path`.
+    pub fn remove(kind: RepairKind, path: JsonPath) -> Self {
+        Self {
+            kind,
+            path,
+            value: None,
+        }
+    }
+
+    /// Returns the JSON Patch operation name: `replace` or `remove`.
+    pub fn op(&self) -> &'static str {
+        if self.value.is_some() {
+            "replace"
+        } else {
+            "remove"
+        }
+    }
+
+    /// Returns this repair as a JSON Patch (RFC 6902) operation.
+    pub fn to_json_patch(&self) -> Value {
+        let mut patch = json!({
+            "op": self.op(),
+            "path": self.path.to_pointer(),
+        });
+        if let Some(value) = &self.value {
+            patch["value"] = value.clone();
+        }
+        patch
+    }
+
+    /// Applies this repair to a document.
+    ///
+    /// Returns `false`, leaving the document unchanged, if the path doesn't
+    /// exist or a removal targets something other than an object field.
+    pub fn apply(&self, document: &mut Value) -> bool {
+        let mut segments: Vec<&PathSegment> = self.path.segments().collect();
+        let Some(value) = &self.value else {
+            let Some(PathSegment::Field(name)) = segments.pop() else {
+                return false;
+            };
+            return match resolve_mut(document, &segments) {
+                Some(Value::Object(map)) => map.remove(name).is_some(),
+                _ => false,
+            };
+        };
+        match resolve_mut(document, &segments) {
+            Some(target) => {
+                *target = value.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Which kinds of repair [`validate_and_repair`](crate::SchemaLike::validate_and_repair)
+/// may apply.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{RepairKind, RepairPolicy};
+///
+/// let policy = RepairPolicy::safe().allow(RepairKind::Truncate);
+/// assert!(policy.allows(RepairKind::Clamp));
+/// assert!(policy.allows(RepairKind::Truncate));
+/// assert!(!policy.allows(RepairKind::Canonicalize));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepairPolicy {
+    allowed: u8,
+}
+
+impl RepairPolicy {
+    /// Creates a policy that allows no repairs.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy allowing repairs that keep the value's meaning:
+    /// trimming whitespace, clamping numbers, dropping unknown fields, and
+    /// coercing numbers and numeric strings.
+    ///
+    /// Truncation and case canonicalization change what the value says and
+    /// must be allowed explicitly.
+    pub fn safe() -> Self {
+        Self::none()
+            .allow(RepairKind::Trim)
+            .allow(RepairKind::Clamp)
+            .allow(RepairKind::RemoveUnknown)
+            .allow(RepairKind::Coerce)
+    }
+
+    /// Creates a policy allowing every kind of repair.
+    pub fn all() -> Self {
+        Self::safe()
+            .allow(RepairKind::Truncate)
+            .allow(RepairKind::Canonicalize)
+    }
+
+    /// Allows a kind of repair.
+    pub fn allow(mut self, kind: RepairKind) -> Self {
+        self.allowed |= Self::bit(kind);
+        self
+    }
+
+    /// Disallows a kind of repair.
+    pub fn deny(mut self, kind: RepairKind) -> Self {
+        self.allowed &= !Self::bit(kind);
+        self
+    }
+
+    /// Returns whether a kind of repair is allowed.
+    pub fn allows(&self, kind: RepairKind) -> bool {
+        self.allowed & Self::bit(kind) != 0
+    }
+
+    fn bit(kind: RepairKind) -> u8 {
+        1 << kind as u8
+    }
+}
+
+/// The result of [`validate_and_repair`](crate::SchemaLike::validate_and_repair).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairOutcome {
+    /// The document with all applied repairs.
+    pub value: Value,
+    /// The schema's output for the repaired document, with its transforms
+    /// and defaults applied, or `None` if the repaired document is invalid.
+    pub output: Option<Value>,
+    /// The repairs applied, in order.
+    pub applied: Vec<Repair>,
+    /// Errors remaining after repair, or `None` if the repaired document is
+    /// valid.
+    pub errors: Option<SchemaErrors>,
+}
+
+impl RepairOutcome {
+    /// Returns whether the repaired document is valid.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_none()
+    }
+
+    /// Converts into the schema's output for the repaired document, or the
+    /// remaining errors.
+    pub fn into_validation(self) -> Validation<Value, SchemaErrors> {
+        match (self.output, self.errors) {
+            (_, Some(errors)) => Validation::Failure(errors),
+            (Some(output), None) => Validation::Success(output),
+            (None, None) => Validation::Success(self.value),
+        }
+    }
+}
+
+/// Repeatedly validates a document and applies the allowed suggestions until
+/// it is valid or no allowed suggestion remains.
+pub(crate) fn repair_with<F>(value: &Value, policy: RepairPolicy, validate: F) -> RepairOutcome
+where
+    F: Fn(&Value) -> Validation<Value, SchemaErrors>,
+{
+    let mut document = value.clone();
+    let mut applied = Vec::new();
+
+    for _ in 0..MAX_REPAIR_PASSES {
+        let errors = match validate(&document) {
+            Validation::Success(output) => {
+                return RepairOutcome {
+                    value: document,
+                    output: Some(output),
+                    applied,
+                    errors: None,
+                };
+            }
+            Validation::Failure(errors) => errors,
+        };
+
+        // One repair per value per pass: a repair at `a` would invalidate
+        // suggestions made at `a` or below it
+        let mut touched: Vec<&JsonPath> = Vec::new();
+        for repair in errors
+            .iter()
+            .filter_map(|e| e.suggestion.as_ref())
+            .filter(|r| policy.allows(r.kind))
+        {
+            if touched
+                .iter()
+                .any(|p| is_prefix(p, &repair.path) || is_prefix(&repair.path, p))
+            {
+                continue;
+            }
+            if repair.apply(&mut document) {
+                touched.push(&repair.path);
+                applied.push(repair.clone());
+            }
+        }
+        if touched.is_empty() {
+            return RepairOutcome {
+                value: document,
+                output: None,
+                applied,
+                errors: Some(errors),
+            };
+        }
+    }
+
+    let (output, errors) = match validate(&document) {
+        Validation::Success(output) => (Some(output), None),
+        Validation::Failure(errors) => (None, Some(errors)),
+    };
+    RepairOutcome {
+        value: document,
+        output,
+        applied,
+        errors,
+    }
+}
+
+/// Returns the value at a path of segments, if it exists.
+fn resolve_mut<'a>(value: &'a mut Value, segments: &[&PathSegment]) -> Option<&'a mut Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Field(name) => current.get_mut(name.as_str()),
+            PathSegment::Index(idx) => current.get_mut(*idx),
+        })
+}
+
+/// Returns whether `prefix` is `path` or one of its ancestors.
+fn is_prefix(prefix: &JsonPath, path: &JsonPath) -> bool {
+    prefix.len() <= path.len() && prefix.segments().zip(path.segments()).all(|(a, b)| a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use crate::SchemaLike;
+
+    #[test]
+    fn test_remove_patch() {
+        let repair = Repair::remove(
+            RepairKind::RemoveUnknown,
+            JsonPath::root().push_field("a/b").push_index(0),
+        );
+        assert_eq!(repair.op(), "remove");
+        assert_eq!(
+            repair.to_json_patch(),
+            json!({"op": "remove", "path": "/a~1b/0"})
+        );
+    }
+
+    #[test]
+    fn test_replace_patch_at_root() {
+        let repair = Repair::replace(RepairKind::Truncate, JsonPath::root(), "abc");
+        assert_eq!(
+            repair.to_json_patch(),
+            json!({"op": "replace", "path": "", "value": "abc"})
+        );
+        assert_eq!(repair.kind.as_str(), "truncate");
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut doc = json!({"a": {"b": 1, "c": [1, 2]}});
+
+        let path = JsonPath::root()
+            .push_field("a")
+            .push_field("c")
+            .push_index(1);
+        assert!(Repair::replace(RepairKind::Clamp, path, 3).apply(&mut doc));
+
+        let path = JsonPath::root().push_field("a").push_field("b");
+        assert!(Repair::remove(RepairKind::RemoveUnknown, path.clone()).apply(&mut doc));
+        assert!(!Repair::remove(RepairKind::RemoveUnknown, path).apply(&mut doc));
+
+        assert_eq!(doc, json!({"a": {"c": [1, 3]}}));
+    }
+
+    #[test]
+    fn test_validate_and_repair() {
+        let schema = Schema::object()
+            .field("name", Schema::string().max_len(5).one_of(["alice", "bob"]))
+            .field("age", Schema::integer().min(0).max(150))
+            .field("role", Schema::string().one_of(["admin", "user"]))
+            .additional_properties(false);
+
+        let doc = json!({"name": " bob ", "age": "200", "role": "Admin", "x": 1});
+        let outcome = schema.validate_and_repair(&doc, RepairPolicy::safe());
+
+        // The age is coerced, then clamped on the next pass
+        let kinds: Vec<&str> = outcome.applied.iter().map(|r| r.kind.as_str()).collect();
+        assert_eq!(kinds, ["trim", "coerce", "remove_unknown", "clamp"]);
+        assert_eq!(
+            outcome.value,
+            json!({"name": "bob", "age": 150, "role": "Admin"})
+        );
+
+        // Canonicalization is not a safe repair
+        let errors = outcome.errors.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_enum");
+
+        let outcome = schema.validate_and_repair(&doc, RepairPolicy::all());
+        assert!(outcome.is_valid());
+        assert_eq!(
+            outcome.into_validation().into_result().unwrap()["role"],
+            "admin"
+        );
+    }
+
+    #[test]
+    fn test_outcome_keeps_validated_output() {
+        let schema = Schema::object()
+            .field("name", Schema::string().trim())
+            .field("port", Schema::integer())
+            .default("region", Schema::string(), json!("eu"));
+
+        let doc = json!({"name": "  api  ", "port": "8080"});
+        let outcome = schema.validate_and_repair(&doc, RepairPolicy::safe());
+        assert_eq!(outcome.value, json!({"name": "  api  ", "port": 8080}));
+        assert_eq!(
+            outcome.into_validation().into_result().unwrap(),
+            json!({"name": "api", "port": 8080, "region": "eu"})
+        );
+    }
+
+    #[test]
+    fn test_repair_policy_none_reports_original_errors() {
+        let schema = Schema::integer().max(10);
+        let outcome = schema.validate_and_repair(&json!(11), RepairPolicy::none());
+        assert!(outcome.applied.is_empty());
+        assert_eq!(outcome.value, json!(11));
+        assert!(!outcome.is_valid());
+
+        let policy = RepairPolicy::all().deny(RepairKind::Clamp);
+        assert!(!policy.allows(RepairKind::Clamp));
+        assert!(policy.allows(RepairKind::Trim));
+    }
+}
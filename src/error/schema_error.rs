@@ -5,8 +5,12 @@
 
 use std::fmt::{self, Display};
 
+use serde_json::{Map, Value};
 use stillwater::prelude::*;
 
+use super::got::{cap, got_limit};
+use super::provenance::Provenance;
+use super::repair::Repair;
 use crate::path::JsonPath;
 
 /// A single validation error with full context.
@@ -17,6 +21,10 @@ use crate::path::JsonPath;
 /// - **got**: The actual value that failed validation (optional)
 /// - **expected**: What was expected instead (optional)
 /// - **code**: Machine-readable error code for programmatic handling
+/// - **suggestion**: A [`Repair`] that would fix the error, for selected codes
+/// - **provenance**: Where the failed rule was defined, for loaded schemas
+/// - **params**: Structured details of the failure, e.g. the index a
+///   duplicate first appeared at
 ///
 /// # Example
 ///
@@ -34,6 +42,7 @@ use crate::path::JsonPath;
 /// assert_eq!(error.code, "invalid_email");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct SchemaError {
     /// The path to the value that failed validation.
     pub path: JsonPath,
@@ -45,6 +54,13 @@ pub struct SchemaError {
     pub expected: Option<String>,
     /// Machine-readable error code (e.g., `min_length_violated`).
     pub code: String,
+    /// A suggested fix, if the error has an obvious one.
+    pub suggestion: Option<Repair>,
+    /// Where the rule that failed was defined, if the schema recorded it.
+    pub provenance: Option<Provenance>,
+    /// Structured details of the failure, keyed by name, for callers that
+    /// would otherwise parse them out of `got` or `message`.
+    pub params: Map<String, Value>,
 }
 
 impl SchemaError {
@@ -59,6 +75,9 @@ impl SchemaError {
             got: None,
             expected: None,
             code: "validation_error".to_string(),
+            suggestion: None,
+            provenance: None,
+            params: Map::new(),
         }
     }
 
@@ -69,8 +88,13 @@ impl SchemaError {
     }
 
     /// Sets the "got" (actual value) field and returns self for chaining.
-    pub fn with_got(mut self, got: impl Into<String>) -> Self {
-        self.got = Some(got.into());
+    ///
+    /// Long values are truncated, or omitted, according to the
+    /// [got limit](crate::validation::ValidationContext::with_got_limit) of the
+    /// validation running on this thread. Only the kept part of the value is
+    /// copied.
+    pub fn with_got(mut self, got: impl AsRef<str>) -> Self {
+        self.got = cap(got.as_ref(), got_limit());
         self
     }
 
@@ -79,6 +103,24 @@ impl SchemaError {
         self.expected = Some(expected.into());
         self
     }
+
+    /// Attaches a suggested fix and returns self for chaining.
+    pub fn with_suggestion(mut self, repair: Repair) -> Self {
+        self.suggestion = Some(repair);
+        self
+    }
+
+    /// Records where the failed rule was defined and returns self for chaining.
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Adds a structured detail and returns self for chaining.
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
 }
 
 impl Display for SchemaError {
@@ -97,6 +139,9 @@ impl Display for SchemaError {
         if let Some(ref got) = self.got {
             write!(f, " (got: {})", got)?;
         }
+        if let Some(ref provenance) = self.provenance {
+            write!(f, " (rule defined in {})", provenance)?;
+        }
 
         Ok(())
     }
@@ -181,6 +226,51 @@ impl SchemaErrors {
         self.0.iter().filter(|e| e.code == code).collect()
     }
 
+    /// Returns the errors that are not in `baseline`, or `None` if every
+    /// error was already there.
+    ///
+    /// An error counts as already there if the baseline has an error with
+    /// the same path and code. Messages and got values are not compared,
+    /// since they change with the data while the violation stays the same.
+    /// This lets a stricter schema be adopted on existing data by failing
+    /// only on regressions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, SchemaError, SchemaErrors};
+    ///
+    /// let error = |field: &str, code: &str, got: &str| {
+    ///     SchemaError::new(JsonPath::root().push_field(field), "invalid")
+    ///         .with_code(code)
+    ///         .with_got(got)
+    /// };
+    ///
+    /// let baseline = SchemaErrors::single(error("sku", "pattern", "abc"));
+    /// let current = SchemaErrors::from_vec(vec![
+    ///     error("sku", "pattern", "abd"),
+    ///     error("qty", "min_value", "-1"),
+    /// ]);
+    ///
+    /// let regressions = current.diff(&baseline).unwrap();
+    /// assert_eq!(regressions.len(), 1);
+    /// assert_eq!(regressions.first().code, "min_value");
+    ///
+    /// assert!(baseline.diff(&current).is_none());
+    /// ```
+    pub fn diff(&self, baseline: &SchemaErrors) -> Option<SchemaErrors> {
+        let new: Vec<SchemaError> = self
+            .iter()
+            .filter(|error| {
+                !baseline
+                    .iter()
+                    .any(|known| known.path == error.path && known.code == error.code)
+            })
+            .cloned()
+            .collect();
+        NonEmptyVec::from_vec(new).map(Self)
+    }
+
     /// Returns the first error in the collection.
     pub fn first(&self) -> &SchemaError {
         self.0.head()
@@ -292,6 +382,17 @@ mod tests {
         assert!(display.contains("got: not-an-email"));
     }
 
+    #[test]
+    fn test_schema_error_display_provenance() {
+        let error = SchemaError::new(JsonPath::root().push_field("age"), "too small")
+            .with_provenance(Provenance::new("schemas/user.json", 12));
+
+        assert_eq!(
+            error.to_string(),
+            "age: too small (rule defined in schemas/user.json:12)"
+        );
+    }
+
     #[test]
     fn test_schema_error_display_root() {
         let error = SchemaError::new(JsonPath::root(), "value is null");
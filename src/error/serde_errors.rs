@@ -0,0 +1,299 @@
+//! Conversions between [`SchemaErrors`] and serde deserialization errors.
+//!
+//! Deserialization can fail after validation succeeded (a schema that is
+//! looser than the target type) or instead of validation entirely. These
+//! conversions report such failures with the same path and code structure as
+//! validation errors, and turn validation errors back into serde errors for
+//! use inside `Deserialize` implementations.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_path_to_error::Segment;
+use stillwater::Validation;
+
+use crate::path::JsonPath;
+
+use super::{SchemaError, SchemaErrors};
+
+impl SchemaErrors {
+    /// Converts a path-tracking deserialization error into schema errors.
+    ///
+    /// The path reported by `serde_path_to_error` becomes the error path and
+    /// serde's message is classified into the codes used by schemas:
+    ///
+    /// | serde message | code |
+    /// |---------------|------|
+    /// | `invalid type` | `invalid_type` |
+    /// | `missing field` | `required` (path points at the missing field) |
+    /// | `unknown field` | `additional_property` |
+    /// | `unknown variant` | `invalid_enum` |
+    /// | `invalid value` | `invalid_value` |
+    /// | `invalid length` | `invalid_length` |
+    /// | `duplicate field` | `duplicate_field` |
+    /// | anything else | `deserialize` |
+    ///
+    /// `value` is the input that failed to deserialize; it is used to report
+    /// the JSON type found at the error path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::SchemaErrors;
+    /// use serde_json::json;
+    ///
+    /// let value = json!({"ports": [80, "443"]});
+    /// let err = serde_path_to_error::deserialize::<_, std::collections::HashMap<String, Vec<u16>>>(&value)
+    ///     .unwrap_err();
+    ///
+    /// let errors = SchemaErrors::from_serde_error(&err, &value);
+    /// assert_eq!(errors.first().path.to_string(), "ports[1]");
+    /// assert_eq!(errors.first().code, "invalid_type");
+    /// assert_eq!(errors.first().got.as_deref(), Some("string"));
+    /// ```
+    pub fn from_serde_error(
+        err: &serde_path_to_error::Error<serde_json::Error>,
+        value: &Value,
+    ) -> Self {
+        let mut path = err
+            .path()
+            .iter()
+            .fold(JsonPath::root(), |acc, segment| match segment {
+                Segment::Seq { index } => acc.push_index(*index),
+                Segment::Map { key } => acc.push_field(key.as_str()),
+                Segment::Enum { variant } => acc.push_field(variant.as_str()),
+                Segment::Unknown => acc,
+            });
+
+        let message = serde_message(err.inner());
+        let (description, expected) = match message.split_once(", expected ") {
+            Some((description, expected)) => (description, Some(expected)),
+            None => (message.as_str(), None),
+        };
+
+        let code = if description.starts_with("invalid type") {
+            "invalid_type"
+        } else if let Some(field) = backticked(description, "missing field ") {
+            path = path.push_field(field);
+            "required"
+        } else if description.starts_with("unknown field") {
+            "additional_property"
+        } else if description.starts_with("unknown variant") {
+            "invalid_enum"
+        } else if description.starts_with("invalid value") {
+            "invalid_value"
+        } else if description.starts_with("invalid length") {
+            "invalid_length"
+        } else if description.starts_with("duplicate field") {
+            "duplicate_field"
+        } else {
+            "deserialize"
+        };
+
+        let mut error = SchemaError::new(path.clone(), message.clone()).with_code(code);
+        if let Some(expected) = expected {
+            error = error.with_expected(expected);
+        }
+        if code == "invalid_type" {
+            if let Some(found) = path.resolve(value) {
+                error = error.with_got(value_type_name(found));
+            }
+        }
+
+        SchemaErrors::single(error)
+    }
+
+    /// Converts these errors into a serde error.
+    ///
+    /// The message follows the `serde_path_to_error` format, `path: message`,
+    /// with multiple errors separated by `"; "`. This lets a `Deserialize`
+    /// implementation run a schema and report failures through its
+    /// deserializer's error type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, SchemaError, SchemaErrors};
+    ///
+    /// let errors = SchemaErrors::single(SchemaError::new(
+    ///     JsonPath::root().push_field("age"),
+    ///     "must be positive",
+    /// ));
+    ///
+    /// let err: serde_json::Error = errors.to_serde_error();
+    /// assert_eq!(err.to_string(), "age: must be positive");
+    /// ```
+    pub fn to_serde_error<E: serde::de::Error>(&self) -> E {
+        let message = self
+            .iter()
+            .map(|e| {
+                if e.path.is_root() {
+                    e.message.clone()
+                } else {
+                    format!("{}: {}", e.path, e.message)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        E::custom(message)
+    }
+}
+
+/// Deserializes a JSON value, reporting failures as [`SchemaErrors`].
+///
+/// This is the deserialization counterpart to schema validation: both report
+/// failures with paths and codes, so clients can parse them the same way.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::error::deserialize;
+/// use serde_json::json;
+///
+/// let ports: Vec<u16> = deserialize(&json!([80, 443])).into_result().unwrap();
+/// assert_eq!(ports, vec![80, 443]);
+///
+/// let errors = deserialize::<Vec<u16>>(&json!([80, 70000]))
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.first().path.to_string(), "[1]");
+/// assert_eq!(errors.first().code, "invalid_value");
+/// ```
+pub fn deserialize<T: DeserializeOwned>(value: &Value) -> Validation<T, SchemaErrors> {
+    match serde_path_to_error::deserialize(value) {
+        Ok(typed) => Validation::Success(typed),
+        Err(e) => Validation::Failure(SchemaErrors::from_serde_error(&e, value)),
+    }
+}
+
+/// Returns serde's message without the position suffix added when parsing text.
+fn serde_message(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    if err.line() == 0 {
+        return message;
+    }
+    let suffix = format!(" at line {} column {}", err.line(), err.column());
+    message
+        .strip_suffix(&suffix)
+        .map(str::to_string)
+        .unwrap_or(message)
+}
+
+/// Extracts the backticked name following `prefix`, e.g. `missing field `id``.
+fn backticked<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    message
+        .strip_prefix(prefix)?
+        .strip_prefix('`')?
+        .split('`')
+        .next()
+}
+
+/// Returns the JSON type name for a value.
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Order {
+        id: String,
+        items: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Item {
+        quantity: u32,
+        kind: Kind,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    enum Kind {
+        Physical,
+        Digital,
+    }
+
+    fn error_for(value: Value) -> SchemaError {
+        deserialize::<Order>(&value)
+            .into_result()
+            .unwrap_err()
+            .first()
+            .clone()
+    }
+
+    #[test]
+    fn test_invalid_type() {
+        let error = error_for(json!({"id": "a", "items": [{"quantity": "x", "kind": "Digital"}]}));
+        assert_eq!(error.path.to_string(), "items[0].quantity");
+        assert_eq!(error.code, "invalid_type");
+        assert_eq!(error.expected.as_deref(), Some("u32"));
+        assert_eq!(error.got.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn test_missing_field_points_at_field() {
+        let error = error_for(json!({"items": []}));
+        assert_eq!(error.path.to_string(), "id");
+        assert_eq!(error.code, "required");
+    }
+
+    #[test]
+    fn test_unknown_field_and_variant() {
+        let error = error_for(json!({"id": "a", "items": [], "extra": 1}));
+        assert_eq!(error.path.to_string(), "extra");
+        assert_eq!(error.code, "additional_property");
+
+        let error = error_for(json!({"id": "a", "items": [{"quantity": 1, "kind": "Gift"}]}));
+        assert_eq!(error.path.to_string(), "items[0].kind");
+        assert_eq!(error.code, "invalid_enum");
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let error = error_for(json!({"id": "a", "items": [{"quantity": -1, "kind": "Digital"}]}));
+        assert_eq!(error.code, "invalid_value");
+        assert!(error.got.is_none());
+    }
+
+    #[test]
+    fn test_strips_position_from_text_errors() {
+        let mut de = serde_json::Deserializer::from_str(r#"{"id": 5, "items": []}"#);
+        let err = serde_path_to_error::deserialize::<_, Order>(&mut de).unwrap_err();
+        let errors = SchemaErrors::from_serde_error(&err, &json!({"id": 5, "items": []}));
+
+        assert_eq!(
+            errors.first().message,
+            "invalid type: integer `5`, expected a string"
+        );
+        assert_eq!(errors.first().got.as_deref(), Some("number"));
+    }
+
+    #[test]
+    fn test_to_serde_error() {
+        let errors = SchemaErrors::from_vec(vec![
+            SchemaError::new(JsonPath::root(), "expected object"),
+            SchemaError::new(
+                JsonPath::root().push_field("items").push_index(0),
+                "too small",
+            ),
+        ]);
+
+        let err: serde_json::Error = errors.to_serde_error();
+        assert_eq!(err.to_string(), "expected object; items[0]: too small");
+    }
+}
@@ -0,0 +1,78 @@
+//! Settings for how errors describe a failure, scoped to a validation.
+//!
+//! A [`ValidationContext`](crate::validation::ValidationContext) carries
+//! its settings, and a [`SchemaRegistry`](crate::SchemaRegistry) applies
+//! them to the current thread while it runs a validation with the context,
+//! since most schemas build their errors without seeing the context.
+//! Schemas validated outside a registry use the defaults.
+
+use std::cell::Cell;
+
+use super::got::{GotLimit, DEFAULT_LIMIT};
+
+/// The settings errors are built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ErrorSettings {
+    /// How much of a failing value errors record.
+    pub(crate) got_limit: GotLimit,
+}
+
+impl ErrorSettings {
+    /// The settings outside any validation run by a registry.
+    pub(crate) const DEFAULT: Self = Self {
+        got_limit: GotLimit::Truncate(DEFAULT_LIMIT),
+    };
+
+    /// Returns the settings of the validation running on this thread.
+    pub(crate) fn current() -> Self {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Runs `f` with these settings as the current ones.
+    pub(crate) fn apply<R>(self, f: impl FnOnce() -> R) -> R {
+        let _restore = Restore(CURRENT.with(|current| current.replace(self)));
+        f()
+    }
+}
+
+impl Default for ErrorSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+thread_local! {
+    /// The settings of the validation running on this thread.
+    static CURRENT: Cell<ErrorSettings> = const { Cell::new(ErrorSettings::DEFAULT) };
+}
+
+/// Restores the previous settings when a validation ends, even by
+/// unwinding.
+struct Restore(ErrorSettings);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_restores_previous_settings() {
+        let limited = ErrorSettings {
+            got_limit: GotLimit::Omit,
+        };
+        let nested = ErrorSettings {
+            got_limit: GotLimit::Unlimited,
+        };
+        limited.apply(|| {
+            assert_eq!(ErrorSettings::current(), limited);
+            nested.apply(|| assert_eq!(ErrorSettings::current(), nested));
+            assert_eq!(ErrorSettings::current(), limited);
+        });
+        assert_eq!(ErrorSettings::current(), ErrorSettings::DEFAULT);
+    }
+}
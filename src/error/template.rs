@@ -0,0 +1,322 @@
+//! Placeholders in custom error messages.
+//!
+//! Messages set with `.error(...)` may refer to the constraint's parameters
+//! and to the failing value, so they stay correct when a limit changes:
+//! `.error("{field} must be between {min} and {max}, got {got}")`.
+//!
+//! Every message can use `{field}` (the last path segment, or `value` at the
+//! root), `{path}`, `{got}`, and `{expected}`. Each schema documents the
+//! parameters its constraints add. Unknown placeholders are left as they
+//! are, and `{{` and `}}` produce literal braces.
+//!
+//! Placeholders can also choose between forms, in ICU MessageFormat style:
+//!
+//! - `{max, plural, =0 {nothing} one {# item} other {# items}}` picks a
+//!   branch by exact value or by the plural category of the number under
+//!   the [message locale](super::set_message_locale); `#` in the branch is
+//!   replaced by the number.
+//! - `{unit, select, bytes {bytes} other {characters}}` picks a branch by
+//!   the parameter's value.
+//!
+//! Either falls back to its `other` branch.
+
+use std::cell::Cell;
+
+use super::plural::PluralRules;
+use super::SchemaError;
+use crate::path::PathSegment;
+
+/// Renders the placeholders in an error's message.
+///
+/// `params` are the schema's constraint parameters; when a name appears
+/// more than once, the last value wins, so the failing constraint's own
+/// parameters should come last.
+pub(crate) fn render_message(error: SchemaError, params: &[(&str, String)]) -> SchemaError {
+    render_message_in(error, params, PluralRules::current()).0
+}
+
+/// Renders the placeholders in an error's message, choosing plural forms
+/// by `rules` rather than the message locale.
+///
+/// Also returns whether every placeholder was rendered, rather than left
+/// as written.
+pub(crate) fn render_message_in(
+    mut error: SchemaError,
+    params: &[(&str, String)],
+    rules: PluralRules,
+) -> (SchemaError, bool) {
+    if !error.message.contains(['{', '}']) {
+        return (error, true);
+    }
+    let lookup = |name: &str| -> Option<String> {
+        if let Some((_, value)) = params.iter().rev().find(|(key, _)| *key == name) {
+            return Some(value.clone());
+        }
+        match name {
+            "field" => Some(match error.path.last() {
+                Some(PathSegment::Field(name)) => name.clone(),
+                Some(PathSegment::Index(index)) => index.to_string(),
+                None => "value".to_string(),
+            }),
+            "path" if error.path.is_root() => Some("(root)".to_string()),
+            "path" => Some(error.path.to_string()),
+            "got" => error.got.clone(),
+            "expected" => error.expected.clone(),
+            _ => None,
+        }
+    };
+    let renderer = Renderer {
+        lookup: &lookup,
+        rules,
+        complete: Cell::new(true),
+    };
+    let message = renderer.render(&error.message, None);
+    let complete = renderer.complete.get();
+    error.message = message;
+    (error, complete)
+}
+
+struct Renderer<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    rules: PluralRules,
+    /// Cleared when a placeholder is left as written.
+    complete: Cell<bool>,
+}
+
+impl Renderer<'_> {
+    /// Replaces each placeholder in `template` for which there is a value.
+    ///
+    /// `number` is what `#` stands for inside a plural branch.
+    fn render(&self, template: &str, number: Option<&str>) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find(['{', '}', '#']) {
+            out.push_str(&rest[..start]);
+            let tail = &rest[start..];
+            if tail.starts_with("{{") || tail.starts_with("}}") {
+                out.push_str(&tail[..1]);
+                rest = &tail[2..];
+                continue;
+            }
+            if let Some(after) = tail.strip_prefix('#') {
+                out.push_str(number.unwrap_or("#"));
+                rest = after;
+                continue;
+            }
+            let rendered = tail
+                .strip_prefix('{')
+                .and_then(|inner| matching_brace(inner).map(|end| &inner[..end]))
+                .and_then(|inner| self.placeholder(inner, number).map(|value| (inner, value)));
+            match rendered {
+                Some((inner, value)) => {
+                    out.push_str(&value);
+                    rest = &tail[inner.len() + 2..];
+                }
+                None => {
+                    if tail.starts_with('{') {
+                        self.complete.set(false);
+                    }
+                    out.push_str(&tail[..1]);
+                    rest = &tail[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Renders the inside of a `{...}` placeholder, or returns `None` to
+    /// leave it as written.
+    fn placeholder(&self, inner: &str, number: Option<&str>) -> Option<String> {
+        let mut parts = inner.splitn(3, ',');
+        let name = parts.next()?;
+        let Some(kind) = parts.next() else {
+            return (self.lookup)(name);
+        };
+        let value = (self.lookup)(name.trim())?;
+        let branches = branches(parts.next()?)?;
+        match kind.trim() {
+            "plural" => {
+                let exact = format!("={}", value);
+                let category = value
+                    .parse::<i64>()
+                    .map(|n| self.rules.category(n.unsigned_abs()).keyword())
+                    .unwrap_or("other");
+                let body = find_branch(&branches, &exact)
+                    .or_else(|| find_branch(&branches, category))
+                    .or_else(|| find_branch(&branches, "other"))?;
+                Some(self.render(body, Some(&value)))
+            }
+            "select" => {
+                let body =
+                    find_branch(&branches, &value).or_else(|| find_branch(&branches, "other"))?;
+                Some(self.render(body, number))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the names of the placeholders in `template`, including those in
+/// plural and select branches, or `None` if its braces are unbalanced.
+pub(crate) fn placeholder_names(template: &str) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        let tail = &rest[start..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            rest = &tail[2..];
+            continue;
+        }
+        let inner = tail.strip_prefix('{')?;
+        let end = matching_brace(inner)?;
+        let mut parts = inner[..end].splitn(3, ',');
+        names.push(parts.next()?.trim().to_string());
+        if let (Some(_), Some(forms)) = (parts.next(), parts.next()) {
+            for (_, body) in branches(forms)? {
+                names.extend(placeholder_names(body)?);
+            }
+        }
+        rest = &inner[end + 1..];
+    }
+    Some(names)
+}
+
+/// Returns the index of the `}` closing a `{` just before `s`.
+fn matching_brace(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `key {body} key {body} ...`.
+fn branches(s: &str) -> Option<Vec<(&str, &str)>> {
+    let mut branches = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let open = rest.find('{')?;
+        let key = rest[..open].trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let end = matching_brace(&rest[open + 1..])?;
+        branches.push((key, &rest[open + 1..open + 1 + end]));
+        rest = rest[open + end + 2..].trim_start();
+    }
+    Some(branches)
+}
+
+fn find_branch<'a>(branches: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, body)| *body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::JsonPath;
+
+    #[test]
+    fn test_render_placeholders() {
+        let error = SchemaError::new(
+            JsonPath::root().push_field("user").push_field("age"),
+            "{field} at {path} must be between {min} and {max}, got {got}",
+        )
+        .with_got("150");
+        let params = [("min", "0".to_string()), ("max", "120".to_string())];
+
+        assert_eq!(
+            render_message(error, &params).message,
+            "age at user.age must be between 0 and 120, got 150"
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_and_unknown_placeholders() {
+        let error = SchemaError::new(JsonPath::root(), "{{literal}} {unknown} {field} {");
+        assert_eq!(
+            render_message(error, &[]).message,
+            "{literal} {unknown} value {"
+        );
+    }
+
+    #[test]
+    fn test_last_parameter_wins() {
+        let error = SchemaError::new(JsonPath::root().push_index(3), "{field}: {max}");
+        let params = [("max", "10".to_string()), ("max", "5".to_string())];
+        assert_eq!(render_message(error, &params).message, "3: 5");
+    }
+
+    fn render_with(rules: PluralRules, template: &str, params: &[(&str, &str)]) -> String {
+        let lookup = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        Renderer {
+            lookup: &lookup,
+            rules,
+            complete: Cell::new(true),
+        }
+        .render(template, None)
+    }
+
+    #[test]
+    fn test_plural_placeholders() {
+        let template = "{n, plural, =0 {no items} one {# item} other {# items}}";
+        let english = |n| render_with(PluralRules::English, template, &[("n", n)]);
+        assert_eq!(english("0"), "no items");
+        assert_eq!(english("1"), "1 item");
+        assert_eq!(english("21"), "21 items");
+
+        let template = "{n, plural, one {# файл} few {# файла} many {# файлов} other {# файла}}";
+        let russian = |n| render_with(PluralRules::EastSlavic, template, &[("n", n)]);
+        assert_eq!(russian("1"), "1 файл");
+        assert_eq!(russian("3"), "3 файла");
+        assert_eq!(russian("11"), "11 файлов");
+        assert_eq!(russian("21"), "21 файл");
+
+        // Missing categories fall back to `other`
+        assert_eq!(
+            render_with(PluralRules::Arabic, "{n, plural, other {#}}", &[("n", "2")]),
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_select_and_nested_placeholders() {
+        let template =
+            "at most {max, plural, one {# {unit, select, bytes {byte} other {character}}} \
+                        other {# {unit, select, bytes {bytes} other {characters}}}} for {field}";
+        let params = [("max", "1"), ("unit", "bytes"), ("field", "name")];
+        assert_eq!(
+            render_with(PluralRules::English, template, &params),
+            "at most 1 byte for name"
+        );
+        let params = [("max", "8"), ("unit", "chars"), ("field", "name")];
+        assert_eq!(
+            render_with(PluralRules::English, template, &params),
+            "at most 8 characters for name"
+        );
+
+        // Malformed and unknown forms are left as written
+        assert_eq!(
+            render_with(PluralRules::English, "{n, plural, one}", &[("n", "1")]),
+            "{n, plural, one}"
+        );
+        assert_eq!(
+            render_with(PluralRules::English, "^[a-z]{2,}$ #1", &[]),
+            "^[a-z]{2,}$ #1"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Concurrent validation with bounded parallelism.
+//!
+//! [`ValidationExecutor`] validates many documents against a registry on a
+//! dedicated thread pool, so a burst of large documents cannot take over the
+//! rest of the process. Each job can be given a timeout, a job that panics
+//! fails on its own, and results come back in submission order.
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde_json::Value;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use stillwater::Validation;
+
+use crate::error::SchemaErrors;
+use crate::registry::{RegistryError, SchemaRegistry};
+use crate::validation::ValidationContext;
+
+/// The outcome of one job run by a [`ValidationExecutor`].
+pub type JobResult = Result<Validation<Value, SchemaErrors>, ExecutorError>;
+
+/// Validates batches of `(schema name, value)` jobs on a bounded thread pool.
+///
+/// At most `max_concurrency` jobs run at once; the rest wait in a queue.
+/// All jobs in a batch share one [`ValidationContext`], so unresolved
+/// references are diagnosed once per batch and any failure hook and
+/// correlation id set with [`with_context`](Self::with_context) apply to
+/// every job.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Schema, SchemaRegistry, ValidationExecutor};
+/// use serde_json::json;
+/// use std::time::Duration;
+///
+/// let registry = SchemaRegistry::new();
+/// registry.register("Age", Schema::integer().non_negative()).unwrap();
+///
+/// let executor = ValidationExecutor::new(&registry, 4).with_timeout(Duration::from_secs(1));
+/// let results = executor.run(vec![
+///     ("Age", json!(30)),
+///     ("Age", json!(-1)),
+///     ("Missing", json!(1)),
+/// ]);
+///
+/// assert!(results[0].as_ref().unwrap().is_success());
+/// assert!(results[1].as_ref().unwrap().is_failure());
+/// assert!(results[2].is_err());
+/// ```
+pub struct ValidationExecutor {
+    registry: SchemaRegistry,
+    pool: ThreadPool,
+    timeout: Option<Duration>,
+    context: Option<ValidationContext>,
+}
+
+impl ValidationExecutor {
+    /// Creates an executor running at most `max_concurrency` jobs at once.
+    ///
+    /// A `max_concurrency` of zero is treated as one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system refuses to create the worker threads.
+    pub fn new(registry: &SchemaRegistry, max_concurrency: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(max_concurrency.max(1))
+            .thread_name(|i| format!("postmortem-validate-{}", i))
+            .build()
+            .expect("failed to create validation thread pool");
+
+        Self {
+            registry: registry.clone(),
+            pool,
+            timeout: None,
+            context: None,
+        }
+    }
+
+    /// Sets how long a job may run before it is reported as timed out.
+    ///
+    /// The time is measured from when the job starts, not from when it was
+    /// submitted, so queued jobs are not penalized for waiting. Validation
+    /// cannot be interrupted: a timed-out job keeps its worker until it
+    /// finishes, and its result is discarded.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Runs every batch with `context`, e.g. one carrying a correlation id
+    /// and failure hook.
+    ///
+    /// The context should be created from the same registry, with
+    /// [`SchemaRegistry::context`]. Without one, each batch gets a fresh
+    /// context.
+    pub fn with_context(mut self, context: ValidationContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Returns the maximum number of jobs run at once.
+    pub fn max_concurrency(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Validates each `(schema name, value)` job and returns the results in
+    /// submission order.
+    ///
+    /// A job fails with [`ExecutorError::Registry`] if its schema is not
+    /// registered, [`ExecutorError::TimedOut`] if it exceeds the timeout,
+    /// and [`ExecutorError::Panicked`] if validation panics. Other jobs are
+    /// unaffected.
+    pub fn run<I, S>(&self, jobs: I) -> Vec<JobResult>
+    where
+        I: IntoIterator<Item = (S, Value)>,
+        S: Into<String>,
+    {
+        let context = self
+            .context
+            .clone()
+            .unwrap_or_else(|| self.registry.context());
+        let (tx, rx) = mpsc::channel();
+        let mut names = Vec::new();
+        let mut starts: Vec<Arc<OnceLock<Instant>>> = Vec::new();
+
+        for (index, (name, value)) in jobs.into_iter().enumerate() {
+            let name: String = name.into();
+            let started = Arc::new(OnceLock::new());
+            names.push(name.clone());
+            starts.push(Arc::clone(&started));
+
+            let (tx, registry, context) = (tx.clone(), self.registry.clone(), context.clone());
+            self.pool.spawn(move || {
+                let _ = started.set(Instant::now());
+                let result = catch_unwind(AssertUnwindSafe(|| {
+                    registry.validate_with_context(&name, &value, &context)
+                }));
+                let result = match result {
+                    Ok(result) => result.map_err(ExecutorError::Registry),
+                    Err(_) => Err(ExecutorError::Panicked(name)),
+                };
+                // The receiver is gone only if the batch was abandoned
+                let _ = tx.send((index, result));
+            });
+        }
+        drop(tx);
+
+        let mut results: Vec<Option<JobResult>> = names.iter().map(|_| None).collect();
+        let mut pending = results.len();
+
+        while pending > 0 {
+            let received = match self.timeout {
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(timeout) => {
+                    // Wait until the earliest running job is due; if none
+                    // has started yet, check again after one timeout
+                    let deadline = starts
+                        .iter()
+                        .zip(&results)
+                        .filter(|(_, result)| result.is_none())
+                        .filter_map(|(started, _)| started.get().map(|at| *at + timeout))
+                        .min()
+                        .unwrap_or_else(|| Instant::now() + timeout);
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+            };
+
+            match received {
+                Ok((index, result)) => {
+                    // A job that already timed out keeps its timeout error
+                    if results[index].is_none() {
+                        results[index] = Some(result);
+                        pending -= 1;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let timeout = self.timeout.unwrap_or_default();
+                    let now = Instant::now();
+                    for (index, started) in starts.iter().enumerate() {
+                        let expired = started.get().is_some_and(|at| now >= *at + timeout);
+                        if results[index].is_none() && expired {
+                            results[index] =
+                                Some(Err(ExecutorError::TimedOut(names[index].clone(), timeout)));
+                            pending -= 1;
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        results
+            .into_iter()
+            .zip(names)
+            .map(|(result, name)| result.unwrap_or(Err(ExecutorError::Panicked(name))))
+            .collect()
+    }
+}
+
+/// Errors for individual jobs run by a [`ValidationExecutor`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ExecutorError {
+    /// The job's schema could not be looked up.
+    #[error(transparent)]
+    Registry(RegistryError),
+
+    /// The job ran longer than the executor's timeout.
+    #[error("validation against '{0}' timed out after {1:?}")]
+    TimedOut(String, Duration),
+
+    /// Validation panicked.
+    #[error("validation against '{0}' panicked")]
+    Panicked(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    fn sleepy_registry(delay: Duration) -> SchemaRegistry {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Sleepy",
+                Schema::string().custom(move |_, _| {
+                    thread::sleep(delay);
+                    Validation::Success(())
+                }),
+            )
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_results_in_submission_order() {
+        let registry = SchemaRegistry::new();
+        registry
+            .register("Age", Schema::integer().non_negative())
+            .unwrap();
+        let executor = ValidationExecutor::new(&registry, 3);
+
+        let jobs: Vec<(&str, Value)> = (-10..10).map(|n| ("Age", json!(n))).collect();
+        let results = executor.run(jobs);
+
+        assert_eq!(results.len(), 20);
+        for (n, result) in (-10..10).zip(&results) {
+            assert_eq!(result.as_ref().unwrap().is_success(), n >= 0, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_concurrency_limit() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (r, p) = (Arc::clone(&running), Arc::clone(&peak));
+
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Tracked",
+                Schema::string().custom(move |_, _| {
+                    let now = r.fetch_add(1, Ordering::SeqCst) + 1;
+                    p.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    r.fetch_sub(1, Ordering::SeqCst);
+                    Validation::Success(())
+                }),
+            )
+            .unwrap();
+
+        let executor = ValidationExecutor::new(&registry, 2);
+        assert_eq!(executor.max_concurrency(), 2);
+
+        let results = executor.run((0..12).map(|_| ("Tracked", json!("x"))));
+        assert!(results.iter().all(|r| r.as_ref().unwrap().is_success()));
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_timeout_is_per_job() {
+        let registry = sleepy_registry(Duration::from_millis(300));
+        registry
+            .register("Quick", Schema::string().min_len(1))
+            .unwrap();
+        let executor =
+            ValidationExecutor::new(&registry, 2).with_timeout(Duration::from_millis(50));
+
+        let started = Instant::now();
+        let results = executor.run(vec![("Sleepy", json!("a")), ("Quick", json!("b"))]);
+
+        assert!(started.elapsed() < Duration::from_millis(300));
+        assert!(matches!(
+            &results[0],
+            Err(ExecutorError::TimedOut(name, _)) if name == "Sleepy"
+        ));
+        assert!(results[1].as_ref().unwrap().is_success());
+    }
+
+    #[test]
+    fn test_queued_jobs_are_not_timed_out_while_waiting() {
+        let registry = sleepy_registry(Duration::from_millis(30));
+        let executor =
+            ValidationExecutor::new(&registry, 1).with_timeout(Duration::from_millis(200));
+
+        // Ten jobs in sequence take longer than one timeout in total
+        let results = executor.run((0..10).map(|_| ("Sleepy", json!("a"))));
+        assert!(results.iter().all(|r| r.as_ref().unwrap().is_success()));
+    }
+
+    #[test]
+    fn test_panicking_job_is_isolated() {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Boom",
+                Schema::string().custom(|s, _| {
+                    if s == "boom" {
+                        panic!("validator bug");
+                    }
+                    Validation::Success(())
+                }),
+            )
+            .unwrap();
+        let executor = ValidationExecutor::new(&registry, 2);
+
+        let results = executor.run(vec![
+            ("Boom", json!("fine")),
+            ("Boom", json!("boom")),
+            ("Missing", json!("fine")),
+        ]);
+
+        assert!(results[0].as_ref().unwrap().is_success());
+        assert!(matches!(&results[1], Err(ExecutorError::Panicked(_))));
+        assert!(matches!(
+            &results[2],
+            Err(ExecutorError::Registry(RegistryError::SchemaNotFound(_)))
+        ));
+    }
+}
@@ -0,0 +1,738 @@
+//! Changelogs between versions of a schema registry.
+//!
+//! [`diff`] compares two registry bundles, as exported by
+//! [`SchemaRegistry::to_json_schema`](crate::SchemaRegistry::to_json_schema),
+//! and reports added and removed schemas and fields, tightened and loosened
+//! constraints, and renamed enum values. The resulting [`Changelog`] renders
+//! as Markdown for API release notes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use postmortem::{Schema, SchemaRegistry};
+//!
+//! let v1 = SchemaRegistry::new();
+//! v1.register("User", Schema::object()
+//!     .field("name", Schema::string().min_len(1))
+//!     .field("role", Schema::string().one_of(["admin", "member"])),
+//! ).unwrap();
+//!
+//! let v2 = SchemaRegistry::new();
+//! v2.register("User", Schema::object()
+//!     .field("name", Schema::string().min_len(3))
+//!     .field("role", Schema::string().one_of(["admin", "user"]))
+//!     .optional("email", Schema::string().email()),
+//! ).unwrap();
+//!
+//! let markdown = v2.changelog_since(&v1).to_markdown();
+//! let lines: Vec<&str> = markdown.lines().collect();
+//! assert_eq!(lines, [
+//!     "### Added",
+//!     "",
+//!     "- `User.email`: optional field added",
+//!     "",
+//!     "### Changed",
+//!     "",
+//!     "- `User.name`: minimum length tightened from 1 to 3",
+//!     "- `User.role`: enum value `member` renamed to `user`",
+//! ]);
+//! ```
+
+use serde_json::{Map, Value};
+use std::fmt::{self, Display};
+
+/// The kind of a [`SchemaChange`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// A schema or field was added.
+    Added,
+    /// A schema or field was removed.
+    Removed,
+    /// A constraint now rejects values it used to accept.
+    Tightened,
+    /// A constraint now accepts values it used to reject.
+    Loosened,
+    /// An enum value was replaced by another.
+    Renamed,
+    /// Any other change, such as a new type or format.
+    Changed,
+}
+
+/// A single difference between two versions of a registry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaChange {
+    /// The kind of change.
+    pub kind: ChangeKind,
+    /// Where the change is: a schema name followed by field names, with
+    /// `[]` for array items, e.g. `User.address.zip` or `User.tags[]`.
+    pub location: String,
+    /// A human-readable description of the change.
+    pub description: String,
+}
+
+impl SchemaChange {
+    fn new(kind: ChangeKind, location: &str, description: impl Into<String>) -> Self {
+        Self {
+            kind,
+            location: location.to_string(),
+            description: description.into(),
+        }
+    }
+}
+
+/// The changes between two versions of a registry.
+///
+/// Changes are ordered by schema name, then by where they occur within the
+/// schema. `Display` renders the same Markdown as [`Changelog::to_markdown`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Changelog {
+    changes: Vec<SchemaChange>,
+}
+
+impl Changelog {
+    /// Returns the changes in order.
+    pub fn changes(&self) -> &[SchemaChange] {
+        &self.changes
+    }
+
+    /// Returns `true` if the two versions describe the same schemas.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Renders the changelog as Markdown.
+    ///
+    /// Changes are grouped under `### Added`, `### Removed`, and
+    /// `### Changed` headings, matching the Keep a Changelog sections, so the
+    /// output can be pasted under a release heading. Empty sections are
+    /// left out.
+    pub fn to_markdown(&self) -> String {
+        let sections = [
+            ("Added", &[ChangeKind::Added][..]),
+            ("Removed", &[ChangeKind::Removed][..]),
+            (
+                "Changed",
+                &[
+                    ChangeKind::Tightened,
+                    ChangeKind::Loosened,
+                    ChangeKind::Renamed,
+                    ChangeKind::Changed,
+                ][..],
+            ),
+        ];
+
+        let mut out = String::new();
+        for (heading, kinds) in sections {
+            let bullets: Vec<String> = self
+                .changes
+                .iter()
+                .filter(|c| kinds.contains(&c.kind))
+                .map(|c| format!("- `{}`: {}\n", c.location, c.description))
+                .collect();
+            if bullets.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("### {}\n\n", heading));
+            out.extend(bullets);
+        }
+
+        if out.is_empty() {
+            out.push_str("No schema changes.\n");
+        }
+        out
+    }
+}
+
+impl Display for Changelog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_markdown())
+    }
+}
+
+/// Compares two registry bundles and returns the changes from `old` to `new`.
+///
+/// Both values are JSON Schema documents with the registered schemas under
+/// `$defs`, as produced by
+/// [`SchemaRegistry::to_json_schema`](crate::SchemaRegistry::to_json_schema).
+/// Bundles saved from earlier releases can be loaded back with
+/// `serde_json` and compared with the current registry.
+///
+/// When an enum loses and gains the same number of values, the values are
+/// paired in order and reported as renames; otherwise they are reported as
+/// removals and additions.
+pub fn diff(old: &Value, new: &Value) -> Changelog {
+    let empty = Map::new();
+    let old_defs = old
+        .get("$defs")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let new_defs = new
+        .get("$defs")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+
+    let mut changes = Vec::new();
+    let mut names: Vec<&String> = old_defs.keys().chain(new_defs.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        match (old_defs.get(name), new_defs.get(name)) {
+            (Some(old), Some(new)) => diff_schema(name, old, new, &mut changes),
+            (Some(_), None) => changes.push(SchemaChange::new(
+                ChangeKind::Removed,
+                name,
+                "schema removed",
+            )),
+            (None, Some(_)) => {
+                changes.push(SchemaChange::new(ChangeKind::Added, name, "schema added"))
+            }
+            (None, None) => {}
+        }
+    }
+
+    Changelog { changes }
+}
+
+/// Bound keywords, and whether a lower value rejects more.
+const BOUNDS: &[(&str, &str, bool)] = &[
+    ("minLength", "minimum length", false),
+    ("maxLength", "maximum length", true),
+    ("minimum", "minimum", false),
+    ("exclusiveMinimum", "exclusive minimum", false),
+    ("maximum", "maximum", true),
+    ("exclusiveMaximum", "exclusive maximum", true),
+    ("minItems", "minimum items", false),
+    ("maxItems", "maximum items", true),
+];
+
+/// Keywords that restrict values, but not in an order that can be compared.
+const RESTRICTIONS: &[(&str, &str)] = &[
+    ("format", "format"),
+    ("pattern", "pattern"),
+    ("contentEncoding", "encoding"),
+    ("contentMediaType", "media type"),
+];
+
+/// Keywords compared elsewhere, or not compared structurally.
+const HANDLED: &[&str] = &[
+    "type",
+    "$ref",
+    "enum",
+    "uniqueItems",
+    "properties",
+    "required",
+    "additionalProperties",
+    "items",
+    "contentSchema",
+];
+
+fn diff_schema(location: &str, old: &Value, new: &Value, changes: &mut Vec<SchemaChange>) {
+    if old == new {
+        return;
+    }
+
+    // Constraints under a different type or reference are not comparable
+    for (key, label) in [("type", "type"), ("$ref", "reference")] {
+        if old.get(key) != new.get(key) {
+            changes.push(SchemaChange::new(
+                ChangeKind::Changed,
+                location,
+                format!(
+                    "{} changed from {} to {}",
+                    label,
+                    describe(old.get(key)),
+                    describe(new.get(key))
+                ),
+            ));
+            return;
+        }
+    }
+
+    let before = changes.len();
+
+    for &(key, label, lower_is_tighter) in BOUNDS {
+        diff_bound(location, key, label, lower_is_tighter, old, new, changes);
+    }
+
+    for &(key, label) in RESTRICTIONS {
+        let change = match (old.get(key), new.get(key)) {
+            (a, b) if a == b => continue,
+            (None, Some(b)) => (
+                ChangeKind::Tightened,
+                format!("{} {} added", label, code(b)),
+            ),
+            (Some(a), None) => (
+                ChangeKind::Loosened,
+                format!("{} {} removed", label, code(a)),
+            ),
+            (a, b) => (
+                ChangeKind::Changed,
+                format!("{} changed from {} to {}", label, describe(a), describe(b)),
+            ),
+        };
+        changes.push(SchemaChange::new(change.0, location, change.1));
+    }
+
+    let unique = |v: &Value| v.get("uniqueItems") == Some(&Value::Bool(true));
+    match (unique(old), unique(new)) {
+        (false, true) => changes.push(SchemaChange::new(
+            ChangeKind::Tightened,
+            location,
+            "items must now be unique",
+        )),
+        (true, false) => changes.push(SchemaChange::new(
+            ChangeKind::Loosened,
+            location,
+            "items no longer need to be unique",
+        )),
+        _ => {}
+    }
+
+    diff_enum(location, old.get("enum"), new.get("enum"), changes);
+    diff_properties(location, old, new, changes);
+
+    match (old.get("items"), new.get("items")) {
+        (Some(a), Some(b)) => diff_schema(&format!("{}[]", location), a, b, changes),
+        (a, b) if a != b => changes.push(SchemaChange::new(
+            ChangeKind::Changed,
+            location,
+            "item schema changed",
+        )),
+        _ => {}
+    }
+
+    match (old.get("contentSchema"), new.get("contentSchema")) {
+        (Some(a), Some(b)) => diff_schema(location, a, b, changes),
+        (None, Some(_)) => changes.push(SchemaChange::new(
+            ChangeKind::Tightened,
+            location,
+            "embedded JSON schema added",
+        )),
+        (Some(_), None) => changes.push(SchemaChange::new(
+            ChangeKind::Loosened,
+            location,
+            "embedded JSON schema removed",
+        )),
+        (None, None) => {}
+    }
+
+    // Anything left over, such as combinator branches, is reported by key
+    let unhandled = |v: &Value| -> Vec<String> {
+        let mut keys: Vec<String> = v
+            .as_object()
+            .map(|o| o.keys().cloned().collect())
+            .unwrap_or_default();
+        keys.retain(|k| {
+            !HANDLED.contains(&k.as_str())
+                && !BOUNDS.iter().any(|(b, ..)| b == k)
+                && !RESTRICTIONS.iter().any(|(r, _)| r == k)
+        });
+        keys
+    };
+    let mut keys = unhandled(old);
+    keys.extend(unhandled(new));
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        if old.get(&key) != new.get(&key) {
+            changes.push(SchemaChange::new(
+                ChangeKind::Changed,
+                location,
+                format!("`{}` changed", key),
+            ));
+        }
+    }
+
+    if changes.len() == before {
+        changes.push(SchemaChange::new(
+            ChangeKind::Changed,
+            location,
+            "definition changed",
+        ));
+    }
+}
+
+fn diff_bound(
+    location: &str,
+    key: &str,
+    label: &str,
+    lower_is_tighter: bool,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let old_bound = old.get(key);
+    let new_bound = new.get(key);
+    let (kind, description) = match (
+        old_bound.and_then(Value::as_f64),
+        new_bound.and_then(Value::as_f64),
+    ) {
+        (Some(a), Some(b)) if a == b => return,
+        (Some(a), Some(b)) => {
+            let kind = if (b < a) == lower_is_tighter {
+                ChangeKind::Tightened
+            } else {
+                ChangeKind::Loosened
+            };
+            let verb = if kind == ChangeKind::Tightened {
+                "tightened"
+            } else {
+                "loosened"
+            };
+            (
+                kind,
+                format!(
+                    "{} {} from {} to {}",
+                    label,
+                    verb,
+                    describe(old_bound),
+                    describe(new_bound)
+                ),
+            )
+        }
+        (None, Some(_)) => (
+            ChangeKind::Tightened,
+            format!("{} of {} added", label, describe(new_bound)),
+        ),
+        (Some(_), None) => (
+            ChangeKind::Loosened,
+            format!("{} of {} removed", label, describe(old_bound)),
+        ),
+        (None, None) => return,
+    };
+    changes.push(SchemaChange::new(kind, location, description));
+}
+
+fn diff_enum(
+    location: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    match (old.and_then(Value::as_array), new.and_then(Value::as_array)) {
+        (Some(old), Some(new)) => {
+            let removed: Vec<&Value> = old.iter().filter(|v| !new.contains(v)).collect();
+            let added: Vec<&Value> = new.iter().filter(|v| !old.contains(v)).collect();
+
+            if !removed.is_empty() && removed.len() == added.len() {
+                for (from, to) in removed.iter().zip(&added) {
+                    changes.push(SchemaChange::new(
+                        ChangeKind::Renamed,
+                        location,
+                        format!("enum value {} renamed to {}", code(from), code(to)),
+                    ));
+                }
+                return;
+            }
+
+            for value in removed {
+                changes.push(SchemaChange::new(
+                    ChangeKind::Tightened,
+                    location,
+                    format!("enum value {} removed", code(value)),
+                ));
+            }
+            for value in added {
+                changes.push(SchemaChange::new(
+                    ChangeKind::Loosened,
+                    location,
+                    format!("enum value {} added", code(value)),
+                ));
+            }
+        }
+        (None, Some(values)) => changes.push(SchemaChange::new(
+            ChangeKind::Tightened,
+            location,
+            format!("restricted to {}", code_list(values)),
+        )),
+        (Some(values), None) => changes.push(SchemaChange::new(
+            ChangeKind::Loosened,
+            location,
+            format!("no longer restricted to {}", code_list(values)),
+        )),
+        (None, None) => {}
+    }
+}
+
+fn diff_properties(location: &str, old: &Value, new: &Value, changes: &mut Vec<SchemaChange>) {
+    let empty = Map::new();
+    let old_props = old
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let new_props = new
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let required = |v: &Value, field: &str| {
+        v.get("required")
+            .and_then(Value::as_array)
+            .is_some_and(|r| r.iter().any(|f| f == field))
+    };
+
+    let mut fields: Vec<&String> = old_props.keys().chain(new_props.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    for field in fields {
+        let field_location = format!("{}.{}", location, field);
+        match (old_props.get(field), new_props.get(field)) {
+            (Some(a), Some(b)) => {
+                match (required(old, field), required(new, field)) {
+                    (false, true) => changes.push(SchemaChange::new(
+                        ChangeKind::Tightened,
+                        &field_location,
+                        "now required",
+                    )),
+                    (true, false) => changes.push(SchemaChange::new(
+                        ChangeKind::Loosened,
+                        &field_location,
+                        "now optional",
+                    )),
+                    _ => {}
+                }
+                diff_schema(&field_location, a, b, changes);
+            }
+            (Some(_), None) => changes.push(SchemaChange::new(
+                ChangeKind::Removed,
+                &field_location,
+                "field removed",
+            )),
+            (None, Some(_)) => {
+                let description = if required(new, field) {
+                    "required field added"
+                } else {
+                    "optional field added"
+                };
+                changes.push(SchemaChange::new(
+                    ChangeKind::Added,
+                    &field_location,
+                    description,
+                ));
+            }
+            (None, None) => {}
+        }
+    }
+
+    // An absent keyword allows any additional property
+    let closed = |v: &Value| v.get("additionalProperties") == Some(&Value::Bool(false));
+    match (
+        old.get("additionalProperties"),
+        new.get("additionalProperties"),
+    ) {
+        (a, b) if a == b => {}
+        _ if !closed(old) && closed(new) => changes.push(SchemaChange::new(
+            ChangeKind::Tightened,
+            location,
+            "additional properties no longer allowed",
+        )),
+        _ if closed(old) && !closed(new) => changes.push(SchemaChange::new(
+            ChangeKind::Loosened,
+            location,
+            "additional properties now allowed",
+        )),
+        (Some(a), Some(b)) if a.is_object() && b.is_object() => {
+            diff_schema(&format!("{}.*", location), a, b, changes)
+        }
+        _ => changes.push(SchemaChange::new(
+            ChangeKind::Changed,
+            location,
+            "additional properties schema changed",
+        )),
+    }
+}
+
+/// Formats an optional keyword value for a description.
+fn describe(value: Option<&Value>) -> String {
+    match value {
+        None => "none".to_string(),
+        Some(Value::String(s)) => format!("`{}`", s),
+        Some(Value::Array(items)) => code_list(items),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// Formats a value as inline code, without quotes around strings.
+fn code(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("`{}`", s),
+        v => format!("`{}`", v),
+    }
+}
+
+fn code_list(values: &[Value]) -> String {
+    values.iter().map(code).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bundle(defs: Value) -> Value {
+        json!({ "$defs": defs })
+    }
+
+    fn descriptions(changelog: &Changelog) -> Vec<(ChangeKind, &str, &str)> {
+        changelog
+            .changes()
+            .iter()
+            .map(|c| (c.kind, c.location.as_str(), c.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_bundles() {
+        let v = bundle(json!({"Id": {"type": "integer"}}));
+        let changelog = diff(&v, &v);
+        assert!(changelog.is_empty());
+        assert_eq!(changelog.to_markdown(), "No schema changes.\n");
+    }
+
+    #[test]
+    fn test_schemas_added_and_removed() {
+        let old = bundle(json!({"A": {"type": "string"}, "B": {"type": "string"}}));
+        let new = bundle(json!({"B": {"type": "string"}, "C": {"type": "integer"}}));
+        assert_eq!(
+            descriptions(&diff(&old, &new)),
+            vec![
+                (ChangeKind::Removed, "A", "schema removed"),
+                (ChangeKind::Added, "C", "schema added"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let old = bundle(json!({"Port": {
+            "type": "integer", "minimum": 0, "maximum": 65535
+        }}));
+        let new = bundle(json!({"Port": {
+            "type": "integer", "minimum": 1, "exclusiveMaximum": 70000
+        }}));
+        assert_eq!(
+            descriptions(&diff(&old, &new)),
+            vec![
+                (
+                    ChangeKind::Tightened,
+                    "Port",
+                    "minimum tightened from 0 to 1"
+                ),
+                (ChangeKind::Loosened, "Port", "maximum of 65535 removed"),
+                (
+                    ChangeKind::Tightened,
+                    "Port",
+                    "exclusive maximum of 70000 added"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_change_skips_constraints() {
+        let old = bundle(json!({"Id": {"type": "integer", "minimum": 1}}));
+        let new = bundle(json!({"Id": {"type": "string", "minLength": 1}}));
+        assert_eq!(
+            descriptions(&diff(&old, &new)),
+            vec![(
+                ChangeKind::Changed,
+                "Id",
+                "type changed from `integer` to `string`"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_enum_changes() {
+        let old = bundle(json!({"S": {"type": "string", "enum": ["a", "b", "c"]}}));
+
+        let renamed = bundle(json!({"S": {"type": "string", "enum": ["a", "x", "c"]}}));
+        assert_eq!(
+            descriptions(&diff(&old, &renamed)),
+            vec![(ChangeKind::Renamed, "S", "enum value `b` renamed to `x`")]
+        );
+
+        let shrunk = bundle(json!({"S": {"type": "string", "enum": ["a"]}}));
+        assert_eq!(
+            descriptions(&diff(&old, &shrunk)),
+            vec![
+                (ChangeKind::Tightened, "S", "enum value `b` removed"),
+                (ChangeKind::Tightened, "S", "enum value `c` removed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_fields_and_items() {
+        let old = bundle(json!({"User": {
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "nick": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["name"]
+        }}));
+        let new = bundle(json!({"User": {
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "email": {"type": "string", "format": "email"},
+                "tags": {"type": "array", "items": {"type": "string", "maxLength": 20}}
+            },
+            "required": ["email"],
+            "additionalProperties": false
+        }}));
+        assert_eq!(
+            descriptions(&diff(&old, &new)),
+            vec![
+                (ChangeKind::Added, "User.email", "required field added"),
+                (ChangeKind::Loosened, "User.name", "now optional"),
+                (ChangeKind::Removed, "User.nick", "field removed"),
+                (
+                    ChangeKind::Tightened,
+                    "User.tags[]",
+                    "maximum length of 20 added"
+                ),
+                (
+                    ChangeKind::Tightened,
+                    "User",
+                    "additional properties no longer allowed"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_keywords() {
+        let old = bundle(json!({"V": {"oneOf": [{"type": "string"}]}}));
+        let new = bundle(json!({"V": {"oneOf": [{"type": "integer"}]}}));
+        assert_eq!(
+            descriptions(&diff(&old, &new)),
+            vec![(ChangeKind::Changed, "V", "`oneOf` changed")]
+        );
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let old = bundle(json!({"A": {"type": "string"}, "B": {"type": "string"}}));
+        let new = bundle(json!({
+            "B": {"type": "string", "minLength": 1},
+            "C": {"type": "string"}
+        }));
+        assert_eq!(
+            diff(&old, &new).to_string(),
+            "### Added\n\n\
+             - `C`: schema added\n\n\
+             ### Removed\n\n\
+             - `A`: schema removed\n\n\
+             ### Changed\n\n\
+             - `B`: minimum length of 1 added\n"
+        );
+    }
+}
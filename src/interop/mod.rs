@@ -1,8 +1,14 @@
 //! Interoperability with other schema formats.
 //!
 //! This module provides bidirectional conversion between postmortem schemas
-//! and industry-standard formats like JSON Schema.
+//! and industry-standard formats like JSON Schema, changelogs between
+//! exported versions of a registry, and example payloads generated from a
+//! registry for clients in other languages.
 
+pub mod changelog;
+pub mod corpus;
 pub mod json_schema;
 
+pub use changelog::{ChangeKind, Changelog, SchemaChange};
+pub use corpus::{Corpus, Example};
 pub use json_schema::ToJsonSchema;
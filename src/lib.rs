@@ -37,23 +37,59 @@
 //! assert!(result.is_failure());
 //! ```
 
+pub mod cache;
+pub mod compat;
+pub mod editor;
 pub mod error;
+pub mod executor;
 pub mod interop;
+pub mod metadata;
 pub mod path;
 pub mod registry;
 pub mod schema;
+pub mod session;
+pub mod suppression;
+pub mod testing;
+pub mod transform;
+pub mod tunable;
 pub mod validation;
 
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "db")]
+pub mod db;
 #[cfg(feature = "effect")]
 pub mod effect;
+#[cfg(feature = "tokio")]
+pub mod stream;
 
-pub use error::{SchemaError, SchemaErrors};
+pub use cache::{CacheStats, OutcomeCache};
+pub use error::{
+    plural_category, set_message_locale, set_value_previews, ConstraintInfo, ErrorFactory,
+    ErrorsExt, GotLimit, MessageCatalog, PluralCategory, Provenance, Repair, RepairKind,
+    RepairOutcome, RepairPolicy, SchemaError, SchemaErrors,
+};
+pub use executor::{ExecutorError, ValidationExecutor};
 pub use interop::ToJsonSchema;
+pub use metadata::{MetadataField, SchemaMetadata};
 pub use path::{JsonPath, PathSegment};
-pub use registry::{RegistryError, SchemaRegistry};
+pub use registry::{RegistryError, RegistryTransaction, SchemaRegistry};
+#[cfg(feature = "chrono")]
+pub use schema::HolidayCalendar;
+#[cfg(feature = "signatures")]
+pub use schema::SignatureAlgorithm;
 pub use schema::{
-    ArraySchema, CombinatorSchema, IntegerSchema, ObjectSchema, RefSchema, Schema, SchemaLike,
-    StringSchema, ValueValidator,
+    ArraySchema, CombinatorBuilder, CombinatorSchema, DependentCondition, FieldDoc, IntegerSchema,
+    KeyCase, LazySchema, LengthUnit, ObjectSchema, PatternLimits, Quorum, RefSchema, Schema,
+    SchemaDefinition, SchemaExt, SchemaLike, ScopedSchema, StringSchema, ValueValidator,
+};
+pub use session::{
+    BulkSummary, ErrorBaseline, ErrorGroup, ErrorSample, LookupSet, ValidationSession,
+};
+pub use suppression::{SuppressedError, Suppression, Suppressions};
+pub use transform::Transform;
+pub use tunable::{
+    set_tunable_source, FileTunables, Limit, Tunable, TunableError, TunableSource, Tunables,
 };
 
 /// Type alias for validation results using SchemaErrors
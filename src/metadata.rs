@@ -0,0 +1,186 @@
+//! Ownership and review metadata for registered schemas.
+//!
+//! A registry shared by many teams needs to say who to ask about each
+//! schema. [`SchemaMetadata`] records a schema's owner, team, description,
+//! version, and reviewers when it is registered with
+//! [`SchemaRegistry::register_with_metadata`](crate::SchemaRegistry::register_with_metadata).
+//! Platform teams can make it mandatory with
+//! [`SchemaRegistry::require_metadata`](crate::SchemaRegistry::require_metadata),
+//! or enforce their own rules with
+//! [`SchemaRegistry::with_registration_hook`](crate::SchemaRegistry::with_registration_hook).
+
+use serde_json::{json, Value};
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+/// A function that accepts a schema being registered, or rejects it with a
+/// reason.
+pub(crate) type RegistrationHook =
+    Arc<dyn Fn(&str, &SchemaMetadata) -> Result<(), String> + Send + Sync>;
+
+/// Who owns a registered schema, and how it was reviewed.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Schema, SchemaMetadata, SchemaRegistry};
+///
+/// let registry = SchemaRegistry::new();
+/// registry.register_with_metadata(
+///     "Invoice",
+///     Schema::object(),
+///     SchemaMetadata::new()
+///         .owner("ada@example.com")
+///         .team("billing")
+///         .description("An invoice sent to a customer")
+///         .version("2.1.0")
+///         .reviewer("grace@example.com"),
+/// ).unwrap();
+///
+/// let metadata = registry.metadata("Invoice").unwrap();
+/// assert_eq!(metadata.team.as_deref(), Some("billing"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaMetadata {
+    /// The person or group to contact about the schema.
+    pub owner: Option<String>,
+    /// The team that maintains the schema.
+    pub team: Option<String>,
+    /// What the schema describes.
+    pub description: Option<String>,
+    /// The schema's version, e.g. `2.1.0`.
+    pub version: Option<String>,
+    /// Who reviewed the schema.
+    pub reviewers: Vec<String>,
+}
+
+impl SchemaMetadata {
+    /// Creates empty metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the owner.
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Sets the team.
+    pub fn team(mut self, team: impl Into<String>) -> Self {
+        self.team = Some(team.into());
+        self
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the version.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Adds a reviewer.
+    pub fn reviewer(mut self, reviewer: impl Into<String>) -> Self {
+        self.reviewers.push(reviewer.into());
+        self
+    }
+
+    /// Returns whether `field` is set to something other than blank text.
+    pub fn has(&self, field: MetadataField) -> bool {
+        let set = |value: &Option<String>| value.as_ref().is_some_and(|v| !v.trim().is_empty());
+        match field {
+            MetadataField::Owner => set(&self.owner),
+            MetadataField::Team => set(&self.team),
+            MetadataField::Description => set(&self.description),
+            MetadataField::Version => set(&self.version),
+            MetadataField::Reviewers => self.reviewers.iter().any(|r| !r.trim().is_empty()),
+        }
+    }
+
+    /// Returns the metadata as a JSON object, omitting unset fields.
+    pub fn to_json(&self) -> Value {
+        let mut object = serde_json::Map::new();
+        for (key, value) in [
+            ("owner", &self.owner),
+            ("team", &self.team),
+            ("description", &self.description),
+            ("version", &self.version),
+        ] {
+            if let Some(value) = value {
+                object.insert(key.to_string(), json!(value));
+            }
+        }
+        if !self.reviewers.is_empty() {
+            object.insert("reviewers".to_string(), json!(self.reviewers));
+        }
+        Value::Object(object)
+    }
+}
+
+/// A field of [`SchemaMetadata`], for
+/// [`SchemaRegistry::require_metadata`](crate::SchemaRegistry::require_metadata).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MetadataField {
+    /// [`SchemaMetadata::owner`].
+    Owner,
+    /// [`SchemaMetadata::team`].
+    Team,
+    /// [`SchemaMetadata::description`].
+    Description,
+    /// [`SchemaMetadata::version`].
+    Version,
+    /// [`SchemaMetadata::reviewers`], which must name at least one.
+    Reviewers,
+}
+
+impl MetadataField {
+    /// Returns the field's name, e.g. `owner`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataField::Owner => "owner",
+            MetadataField::Team => "team",
+            MetadataField::Description => "description",
+            MetadataField::Version => "version",
+            MetadataField::Reviewers => "reviewers",
+        }
+    }
+}
+
+impl Display for MetadataField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_ignores_blank_values() {
+        let metadata = SchemaMetadata::new().owner("ada").team("  ").reviewer("");
+        assert!(metadata.has(MetadataField::Owner));
+        assert!(!metadata.has(MetadataField::Team));
+        assert!(!metadata.has(MetadataField::Version));
+        assert!(!metadata.has(MetadataField::Reviewers));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let metadata = SchemaMetadata::new()
+            .owner("ada")
+            .version("1.0.0")
+            .reviewer("grace");
+        assert_eq!(
+            metadata.to_json(),
+            json!({"owner": "ada", "version": "1.0.0", "reviewers": ["grace"]})
+        );
+        assert_eq!(SchemaMetadata::new().to_json(), json!({}));
+    }
+}
@@ -5,6 +5,8 @@
 
 use std::fmt::{self, Display};
 
+use serde_json::Value;
+
 /// A segment of a JSON path.
 ///
 /// Paths are built from segments that represent either field access or array indexing.
@@ -70,6 +72,43 @@ impl JsonPath {
         }
     }
 
+    /// Parses a path written the way it is displayed, e.g. `users[0].email`.
+    ///
+    /// The empty string is the root path. Returns `None` if the path is
+    /// malformed, such as an empty field name or an index that is not a
+    /// number. Field names containing `.` or `[` cannot be written this way.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::JsonPath;
+    ///
+    /// let path = JsonPath::parse("users[0].email").unwrap();
+    /// assert_eq!(path, JsonPath::root().push_field("users").push_index(0).push_field("email"));
+    /// assert!(JsonPath::parse("users..email").is_none());
+    /// ```
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut segments = Vec::new();
+        if s.is_empty() {
+            return Some(Self { segments });
+        }
+        for (i, part) in s.split('.').enumerate() {
+            let (name, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+            if !name.is_empty() {
+                segments.push(PathSegment::Field(name.to_string()));
+            } else if i > 0 || rest.is_empty() {
+                return None;
+            }
+            while !rest.is_empty() {
+                let inner = rest.strip_prefix('[')?;
+                let end = inner.find(']')?;
+                segments.push(PathSegment::Index(inner[..end].parse().ok()?));
+                rest = &inner[end + 1..];
+            }
+        }
+        Some(Self { segments })
+    }
+
     /// Returns a new path with a field segment appended.
     ///
     /// This method does not modify the original path; it returns a new one.
@@ -88,6 +127,13 @@ impl JsonPath {
         Self { segments }
     }
 
+    /// Returns a new path with all segments of `other` appended.
+    pub fn join(&self, other: &JsonPath) -> Self {
+        let mut segments = self.segments.clone();
+        segments.extend(other.segments.iter().cloned());
+        Self { segments }
+    }
+
     /// Returns true if this is the root path (no segments).
     pub fn is_root(&self) -> bool {
         self.segments.is_empty()
@@ -123,6 +169,62 @@ impl JsonPath {
     pub fn last(&self) -> Option<&PathSegment> {
         self.segments.last()
     }
+
+    /// Returns this path as a JSON Pointer (RFC 6901), e.g. `/users/0/email`.
+    ///
+    /// The root path is the empty pointer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::JsonPath;
+    ///
+    /// let path = JsonPath::root().push_field("users").push_index(0).push_field("a/b");
+    /// assert_eq!(path.to_pointer(), "/users/0/a~1b");
+    /// ```
+    pub fn to_pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Field(name) => {
+                    format!("/{}", name.replace('~', "~0").replace('/', "~1"))
+                }
+                PathSegment::Index(idx) => format!("/{}", idx),
+            })
+            .collect()
+    }
+
+    /// Returns the value this path points to within `value`, if it exists.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::JsonPath;
+    /// use serde_json::json;
+    ///
+    /// let value = json!({"users": [{"email": "a@example.com"}]});
+    /// let path = JsonPath::root().push_field("users").push_index(0).push_field("email");
+    ///
+    /// assert_eq!(path.resolve(&value), Some(&json!("a@example.com")));
+    /// ```
+    pub fn resolve<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                PathSegment::Field(name) => current.get(name.as_str()),
+                PathSegment::Index(idx) => current.get(*idx),
+            })
+    }
+
+    /// Returns the value at this path mutably, if it exists.
+    pub(crate) fn resolve_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                PathSegment::Field(name) => current.get_mut(name.as_str()),
+                PathSegment::Index(idx) => current.get_mut(*idx),
+            })
+    }
 }
 
 impl Display for JsonPath {
@@ -145,6 +247,7 @@ impl Display for JsonPath {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_root_path_is_empty() {
@@ -279,4 +382,36 @@ mod tests {
         let cloned = path.clone();
         assert_eq!(path, cloned);
     }
+
+    #[test]
+    fn test_join() {
+        let base = JsonPath::root().push_field("orders").push_index(2);
+        let relative = JsonPath::root().push_field("items").push_index(0);
+        assert_eq!(base.join(&relative).to_string(), "orders[2].items[0]");
+        assert_eq!(JsonPath::root().join(&base), base);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let value = json!({"users": [{"email": "a@example.com"}]});
+
+        assert_eq!(JsonPath::root().resolve(&value), Some(&value));
+        let path = JsonPath::root().push_field("users").push_index(0);
+        assert_eq!(
+            path.resolve(&value),
+            Some(&json!({"email": "a@example.com"}))
+        );
+        assert!(path.push_field("name").resolve(&value).is_none());
+        assert!(JsonPath::root().push_index(0).resolve(&value).is_none());
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        for text in ["", "user", "users[0].email", "[3]", "[0][1].a", "a.b[12]"] {
+            assert_eq!(JsonPath::parse(text).unwrap().to_string(), text);
+        }
+        for text in [".", "a.", ".a", "a[", "a[x]", "a[0]b", "a.[0]"] {
+            assert!(JsonPath::parse(text).is_none(), "{}", text);
+        }
+    }
 }
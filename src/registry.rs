@@ -5,18 +5,38 @@
 
 use parking_lot::RwLock;
 use serde_json::{json, Value};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use crate::error::SchemaErrors;
-use crate::path::JsonPath;
-use crate::schema::ValueValidator;
+use crate::cache::{OutcomeCache, Subject};
+use crate::error::{
+    rebuild_errors, ConstraintInfo, ErrorFactory, ErrorSettings, GotLimit, SchemaError,
+    SchemaErrors,
+};
+use crate::interop::changelog::{self, Changelog};
+use crate::interop::corpus::{self, Corpus};
+use crate::metadata::{MetadataField, RegistrationHook, SchemaMetadata};
+use crate::path::{JsonPath, PathSegment};
+use crate::schema::{FieldDoc, ValueValidator};
+use crate::transform::Transform;
 use crate::validation::{RegistryAccess, ValidationContext};
 use stillwater::Validation;
 
 /// Type alias for the schema storage map.
 type SchemaMap = Arc<RwLock<HashMap<String, Arc<dyn ValueValidator>>>>;
 
+/// Type alias for the transforms of registered schemas.
+type TransformMap = Arc<RwLock<HashMap<String, Arc<Transform>>>>;
+
+/// Type alias for the metadata of registered schemas.
+type MetadataMap = Arc<RwLock<HashMap<String, SchemaMetadata>>>;
+
+/// The id of the next registry created, so outcomes cached for one
+/// registry are never returned for a schema of the same name in another.
+static NEXT_REGISTRY_ID: AtomicU64 = AtomicU64::new(0);
+
 /// A thread-safe registry for storing and retrieving named schemas.
 ///
 /// The registry enables schema reuse through references. Schemas can be
@@ -48,16 +68,34 @@ type SchemaMap = Arc<RwLock<HashMap<String, Arc<dyn ValueValidator>>>>;
 /// ).unwrap();
 /// ```
 pub struct SchemaRegistry {
+    /// Shared by clones, which share the schemas too. A name never changes
+    /// schema once registered, so the id and a name identify a schema.
+    id: u64,
     schemas: SchemaMap,
+    transforms: TransformMap,
+    metadata: MetadataMap,
+    registration_hooks: Vec<RegistrationHook>,
     max_depth: usize,
+    error_factory: Option<ErrorFactory>,
+    doc_links: bool,
+    outcome_cache: Option<OutcomeCache>,
+    error_settings: ErrorSettings,
 }
 
 impl SchemaRegistry {
     /// Creates a new empty schema registry with default max depth (100).
     pub fn new() -> Self {
         Self {
+            id: NEXT_REGISTRY_ID.fetch_add(1, Ordering::Relaxed),
             schemas: Arc::new(RwLock::new(HashMap::new())),
+            transforms: Arc::new(RwLock::new(HashMap::new())),
+            metadata: Arc::new(RwLock::new(HashMap::new())),
+            registration_hooks: Vec::new(),
             max_depth: 100,
+            error_factory: None,
+            doc_links: false,
+            outcome_cache: None,
+            error_settings: ErrorSettings::DEFAULT,
         }
     }
 
@@ -80,13 +118,321 @@ impl SchemaRegistry {
         self
     }
 
+    /// Builds every error reported by this registry's validation methods
+    /// with `factory`.
+    ///
+    /// The factory sees each constraint's own error and the value that
+    /// failed, so one function can set the message style, codes, and
+    /// metadata for every schema in the registry. See [`ErrorFactory`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaError, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new().with_error_factory(|info, value, path| {
+    ///     SchemaError::new(path.clone(), format!("{} (got {})", info.message, value))
+    ///         .with_code(format!("acme.{}", info.code))
+    /// });
+    /// registry.register("User", Schema::object()
+    ///     .field("age", Schema::integer().non_negative())
+    /// ).unwrap();
+    ///
+    /// let errors = registry
+    ///     .validate("User", &json!({"age": -3}))
+    ///     .unwrap()
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "acme.non_negative");
+    /// assert!(errors.first().message.ends_with("(got -3)"));
+    /// ```
+    pub fn with_error_factory<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(ConstraintInfo<'_>, &Value, &JsonPath) -> SchemaError + Send + Sync + 'static,
+    {
+        self.error_factory = Some(Arc::new(factory));
+        self
+    }
+
+    /// Sets how much of a failing value the errors of this registry's
+    /// validations record.
+    ///
+    /// The limit applies to validations with the registry's own contexts,
+    /// including those from [`context`](Self::context); a context can
+    /// override it with [`ValidationContext::with_got_limit`]. The default
+    /// is [`GotLimit::default`]. See [`GotLimit`] for an example.
+    pub fn with_got_limit(mut self, limit: GotLimit) -> Self {
+        self.error_settings.got_limit = limit;
+        self
+    }
+
+    /// Caches the outcome of validating each distinct value.
+    ///
+    /// Validating a value identical to one seen before, against the same
+    /// schema and in the same [mode](ValidationContext::with_mode), returns
+    /// the cached outcome without validating again, which makes retried
+    /// webhook deliveries cheap. Values are compared by content, ignoring
+    /// the order of object keys. The error factory and doc links are applied
+    /// to cached outcomes as to fresh ones, and the warnings of the
+    /// validation are recorded on the context again. Outcomes that used a
+    /// [computed default](crate::ObjectSchema::default_with) are not cached.
+    ///
+    /// Registering a schema clears the cache, since it may resolve a
+    /// reference that previously failed. Changing a
+    /// [tunable limit](crate::tunable) starts a new
+    /// [generation](crate::TunableSource::generation) of outcomes, so
+    /// retried payloads are checked against the new limit. Outcomes are
+    /// cached separately for each [got limit](ValidationContext::with_got_limit).
+    /// They also depend on the process-wide message settings such as
+    /// [`set_value_previews`](crate::set_value_previews); clear the cache with
+    /// [`OutcomeCache::clear`] after changing them. Custom validators that
+    /// consult outside state, such as a database, should not be cached.
+    ///
+    /// See [`OutcomeCache`] for an example.
+    pub fn with_outcome_cache(mut self, cache: OutcomeCache) -> Self {
+        self.outcome_cache = Some(cache);
+        self
+    }
+
+    /// Runs `hook` on every schema registered from now on, rejecting the
+    /// registration if it returns an error.
+    ///
+    /// The hook is given the schema's name and [`SchemaMetadata`]; schemas
+    /// registered with [`register`](Self::register) have empty metadata.
+    /// Every hook must accept a schema for it to be registered. Hooks run
+    /// before the registry is locked, so they may read it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{RegistryError, Schema, SchemaMetadata, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new().with_registration_hook(|name, metadata| {
+    ///     match metadata.team.as_deref() {
+    ///         Some("billing") if !name.starts_with("Billing") => {
+    ///             Err("billing schemas are named Billing*".to_string())
+    ///         }
+    ///         _ => Ok(()),
+    ///     }
+    /// });
+    ///
+    /// let result = registry.register_with_metadata(
+    ///     "Invoice",
+    ///     Schema::object(),
+    ///     SchemaMetadata::new().team("billing"),
+    /// );
+    /// assert!(matches!(result, Err(RegistryError::Rejected { .. })));
+    /// ```
+    pub fn with_registration_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &SchemaMetadata) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.registration_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Rejects the registration of schemas whose metadata does not set
+    /// every one of `fields`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{MetadataField, Schema, SchemaMetadata, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new().require_metadata([
+    ///     MetadataField::Owner,
+    ///     MetadataField::Description,
+    ///     MetadataField::Version,
+    /// ]);
+    ///
+    /// let error = registry.register("Scratch", Schema::object()).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "schema 'Scratch' was rejected: missing required metadata: owner, description, version"
+    /// );
+    ///
+    /// registry.register_with_metadata(
+    ///     "User",
+    ///     Schema::object(),
+    ///     SchemaMetadata::new()
+    ///         .owner("identity@example.com")
+    ///         .description("A signed-up user")
+    ///         .version("1.0.0"),
+    /// ).unwrap();
+    /// ```
+    pub fn require_metadata<I>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = MetadataField>,
+    {
+        let fields: Vec<MetadataField> = fields.into_iter().collect();
+        self.with_registration_hook(move |_, metadata| {
+            let missing: Vec<&str> = fields
+                .iter()
+                .filter(|field| !metadata.has(**field))
+                .map(MetadataField::as_str)
+                .collect();
+            if missing.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("missing required metadata: {}", missing.join(", ")))
+            }
+        })
+    }
+
+    /// Validates a value against a schema, through the outcome cache if
+    /// there is one.
+    pub(crate) fn run(
+        &self,
+        schema_name: &str,
+        schema: &dyn ValueValidator,
+        value: &Value,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        let validate = |context: &ValidationContext| {
+            context
+                .error_settings()
+                .apply(|| schema.validate_value_with_context(value, &JsonPath::root(), context))
+        };
+        match &self.outcome_cache {
+            Some(cache) => cache.get_or_validate(
+                Subject::new(self.id, schema_name, context),
+                value,
+                context,
+                validate,
+            ),
+            None => validate(context),
+        }
+    }
+
+    /// Appends a link to each error on a documented field.
+    ///
+    /// Errors on fields whose [`FieldDoc`] has a link end with
+    /// `(see docs: <link>)`, so API responses can point clients at how to
+    /// fix the value. The link is also applied to errors rebuilt by the
+    /// [error factory](Self::with_error_factory).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{FieldDoc, Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new().with_doc_links();
+    /// registry.register("User", Schema::object()
+    ///     .field("email", Schema::string().email())
+    ///     .doc(FieldDoc::new("Where we send receipts")
+    ///         .link("https://docs.example.com/users#email"))
+    /// ).unwrap();
+    ///
+    /// let errors = registry
+    ///     .validate("User", &json!({"email": "nope"}))
+    ///     .unwrap()
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     errors.first().message,
+    ///     "must be valid email (see docs: https://docs.example.com/users#email)"
+    /// );
+    /// ```
+    pub fn with_doc_links(mut self) -> Self {
+        self.doc_links = true;
+        self
+    }
+
+    /// Returns the documentation of a field of a registered schema.
+    ///
+    /// The path names the fields from the schema's root, separated by
+    /// dots, e.g. `address.street`. Array indices such as `items[0]` are
+    /// ignored, since every item shares the item schema's documentation, so
+    /// error paths can be passed as they are displayed. Fields of a
+    /// referenced schema are looked up in that schema by its own name.
+    ///
+    /// Returns `None` if the schema or field does not exist or the field is
+    /// not documented.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{FieldDoc, Schema, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("User", Schema::object()
+    ///     .field("email", Schema::string().email())
+    ///     .doc(FieldDoc::new("Where we send receipts"))
+    ///     .field("addresses", Schema::array(Schema::object()
+    ///         .field("zip", Schema::string())
+    ///         .doc(FieldDoc::new("Postal code").link("https://docs.example.com/zip"))))
+    /// ).unwrap();
+    ///
+    /// let doc = registry.doc_for_path("User", "email").unwrap();
+    /// assert_eq!(doc.description.as_deref(), Some("Where we send receipts"));
+    ///
+    /// let doc = registry.doc_for_path("User", "addresses[1].zip").unwrap();
+    /// assert_eq!(doc.link.as_deref(), Some("https://docs.example.com/zip"));
+    ///
+    /// assert!(registry.doc_for_path("User", "name").is_none());
+    /// ```
+    pub fn doc_for_path(&self, schema_name: &str, path: &str) -> Option<FieldDoc> {
+        let fields: Vec<&str> = path
+            .split('.')
+            .map(|segment| segment.split('[').next().unwrap_or(segment))
+            .filter(|name| !name.is_empty())
+            .collect();
+        self.get(schema_name)?.field_doc(&fields)
+    }
+
+    /// Rebuilds the errors of a failed validation with the error factory,
+    /// if there is one, and links them to their fields' documentation.
+    pub(crate) fn finish(
+        &self,
+        schema: &dyn ValueValidator,
+        result: Validation<Value, SchemaErrors>,
+        value: &Value,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        let errors = match result {
+            Validation::Failure(errors) => errors,
+            success => return success,
+        };
+        let errors = match &self.error_factory {
+            Some(factory) => context
+                .error_settings()
+                .apply(|| rebuild_errors(factory, errors, value, &JsonPath::root())),
+            None => errors,
+        };
+        if !self.doc_links {
+            return Validation::Failure(errors);
+        }
+        let linked = errors
+            .into_iter()
+            .map(|mut error| {
+                let fields: Vec<&str> = error
+                    .path
+                    .segments()
+                    .filter_map(|segment| match segment {
+                        PathSegment::Field(name) => Some(name.as_str()),
+                        PathSegment::Index(_) => None,
+                    })
+                    .collect();
+                if let Some(link) = schema.field_doc(&fields).and_then(|doc| doc.link) {
+                    error.message = format!("{} (see docs: {})", error.message, link);
+                }
+                error
+            })
+            .collect();
+        Validation::Failure(SchemaErrors::from_vec(linked))
+    }
+
     /// Registers a schema with the given name.
     ///
     /// Returns an error if a schema with the same name is already registered.
     ///
     /// # Errors
     ///
-    /// Returns `RegistryError::DuplicateName` if the name is already registered.
+    /// Returns `RegistryError::DuplicateName` if the name is already
+    /// registered, and `RegistryError::Rejected` if a
+    /// [registration hook](Self::with_registration_hook) rejects the schema.
     ///
     /// # Example
     ///
@@ -100,20 +446,208 @@ impl SchemaRegistry {
     /// assert!(registry.register("Email", Schema::string()).is_err());
     /// ```
     pub fn register<S>(&self, name: impl Into<String>, schema: S) -> Result<(), RegistryError>
+    where
+        S: ValueValidator + 'static,
+    {
+        self.register_with_metadata(name, schema, SchemaMetadata::new())
+    }
+
+    /// Registers a schema with the given name and ownership metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::DuplicateName` if the name is already
+    /// registered, and `RegistryError::Rejected` if a
+    /// [registration hook](Self::with_registration_hook) rejects the schema.
+    ///
+    /// See [`SchemaMetadata`] for an example.
+    pub fn register_with_metadata<S>(
+        &self,
+        name: impl Into<String>,
+        schema: S,
+        metadata: SchemaMetadata,
+    ) -> Result<(), RegistryError>
     where
         S: ValueValidator + 'static,
     {
         let name = name.into();
+        for hook in &self.registration_hooks {
+            if let Err(reason) = hook(&name, &metadata) {
+                return Err(RegistryError::Rejected { name, reason });
+            }
+        }
         let mut schemas = self.schemas.write();
 
         if schemas.contains_key(&name) {
             return Err(RegistryError::DuplicateName(name));
         }
 
+        self.metadata.write().insert(name.clone(), metadata);
         schemas.insert(name, Arc::new(schema));
+        if let Some(cache) = &self.outcome_cache {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Registers a batch of schemas atomically: either all of them are
+    /// registered, or none are.
+    ///
+    /// `build` adds schemas to a [`RegistryTransaction`]. When it returns,
+    /// the whole batch is checked before anything is registered:
+    ///
+    /// - each schema must pass the [registration hooks](Self::with_registration_hook)
+    /// - no name may be registered already or twice in the batch
+    /// - every reference from the batch must name a schema that is
+    ///   registered or in the batch, so schemas can refer to each other in
+    ///   any order
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found, as a single error or
+    /// `RegistryError::Multiple`, and leaves the registry unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{RegistryError, Schema, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.transaction(|tx| {
+    ///     tx.register("User", Schema::object().field("email", Schema::ref_("Email")));
+    ///     tx.register("Email", Schema::string());
+    /// }).unwrap();
+    /// assert_eq!(registry.names(), vec!["Email", "User"]);
+    ///
+    /// // A dangling reference rolls back the whole batch
+    /// let result = registry.transaction(|tx| {
+    ///     tx.register("Team", Schema::object());
+    ///     tx.register("Order", Schema::object().field("customer", Schema::ref_("Customer")));
+    /// });
+    /// assert!(matches!(result, Err(RegistryError::UnresolvedRefs { .. })));
+    /// assert!(registry.get("Team").is_none());
+    /// ```
+    pub fn transaction<F>(&self, build: F) -> Result<(), RegistryError>
+    where
+        F: FnOnce(&mut RegistryTransaction),
+    {
+        let mut tx = RegistryTransaction {
+            pending: Vec::new(),
+        };
+        build(&mut tx);
+
+        let mut errors = Vec::new();
+        for (name, _, metadata) in &tx.pending {
+            for hook in &self.registration_hooks {
+                if let Err(reason) = hook(name, metadata) {
+                    errors.push(RegistryError::Rejected {
+                        name: name.clone(),
+                        reason,
+                    });
+                    break;
+                }
+            }
+        }
+
+        // Check names and references under the write lock, so nothing can
+        // be registered between the checks and the commit
+        let mut schemas = self.schemas.write();
+        for (i, (name, _, _)) in tx.pending.iter().enumerate() {
+            if schemas.contains_key(name) || tx.pending[..i].iter().any(|(n, _, _)| n == name) {
+                errors.push(RegistryError::DuplicateName(name.clone()));
+            }
+        }
+        for (name, schema, _) in &tx.pending {
+            let mut refs = Vec::new();
+            schema.collect_refs(&mut refs);
+            refs.retain(|r| !schemas.contains_key(r) && !tx.pending.iter().any(|(n, _, _)| n == r));
+            refs.sort();
+            refs.dedup();
+            if !refs.is_empty() {
+                errors.push(RegistryError::UnresolvedRefs {
+                    name: name.clone(),
+                    refs,
+                });
+            }
+        }
+
+        match errors.len() {
+            0 => {}
+            1 => return Err(errors.remove(0)),
+            _ => return Err(RegistryError::Multiple(errors)),
+        }
+
+        let mut all_metadata = self.metadata.write();
+        for (name, schema, metadata) in tx.pending {
+            all_metadata.insert(name.clone(), metadata);
+            schemas.insert(name, schema);
+        }
+        if let Some(cache) = &self.outcome_cache {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Reshapes values validated against a registered schema with
+    /// `transform` before validating them.
+    ///
+    /// The transform applies to values validated by name, through
+    /// [`validate`](Self::validate) and its siblings, and errors point at
+    /// paths in the transformed value. It does not apply when the schema is
+    /// reached through a reference from another schema. Setting a transform
+    /// replaces the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::SchemaNotFound` if the schema name doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry, Transform};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Order", Schema::object()
+    ///     .field("order_id", Schema::string())
+    ///     .field("zip", Schema::string().min_len(5))).unwrap();
+    ///
+    /// // Acme spells the id differently and nests the zip code
+    /// registry.set_transform("Order", Transform::new()
+    ///     .rename("orderId", "order_id")
+    ///     .move_value("shipping.zip", "zip")).unwrap();
+    ///
+    /// let acme = json!({"orderId": "A-1", "shipping": {"zip": "02134"}});
+    /// let validated = registry.validate("Order", &acme).unwrap().into_result().unwrap();
+    /// assert_eq!(validated["order_id"], "A-1");
+    ///
+    /// // Errors point at the transformed payload
+    /// let acme = json!({"orderId": "A-2", "shipping": {"zip": "021"}});
+    /// let errors = registry.validate("Order", &acme).unwrap().into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "zip");
+    /// ```
+    pub fn set_transform(&self, name: &str, transform: Transform) -> Result<(), RegistryError> {
+        if !self.schemas.read().contains_key(name) {
+            return Err(RegistryError::SchemaNotFound(name.to_string()));
+        }
+        self.transforms
+            .write()
+            .insert(name.to_string(), Arc::new(transform));
         Ok(())
     }
 
+    /// Applies the transform of a schema, if it has one, to a value.
+    pub(crate) fn transformed<'a>(&self, schema_name: &str, value: &'a Value) -> Cow<'a, Value> {
+        match self.transforms.read().get(schema_name) {
+            Some(transform) => {
+                let mut value = value.clone();
+                transform.apply(&mut value);
+                Cow::Owned(value)
+            }
+            None => Cow::Borrowed(value),
+        }
+    }
+
     /// Retrieves a schema by name.
     ///
     /// Returns `None` if no schema with the given name is registered.
@@ -136,6 +670,31 @@ impl SchemaRegistry {
         self.schemas.read().get(name).cloned()
     }
 
+    /// Returns the metadata a schema was registered with, or `None` if it
+    /// is not registered.
+    pub fn metadata(&self, name: &str) -> Option<SchemaMetadata> {
+        self.metadata.read().get(name).cloned()
+    }
+
+    /// Returns the names of all registered schemas, sorted alphabetically.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{SchemaRegistry, Schema};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("User", Schema::object()).unwrap();
+    /// registry.register("Email", Schema::string()).unwrap();
+    ///
+    /// assert_eq!(registry.names(), vec!["Email", "User"]);
+    /// ```
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.schemas.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Validates that all schema references can be resolved.
     ///
     /// Returns a list of reference names that don't exist in the registry.
@@ -215,8 +774,101 @@ impl SchemaRegistry {
             .get(schema_name)
             .ok_or_else(|| RegistryError::SchemaNotFound(schema_name.to_string()))?;
 
-        let context = ValidationContext::new(Arc::new(self.clone()), self.max_depth);
-        Ok(schema.validate_value_with_context(value, &JsonPath::root(), &context))
+        let value = self.transformed(schema_name, value);
+        let context = self.context();
+        let result = self.run(schema_name, schema.as_ref(), &value, &context);
+        Ok(self.finish(schema.as_ref(), result, &value, &context))
+    }
+
+    /// Creates a validation context for this registry.
+    ///
+    /// Use it with [`validate_with_context`](Self::validate_with_context)
+    /// after adding a correlation id or failure hook.
+    pub fn context(&self) -> ValidationContext {
+        ValidationContext::new(Arc::new(self.clone()), self.max_depth)
+            .with_error_settings(self.error_settings)
+    }
+
+    /// Validates a value against a named schema with a caller-supplied
+    /// context.
+    ///
+    /// Behaves like [`validate`](Self::validate), and additionally applies
+    /// the context's [suppressions](ValidationContext::with_suppressions)
+    /// and calls its [failure hook](ValidationContext::on_failure) if the
+    /// value is invalid. Reusing one context across calls also shares its cache
+    /// of unresolved references.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::SchemaNotFound` if the schema name doesn't exist.
+    pub fn validate_with_context(
+        &self,
+        schema_name: &str,
+        value: &Value,
+        context: &ValidationContext,
+    ) -> Result<Validation<Value, SchemaErrors>, RegistryError> {
+        let schema = self
+            .get(schema_name)
+            .ok_or_else(|| RegistryError::SchemaNotFound(schema_name.to_string()))?;
+
+        let value = self.transformed(schema_name, value);
+        let result = self.run(schema_name, schema.as_ref(), &value, context);
+        let result = context.close_scope(
+            self.finish(schema.as_ref(), result, &value, context),
+            &value,
+        );
+        if let Validation::Failure(errors) = &result {
+            context.report_failure(schema_name, errors);
+        }
+        Ok(result)
+    }
+
+    /// Validates a batch of values against a named schema.
+    ///
+    /// All values share one [`ValidationContext`], so a reference that fails
+    /// to resolve is looked up and diagnosed once for the whole batch rather
+    /// than once per value. Results are returned in input order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::SchemaNotFound` if the schema name doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{SchemaRegistry, Schema};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Age", Schema::integer().non_negative()).unwrap();
+    ///
+    /// let values = vec![json!(30), json!(-1)];
+    /// let results = registry.validate_batch("Age", &values).unwrap();
+    ///
+    /// assert!(results[0].is_success());
+    /// assert!(results[1].is_failure());
+    /// ```
+    pub fn validate_batch<'a, I>(
+        &self,
+        schema_name: &str,
+        values: I,
+    ) -> Result<Vec<Validation<Value, SchemaErrors>>, RegistryError>
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        let schema = self
+            .get(schema_name)
+            .ok_or_else(|| RegistryError::SchemaNotFound(schema_name.to_string()))?;
+
+        let context = self.context();
+        Ok(values
+            .into_iter()
+            .map(|value| {
+                let value = self.transformed(schema_name, value);
+                let result = self.run(schema_name, schema.as_ref(), &value, &context);
+                self.finish(schema.as_ref(), result, &value, &context)
+            })
+            .collect())
     }
 
     /// Exports all registered schemas as a JSON Schema document with $defs.
@@ -287,6 +939,60 @@ impl SchemaRegistry {
 
         Some(result)
     }
+
+    /// Describes the changes from a previous version of the registry.
+    ///
+    /// Both registries are exported with [`to_json_schema`](Self::to_json_schema)
+    /// and compared with [`changelog::diff`](crate::interop::changelog::diff).
+    /// To compare against a released version, save its export and call
+    /// `diff` directly.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{SchemaRegistry, Schema};
+    ///
+    /// let v1 = SchemaRegistry::new();
+    /// v1.register("Port", Schema::integer().min(0)).unwrap();
+    ///
+    /// let v2 = SchemaRegistry::new();
+    /// v2.register("Port", Schema::integer().min(1)).unwrap();
+    ///
+    /// let changelog = v2.changelog_since(&v1);
+    /// assert_eq!(
+    ///     changelog.to_markdown(),
+    ///     "### Changed\n\n- `Port`: minimum tightened from 0 to 1\n",
+    /// );
+    /// ```
+    pub fn changelog_since(&self, previous: &SchemaRegistry) -> Changelog {
+        changelog::diff(&previous.to_json_schema(), &self.to_json_schema())
+    }
+
+    /// Generates valid and invalid example payloads for every schema.
+    ///
+    /// Up to `n_per_schema` valid and `n_per_schema` invalid examples are
+    /// generated per schema, each checked against the schema, and the
+    /// invalid ones tagged with the codes of their errors. The same `seed`
+    /// always gives the same corpus. See [`corpus`](crate::interop::corpus)
+    /// for how examples are made.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Port", Schema::integer().min(1).max(65535)).unwrap();
+    ///
+    /// let corpus = registry.generate_corpus(42, 3);
+    /// assert_eq!(corpus.len(), 6);
+    /// for example in corpus.invalid() {
+    ///     assert!(!example.codes.is_empty());
+    /// }
+    /// ```
+    pub fn generate_corpus(&self, seed: u64, n_per_schema: usize) -> Corpus {
+        corpus::generate(self, seed, n_per_schema)
+    }
 }
 
 impl Default for SchemaRegistry {
@@ -298,8 +1004,16 @@ impl Default for SchemaRegistry {
 impl Clone for SchemaRegistry {
     fn clone(&self) -> Self {
         Self {
+            id: self.id,
             schemas: Arc::clone(&self.schemas),
+            transforms: Arc::clone(&self.transforms),
+            metadata: Arc::clone(&self.metadata),
+            registration_hooks: self.registration_hooks.clone(),
             max_depth: self.max_depth,
+            error_factory: self.error_factory.clone(),
+            doc_links: self.doc_links,
+            outcome_cache: self.outcome_cache.clone(),
+            error_settings: self.error_settings,
         }
     }
 }
@@ -308,10 +1022,55 @@ impl RegistryAccess for SchemaRegistry {
     fn get_schema(&self, name: &str) -> Option<Arc<dyn ValueValidator>> {
         self.get(name)
     }
+
+    fn schema_names(&self) -> Vec<String> {
+        self.names()
+    }
+}
+
+/// A batch of schemas to register atomically, built by
+/// [`SchemaRegistry::transaction`].
+pub struct RegistryTransaction {
+    pending: Vec<(String, Arc<dyn ValueValidator>, SchemaMetadata)>,
+}
+
+impl RegistryTransaction {
+    /// Adds a schema to the batch.
+    pub fn register<S>(&mut self, name: impl Into<String>, schema: S) -> &mut Self
+    where
+        S: ValueValidator + 'static,
+    {
+        self.register_with_metadata(name, schema, SchemaMetadata::new())
+    }
+
+    /// Adds a schema to the batch with ownership metadata.
+    pub fn register_with_metadata<S>(
+        &mut self,
+        name: impl Into<String>,
+        schema: S,
+        metadata: SchemaMetadata,
+    ) -> &mut Self
+    where
+        S: ValueValidator + 'static,
+    {
+        self.register_shared(name.into(), Arc::new(schema), metadata)
+    }
+
+    /// Adds an already shared schema to the batch.
+    pub(crate) fn register_shared(
+        &mut self,
+        name: String,
+        schema: Arc<dyn ValueValidator>,
+        metadata: SchemaMetadata,
+    ) -> &mut Self {
+        self.pending.push((name, schema, metadata));
+        self
+    }
 }
 
 /// Errors that can occur during registry operations.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum RegistryError {
     /// Attempted to register a schema with a name that already exists.
     #[error("schema '{0}' already registered")]
@@ -320,4 +1079,27 @@ pub enum RegistryError {
     /// Attempted to validate with a schema name that doesn't exist.
     #[error("schema '{0}' not found")]
     SchemaNotFound(String),
+
+    /// A registration hook rejected a schema.
+    #[error("schema '{name}' was rejected: {reason}")]
+    Rejected {
+        /// The name the schema was to be registered under.
+        name: String,
+        /// Why the hook rejected it.
+        reason: String,
+    },
+
+    /// A schema in a transaction references schemas that are neither
+    /// registered nor in the transaction.
+    #[error("schema '{name}' references unregistered schemas: {}", refs.join(", "))]
+    UnresolvedRefs {
+        /// The schema with the references.
+        name: String,
+        /// The names it references that do not exist.
+        refs: Vec<String>,
+    },
+
+    /// Several errors occurred in a transaction.
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Multiple(Vec<RegistryError>),
 }
@@ -5,31 +5,66 @@
 
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 use stillwater::Validation;
 
-use crate::error::{SchemaError, SchemaErrors};
+use crate::error::{count, describe_type, render_message, SchemaError, SchemaErrors};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
+use crate::tunable::Limit;
 
-use super::traits::SchemaLike;
+use super::traits::{SchemaLike, ValueValidator};
+use super::FieldDoc;
+
+/// A validator for a whole array.
+///
+/// An array validator receives the items (after item validation) and the
+/// array's path, returning a validation result.
+type ArrayValidator =
+    Arc<dyn Fn(&[Value], &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static>;
+
+/// Selects the item indices an [`ArraySchema::items_where`] schema applies to.
+type IndexSelector = Box<dyn Fn(usize) -> bool + Send + Sync>;
+
+/// Derives the key of a string item for uniqueness checks.
+type Normalizer = Box<dyn Fn(&str) -> String + Send + Sync>;
 
 /// A constraint applied to array values.
 enum ArrayConstraint {
     MinLength {
-        min: usize,
+        min: Limit<usize>,
         message: Option<String>,
     },
     MaxLength {
-        max: usize,
+        max: Limit<usize>,
+        message: Option<String>,
+    },
+    LengthBetween {
+        min: Limit<usize>,
+        max: Limit<usize>,
         message: Option<String>,
     },
     Unique {
+        normalize: Option<Normalizer>,
         message: Option<String>,
     },
     UniqueBy {
         key_fn: Box<dyn Fn(&Value) -> Value + Send + Sync>,
         message: Option<String>,
     },
+    MeanBetween {
+        min: f64,
+        max: f64,
+        message: Option<String>,
+    },
+    NullRatioBelow {
+        max: f64,
+        message: Option<String>,
+    },
+    DistinctRatioAbove {
+        min: f64,
+        message: Option<String>,
+    },
 }
 
 /// A schema for validating array values.
@@ -59,7 +94,12 @@ enum ArrayConstraint {
 /// ```
 pub struct ArraySchema<S> {
     item_schema: S,
+    prefix_items: Vec<Box<dyn ValueValidator>>,
+    index_schemas: Vec<(IndexSelector, Box<dyn ValueValidator>)>,
+    max_item_errors: Option<usize>,
     constraints: Vec<ArrayConstraint>,
+    custom_validators: Vec<ArrayValidator>,
+    skip_on_item_errors: bool,
     type_error_message: Option<String>,
 }
 
@@ -68,14 +108,113 @@ impl<S: SchemaLike> ArraySchema<S> {
     pub fn new(item_schema: S) -> Self {
         Self {
             item_schema,
+            prefix_items: Vec::new(),
+            index_schemas: Vec::new(),
+            max_item_errors: None,
             constraints: Vec::new(),
+            custom_validators: Vec::new(),
+            skip_on_item_errors: true,
             type_error_message: None,
         }
     }
 
+    /// Validates the first items against positional schemas, like JSON
+    /// Schema's `prefixItems`.
+    ///
+    /// The item at index `i` is validated against the `i`th schema, and
+    /// items past the last of them against the item schema. An array may be
+    /// shorter than the prefix; combine with [`min_len`](Self::min_len) to
+    /// require every position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// // A name and an age, then any number of tags
+    /// let record = Schema::array(Schema::string())
+    ///     .prefix_items([
+    ///         Box::new(Schema::string().min_len(1)) as Box<dyn ValueValidator>,
+    ///         Box::new(Schema::integer().non_negative()),
+    ///     ])
+    ///     .min_len(2);
+    ///
+    /// assert!(record.validate(&json!(["Ada", 36, "admin"]), &JsonPath::root()).is_success());
+    ///
+    /// let errors = record.validate(&json!(["Ada", "36"]), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "[1]");
+    /// ```
+    pub fn prefix_items<I>(mut self, schemas: I) -> Self
+    where
+        I: IntoIterator<Item = Box<dyn ValueValidator>>,
+    {
+        self.prefix_items = schemas.into_iter().collect();
+        self
+    }
+
+    /// Validates the item at `index` against `schema` instead of the item
+    /// schema.
+    ///
+    /// This takes precedence over [`prefix_items`](Self::prefix_items). If
+    /// several [`item_at`](Self::item_at) or [`items_where`](Self::items_where)
+    /// schemas select an index, the first one added is used.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// // A header row of column names, then rows of numbers
+    /// let table = Schema::array(Schema::array(Schema::integer()))
+    ///     .item_at(0, Schema::array(Schema::string()));
+    ///
+    /// assert!(table.validate(&json!([["a", "b"], [1, 2]]), &JsonPath::root()).is_success());
+    ///
+    /// let errors = table.validate(&json!([["a", "b"], ["c", 2]]), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "[1][0]");
+    /// ```
+    pub fn item_at<V>(self, index: usize, schema: V) -> Self
+    where
+        V: ValueValidator + 'static,
+    {
+        self.items_where(move |i| i == index, schema)
+    }
+
+    /// Validates the items whose index matches `selects` against `schema`
+    /// instead of the item schema.
+    ///
+    /// See [`item_at`](Self::item_at) for how this combines with other
+    /// positional schemas.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// // Alternating keys and values
+    /// let pairs = Schema::array(Schema::integer())
+    ///     .items_where(|index| index % 2 == 0, Schema::string());
+    ///
+    /// assert!(pairs.validate(&json!(["a", 1, "b", 2]), &JsonPath::root()).is_success());
+    /// assert!(pairs.validate(&json!(["a", 1, 2, "b"]), &JsonPath::root()).is_failure());
+    /// ```
+    pub fn items_where<F, V>(mut self, selects: F, schema: V) -> Self
+    where
+        F: Fn(usize) -> bool + Send + Sync + 'static,
+        V: ValueValidator + 'static,
+    {
+        self.index_schemas
+            .push((Box::new(selects), Box::new(schema)));
+        self
+    }
+
     /// Adds a minimum length constraint.
     ///
-    /// The array must have at least `min` items.
+    /// The array must have at least `min` items, where `min` may be a
+    /// [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -91,15 +230,18 @@ impl<S: SchemaLike> ArraySchema<S> {
     /// let result = schema.validate(&json!([1]), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn min_len(mut self, min: usize) -> Self {
-        self.constraints
-            .push(ArrayConstraint::MinLength { min, message: None });
+    pub fn min_len(mut self, min: impl Into<Limit<usize>>) -> Self {
+        self.constraints.push(ArrayConstraint::MinLength {
+            min: min.into(),
+            message: None,
+        });
         self
     }
 
     /// Adds a maximum length constraint.
     ///
-    /// The array must have at most `max` items.
+    /// The array must have at most `max` items, where `max` may be a
+    /// [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -115,9 +257,76 @@ impl<S: SchemaLike> ArraySchema<S> {
     /// let result = schema.validate(&json!([1, 2, 3, 4]), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn max_len(mut self, max: usize) -> Self {
-        self.constraints
-            .push(ArrayConstraint::MaxLength { max, message: None });
+    pub fn max_len(mut self, max: impl Into<Limit<usize>>) -> Self {
+        self.constraints.push(ArrayConstraint::MaxLength {
+            max: max.into(),
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an exact length constraint.
+    ///
+    /// The array must have exactly `len` items, where `len` may be a
+    /// [`Tunable`](crate::Tunable). Too short and too long arrays are
+    /// reported with the `min_length` and `max_length` codes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// // An RGB triple
+    /// let schema = Schema::array(Schema::integer().range(0..=255)).len(3);
+    ///
+    /// let result = schema.validate(&json!([255, 128, 0]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let errors = schema.validate(&json!([255, 128]), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().message, "array must have exactly 3 items, got 2");
+    /// assert_eq!(errors.first().got.as_deref(), Some("2 items (missing index 2)"));
+    /// assert_eq!(errors.first().params["missing"], json!({"from": 2, "to": 2}));
+    /// ```
+    pub fn len(self, len: impl Into<Limit<usize>>) -> Self {
+        let len = len.into();
+        self.len_between(len, len)
+    }
+
+    /// Adds a length range constraint.
+    ///
+    /// The array must have between `min` and `max` items, inclusive. Unlike
+    /// [`min_len`](Self::min_len) and [`max_len`](Self::max_len), a single
+    /// [`error`](Self::error) message covers both bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::string()).len_between(1, 3);
+    ///
+    /// let result = schema.validate(&json!(["a", "b"]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!(["a", "b", "c", "d", "e"]), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "max_length");
+    /// assert_eq!(errors.first().got.as_deref(), Some("5 items (extra indices 3 to 4)"));
+    /// ```
+    pub fn len_between(
+        mut self,
+        min: impl Into<Limit<usize>>,
+        max: impl Into<Limit<usize>>,
+    ) -> Self {
+        self.constraints.push(ArrayConstraint::LengthBetween {
+            min: min.into(),
+            max: max.into(),
+            message: None,
+        });
         self
     }
 
@@ -146,7 +355,9 @@ impl<S: SchemaLike> ArraySchema<S> {
 
     /// Adds a uniqueness constraint.
     ///
-    /// All items in the array must be distinct (by JSON equality).
+    /// All items in the array must be distinct (by JSON equality). Each
+    /// repeated item is reported at its own path, e.g. `tags[2]`, with the
+    /// index of its first occurrence in the error's `first` param and `got`.
     ///
     /// # Example
     ///
@@ -159,12 +370,73 @@ impl<S: SchemaLike> ArraySchema<S> {
     /// let result = schema.validate(&json!(["a", "b", "c"]), &JsonPath::root());
     /// assert!(result.is_success());
     ///
-    /// let result = schema.validate(&json!(["a", "b", "a"]), &JsonPath::root());
-    /// assert!(result.is_failure());
+    /// let errors = schema.validate(&json!(["a", "b", "a"]), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "[2]");
+    /// assert_eq!(errors.first().got.as_deref(), Some("duplicate of index 0"));
+    /// assert_eq!(errors.first().params["first"], json!(0));
     /// ```
     pub fn unique(mut self) -> Self {
-        self.constraints
-            .push(ArrayConstraint::Unique { message: None });
+        self.constraints.push(ArrayConstraint::Unique {
+            normalize: None,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a case-insensitive uniqueness constraint.
+    ///
+    /// Like [`unique`](Self::unique), but strings that differ only in case,
+    /// such as `"Foo"` and `"foo"`, are duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::string()).unique_ci();
+    ///
+    /// let result = schema.validate(&json!(["Foo", "bar"]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!(["Foo", "foo"]), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn unique_ci(self) -> Self {
+        self.unique_with(str::to_lowercase)
+    }
+
+    /// Adds a uniqueness constraint on normalized strings.
+    ///
+    /// String items are passed through `normalize` before duplicates are
+    /// detected, so `normalize` decides which strings count as the same,
+    /// e.g. by trimming, lowercasing, or Unicode normalization. Other items
+    /// are compared by JSON equality, as with [`unique`](Self::unique).
+    /// The validated items are left as they were.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::string())
+    ///     .unique_with(|tag| tag.trim().to_lowercase());
+    ///
+    /// let result = schema.validate(&json!(["rust", "Go"]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!(["rust", " Rust "]), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn unique_with<F>(mut self, normalize: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.constraints.push(ArrayConstraint::Unique {
+            normalize: Some(Box::new(normalize)),
+            message: None,
+        });
         self
     }
 
@@ -208,11 +480,187 @@ impl<S: SchemaLike> ArraySchema<S> {
         self
     }
 
+    /// Adds a data-quality constraint on the mean of the numeric items.
+    ///
+    /// The mean of all numeric items must be within `min..=max`. Items that
+    /// are not numbers (e.g. `null` in an array of optional values) are
+    /// ignored, and arrays without numeric items pass. Violations are
+    /// reported at the array's path with code `quality_mean`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::integer()).mean_between(10.0, 20.0);
+    ///
+    /// let result = schema.validate(&json!([12, 18, 15]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!([1, 2, 60]), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn mean_between(mut self, min: f64, max: f64) -> Self {
+        self.constraints.push(ArrayConstraint::MeanBetween {
+            min,
+            max,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a data-quality constraint on the share of `null` items.
+    ///
+    /// The fraction of items that are `null` must be strictly below `max`
+    /// (between 0 and 1). Use it with optional items, e.g.
+    /// `Schema::array(Schema::optional(...))`. Empty arrays pass. Violations
+    /// are reported at the array's path with code `quality_null_ratio`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::optional(Box::new(Schema::string())))
+    ///     .null_ratio_below(0.5);
+    ///
+    /// let result = schema.validate(&json!(["a", null, "b"]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!([null, null, "b"]), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn null_ratio_below(mut self, max: f64) -> Self {
+        self.constraints
+            .push(ArrayConstraint::NullRatioBelow { max, message: None });
+        self
+    }
+
+    /// Adds a data-quality constraint on the share of distinct items.
+    ///
+    /// The number of distinct items (by JSON equality) divided by the number
+    /// of items must be strictly above `min` (between 0 and 1). Empty arrays
+    /// pass. Violations are reported at the array's path with code
+    /// `quality_distinct_ratio`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::string()).distinct_ratio_above(0.5);
+    ///
+    /// let result = schema.validate(&json!(["a", "b", "c", "a"]), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!(["a", "a", "a", "b"]), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn distinct_ratio_above(mut self, min: f64) -> Self {
+        self.constraints
+            .push(ArrayConstraint::DistinctRatioAbove { min, message: None });
+        self
+    }
+
+    /// Reports the errors of at most `max` failing items.
+    ///
+    /// Every item is still validated, but past the `max`th failing item
+    /// their errors are dropped, and one `too_many_item_errors` error at the
+    /// array counts the rest. This bounds the errors, and the memory they
+    /// take, when a large array fails throughout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::array(Schema::integer()).max_item_errors(2);
+    ///
+    /// let items: Vec<_> = (0..100).map(|i| json!(i.to_string())).collect();
+    /// let errors = schema.validate(&json!(items), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.len(), 3);
+    /// assert_eq!(errors.iter().last().unwrap().message, "…and 98 more items failed");
+    /// ```
+    pub fn max_item_errors(mut self, max: usize) -> Self {
+        self.max_item_errors = Some(max);
+        self
+    }
+
+    /// Adds a custom validator for the whole array.
+    ///
+    /// Custom validators check aggregate rules over the items, such as a
+    /// total, and run after item validation. By default they are skipped if
+    /// any item failed; see
+    /// [`skip_custom_on_item_errors`](Self::skip_custom_on_item_errors).
+    /// They receive the validated items and the array's path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaError, SchemaErrors};
+    /// use serde_json::json;
+    /// use stillwater::Validation;
+    ///
+    /// let schema = Schema::array(Schema::integer().non_negative()).custom(|items, path| {
+    ///     let total: i64 = items.iter().filter_map(|item| item.as_i64()).sum();
+    ///     if total == 100 {
+    ///         Validation::Success(())
+    ///     } else {
+    ///         Validation::Failure(SchemaErrors::single(
+    ///             SchemaError::new(path.clone(), format!("weights must sum to 100, got {}", total))
+    ///                 .with_code("invalid_total"),
+    ///         ))
+    ///     }
+    /// });
+    ///
+    /// assert!(schema.validate(&json!([50, 30, 20]), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema.validate(&json!([50, 30]), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().code, "invalid_total");
+    /// ```
+    pub fn custom<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&[Value], &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static,
+    {
+        self.custom_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Configure whether to skip custom validators if item validation fails.
+    ///
+    /// Default: `true` (skip custom validators when items are invalid).
+    ///
+    /// Set to `false` to always run custom validators. If any item failed,
+    /// they then receive the items as given rather than the validated items,
+    /// so that indices still match the input.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    ///
+    /// let schema = Schema::array(Schema::integer())
+    ///     .skip_custom_on_item_errors(false); // Always run custom validators
+    /// ```
+    pub fn skip_custom_on_item_errors(mut self, skip: bool) -> Self {
+        self.skip_on_item_errors = skip;
+        self
+    }
+
     /// Sets a custom error message for the most recent constraint.
     ///
     /// If no constraints have been added yet, this sets the type error message
     /// (used when the value is not an array).
     ///
+    /// Messages may contain the placeholders `{field}`, `{path}`, `{got}`,
+    /// and `{expected}`. Length messages can also use `{len}`, and `{min}`
+    /// and `{max}` for the length bounds of the whole schema; uniqueness
+    /// messages can use `{first}` for the index of the first occurrence.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -225,14 +673,26 @@ impl<S: SchemaLike> ArraySchema<S> {
     ///
     /// let result = schema.validate(&json!([]), &JsonPath::root());
     /// // Error message will be "at least one tag is required"
+    ///
+    /// let schema = Schema::array(Schema::string())
+    ///     .max_len(2)
+    ///     .error("{field} allows at most {max} entries, got {len}");
+    ///
+    /// let path = JsonPath::root().push_field("tags");
+    /// let errors = schema.validate(&json!(["a", "b", "c"]), &path).into_result().unwrap_err();
+    /// assert_eq!(errors.first().message, "tags allows at most 2 entries, got 3");
     /// ```
     pub fn error(mut self, message: impl Into<String>) -> Self {
         if let Some(last) = self.constraints.last_mut() {
             match last {
                 ArrayConstraint::MinLength { message: m, .. } => *m = Some(message.into()),
                 ArrayConstraint::MaxLength { message: m, .. } => *m = Some(message.into()),
-                ArrayConstraint::Unique { message: m } => *m = Some(message.into()),
+                ArrayConstraint::LengthBetween { message: m, .. } => *m = Some(message.into()),
+                ArrayConstraint::Unique { message: m, .. } => *m = Some(message.into()),
                 ArrayConstraint::UniqueBy { message: m, .. } => *m = Some(message.into()),
+                ArrayConstraint::MeanBetween { message: m, .. } => *m = Some(message.into()),
+                ArrayConstraint::NullRatioBelow { message: m, .. } => *m = Some(message.into()),
+                ArrayConstraint::DistinctRatioAbove { message: m, .. } => *m = Some(message.into()),
             }
         } else {
             self.type_error_message = Some(message.into());
@@ -250,8 +710,10 @@ impl<S: SchemaLike> ArraySchema<S> {
     ///
     /// 1. Check that the value is an array (type check)
     /// 2. Check length constraints (min/max)
-    /// 3. Validate each item against the item schema
+    /// 3. Validate each item against its prefix schema or the item schema
     /// 4. Check uniqueness constraints
+    /// 5. Check data-quality statistics
+    /// 6. Run custom validators
     ///
     /// All errors from all steps are accumulated and returned together.
     pub fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Vec<Value>, SchemaErrors> {
@@ -263,97 +725,322 @@ impl<S: SchemaLike> ArraySchema<S> {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected array".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("array"),
-                ));
+                let error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("array");
+                let error = match self.type_error_message {
+                    Some(_) => render_message(error, &[]),
+                    None => error,
+                };
+                return Validation::Failure(SchemaErrors::single(error));
             }
         };
 
         let mut errors = Vec::new();
 
         // Check length constraints
-        for constraint in &self.constraints {
-            match constraint {
-                ArrayConstraint::MinLength { min, message } if arr.len() < *min => {
-                    let msg = message.clone().unwrap_or_else(|| {
-                        format!("array must have at least {} items, got {}", min, arr.len())
-                    });
-                    errors.push(
-                        SchemaError::new(path.clone(), msg)
-                            .with_code("min_length")
-                            .with_expected(format!("at least {} items", min))
-                            .with_got(format!("{} items", arr.len())),
-                    );
-                }
-                ArrayConstraint::MaxLength { max, message } if arr.len() > *max => {
-                    let msg = message.clone().unwrap_or_else(|| {
-                        format!("array must have at most {} items, got {}", max, arr.len())
-                    });
-                    errors.push(
-                        SchemaError::new(path.clone(), msg)
-                            .with_code("max_length")
-                            .with_expected(format!("at most {} items", max))
-                            .with_got(format!("{} items", arr.len())),
-                    );
-                }
-                _ => {}
-            }
-        }
+        self.check_length(arr, path, &mut errors);
 
         // Validate each item
         let mut validated_items = Vec::with_capacity(arr.len());
+        let mut failed_items = 0;
         for (index, item) in arr.iter().enumerate() {
             let item_path = path.push_index(index);
-            match self.item_schema.validate_to_value(item, &item_path) {
+            let result = match self.positional_schema(index) {
+                Some(schema) => schema.validate_value(item, &item_path),
+                None => self.item_schema.validate_to_value(item, &item_path),
+            };
+            match result {
                 Validation::Success(v) => validated_items.push(v),
-                Validation::Failure(e) => errors.extend(e.into_iter()),
+                Validation::Failure(e) => {
+                    failed_items += 1;
+                    if self.max_item_errors.is_none_or(|max| failed_items <= max) {
+                        errors.extend(e.into_iter());
+                    }
+                }
             }
         }
+        self.summarize_item_errors(failed_items, path, &mut errors);
+
+        self.check_uniqueness(arr, path, &mut errors);
+
+        self.check_statistics(arr, path, &mut errors);
+        self.check_custom(arr, &validated_items, failed_items, path, &mut errors);
+
+        if errors.is_empty() {
+            Validation::Success(validated_items)
+        } else {
+            Validation::Failure(SchemaErrors::from_vec(errors))
+        }
+    }
+}
 
-        // Check uniqueness constraints
+impl<S> ArraySchema<S> {
+    /// Renders the placeholders in a custom length message: the schema's
+    /// length bounds and the array's length.
+    fn render_custom(
+        &self,
+        error: SchemaError,
+        message: &Option<String>,
+        len: usize,
+    ) -> SchemaError {
+        if message.is_none() {
+            return error;
+        }
+        let mut params: Vec<_> = self
+            .constraints
+            .iter()
+            .flat_map(|constraint| match constraint {
+                ArrayConstraint::MinLength { min, .. } => vec![("min", min.describe())],
+                ArrayConstraint::MaxLength { max, .. } => vec![("max", max.describe())],
+                ArrayConstraint::LengthBetween { min, max, .. } => {
+                    vec![("min", min.describe()), ("max", max.describe())]
+                }
+                _ => Vec::new(),
+            })
+            .collect();
+        params.push(("len", len.to_string()));
+        render_message(error, &params)
+    }
+
+    /// Counts the failing items whose errors were dropped, past
+    /// [`max_item_errors`](ArraySchema::max_item_errors).
+    fn summarize_item_errors(&self, failed: usize, path: &JsonPath, errors: &mut Vec<SchemaError>) {
+        let Some(max) = self.max_item_errors else {
+            return;
+        };
+        if failed > max {
+            errors.push(
+                SchemaError::new(
+                    path.clone(),
+                    format!(
+                        "…and {} failed",
+                        count(failed - max, "more item", "more items")
+                    ),
+                )
+                .with_code("too_many_item_errors")
+                .with_expected(format!(
+                    "errors for at most {}",
+                    count(max, "item", "items")
+                ))
+                .with_got(count(failed, "failing item", "failing items")),
+            );
+        }
+    }
+
+    /// Checks the length constraints.
+    fn check_length(&self, arr: &[Value], path: &JsonPath, errors: &mut Vec<SchemaError>) {
         for constraint in &self.constraints {
             match constraint {
-                ArrayConstraint::Unique { message } => {
-                    let duplicates = find_duplicates(arr, |v| v.clone());
-                    for indices in duplicates.values() {
-                        if indices.len() > 1 {
-                            let msg = message.clone().unwrap_or_else(|| {
-                                format!("duplicate value at indices {:?}", indices)
-                            });
-                            errors.push(
-                                SchemaError::new(path.clone(), msg)
-                                    .with_code("unique")
-                                    .with_got(format!("duplicates at indices {:?}", indices)),
-                            );
-                        }
+                ArrayConstraint::MinLength { min, message } => match min.current() {
+                    Some(min) if arr.len() < min => {
+                        let msg = message.clone().unwrap_or_else(|| {
+                            format!(
+                                "array must have at least {}, got {}",
+                                count(min, "item", "items"),
+                                arr.len()
+                            )
+                        });
+                        let error = with_length(
+                            SchemaError::new(path.clone(), msg)
+                                .with_code("min_length")
+                                .with_expected(format!("at least {}", count(min, "item", "items"))),
+                            arr.len(),
+                            min,
+                            usize::MAX,
+                        );
+                        errors.push(self.render_custom(error, message, arr.len()));
                     }
-                }
-                ArrayConstraint::UniqueBy { key_fn, message } => {
-                    let duplicates = find_duplicates(arr, key_fn);
-                    for indices in duplicates.values() {
-                        if indices.len() > 1 {
+                    Some(_) => {}
+                    None => errors.push(min.unresolved(path)),
+                },
+                ArrayConstraint::MaxLength { max, message } => match max.current() {
+                    Some(max) if arr.len() > max => {
+                        let msg = message.clone().unwrap_or_else(|| {
+                            format!(
+                                "array must have at most {}, got {}",
+                                count(max, "item", "items"),
+                                arr.len()
+                            )
+                        });
+                        let error = with_length(
+                            SchemaError::new(path.clone(), msg)
+                                .with_code("max_length")
+                                .with_expected(format!("at most {}", count(max, "item", "items"))),
+                            arr.len(),
+                            0,
+                            max,
+                        );
+                        errors.push(self.render_custom(error, message, arr.len()));
+                    }
+                    Some(_) => {}
+                    None => errors.push(max.unresolved(path)),
+                },
+                ArrayConstraint::LengthBetween { min, max, message } => {
+                    match (min.current(), max.current()) {
+                        (Some(min), Some(max)) if arr.len() < min || arr.len() > max => {
+                            let expected = if min == max {
+                                format!("exactly {}", count(min, "item", "items"))
+                            } else {
+                                format!("between {} and {} items", min, max)
+                            };
                             let msg = message.clone().unwrap_or_else(|| {
-                                format!("duplicate key at indices {:?}", indices)
+                                format!("array must have {}, got {}", expected, arr.len())
                             });
-                            errors.push(
+                            let code = if arr.len() < min {
+                                "min_length"
+                            } else {
+                                "max_length"
+                            };
+                            let error = with_length(
                                 SchemaError::new(path.clone(), msg)
-                                    .with_code("unique")
-                                    .with_got(format!("duplicates at indices {:?}", indices)),
+                                    .with_code(code)
+                                    .with_expected(expected),
+                                arr.len(),
+                                min,
+                                max,
                             );
+                            errors.push(self.render_custom(error, message, arr.len()));
                         }
+                        (Some(_), Some(_)) => {}
+                        (None, _) => errors.push(min.unresolved(path)),
+                        (_, None) => errors.push(max.unresolved(path)),
                     }
                 }
                 _ => {}
             }
         }
+    }
 
-        if errors.is_empty() {
-            Validation::Success(validated_items)
+    /// Returns the schema for the item at `index`, if it is not the item
+    /// schema: the first [`items_where`](ArraySchema::items_where) schema
+    /// selecting the index, or else its prefix schema.
+    fn positional_schema(&self, index: usize) -> Option<&dyn ValueValidator> {
+        self.index_schemas
+            .iter()
+            .find(|(selects, _)| selects(index))
+            .map(|(_, schema)| schema.as_ref())
+            .or_else(|| self.prefix_items.get(index).map(Box::as_ref))
+    }
+
+    /// Runs the custom validators, unless they are skipped for item errors.
+    fn check_custom(
+        &self,
+        arr: &[Value],
+        validated_items: &[Value],
+        failed_items: usize,
+        path: &JsonPath,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if self.custom_validators.is_empty() || (self.skip_on_item_errors && failed_items > 0) {
+            return;
+        }
+        // Failed items are missing from the validated items, so give the
+        // input instead to keep indices aligned
+        let items = if failed_items == 0 {
+            validated_items
         } else {
-            Validation::Failure(SchemaErrors::from_vec(errors))
+            arr
+        };
+        for validator in &self.custom_validators {
+            if let Validation::Failure(e) = validator(items, path) {
+                errors.extend(e.into_iter());
+            }
+        }
+    }
+
+    /// Checks the uniqueness constraints, reporting each repeated item at
+    /// its own path.
+    fn check_uniqueness(&self, arr: &[Value], path: &JsonPath, errors: &mut Vec<SchemaError>) {
+        for constraint in &self.constraints {
+            let (repeats, noun, message) = match constraint {
+                ArrayConstraint::Unique { normalize, message } => {
+                    let repeats = find_repeats(arr, |v| match (normalize, v) {
+                        (Some(normalize), Value::String(s)) => Value::String(normalize(s)),
+                        _ => v.clone(),
+                    });
+                    (repeats, "value", message)
+                }
+                ArrayConstraint::UniqueBy { key_fn, message } => {
+                    (find_repeats(arr, key_fn), "key", message)
+                }
+                _ => continue,
+            };
+            for (index, first) in repeats {
+                let msg = message.clone().unwrap_or_else(|| {
+                    format!("duplicate {}, first seen at index {}", noun, first)
+                });
+                let error = SchemaError::new(path.push_index(index), msg)
+                    .with_code("unique")
+                    .with_expected(format!("a unique {}", noun))
+                    .with_got(format!("duplicate of index {}", first))
+                    .with_param("first", first);
+                errors.push(match message {
+                    Some(_) => render_message(error, &[("first", first.to_string())]),
+                    None => error,
+                });
+            }
+        }
+    }
+
+    /// Checks the data-quality statistics constraints.
+    fn check_statistics(&self, arr: &[Value], path: &JsonPath, errors: &mut Vec<SchemaError>) {
+        if arr.is_empty() {
+            return;
+        }
+        let len = arr.len() as f64;
+
+        for constraint in &self.constraints {
+            match constraint {
+                ArrayConstraint::MeanBetween { min, max, message } => {
+                    let numbers: Vec<f64> = arr.iter().filter_map(Value::as_f64).collect();
+                    if numbers.is_empty() {
+                        continue;
+                    }
+                    let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+                    if mean < *min || mean > *max {
+                        let msg = message.clone().unwrap_or_else(|| {
+                            format!("mean must be between {} and {}, got {}", min, max, mean)
+                        });
+                        errors.push(
+                            SchemaError::new(path.clone(), msg)
+                                .with_code("quality_mean")
+                                .with_expected(format!("mean between {} and {}", min, max))
+                                .with_got(format!("mean {}", mean)),
+                        );
+                    }
+                }
+                ArrayConstraint::NullRatioBelow { max, message } => {
+                    let ratio = arr.iter().filter(|v| v.is_null()).count() as f64 / len;
+                    if ratio >= *max {
+                        let msg = message.clone().unwrap_or_else(|| {
+                            format!("null ratio must be below {}, got {}", max, ratio)
+                        });
+                        errors.push(
+                            SchemaError::new(path.clone(), msg)
+                                .with_code("quality_null_ratio")
+                                .with_expected(format!("null ratio below {}", max))
+                                .with_got(format!("null ratio {}", ratio)),
+                        );
+                    }
+                }
+                ArrayConstraint::DistinctRatioAbove { min, message } => {
+                    let ratio = find_duplicates(arr, |v| v.clone()).len() as f64 / len;
+                    if ratio <= *min {
+                        let msg = message.clone().unwrap_or_else(|| {
+                            format!("distinct ratio must be above {}, got {}", min, ratio)
+                        });
+                        errors.push(
+                            SchemaError::new(path.clone(), msg)
+                                .with_code("quality_distinct_ratio")
+                                .with_expected(format!("distinct ratio above {}", min))
+                                .with_got(format!("distinct ratio {}", ratio)),
+                        );
+                    }
+                }
+                _ => {}
+            }
         }
     }
 }
@@ -383,95 +1070,50 @@ impl<S: SchemaLike> SchemaLike for ArraySchema<S> {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected array".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("array"),
-                ));
+                let error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("array");
+                let error = match self.type_error_message {
+                    Some(_) => render_message(error, &[]),
+                    None => error,
+                };
+                return Validation::Failure(SchemaErrors::single(error));
             }
         };
 
         let mut errors = Vec::new();
 
         // Check length constraints
-        for constraint in &self.constraints {
-            match constraint {
-                ArrayConstraint::MinLength { min, message } if arr.len() < *min => {
-                    let msg = message.clone().unwrap_or_else(|| {
-                        format!("array must have at least {} items, got {}", min, arr.len())
-                    });
-                    errors.push(
-                        SchemaError::new(path.clone(), msg)
-                            .with_code("min_length")
-                            .with_expected(format!("at least {} items", min))
-                            .with_got(format!("{} items", arr.len())),
-                    );
-                }
-                ArrayConstraint::MaxLength { max, message } if arr.len() > *max => {
-                    let msg = message.clone().unwrap_or_else(|| {
-                        format!("array must have at most {} items, got {}", max, arr.len())
-                    });
-                    errors.push(
-                        SchemaError::new(path.clone(), msg)
-                            .with_code("max_length")
-                            .with_expected(format!("at most {} items", max))
-                            .with_got(format!("{} items", arr.len())),
-                    );
-                }
-                _ => {}
-            }
-        }
+        self.check_length(arr, path, &mut errors);
 
         // Validate each item with context (depth does not increment for array items)
         let mut validated_items = Vec::with_capacity(arr.len());
+        let mut failed_items = 0;
         for (index, item) in arr.iter().enumerate() {
             let item_path = path.push_index(index);
-            match self
-                .item_schema
-                .validate_to_value_with_context(item, &item_path, context)
-            {
+            let result = match self.positional_schema(index) {
+                Some(schema) => schema.validate_value_with_context(item, &item_path, context),
+                None => self
+                    .item_schema
+                    .validate_to_value_with_context(item, &item_path, context),
+            };
+            match result {
                 Validation::Success(v) => validated_items.push(v),
-                Validation::Failure(e) => errors.extend(e.into_iter()),
-            }
-        }
-
-        // Check uniqueness constraints
-        for constraint in &self.constraints {
-            match constraint {
-                ArrayConstraint::Unique { message } => {
-                    let duplicates = find_duplicates(arr, |v| v.clone());
-                    for indices in duplicates.values() {
-                        if indices.len() > 1 {
-                            let msg = message.clone().unwrap_or_else(|| {
-                                format!("duplicate value at indices {:?}", indices)
-                            });
-                            errors.push(
-                                SchemaError::new(path.clone(), msg)
-                                    .with_code("unique")
-                                    .with_got(format!("duplicates at indices {:?}", indices)),
-                            );
-                        }
+                Validation::Failure(e) => {
+                    failed_items += 1;
+                    if self.max_item_errors.is_none_or(|max| failed_items <= max) {
+                        errors.extend(e.into_iter());
                     }
                 }
-                ArrayConstraint::UniqueBy { key_fn, message } => {
-                    let duplicates = find_duplicates(arr, key_fn);
-                    for indices in duplicates.values() {
-                        if indices.len() > 1 {
-                            let msg = message.clone().unwrap_or_else(|| {
-                                format!("duplicate key at indices {:?}", indices)
-                            });
-                            errors.push(
-                                SchemaError::new(path.clone(), msg)
-                                    .with_code("unique")
-                                    .with_got(format!("duplicates at indices {:?}", indices)),
-                            );
-                        }
-                    }
-                }
-                _ => {}
             }
         }
+        self.summarize_item_errors(failed_items, path, &mut errors);
+
+        self.check_uniqueness(arr, path, &mut errors);
+
+        self.check_statistics(arr, path, &mut errors);
+        self.check_custom(arr, &validated_items, failed_items, path, &mut errors);
 
         if errors.is_empty() {
             Validation::Success(validated_items)
@@ -491,8 +1133,18 @@ impl<S: SchemaLike> SchemaLike for ArraySchema<S> {
     }
 
     fn collect_refs(&self, refs: &mut Vec<String>) {
+        for schema in &self.prefix_items {
+            schema.collect_refs(refs);
+        }
+        for (_, schema) in &self.index_schemas {
+            schema.collect_refs(refs);
+        }
         self.item_schema.collect_refs(refs);
     }
+
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        self.item_schema.field_doc(path)
+    }
 }
 
 impl<S: SchemaLike + ToJsonSchema> ToJsonSchema for ArraySchema<S> {
@@ -501,19 +1153,38 @@ impl<S: SchemaLike + ToJsonSchema> ToJsonSchema for ArraySchema<S> {
             "type": "array",
             "items": self.item_schema.to_json_schema(),
         });
+        if !self.prefix_items.is_empty() {
+            schema["prefixItems"] = self
+                .prefix_items
+                .iter()
+                .map(|schema| schema.to_json_schema())
+                .collect();
+        }
 
         for constraint in &self.constraints {
             match constraint {
                 ArrayConstraint::MinLength { min, .. } => {
-                    schema["minItems"] = json!(min);
+                    if let Some(min) = min.current() {
+                        schema["minItems"] = json!(min);
+                    }
                 }
                 ArrayConstraint::MaxLength { max, .. } => {
-                    schema["maxItems"] = json!(max);
+                    if let Some(max) = max.current() {
+                        schema["maxItems"] = json!(max);
+                    }
+                }
+                ArrayConstraint::LengthBetween { min, max, .. } => {
+                    if let Some(min) = min.current() {
+                        schema["minItems"] = json!(min);
+                    }
+                    if let Some(max) = max.current() {
+                        schema["maxItems"] = json!(max);
+                    }
                 }
                 ArrayConstraint::Unique { .. } => {
                     schema["uniqueItems"] = json!(true);
                 }
-                // UniqueBy doesn't have a direct JSON Schema equivalent
+                // UniqueBy and statistics don't have a JSON Schema equivalent
                 _ => {}
             }
         }
@@ -522,6 +1193,51 @@ impl<S: SchemaLike + ToJsonSchema> ToJsonSchema for ArraySchema<S> {
     }
 }
 
+/// Records the length of an array outside `min..=max` on `error`, with the
+/// indices it is missing or has in excess: as `got`, e.g.
+/// `4 items (extra index 3)`, and as the `len` and `missing` or `extra`
+/// params, the latter a `{"from": .., "to": ..}` index range.
+fn with_length(error: SchemaError, len: usize, min: usize, max: usize) -> SchemaError {
+    let (kind, first, last) = if len < min {
+        ("missing", len, min - 1)
+    } else {
+        ("extra", max, len - 1)
+    };
+    let indices = if first == last {
+        format!("index {}", first)
+    } else {
+        format!("indices {} to {}", first, last)
+    };
+    error
+        .with_got(format!(
+            "{} ({} {})",
+            count(len, "item", "items"),
+            kind,
+            indices
+        ))
+        .with_param("len", len)
+        .with_param(kind, json!({"from": first, "to": last}))
+}
+
+/// Finds the items whose key was already seen at an earlier index.
+///
+/// Returns `(index, first)` pairs in index order, where `first` is the
+/// index at which the item's key first appeared.
+fn find_repeats<F>(arr: &[Value], key_fn: F) -> Vec<(usize, usize)>
+where
+    F: Fn(&Value) -> Value,
+{
+    let mut repeats: Vec<(usize, usize)> = find_duplicates(arr, key_fn)
+        .into_values()
+        .flat_map(|indices| {
+            let first = indices[0];
+            indices.into_iter().skip(1).map(move |index| (index, first))
+        })
+        .collect();
+    repeats.sort_unstable();
+    repeats
+}
+
 /// Finds duplicate values in an array based on a key function.
 ///
 /// Returns a HashMap where keys are the JSON-serialized key values and values
@@ -541,18 +1257,6 @@ where
     seen
 }
 
-/// Returns the JSON type name for a value.
-fn value_type_name(value: &Value) -> &'static str {
-    match value {
-        Value::Null => "null",
-        Value::Bool(_) => "boolean",
-        Value::Number(_) => "number",
-        Value::String(_) => "string",
-        Value::Array(_) => "array",
-        Value::Object(_) => "object",
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -736,6 +1440,72 @@ mod tests {
         assert!(result.is_failure());
     }
 
+    #[test]
+    fn test_exact_len_constraint() {
+        let schema = ArraySchema::new(IntegerSchema::new()).len(3);
+
+        let result = schema.validate(&json!([1, 2, 3]), &JsonPath::root());
+        assert!(result.is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!([1]), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "min_length");
+        assert_eq!(errors.first().expected.as_deref(), Some("exactly 3 items"));
+        assert_eq!(
+            errors.first().got.as_deref(),
+            Some("1 item (missing indices 1 to 2)")
+        );
+
+        let errors = unwrap_failure(schema.validate(&json!([1, 2, 3, 4]), &JsonPath::root()));
+        assert_eq!(errors.first().code, "max_length");
+        assert_eq!(
+            errors.first().got.as_deref(),
+            Some("4 items (extra index 3)")
+        );
+
+        let json_schema = ToJsonSchema::to_json_schema(&schema);
+        assert_eq!(json_schema["minItems"], json!(3));
+        assert_eq!(json_schema["maxItems"], json!(3));
+    }
+
+    #[test]
+    fn test_len_between_constraint() {
+        let schema = ArraySchema::new(StringSchema::new())
+            .len_between(2, 3)
+            .error("{field} needs {min} to {max} entries, got {len}");
+        let path = JsonPath::root().push_field("tags");
+
+        let result = schema.validate(&json!(["a", "b", "c"]), &path);
+        assert!(result.is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!(["a"]), &path));
+        assert_eq!(errors.first().message, "tags needs 2 to 3 entries, got 1");
+        assert_eq!(
+            errors.first().expected.as_deref(),
+            Some("between 2 and 3 items")
+        );
+    }
+
+    #[test]
+    fn test_length_errors_include_indices() {
+        let schema = ArraySchema::new(StringSchema::new()).min_len(2).max_len(2);
+
+        let errors = unwrap_failure(schema.validate(&json!([]), &JsonPath::root()));
+        assert_eq!(
+            errors.first().got.as_deref(),
+            Some("0 items (missing indices 0 to 1)")
+        );
+
+        let errors =
+            unwrap_failure(schema.validate(&json!(["a", "b", "c", "d"]), &JsonPath::root()));
+        assert_eq!(
+            errors.first().got.as_deref(),
+            Some("4 items (extra indices 2 to 3)")
+        );
+        assert_eq!(errors.first().params["len"], json!(4));
+        assert_eq!(errors.first().params["extra"], json!({"from": 2, "to": 3}));
+    }
+
     // Uniqueness constraint tests
 
     #[test]
@@ -779,6 +1549,69 @@ mod tests {
         assert!(result.is_success());
     }
 
+    #[test]
+    fn test_unique_ci_constraint() {
+        let schema = ArraySchema::new(StringSchema::new()).unique_ci();
+
+        let result = schema.validate(&json!(["Foo", "bar"]), &JsonPath::root());
+        assert!(result.is_success());
+
+        let result = schema.validate(&json!(["Foo", "bar", "FOO"]), &JsonPath::root());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "unique");
+        assert_eq!(errors.first().path.to_string(), "[2]");
+    }
+
+    #[test]
+    fn test_unique_with_normalizer() {
+        let schema = ArraySchema::new(StringSchema::new())
+            .unique_with(|s| s.trim().to_lowercase())
+            .error("tags must be unique");
+
+        // Items are not rewritten by the normalizer
+        let result = schema.validate(&json!([" a", "b"]), &JsonPath::root());
+        assert_eq!(unwrap_success(result), vec![json!(" a"), json!("b")]);
+
+        let result = schema.validate(&json!(["a", " A "]), &JsonPath::root());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().message, "tags must be unique");
+        assert_eq!(
+            ToJsonSchema::to_json_schema(&schema)["uniqueItems"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_unique_reports_each_repeat() {
+        let schema = ArraySchema::new(StringSchema::new()).unique();
+        let path = JsonPath::root().push_field("tags");
+
+        let errors = unwrap_failure(schema.validate(&json!(["a", "b", "a", "b", "a"]), &path));
+        let reported: Vec<_> = errors
+            .iter()
+            .map(|e| (e.path.to_string(), e.got.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                ("tags[2]".to_string(), "duplicate of index 0".to_string()),
+                ("tags[3]".to_string(), "duplicate of index 1".to_string()),
+                ("tags[4]".to_string(), "duplicate of index 0".to_string()),
+            ]
+        );
+        assert_eq!(
+            errors.first().message,
+            "duplicate value, first seen at index 0"
+        );
+        let firsts: Vec<_> = errors.iter().map(|e| e.params["first"].clone()).collect();
+        assert_eq!(firsts, [json!(0), json!(1), json!(0)]);
+
+        let schema = schema.error("{path} repeats item {first}");
+        let errors = unwrap_failure(schema.validate(&json!(["a", "a"]), &path));
+        assert_eq!(errors.first().message, "tags[1] repeats item 0");
+    }
+
     #[test]
     fn test_unique_by_constraint() {
         let user_schema = ObjectSchema::new()
@@ -961,6 +1794,211 @@ mod tests {
         assert!(result.is_failure());
     }
 
+    // Data-quality statistics tests
+
+    #[test]
+    fn test_mean_between() {
+        let schema = ArraySchema::new(IntegerSchema::new()).mean_between(2.0, 4.0);
+
+        assert!(schema
+            .validate(&json!([2, 4]), &JsonPath::root())
+            .is_success());
+        assert!(schema.validate(&json!([]), &JsonPath::root()).is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!([5, 6]), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "quality_mean");
+        assert_eq!(errors.first().got.as_deref(), Some("mean 5.5"));
+    }
+
+    #[test]
+    fn test_mean_ignores_non_numeric_items() {
+        let schema = ArraySchema::new(crate::Schema::optional(Box::new(IntegerSchema::new())))
+            .mean_between(0.0, 1.0);
+
+        assert!(schema
+            .validate(&json!([null, 1, null, 0]), &JsonPath::root())
+            .is_success());
+        assert!(schema
+            .validate(&json!([null]), &JsonPath::root())
+            .is_success());
+    }
+
+    #[test]
+    fn test_null_ratio_below() {
+        let schema = ArraySchema::new(crate::Schema::optional(Box::new(StringSchema::new())))
+            .null_ratio_below(0.25);
+
+        assert!(schema
+            .validate(&json!(["a", "b", "c", "d", null]), &JsonPath::root())
+            .is_success());
+
+        let errors =
+            unwrap_failure(schema.validate(&json!(["a", "b", "c", null]), &JsonPath::root()));
+        assert_eq!(errors.first().code, "quality_null_ratio");
+        assert_eq!(errors.first().got.as_deref(), Some("null ratio 0.25"));
+    }
+
+    #[test]
+    fn test_distinct_ratio_above() {
+        let schema = ArraySchema::new(StringSchema::new())
+            .distinct_ratio_above(0.5)
+            .error("too many repeated values");
+
+        assert!(schema
+            .validate(&json!(["a", "b", "c", "a"]), &JsonPath::root())
+            .is_success());
+
+        let errors =
+            unwrap_failure(schema.validate(&json!(["a", "a", "b", "b"]), &JsonPath::root()));
+        assert_eq!(errors.first().code, "quality_distinct_ratio");
+        assert_eq!(errors.first().message, "too many repeated values");
+    }
+
+    #[test]
+    fn test_statistics_accumulate_with_item_errors() {
+        let schema = ArraySchema::new(IntegerSchema::new().positive())
+            .mean_between(10.0, 20.0)
+            .distinct_ratio_above(0.9);
+
+        let errors = unwrap_failure(schema.validate(&json!([-1, -1]), &JsonPath::root()));
+        let codes: Vec<&str> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            [
+                "positive",
+                "positive",
+                "quality_mean",
+                "quality_distinct_ratio"
+            ]
+        );
+    }
+
+    // Prefix items tests
+
+    #[test]
+    fn test_prefix_items() {
+        let schema = ArraySchema::new(StringSchema::new()).prefix_items([
+            Box::new(IntegerSchema::new()) as Box<dyn ValueValidator>,
+            Box::new(ObjectSchema::new().field("id", IntegerSchema::new())),
+        ]);
+
+        assert!(schema.validate(&json!([]), &JsonPath::root()).is_success());
+        assert!(schema.validate(&json!([1]), &JsonPath::root()).is_success());
+        assert_eq!(
+            unwrap_success(schema.validate(&json!([1, {"id": 2}, "a", "b"]), &JsonPath::root())),
+            vec![json!(1), json!({"id": 2}), json!("a"), json!("b")]
+        );
+
+        let errors =
+            unwrap_failure(schema.validate(&json!(["1", {"id": "2"}, 3]), &JsonPath::root()));
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["[0]", "[1].id", "[2]"]);
+
+        let context = crate::SchemaRegistry::new().context();
+        assert!(schema
+            .validate_with_context(&json!(["1"]), &JsonPath::root(), &context)
+            .is_failure());
+
+        assert_eq!(
+            ToJsonSchema::to_json_schema(&schema)["prefixItems"],
+            json!([
+                {"type": "integer"},
+                {"type": "object", "properties": {"id": {"type": "integer"}}, "required": ["id"]}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_item_at_and_items_where() {
+        let schema = ArraySchema::new(IntegerSchema::new())
+            .item_at(0, StringSchema::new())
+            .items_where(|index| index % 2 == 0, ObjectSchema::new())
+            .prefix_items([Box::new(StringSchema::new()) as Box<dyn ValueValidator>]);
+
+        assert!(schema
+            .validate(&json!(["header", 1, {}, 2, {}]), &JsonPath::root())
+            .is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!([true, 1, 2]), &JsonPath::root()));
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["[0]", "[2]"]);
+
+        let context = crate::SchemaRegistry::new().context();
+        assert!(schema
+            .validate_with_context(&json!([1]), &JsonPath::root(), &context)
+            .is_failure());
+    }
+
+    fn sum_to(total: i64) -> impl Fn(&[Value], &JsonPath) -> Validation<(), SchemaErrors> {
+        move |items, path| {
+            let sum: i64 = items.iter().filter_map(Value::as_i64).sum();
+            if sum == total {
+                Validation::Success(())
+            } else {
+                Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(path.clone(), "bad total").with_code("invalid_total"),
+                ))
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_validator() {
+        let schema = ArraySchema::new(IntegerSchema::new()).custom(sum_to(100));
+
+        let result = schema.validate(&json!([60, 40]), &JsonPath::root());
+        assert!(result.is_success());
+
+        let path = JsonPath::root().push_field("weights");
+        let errors = unwrap_failure(schema.validate(&json!([60, 30]), &path));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_total");
+        assert_eq!(errors.first().path.to_string(), "weights");
+    }
+
+    #[test]
+    fn test_custom_validator_skipped_on_item_errors() {
+        let schema = ArraySchema::new(IntegerSchema::new()).custom(sum_to(100));
+        let errors = unwrap_failure(schema.validate(&json!([60, "30"]), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_type");
+
+        let schema = schema.skip_custom_on_item_errors(false);
+        let errors = unwrap_failure(schema.validate(&json!([60, "30"]), &JsonPath::root()));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.with_code("invalid_total").len(), 1);
+    }
+
+    #[test]
+    fn test_max_item_errors() {
+        let schema = ArraySchema::new(IntegerSchema::new().positive()).max_item_errors(2);
+        let items: Vec<Value> = (0..10).map(|i| json!(-i)).collect();
+
+        let errors = unwrap_failure(schema.validate(&json!(items), &JsonPath::root()));
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["[0]", "[1]", ""]);
+        let summary = errors.iter().last().unwrap();
+        assert_eq!(summary.code, "too_many_item_errors");
+        assert_eq!(summary.message, "…and 8 more items failed");
+
+        let context = crate::SchemaRegistry::new().context();
+        let errors = unwrap_failure(schema.validate_with_context(
+            &json!([-1, 1, -2, -3]),
+            &JsonPath::root(),
+            &context,
+        ));
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors.iter().last().unwrap().message,
+            "…and 1 more item failed"
+        );
+
+        // At the cap, nothing is dropped
+        let errors = unwrap_failure(schema.validate(&json!([-1, -2]), &JsonPath::root()));
+        assert_eq!(errors.len(), 2);
+    }
+
     // SchemaLike trait tests
 
     #[test]
@@ -0,0 +1,235 @@
+//! Calendar-aware date and datetime parsing for the `chrono` feature.
+//!
+//! The default date check only looks at the shape of the string and the
+//! range of each field. These parsers check the real calendar, so
+//! `2025-02-31` is rejected and `2024-02-29` is not, and they back the
+//! range constraints such as
+//! [`StringSchema::date_min`](super::StringSchema::date_min) and the
+//! business rules such as
+//! [`StringSchema::business_hours`](super::StringSchema::business_hours).
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::civil::{has_date_shape, Date};
+
+/// A source of holidays for
+/// [`StringSchema::not_holiday`](super::StringSchema::not_holiday).
+///
+/// Implemented for sets of dates and for closures, so a fixed list, a
+/// generated calendar, or a lookup in a shared service can all be used.
+///
+/// # Example
+///
+/// ```rust
+/// use chrono::{Datelike, NaiveDate};
+/// use postmortem::HolidayCalendar;
+/// use std::collections::HashSet;
+///
+/// let fixed: HashSet<NaiveDate> = [NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()].into();
+/// assert!(fixed.is_holiday(NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()));
+///
+/// let new_year = |date: NaiveDate| date.month() == 1 && date.day() == 1;
+/// assert!(new_year.is_holiday(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()));
+/// ```
+pub trait HolidayCalendar: Send + Sync {
+    /// Returns whether `date` is a holiday.
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+}
+
+impl HolidayCalendar for HashSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for BTreeSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl<F> HolidayCalendar for F
+where
+    F: Fn(NaiveDate) -> bool + Send + Sync,
+{
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self(date)
+    }
+}
+
+/// A time zone that datetimes are converted to before a business rule is
+/// checked.
+#[derive(Clone)]
+pub(super) struct Zone {
+    name: String,
+    to_local: Arc<dyn Fn(DateTime<Utc>) -> NaiveDateTime + Send + Sync>,
+}
+
+impl Zone {
+    pub(super) fn new<Tz>(tz: Tz) -> Self
+    where
+        Tz: TimeZone + Display + Send + Sync + 'static,
+    {
+        Self {
+            name: tz.to_string(),
+            to_local: Arc::new(move |utc| utc.with_timezone(&tz).naive_local()),
+        }
+    }
+
+    /// Returns the zone's name, e.g. `Europe/Berlin` or `+05:30`.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the local time of a datetime string in this zone, or `None`
+    /// if it is not a datetime.
+    ///
+    /// Datetimes without an offset are taken to be local time already.
+    pub(super) fn local_datetime(&self, s: &str) -> Option<NaiveDateTime> {
+        match parse_datetime(s)? {
+            ParsedDateTime::Offset(datetime) => Some((self.to_local)(datetime.with_timezone(&Utc))),
+            ParsedDateTime::Local(datetime) => Some(datetime),
+        }
+    }
+
+    /// Returns the local date of a date or datetime string in this zone,
+    /// or `None` if it is neither.
+    pub(super) fn local_date(&self, s: &str) -> Option<NaiveDate> {
+        parse_date(s).or_else(|| self.local_datetime(s).map(|datetime| datetime.date()))
+    }
+}
+
+/// A parsed datetime, with or without a timezone offset.
+pub(super) enum ParsedDateTime {
+    /// An RFC 3339 datetime ending in `Z` or `±HH:MM`.
+    Offset(DateTime<FixedOffset>),
+    /// A datetime with no offset, e.g. `2025-11-28T14:30:00`.
+    Local(NaiveDateTime),
+}
+
+impl ParsedDateTime {
+    /// Returns the calendar date as written, ignoring any offset.
+    fn date(&self) -> NaiveDate {
+        match self {
+            ParsedDateTime::Offset(datetime) => datetime.date_naive(),
+            ParsedDateTime::Local(datetime) => datetime.date(),
+        }
+    }
+
+    /// Returns the instant in UTC; datetimes without an offset are taken
+    /// to be in UTC.
+    fn to_utc(&self) -> DateTime<Utc> {
+        match self {
+            ParsedDateTime::Offset(datetime) => datetime.with_timezone(&Utc),
+            ParsedDateTime::Local(datetime) => datetime.and_utc(),
+        }
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date that exists in the calendar.
+pub(super) fn parse_date(s: &str) -> Option<NaiveDate> {
+    let date = Date::parse(s)?;
+    NaiveDate::from_ymd_opt(date.year as i32, date.month, date.day)
+}
+
+/// Parses an ISO 8601 datetime, with an optional fraction of a second and
+/// an optional RFC 3339 offset.
+pub(super) fn parse_datetime(s: &str) -> Option<ParsedDateTime> {
+    if !has_date_shape(s) {
+        return None;
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(ParsedDateTime::Offset(datetime));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(ParsedDateTime::Local)
+}
+
+/// Returns the calendar date of a date or datetime string.
+pub(super) fn date_of(s: &str) -> Option<NaiveDate> {
+    parse_date(s).or_else(|| parse_datetime(s).map(|datetime| datetime.date()))
+}
+
+/// Returns whether a datetime string is strictly before the current time,
+/// or `None` if it is not a datetime.
+pub(super) fn is_before_now(s: &str) -> Option<bool> {
+    let now = DateTime::<Utc>::from(SystemTime::now());
+    parse_datetime(s).map(|datetime| datetime.to_utc() < now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_checks_calendar() {
+        assert!(parse_date("2024-02-29").is_some());
+        assert!(parse_date("2025-02-29").is_none());
+        assert!(parse_date("2025-02-31").is_none());
+        assert!(parse_date("2025-04-31").is_none());
+        assert!(parse_date("2025-1-05").is_none());
+        assert!(parse_date("+2025-01-05").is_none());
+    }
+
+    #[test]
+    fn test_parse_datetime_offsets() {
+        assert!(matches!(
+            parse_datetime("2025-11-28T14:30:00Z"),
+            Some(ParsedDateTime::Offset(_))
+        ));
+        assert!(matches!(
+            parse_datetime("2025-11-28T14:30:00.125+05:30"),
+            Some(ParsedDateTime::Offset(_))
+        ));
+        assert!(matches!(
+            parse_datetime("2025-11-28T14:30:00"),
+            Some(ParsedDateTime::Local(_))
+        ));
+        assert!(parse_datetime("2025-11-28T24:30:00Z").is_none());
+        assert!(parse_datetime("2025-02-30T10:00:00Z").is_none());
+        assert!(parse_datetime("2025-11-28T14:30:00Zjunk").is_none());
+    }
+
+    #[test]
+    fn test_date_of_uses_written_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 28).unwrap();
+        assert_eq!(date_of("2025-11-28"), Some(date));
+        assert_eq!(date_of("2025-11-28T23:30:00-05:00"), Some(date));
+        assert_eq!(date_of("not a date"), None);
+    }
+
+    #[test]
+    fn test_zone_converts_offsets() {
+        let zone = Zone::new(FixedOffset::east_opt(-5 * 3600).unwrap());
+        assert_eq!(zone.name(), "-05:00");
+
+        // 03:30 UTC is still the previous evening in UTC-5
+        let local = zone.local_datetime("2025-11-29T03:30:00Z").unwrap();
+        assert_eq!(local.to_string(), "2025-11-28 22:30:00");
+        assert_eq!(
+            zone.local_date("2025-11-29T03:30:00Z"),
+            NaiveDate::from_ymd_opt(2025, 11, 28)
+        );
+
+        // Datetimes without an offset and dates are taken as written
+        let local = zone.local_datetime("2025-11-29T03:30:00").unwrap();
+        assert_eq!(local.to_string(), "2025-11-29 03:30:00");
+        assert_eq!(
+            zone.local_date("2025-11-29"),
+            NaiveDate::from_ymd_opt(2025, 11, 29)
+        );
+        assert_eq!(zone.local_datetime("2025-11-29"), None);
+    }
+
+    #[test]
+    fn test_is_before_now() {
+        assert_eq!(is_before_now("2000-01-01T00:00:00Z"), Some(true));
+        assert_eq!(is_before_now("9999-01-01T00:00:00Z"), Some(false));
+        assert_eq!(is_before_now("2000-01-01"), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Calendar dates without chrono.
+//!
+//! Date formats, chrono's date parsing and suppression expiries all read
+//! `YYYY-MM-DD` dates through [`Date::parse`], so they agree on which
+//! dates exist.
+
+use std::fmt::{self, Display};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date, ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Date {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+}
+
+impl Date {
+    /// Parses a `YYYY-MM-DD` date that exists in the calendar.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        if s.len() != 10 || !has_date_shape(s) {
+            return None;
+        }
+        let date = Date {
+            year: s[0..4].parse().ok()?,
+            month: s[5..7].parse().ok()?,
+            day: s[8..10].parse().ok()?,
+        };
+        let leap = date.year % 4 == 0 && (date.year % 100 != 0 || date.year % 400 == 0);
+        let days_in_month = match date.month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        ((1..=12).contains(&date.month) && (1..=days_in_month).contains(&date.day)).then_some(date)
+    }
+
+    /// Returns today's date in UTC.
+    pub(crate) fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_days((secs / 86_400) as i64)
+    }
+
+    /// Converts days since 1970-01-01 to a date.
+    pub(crate) fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Date { year, month, day }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Checks that `s` starts with the `YYYY-MM-DD` shape in ASCII digits, since
+/// integer parsing also accepts signs.
+pub(crate) fn has_date_shape(s: &str) -> bool {
+    s.len() >= 10
+        && s.bytes().take(10).enumerate().all(|(i, b)| match i {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+        assert_eq!(Date::from_days(-1).to_string(), "1969-12-31");
+        assert!(Date::parse("2024-02-29").is_some());
+        assert!(Date::parse("2023-02-29").is_none());
+        assert!(Date::parse("2024-2-01").is_none());
+        assert!(Date::parse("2024-13-01").is_none());
+        assert!(Date::parse("2024-01-31").unwrap() < Date::parse("2024-02-01").unwrap());
+    }
+
+    #[test]
+    fn test_parse_rejects_signs() {
+        assert!(Date::parse("+999-01-01").is_none());
+        assert!(Date::parse("2024-+1-01").is_none());
+        assert!(Date::parse("2024-01-+1").is_none());
+        assert!(Date::parse("-999-01-01").is_none());
+        assert!(Date::parse("2024-01-01T00:00:00").is_none());
+    }
+}
@@ -5,6 +5,9 @@
 //! - `any_of`: At least one schema must match (flexible unions)
 //! - `all_of`: All schemas must match (intersection/merging)
 //! - `optional`: Value can be null
+//! - `consensus`: A quorum of schemas must match (side-by-side definitions)
+//! - `discriminated_union`: A tag field selects the schema that must match
+//! - `if_then_else`: A condition schema selects the schema that must match
 //!
 //! # Example
 //!
@@ -34,10 +37,11 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use stillwater::Validation;
 
-use crate::error::{SchemaError, SchemaErrors};
+use crate::error::{count, describe_type, preview, value_previews, SchemaError, SchemaErrors};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
 use crate::schema::traits::{SchemaLike, ValueValidator};
+use crate::suppression::SuppressedError;
 use crate::validation::ValidationContext;
 
 /// Type alias for validation function stored in combinators.
@@ -46,39 +50,69 @@ pub(crate) type ValidatorFn =
 
 /// Schema combinators for composing validation logic.
 ///
-/// `CombinatorSchema` provides four composition patterns:
+/// `CombinatorSchema` provides seven composition patterns:
 /// - `OneOf`: Exactly one schema must match (discriminated unions)
 /// - `AnyOf`: At least one schema must match (flexible unions)
 /// - `AllOf`: All schemas must match (intersection)
 /// - `Optional`: Value can be null
+/// - `Consensus`: A quorum of schemas must match
+/// - `DiscriminatedUnion`: A tag field selects the schema that must match
+/// - `IfThenElse`: A condition schema selects the schema that must match
 ///
 /// Each combinator implements `SchemaLike` and can be used anywhere a schema is expected.
+/// Build them with the [`Schema`](crate::Schema) constructors: new fields
+/// may be added to any variant, so the variants cannot be constructed or
+/// matched field by field outside this crate.
+///
+/// Schemas can also be combined with operators: `a | b` builds an `AnyOf`
+/// and `a & b` an `AllOf`.
+///
+/// ```rust
+/// use postmortem::{JsonPath, Schema, SchemaLike};
+/// use serde_json::json;
+///
+/// let id = Schema::string().min_len(1) | Schema::integer().positive();
+/// assert!(id.validate(&json!(42), &JsonPath::root()).is_success());
+///
+/// let code = Schema::string().min_len(2) & Schema::string().max_len(4);
+/// assert!(code.validate(&json!("abcde"), &JsonPath::root()).is_failure());
+/// ```
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum CombinatorSchema {
     /// Exactly one schema must match.
     ///
     /// Validates the value against all schemas. Succeeds if exactly one matches,
     /// fails if none or multiple match. Ideal for discriminated unions where
     /// a value must be one of several distinct types.
+    #[non_exhaustive]
     OneOf {
         schemas: Vec<ValidatorFn>,
         validators: Vec<Arc<dyn ValueValidator>>,
+        branch_errors: bool,
+        /// Branch names, from [`one_of_named`](crate::Schema::one_of_named);
+        /// empty if the branches are unnamed.
+        names: Vec<String>,
     },
 
     /// At least one schema must match.
     ///
     /// Validates the value against schemas in order, short-circuiting on the
     /// first match. Fails only if none match. More permissive than `OneOf`.
+    #[non_exhaustive]
     AnyOf {
         schemas: Vec<ValidatorFn>,
         validators: Vec<Arc<dyn ValueValidator>>,
+        branch_errors: bool,
     },
 
     /// All schemas must match.
     ///
     /// Validates the value against all schemas. Succeeds only if all pass,
-    /// accumulating errors from any that fail. Useful for schema composition
-    /// and intersection.
+    /// accumulating errors from any that fail, with the outputs of every
+    /// schema merged; see [`Schema::all_of`](crate::Schema::all_of). Useful
+    /// for schema composition and intersection.
+    #[non_exhaustive]
     AllOf {
         schemas: Vec<ValidatorFn>,
         validators: Vec<Arc<dyn ValueValidator>>,
@@ -88,18 +122,258 @@ pub enum CombinatorSchema {
     ///
     /// Null values pass validation. Non-null values are validated against
     /// the inner schema.
+    #[non_exhaustive]
     Optional {
         inner: ValidatorFn,
         validator: Arc<dyn ValueValidator>,
     },
+
+    /// A quorum of schemas must match.
+    ///
+    /// Validates the value against all schemas. Succeeds if at least the
+    /// quorum match, keeping the output of the first that does; the errors
+    /// of the dissenting schemas become warnings on the validation context.
+    /// Useful while running two definitions of a schema side by side.
+    #[non_exhaustive]
+    Consensus {
+        validators: Vec<Arc<dyn ValueValidator>>,
+        quorum: Quorum,
+    },
+
+    /// The schema named by a tag field must match.
+    ///
+    /// Reads the discriminator field of an object and validates the object
+    /// against the branch for its value only, so errors are those of the
+    /// intended branch rather than a summary of every branch.
+    #[non_exhaustive]
+    DiscriminatedUnion {
+        discriminator: String,
+        branches: Vec<(String, Arc<dyn ValueValidator>)>,
+    },
+
+    /// The schema selected by a condition must match.
+    ///
+    /// Validates the value against the condition schema, discarding its
+    /// errors. If it matches, the value must match the `then` schema;
+    /// otherwise it must match the `else` schema, if there is one. Mirrors
+    /// JSON Schema's `if`/`then`/`else`.
+    #[non_exhaustive]
+    IfThenElse {
+        condition: Arc<dyn ValueValidator>,
+        then: Arc<dyn ValueValidator>,
+        otherwise: Option<Arc<dyn ValueValidator>>,
+    },
+}
+
+/// Wraps each validator in a [`ValidatorFn`].
+pub(crate) fn validator_fns(validators: &[Arc<dyn ValueValidator>]) -> Vec<ValidatorFn> {
+    validators
+        .iter()
+        .map(|validator| {
+            let v = Arc::clone(validator);
+            Arc::new(move |value: &Value, path: &JsonPath| v.validate_value(value, path))
+                as ValidatorFn
+        })
+        .collect()
+}
+
+/// The combinator a [`CombinatorBuilder`] builds.
+#[derive(Debug, Clone, Copy)]
+enum BuilderKind {
+    One,
+    Any,
+    All,
+}
+
+/// Builds a `one_of`, `any_of`, or `all_of` from schemas of any type.
+///
+/// Created by [`Schema::one_of_builder`](crate::Schema::one_of_builder),
+/// [`Schema::any_of_builder`](crate::Schema::any_of_builder), or
+/// [`Schema::all_of_builder`](crate::Schema::all_of_builder). Each schema is
+/// boxed as it is added, so branches need no `Box<dyn ValueValidator>`
+/// casts.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{JsonPath, Schema, SchemaLike};
+/// use serde_json::json;
+///
+/// let id = Schema::any_of_builder()
+///     .or(Schema::string().min_len(1))
+///     .or(Schema::integer().positive())
+///     .build();
+///
+/// assert!(id.validate(&json!("abc-123"), &JsonPath::root()).is_success());
+/// assert!(id.validate(&json!(42), &JsonPath::root()).is_success());
+///
+/// let entity = Schema::all_of_builder()
+///     .and(Schema::object().field("name", Schema::string()))
+///     .and(Schema::object().field("created_at", Schema::string()))
+///     .build();
+///
+/// assert!(entity.validate(&json!({"name": "Alice"}), &JsonPath::root()).is_failure());
+/// ```
+pub struct CombinatorBuilder {
+    kind: BuilderKind,
+    schemas: Vec<Box<dyn ValueValidator>>,
+}
+
+impl CombinatorBuilder {
+    pub(crate) fn one_of() -> Self {
+        Self::new(BuilderKind::One)
+    }
+
+    pub(crate) fn any_of() -> Self {
+        Self::new(BuilderKind::Any)
+    }
+
+    pub(crate) fn all_of() -> Self {
+        Self::new(BuilderKind::All)
+    }
+
+    fn new(kind: BuilderKind) -> Self {
+        Self {
+            kind,
+            schemas: Vec::new(),
+        }
+    }
+
+    /// Adds a schema.
+    pub fn or<V: ValueValidator + 'static>(mut self, schema: V) -> Self {
+        self.schemas.push(Box::new(schema));
+        self
+    }
+
+    /// Adds a schema; the same as [`or`](Self::or), for an `all_of`.
+    pub fn and<V: ValueValidator + 'static>(self, schema: V) -> Self {
+        self.or(schema)
+    }
+
+    /// Builds the combinator from the schemas added, in order.
+    pub fn build(self) -> CombinatorSchema {
+        match self.kind {
+            BuilderKind::One => crate::Schema::one_of(self.schemas),
+            BuilderKind::Any => crate::Schema::any_of(self.schemas),
+            BuilderKind::All => crate::Schema::all_of(self.schemas),
+        }
+    }
+}
+
+/// Merges the output of an `all_of` schema into those of the schemas
+/// before it.
+///
+/// Objects are merged key by key, recursively, keeping the keys of both,
+/// except that a key of the `input` missing from either output was removed
+/// or renamed by that schema and is left out. Other values conflict: a
+/// value that differs from the `input`, i.e. one a schema transformed or
+/// defaulted, wins over one passed through unchanged, and otherwise the
+/// later value wins.
+fn merge_outputs(input: Option<&Value>, merged: Value, next: Value) -> Value {
+    match (merged, next) {
+        (Value::Object(mut merged), Value::Object(mut next)) => {
+            let input = input.and_then(Value::as_object);
+            for key in input.into_iter().flat_map(|input| input.keys()) {
+                if !merged.contains_key(key) || !next.contains_key(key) {
+                    merged.remove(key);
+                    next.remove(key);
+                }
+            }
+            for (key, value) in next {
+                let input = input.and_then(|input| input.get(&key));
+                match merged.get_mut(&key) {
+                    Some(existing) => {
+                        *existing = merge_outputs(input, existing.take(), value);
+                    }
+                    None => {
+                        merged.insert(key, value);
+                    }
+                }
+            }
+            Value::Object(merged)
+        }
+        (merged, next) if input == Some(&next) && input != Some(&merged) => merged,
+        (_, next) => next,
+    }
+}
+
+/// Labels a branch by its name, if it has one, or else its index.
+fn branch_label(names: &[String], index: usize) -> String {
+    match names.get(index) {
+        Some(name) => format!("'{}'", name),
+        None => index.to_string(),
+    }
+}
+
+/// How many schemas of a [`consensus`](crate::Schema::consensus) must accept
+/// a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Quorum {
+    /// At least this many schemas.
+    MinAgree(usize),
+    /// More than half of the schemas.
+    Majority,
+}
+
+impl Quorum {
+    /// Returns the number of schemas out of `total` that must agree.
+    pub(crate) fn required(self, total: usize) -> usize {
+        match self {
+            Quorum::MinAgree(n) => n,
+            Quorum::Majority => total / 2 + 1,
+        }
+    }
 }
 
 impl CombinatorSchema {
+    /// Configures whether a `one_of` or `any_of` that matches no schema
+    /// also reports why each schema rejected the value.
+    ///
+    /// Default: `false` (report only the summary error).
+    ///
+    /// When `true`, the summary error is followed by the errors of every
+    /// schema, in order, with messages prefixed by the schema's index, or
+    /// its name for [`one_of_named`](crate::Schema::one_of_named), e.g.
+    /// `branch 1: must be positive, got -1`. Other combinators ignore this
+    /// setting.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// let id = Schema::any_of(vec![
+    ///     Box::new(Schema::string().min_len(1)) as Box<dyn ValueValidator>,
+    ///     Box::new(Schema::integer().positive()),
+    /// ])
+    /// .with_branch_errors(true);
+    ///
+    /// let errors = id.validate(&json!(-1), &JsonPath::root()).into_result().unwrap_err();
+    /// let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    /// assert_eq!(messages, [
+    ///     "value did not match any of 2 schemas",
+    ///     "branch 0: expected string",
+    ///     "branch 1: must be positive, got -1",
+    /// ]);
+    /// ```
+    pub fn with_branch_errors(mut self, enabled: bool) -> Self {
+        if let CombinatorSchema::OneOf { branch_errors, .. }
+        | CombinatorSchema::AnyOf { branch_errors, .. } = &mut self
+        {
+            *branch_errors = enabled;
+        }
+        self
+    }
+
     /// Validates a value against exactly one of the provided schemas.
     ///
     /// Returns success if exactly one schema matches, failure if none or multiple match.
     fn validate_one_of(
         schemas: &[ValidatorFn],
+        names: &[String],
+        branch_errors: bool,
         value: &Value,
         path: &JsonPath,
     ) -> Validation<Value, SchemaErrors> {
@@ -114,13 +388,19 @@ impl CombinatorSchema {
         match valid.len() {
             0 => {
                 // None matched - report with count
-                let error = SchemaError::new(
+                let mut error = SchemaError::new(
                     path.clone(),
-                    format!("value did not match any of {} schemas", schemas.len()),
+                    format!(
+                        "value did not match any of {}",
+                        count(schemas.len(), "schema", "schemas")
+                    ),
                 )
                 .with_code("one_of_none_matched");
+                if value_previews() {
+                    error = error.with_got(preview(value));
+                }
 
-                Validation::Failure(SchemaErrors::single(error))
+                Self::none_matched(error, results, names, branch_errors)
             }
             1 => {
                 // Exactly one matched - success
@@ -131,19 +411,12 @@ impl CombinatorSchema {
                     _ => unreachable!(),
                 }
             }
-            n => {
+            _ => {
                 // Multiple matched - ambiguous
-                let indices: Vec<_> = valid.iter().map(|(i, _)| i).collect();
-                let error = SchemaError::new(
-                    path.clone(),
-                    format!(
-                        "value matched {} schemas (indices {:?}), expected exactly one",
-                        n, indices
-                    ),
-                )
-                .with_code("one_of_multiple_matched");
-
-                Validation::Failure(SchemaErrors::single(error))
+                let indices: Vec<_> = valid.iter().map(|(i, _)| *i).collect();
+                Validation::Failure(SchemaErrors::single(Self::multiple_matched(
+                    &indices, names, path,
+                )))
             }
         }
     }
@@ -153,46 +426,105 @@ impl CombinatorSchema {
     /// Short-circuits on the first match. Returns failure only if none match.
     fn validate_any_of(
         schemas: &[ValidatorFn],
+        branch_errors: bool,
         value: &Value,
         path: &JsonPath,
     ) -> Validation<Value, SchemaErrors> {
-        for validator in schemas {
+        let mut failures = Vec::new();
+        for (i, validator) in schemas.iter().enumerate() {
             match validator(value, path) {
                 Validation::Success(v) => return Validation::Success(v),
-                Validation::Failure(_) => continue,
+                failure => failures.push((i, failure)),
             }
         }
 
         // None matched
         let error = SchemaError::new(
             path.clone(),
-            format!("value did not match any of {} schemas", schemas.len()),
+            format!(
+                "value did not match any of {}",
+                count(schemas.len(), "schema", "schemas")
+            ),
         )
         .with_code("any_of_none_matched");
 
-        Validation::Failure(SchemaErrors::single(error))
+        Self::none_matched(error, failures, &[], branch_errors)
+    }
+
+    /// Builds the failure for a value that matched no branch: `summary`,
+    /// followed by the errors of each branch, labeled with its name or
+    /// index, if `branch_errors` is set.
+    fn none_matched(
+        summary: SchemaError,
+        results: Vec<(usize, Validation<Value, SchemaErrors>)>,
+        names: &[String],
+        branch_errors: bool,
+    ) -> Validation<Value, SchemaErrors> {
+        if !branch_errors {
+            return Validation::Failure(SchemaErrors::single(summary));
+        }
+        let branches = results.into_iter().flat_map(|(i, result)| {
+            let errors = match result {
+                Validation::Failure(errors) => errors.into_iter().collect(),
+                Validation::Success(_) => Vec::new(),
+            };
+            let label = branch_label(names, i);
+            errors.into_iter().map(move |mut error| {
+                error.message = format!("branch {}: {}", label, error.message);
+                error
+            })
+        });
+        Validation::Failure(SchemaErrors::from_vec(
+            std::iter::once(summary).chain(branches).collect(),
+        ))
+    }
+
+    /// Builds the error for a value that matched the branches at `indices`
+    /// of a `one_of`.
+    fn multiple_matched(indices: &[usize], names: &[String], path: &JsonPath) -> SchemaError {
+        let matched = if names.is_empty() {
+            format!("indices {:?}", indices)
+        } else {
+            let labels: Vec<_> = indices.iter().map(|&i| branch_label(names, i)).collect();
+            format!("branches {}", labels.join(", "))
+        };
+        SchemaError::new(
+            path.clone(),
+            format!(
+                "value matched {} schemas ({}), expected exactly one",
+                indices.len(),
+                matched
+            ),
+        )
+        .with_code("one_of_multiple_matched")
     }
 
     /// Validates a value against all of the provided schemas.
     ///
-    /// Returns success only if all schemas pass, accumulating errors from failures.
+    /// Returns success only if all schemas pass, accumulating errors from
+    /// failures. The outputs are merged with [`merge_outputs`].
     fn validate_all_of(
         schemas: &[ValidatorFn],
         value: &Value,
         path: &JsonPath,
     ) -> Validation<Value, SchemaErrors> {
         let mut all_errors = Vec::new();
-        let mut last_valid = None;
+        let mut merged: Option<Value> = None;
 
         for validator in schemas {
             match validator(value, path) {
-                Validation::Success(v) => last_valid = Some(v),
+                Validation::Success(v) => {
+                    merged = Some(match merged {
+                        Some(merged) => merge_outputs(Some(value), merged, v),
+                        None => v,
+                    });
+                }
                 Validation::Failure(e) => all_errors.extend(e.into_iter()),
             }
         }
 
         if all_errors.is_empty() {
-            Validation::Success(last_valid.unwrap_or_else(|| value.clone()))
+            Validation::Success(merged.unwrap_or_else(|| value.clone()))
         } else {
             Validation::Failure(SchemaErrors::from_vec(all_errors))
         }
@@ -213,21 +545,185 @@ impl CombinatorSchema {
         }
     }
 
+    /// Validates a value against a quorum of the provided schemas.
+    ///
+    /// Dissenting errors are recorded as warnings on `context`, if there is
+    /// one. On failure, the errors of every dissenting schema follow a
+    /// summary error.
+    fn validate_consensus(
+        validators: &[Arc<dyn ValueValidator>],
+        quorum: Quorum,
+        value: &Value,
+        path: &JsonPath,
+        context: Option<&ValidationContext>,
+    ) -> Validation<Value, SchemaErrors> {
+        let total = validators.len();
+        let required = quorum.required(total);
+        let mut output = None;
+        let mut dissent = Vec::new();
+        // The warnings of the accepting schemas, kept if the quorum is met
+        let mut accepting = Vec::new();
+        for (i, validator) in validators.iter().enumerate() {
+            let branch = context.map(ValidationContext::detached);
+            let result = match &branch {
+                Some(branch) => validator.validate_value_with_context(value, path, branch),
+                None => validator.validate_value(value, path),
+            };
+            match result {
+                Validation::Success(v) => {
+                    output.get_or_insert(v);
+                    accepting.extend(branch);
+                }
+                Validation::Failure(e) => dissent.push((i, e)),
+            }
+        }
+        let agreed = total - dissent.len();
+
+        if agreed >= required {
+            if let Some(context) = context {
+                for branch in &accepting {
+                    context.adopt(branch);
+                }
+                context.warn(dissent.into_iter().flat_map(|(i, errors)| {
+                    errors.into_iter().map(move |error| SuppressedError {
+                        error,
+                        reason: format!(
+                            "schema {} dissented; {} of {} accepted the value",
+                            i, agreed, total
+                        ),
+                    })
+                }));
+            }
+            return Validation::Success(output.unwrap_or_else(|| value.clone()));
+        }
+
+        let summary = SchemaError::new(
+            path.clone(),
+            format!(
+                "{} of {} accepted the value, at least {} required",
+                agreed,
+                count(total, "schema", "schemas"),
+                required
+            ),
+        )
+        .with_code("consensus_not_reached")
+        .with_expected(format!("at least {} accepting", required))
+        .with_got(agreed.to_string());
+        let errors = std::iter::once(summary)
+            .chain(
+                dissent
+                    .into_iter()
+                    .flat_map(|(_, errors)| errors.into_iter()),
+            )
+            .collect();
+        Validation::Failure(SchemaErrors::from_vec(errors))
+    }
+
+    /// Validates an object against the branch its discriminator selects.
+    fn validate_discriminated_union(
+        discriminator: &str,
+        branches: &[(String, Arc<dyn ValueValidator>)],
+        value: &Value,
+        path: &JsonPath,
+        context: Option<&ValidationContext>,
+    ) -> Validation<Value, SchemaErrors> {
+        let Some(obj) = value.as_object() else {
+            let error = SchemaError::new(path.clone(), "expected object")
+                .with_code("invalid_type")
+                .with_got(describe_type(value))
+                .with_expected("object");
+            return Validation::Failure(SchemaErrors::single(error));
+        };
+
+        let tags = branches
+            .iter()
+            .map(|(tag, _)| tag.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let tag_path = path.push_field(discriminator);
+        let Some(tag) = obj.get(discriminator) else {
+            let error = SchemaError::new(
+                tag_path,
+                format!("required field '{}' is missing", discriminator),
+            )
+            .with_code("required")
+            .with_expected(format!("one of: {}", tags));
+            return Validation::Failure(SchemaErrors::single(error));
+        };
+
+        let branch = tag
+            .as_str()
+            .and_then(|tag| branches.iter().find(|(name, _)| name == tag));
+        match branch {
+            Some((_, validator)) => match context {
+                Some(context) => validator.validate_value_with_context(value, path, context),
+                None => validator.validate_value(value, path),
+            },
+            None => {
+                let error = SchemaError::new(
+                    tag_path,
+                    format!(
+                        "unknown {} {}, must be one of: {}",
+                        discriminator, tag, tags
+                    ),
+                )
+                .with_code("unknown_discriminator")
+                .with_got(preview(tag))
+                .with_expected(format!("one of: {}", tags));
+                Validation::Failure(SchemaErrors::single(error))
+            }
+        }
+    }
+
+    /// Validates a value against the schema its condition selects.
+    fn validate_if_then_else(
+        condition: &Arc<dyn ValueValidator>,
+        then: &Arc<dyn ValueValidator>,
+        otherwise: Option<&Arc<dyn ValueValidator>>,
+        value: &Value,
+        path: &JsonPath,
+        context: Option<&ValidationContext>,
+    ) -> Validation<Value, SchemaErrors> {
+        let validate = |validator: &Arc<dyn ValueValidator>| match context {
+            Some(context) => validator.validate_value_with_context(value, path, context),
+            None => validator.validate_value(value, path),
+        };
+        // The condition only selects a schema, so its warnings are dropped
+        let matched = match context {
+            Some(context) => condition
+                .validate_value_with_context(value, path, &context.detached())
+                .is_success(),
+            None => condition.validate_value(value, path).is_success(),
+        };
+        if matched {
+            validate(then)
+        } else {
+            match otherwise {
+                Some(otherwise) => validate(otherwise),
+                None => Validation::Success(value.clone()),
+            }
+        }
+    }
+
     /// Validates a value against exactly one of the provided schemas with context.
     fn validate_one_of_with_context(
         validators: &[Arc<dyn ValueValidator>],
+        names: &[String],
+        branch_errors: bool,
         value: &Value,
         path: &JsonPath,
         context: &ValidationContext,
     ) -> Validation<Value, SchemaErrors> {
+        // Each branch records its warnings apart, to keep only the match's
+        let mut branches = Vec::with_capacity(validators.len());
         let results: Vec<_> = validators
             .iter()
             .enumerate()
             .map(|(i, validator)| {
-                (
-                    i,
-                    validator.validate_value_with_context(value, path, context),
-                )
+                let branch = context.detached();
+                let result = validator.validate_value_with_context(value, path, &branch);
+                branches.push(branch);
+                (i, result)
             })
             .collect();
 
@@ -235,33 +731,33 @@ impl CombinatorSchema {
 
         match valid.len() {
             0 => {
-                let error = SchemaError::new(
+                let mut error = SchemaError::new(
                     path.clone(),
-                    format!("value did not match any of {} schemas", validators.len()),
+                    format!(
+                        "value did not match any of {}",
+                        count(validators.len(), "schema", "schemas")
+                    ),
                 )
                 .with_code("one_of_none_matched");
+                if value_previews() {
+                    error = error.with_got(preview(value));
+                }
 
-                Validation::Failure(SchemaErrors::single(error))
+                Self::none_matched(error, results, names, branch_errors)
             }
             1 => {
-                let (_, result) = valid.into_iter().next().unwrap();
+                let (i, result) = valid.into_iter().next().unwrap();
+                context.adopt(&branches[*i]);
                 match result {
                     Validation::Success(v) => Validation::Success(v.clone()),
                     _ => unreachable!(),
                 }
             }
-            n => {
-                let indices: Vec<_> = valid.iter().map(|(i, _)| i).collect();
-                let error = SchemaError::new(
-                    path.clone(),
-                    format!(
-                        "value matched {} schemas (indices {:?}), expected exactly one",
-                        n, indices
-                    ),
-                )
-                .with_code("one_of_multiple_matched");
-
-                Validation::Failure(SchemaErrors::single(error))
+            _ => {
+                let indices: Vec<_> = valid.iter().map(|(i, _)| *i).collect();
+                Validation::Failure(SchemaErrors::single(Self::multiple_matched(
+                    &indices, names, path,
+                )))
             }
         }
     }
@@ -269,24 +765,33 @@ impl CombinatorSchema {
     /// Validates a value against at least one of the provided schemas with context.
     fn validate_any_of_with_context(
         validators: &[Arc<dyn ValueValidator>],
+        branch_errors: bool,
         value: &Value,
         path: &JsonPath,
         context: &ValidationContext,
     ) -> Validation<Value, SchemaErrors> {
-        for validator in validators {
-            match validator.validate_value_with_context(value, path, context) {
-                Validation::Success(v) => return Validation::Success(v),
-                Validation::Failure(_) => continue,
+        let mut failures = Vec::new();
+        for (i, validator) in validators.iter().enumerate() {
+            let branch = context.detached();
+            match validator.validate_value_with_context(value, path, &branch) {
+                Validation::Success(v) => {
+                    context.adopt(&branch);
+                    return Validation::Success(v);
+                }
+                failure => failures.push((i, failure)),
             }
         }
 
         let error = SchemaError::new(
             path.clone(),
-            format!("value did not match any of {} schemas", validators.len()),
+            format!(
+                "value did not match any of {}",
+                count(validators.len(), "schema", "schemas")
+            ),
         )
         .with_code("any_of_none_matched");
 
-        Validation::Failure(SchemaErrors::single(error))
+        Self::none_matched(error, failures, &[], branch_errors)
     }
 
     /// Validates a value against all of the provided schemas with context.
@@ -297,17 +802,22 @@ impl CombinatorSchema {
         context: &ValidationContext,
     ) -> Validation<Value, SchemaErrors> {
         let mut all_errors = Vec::new();
-        let mut last_valid = None;
+        let mut merged: Option<Value> = None;
 
         for validator in validators {
             match validator.validate_value_with_context(value, path, context) {
-                Validation::Success(v) => last_valid = Some(v),
+                Validation::Success(v) => {
+                    merged = Some(match merged {
+                        Some(merged) => merge_outputs(Some(value), merged, v),
+                        None => v,
+                    });
+                }
                 Validation::Failure(e) => all_errors.extend(e.into_iter()),
             }
         }
 
         if all_errors.is_empty() {
-            Validation::Success(last_valid.unwrap_or_else(|| value.clone()))
+            Validation::Success(merged.unwrap_or_else(|| value.clone()))
         } else {
             Validation::Failure(SchemaErrors::from_vec(all_errors))
         }
@@ -333,10 +843,33 @@ impl SchemaLike for CombinatorSchema {
 
     fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
         match self {
-            CombinatorSchema::OneOf { schemas, .. } => Self::validate_one_of(schemas, value, path),
-            CombinatorSchema::AnyOf { schemas, .. } => Self::validate_any_of(schemas, value, path),
+            CombinatorSchema::OneOf {
+                schemas,
+                branch_errors,
+                names,
+                ..
+            } => Self::validate_one_of(schemas, names, *branch_errors, value, path),
+            CombinatorSchema::AnyOf {
+                schemas,
+                branch_errors,
+                ..
+            } => Self::validate_any_of(schemas, *branch_errors, value, path),
             CombinatorSchema::AllOf { schemas, .. } => Self::validate_all_of(schemas, value, path),
             CombinatorSchema::Optional { inner, .. } => Self::validate_optional(inner, value, path),
+            CombinatorSchema::Consensus { validators, quorum } => {
+                Self::validate_consensus(validators, *quorum, value, path, None)
+            }
+            CombinatorSchema::DiscriminatedUnion {
+                discriminator,
+                branches,
+            } => Self::validate_discriminated_union(discriminator, branches, value, path, None),
+            CombinatorSchema::IfThenElse {
+                condition,
+                then,
+                otherwise,
+            } => {
+                Self::validate_if_then_else(condition, then, otherwise.as_ref(), value, path, None)
+            }
         }
     }
 
@@ -351,11 +884,25 @@ impl SchemaLike for CombinatorSchema {
         context: &ValidationContext,
     ) -> Validation<Value, SchemaErrors> {
         match self {
-            CombinatorSchema::OneOf { validators, .. } => {
-                Self::validate_one_of_with_context(validators, value, path, context)
-            }
-            CombinatorSchema::AnyOf { validators, .. } => {
-                Self::validate_any_of_with_context(validators, value, path, context)
+            CombinatorSchema::OneOf {
+                validators,
+                branch_errors,
+                names,
+                ..
+            } => Self::validate_one_of_with_context(
+                validators,
+                names,
+                *branch_errors,
+                value,
+                path,
+                context,
+            ),
+            CombinatorSchema::AnyOf {
+                validators,
+                branch_errors,
+                ..
+            } => {
+                Self::validate_any_of_with_context(validators, *branch_errors, value, path, context)
             }
             CombinatorSchema::AllOf { validators, .. } => {
                 Self::validate_all_of_with_context(validators, value, path, context)
@@ -363,6 +910,31 @@ impl SchemaLike for CombinatorSchema {
             CombinatorSchema::Optional { validator, .. } => {
                 Self::validate_optional_with_context(validator, value, path, context)
             }
+            CombinatorSchema::Consensus { validators, quorum } => {
+                Self::validate_consensus(validators, *quorum, value, path, Some(context))
+            }
+            CombinatorSchema::DiscriminatedUnion {
+                discriminator,
+                branches,
+            } => Self::validate_discriminated_union(
+                discriminator,
+                branches,
+                value,
+                path,
+                Some(context),
+            ),
+            CombinatorSchema::IfThenElse {
+                condition,
+                then,
+                otherwise,
+            } => Self::validate_if_then_else(
+                condition,
+                then,
+                otherwise.as_ref(),
+                value,
+                path,
+                Some(context),
+            ),
         }
     }
 
@@ -395,6 +967,27 @@ impl SchemaLike for CombinatorSchema {
             CombinatorSchema::Optional { validator, .. } => {
                 validator.collect_refs(refs);
             }
+            CombinatorSchema::Consensus { validators, .. } => {
+                for validator in validators {
+                    validator.collect_refs(refs);
+                }
+            }
+            CombinatorSchema::DiscriminatedUnion { branches, .. } => {
+                for (_, validator) in branches {
+                    validator.collect_refs(refs);
+                }
+            }
+            CombinatorSchema::IfThenElse {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.collect_refs(refs);
+                then.collect_refs(refs);
+                if let Some(otherwise) = otherwise {
+                    otherwise.collect_refs(refs);
+                }
+            }
         }
     }
 }
@@ -402,10 +995,22 @@ impl SchemaLike for CombinatorSchema {
 impl ToJsonSchema for CombinatorSchema {
     fn to_json_schema(&self) -> Value {
         match self {
-            CombinatorSchema::OneOf { validators, .. } => {
-                json!({
-                    "oneOf": validators.iter().map(|v| v.to_json_schema()).collect::<Vec<_>>()
-                })
+            // Name each branch with a title, unless it has its own
+            CombinatorSchema::OneOf {
+                validators, names, ..
+            } => {
+                let branches: Vec<Value> = validators
+                    .iter()
+                    .enumerate()
+                    .map(|(i, validator)| {
+                        let mut branch = validator.to_json_schema();
+                        if let (Some(name), Some(object)) = (names.get(i), branch.as_object_mut()) {
+                            object.entry("title").or_insert_with(|| json!(name));
+                        }
+                        branch
+                    })
+                    .collect();
+                json!({ "oneOf": branches })
             }
             CombinatorSchema::AnyOf { validators, .. } => {
                 json!({
@@ -425,6 +1030,70 @@ impl ToJsonSchema for CombinatorSchema {
                     ]
                 })
             }
+            // JSON Schema has no quorum, so export the weaker anyOf and
+            // record the quorum as an extension
+            CombinatorSchema::Consensus { validators, quorum } => {
+                json!({
+                    "anyOf": validators.iter().map(|v| v.to_json_schema()).collect::<Vec<_>>(),
+                    "x-quorum": quorum.required(validators.len())
+                })
+            }
+            // Pin each branch's discriminator to its tag, and name the
+            // discriminator as OpenAPI does
+            CombinatorSchema::DiscriminatedUnion {
+                discriminator,
+                branches,
+            } => {
+                let branches: Vec<Value> = branches
+                    .iter()
+                    .map(|(tag, validator)| {
+                        let mut branch = validator.to_json_schema();
+                        if let Some(properties) =
+                            branch.get_mut("properties").and_then(Value::as_object_mut)
+                        {
+                            properties.insert(discriminator.clone(), json!({ "const": tag }));
+                            let required = branch
+                                .as_object_mut()
+                                .expect("a schema with properties is an object")
+                                .entry("required")
+                                .or_insert_with(|| json!([]));
+                            if let Some(required) = required.as_array_mut() {
+                                if !required.iter().any(|name| name == discriminator.as_str()) {
+                                    required.push(json!(discriminator));
+                                }
+                            }
+                            return branch;
+                        }
+                        json!({
+                            "allOf": [
+                                branch,
+                                {
+                                    "properties": { discriminator.as_str(): { "const": tag } },
+                                    "required": [discriminator]
+                                }
+                            ]
+                        })
+                    })
+                    .collect();
+                json!({
+                    "oneOf": branches,
+                    "discriminator": { "propertyName": discriminator }
+                })
+            }
+            CombinatorSchema::IfThenElse {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let mut schema = json!({
+                    "if": condition.to_json_schema(),
+                    "then": then.to_json_schema(),
+                });
+                if let Some(otherwise) = otherwise {
+                    schema["else"] = otherwise.to_json_schema();
+                }
+                schema
+            }
         }
     }
 }
@@ -0,0 +1,172 @@
+//! Exact decimal arithmetic for monetary rules.
+//!
+//! Amounts in documents are JSON numbers or decimal strings such as
+//! `"19.99"`. Adding them as `f64` drifts (`0.1 + 0.2 != 0.3`), so the
+//! monetary cross-field rules parse them into a scaled integer instead.
+
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A decimal number, `units * 10^-scale`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Decimal {
+    units: i128,
+    scale: u32,
+}
+
+/// The largest scale accepted, so rescaling cannot overflow an `i128`
+/// for amounts of a realistic size.
+const MAX_SCALE: u32 = 18;
+
+impl Decimal {
+    pub(super) const ZERO: Decimal = Decimal { units: 0, scale: 0 };
+
+    /// Parses a JSON number or a decimal string such as `"-12.50"`.
+    ///
+    /// Returns `None` for other values, for strings that are not plain
+    /// decimals, and for numbers too large or too precise to represent.
+    pub(super) fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Self::parse(&n.to_string()),
+            Value::String(s) => Self::parse(s),
+            _ => None,
+        }
+    }
+
+    /// Parses `[+-]digits[.digits][e[+-]digits]`.
+    fn parse(s: &str) -> Option<Self> {
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], s[i + 1..].parse::<i32>().ok()?),
+            None => (s, 0),
+        };
+        let (negative, digits) = match mantissa.as_bytes().first()? {
+            b'-' => (true, &mantissa[1..]),
+            b'+' => (false, &mantissa[1..]),
+            _ => (false, mantissa),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+            || (digits.contains('.') && fraction.is_empty())
+        {
+            return None;
+        }
+
+        let mut units: i128 = 0;
+        for b in whole.bytes().chain(fraction.bytes()) {
+            units = units.checked_mul(10)?.checked_add(i128::from(b - b'0'))?;
+        }
+        if negative {
+            units = -units;
+        }
+        // In i64, so an exponent near i32::MIN cannot overflow
+        let scale = i64::try_from(fraction.len())
+            .ok()?
+            .checked_sub(i64::from(exponent))?;
+        let decimal = if scale >= 0 {
+            Decimal {
+                units,
+                scale: u32::try_from(scale).ok()?,
+            }
+        } else {
+            let shift = u32::try_from(scale.unsigned_abs()).ok()?;
+            Decimal {
+                units: units.checked_mul(10i128.checked_pow(shift)?)?,
+                scale: 0,
+            }
+        };
+        (decimal.scale <= MAX_SCALE).then_some(decimal)
+    }
+
+    /// Returns `self` with `scale` fractional digits, which must not be
+    /// less than its own.
+    fn rescale(self, scale: u32) -> Option<i128> {
+        self.units
+            .checked_mul(10i128.checked_pow(scale - self.scale)?)
+    }
+
+    /// Adds two decimals, or returns `None` on overflow.
+    pub(super) fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        Some(Decimal {
+            units: self.rescale(scale)?.checked_add(other.rescale(scale)?)?,
+            scale,
+        })
+    }
+
+    /// Compares two decimals by value, so `12.3` equals `12.30`.
+    pub(super) fn compare(self, other: Self) -> Option<Ordering> {
+        let scale = self.scale.max(other.scale);
+        Some(self.rescale(scale)?.cmp(&other.rescale(scale)?))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.units < 0 { "-" } else { "" };
+        let digits = self.units.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn decimal(value: Value) -> Decimal {
+        Decimal::from_value(&value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for (value, shown) in [
+            (json!("19.99"), "19.99"),
+            (json!("-0.05"), "-0.05"),
+            (json!("+7"), "7"),
+            (json!(42), "42"),
+            (json!(0.1), "0.1"),
+            (json!("1.5e2"), "150"),
+            (json!("25e-3"), "0.025"),
+        ] {
+            assert_eq!(decimal(value).to_string(), shown);
+        }
+        for value in [
+            json!("12."),
+            json!(".5"),
+            json!("1,000.00"),
+            json!(""),
+            json!("-"),
+            json!(true),
+            json!("1e-40"),
+            json!("1e-2147483648"),
+            json!("1e2147483647"),
+        ] {
+            assert!(Decimal::from_value(&value).is_none(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let sum = decimal(json!(0.1))
+            .checked_add(decimal(json!(0.2)))
+            .unwrap();
+        assert_eq!(sum.compare(decimal(json!("0.3"))), Some(Ordering::Equal));
+        assert_eq!(sum.to_string(), "0.3");
+
+        let sum = decimal(json!("10.50"))
+            .checked_add(decimal(json!(-3)))
+            .unwrap();
+        assert_eq!(sum.to_string(), "7.50");
+        assert_eq!(sum.compare(decimal(json!(7.5))), Some(Ordering::Equal));
+        assert_eq!(sum.compare(decimal(json!("7.51"))), Some(Ordering::Less));
+    }
+}
@@ -0,0 +1,245 @@
+//! Parsing of ISO 8601 and human-readable durations.
+//!
+//! [`StringSchema::duration`](super::StringSchema::duration) and
+//! [`StringSchema::human_duration`](super::StringSchema::human_duration)
+//! validate duration strings with these parsers. [`Duration`] is public so
+//! custom validators can check the parsed components, e.g. to cap a
+//! retention period.
+
+/// The components of a parsed duration.
+///
+/// Components are kept as written rather than normalized, since years and
+/// months have no fixed length: `PT90M` has 90 minutes and no hours.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::schema::Duration;
+///
+/// let duration = Duration::parse_iso8601("P3DT4H").unwrap();
+/// assert_eq!(duration.days, 3.0);
+/// assert_eq!(duration.hours, 4.0);
+/// assert_eq!(duration.to_std().unwrap().as_secs(), 3 * 86_400 + 4 * 3_600);
+///
+/// let duration = Duration::parse_human("1h 30min").unwrap();
+/// assert_eq!(duration.to_std().unwrap().as_secs(), 5_400);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Duration {
+    /// Calendar years.
+    pub years: f64,
+    /// Calendar months.
+    pub months: f64,
+    /// Weeks of seven days.
+    pub weeks: f64,
+    /// Days of 24 hours.
+    pub days: f64,
+    /// Hours.
+    pub hours: f64,
+    /// Minutes.
+    pub minutes: f64,
+    /// Seconds, including any sub-second part.
+    pub seconds: f64,
+}
+
+impl Duration {
+    /// Parses an ISO 8601 duration such as `P1Y2M`, `P3DT4H`, `PT0.5S`, or
+    /// `P2W`.
+    ///
+    /// Components must appear in order (years, months, weeks, days, then
+    /// hours, minutes, seconds after `T`), at least one must be present, and
+    /// only the last may have a fractional part. Negative durations are not
+    /// accepted.
+    pub fn parse_iso8601(s: &str) -> Option<Self> {
+        let rest = s.strip_prefix('P')?;
+        let (date, time) = match rest.split_once('T') {
+            Some((date, time)) if !time.is_empty() => (date, Some(time)),
+            Some(_) => return None,
+            None => (rest, None),
+        };
+
+        let mut duration = Self::default();
+        let mut components = 0;
+        let mut fractional = false;
+        let mut parse_part = |part: &str, units: &[char]| -> Option<Vec<(char, f64)>> {
+            let mut values = Vec::new();
+            let mut remaining = part;
+            let mut next_unit = 0;
+            while !remaining.is_empty() {
+                let end =
+                    remaining.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))?;
+                let (number, tail) = remaining.split_at(end);
+                let unit = tail.chars().next()?;
+                let position = units[next_unit..].iter().position(|&u| u == unit)?;
+                next_unit += position + 1;
+                if fractional || number.is_empty() {
+                    return None;
+                }
+                fractional = number.contains(['.', ',']);
+                values.push((unit, parse_number(&number.replace(',', "."))?));
+                components += 1;
+                remaining = &tail[unit.len_utf8()..];
+            }
+            Some(values)
+        };
+
+        for (unit, value) in parse_part(date, &['Y', 'M', 'W', 'D'])? {
+            match unit {
+                'Y' => duration.years = value,
+                'M' => duration.months = value,
+                'W' => duration.weeks = value,
+                _ => duration.days = value,
+            }
+        }
+        if let Some(time) = time {
+            for (unit, value) in parse_part(time, &['H', 'M', 'S'])? {
+                match unit {
+                    'H' => duration.hours = value,
+                    'M' => duration.minutes = value,
+                    _ => duration.seconds = value,
+                }
+            }
+        }
+
+        (components > 0).then_some(duration)
+    }
+
+    /// Parses a human-readable duration such as `90s`, `1h 30min`, or
+    /// `2weeks 3days`, in the style of the `humantime` crate.
+    ///
+    /// Each component is a whole number followed by a unit; components may
+    /// be separated by whitespace and repeated units add up. Accepted units
+    /// are `ns`, `us`, `ms`, `s`/`sec`/`second`, `m`/`min`/`minute`,
+    /// `h`/`hr`/`hour`, `d`/`day`, `w`/`week`, `M`/`month`, and `y`/`year`,
+    /// with plural forms of the spelled-out units.
+    pub fn parse_human(s: &str) -> Option<Self> {
+        let mut duration = Self::default();
+        let mut remaining = s.trim();
+        if remaining.is_empty() {
+            return None;
+        }
+
+        while !remaining.is_empty() {
+            let digits = remaining
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(remaining.len());
+            if digits == 0 {
+                return None;
+            }
+            let value = parse_number(&remaining[..digits])?;
+            remaining = remaining[digits..].trim_start();
+
+            let unit_len = remaining
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(remaining.len());
+            match &remaining[..unit_len] {
+                "ns" | "nsec" | "nsecs" => duration.seconds += value / 1e9,
+                "us" | "usec" | "usecs" => duration.seconds += value / 1e6,
+                "ms" | "msec" | "msecs" => duration.seconds += value / 1e3,
+                "s" | "sec" | "secs" | "second" | "seconds" => duration.seconds += value,
+                "m" | "min" | "mins" | "minute" | "minutes" => duration.minutes += value,
+                "h" | "hr" | "hrs" | "hour" | "hours" => duration.hours += value,
+                "d" | "day" | "days" => duration.days += value,
+                "w" | "week" | "weeks" => duration.weeks += value,
+                "M" | "month" | "months" => duration.months += value,
+                "y" | "year" | "years" => duration.years += value,
+                _ => return None,
+            }
+            remaining = remaining[unit_len..].trim_start();
+        }
+
+        Some(duration)
+    }
+
+    /// Converts to a [`std::time::Duration`], treating a week as 7 days and a
+    /// day as 24 hours.
+    ///
+    /// Returns `None` if the duration has years or months, whose length
+    /// depends on the calendar.
+    pub fn to_std(&self) -> Option<std::time::Duration> {
+        if self.years != 0.0 || self.months != 0.0 {
+            return None;
+        }
+        let seconds = self.weeks * 604_800.0
+            + self.days * 86_400.0
+            + self.hours * 3_600.0
+            + self.minutes * 60.0
+            + self.seconds;
+        std::time::Duration::try_from_secs_f64(seconds).ok()
+    }
+}
+
+/// Parses a non-negative decimal number, rejecting forms like `.5` or `1.`.
+fn parse_number(s: &str) -> Option<f64> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, "0"));
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !digits(whole) || !digits(fraction) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso8601() {
+        let duration = Duration::parse_iso8601("P1Y2M3W4DT5H6M7.5S").unwrap();
+        assert_eq!(
+            duration,
+            Duration {
+                years: 1.0,
+                months: 2.0,
+                weeks: 3.0,
+                days: 4.0,
+                hours: 5.0,
+                minutes: 6.0,
+                seconds: 7.5,
+            }
+        );
+        assert_eq!(Duration::parse_iso8601("PT1M").unwrap().minutes, 1.0);
+        assert_eq!(Duration::parse_iso8601("P1M").unwrap().months, 1.0);
+        assert_eq!(Duration::parse_iso8601("PT0,5S").unwrap().seconds, 0.5);
+        assert_eq!(Duration::parse_iso8601("P0D").unwrap(), Duration::default());
+    }
+
+    #[test]
+    fn test_parse_iso8601_rejects_malformed() {
+        for invalid in [
+            "", "P", "PT", "P1DT", "3D", "P3", "PD", "P1D2Y", "PT1H1H", "P1.5DT1H", "PT.5S",
+            "PT1.S", "P-1D", "p1d", "P1S", "PT1D", " P1D",
+        ] {
+            assert!(Duration::parse_iso8601(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_human() {
+        let duration = Duration::parse_human("1h 30min").unwrap();
+        assert_eq!(duration.hours, 1.0);
+        assert_eq!(duration.minutes, 30.0);
+
+        let duration = Duration::parse_human("2weeks3days 500ms").unwrap();
+        assert_eq!(duration.weeks, 2.0);
+        assert_eq!(duration.days, 3.0);
+        assert_eq!(duration.seconds, 0.5);
+
+        assert_eq!(Duration::parse_human("1M").unwrap().months, 1.0);
+        assert_eq!(Duration::parse_human("1m 1m").unwrap().minutes, 2.0);
+
+        for invalid in ["", "1", "h", "1.5h", "1 fortnight", "-1s", "1h,2m"] {
+            assert!(Duration::parse_human(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_to_std() {
+        let duration = Duration::parse_iso8601("P1WT1.5S").unwrap();
+        assert_eq!(
+            duration.to_std(),
+            Some(std::time::Duration::from_millis(604_801_500))
+        );
+        assert!(Duration::parse_iso8601("P1Y").unwrap().to_std().is_none());
+    }
+}
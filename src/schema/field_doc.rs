@@ -0,0 +1,43 @@
+//! Documentation attached to object fields.
+//!
+//! Fields are annotated with [`ObjectSchema::doc`](super::ObjectSchema::doc).
+//! [`SchemaRegistry::doc_for_path`](crate::SchemaRegistry::doc_for_path)
+//! looks them up, and
+//! [`SchemaRegistry::with_doc_links`](crate::SchemaRegistry::with_doc_links)
+//! points errors at them.
+
+/// Documentation for a field: what it holds and where to read more.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::FieldDoc;
+///
+/// let doc = FieldDoc::new("Where we send receipts")
+///     .link("https://docs.example.com/users#email");
+/// assert_eq!(doc.description.as_deref(), Some("Where we send receipts"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldDoc {
+    /// A short description, exported as the field's JSON Schema
+    /// `description`.
+    pub description: Option<String>,
+    /// A link to documentation on how to fix an invalid value.
+    pub link: Option<String>,
+}
+
+impl FieldDoc {
+    /// Creates documentation with a description.
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            link: None,
+        }
+    }
+
+    /// Sets the documentation link.
+    pub fn link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+}
@@ -0,0 +1,1263 @@
+//! Schema inference from example documents.
+//!
+//! This module induces a [`SchemaDefinition`] from sample payloads, as a
+//! starting point for APIs that have no specification. The definition is a
+//! plain description that can be inspected, edited, exported with
+//! [`ToJsonSchema`], or turned into a validating schema with
+//! [`SchemaDefinition::to_schema`]. [`SchemaDefinition::refine`] widens a
+//! definition as new samples arrive, reporting what each sample changed.
+
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use stillwater::Validation;
+
+use crate::error::{describe_type, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+use crate::validation::ValidationContext;
+
+use super::primitive::{AnySchema, BooleanSchema, NullSchema, NumberBound, NumberSchema};
+use super::{
+    ArraySchema, IntegerSchema, ObjectSchema, Schema, SchemaLike, StringSchema, ValueValidator,
+};
+
+/// Options controlling how strict an inferred schema is.
+///
+/// The defaults infer enums, ranges, and formats but allow additional
+/// properties. [`strict`](Self::strict) also rejects unknown properties;
+/// [`lenient`](Self::lenient) infers only types and optionality.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::schema::InferOptions;
+///
+/// let options = InferOptions::new().max_enum_values(3).ranges(false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferOptions {
+    max_enum_values: usize,
+    ranges: bool,
+    formats: bool,
+    additional_properties: bool,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        Self {
+            max_enum_values: 10,
+            ranges: true,
+            formats: true,
+            additional_properties: true,
+        }
+    }
+}
+
+impl InferOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Infers enums, ranges, and formats, and rejects unknown properties.
+    pub fn strict() -> Self {
+        Self::default().additional_properties(false)
+    }
+
+    /// Infers only types and optionality.
+    pub fn lenient() -> Self {
+        Self::default()
+            .max_enum_values(0)
+            .ranges(false)
+            .formats(false)
+    }
+
+    /// Sets the maximum number of distinct values for a string field to be
+    /// inferred as an enum. `0` disables enum inference.
+    ///
+    /// A field only becomes an enum when its values repeat, i.e. there are at
+    /// least twice as many samples as distinct values.
+    pub fn max_enum_values(mut self, max: usize) -> Self {
+        self.max_enum_values = max;
+        self
+    }
+
+    /// Sets whether numeric ranges, string lengths, and array lengths are
+    /// bounded by the observed minimum and maximum.
+    pub fn ranges(mut self, enabled: bool) -> Self {
+        self.ranges = enabled;
+        self
+    }
+
+    /// Sets whether string formats are detected when every sample matches.
+    pub fn formats(mut self, enabled: bool) -> Self {
+        self.formats = enabled;
+        self
+    }
+
+    /// Sets whether inferred objects accept properties not seen in samples.
+    pub fn additional_properties(mut self, allowed: bool) -> Self {
+        self.additional_properties = allowed;
+        self
+    }
+}
+
+/// A string format detected during inference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StringFormat {
+    /// See [`StringSchema::uuid`].
+    Uuid,
+    /// See [`StringSchema::datetime`].
+    DateTime,
+    /// See [`StringSchema::date`].
+    Date,
+    /// See [`StringSchema::email`].
+    Email,
+    /// See [`StringSchema::url`].
+    Url,
+    /// See [`StringSchema::ip`].
+    Ip,
+}
+
+impl StringFormat {
+    /// Formats in detection order, most specific first.
+    const ALL: [StringFormat; 6] = [
+        StringFormat::Uuid,
+        StringFormat::DateTime,
+        StringFormat::Date,
+        StringFormat::Email,
+        StringFormat::Url,
+        StringFormat::Ip,
+    ];
+
+    fn apply(self, schema: StringSchema) -> StringSchema {
+        match self {
+            StringFormat::Uuid => schema.uuid(),
+            StringFormat::DateTime => schema.datetime(),
+            StringFormat::Date => schema.date(),
+            StringFormat::Email => schema.email(),
+            StringFormat::Url => schema.url(),
+            StringFormat::Ip => schema.ip(),
+        }
+    }
+}
+
+/// A field of an inferred object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDefinition {
+    /// The field name.
+    pub name: String,
+    /// The schema of the field's values.
+    pub schema: SchemaDefinition,
+    /// Whether the field was present in every sample object.
+    pub required: bool,
+}
+
+/// The kind of change made by [`SchemaDefinition::refine`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WideningKind {
+    /// A value of a new type was seen.
+    Type,
+    /// `null` was seen where it was not accepted.
+    Nullable,
+    /// A required field was missing.
+    Optional,
+    /// A field not seen before was present.
+    Field,
+    /// A number or length fell outside the observed range.
+    Range,
+    /// A new value was added to an enum.
+    EnumValue,
+    /// An enum exceeded the maximum number of values and became a string.
+    EnumDropped,
+    /// A string did not match the detected format.
+    FormatDropped,
+}
+
+/// A change made to a [`SchemaDefinition`] to accept a new sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Widening {
+    /// Where in the sample the change was needed.
+    pub path: JsonPath,
+    /// What kind of change was made.
+    pub kind: WideningKind,
+    /// A description of the change.
+    pub message: String,
+    /// The part of the sample that forced the change; for
+    /// [`WideningKind::Optional`], the object missing the field.
+    pub value: Value,
+}
+
+impl fmt::Display for Widening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_root() {
+            "(root)".to_string()
+        } else {
+            self.path.to_string()
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// A schema description produced by [`Schema::infer`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SchemaDefinition {
+    /// Any value; inferred when there are no samples.
+    Any,
+    /// Only `null`.
+    Null,
+    /// A boolean.
+    Boolean,
+    /// An integer, with the observed range when ranges are inferred.
+    Integer {
+        /// The smallest observed value.
+        min: Option<i64>,
+        /// The largest observed value.
+        max: Option<i64>,
+    },
+    /// A number with a fractional part in at least one sample.
+    Number {
+        /// The smallest observed value.
+        min: Option<f64>,
+        /// The largest observed value.
+        max: Option<f64>,
+    },
+    /// A string.
+    String {
+        /// The shortest observed length, in characters.
+        min_len: Option<usize>,
+        /// The longest observed length, in characters.
+        max_len: Option<usize>,
+        /// The format every sample matched.
+        format: Option<StringFormat>,
+        /// The allowed values, when inferred as an enum.
+        values: Option<Vec<String>>,
+    },
+    /// An array.
+    Array {
+        /// The schema of the items across all samples.
+        items: Box<SchemaDefinition>,
+        /// The fewest observed items.
+        min_items: Option<usize>,
+        /// The most observed items.
+        max_items: Option<usize>,
+    },
+    /// An object.
+    Object {
+        /// The fields, in the order they were first seen.
+        fields: Vec<FieldDefinition>,
+        /// Whether properties not seen in samples are accepted.
+        additional_properties: bool,
+    },
+    /// The inner schema, or `null`.
+    Nullable(Box<SchemaDefinition>),
+    /// Any of several schemas; inferred when samples have mixed types.
+    AnyOf(Vec<SchemaDefinition>),
+}
+
+impl SchemaDefinition {
+    /// Builds a validating schema from this definition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::infer(&[json!({"id": 1}), json!({"id": 2})]).to_schema();
+    ///
+    /// assert!(schema.validate_value(&json!({"id": 2}), &JsonPath::root()).is_success());
+    /// assert!(schema.validate_value(&json!({"id": "2"}), &JsonPath::root()).is_failure());
+    /// ```
+    pub fn to_schema(&self) -> Box<dyn ValueValidator> {
+        match self {
+            SchemaDefinition::Any => Box::new(AnySchema),
+            SchemaDefinition::Null => Box::new(NullSchema),
+            SchemaDefinition::Boolean => Box::new(BooleanSchema),
+            SchemaDefinition::Integer { min, max } => {
+                let mut schema = IntegerSchema::new();
+                if let Some(min) = min {
+                    schema = schema.min(*min);
+                }
+                if let Some(max) = max {
+                    schema = schema.max(*max);
+                }
+                Box::new(schema)
+            }
+            SchemaDefinition::Number { min, max } => {
+                let mut schema = NumberSchema::new();
+                if let Some(min) = min {
+                    schema = schema.bound(NumberBound::Min(*min), None);
+                }
+                if let Some(max) = max {
+                    schema = schema.bound(NumberBound::Max(*max), None);
+                }
+                Box::new(schema)
+            }
+            SchemaDefinition::String {
+                min_len,
+                max_len,
+                format,
+                values,
+            } => {
+                let mut schema = StringSchema::new();
+                if let Some(min) = min_len {
+                    schema = schema.min_len(*min);
+                }
+                if let Some(max) = max_len {
+                    schema = schema.max_len(*max);
+                }
+                if let Some(format) = format {
+                    schema = format.apply(schema);
+                }
+                if let Some(values) = values {
+                    schema = schema.one_of(values.clone());
+                }
+                Box::new(schema)
+            }
+            SchemaDefinition::Array {
+                items,
+                min_items,
+                max_items,
+            } => {
+                let mut schema = ArraySchema::new(Erased(items.to_schema()));
+                if let Some(min) = min_items {
+                    schema = schema.min_len(*min);
+                }
+                if let Some(max) = max_items {
+                    schema = schema.max_len(*max);
+                }
+                Box::new(schema)
+            }
+            SchemaDefinition::Object {
+                fields,
+                additional_properties,
+            } => {
+                let schema = fields.iter().fold(ObjectSchema::new(), |schema, field| {
+                    let value = Erased(field.schema.to_schema());
+                    if field.required {
+                        schema.field(field.name.clone(), value)
+                    } else {
+                        schema.optional(field.name.clone(), value)
+                    }
+                });
+                Box::new(schema.additional_properties(*additional_properties))
+            }
+            SchemaDefinition::Nullable(inner) => Box::new(Schema::optional(inner.to_schema())),
+            SchemaDefinition::AnyOf(variants) => Box::new(Schema::any_of(
+                variants.iter().map(SchemaDefinition::to_schema),
+            )),
+        }
+    }
+
+    /// Widens this definition so it also accepts `sample`, using the default
+    /// [`InferOptions`].
+    ///
+    /// Returns one [`Widening`] per change, each carrying the part of the
+    /// sample that forced it. Feeding production traffic through `refine`
+    /// grows an inferred schema to cover real payloads, and the report shows
+    /// which payloads were responsible so anomalies can be told apart from
+    /// legitimate variation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::schema::WideningKind;
+    /// use postmortem::Schema;
+    /// use serde_json::json;
+    ///
+    /// let mut definition = Schema::infer(&[json!({"age": 30}), json!({"age": 41})]);
+    ///
+    /// let widenings = definition.refine(&json!({"age": 17}));
+    /// assert_eq!(widenings.len(), 1);
+    /// assert_eq!(widenings[0].kind, WideningKind::Range);
+    /// assert_eq!(widenings[0].path.to_string(), "age");
+    /// assert_eq!(widenings[0].value, json!(17));
+    ///
+    /// // Samples the definition already accepts change nothing
+    /// assert!(definition.refine(&json!({"age": 35})).is_empty());
+    /// ```
+    pub fn refine(&mut self, sample: &Value) -> Vec<Widening> {
+        self.refine_with(sample, &InferOptions::default())
+    }
+
+    /// Widens this definition so it also accepts `sample`, with custom
+    /// options.
+    ///
+    /// The options decide how new parts of the sample are inferred and when
+    /// an enum grows too large and becomes a plain string.
+    pub fn refine_with(&mut self, sample: &Value, options: &InferOptions) -> Vec<Widening> {
+        let mut widenings = Vec::new();
+        refine_value(self, sample, &JsonPath::root(), options, &mut widenings);
+        widenings
+    }
+
+    /// Returns the definition's JSON Schema in a canonical format, for
+    /// definition files kept under version control.
+    ///
+    /// The output depends only on what the definition accepts, so tooling
+    /// that regenerates a file produces no diff unless the schema changed:
+    /// keywords appear in a fixed order (`type` first, then constraints,
+    /// then nested schemas), properties are sorted by name, `required` and
+    /// `enum` values are sorted, every member and item is on its own line
+    /// indented by two spaces, and the text ends with a newline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    /// use serde_json::json;
+    ///
+    /// let definition = Schema::infer(&[json!({"name": "Ada", "id": 1})]);
+    ///
+    /// assert_eq!(
+    ///     definition.to_canonical_string(),
+    ///     r#"{
+    ///   "type": "object",
+    ///   "properties": {
+    ///     "id": {
+    ///       "type": "integer",
+    ///       "minimum": 1,
+    ///       "maximum": 1
+    ///     },
+    ///     "name": {
+    ///       "type": "string",
+    ///       "minLength": 3,
+    ///       "maxLength": 3
+    ///     }
+    ///   },
+    ///   "required": [
+    ///     "id",
+    ///     "name"
+    ///   ]
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        let mut out = String::new();
+        write_canonical(&self.to_json_schema(), KeyOrder::Keywords, 0, &mut out);
+        out.push('\n');
+        out
+    }
+}
+
+/// The JSON Schema keywords in the order the canonical format writes them.
+/// Other keys follow, sorted.
+const KEYWORD_ORDER: [&str; 27] = [
+    "$schema",
+    "$id",
+    "$anchor",
+    "$ref",
+    "title",
+    "description",
+    "type",
+    "format",
+    "const",
+    "enum",
+    "minimum",
+    "exclusiveMinimum",
+    "maximum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "items",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "properties",
+    "required",
+    "additionalProperties",
+    "anyOf",
+    "oneOf",
+];
+
+/// How the canonical format orders the keys of an object.
+#[derive(Clone, Copy)]
+enum KeyOrder {
+    /// A schema, whose keys are keywords.
+    Keywords,
+    /// A map from names to schemas, such as `properties`.
+    Names,
+}
+
+fn write_canonical(value: &Value, order: KeyOrder, indent: usize, out: &mut String) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            match order {
+                KeyOrder::Keywords => keys.sort_by_key(|key| {
+                    let rank = KEYWORD_ORDER.iter().position(|keyword| keyword == key);
+                    (rank.unwrap_or(KEYWORD_ORDER.len()), key.as_str())
+                }),
+                KeyOrder::Names => keys.sort(),
+            }
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(indent + 1, out);
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push_str(": ");
+                let value = &object[key.as_str()];
+                match (order, key.as_str(), value) {
+                    (KeyOrder::Keywords, "required" | "enum", Value::Array(items)) => {
+                        let mut items = items.clone();
+                        items.sort_by_cached_key(Value::to_string);
+                        write_canonical(&Value::Array(items), order, indent + 1, out);
+                    }
+                    (KeyOrder::Keywords, "properties" | "patternProperties" | "$defs", _) => {
+                        write_canonical(value, KeyOrder::Names, indent + 1, out);
+                    }
+                    _ => write_canonical(value, KeyOrder::Keywords, indent + 1, out),
+                }
+            }
+            newline(indent, out);
+            out.push('}');
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(indent + 1, out);
+                write_canonical(item, KeyOrder::Keywords, indent + 1, out);
+            }
+            newline(indent, out);
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn newline(indent: usize, out: &mut String) {
+    out.push('\n');
+    out.extend(std::iter::repeat_n("  ", indent));
+}
+
+impl ToJsonSchema for SchemaDefinition {
+    fn to_json_schema(&self) -> Value {
+        self.to_schema().to_json_schema()
+    }
+}
+
+/// Infers a definition from sample values.
+pub(super) fn infer(samples: &[Value], options: &InferOptions) -> SchemaDefinition {
+    let samples: Vec<&Value> = samples.iter().collect();
+    infer_values(&samples, options)
+}
+
+fn infer_values(values: &[&Value], options: &InferOptions) -> SchemaDefinition {
+    if values.is_empty() {
+        return SchemaDefinition::Any;
+    }
+
+    let mut nulls = 0;
+    let mut booleans = 0;
+    let mut numbers = Vec::new();
+    let mut strings = Vec::new();
+    let mut arrays = Vec::new();
+    let mut objects = Vec::new();
+    for value in values {
+        match value {
+            Value::Null => nulls += 1,
+            Value::Bool(_) => booleans += 1,
+            Value::Number(n) => numbers.push(n),
+            Value::String(s) => strings.push(s.as_str()),
+            Value::Array(items) => arrays.push(items),
+            Value::Object(map) => objects.push(map),
+        }
+    }
+
+    let mut variants = Vec::new();
+    if booleans > 0 {
+        variants.push(SchemaDefinition::Boolean);
+    }
+    if !numbers.is_empty() {
+        variants.push(infer_number(&numbers, options));
+    }
+    if !strings.is_empty() {
+        variants.push(infer_string(&strings, options));
+    }
+    if !arrays.is_empty() {
+        variants.push(infer_array(&arrays, options));
+    }
+    if !objects.is_empty() {
+        variants.push(infer_object(&objects, options));
+    }
+
+    let definition = match variants.len() {
+        0 => return SchemaDefinition::Null,
+        1 => variants.remove(0),
+        _ => SchemaDefinition::AnyOf(variants),
+    };
+    if nulls > 0 {
+        SchemaDefinition::Nullable(Box::new(definition))
+    } else {
+        definition
+    }
+}
+
+fn infer_number(numbers: &[&serde_json::Number], options: &InferOptions) -> SchemaDefinition {
+    let integers: Option<Vec<i64>> = numbers.iter().map(|n| n.as_i64()).collect();
+    match integers {
+        Some(integers) => SchemaDefinition::Integer {
+            min: integers.iter().min().copied().filter(|_| options.ranges),
+            max: integers.iter().max().copied().filter(|_| options.ranges),
+        },
+        None => {
+            let floats: Vec<f64> = numbers.iter().filter_map(|n| n.as_f64()).collect();
+            SchemaDefinition::Number {
+                min: floats
+                    .iter()
+                    .copied()
+                    .reduce(f64::min)
+                    .filter(|_| options.ranges),
+                max: floats
+                    .iter()
+                    .copied()
+                    .reduce(f64::max)
+                    .filter(|_| options.ranges),
+            }
+        }
+    }
+}
+
+fn infer_string(strings: &[&str], options: &InferOptions) -> SchemaDefinition {
+    let mut distinct: Vec<&str> = Vec::new();
+    for s in strings {
+        if !distinct.contains(s) {
+            distinct.push(s);
+        }
+    }
+    if distinct.len() <= options.max_enum_values && strings.len() >= 2 * distinct.len() {
+        return SchemaDefinition::String {
+            min_len: None,
+            max_len: None,
+            format: None,
+            values: Some(distinct.into_iter().map(str::to_string).collect()),
+        };
+    }
+
+    let format = if options.formats {
+        StringFormat::ALL.into_iter().find(|format| {
+            let schema = format.apply(StringSchema::new());
+            strings.iter().all(|s| {
+                schema
+                    .validate(&Value::String(s.to_string()), &JsonPath::root())
+                    .is_success()
+            })
+        })
+    } else {
+        None
+    };
+
+    let lengths = strings.iter().map(|s| s.chars().count());
+    SchemaDefinition::String {
+        min_len: lengths.clone().min().filter(|_| options.ranges),
+        max_len: lengths.max().filter(|_| options.ranges),
+        format,
+        values: None,
+    }
+}
+
+fn infer_array(arrays: &[&Vec<Value>], options: &InferOptions) -> SchemaDefinition {
+    let items: Vec<&Value> = arrays.iter().flat_map(|items| items.iter()).collect();
+    let lengths = arrays.iter().map(|items| items.len());
+    SchemaDefinition::Array {
+        items: Box::new(infer_values(&items, options)),
+        min_items: lengths.clone().min().filter(|_| options.ranges),
+        max_items: lengths.max().filter(|_| options.ranges),
+    }
+}
+
+fn infer_object(objects: &[&Map<String, Value>], options: &InferOptions) -> SchemaDefinition {
+    let mut fields: IndexMap<&str, Vec<&Value>> = IndexMap::new();
+    for object in objects {
+        for (name, value) in object.iter() {
+            fields.entry(name.as_str()).or_default().push(value);
+        }
+    }
+
+    SchemaDefinition::Object {
+        fields: fields
+            .into_iter()
+            .map(|(name, values)| FieldDefinition {
+                name: name.to_string(),
+                required: values.len() == objects.len(),
+                schema: infer_values(&values, options),
+            })
+            .collect(),
+        additional_properties: options.additional_properties,
+    }
+}
+
+/// Widens `definition` to accept `value`, recording each change.
+fn refine_value(
+    definition: &mut SchemaDefinition,
+    value: &Value,
+    path: &JsonPath,
+    options: &InferOptions,
+    widenings: &mut Vec<Widening>,
+) {
+    let mut widen = |kind, message: String| {
+        widenings.push(Widening {
+            path: path.clone(),
+            kind,
+            message,
+            value: value.clone(),
+        })
+    };
+
+    match (&mut *definition, value) {
+        // Nothing has been observed yet, so there is nothing to widen
+        (SchemaDefinition::Any, _) => *definition = infer_values(&[value], options),
+        (SchemaDefinition::Nullable(_) | SchemaDefinition::Null, Value::Null) => {}
+        (SchemaDefinition::Nullable(inner), _) => {
+            refine_value(inner, value, path, options, widenings)
+        }
+        (_, Value::Null) => {
+            widen(WideningKind::Nullable, "now accepts null".to_string());
+            let inner = std::mem::replace(definition, SchemaDefinition::Any);
+            *definition = SchemaDefinition::Nullable(Box::new(inner));
+        }
+        (SchemaDefinition::Null, _) => {
+            widen(
+                WideningKind::Type,
+                format!("now accepts {}", describe_type(value)),
+            );
+            *definition = SchemaDefinition::Nullable(Box::new(infer_values(&[value], options)));
+        }
+        (SchemaDefinition::Boolean, Value::Bool(_)) => {}
+        (SchemaDefinition::Integer { min, max }, Value::Number(n)) => match n.as_i64() {
+            Some(n) => {
+                if min.is_some_and(|min| n < min) {
+                    widen(
+                        WideningKind::Range,
+                        format!("minimum widened from {} to {}", min.unwrap(), n),
+                    );
+                    *min = Some(n);
+                }
+                if max.is_some_and(|max| n > max) {
+                    widen(
+                        WideningKind::Range,
+                        format!("maximum widened from {} to {}", max.unwrap(), n),
+                    );
+                    *max = Some(n);
+                }
+            }
+            None => {
+                widen(WideningKind::Type, "integer widened to number".to_string());
+                *definition = SchemaDefinition::Number {
+                    min: min.map(|v| v as f64),
+                    max: max.map(|v| v as f64),
+                };
+                refine_value(definition, value, path, options, widenings);
+            }
+        },
+        (SchemaDefinition::Number { min, max }, Value::Number(n)) => {
+            let n = n.as_f64().unwrap_or(f64::NAN);
+            if min.is_some_and(|min| n < min) {
+                widen(
+                    WideningKind::Range,
+                    format!("minimum widened from {} to {}", min.unwrap(), n),
+                );
+                *min = Some(n);
+            }
+            if max.is_some_and(|max| n > max) {
+                widen(
+                    WideningKind::Range,
+                    format!("maximum widened from {} to {}", max.unwrap(), n),
+                );
+                *max = Some(n);
+            }
+        }
+        (
+            SchemaDefinition::String {
+                min_len,
+                max_len,
+                format,
+                values,
+            },
+            Value::String(s),
+        ) => {
+            if let Some(known) = values {
+                if known.contains(s) {
+                    return;
+                }
+                if known.len() < options.max_enum_values {
+                    widen(WideningKind::EnumValue, format!("enum value '{}' added", s));
+                    known.push(s.clone());
+                    return;
+                }
+                widen(
+                    WideningKind::EnumDropped,
+                    format!("more than {} distinct values", options.max_enum_values),
+                );
+                let mut strings: Vec<&str> = known.iter().map(String::as_str).collect();
+                strings.push(s);
+                // Re-infer without enums from every value seen so far
+                *definition = infer_string(&strings, &options.clone().max_enum_values(0));
+                return;
+            }
+
+            let len = s.chars().count();
+            if min_len.is_some_and(|min| len < min) {
+                widen(
+                    WideningKind::Range,
+                    format!(
+                        "minimum length widened from {} to {}",
+                        min_len.unwrap(),
+                        len
+                    ),
+                );
+                *min_len = Some(len);
+            }
+            if max_len.is_some_and(|max| len > max) {
+                widen(
+                    WideningKind::Range,
+                    format!(
+                        "maximum length widened from {} to {}",
+                        max_len.unwrap(),
+                        len
+                    ),
+                );
+                *max_len = Some(len);
+            }
+            if let Some(f) = format {
+                if f.apply(StringSchema::new())
+                    .validate(value, path)
+                    .is_failure()
+                {
+                    widen(
+                        WideningKind::FormatDropped,
+                        format!("no longer matches format {:?}", f),
+                    );
+                    *format = None;
+                }
+            }
+        }
+        (
+            SchemaDefinition::Array {
+                items,
+                min_items,
+                max_items,
+            },
+            Value::Array(elements),
+        ) => {
+            let len = elements.len();
+            if min_items.is_some_and(|min| len < min) {
+                widen(
+                    WideningKind::Range,
+                    format!(
+                        "minimum items widened from {} to {}",
+                        min_items.unwrap(),
+                        len
+                    ),
+                );
+                *min_items = Some(len);
+            }
+            if max_items.is_some_and(|max| len > max) {
+                widen(
+                    WideningKind::Range,
+                    format!(
+                        "maximum items widened from {} to {}",
+                        max_items.unwrap(),
+                        len
+                    ),
+                );
+                *max_items = Some(len);
+            }
+            for (i, element) in elements.iter().enumerate() {
+                refine_value(items, element, &path.push_index(i), options, widenings);
+            }
+        }
+        (SchemaDefinition::Object { fields, .. }, Value::Object(map)) => {
+            for field in fields.iter_mut() {
+                match map.get(&field.name) {
+                    Some(v) => refine_value(
+                        &mut field.schema,
+                        v,
+                        &path.push_field(&field.name),
+                        options,
+                        widenings,
+                    ),
+                    None if field.required => {
+                        widenings.push(Widening {
+                            path: path.push_field(&field.name),
+                            kind: WideningKind::Optional,
+                            message: "field is now optional".to_string(),
+                            value: value.clone(),
+                        });
+                        field.required = false;
+                    }
+                    None => {}
+                }
+            }
+            for (name, v) in map {
+                if fields.iter().any(|f| f.name == *name) {
+                    continue;
+                }
+                widenings.push(Widening {
+                    path: path.push_field(name),
+                    kind: WideningKind::Field,
+                    message: "new optional field".to_string(),
+                    value: v.clone(),
+                });
+                fields.push(FieldDefinition {
+                    name: name.clone(),
+                    schema: infer_values(&[v], options),
+                    required: false,
+                });
+            }
+        }
+        (SchemaDefinition::AnyOf(variants), _) => {
+            match variants.iter_mut().find(|v| accepts_type(v, value)) {
+                Some(variant) => refine_value(variant, value, path, options, widenings),
+                None => {
+                    widen(
+                        WideningKind::Type,
+                        format!("now accepts {}", describe_type(value)),
+                    );
+                    variants.push(infer_values(&[value], options));
+                }
+            }
+        }
+        _ => {
+            widen(
+                WideningKind::Type,
+                format!("now accepts {}", describe_type(value)),
+            );
+            let existing = std::mem::replace(definition, SchemaDefinition::Any);
+            *definition = SchemaDefinition::AnyOf(vec![existing, infer_values(&[value], options)]);
+        }
+    }
+}
+
+/// Returns whether a definition is of the JSON type of `value`.
+fn accepts_type(definition: &SchemaDefinition, value: &Value) -> bool {
+    matches!(
+        (definition, value),
+        (SchemaDefinition::Boolean, Value::Bool(_))
+            | (
+                SchemaDefinition::Integer { .. } | SchemaDefinition::Number { .. },
+                Value::Number(_)
+            )
+            | (SchemaDefinition::String { .. }, Value::String(_))
+            | (SchemaDefinition::Array { .. }, Value::Array(_))
+            | (SchemaDefinition::Object { .. }, Value::Object(_))
+    )
+}
+
+/// Adapts a boxed validator for use as a field or item schema.
+struct Erased(Box<dyn ValueValidator>);
+
+impl SchemaLike for Erased {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.0.validate_value(value, path)
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.0.validate_value(value, path)
+    }
+
+    fn validate_to_value_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        self.0.validate_value_with_context(value, path, context)
+    }
+}
+
+impl ToJsonSchema for Erased {
+    fn to_json_schema(&self) -> Value {
+        self.0.to_json_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn samples() -> Vec<Value> {
+        vec![
+            json!({"id": "6fa459ea-ee8a-3ca4-894e-db77e160355e", "status": "active", "age": 31, "score": 1.5, "tags": ["a"]}),
+            json!({"id": "16fd2706-8baf-433b-82eb-8c7fada847da", "status": "inactive", "age": 45, "score": 2, "tags": []}),
+            json!({"id": "886313e1-3b8a-5372-9b90-0c9aee199e5d", "status": "active", "age": 27, "score": null, "nickname": "bo"}),
+            json!({"id": "a8098c1a-f86e-11da-bd1a-00112444be1e", "status": "inactive", "age": 60, "score": 0.25, "tags": ["b", "c"]}),
+        ]
+    }
+
+    fn field<'a>(definition: &'a SchemaDefinition, name: &str) -> &'a FieldDefinition {
+        match definition {
+            SchemaDefinition::Object { fields, .. } => {
+                fields.iter().find(|f| f.name == name).unwrap()
+            }
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_infers_types_and_optionality() {
+        let definition = infer(&samples(), &InferOptions::new());
+
+        let id = field(&definition, "id");
+        assert!(id.required);
+        assert!(matches!(
+            id.schema,
+            SchemaDefinition::String {
+                format: Some(StringFormat::Uuid),
+                ..
+            }
+        ));
+
+        assert_eq!(
+            field(&definition, "age").schema,
+            SchemaDefinition::Integer {
+                min: Some(27),
+                max: Some(60)
+            }
+        );
+        assert_eq!(
+            field(&definition, "score").schema,
+            SchemaDefinition::Nullable(Box::new(SchemaDefinition::Number {
+                min: Some(0.25),
+                max: Some(2.0)
+            }))
+        );
+        assert!(!field(&definition, "tags").required);
+        assert!(!field(&definition, "nickname").required);
+    }
+
+    #[test]
+    fn test_infers_enums_from_repeated_values() {
+        let definition = infer(&samples(), &InferOptions::new());
+        let SchemaDefinition::String { values, .. } = &field(&definition, "status").schema else {
+            panic!("expected string");
+        };
+        assert_eq!(
+            values.as_deref(),
+            Some(&["active".to_string(), "inactive".to_string()][..])
+        );
+
+        // Unique values are not an enum
+        let definition = infer(&[json!("a"), json!("b")], &InferOptions::new());
+        assert!(matches!(
+            definition,
+            SchemaDefinition::String { values: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_mixed_types() {
+        let definition = infer(
+            &[json!(1), json!("x"), json!(true)],
+            &InferOptions::lenient(),
+        );
+        assert_eq!(
+            definition,
+            SchemaDefinition::AnyOf(vec![
+                SchemaDefinition::Boolean,
+                SchemaDefinition::Integer {
+                    min: None,
+                    max: None
+                },
+                SchemaDefinition::String {
+                    min_len: None,
+                    max_len: None,
+                    format: None,
+                    values: None
+                },
+            ])
+        );
+        assert_eq!(infer(&[], &InferOptions::new()), SchemaDefinition::Any);
+        assert_eq!(
+            infer(&[json!(null)], &InferOptions::new()),
+            SchemaDefinition::Null
+        );
+    }
+
+    #[test]
+    fn test_generated_schema_validates_samples() {
+        let samples = samples();
+        for options in [
+            InferOptions::new(),
+            InferOptions::strict(),
+            InferOptions::lenient(),
+        ] {
+            let schema = infer(&samples, &options).to_schema();
+            for sample in &samples {
+                assert!(schema
+                    .validate_value(sample, &JsonPath::root())
+                    .is_success());
+            }
+        }
+    }
+
+    #[test]
+    fn test_strictness() {
+        let extra = json!({
+            "id": "6fa459ea-ee8a-3ca4-894e-db77e160355e",
+            "status": "active",
+            "age": 99,
+            "score": 1,
+            "extra": true
+        });
+
+        let errors = infer(&samples(), &InferOptions::strict())
+            .to_schema()
+            .validate_value(&extra, &JsonPath::root())
+            .into_result()
+            .unwrap_err();
+        let codes: Vec<&str> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["max_value", "additional_property"]);
+
+        let schema = infer(&samples(), &InferOptions::lenient()).to_schema();
+        assert!(schema
+            .validate_value(&extra, &JsonPath::root())
+            .is_success());
+    }
+
+    #[test]
+    fn test_refine_reports_widenings() {
+        let mut definition = infer(&samples(), &InferOptions::new());
+
+        let sample = json!({
+            "id": "not-a-uuid",
+            "status": "banned",
+            "age": 12.5,
+            "score": 3,
+            "tags": ["d", 1],
+            "region": "eu"
+        });
+        let widenings = definition.refine(&sample);
+        let report: Vec<(String, WideningKind)> = widenings
+            .iter()
+            .map(|w| (w.path.to_string(), w.kind))
+            .collect();
+        assert_eq!(
+            report,
+            [
+                ("age".to_string(), WideningKind::Type),
+                ("age".to_string(), WideningKind::Range),
+                ("id".to_string(), WideningKind::Range),
+                ("id".to_string(), WideningKind::FormatDropped),
+                ("score".to_string(), WideningKind::Range),
+                ("status".to_string(), WideningKind::EnumValue),
+                ("tags[1]".to_string(), WideningKind::Type),
+                ("region".to_string(), WideningKind::Field),
+            ]
+        );
+        assert_eq!(widenings[0].value, json!(12.5));
+        assert_eq!(
+            widenings[1].to_string(),
+            "age: minimum widened from 27 to 12.5"
+        );
+
+        // The refined definition accepts the sample, and refining again is a no-op
+        let schema = definition.to_schema();
+        assert!(schema
+            .validate_value(&sample, &JsonPath::root())
+            .is_success());
+        assert!(definition.refine(&sample).is_empty());
+    }
+
+    #[test]
+    fn test_refine_optional_and_nullable() {
+        let mut definition = infer(&[json!({"a": 1, "b": "x"})], &InferOptions::new());
+        let widenings = definition.refine(&json!({"a": null}));
+
+        assert_eq!(widenings.len(), 2);
+        assert_eq!(widenings[0].kind, WideningKind::Nullable);
+        assert_eq!(widenings[1].kind, WideningKind::Optional);
+        assert_eq!(widenings[1].path.to_string(), "b");
+        assert_eq!(widenings[1].value, json!({"a": null}));
+    }
+
+    #[test]
+    fn test_refine_drops_large_enums() {
+        let options = InferOptions::new().max_enum_values(2);
+        let mut definition = infer(&[json!("a"), json!("a"), json!("b"), json!("b")], &options);
+
+        let widenings = definition.refine_with(&json!("cc"), &options);
+        assert_eq!(widenings[0].kind, WideningKind::EnumDropped);
+        assert_eq!(
+            definition,
+            SchemaDefinition::String {
+                min_len: Some(1),
+                max_len: Some(2),
+                format: None,
+                values: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_refine_fills_unknown_items() {
+        let mut definition = infer(&[json!([])], &InferOptions::lenient());
+        assert!(definition.refine(&json!([1])).is_empty());
+        assert!(matches!(
+            definition,
+            SchemaDefinition::Array { ref items, .. } if matches!(**items, SchemaDefinition::Integer { .. })
+        ));
+    }
+
+    #[test]
+    fn test_canonical_string_is_stable() {
+        let first = infer(
+            &[
+                json!({"status": "open", "tags": ["a"], "id": 1}),
+                json!({"id": 2, "status": "closed", "tags": []}),
+                json!({"status": "open", "id": 3, "tags": ["b"]}),
+                json!({"id": 4, "tags": [], "status": "closed"}),
+            ],
+            &InferOptions::new(),
+        );
+        let second = infer(
+            &[
+                json!({"tags": [], "status": "closed", "id": 4}),
+                json!({"id": 3, "status": "open", "tags": ["b"]}),
+                json!({"status": "closed", "id": 2, "tags": []}),
+                json!({"id": 1, "tags": ["a"], "status": "open"}),
+            ],
+            &InferOptions::new(),
+        );
+        assert_ne!(first, second);
+
+        let canonical = first.to_canonical_string();
+        assert_eq!(canonical, second.to_canonical_string());
+        assert!(canonical.contains("\"enum\": [\n        \"closed\",\n        \"open\"\n      ]"));
+        assert!(canonical.ends_with("}\n"));
+        assert_eq!(
+            serde_json::from_str::<Value>(&canonical).unwrap(),
+            second.to_json_schema()
+        );
+    }
+
+    #[test]
+    fn test_json_schema_output() {
+        let definition = infer(&[json!({"n": 1}), json!({"n": 3})], &InferOptions::new());
+        assert_eq!(
+            definition.to_json_schema(),
+            json!({
+                "type": "object",
+                "properties": {"n": {"type": "integer", "minimum": 1, "maximum": 3}},
+                "required": ["n"]
+            })
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Key case conventions for object output.
+//!
+//! [`ObjectSchema::rename_keys`](super::ObjectSchema::rename_keys) renames the
+//! keys of a validated object, so that e.g. a camelCase API payload comes out
+//! of validation with the snake_case keys the rest of the program uses.
+
+/// A naming convention for object keys.
+///
+/// Keys are split into words at underscores, hyphens, spaces, and changes of
+/// case, so `userID`, `user_id`, and `User-Id` all become `user_id` in
+/// [`SnakeCase`](KeyCase::SnakeCase). A run of capitals is one word, except
+/// that its last capital starts the next word if a lowercase letter
+/// follows: `HTTPServer` is `http` and `server`.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::KeyCase;
+///
+/// assert_eq!(KeyCase::SnakeCase.apply("createdAt"), "created_at");
+/// assert_eq!(KeyCase::CamelCase.apply("created_at"), "createdAt");
+/// assert_eq!(KeyCase::KebabCase.apply("HTTPServer"), "http-server");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyCase {
+    /// `user_name`
+    SnakeCase,
+    /// `userName`
+    CamelCase,
+    /// `UserName`
+    PascalCase,
+    /// `user-name`
+    KebabCase,
+    /// `USER_NAME`
+    ScreamingSnakeCase,
+}
+
+impl KeyCase {
+    /// Converts a key to this convention.
+    pub fn apply(self, key: &str) -> String {
+        let words = words(key);
+        match self {
+            KeyCase::SnakeCase => words.join("_"),
+            KeyCase::KebabCase => words.join("-"),
+            KeyCase::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            KeyCase::CamelCase => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.clone()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            KeyCase::PascalCase => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+/// Splits a key into lowercase words.
+fn words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, '_' | '-' | ' ' | '.') {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Uppercases the first letter of a word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("userName"), ["user", "name"]);
+        assert_eq!(words("user_name"), ["user", "name"]);
+        assert_eq!(words("User-Name"), ["user", "name"]);
+        assert_eq!(words("userID"), ["user", "id"]);
+        assert_eq!(words("HTTPServer"), ["http", "server"]);
+        assert_eq!(words("address2Line"), ["address2", "line"]);
+        assert_eq!(words("__private"), ["private"]);
+        assert!(words("").is_empty());
+    }
+
+    #[test]
+    fn test_apply() {
+        let key = "shippingAddressLine1";
+        assert_eq!(KeyCase::SnakeCase.apply(key), "shipping_address_line1");
+        assert_eq!(KeyCase::CamelCase.apply(key), "shippingAddressLine1");
+        assert_eq!(KeyCase::PascalCase.apply(key), "ShippingAddressLine1");
+        assert_eq!(KeyCase::KebabCase.apply(key), "shipping-address-line1");
+        assert_eq!(
+            KeyCase::ScreamingSnakeCase.apply(key),
+            "SHIPPING_ADDRESS_LINE1"
+        );
+    }
+}
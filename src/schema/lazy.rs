@@ -0,0 +1,335 @@
+//! Deferred and self-referencing schemas.
+//!
+//! Recursive structures such as comment trees can be described with a
+//! registry and [`RefSchema`](super::RefSchema), but library code often has
+//! no registry to hand. [`LazySchema`] builds its schema on first use, so a
+//! function can refer to itself through [`Schema::lazy`](super::Schema::lazy),
+//! and [`Schema::recursive`](super::Schema::recursive) hands a schema a
+//! reference to itself.
+//!
+//! Nesting is limited, like references through a registry: each lazy schema
+//! entered counts one level, and past the limit validation fails with a
+//! `max_depth_exceeded` error instead of overflowing the stack.
+
+use serde_json::{json, Value};
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+use crate::schema::{FieldDoc, SchemaLike, ValueValidator};
+use crate::validation::ValidationContext;
+
+/// The nesting limit without a [`ValidationContext`], the same as a
+/// registry's default.
+const DEFAULT_MAX_DEPTH: usize = 100;
+
+type Builder = Box<dyn Fn() -> Arc<dyn ValueValidator> + Send + Sync>;
+
+thread_local! {
+    /// How many lazy schemas the current thread is inside.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// The lazy schemas being exported or searched for references, and
+    /// whether they were reached again.
+    static VISITING: RefCell<Vec<(Key, bool)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Identifies a recursive definition: the builder function of a lazy
+/// schema that captures nothing, so every instance of it builds the same
+/// schema, or else the address of the schema's shared cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Builder(TypeId),
+    Cell(usize),
+}
+
+impl Key {
+    /// Returns the JSON Schema anchor of the definition.
+    fn anchor(self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        format!("lazy-{:08x}", hasher.finish() as u32)
+    }
+}
+
+struct LazyCell {
+    schema: OnceLock<Arc<dyn ValueValidator>>,
+    build: Option<Builder>,
+    key: Key,
+    max_depth: AtomicUsize,
+}
+
+impl LazyCell {
+    fn schema(&self) -> Option<&Arc<dyn ValueValidator>> {
+        match &self.build {
+            Some(build) => Some(self.schema.get_or_init(build)),
+            None => self.schema.get(),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Link {
+    Owned(Arc<LazyCell>),
+    /// A schema's reference to itself, which must not keep it alive.
+    SelfRef(Weak<LazyCell>),
+}
+
+/// A schema built on first use, or referring to itself.
+///
+/// Created by [`Schema::lazy`](super::Schema::lazy) and
+/// [`Schema::recursive`](super::Schema::recursive).
+#[derive(Clone)]
+pub struct LazySchema {
+    link: Link,
+}
+
+impl LazySchema {
+    pub(crate) fn lazy<F, S>(build: F) -> Self
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: ValueValidator + 'static,
+    {
+        // A closure with captures, e.g. `move || level(n - 1)`, may build a
+        // different schema for each instance
+        let stateless = std::mem::size_of::<F>() == 0;
+        let cell = Arc::new_cyclic(|weak: &Weak<LazyCell>| LazyCell {
+            schema: OnceLock::new(),
+            build: Some(Box::new(move || Arc::new(build()))),
+            key: if stateless {
+                Key::Builder(TypeId::of::<F>())
+            } else {
+                Key::Cell(weak.as_ptr() as usize)
+            },
+            max_depth: AtomicUsize::new(DEFAULT_MAX_DEPTH),
+        });
+        Self {
+            link: Link::Owned(cell),
+        }
+    }
+
+    pub(crate) fn recursive<F, S>(define: F) -> Self
+    where
+        F: FnOnce(LazySchema) -> S,
+        S: ValueValidator + 'static,
+    {
+        let cell = Arc::new_cyclic(|weak: &Weak<LazyCell>| LazyCell {
+            schema: OnceLock::new(),
+            build: None,
+            key: Key::Cell(weak.as_ptr() as usize),
+            max_depth: AtomicUsize::new(DEFAULT_MAX_DEPTH),
+        });
+        let this = LazySchema {
+            link: Link::SelfRef(Arc::downgrade(&cell)),
+        };
+        let schema = define(this);
+        let _ = cell.schema.set(Arc::new(schema));
+        Self {
+            link: Link::Owned(cell),
+        }
+    }
+
+    /// Sets how many lazy schemas deep validation may go without a
+    /// [`ValidationContext`]; with one, the context's limit applies. The
+    /// default is 100.
+    pub fn max_depth(self, depth: usize) -> Self {
+        if let Some(cell) = self.cell() {
+            cell.max_depth.store(depth, Ordering::Relaxed);
+        }
+        self
+    }
+
+    fn cell(&self) -> Option<Arc<LazyCell>> {
+        match &self.link {
+            Link::Owned(cell) => Some(Arc::clone(cell)),
+            Link::SelfRef(cell) => cell.upgrade(),
+        }
+    }
+
+    /// Validates against the schema, or fails if it cannot be reached.
+    fn with_schema<F>(&self, path: &JsonPath, validate: F) -> Validation<Value, SchemaErrors>
+    where
+        F: FnOnce(&dyn ValueValidator) -> Validation<Value, SchemaErrors>,
+    {
+        match self.cell() {
+            Some(cell) => match cell.schema() {
+                Some(schema) => validate(schema.as_ref()),
+                None => unavailable(path),
+            },
+            None => unavailable(path),
+        }
+    }
+
+    /// Runs `f` on the schema unless this definition is already being
+    /// visited, in which case `reentered` is called instead. Past the
+    /// default nesting limit, `unavailable` is returned, so definitions that
+    /// build a new schema at each level still end.
+    fn visit<T>(
+        &self,
+        f: impl FnOnce(&dyn ValueValidator) -> T,
+        reentered: impl FnOnce(Key) -> T,
+        unavailable: T,
+    ) -> (T, bool) {
+        let Some(cell) = self.cell() else {
+            return (unavailable, false);
+        };
+        let key = cell.key;
+        // Whether the definition was seen before, or `None` past the limit
+        let seen = VISITING.with(|visiting| {
+            let mut visiting = visiting.borrow_mut();
+            let depth = visiting.len();
+            match visiting.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, reached)) => {
+                    *reached = true;
+                    Some(true)
+                }
+                None if depth >= DEFAULT_MAX_DEPTH => None,
+                None => {
+                    visiting.push((key, false));
+                    Some(false)
+                }
+            }
+        });
+        match seen {
+            Some(true) => return (reentered(key), false),
+            None => return (unavailable, false),
+            Some(false) => {}
+        }
+        let result = match cell.schema() {
+            Some(schema) => f(schema.as_ref()),
+            None => unavailable,
+        };
+        let reached = VISITING.with(|visiting| {
+            visiting
+                .borrow_mut()
+                .pop()
+                .map(|(_, reached)| reached)
+                .unwrap_or(false)
+        });
+        (result, reached)
+    }
+}
+
+fn unavailable(path: &JsonPath) -> Validation<Value, SchemaErrors> {
+    Validation::Failure(SchemaErrors::single(
+        SchemaError::new(
+            path.clone(),
+            "recursive schema was used after the schema it refers to was dropped",
+        )
+        .with_code("missing_reference"),
+    ))
+}
+
+fn depth_exceeded(max_depth: usize, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+    Validation::Failure(SchemaErrors::single(
+        SchemaError::new(
+            path.clone(),
+            format!(
+                "maximum recursion depth {} exceeded at path '{}'",
+                max_depth, path
+            ),
+        )
+        .with_code("max_depth_exceeded"),
+    ))
+}
+
+/// Counts a level of nesting on the current thread until dropped.
+struct DepthGuard;
+
+impl DepthGuard {
+    fn enter(max_depth: usize) -> Option<Self> {
+        DEPTH.with(|depth| {
+            if depth.get() >= max_depth {
+                None
+            } else {
+                depth.set(depth.get() + 1);
+                Some(DepthGuard)
+            }
+        })
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+impl SchemaLike for LazySchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        let max_depth = self
+            .cell()
+            .map(|cell| cell.max_depth.load(Ordering::Relaxed))
+            .unwrap_or(DEFAULT_MAX_DEPTH);
+        let Some(_guard) = DepthGuard::enter(max_depth) else {
+            return depth_exceeded(max_depth, path);
+        };
+        self.with_schema(path, |schema| schema.validate_value(value, path))
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path)
+    }
+
+    fn validate_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        if context.depth() >= context.max_depth() {
+            return depth_exceeded(context.max_depth(), path);
+        }
+        self.with_schema(path, |schema| {
+            schema.validate_value_with_context(value, path, &context.increment_depth())
+        })
+    }
+
+    fn validate_to_value_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        self.validate_with_context(value, path, context)
+    }
+
+    fn collect_refs(&self, refs: &mut Vec<String>) {
+        self.visit(|schema| schema.collect_refs(refs), |_| (), ());
+    }
+
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        match self.cell()?.schema() {
+            Some(schema) => schema.field_doc(path),
+            None => None,
+        }
+    }
+}
+
+impl ToJsonSchema for LazySchema {
+    /// Exports the schema, with each reference back to a definition being
+    /// exported as a `$ref` to an anchor on it.
+    fn to_json_schema(&self) -> Value {
+        let mut anchor = None;
+        let (mut exported, reached) = self.visit(
+            |schema| {
+                anchor = self.cell().map(|cell| cell.key.anchor());
+                schema.to_json_schema()
+            },
+            |key| json!({ "$ref": format!("#{}", key.anchor()) }),
+            json!({}),
+        );
+        if let (true, Some(anchor), Some(object)) = (reached, anchor, exported.as_object_mut()) {
+            object.insert("$anchor".to_string(), json!(anchor));
+        }
+        exported
+    }
+}
@@ -0,0 +1,185 @@
+//! Embedded code tables for localization formats.
+//!
+//! [`StringSchema::country_code`](super::StringSchema::country_code),
+//! [`StringSchema::currency_code`](super::StringSchema::currency_code), and
+//! [`StringSchema::language_tag`](super::StringSchema::language_tag) check
+//! values against these tables. The tables are sorted so lookups can use a
+//! binary search.
+
+/// Officially assigned ISO 3166-1 alpha-2 country codes.
+const COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Active ISO 4217 currency codes, including fund and precious-metal codes.
+const CURRENCY_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD",
+    "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD",
+    "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF", "XPD",
+    "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+/// Returns whether `s` is an assigned ISO 3166-1 alpha-2 country code.
+///
+/// Codes are matched in upper case only, as ISO 3166 writes them.
+pub(crate) fn is_country_code(s: &str) -> bool {
+    COUNTRY_CODES.binary_search(&s).is_ok()
+}
+
+/// Returns whether `s` is an active ISO 4217 currency code.
+///
+/// Codes are matched in upper case only, as ISO 4217 writes them.
+pub(crate) fn is_currency_code(s: &str) -> bool {
+    CURRENCY_CODES.binary_search(&s).is_ok()
+}
+
+/// Returns whether `s` is a well-formed BCP 47 language tag.
+///
+/// This checks the RFC 5646 syntax (language, script, region, variants,
+/// extensions, and private use) case-insensitively, without consulting the
+/// IANA subtag registry, so `en-US`, `zh-Hant-TW`, and `x-private` pass while
+/// `en_US` and `en-US-12` do not.
+pub(crate) fn is_language_tag(s: &str) -> bool {
+    let subtags: Vec<&str> = s.split('-').collect();
+    if subtags
+        .iter()
+        .any(|t| t.is_empty() || t.len() > 8 || !t.bytes().all(|b| b.is_ascii_alphanumeric()))
+    {
+        return false;
+    }
+
+    let mut rest = subtags.as_slice();
+    if is_private_use(rest) {
+        return true;
+    }
+
+    // language: 2-3 letters with up to three extlang subtags, or 4-8 letters
+    match rest.first() {
+        Some(t) if is_alpha(t) && (2..=3).contains(&t.len()) => {
+            rest = &rest[1..];
+            let mut extlangs = 0;
+            while extlangs < 3 && rest.first().is_some_and(|t| t.len() == 3 && is_alpha(t)) {
+                rest = &rest[1..];
+                extlangs += 1;
+            }
+        }
+        Some(t) if is_alpha(t) && t.len() >= 4 => rest = &rest[1..],
+        _ => return false,
+    }
+
+    // script: 4 letters
+    if rest.first().is_some_and(|t| t.len() == 4 && is_alpha(t)) {
+        rest = &rest[1..];
+    }
+
+    // region: 2 letters or 3 digits
+    if rest.first().is_some_and(|t| {
+        (t.len() == 2 && is_alpha(t)) || (t.len() == 3 && t.bytes().all(|b| b.is_ascii_digit()))
+    }) {
+        rest = &rest[1..];
+    }
+
+    // variants: 5-8 alphanumerics, or 4 starting with a digit
+    while rest
+        .first()
+        .is_some_and(|t| t.len() >= 5 || (t.len() == 4 && t.as_bytes()[0].is_ascii_digit()))
+    {
+        rest = &rest[1..];
+    }
+
+    // extensions: a singleton other than `x` followed by 2-8 character subtags
+    while let Some(singleton) = rest.first() {
+        if singleton.len() != 1 || singleton.eq_ignore_ascii_case("x") {
+            break;
+        }
+        let count = rest[1..].iter().take_while(|t| t.len() >= 2).count();
+        if count == 0 {
+            return false;
+        }
+        rest = &rest[1 + count..];
+    }
+
+    rest.is_empty() || is_private_use(rest)
+}
+
+/// Returns whether the subtags are `x` followed by at least one private-use
+/// subtag.
+fn is_private_use(subtags: &[&str]) -> bool {
+    subtags.len() > 1 && subtags[0].eq_ignore_ascii_case("x")
+}
+
+fn is_alpha(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_are_sorted() {
+        assert!(COUNTRY_CODES.windows(2).all(|w| w[0] < w[1]));
+        assert!(CURRENCY_CODES.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_language_tag() {
+        for valid in [
+            "en",
+            "en-US",
+            "zh-Hant-TW",
+            "sr-Latn-RS",
+            "es-419",
+            "de-CH-1996",
+            "sl-rozaj-biske",
+            "zh-yue-HK",
+            "en-a-bbb-x-a-ccc",
+            "x-whatever",
+            "EN-us",
+        ] {
+            assert!(is_language_tag(valid), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "",
+            "e",
+            "en_US",
+            "en-",
+            "-en",
+            "en--US",
+            "123",
+            "en-a",
+            "en-x",
+            "en-US-u",
+            "toolongsubtag",
+            "en-US-12",
+        ] {
+            assert!(!is_language_tag(invalid), "{} should be invalid", invalid);
+        }
+    }
+}
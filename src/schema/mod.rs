@@ -17,20 +17,50 @@
 //! ```
 
 mod array;
+#[cfg(feature = "chrono")]
+mod calendar;
+pub(crate) mod civil;
 mod combinators;
+mod decimal;
+mod duration;
+mod field_doc;
+mod infer;
+mod key_case;
+mod lazy;
+mod locale;
 mod numeric;
 mod object;
+mod ops;
+pub(crate) mod primitive;
+mod projection;
 mod ref_schema;
+mod scoped;
+#[cfg(feature = "signatures")]
+mod signature;
+#[cfg(feature = "strict-formats")]
+mod strict;
 mod string;
 mod traits;
 
 pub use array::ArraySchema;
-pub use combinators::CombinatorSchema;
+#[cfg(feature = "chrono")]
+pub use calendar::HolidayCalendar;
+pub use combinators::{CombinatorBuilder, CombinatorSchema, Quorum};
+pub use duration::Duration;
+pub use field_doc::FieldDoc;
+pub use infer::{
+    FieldDefinition, InferOptions, SchemaDefinition, StringFormat, Widening, WideningKind,
+};
+pub use key_case::KeyCase;
+pub use lazy::LazySchema;
 pub use numeric::IntegerSchema;
-pub use object::ObjectSchema;
+pub use object::{DependentCondition, ObjectSchema};
 pub use ref_schema::RefSchema;
-pub use string::StringSchema;
-pub use traits::{SchemaLike, ValueValidator};
+pub use scoped::ScopedSchema;
+#[cfg(feature = "signatures")]
+pub use signature::SignatureAlgorithm;
+pub use string::{LengthUnit, PatternLimits, StringSchema};
+pub use traits::{SchemaExt, SchemaLike, ValueValidator};
 
 /// Entry point for creating validation schemas.
 ///
@@ -205,27 +235,69 @@ impl Schema {
     where
         I: IntoIterator<Item = Box<dyn ValueValidator>>,
     {
-        use crate::schema::combinators::ValidatorFn;
         use std::sync::Arc;
         let validators: Vec<Arc<dyn ValueValidator>> = schemas
             .into_iter()
             .map(|schema| Arc::from(schema) as Arc<dyn ValueValidator>)
             .collect();
-        let validator_fns: Vec<ValidatorFn> = validators
-            .iter()
-            .map(|validator| {
-                let v = Arc::clone(validator);
-                Arc::new(
-                    move |value: &serde_json::Value, path: &crate::path::JsonPath| {
-                        v.validate_value(value, path)
-                    },
-                ) as ValidatorFn
-            })
-            .collect();
+        let validator_fns = combinators::validator_fns(&validators);
         CombinatorSchema::OneOf {
             schemas: validator_fns,
             validators,
+            branch_errors: false,
+            names: Vec::new(),
+        }
+    }
+
+    /// Creates a one-of combinator schema with named branches.
+    ///
+    /// Like [`one_of`](Self::one_of), but errors refer to the branches by
+    /// name, e.g. `branches 'circle', 'ellipse'`, and each branch is
+    /// exported to JSON Schema with its name as `title`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two branches have the same name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// let shape = Schema::one_of_named([
+    ///     ("circle", Box::new(Schema::object().field("radius", Schema::integer())) as Box<dyn ValueValidator>),
+    ///     ("ellipse", Box::new(Schema::object().field("radius", Schema::integer()))),
+    /// ]);
+    ///
+    /// let errors = shape.validate(&json!({"radius": 2}), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(
+    ///     errors.first().message,
+    ///     "value matched 2 schemas (branches 'circle', 'ellipse'), expected exactly one"
+    /// );
+    /// ```
+    pub fn one_of_named<I, T>(branches: I) -> CombinatorSchema
+    where
+        I: IntoIterator<Item = (T, Box<dyn ValueValidator>)>,
+        T: Into<String>,
+    {
+        let mut branch_names: Vec<String> = Vec::new();
+        let mut schemas = Vec::new();
+        for (name, schema) in branches {
+            let name = name.into();
+            assert!(
+                !branch_names.contains(&name),
+                "one_of_named() has two branches named '{}'",
+                name
+            );
+            branch_names.push(name);
+            schemas.push(schema);
         }
+        let mut schema = Self::one_of(schemas);
+        if let CombinatorSchema::OneOf { names, .. } = &mut schema {
+            *names = branch_names;
+        }
+        schema
     }
 
     /// Creates an any-of combinator schema.
@@ -256,26 +328,16 @@ impl Schema {
     where
         I: IntoIterator<Item = Box<dyn ValueValidator>>,
     {
-        use crate::schema::combinators::ValidatorFn;
         use std::sync::Arc;
         let validators: Vec<Arc<dyn ValueValidator>> = schemas
             .into_iter()
             .map(|schema| Arc::from(schema) as Arc<dyn ValueValidator>)
             .collect();
-        let validator_fns: Vec<ValidatorFn> = validators
-            .iter()
-            .map(|validator| {
-                let v = Arc::clone(validator);
-                Arc::new(
-                    move |value: &serde_json::Value, path: &crate::path::JsonPath| {
-                        v.validate_value(value, path)
-                    },
-                ) as ValidatorFn
-            })
-            .collect();
+        let validator_fns = combinators::validator_fns(&validators);
         CombinatorSchema::AnyOf {
             schemas: validator_fns,
             validators,
+            branch_errors: false,
         }
     }
 
@@ -285,6 +347,13 @@ impl Schema {
     /// composition and intersection, where a value must satisfy multiple
     /// independent constraints.
     ///
+    /// The output merges the outputs of every schema, so fields normalized
+    /// or defaulted by one schema are kept alongside those of the others.
+    /// Objects are merged key by key, recursively. Where schemas disagree on
+    /// any other value, a value changed from the input (e.g. trimmed, or a
+    /// default) wins over one passed through unchanged; otherwise the later
+    /// schema wins.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -308,34 +377,68 @@ impl Schema {
     ///     "created_at": "2025-01-01"
     /// }), &JsonPath::root());
     /// assert!(result.is_success());
+    ///
+    /// // Defaults and normalization from every schema are kept
+    /// let entity = Schema::all_of(vec![
+    ///     Box::new(Schema::object().field("name", Schema::string().trim())) as Box<dyn ValueValidator>,
+    ///     Box::new(Schema::object().default("role", Schema::string(), json!("user"))),
+    /// ]);
+    /// let output = entity.validate(&json!({"name": " Alice "}), &JsonPath::root()).into_result().unwrap();
+    /// assert_eq!(output, json!({"name": "Alice", "role": "user"}));
     /// ```
     pub fn all_of<I>(schemas: I) -> CombinatorSchema
     where
         I: IntoIterator<Item = Box<dyn ValueValidator>>,
     {
-        use crate::schema::combinators::ValidatorFn;
         use std::sync::Arc;
         let validators: Vec<Arc<dyn ValueValidator>> = schemas
             .into_iter()
             .map(|schema| Arc::from(schema) as Arc<dyn ValueValidator>)
             .collect();
-        let validator_fns: Vec<ValidatorFn> = validators
-            .iter()
-            .map(|validator| {
-                let v = Arc::clone(validator);
-                Arc::new(
-                    move |value: &serde_json::Value, path: &crate::path::JsonPath| {
-                        v.validate_value(value, path)
-                    },
-                ) as ValidatorFn
-            })
-            .collect();
+        let validator_fns = combinators::validator_fns(&validators);
         CombinatorSchema::AllOf {
             schemas: validator_fns,
             validators,
         }
     }
 
+    /// Starts building a one-of combinator from schemas of any type.
+    ///
+    /// Equivalent to [`one_of`](Self::one_of), without boxing each branch.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike};
+    /// use serde_json::json;
+    ///
+    /// let shape = Schema::one_of_builder()
+    ///     .or(Schema::object().field("radius", Schema::integer().positive()))
+    ///     .or(Schema::object().field("width", Schema::integer().positive()))
+    ///     .build();
+    ///
+    /// assert!(shape.validate(&json!({"radius": 5}), &JsonPath::root()).is_success());
+    /// ```
+    pub fn one_of_builder() -> CombinatorBuilder {
+        CombinatorBuilder::one_of()
+    }
+
+    /// Starts building an any-of combinator from schemas of any type.
+    ///
+    /// Equivalent to [`any_of`](Self::any_of), without boxing each branch.
+    /// See [`CombinatorBuilder`] for an example.
+    pub fn any_of_builder() -> CombinatorBuilder {
+        CombinatorBuilder::any_of()
+    }
+
+    /// Starts building an all-of combinator from schemas of any type.
+    ///
+    /// Equivalent to [`all_of`](Self::all_of), without boxing each schema.
+    /// See [`CombinatorBuilder`] for an example.
+    pub fn all_of_builder() -> CombinatorBuilder {
+        CombinatorBuilder::all_of()
+    }
+
     /// Creates an optional combinator schema.
     ///
     /// The value can be null. Non-null values are validated against the inner schema.
@@ -379,6 +482,181 @@ impl Schema {
         }
     }
 
+    /// Creates a consensus combinator schema.
+    ///
+    /// The value is validated against every schema and passes if at least
+    /// `quorum` of them accept it; the output is that of the first accepting
+    /// schema. This is for running independent definitions of the same data
+    /// side by side, such as a legacy schema and its rewrite. When validated
+    /// with a [`ValidationContext`](crate::validation::ValidationContext),
+    /// the errors of the schemas that were outvoted are recorded as
+    /// [warnings](crate::validation::ValidationContext::take_warnings). If
+    /// the quorum is not reached, a `consensus_not_reached` error is
+    /// followed by the errors of every dissenting schema.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quorum asks for more schemas than there are.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Quorum, Schema, SchemaRegistry, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// let legacy = Schema::object().field("sku", Schema::string().max_len(12));
+    /// let rewrite = Schema::object().field("sku", Schema::string().max_len(8));
+    /// let strict = Schema::object().field("sku", Schema::string().max_len(10));
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Item", Schema::consensus(vec![
+    ///     Box::new(legacy) as Box<dyn ValueValidator>,
+    ///     Box::new(rewrite) as Box<dyn ValueValidator>,
+    ///     Box::new(strict) as Box<dyn ValueValidator>,
+    /// ], Quorum::MinAgree(2))).unwrap();
+    ///
+    /// let context = registry.context();
+    /// let result = registry
+    ///     .validate_with_context("Item", &json!({"sku": "ABC-12345"}), &context)
+    ///     .unwrap();
+    /// assert!(result.is_success());
+    ///
+    /// // The rewrite disagreed
+    /// let warnings = context.take_warnings();
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].error.code, "max_length");
+    /// ```
+    pub fn consensus<I>(schemas: I, quorum: Quorum) -> CombinatorSchema
+    where
+        I: IntoIterator<Item = Box<dyn ValueValidator>>,
+    {
+        use std::sync::Arc;
+        let validators: Vec<Arc<dyn ValueValidator>> = schemas
+            .into_iter()
+            .map(|schema| Arc::from(schema) as Arc<dyn ValueValidator>)
+            .collect();
+        assert!(
+            quorum.required(validators.len()) <= validators.len(),
+            "consensus quorum of {} exceeds the {} schemas",
+            quorum.required(validators.len()),
+            validators.len()
+        );
+        CombinatorSchema::Consensus { validators, quorum }
+    }
+
+    /// Creates a discriminated union, which validates an object against the
+    /// schema selected by a tag field.
+    ///
+    /// Unlike [`one_of`](Self::one_of), only the selected branch is
+    /// validated, so a payload with the right tag reports that branch's
+    /// detailed errors. A tag that names no branch is an
+    /// `unknown_discriminator` error listing the valid tags, and a missing
+    /// tag a `required` error. Each branch sees the whole object, so it
+    /// should declare the discriminator field if it denies additional
+    /// properties.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two branches have the same tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// let shape = Schema::discriminated_union("type", [
+    ///     ("circle", Box::new(Schema::object()
+    ///         .field("type", Schema::string())
+    ///         .field("radius", Schema::integer().positive())) as Box<dyn ValueValidator>),
+    ///     ("rect", Box::new(Schema::object()
+    ///         .field("type", Schema::string())
+    ///         .field("width", Schema::integer().positive())
+    ///         .field("height", Schema::integer().positive())) as Box<dyn ValueValidator>),
+    /// ]);
+    ///
+    /// // Only the rect branch is checked
+    /// let errors = shape
+    ///     .validate(&json!({"type": "rect", "width": 3}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors.first().path.to_string(), "height");
+    ///
+    /// let errors = shape
+    ///     .validate(&json!({"type": "hexagon"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "unknown_discriminator");
+    /// assert_eq!(errors.first().expected.as_deref(), Some("one of: circle, rect"));
+    /// ```
+    pub fn discriminated_union<I, T>(
+        discriminator: impl Into<String>,
+        branches: I,
+    ) -> CombinatorSchema
+    where
+        I: IntoIterator<Item = (T, Box<dyn ValueValidator>)>,
+        T: Into<String>,
+    {
+        use std::sync::Arc;
+        let mut tagged: Vec<(String, Arc<dyn ValueValidator>)> = Vec::new();
+        for (tag, schema) in branches {
+            let tag = tag.into();
+            assert!(
+                tagged.iter().all(|(existing, _)| *existing != tag),
+                "discriminated_union() has two branches tagged '{}'",
+                tag
+            );
+            tagged.push((tag, Arc::from(schema)));
+        }
+        CombinatorSchema::DiscriminatedUnion {
+            discriminator: discriminator.into(),
+            branches: tagged,
+        }
+    }
+
+    /// Creates a conditional schema, like JSON Schema's `if`/`then`/`else`.
+    ///
+    /// If the value matches `condition`, it must match `then`; otherwise it
+    /// must match `otherwise`, or, if there is none, it passes. The errors of
+    /// `condition` are never reported, only those of the selected schema.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// // Card payments need a card number
+    /// let payment = Schema::if_then_else(
+    ///     Box::new(Schema::object().field("type", Schema::string().one_of(["card"]))),
+    ///     Box::new(Schema::object()
+    ///         .field("type", Schema::string())
+    ///         .field("card_number", Schema::string().min_len(12))),
+    ///     None,
+    /// );
+    ///
+    /// assert!(payment.validate(&json!({"type": "invoice"}), &JsonPath::root()).is_success());
+    ///
+    /// let errors = payment
+    ///     .validate(&json!({"type": "card"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "card_number");
+    /// ```
+    pub fn if_then_else(
+        condition: Box<dyn ValueValidator>,
+        then: Box<dyn ValueValidator>,
+        otherwise: Option<Box<dyn ValueValidator>>,
+    ) -> CombinatorSchema {
+        use std::sync::Arc;
+        CombinatorSchema::IfThenElse {
+            condition: Arc::from(condition),
+            then: Arc::from(then),
+            otherwise: otherwise.map(Arc::from),
+        }
+    }
+
     /// Creates a reference to a named schema.
     ///
     /// Schema references enable reuse and recursive structures. The referenced
@@ -414,4 +692,169 @@ impl Schema {
     pub fn ref_(name: impl Into<String>) -> RefSchema {
         RefSchema::new(name)
     }
+
+    /// Creates a schema that is built by `build` when first used.
+    ///
+    /// A function returning a schema can refer to itself through `lazy`,
+    /// describing a recursive structure without a registry. Each lazy
+    /// schema entered during validation counts one level of nesting; past
+    /// 100 levels, or the limit set with
+    /// [`LazySchema::max_depth`], validation fails with a
+    /// `max_depth_exceeded` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+    /// use serde_json::json;
+    ///
+    /// fn category() -> impl ValueValidator {
+    ///     Schema::object()
+    ///         .field("name", Schema::string().min_len(1))
+    ///         .optional("subcategories", Schema::array(Schema::lazy(category)))
+    /// }
+    ///
+    /// let schema = Schema::lazy(category);
+    /// let tree = json!({"name": "Books", "subcategories": [{"name": ""}]});
+    /// let errors = schema.validate(&tree, &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "subcategories[0].name");
+    /// ```
+    pub fn lazy<F, S>(build: F) -> LazySchema
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: ValueValidator + 'static,
+    {
+        LazySchema::lazy(build)
+    }
+
+    /// Creates a schema that refers to itself.
+    ///
+    /// `define` receives a reference to the schema it defines, to use
+    /// wherever the structure nests. Nesting is limited as with
+    /// [`lazy`](Self::lazy). When exported to JSON Schema, the schema gets
+    /// an `$anchor` and each self-reference becomes a `$ref` to it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaLike};
+    /// use serde_json::json;
+    ///
+    /// let comment = Schema::recursive(|comment| {
+    ///     Schema::object()
+    ///         .field("text", Schema::string().min_len(1))
+    ///         .default("replies", Schema::array(comment), json!([]))
+    /// });
+    ///
+    /// let thread = json!({"text": "First!", "replies": [{"text": "Welcome"}]});
+    /// assert!(comment.validate(&thread, &JsonPath::root()).is_success());
+    ///
+    /// let deep = (0..200).fold(json!({"text": "leaf"}), |reply, _| {
+    ///     json!({"text": "re", "replies": [reply]})
+    /// });
+    /// let errors = comment.max_depth(50)
+    ///     .validate(&deep, &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "max_depth_exceeded");
+    /// ```
+    pub fn recursive<F, S>(define: F) -> LazySchema
+    where
+        F: FnOnce(LazySchema) -> S,
+        S: ValueValidator + 'static,
+    {
+        LazySchema::recursive(define)
+    }
+
+    /// Validates `schema` with the validation context adjusted by `adjust`.
+    ///
+    /// The adjusted context applies to this subtree only; see
+    /// [`ValidationContext::scoped`](crate::validation::ValidationContext::scoped).
+    /// This lets a document be validated strictly while a part of it, such
+    /// as a third-party payload, is validated leniently or with its own
+    /// suppressions. Without a context, `schema` is validated as is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Webhook", Schema::object()
+    ///     .field("event", Schema::string().one_of(["order.paid"]))
+    ///     .field("payload", Schema::scoped(
+    ///         Schema::object().field("amount", Schema::integer().positive()),
+    ///         |context| context.lenient(),
+    ///     ))).unwrap();
+    ///
+    /// let context = registry.context();
+    /// let hook = json!({"event": "order.paid", "payload": {"amount": -5}});
+    /// let result = registry.validate_with_context("Webhook", &hook, &context).unwrap();
+    /// assert!(result.is_success());
+    /// assert_eq!(context.take_warnings()[0].error.path.to_string(), "payload.amount");
+    ///
+    /// // The envelope is still strict
+    /// let hook = json!({"event": "order.lost", "payload": {}});
+    /// let result = registry.validate_with_context("Webhook", &hook, &context).unwrap();
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn scoped<S, F>(schema: S, adjust: F) -> ScopedSchema
+    where
+        S: ValueValidator + 'static,
+        F: Fn(crate::validation::ValidationContext) -> crate::validation::ValidationContext
+            + Send
+            + Sync
+            + 'static,
+    {
+        ScopedSchema::new(schema, adjust)
+    }
+
+    /// Infers a schema definition from sample payloads.
+    ///
+    /// Types, optional fields, low-cardinality string enums, string formats,
+    /// and numeric ranges are induced from the samples using the default
+    /// [`InferOptions`]. The result is meant as a starting point for APIs
+    /// without a specification: review it, then build a schema with
+    /// [`SchemaDefinition::to_schema`] or export it as JSON Schema.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, ToJsonSchema};
+    /// use serde_json::json;
+    ///
+    /// let definition = Schema::infer(&[
+    ///     json!({"name": "Alice", "role": "admin"}),
+    ///     json!({"name": "Bob", "role": "user", "age": 42}),
+    ///     json!({"name": "Carol", "role": "user"}),
+    ///     json!({"name": "Dan", "role": "admin"}),
+    /// ]);
+    ///
+    /// let schema = definition.to_json_schema();
+    /// assert_eq!(schema["required"], json!(["name", "role"]));
+    /// assert_eq!(schema["properties"]["role"]["enum"], json!(["admin", "user"]));
+    /// ```
+    pub fn infer(samples: &[serde_json::Value]) -> SchemaDefinition {
+        infer::infer(samples, &InferOptions::default())
+    }
+
+    /// Infers a schema definition from sample payloads with custom options.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::schema::InferOptions;
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let samples = [json!({"id": 1}), json!({"id": 2})];
+    /// let schema = Schema::infer_with(&samples, &InferOptions::strict()).to_schema();
+    ///
+    /// let result = schema.validate_value(&json!({"id": 1, "extra": true}), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn infer_with(samples: &[serde_json::Value], options: &InferOptions) -> SchemaDefinition {
+        infer::infer(samples, options)
+    }
 }
@@ -4,23 +4,52 @@
 //! constraints like minimum/maximum value and sign requirements.
 
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::ops::RangeInclusive;
 use stillwater::Validation;
 
-use crate::error::{SchemaError, SchemaErrors};
+use crate::error::{
+    describe_type, render_message, Provenance, Repair, RepairKind, SchemaError, SchemaErrors,
+};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
+use crate::tunable::Limit;
 
 use super::traits::SchemaLike;
 
 /// A constraint applied to integer values.
 #[derive(Clone)]
 enum IntegerConstraint {
-    Min { value: i64, message: Option<String> },
-    Max { value: i64, message: Option<String> },
-    Positive { message: Option<String> },
-    NonNegative { message: Option<String> },
-    Negative { message: Option<String> },
+    Min {
+        value: Limit<i64>,
+        message: Option<String>,
+    },
+    Max {
+        value: Limit<i64>,
+        message: Option<String>,
+    },
+    Positive {
+        message: Option<String>,
+    },
+    NonNegative {
+        message: Option<String>,
+    },
+    Negative {
+        message: Option<String>,
+    },
+}
+
+impl IntegerConstraint {
+    /// Returns the custom message set with `.error(...)`, if any.
+    fn message(&self) -> Option<&str> {
+        match self {
+            IntegerConstraint::Min { message, .. }
+            | IntegerConstraint::Max { message, .. }
+            | IntegerConstraint::Positive { message }
+            | IntegerConstraint::NonNegative { message }
+            | IntegerConstraint::Negative { message } => message.as_deref(),
+        }
+    }
 }
 
 /// A schema for validating integer values.
@@ -49,6 +78,8 @@ enum IntegerConstraint {
 pub struct IntegerSchema {
     constraints: Vec<IntegerConstraint>,
     type_error_message: Option<String>,
+    type_provenance: Option<Provenance>,
+    constraint_provenance: HashMap<usize, Provenance>,
 }
 
 impl IntegerSchema {
@@ -57,12 +88,15 @@ impl IntegerSchema {
         Self {
             constraints: Vec::new(),
             type_error_message: None,
+            type_provenance: None,
+            constraint_provenance: HashMap::new(),
         }
     }
 
     /// Adds a minimum value constraint (inclusive).
     ///
-    /// The integer must be at least `value`.
+    /// The integer must be at least `value`, which may be a
+    /// [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -78,9 +112,9 @@ impl IntegerSchema {
     /// let result = schema.validate(&json!(3), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn min(mut self, value: i64) -> Self {
+    pub fn min(mut self, value: impl Into<Limit<i64>>) -> Self {
         self.constraints.push(IntegerConstraint::Min {
-            value,
+            value: value.into(),
             message: None,
         });
         self
@@ -88,7 +122,8 @@ impl IntegerSchema {
 
     /// Adds a maximum value constraint (inclusive).
     ///
-    /// The integer must be at most `value`.
+    /// The integer must be at most `value`, which may be a
+    /// [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -104,9 +139,9 @@ impl IntegerSchema {
     /// let result = schema.validate(&json!(15), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn max(mut self, value: i64) -> Self {
+    pub fn max(mut self, value: impl Into<Limit<i64>>) -> Self {
         self.constraints.push(IntegerConstraint::Max {
-            value,
+            value: value.into(),
             message: None,
         });
         self
@@ -220,6 +255,10 @@ impl IntegerSchema {
     /// If no constraints have been added yet, this sets the type error message
     /// (used when the value is not an integer).
     ///
+    /// The message may contain the placeholders `{field}`, `{path}`, `{got}`,
+    /// `{expected}`, and `{value}`, and `{min}` and `{max}` for the bounds
+    /// of the whole schema, so either bound's message can mention both.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -232,6 +271,14 @@ impl IntegerSchema {
     ///
     /// let result = schema.validate(&json!(16), &JsonPath::root());
     /// // Error message will be "must be at least 18 years old"
+    ///
+    /// let schema = Schema::integer()
+    ///     .range(0..=120)
+    ///     .error("{field} must be between {min} and {max}, got {got}");
+    ///
+    /// let path = JsonPath::root().push_field("age");
+    /// let errors = schema.validate(&json!(150), &path).into_result().unwrap_err();
+    /// assert_eq!(errors.first().message, "age must be between 0 and 120, got 150");
     /// ```
     pub fn error(mut self, message: impl Into<String>) -> Self {
         if let Some(last) = self.constraints.last_mut() {
@@ -248,6 +295,40 @@ impl IntegerSchema {
         self
     }
 
+    /// Records where the most recent constraint was defined.
+    ///
+    /// Errors from that constraint carry the [`Provenance`] and mention it
+    /// when displayed. Like [`error`](Self::error), if no constraints have
+    /// been added yet this applies to the type check instead. Schema loaders
+    /// call this for each rule they read from a file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Provenance, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::integer()
+    ///     .defined_at(Provenance::new("schemas/user.json", 3))
+    ///     .min(0)
+    ///     .defined_at(Provenance::new("schemas/user.json", 4));
+    ///
+    /// let errors = schema.validate(&json!(-1), &JsonPath::root()).into_result().unwrap_err();
+    /// assert!(errors.first().to_string().ends_with("(rule defined in schemas/user.json:4)"));
+    ///
+    /// let errors = schema.validate(&json!(null), &JsonPath::root()).into_result().unwrap_err();
+    /// assert!(errors.first().to_string().ends_with("(rule defined in schemas/user.json:3)"));
+    /// ```
+    pub fn defined_at(mut self, provenance: Provenance) -> Self {
+        if self.constraints.is_empty() {
+            self.type_provenance = Some(provenance);
+        } else {
+            self.constraint_provenance
+                .insert(self.constraints.len() - 1, provenance);
+        }
+        self
+    }
+
     /// Validates a value against this schema.
     ///
     /// Returns `Validation::Success` with the validated i64 if all
@@ -286,12 +367,11 @@ impl IntegerSchema {
                         .type_error_message
                         .clone()
                         .unwrap_or_else(|| "integer value too large for i64".to_string());
-                    return Validation::Failure(SchemaErrors::single(
-                        SchemaError::new(path.clone(), message)
-                            .with_code("overflow")
-                            .with_got(format!("{}", u))
-                            .with_expected("integer in i64 range"),
-                    ));
+                    let error = SchemaError::new(path.clone(), message)
+                        .with_code("overflow")
+                        .with_got(format!("{}", u))
+                        .with_expected("integer in i64 range");
+                    return self.type_failure(error);
                 }
             }
             Value::Number(_) => {
@@ -300,24 +380,30 @@ impl IntegerSchema {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected integer, got float".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got("float")
-                        .with_expected("integer"),
-                ));
+                let mut error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got("float")
+                    .with_expected("integer");
+                if let Some(n) = coerce_integer(value) {
+                    error =
+                        error.with_suggestion(Repair::replace(RepairKind::Coerce, path.clone(), n));
+                }
+                return self.type_failure(error);
             }
             _ => {
                 let message = self
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected integer".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("integer"),
-                ));
+                let mut error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("integer");
+                if let Some(n) = coerce_integer(value) {
+                    error =
+                        error.with_suggestion(Repair::replace(RepairKind::Coerce, path.clone(), n));
+                }
+                return self.type_failure(error);
             }
         };
 
@@ -325,7 +411,17 @@ impl IntegerSchema {
         let errors: Vec<SchemaError> = self
             .constraints
             .iter()
-            .filter_map(|c| check_constraint(c, n, path))
+            .enumerate()
+            .filter_map(|(i, c)| {
+                let mut error = check_constraint(c, n, path)?;
+                if c.message().is_some() {
+                    error = render_message(error, &self.template_params(n));
+                }
+                Some(match self.constraint_provenance.get(&i) {
+                    Some(provenance) => error.with_provenance(provenance.clone()),
+                    None => error,
+                })
+            })
             .collect();
 
         if errors.is_empty() {
@@ -334,6 +430,32 @@ impl IntegerSchema {
             Validation::Failure(SchemaErrors::from_vec(errors))
         }
     }
+
+    /// Returns the message placeholders: the schema's bounds and the value.
+    fn template_params(&self, value: i64) -> Vec<(&'static str, String)> {
+        let mut params: Vec<_> = self
+            .constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                IntegerConstraint::Min { value, .. } => Some(("min", value.describe())),
+                IntegerConstraint::Max { value, .. } => Some(("max", value.describe())),
+                _ => None,
+            })
+            .collect();
+        params.push(("value", value.to_string()));
+        params
+    }
+
+    /// Fails with a type error, noting where the type rule was defined.
+    fn type_failure(&self, mut error: SchemaError) -> Validation<i64, SchemaErrors> {
+        if self.type_error_message.is_some() {
+            error = render_message(error, &[]);
+        }
+        if let Some(provenance) = &self.type_provenance {
+            error = error.with_provenance(provenance.clone());
+        }
+        Validation::Failure(SchemaErrors::single(error))
+    }
 }
 
 impl Default for IntegerSchema {
@@ -361,10 +483,14 @@ impl ToJsonSchema for IntegerSchema {
         for constraint in &self.constraints {
             match constraint {
                 IntegerConstraint::Min { value, .. } => {
-                    schema["minimum"] = json!(value);
+                    if let Some(min) = value.current() {
+                        schema["minimum"] = json!(min);
+                    }
                 }
                 IntegerConstraint::Max { value, .. } => {
-                    schema["maximum"] = json!(value);
+                    if let Some(max) = value.current() {
+                        schema["maximum"] = json!(max);
+                    }
                 }
                 IntegerConstraint::Positive { .. } => {
                     schema["exclusiveMinimum"] = json!(0);
@@ -393,7 +519,10 @@ fn check_constraint(
             value: min,
             message,
         } => {
-            if value < *min {
+            let Some(min) = min.current() else {
+                return Some(min.unresolved(path));
+            };
+            if value < min {
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("must be at least {}, got {}", min, value));
@@ -401,7 +530,8 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("min_value")
                         .with_expected(format!("at least {}", min))
-                        .with_got(format!("{}", value)),
+                        .with_got(format!("{}", value))
+                        .with_suggestion(Repair::replace(RepairKind::Clamp, path.clone(), min)),
                 )
             } else {
                 None
@@ -411,7 +541,10 @@ fn check_constraint(
             value: max,
             message,
         } => {
-            if value > *max {
+            let Some(max) = max.current() else {
+                return Some(max.unresolved(path));
+            };
+            if value > max {
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("must be at most {}, got {}", max, value));
@@ -419,7 +552,8 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("max_value")
                         .with_expected(format!("at most {}", max))
-                        .with_got(format!("{}", value)),
+                        .with_got(format!("{}", value))
+                        .with_suggestion(Repair::replace(RepairKind::Clamp, path.clone(), max)),
                 )
             } else {
                 None
@@ -434,7 +568,8 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("positive")
                         .with_expected("value > 0")
-                        .with_got(format!("{}", value)),
+                        .with_got(format!("{}", value))
+                        .with_suggestion(Repair::replace(RepairKind::Clamp, path.clone(), 1)),
                 )
             } else {
                 None
@@ -449,7 +584,8 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("non_negative")
                         .with_expected("value >= 0")
-                        .with_got(format!("{}", value)),
+                        .with_got(format!("{}", value))
+                        .with_suggestion(Repair::replace(RepairKind::Clamp, path.clone(), 0)),
                 )
             } else {
                 None
@@ -464,7 +600,8 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("negative")
                         .with_expected("value < 0")
-                        .with_got(format!("{}", value)),
+                        .with_got(format!("{}", value))
+                        .with_suggestion(Repair::replace(RepairKind::Clamp, path.clone(), -1)),
                 )
             } else {
                 None
@@ -473,15 +610,15 @@ fn check_constraint(
     }
 }
 
-/// Returns the JSON type name for a value.
-fn value_type_name(value: &Value) -> &'static str {
+/// Returns the integer a float or numeric string represents, if any.
+fn coerce_integer(value: &Value) -> Option<i64> {
     match value {
-        Value::Null => "null",
-        Value::Bool(_) => "boolean",
-        Value::Number(_) => "number",
-        Value::String(_) => "string",
-        Value::Array(_) => "array",
-        Value::Object(_) => "object",
+        Value::Number(num) => num
+            .as_f64()
+            .filter(|f| f.fract() == 0.0 && *f >= i64::MIN as f64 && *f < i64::MAX as f64)
+            .map(|f| f as i64),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
     }
 }
 
@@ -709,6 +846,22 @@ mod tests {
         assert_eq!(errors.first().message, "must be at least 18 years old");
     }
 
+    #[test]
+    fn test_error_message_placeholders() {
+        let schema = IntegerSchema::new()
+            .min(1)
+            .error("{field} must be at least {min}, got {value}")
+            .max(10)
+            .error("{field} must be {min}..={max}");
+        let path = JsonPath::root().push_field("items").push_index(2);
+
+        let errors = unwrap_failure(schema.validate(&json!(0), &path));
+        assert_eq!(errors.first().message, "2 must be at least 1, got 0");
+
+        let errors = unwrap_failure(schema.validate(&json!(11), &path));
+        assert_eq!(errors.first().message, "2 must be 1..=10");
+    }
+
     #[test]
     fn test_custom_type_error_message() {
         let schema = IntegerSchema::new().error("must be an integer");
@@ -756,6 +909,34 @@ mod tests {
         assert_eq!(unwrap_success(result), i64::MAX);
     }
 
+    #[test]
+    fn test_repair_suggestions() {
+        let schema = IntegerSchema::new().min(1).max(10);
+        let suggestion = |value: Value| {
+            let errors = unwrap_failure(schema.validate(&value, &JsonPath::root()));
+            errors.first().suggestion.clone().map(|r| (r.kind, r.value))
+        };
+
+        assert_eq!(
+            suggestion(json!(" 7 ")),
+            Some((RepairKind::Coerce, Some(json!(7))))
+        );
+        assert_eq!(
+            suggestion(json!(7.0)),
+            Some((RepairKind::Coerce, Some(json!(7))))
+        );
+        assert_eq!(
+            suggestion(json!(42)),
+            Some((RepairKind::Clamp, Some(json!(10))))
+        );
+        assert_eq!(
+            suggestion(json!(-3)),
+            Some((RepairKind::Clamp, Some(json!(1))))
+        );
+        assert_eq!(suggestion(json!(7.5)), None);
+        assert_eq!(suggestion(json!("seven")), None);
+    }
+
     #[test]
     fn test_schema_clone() {
         let schema = IntegerSchema::new().min(5).max(10);
@@ -5,21 +5,33 @@
 //! and cross-field validation.
 
 use indexmap::IndexMap;
+use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde_json::{json, Map, Value};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::sync::Arc;
 use stillwater::Validation;
 
-use crate::error::{SchemaError, SchemaErrors};
+use crate::error::{
+    count, describe_type, rebuild_errors, render_message, ConstraintInfo, ErrorFactory, Repair,
+    RepairKind, SchemaError, SchemaErrors,
+};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
+use crate::session::{parse_pattern, resolve_pattern, KeySegment};
+use crate::suppression::SuppressedError;
+use crate::validation::ValidationMode;
 
+use super::decimal::Decimal;
 use super::traits::SchemaLike;
+use super::{FieldDoc, KeyCase};
 
 /// Type alias for cross-field validators.
 ///
 /// A cross-field validator receives the validated object (after field validation)
 /// and the current path, returning a validation result.
-type CrossFieldValidator = Box<
+type CrossFieldValidator = Arc<
     dyn Fn(&ValidatedObject, &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static,
 >;
 
@@ -29,6 +41,8 @@ type CrossFieldValidator = Box<
 /// This type provides safe access to validated field values for cross-field validation.
 pub struct ValidatedObject {
     fields: HashMap<String, Value>,
+    raw: Map<String, Value>,
+    path: JsonPath,
 }
 
 impl ValidatedObject {
@@ -43,23 +57,275 @@ impl ValidatedObject {
     pub fn has(&self, field: &str) -> bool {
         self.get(field).is_some_and(|v| !v.is_null())
     }
+
+    /// Get a field as an `i64`. Returns None if the field doesn't exist or
+    /// isn't an integer that fits.
+    pub fn get_i64(&self, field: &str) -> Option<i64> {
+        self.get(field).and_then(Value::as_i64)
+    }
+
+    /// Get a field as an `f64`. Returns None if the field doesn't exist or
+    /// isn't a number.
+    pub fn get_f64(&self, field: &str) -> Option<f64> {
+        self.get(field).and_then(Value::as_f64)
+    }
+
+    /// Get a field as a string. Returns None if the field doesn't exist or
+    /// isn't a string.
+    pub fn get_str(&self, field: &str) -> Option<&str> {
+        self.get(field).and_then(Value::as_str)
+    }
+
+    /// Get a field as a bool. Returns None if the field doesn't exist or
+    /// isn't a bool.
+    pub fn get_bool(&self, field: &str) -> Option<bool> {
+        self.get(field).and_then(Value::as_bool)
+    }
+
+    /// Get a field as an array. Returns None if the field doesn't exist or
+    /// isn't an array.
+    pub fn get_array(&self, field: &str) -> Option<&Vec<Value>> {
+        self.get(field).and_then(Value::as_array)
+    }
+
+    /// Get a field that a cross-field rule cannot do without.
+    ///
+    /// Fails with a `required` error at the field's path if the field is
+    /// missing or `null`, e.g. because it is optional or, with
+    /// [`skip_cross_field_on_errors(false)`](ObjectSchema::skip_cross_field_on_errors),
+    /// failed validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    /// use stillwater::Validation;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("discount", Schema::integer())
+    ///     .optional("coupon", Schema::string())
+    ///     .custom(|obj, _path| {
+    ///         if obj.has("coupon") {
+    ///             obj.require("discount").map(|_| ())
+    ///         } else {
+    ///             Validation::Success(())
+    ///         }
+    ///     });
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"coupon": "SPRING"}), &JsonPath::root().push_field("order"))
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "required");
+    /// assert_eq!(errors.first().path.to_string(), "order.discount");
+    /// ```
+    pub fn require(&self, field: &str) -> Validation<&Value, SchemaErrors> {
+        match self.get(field) {
+            Some(value) if !value.is_null() => Validation::Success(value),
+            _ => Validation::Failure(SchemaErrors::single(
+                SchemaError::new(
+                    self.path.push_field(field),
+                    format!("required field '{}' is missing", field),
+                )
+                .with_code("required")
+                .with_expected("value"),
+            )),
+        }
+    }
 }
 
 /// Definition of a field within an object schema.
+#[derive(Clone)]
 struct FieldDef {
-    schema: Box<dyn super::traits::ValueValidator>,
+    schema: Arc<dyn super::traits::ValueValidator>,
     required: bool,
-    default: Option<Value>,
+    default: Option<FieldDefault>,
+    doc: Option<FieldDoc>,
+    aliases: Vec<String>,
+    output_name: Option<String>,
+    access: FieldAccess,
+    required_message: Option<String>,
+}
+
+/// Which documents a field may appear in, depending on the
+/// [`ValidationMode`].
+#[derive(Clone, Copy, PartialEq)]
+enum FieldAccess {
+    ReadWrite,
+    ReadOnly,
+    WriteOnly,
+}
+
+/// The value an absent optional field takes.
+#[derive(Clone)]
+enum FieldDefault {
+    /// A fixed value; `checked` if it already passed the field's schema
+    /// when it was declared.
+    Value {
+        value: Value,
+        checked: bool,
+    },
+    Computed(Arc<dyn Fn() -> Value + Send + Sync>),
+}
+
+impl FieldDef {
+    /// Creates the error for this field being required but absent.
+    fn missing(&self, name: &str, path: JsonPath) -> SchemaError {
+        let message = match &self.required_message {
+            Some(message) => message.clone(),
+            None => format!("required field '{}' is missing", name),
+        };
+        let error = SchemaError::new(path, message)
+            .with_code("required")
+            .with_expected("value");
+        match self.required_message {
+            Some(_) => render_message(error, &[]),
+            None => error,
+        }
+    }
+
+    /// Returns the default of an absent field, validated against the
+    /// field's schema unless that was done when it was declared.
+    ///
+    /// A default that does not match the schema is a bug in the schema, so
+    /// its errors are reported with the `invalid_default` code.
+    fn default_value(
+        &self,
+        path: &JsonPath,
+        context: Option<&crate::validation::ValidationContext>,
+    ) -> Option<Validation<Value, SchemaErrors>> {
+        let value = match self.default.as_ref()? {
+            FieldDefault::Value {
+                value,
+                checked: true,
+            } => return Some(Validation::Success(value.clone())),
+            FieldDefault::Value { value, .. } => value.clone(),
+            FieldDefault::Computed(compute) => {
+                if let Some(context) = context {
+                    context.mark_computed();
+                }
+                compute()
+            }
+        };
+        let result = match context {
+            Some(context) => self
+                .schema
+                .validate_value_with_context(&value, path, context),
+            None => self.schema.validate_value(&value, path),
+        };
+        Some(match result {
+            Validation::Success(value) => Validation::Success(value),
+            Validation::Failure(errors) => Validation::Failure(SchemaErrors::from_vec(
+                errors
+                    .into_iter()
+                    .map(|mut error| {
+                        error.message = format!("default value is invalid: {}", error.message);
+                        error.with_code("invalid_default")
+                    })
+                    .collect(),
+            )),
+        })
+    }
+
+    /// Returns whether `key` is the field's name or one of its aliases.
+    fn named(&self, name: &str, key: &str, ignore_case: bool) -> bool {
+        std::iter::once(name)
+            .chain(self.aliases.iter().map(String::as_str))
+            .any(|candidate| same_key(candidate, key, ignore_case))
+    }
+
+    /// Finds the field's value in `obj` under its name or one of its
+    /// aliases, ignoring case if `ignore_case`, returning the key it was
+    /// found under.
+    ///
+    /// Returns every key present if there is more than one.
+    fn find<'a>(
+        &self,
+        name: &'a str,
+        obj: &'a Map<String, Value>,
+        ignore_case: bool,
+    ) -> Result<Option<(&'a str, &'a Value)>, Vec<&'a str>> {
+        let present: Vec<_> = if ignore_case {
+            obj.iter()
+                .filter(|(key, _)| self.named(name, key, true))
+                .map(|(key, value)| (key.as_str(), value))
+                .collect()
+        } else {
+            std::iter::once(name)
+                .chain(self.aliases.iter().map(String::as_str))
+                .filter_map(|key| obj.get_key_value(key))
+                .map(|(key, value)| (key.as_str(), value))
+                .collect()
+        };
+        match present.as_slice() {
+            [] => Ok(None),
+            [found] => Ok(Some(*found)),
+            _ => Err(present.into_iter().map(|(key, _)| key).collect()),
+        }
+    }
+}
+
+/// Returns whether two keys are the same, ignoring case if `ignore_case`.
+fn same_key(a: &str, b: &str, ignore_case: bool) -> bool {
+    a == b
+        || (ignore_case
+            && a.chars()
+                .flat_map(char::to_lowercase)
+                .eq(b.chars().flat_map(char::to_lowercase)))
+}
+
+/// Creates the error for a field sent under more than one of its names.
+fn alias_conflict(name: &str, keys: &[&str], path: &JsonPath) -> SchemaError {
+    let keys: Vec<_> = keys.iter().map(|key| format!("'{}'", key)).collect();
+    SchemaError::new(
+        path.push_field(name),
+        format!(
+            "field '{}' is given more than once, as {}",
+            name,
+            keys.join(" and ")
+        ),
+    )
+    .with_code("alias_conflict")
+    .with_expected("one of its names")
+}
+
+/// Creates the error for a read-only field given in a document being
+/// created.
+fn read_only_set(name: &str, path: JsonPath) -> SchemaError {
+    SchemaError::new(
+        path.clone(),
+        format!("field '{}' is read-only and cannot be set", name),
+    )
+    .with_code("read_only")
+    .with_suggestion(Repair::remove(RepairKind::RemoveUnknown, path))
+}
+
+/// Returns the non-null values of a validated object matching a key
+/// pattern such as `items[*].amount`, with their paths.
+fn resolve_in_object<'a>(
+    obj: &'a ValidatedObject,
+    pattern: &[KeySegment],
+    path: &JsonPath,
+) -> Vec<(JsonPath, &'a Value)> {
+    match pattern.split_first() {
+        Some((KeySegment::Field(name), rest)) => match obj.get(name) {
+            Some(value) => resolve_pattern(value, rest, path.push_field(name.as_str())),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
 }
 
 /// How to handle properties not defined in the schema.
+#[derive(Clone)]
 enum AdditionalProperties {
     /// Allow unknown properties (default behavior).
     Allow,
     /// Reject unknown properties.
     Deny,
     /// Validate unknown properties against a schema.
-    Validate(Box<dyn super::traits::ValueValidator>),
+    Validate(Arc<dyn super::traits::ValueValidator>),
 }
 
 /// A schema for validating JSON objects.
@@ -87,12 +353,32 @@ enum AdditionalProperties {
 /// }), &JsonPath::root());
 /// assert!(result.is_success());
 /// ```
+#[derive(Clone)]
 pub struct ObjectSchema {
     fields: IndexMap<String, FieldDef>,
+    pattern_properties: Vec<(Regex, Arc<dyn super::traits::ValueValidator>)>,
     additional_properties: AdditionalProperties,
+    max_additional_properties: Option<usize>,
     type_error_message: Option<String>,
     cross_field_validators: Vec<CrossFieldValidator>,
     skip_on_field_errors: bool,
+    error_factory: Option<ErrorFactory>,
+    key_case: Option<KeyCase>,
+    deprecated: IndexMap<String, String>,
+    deny_deprecated: bool,
+    case_insensitive_fields: bool,
+    null_as_absent: bool,
+    dependent_required: IndexMap<String, Vec<String>>,
+    dependent_schemas: Vec<DependentSchema>,
+}
+
+/// A schema applied to the whole object when a trigger field matches a
+/// condition.
+#[derive(Clone)]
+struct DependentSchema {
+    trigger: String,
+    condition: DependentCondition,
+    schema: ObjectSchema,
 }
 
 impl ObjectSchema {
@@ -100,10 +386,20 @@ impl ObjectSchema {
     pub fn new() -> Self {
         Self {
             fields: IndexMap::new(),
+            pattern_properties: Vec::new(),
             additional_properties: AdditionalProperties::Allow,
+            max_additional_properties: None,
             type_error_message: None,
             cross_field_validators: Vec::new(),
             skip_on_field_errors: true,
+            error_factory: None,
+            key_case: None,
+            deprecated: IndexMap::new(),
+            deny_deprecated: false,
+            case_insensitive_fields: false,
+            null_as_absent: false,
+            dependent_required: IndexMap::new(),
+            dependent_schemas: Vec::new(),
         }
     }
 
@@ -133,9 +429,72 @@ impl ObjectSchema {
         self.fields.insert(
             name,
             FieldDef {
-                schema: Box::new(SchemaWrapper(schema)),
+                schema: Arc::new(SchemaWrapper(schema)),
+                required: true,
+                default: None,
+                doc: None,
+                aliases: Vec::new(),
+                output_name: None,
+                access: FieldAccess::ReadWrite,
+                required_message: None,
+            },
+        );
+        self
+    }
+
+    /// Adds a required field that may also be given under other names.
+    ///
+    /// Use this when a field has been renamed but clients still send the
+    /// old name. Either name satisfies the field, and the validated output
+    /// always uses the canonical `name`. Errors in the value point at the
+    /// key it was actually sent under. An object that sends the field under
+    /// more than one of its names fails with an `alias_conflict` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field_with_aliases("user_name", ["userName"], Schema::string())
+    ///     .additional_properties(false);
+    ///
+    /// let validated = schema
+    ///     .validate(&json!({"userName": "ada"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(validated["user_name"], "ada");
+    /// assert!(!validated.contains_key("userName"));
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"user_name": "ada", "userName": "ada"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "alias_conflict");
+    /// ```
+    pub fn field_with_aliases<S, A>(
+        mut self,
+        name: impl Into<String>,
+        aliases: A,
+        schema: S,
+    ) -> Self
+    where
+        S: SchemaLike + ToJsonSchema + 'static,
+        A: IntoIterator,
+        A::Item: Into<String>,
+    {
+        self.fields.insert(
+            name.into(),
+            FieldDef {
+                schema: Arc::new(SchemaWrapper(schema)),
                 required: true,
                 default: None,
+                doc: None,
+                aliases: aliases.into_iter().map(Into::into).collect(),
+                output_name: None,
+                access: FieldAccess::ReadWrite,
+                required_message: None,
             },
         );
         self
@@ -167,9 +526,14 @@ impl ObjectSchema {
         self.fields.insert(
             name,
             FieldDef {
-                schema: Box::new(SchemaWrapper(schema)),
+                schema: Arc::new(SchemaWrapper(schema)),
                 required: false,
                 default: None,
+                doc: None,
+                aliases: Vec::new(),
+                output_name: None,
+                access: FieldAccess::ReadWrite,
+                required_message: None,
             },
         );
         self
@@ -180,6 +544,16 @@ impl ObjectSchema {
     /// If the field is absent from the input object, the default value is used.
     /// If present, its value must pass validation against the provided schema.
     ///
+    /// The default is validated against the schema too, so a default that
+    /// could never be valid is caught where it is declared. A default that
+    /// needs a registry to be checked, e.g. one for a
+    /// [`Schema::ref_`](super::Schema::ref_) field, is checked when it is
+    /// applied instead, and fails validation with `invalid_default` errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the default does not match the schema.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -198,258 +572,1391 @@ impl ObjectSchema {
         S: SchemaLike + ToJsonSchema + 'static,
     {
         let name = name.into();
+        let schema: Arc<dyn super::traits::ValueValidator> = Arc::new(SchemaWrapper(schema));
+        let default = match schema.validate_value(&default, &JsonPath::root().push_field(&name)) {
+            Validation::Success(value) => FieldDefault::Value {
+                value,
+                checked: true,
+            },
+            // References can only be checked against a registry, so check
+            // the default when it is applied
+            Validation::Failure(errors) if errors.iter().all(|e| e.code == "missing_registry") => {
+                FieldDefault::Value {
+                    value: default,
+                    checked: false,
+                }
+            }
+            Validation::Failure(errors) => {
+                panic!(
+                    "default for '{}' does not match its schema: {}",
+                    name, errors
+                )
+            }
+        };
         self.fields.insert(
             name,
             FieldDef {
-                schema: Box::new(SchemaWrapper(schema)),
+                schema,
                 required: false,
                 default: Some(default),
+                doc: None,
+                aliases: Vec::new(),
+                output_name: None,
+                access: FieldAccess::ReadWrite,
+                required_message: None,
             },
         );
         self
     }
 
-    /// Configures how unknown properties are handled.
+    /// Adds an optional field whose default is computed when it is needed.
     ///
-    /// By default, unknown properties are allowed. Use this method to reject
-    /// unknown properties or validate them against a schema.
+    /// Like [`default`](Self::default), but `default` is called on every
+    /// validation where the field is absent, so the value can be a
+    /// timestamp, a fresh id, or read from the environment. Each computed
+    /// value is validated against the schema, and one that does not match
+    /// fails validation with `invalid_default` errors.
     ///
     /// # Example
     ///
     /// ```rust
     /// use postmortem::{Schema, JsonPath};
     /// use serde_json::json;
+    /// use std::sync::atomic::{AtomicU64, Ordering};
     ///
-    /// // Reject unknown properties
-    /// let strict = Schema::object()
-    ///     .field("name", Schema::string())
-    ///     .additional_properties(false);
+    /// static NEXT_ID: AtomicU64 = AtomicU64::new(1);
     ///
-    /// let result = strict.validate(&json!({
-    ///     "name": "Alice",
-    ///     "unknown": "field"
-    /// }), &JsonPath::root());
-    /// assert!(result.is_failure());
+    /// let schema = Schema::object()
+    ///     .default_with("request_id", Schema::integer(), || {
+    ///         json!(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    ///     });
     ///
-    /// // Validate unknown properties against a schema
-    /// let validated = Schema::object()
-    ///     .field("name", Schema::string())
-    ///     .additional_properties(Schema::string());
+    /// let first = schema.validate(&json!({}), &JsonPath::root()).into_result().unwrap();
+    /// let second = schema.validate(&json!({}), &JsonPath::root()).into_result().unwrap();
+    /// assert_eq!(first["request_id"], 1);
+    /// assert_eq!(second["request_id"], 2);
+    ///
+    /// // A given value is validated and kept
+    /// let given = schema.validate(&json!({"request_id": 7}), &JsonPath::root());
+    /// assert_eq!(given.into_result().unwrap()["request_id"], 7);
     /// ```
-    pub fn additional_properties<S>(mut self, setting: S) -> Self
+    pub fn default_with<S, F>(mut self, name: impl Into<String>, schema: S, default: F) -> Self
     where
-        S: Into<AdditionalPropertiesSetting>,
+        S: SchemaLike + ToJsonSchema + 'static,
+        F: Fn() -> Value + Send + Sync + 'static,
     {
-        self.additional_properties = setting.into().0;
+        let name = name.into();
+        self.fields.insert(
+            name,
+            FieldDef {
+                schema: Arc::new(SchemaWrapper(schema)),
+                required: false,
+                default: Some(FieldDefault::Computed(Arc::new(default))),
+                doc: None,
+                aliases: Vec::new(),
+                output_name: None,
+                access: FieldAccess::ReadWrite,
+                required_message: None,
+            },
+        );
         self
     }
 
-    /// Sets a custom error message for type errors.
+    /// Documents the most recently added field.
+    ///
+    /// The description is exported as the field's JSON Schema
+    /// `description`. Registries can look the documentation up by path with
+    /// [`SchemaRegistry::doc_for_path`](crate::SchemaRegistry::doc_for_path)
+    /// and link errors to it with
+    /// [`SchemaRegistry::with_doc_links`](crate::SchemaRegistry::with_doc_links).
+    ///
+    /// # Panics
     ///
-    /// This message is used when the input value is not an object.
+    /// Panics if no field has been added yet.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use postmortem::{Schema, JsonPath};
-    /// use serde_json::json;
+    /// use postmortem::{FieldDoc, Schema, ToJsonSchema};
     ///
     /// let schema = Schema::object()
-    ///     .error("must be a user object");
+    ///     .field("email", Schema::string().email())
+    ///     .doc(FieldDoc::new("Where we send receipts")
+    ///         .link("https://docs.example.com/users#email"));
     ///
-    /// let result = schema.validate(&json!("not an object"), &JsonPath::root());
-    /// // Error message will be "must be a user object"
+    /// let exported = schema.to_json_schema();
+    /// assert_eq!(exported["properties"]["email"]["description"], "Where we send receipts");
     /// ```
-    pub fn error(mut self, message: impl Into<String>) -> Self {
-        self.type_error_message = Some(message.into());
+    pub fn doc(mut self, doc: FieldDoc) -> Self {
+        let (_, field) = self
+            .fields
+            .last_mut()
+            .expect("doc() must follow field(), optional(), default(), or default_with()");
+        field.doc = Some(doc);
         self
     }
 
-    /// Adds a custom cross-field validator.
+    /// Sets the key the most recently added field has in the validated
+    /// output.
     ///
-    /// Cross-field validators run after all field-level validations pass (or fail,
-    /// depending on `skip_on_field_errors` configuration). They receive a
-    /// `ValidatedObject` containing all validated field values.
+    /// Input is still read under the field's name and aliases, and errors
+    /// still point at the input key. An output name takes precedence over
+    /// [`rename_keys`](Self::rename_keys).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no field has been added yet.
     ///
     /// # Example
     ///
     /// ```rust
     /// use postmortem::{Schema, JsonPath};
     /// use serde_json::json;
-    /// use stillwater::Validation;
     ///
     /// let schema = Schema::object()
-    ///     .field("quantity", Schema::integer().positive())
-    ///     .field("unit_price", Schema::integer().non_negative())
-    ///     .field("total", Schema::integer().non_negative())
-    ///     .custom(|obj, path| {
-    ///         let qty = obj.get("quantity").and_then(|v| v.as_i64()).unwrap_or(0);
-    ///         let price = obj.get("unit_price").and_then(|v| v.as_i64()).unwrap_or(0);
-    ///         let total = obj.get("total").and_then(|v| v.as_i64()).unwrap_or(0);
+    ///     .field("e-mail", Schema::string().email())
+    ///     .output_name("email");
     ///
-    ///         if qty * price != total {
-    ///             Validation::Failure(postmortem::SchemaErrors::single(
-    ///                 postmortem::SchemaError::new(
-    ///                     path.push_field("total"),
-    ///                     "total must equal quantity * unit_price"
-    ///                 ).with_code("invalid_total")
-    ///             ))
-    ///         } else {
-    ///             Validation::Success(())
-    ///         }
-    ///     });
+    /// let validated = schema
+    ///     .validate(&json!({"e-mail": "ada@example.com"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(validated["email"], "ada@example.com");
     /// ```
-    pub fn custom<F>(self, validator: F) -> Self
-    where
-        F: Fn(&ValidatedObject, &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static,
-    {
-        let mut schema = self;
-        schema.cross_field_validators.push(Box::new(validator));
-        schema
+    pub fn output_name(mut self, name: impl Into<String>) -> Self {
+        let (_, field) = self
+            .fields
+            .last_mut()
+            .expect("output_name() must follow field(), optional(), default(), or default_with()");
+        field.output_name = Some(name.into());
+        self
     }
 
-    /// Configure whether to skip cross-field validation if field validation fails.
+    /// Marks the most recently added field as read-only, like a
+    /// server-assigned id.
     ///
-    /// Default: `true` (skip cross-field when fields are invalid).
+    /// With a [`ValidationMode`] on the context, a read-only field is
+    /// rejected with a `read_only` error when creating, and required when
+    /// updating. Without a mode, it is validated as usual. It is exported
+    /// with JSON Schema's `readOnly` keyword.
     ///
-    /// When `true`, cross-field validators only run if all field-level validations
-    /// pass. This is usually the desired behavior since cross-field validators
-    /// often assume fields have valid values.
+    /// # Panics
     ///
-    /// Set to `false` to always run cross-field validators, even if some field
-    /// validations failed.
+    /// Panics if no field has been added yet.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use postmortem::Schema;
+    /// use postmortem::validation::ValidationMode;
+    /// use postmortem::{Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Order", Schema::object()
+    ///     .optional("id", Schema::string()).read_only()
+    ///     .field("sku", Schema::string())).unwrap();
+    ///
+    /// let create = registry.context().with_mode(ValidationMode::Create);
+    /// let order = json!({"id": "o-1", "sku": "A-1"});
+    /// let errors = registry
+    ///     .validate_with_context("Order", &order, &create)
+    ///     .unwrap()
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "read_only");
+    /// assert_eq!(errors.first().path.to_string(), "id");
+    /// ```
+    pub fn read_only(mut self) -> Self {
+        let (_, field) = self
+            .fields
+            .last_mut()
+            .expect("read_only() must follow field(), optional(), default(), or default_with()");
+        field.access = FieldAccess::ReadOnly;
+        self
+    }
+
+    /// Marks the most recently added field as write-only, like a password.
+    ///
+    /// With [`ValidationMode::Read`] on the context, a write-only field is
+    /// neither validated nor required, and is removed from the output, so
+    /// it is never returned. Otherwise it is validated as usual. It is
+    /// exported with JSON Schema's `writeOnly` keyword.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no field has been added yet.
+    pub fn write_only(mut self) -> Self {
+        let (_, field) = self
+            .fields
+            .last_mut()
+            .expect("write_only() must follow field(), optional(), default(), or default_with()");
+        field.access = FieldAccess::WriteOnly;
+        self
+    }
+
+    /// Renames every key of the validated output to a naming convention.
+    ///
+    /// This normalizes keys in the same pass as validation, e.g. turning a
+    /// camelCase payload into snake_case. Fields are declared, and errors
+    /// reported, under their input names; renaming happens after
+    /// [cross-field validation](Self::custom), so cross-field rules also
+    /// use the input names. Undeclared properties that are kept in the
+    /// output are renamed too. Nested objects are renamed only if their own
+    /// schemas say so.
+    ///
+    /// If two keys become the same key, validation fails with a
+    /// `key_conflict` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{KeyCase, Schema, JsonPath};
+    /// use serde_json::json;
     ///
     /// let schema = Schema::object()
-    ///     .field("name", Schema::string())
-    ///     .skip_cross_field_on_errors(false);  // Always run cross-field validators
+    ///     .field("firstName", Schema::string())
+    ///     .optional("createdAt", Schema::string().datetime())
+    ///     .rename_keys(KeyCase::SnakeCase);
+    ///
+    /// let validated = schema
+    ///     .validate(&json!({"firstName": "Ada", "lastName": "Lovelace"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(validated["first_name"], "Ada");
+    /// assert_eq!(validated["last_name"], "Lovelace");
     /// ```
-    pub fn skip_cross_field_on_errors(mut self, skip: bool) -> Self {
-        self.skip_on_field_errors = skip;
+    pub fn rename_keys(mut self, case: KeyCase) -> Self {
+        self.key_case = Some(case);
         self
     }
 
-    /// Requires a field when a condition is met.
+    /// Configures whether input keys match declared fields ignoring case.
     ///
-    /// If the condition field matches the predicate, the required field must be present.
+    /// When `true`, a field declared as `contentType` is also read from
+    /// `ContentType` or `CONTENTTYPE`, and so are its aliases. The validated
+    /// output uses the declared name, while errors point at the input key.
+    /// If a field is given under more than one such key, validation fails
+    /// with an `alias_conflict` error, as for aliases.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use postmortem::Schema;
+    /// use postmortem::{Schema, JsonPath};
     /// use serde_json::json;
     ///
     /// let schema = Schema::object()
-    ///     .field("method", Schema::string())
-    ///     .optional("card_number", Schema::string())
-    ///     .require_if("method", |v| v == &json!("card"), "card_number");
+    ///     .field("contentType", Schema::string())
+    ///     .additional_properties(false)
+    ///     .case_insensitive_fields(true);
+    ///
+    /// let validated = schema
+    ///     .validate(&json!({"ContentType": "text/plain"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(validated["contentType"], "text/plain");
+    ///
+    /// let errors = schema
+    ///     .validate(
+    ///         &json!({"ContentType": "text/plain", "contentType": "text/html"}),
+    ///         &JsonPath::root(),
+    ///     )
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "alias_conflict");
     /// ```
-    pub fn require_if<P>(
-        self,
-        condition_field: impl Into<String>,
-        predicate: P,
-        required_field: impl Into<String>,
-    ) -> Self
-    where
-        P: Fn(&Value) -> bool + Send + Sync + 'static,
-    {
-        let condition_field = condition_field.into();
-        let required_field = required_field.into();
+    pub fn case_insensitive_fields(mut self, ignore_case: bool) -> Self {
+        self.case_insensitive_fields = ignore_case;
+        self
+    }
 
-        self.custom(move |obj, path| {
-            let condition_value = obj.get(&condition_field);
-            let required_value = obj.get(&required_field);
+    /// Configures whether `null` counts as absent for optional fields.
+    ///
+    /// When `false` (the default), an optional field given as `null` is
+    /// validated like any other value, so it fails unless its schema is
+    /// [nullable](crate::SchemaExt::nullable). When `true`, a `null` that the
+    /// field's schema rejects is treated as if the field were absent: its
+    /// default is used, if it has one, and otherwise it is left out of the
+    /// output. Nullable fields keep their `null`, and required fields are
+    /// unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaExt};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("nickname", Schema::string())
+    ///     .default("role", Schema::string(), json!("user"))
+    ///     .optional("manager", Schema::string().nullable())
+    ///     .null_as_absent(true);
+    ///
+    /// let validated = schema
+    ///     .validate(&json!({"nickname": null, "role": null, "manager": null}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert!(!validated.contains_key("nickname"));
+    /// assert_eq!(validated["role"], "user");
+    /// assert!(validated["manager"].is_null());
+    /// ```
+    pub fn null_as_absent(mut self, enabled: bool) -> Self {
+        self.null_as_absent = enabled;
+        self
+    }
 
-            match (condition_value, required_value) {
-                (Some(cv), None) if predicate(cv) => Validation::Failure(SchemaErrors::single(
+    /// Marks a field as deprecated, so clients can be moved off it.
+    ///
+    /// When the field is present, a `deprecated` error is reported with
+    /// `message`, such as what to use instead. By default it is only a
+    /// warning, recorded on the [`ValidationContext`](crate::validation::ValidationContext)
+    /// when there is one (see
+    /// [`take_warnings`](crate::validation::ValidationContext::take_warnings));
+    /// [`deny_deprecated`](Self::deny_deprecated) makes it fail validation.
+    ///
+    /// The name may be a declared field, which is then validated as usual,
+    /// or undeclared, in which case its value is accepted and passed through
+    /// even if additional properties are denied. Deprecated fields are
+    /// exported with JSON Schema's `deprecated` keyword.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("User", Schema::object()
+    ///     .field("id", Schema::integer())
+    ///     .deprecated_field("legacy_id", "use 'id' instead")
+    ///     .additional_properties(false)).unwrap();
+    ///
+    /// let context = registry.context();
+    /// let user = json!({"id": 7, "legacy_id": "u-7"});
+    /// assert!(registry.validate_with_context("User", &user, &context).unwrap().is_success());
+    ///
+    /// let warnings = context.take_warnings();
+    /// assert_eq!(warnings[0].error.code, "deprecated");
+    /// assert_eq!(warnings[0].error.message, "field 'legacy_id' is deprecated: use 'id' instead");
+    /// ```
+    pub fn deprecated_field(mut self, name: impl Into<String>, message: impl Into<String>) -> Self {
+        self.deprecated.insert(name.into(), message.into());
+        self
+    }
+
+    /// Configures whether deprecated fields fail validation.
+    ///
+    /// When `false` (the default), a present [deprecated
+    /// field](Self::deprecated_field) is only a warning. Set to `true` once
+    /// clients have had time to migrate, or in tests to catch new uses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("id", Schema::integer())
+    ///     .deprecated_field("legacy_id", "use 'id' instead")
+    ///     .deny_deprecated(true);
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"id": 7, "legacy_id": "u-7"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "deprecated");
+    /// ```
+    pub fn deny_deprecated(mut self, deny: bool) -> Self {
+        self.deny_deprecated = deny;
+        self
+    }
+
+    /// Validates undeclared properties whose names match a regex pattern.
+    ///
+    /// This is JSON Schema's `patternProperties`. A property that is not
+    /// declared with [`field`](Self::field) or one of its siblings is
+    /// validated against every pattern schema whose regex matches its name;
+    /// the output of the first one is kept. Only properties that match no
+    /// pattern fall through to [`additional_properties`](Self::additional_properties).
+    ///
+    /// Returns an error if the regex pattern is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("name", Schema::string())
+    ///     .pattern_property("^x-", Schema::string())
+    ///     .unwrap()
+    ///     .pattern_property(r"^\d+$", Schema::integer())
+    ///     .unwrap()
+    ///     .additional_properties(false);
+    ///
+    /// let result = schema.validate(&json!({
+    ///     "name": "widget",
+    ///     "x-owner": "platform",
+    ///     "42": 7
+    /// }), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"name": "widget", "x-owner": 1, "color": "red"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors.with_code("invalid_type").len(), 1);
+    /// assert_eq!(errors.with_code("additional_property").len(), 1);
+    /// ```
+    pub fn pattern_property<S>(mut self, pattern: &str, schema: S) -> Result<Self, regex::Error>
+    where
+        S: SchemaLike + ToJsonSchema + 'static,
+    {
+        let regex = Regex::new(pattern)?;
+        self.pattern_properties
+            .push((regex, Arc::new(SchemaWrapper(schema))));
+        Ok(self)
+    }
+
+    /// Configures how unknown properties are handled.
+    ///
+    /// By default, unknown properties are allowed. Use this method to reject
+    /// unknown properties or validate them against a schema.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// // Reject unknown properties
+    /// let strict = Schema::object()
+    ///     .field("name", Schema::string())
+    ///     .additional_properties(false);
+    ///
+    /// let result = strict.validate(&json!({
+    ///     "name": "Alice",
+    ///     "unknown": "field"
+    /// }), &JsonPath::root());
+    /// assert!(result.is_failure());
+    ///
+    /// // Validate unknown properties against a schema
+    /// let validated = Schema::object()
+    ///     .field("name", Schema::string())
+    ///     .additional_properties(Schema::string());
+    /// ```
+    pub fn additional_properties<S>(mut self, setting: S) -> Self
+    where
+        S: Into<AdditionalPropertiesSetting>,
+    {
+        self.additional_properties = setting.into().0;
+        self
+    }
+
+    /// Limits how many properties beyond the declared fields are checked.
+    ///
+    /// Pattern and additional properties are validated and copied to the
+    /// output one by one, so a map from an untrusted client with a million
+    /// keys costs a million validations. Past `max` such properties,
+    /// validation stops looking at the rest and reports a single
+    /// `too_many_properties` error at the object.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::{json, Map, Value};
+    ///
+    /// let labels = Schema::object()
+    ///     .additional_properties(Schema::string().max_len(64))
+    ///     .max_additional_properties(100);
+    ///
+    /// let flood: Map<String, Value> = (0..10_000).map(|i| (format!("k{}", i), json!(i))).collect();
+    /// let errors = labels
+    ///     .validate(&Value::Object(flood), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// // 100 type errors for the keys checked, then one for the rest
+    /// assert_eq!(errors.len(), 101);
+    /// assert_eq!(errors.with_code("too_many_properties").len(), 1);
+    /// ```
+    pub fn max_additional_properties(mut self, max: usize) -> Self {
+        self.max_additional_properties = Some(max);
+        self
+    }
+
+    /// Counts one more property beyond the declared fields, returning the
+    /// error to stop at if it is past the limit.
+    fn past_property_limit(&self, extra: &mut usize, path: &JsonPath) -> Option<SchemaError> {
+        *extra += 1;
+        let max = self.max_additional_properties?;
+        (*extra > max).then(|| {
+            SchemaError::new(
+                path.clone(),
+                format!(
+                    "more than {} beyond the declared fields",
+                    count(max, "property", "properties")
+                ),
+            )
+            .with_code("too_many_properties")
+            .with_expected(format!("at most {}", max))
+        })
+    }
+
+    /// Sets a custom error message for type errors.
+    ///
+    /// This message is used when the input value is not an object. It may
+    /// contain the placeholders `{field}`, `{path}`, `{got}`, and
+    /// `{expected}`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .error("must be a user object");
+    ///
+    /// let result = schema.validate(&json!("not an object"), &JsonPath::root());
+    /// // Error message will be "must be a user object"
+    /// ```
+    pub fn error(mut self, message: impl Into<String>) -> Self {
+        self.type_error_message = Some(message.into());
+        self
+    }
+
+    /// Sets a custom error message for when a required field is missing.
+    ///
+    /// The message replaces the default of the field's `required` error.
+    /// It may contain the placeholders `{field}`, `{path}`, and
+    /// `{expected}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field` has not been declared.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("name", Schema::string())
+    ///     .required_error("name", "Please provide your full name");
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "required");
+    /// assert_eq!(errors.first().message, "Please provide your full name");
+    /// ```
+    pub fn required_error(mut self, field: &str, message: impl Into<String>) -> Self {
+        let Some(field_def) = self.fields.get_mut(field) else {
+            panic!("required_error() names undeclared field '{}'", field);
+        };
+        field_def.required_message = Some(message.into());
+        self
+    }
+
+    /// Adds a custom cross-field validator.
+    ///
+    /// Cross-field validators run after all field-level validations pass (or fail,
+    /// depending on `skip_on_field_errors` configuration). They receive a
+    /// `ValidatedObject` containing all validated field values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    /// use stillwater::Validation;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("quantity", Schema::integer().positive())
+    ///     .field("unit_price", Schema::integer().non_negative())
+    ///     .field("total", Schema::integer().non_negative())
+    ///     .custom(|obj, path| {
+    ///         let qty = obj.get_i64("quantity").unwrap_or(0);
+    ///         let price = obj.get_i64("unit_price").unwrap_or(0);
+    ///         let total = obj.get_i64("total").unwrap_or(0);
+    ///
+    ///         if qty * price != total {
+    ///             Validation::Failure(postmortem::SchemaErrors::single(
+    ///                 postmortem::SchemaError::new(
+    ///                     path.push_field("total"),
+    ///                     "total must equal quantity * unit_price"
+    ///                 ).with_code("invalid_total")
+    ///             ))
+    ///         } else {
+    ///             Validation::Success(())
+    ///         }
+    ///     });
+    /// ```
+    pub fn custom<F>(self, validator: F) -> Self
+    where
+        F: Fn(&ValidatedObject, &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static,
+    {
+        let mut schema = self;
+        schema.cross_field_validators.push(Arc::new(validator));
+        schema
+    }
+
+    /// Adds a custom cross-field validator that also sees the raw input.
+    ///
+    /// Like [`custom`](Self::custom), but the validator also receives the
+    /// object as it was given, before field validation. With
+    /// [`skip_cross_field_on_errors(false)`](Self::skip_cross_field_on_errors),
+    /// fields that failed validation are missing from the `ValidatedObject`,
+    /// and the raw object is the only way to inspect them. Raw values are
+    /// unvalidated and keyed by the input's names, before aliases and key
+    /// case conversion, so check their types before relying on them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaError, SchemaErrors, JsonPath};
+    /// use serde_json::json;
+    /// use stillwater::Validation;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("password", Schema::string().min_len(12))
+    ///     .field("username", Schema::string())
+    ///     .skip_cross_field_on_errors(false)
+    ///     .custom_raw(|_obj, raw, path| {
+    ///         // The password is too short, so only the raw object has it
+    ///         if raw.get("password").is_some() && raw.get("password") == raw.get("username") {
+    ///             Validation::Failure(SchemaErrors::single(
+    ///                 SchemaError::new(path.push_field("password"), "password must differ from username")
+    ///                     .with_code("password_is_username"),
+    ///             ))
+    ///         } else {
+    ///             Validation::Success(())
+    ///         }
+    ///     });
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"username": "ada", "password": "ada"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors.with_code("password_is_username").len(), 1);
+    /// ```
+    pub fn custom_raw<F>(self, validator: F) -> Self
+    where
+        F: Fn(&ValidatedObject, &Map<String, Value>, &JsonPath) -> Validation<(), SchemaErrors>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.custom(move |obj, path| validator(obj, &obj.raw, path))
+    }
+
+    /// Configure whether to skip cross-field validation if field validation fails.
+    ///
+    /// Default: `true` (skip cross-field when fields are invalid).
+    ///
+    /// When `true`, cross-field validators only run if all field-level validations
+    /// pass. This is usually the desired behavior since cross-field validators
+    /// often assume fields have valid values.
+    ///
+    /// Set to `false` to always run cross-field validators, even if some field
+    /// validations failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("name", Schema::string())
+    ///     .skip_cross_field_on_errors(false);  // Always run cross-field validators
+    /// ```
+    pub fn skip_cross_field_on_errors(mut self, skip: bool) -> Self {
+        self.skip_on_field_errors = skip;
+        self
+    }
+
+    /// Builds every error reported for this object, including those of its
+    /// fields, with `factory`.
+    ///
+    /// The factory sees each constraint's own error and the value that
+    /// failed, so it can set the message style, codes, and metadata without
+    /// an `.error(...)` on every constraint. See [`ErrorFactory`]. When
+    /// objects with factories are nested, the outermost factory builds the
+    /// final error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SchemaError};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("name", Schema::string().min_len(2))
+    ///     .field("email", Schema::string().email())
+    ///     .with_error_factory(|info, _value, path| {
+    ///         SchemaError::new(path.clone(), format!("Please check {}: {}", path, info.message))
+    ///             .with_code(info.code)
+    ///     });
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"name": "A"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert!(errors.first().message.starts_with("Please check name: length must be at least 2"));
+    /// assert_eq!(errors.with_code("required").len(), 1);
+    /// ```
+    pub fn with_error_factory<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(ConstraintInfo<'_>, &Value, &JsonPath) -> SchemaError + Send + Sync + 'static,
+    {
+        self.error_factory = Some(Arc::new(factory));
+        self
+    }
+
+    /// Rebuilds the errors of a failed validation with the error factory,
+    /// if there is one.
+    fn finish(
+        &self,
+        result: Validation<Map<String, Value>, SchemaErrors>,
+        value: &Value,
+        path: &JsonPath,
+    ) -> Validation<Map<String, Value>, SchemaErrors> {
+        let result = match result {
+            Validation::Success(validated) => self.rename_output(validated, path),
+            failure => failure,
+        };
+        match (result, &self.error_factory) {
+            (Validation::Failure(errors), Some(factory)) => {
+                Validation::Failure(rebuild_errors(factory, errors, value, path))
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Returns the key `key` has in the validated output.
+    fn output_key(&self, key: &str) -> String {
+        match self.fields.get(key).and_then(|f| f.output_name.clone()) {
+            Some(name) => name,
+            None => match self.key_case {
+                Some(case) => case.apply(key),
+                None => key.to_string(),
+            },
+        }
+    }
+
+    /// Renames the keys of a validated object to their output names.
+    fn rename_output(
+        &self,
+        validated: Map<String, Value>,
+        path: &JsonPath,
+    ) -> Validation<Map<String, Value>, SchemaErrors> {
+        let renames = self.key_case.is_some()
+            || self
+                .fields
+                .values()
+                .any(|field| field.output_name.is_some());
+        if !renames {
+            return Validation::Success(validated);
+        }
+
+        let mut output = Map::new();
+        let mut sources: HashMap<String, String> = HashMap::new();
+        let mut errors = Vec::new();
+        for (key, value) in validated {
+            let renamed = self.output_key(&key);
+            if let Some(other) = sources.get(&renamed) {
+                errors.push(
+                    SchemaError::new(
+                        path.push_field(&key),
+                        format!(
+                            "keys '{}' and '{}' would both be renamed to '{}'",
+                            other, key, renamed
+                        ),
+                    )
+                    .with_code("key_conflict")
+                    .with_expected(format!("a single '{}' key", renamed)),
+                );
+                continue;
+            }
+            sources.insert(renamed.clone(), key);
+            output.insert(renamed, value);
+        }
+
+        if errors.is_empty() {
+            Validation::Success(output)
+        } else {
+            Validation::Failure(SchemaErrors::from_vec(errors))
+        }
+    }
+
+    /// Requires a field when a condition is met.
+    ///
+    /// If the condition field matches the predicate, the required field must be present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("method", Schema::string())
+    ///     .optional("card_number", Schema::string())
+    ///     .require_if("method", |v| v == &json!("card"), "card_number");
+    /// ```
+    pub fn require_if<P>(
+        self,
+        condition_field: impl Into<String>,
+        predicate: P,
+        required_field: impl Into<String>,
+    ) -> Self
+    where
+        P: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        let condition_field = condition_field.into();
+        let required_field = required_field.into();
+
+        self.custom(move |obj, path| {
+            let condition_value = obj.get(&condition_field);
+            let required_value = obj.get(&required_field);
+
+            match (condition_value, required_value) {
+                (Some(cv), None) if predicate(cv) => Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(
+                        path.push_field(&required_field),
+                        format!(
+                            "'{}' is required when '{}' matches condition",
+                            required_field, condition_field
+                        ),
+                    )
+                    .with_code("conditional_required"),
+                )),
+                _ => Validation::Success(()),
+            }
+        })
+    }
+
+    /// Requires a field unless a condition is met.
+    ///
+    /// The inverse of [`require_if`](Self::require_if): the required field
+    /// must be present unless the condition field is present and matches
+    /// the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("sso_provider", Schema::string())
+    ///     .optional("password", Schema::string())
+    ///     .require_unless("sso_provider", |v| v.is_string(), "password");
+    ///
+    /// assert!(schema.validate(&json!({"sso_provider": "okta"}), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema.validate(&json!({}), &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().code, "conditional_required");
+    /// ```
+    pub fn require_unless<P>(
+        self,
+        condition_field: impl Into<String>,
+        predicate: P,
+        required_field: impl Into<String>,
+    ) -> Self
+    where
+        P: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        let condition_field = condition_field.into();
+        let required_field = required_field.into();
+
+        self.custom(move |obj, path| {
+            let exempt = obj.get(&condition_field).is_some_and(&predicate);
+            if exempt || obj.has(&required_field) {
+                return Validation::Success(());
+            }
+            Validation::Failure(SchemaErrors::single(
+                SchemaError::new(
+                    path.push_field(&required_field),
+                    format!(
+                        "'{}' is required unless '{}' matches condition",
+                        required_field, condition_field
+                    ),
+                )
+                .with_code("conditional_required"),
+            ))
+        })
+    }
+
+    /// Forbids a field when a condition is met.
+    ///
+    /// If the condition field matches the predicate, the forbidden field
+    /// must be absent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("role", Schema::string())
+    ///     .optional("permissions", Schema::array(Schema::string()))
+    ///     .forbid_if("role", |v| v == "guest", "permissions");
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"role": "guest", "permissions": ["admin"]}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "conditional_forbidden");
+    /// assert_eq!(errors.first().path.to_string(), "permissions");
+    /// ```
+    pub fn forbid_if<P>(
+        self,
+        condition_field: impl Into<String>,
+        predicate: P,
+        forbidden_field: impl Into<String>,
+    ) -> Self
+    where
+        P: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        let condition_field = condition_field.into();
+        let forbidden_field = forbidden_field.into();
+
+        self.custom(move |obj, path| {
+            let applies = obj.get(&condition_field).is_some_and(&predicate);
+            if !applies || !obj.has(&forbidden_field) {
+                return Validation::Success(());
+            }
+            Validation::Failure(SchemaErrors::single(
+                SchemaError::new(
+                    path.push_field(&forbidden_field),
+                    format!(
+                        "'{}' is not allowed when '{}' matches condition",
+                        forbidden_field, condition_field
+                    ),
+                )
+                .with_code("conditional_forbidden"),
+            ))
+        })
+    }
+
+    /// Requires other fields whenever a trigger field is present.
+    ///
+    /// This is JSON Schema's `dependentRequired`. If `trigger` is present,
+    /// each field in `dependents` that is missing gets its own
+    /// `dependent_required` error. Unlike cross-field rules added with
+    /// [`custom`](Self::custom), the check runs even if fields have other
+    /// errors, like the check for required fields. Calling it again for the
+    /// same trigger adds to its dependents.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("credit_card", Schema::string())
+    ///     .optional("billing_address", Schema::string())
+    ///     .optional("cvv", Schema::string())
+    ///     .dependent_required("credit_card", ["billing_address", "cvv"]);
+    ///
+    /// assert!(schema.validate(&json!({}), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"credit_card": "4111 1111 1111 1111"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.with_code("dependent_required").len(), 2);
+    /// assert_eq!(errors.first().message, "field 'billing_address' is required when 'credit_card' is present");
+    /// ```
+    pub fn dependent_required<I>(mut self, trigger: impl Into<String>, dependents: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.dependent_required
+            .entry(trigger.into())
+            .or_default()
+            .extend(dependents.into_iter().map(Into::into));
+        self
+    }
+
+    /// Applies another object schema to the whole object when a trigger
+    /// field matches a condition.
+    ///
+    /// This is JSON Schema's `dependentSchemas`, extended to conditions on
+    /// the trigger's value, so a payload whose shape depends on a
+    /// discriminator can be validated without [`one_of`](super::Schema::one_of).
+    /// The condition is one of:
+    ///
+    /// - [`DependentCondition::present`]: the trigger is present
+    /// - a value, e.g. `"card"` or `json!(2)`: the trigger equals it
+    /// - a predicate on the trigger's value, e.g. `|v: &Value| v.is_string()`
+    ///
+    /// When the condition holds, the object must also be valid against
+    /// `schema`, and the fields `schema` declares are accepted even if this
+    /// schema denies additional properties. Its errors are reported with the
+    /// errors of this schema's fields, and its output fields are added to
+    /// the output.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("type", Schema::string())
+    ///     .additional_properties(false)
+    ///     .dependent_schema(
+    ///         "type",
+    ///         "card",
+    ///         Schema::object().field("card_number", Schema::string().min_len(12)),
+    ///     )
+    ///     .dependent_schema(
+    ///         "type",
+    ///         "bank",
+    ///         Schema::object().field("iban", Schema::string()),
+    ///     );
+    ///
+    /// let card = json!({"type": "card", "card_number": "4111111111111111"});
+    /// assert!(schema.validate(&card, &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"type": "bank", "card_number": "4111"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors.with_code("required")[0].path.to_string(), "iban");
+    /// assert_eq!(errors.with_code("additional_property")[0].path.to_string(), "card_number");
+    /// ```
+    pub fn dependent_schema(
+        mut self,
+        trigger: impl Into<String>,
+        condition: impl Into<DependentCondition>,
+        schema: ObjectSchema,
+    ) -> Self {
+        self.dependent_schemas.push(DependentSchema {
+            trigger: trigger.into(),
+            condition: condition.into(),
+            schema,
+        });
+        self
+    }
+
+    /// Ensures two fields are mutually exclusive.
+    ///
+    /// At most one of the two fields can be present (non-null).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("email", Schema::string())
+    ///     .optional("phone", Schema::string())
+    ///     .mutually_exclusive("email", "phone");
+    /// ```
+    pub fn mutually_exclusive(self, field1: impl Into<String>, field2: impl Into<String>) -> Self {
+        let field1 = field1.into();
+        let field2 = field2.into();
+
+        self.custom(move |obj, path| {
+            let has_field1 = obj.has(&field1);
+            let has_field2 = obj.has(&field2);
+
+            if has_field1 && has_field2 {
+                Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(
+                        path.clone(),
+                        format!("'{}' and '{}' are mutually exclusive", field1, field2),
+                    )
+                    .with_code("mutually_exclusive"),
+                ))
+            } else {
+                Validation::Success(())
+            }
+        })
+    }
+
+    /// Requires at least one of the specified fields to be present.
+    ///
+    /// At least one field must exist and be non-null.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::Schema;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("email", Schema::string())
+    ///     .optional("phone", Schema::string())
+    ///     .at_least_one_of(["email", "phone"]);
+    /// ```
+    pub fn at_least_one_of<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+
+        self.custom(move |obj, path| {
+            let has_any = fields.iter().any(|f| obj.has(f));
+
+            if has_any {
+                Validation::Success(())
+            } else {
+                Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(
+                        path.clone(),
+                        format!("at least one of {:?} is required", fields),
+                    )
+                    .with_code("at_least_one_required"),
+                ))
+            }
+        })
+    }
+
+    /// Requires exactly one of the specified fields to be present.
+    ///
+    /// Exactly one field must exist and be non-null. The error message names
+    /// the fields that are present when there are too many.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("email", Schema::string())
+    ///     .optional("phone", Schema::string())
+    ///     .optional("telegram", Schema::string())
+    ///     .exactly_one_of(["email", "phone", "telegram"]);
+    ///
+    /// assert!(schema.validate(&json!({"phone": "+1234567890"}), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"email": "a@example.com", "phone": "+1234567890"}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "exactly_one_required");
+    /// ```
+    pub fn exactly_one_of<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+
+        self.custom(move |obj, path| {
+            let present: Vec<&String> = fields.iter().filter(|f| obj.has(f)).collect();
+
+            let message = match present.len() {
+                1 => return Validation::Success(()),
+                0 => format!("exactly one of {:?} is required", fields),
+                _ => format!("exactly one of {:?} is allowed, got {:?}", fields, present),
+            };
+            Validation::Failure(SchemaErrors::single(
+                SchemaError::new(path.clone(), message).with_code("exactly_one_required"),
+            ))
+        })
+    }
+
+    /// Requires the specified fields to be either all present or all absent.
+    ///
+    /// If any field exists and is non-null, all of them must. The error
+    /// message names the fields that are missing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .optional("lat", Schema::integer())
+    ///     .optional("lng", Schema::integer())
+    ///     .all_or_none(["lat", "lng"]);
+    ///
+    /// assert!(schema.validate(&json!({}), &JsonPath::root()).is_success());
+    /// assert!(schema.validate(&json!({"lat": 52, "lng": 13}), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!({"lat": 52}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "all_or_none");
+    /// ```
+    pub fn all_or_none<I, S>(self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+
+        self.custom(move |obj, path| {
+            let missing: Vec<&String> = fields.iter().filter(|f| !obj.has(f)).collect();
+
+            if missing.is_empty() || missing.len() == fields.len() {
+                Validation::Success(())
+            } else {
+                Validation::Failure(SchemaErrors::single(
                     SchemaError::new(
-                        path.push_field(&required_field),
-                        format!(
-                            "'{}' is required when '{}' matches condition",
-                            required_field, condition_field
-                        ),
+                        path.clone(),
+                        format!("{:?} must be given together, missing {:?}", fields, missing),
                     )
-                    .with_code("conditional_required"),
-                )),
-                _ => Validation::Success(()),
+                    .with_code("all_or_none"),
+                ))
             }
         })
     }
 
-    /// Ensures two fields are mutually exclusive.
+    /// Ensures the amounts matching a pattern add up to a total field.
     ///
-    /// At most one of the two fields can be present (non-null).
+    /// `items_pattern` selects the amounts, with `[*]` matching every
+    /// element of an array, e.g. `"items[*].amount"`. Amounts and the
+    /// total may be JSON numbers or decimal strings such as `"19.99"`, and
+    /// are added exactly, so `0.1 + 0.2` equals `0.3`. The check is skipped
+    /// if the total is missing or any value is not a decimal, which the
+    /// field schemas report instead.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use postmortem::Schema;
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
     ///
     /// let schema = Schema::object()
-    ///     .optional("email", Schema::string())
-    ///     .optional("phone", Schema::string())
-    ///     .mutually_exclusive("email", "phone");
+    ///     .field("items", Schema::array(Schema::object().field("amount", Schema::string())))
+    ///     .field("total", Schema::string())
+    ///     .amounts_sum_to("items[*].amount", "total");
+    ///
+    /// let order = json!({"items": [{"amount": "10.10"}, {"amount": "0.20"}], "total": "10.3"});
+    /// assert!(schema.validate(&order, &JsonPath::root()).is_success());
+    ///
+    /// let order = json!({"items": [{"amount": "10.10"}], "total": "10.00"});
+    /// let errors = schema
+    ///     .validate(&order, &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "sum_mismatch");
+    /// assert_eq!(errors.first().path.to_string(), "total");
     /// ```
-    pub fn mutually_exclusive(self, field1: impl Into<String>, field2: impl Into<String>) -> Self {
-        let field1 = field1.into();
-        let field2 = field2.into();
+    pub fn amounts_sum_to(
+        self,
+        items_pattern: impl Into<String>,
+        total_field: impl Into<String>,
+    ) -> Self {
+        let pattern = parse_pattern(&items_pattern.into());
+        let total_field = total_field.into();
 
         self.custom(move |obj, path| {
-            let has_field1 = obj.has(&field1);
-            let has_field2 = obj.has(&field2);
-
-            if has_field1 && has_field2 {
-                Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(
-                        path.clone(),
-                        format!("'{}' and '{}' are mutually exclusive", field1, field2),
-                    )
-                    .with_code("mutually_exclusive"),
-                ))
-            } else {
-                Validation::Success(())
+            let Some(total) = obj.get(&total_field).and_then(Decimal::from_value) else {
+                return Validation::Success(());
+            };
+            let sum = resolve_in_object(obj, &pattern, path)
+                .into_iter()
+                .try_fold(Decimal::ZERO, |sum, (_, value)| {
+                    sum.checked_add(Decimal::from_value(value)?)
+                });
+            match sum {
+                Some(sum) if sum.compare(total) != Some(Ordering::Equal) => {
+                    Validation::Failure(SchemaErrors::single(
+                        SchemaError::new(
+                            path.push_field(&total_field),
+                            format!(
+                                "'{}' is {} but the amounts add up to {}",
+                                total_field, total, sum
+                            ),
+                        )
+                        .with_code("sum_mismatch")
+                        .with_expected(sum.to_string())
+                        .with_got(total.to_string()),
+                    ))
+                }
+                _ => Validation::Success(()),
             }
         })
     }
 
-    /// Requires at least one of the specified fields to be present.
+    /// Ensures the currencies matching a pattern agree with a currency
+    /// field.
     ///
-    /// At least one field must exist and be non-null.
+    /// `items_pattern` selects the item currencies, e.g.
+    /// `"items[*].currency"`. Currencies are compared ignoring ASCII case.
+    /// If `currency_field` is absent, the items must agree with the first
+    /// item's currency. Each disagreeing item is reported at its own path.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use postmortem::Schema;
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
     ///
     /// let schema = Schema::object()
-    ///     .optional("email", Schema::string())
-    ///     .optional("phone", Schema::string())
-    ///     .at_least_one_of(["email", "phone"]);
+    ///     .field("items", Schema::array(Schema::object().field("currency", Schema::string())))
+    ///     .optional("currency", Schema::string())
+    ///     .currency_consistent("items[*].currency", "currency");
+    ///
+    /// let order = json!({"items": [{"currency": "EUR"}, {"currency": "USD"}], "currency": "eur"});
+    /// let errors = schema
+    ///     .validate(&order, &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "currency_mismatch");
+    /// assert_eq!(errors.first().path.to_string(), "items[1].currency");
     /// ```
-    pub fn at_least_one_of<I, S>(self, fields: I) -> Self
-    where
-        I: IntoIterator<Item = S>,
-        S: Into<String>,
-    {
-        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+    pub fn currency_consistent(
+        self,
+        items_pattern: impl Into<String>,
+        currency_field: impl Into<String>,
+    ) -> Self {
+        let pattern = parse_pattern(&items_pattern.into());
+        let currency_field = currency_field.into();
 
         self.custom(move |obj, path| {
-            let has_any = fields.iter().any(|f| obj.has(f));
+            let currencies: Vec<(JsonPath, &str)> = resolve_in_object(obj, &pattern, path)
+                .into_iter()
+                .filter_map(|(path, value)| Some((path, value.as_str()?)))
+                .collect();
+            let (expected, source) = match obj.get(&currency_field).and_then(Value::as_str) {
+                Some(currency) => (currency, currency_field.clone()),
+                None => match currencies.first() {
+                    Some((first, currency)) => (*currency, first.to_string()),
+                    None => return Validation::Success(()),
+                },
+            };
 
-            if has_any {
-                Validation::Success(())
-            } else {
-                Validation::Failure(SchemaErrors::single(
+            let errors: Vec<SchemaError> = currencies
+                .iter()
+                .filter(|(_, currency)| !currency.eq_ignore_ascii_case(expected))
+                .map(|(item_path, currency)| {
                     SchemaError::new(
-                        path.clone(),
-                        format!("at least one of {:?} is required", fields),
+                        item_path.clone(),
+                        format!(
+                            "currency '{}' does not match '{}' from '{}'",
+                            currency, expected, source
+                        ),
                     )
-                    .with_code("at_least_one_required"),
-                ))
+                    .with_code("currency_mismatch")
+                    .with_expected(expected.to_string())
+                    .with_got(currency)
+                })
+                .collect();
+            if errors.is_empty() {
+                Validation::Success(())
+            } else {
+                Validation::Failure(SchemaErrors::from_vec(errors))
             }
         })
     }
@@ -613,6 +2120,159 @@ impl ObjectSchema {
         })
     }
 
+    /// Returns a copy of the schema with only the named fields.
+    ///
+    /// Useful for projections and for endpoints that accept a subset of a
+    /// resource. The fields keep their schemas, aliases, and docs, and
+    /// object-level settings such as
+    /// [`additional_properties`](Self::additional_properties) are kept.
+    /// Rules about fields, such as [`dependent_required`](Self::dependent_required)
+    /// and [`deprecated_field`](Self::deprecated_field), are kept for the
+    /// fields that remain. Cross-field rules added with
+    /// [`custom`](Self::custom) or its helpers are dropped, as they may refer
+    /// to fields that were removed; add them again if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a name is not a declared field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let user = Schema::object()
+    ///     .field("id", Schema::integer())
+    ///     .field("name", Schema::string().min_len(1))
+    ///     .field("email", Schema::string().email())
+    ///     .additional_properties(false);
+    ///
+    /// let contact = user.pick(["name", "email"]);
+    /// let value = json!({"name": "Ada", "email": "ada@example.com"});
+    /// assert!(contact.validate(&value, &JsonPath::root()).is_success());
+    /// assert!(user.validate(&value, &JsonPath::root()).is_failure());
+    /// ```
+    pub fn pick<I>(&self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let names = self.declared_names(names, "pick");
+        self.retain_fields(|name| names.iter().any(|n| n == name))
+    }
+
+    /// Returns a copy of the schema without the named fields.
+    ///
+    /// The counterpart of [`pick`](Self::pick), which describes what is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a name is not a declared field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let user = Schema::object()
+    ///     .field("id", Schema::integer())
+    ///     .field("name", Schema::string().min_len(1));
+    ///
+    /// let new_user = user.omit(["id"]);
+    /// assert!(new_user.validate(&json!({"name": "Ada"}), &JsonPath::root()).is_success());
+    /// ```
+    pub fn omit<I>(&self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let names = self.declared_names(names, "omit");
+        self.retain_fields(|name| !names.iter().any(|n| n == name))
+    }
+
+    /// Returns a copy of the schema with every field optional.
+    ///
+    /// This is the schema of a PATCH request body: each field that is sent
+    /// must be valid, but none has to be sent. Defaults are dropped, so
+    /// the output only contains what was sent and a patch does not
+    /// overwrite stored values with defaults. Other presence requirements
+    /// are lifted too: [`dependent_required`](Self::dependent_required)
+    /// rules are dropped, and [dependent schemas](Self::dependent_schema)
+    /// are made partial. Cross-field rules are kept, and see only the fields
+    /// that were sent. Nested object schemas are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema};
+    /// use serde_json::json;
+    ///
+    /// let user = Schema::object()
+    ///     .field("name", Schema::string().min_len(1))
+    ///     .default("role", Schema::string(), json!("member"));
+    ///
+    /// let patch = user.partial();
+    /// let output = patch
+    ///     .validate(&json!({}), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert!(output.is_empty());
+    /// assert!(patch.validate(&json!({"name": ""}), &JsonPath::root()).is_failure());
+    /// ```
+    pub fn partial(&self) -> Self {
+        let mut schema = self.clone();
+        for field in schema.fields.values_mut() {
+            field.required = false;
+            field.default = None;
+        }
+        schema.dependent_required.clear();
+        for dependent in &mut schema.dependent_schemas {
+            dependent.schema = dependent.schema.partial();
+        }
+        schema
+    }
+
+    /// Collects names given to `method`, checking that they are declared
+    /// fields.
+    fn declared_names<I>(&self, names: I, method: &str) -> Vec<String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        names
+            .into_iter()
+            .map(|name| {
+                let name = name.as_ref();
+                assert!(
+                    self.fields.contains_key(name),
+                    "{}() names a field the schema does not declare: '{}'",
+                    method,
+                    name
+                );
+                name.to_string()
+            })
+            .collect()
+    }
+
+    /// Returns a copy of the schema with the fields for which `keep` is
+    /// true, and the rules about them.
+    fn retain_fields(&self, keep: impl Fn(&str) -> bool) -> Self {
+        let mut schema = self.clone();
+        schema.fields.retain(|name, _| keep(name));
+        schema.deprecated.retain(|name, _| keep(name));
+        schema.dependent_required.retain(|trigger, dependents| {
+            dependents.retain(|dependent| keep(dependent));
+            keep(trigger) && !dependents.is_empty()
+        });
+        schema
+            .dependent_schemas
+            .retain(|dependent| keep(&dependent.trigger));
+        schema.cross_field_validators.clear();
+        schema
+    }
+
     /// Validates a value against this schema.
     ///
     /// Returns `Validation::Success` with a `Map<String, Value>` containing
@@ -622,6 +2282,259 @@ impl ObjectSchema {
         &self,
         value: &Value,
         path: &JsonPath,
+    ) -> Validation<Map<String, Value>, SchemaErrors> {
+        self.finish(self.validate_fields(value, path), value, path)
+    }
+
+    /// Maps a validated object into a typed view of its declared fields.
+    ///
+    /// Only fields declared on this schema are kept, under their output
+    /// names; undeclared properties that the schema allowed are left out,
+    /// so `T` can deny unknown fields. Deserialization errors are reported
+    /// like validation errors. Use [`undeclared_fields`](Self::undeclared_fields)
+    /// in a test to check that `T` only asks for declared fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{KeyCase, Schema, JsonPath};
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Signup {
+    ///     user_name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// let schema = Schema::object()
+    ///     .field("userName", Schema::string().min_len(1))
+    ///     .field("age", Schema::integer().min(13))
+    ///     .rename_keys(KeyCase::SnakeCase);
+    ///
+    /// let payload = json!({"userName": "ada", "age": 36, "utm_source": "ad"});
+    /// let signup: Signup = schema
+    ///     .validate_into(&payload, &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(signup.user_name, "ada");
+    /// assert!(schema.undeclared_fields::<Signup>().is_empty());
+    /// ```
+    pub fn project<T: DeserializeOwned>(
+        &self,
+        output: &Map<String, Value>,
+    ) -> Validation<T, SchemaErrors> {
+        let declared: Map<String, Value> = self
+            .fields
+            .keys()
+            .map(|name| self.output_key(name))
+            .filter_map(|key| output.get(&key).map(|value| (key, value.clone())))
+            .collect();
+        crate::error::deserialize(&Value::Object(declared))
+    }
+
+    /// Validates a value and [projects](Self::project) the output into `T`.
+    ///
+    /// Errors from deserializing into `T` are placed under `path`.
+    pub fn validate_into<T: DeserializeOwned>(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+    ) -> Validation<T, SchemaErrors> {
+        self.validate(value, path).and_then(|output| {
+            self.project(&output).map_err(|errors| {
+                let errors = errors
+                    .into_iter()
+                    .map(|mut error| {
+                        error.path = path.join(&error.path);
+                        error
+                    })
+                    .collect();
+                SchemaErrors::from_vec(errors)
+            })
+        })
+    }
+
+    /// Returns the fields `T` deserializes from that this schema does not
+    /// declare, under their output names.
+    ///
+    /// A struct used with [`project`](Self::project) should only read
+    /// declared fields; otherwise a field the schema never checked is
+    /// either missing or, for an `Option`, always `None`. Assert that this
+    /// is empty in a test, so the struct and schema cannot drift apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` does not deserialize as a struct, such as a map or a
+    /// struct with flattened fields.
+    pub fn undeclared_fields<T: DeserializeOwned>(&self) -> Vec<&'static str> {
+        let fields = super::projection::struct_fields::<T>()
+            .expect("undeclared_fields() takes a struct that derives Deserialize");
+        let declared: Vec<String> = self
+            .fields
+            .keys()
+            .map(|name| self.output_key(name))
+            .collect();
+        fields
+            .iter()
+            .copied()
+            .filter(|field| !declared.iter().any(|key| key == field))
+            .collect()
+    }
+
+    /// Returns whether `key` is a declared field name or alias, or a
+    /// deprecated field.
+    fn declares(&self, key: &str) -> bool {
+        self.fields.contains_key(key)
+            || self.deprecated.contains_key(key)
+            || self
+                .fields
+                .iter()
+                .any(|(name, field)| field.named(name, key, self.case_insensitive_fields))
+    }
+
+    /// Returns the value of `name` in `obj`, under its name or, for a
+    /// declared field, one of its aliases.
+    fn present<'a>(&self, obj: &'a Map<String, Value>, name: &str) -> Option<&'a Value> {
+        obj.get(name).or_else(|| {
+            let field = self.fields.get(name)?;
+            field
+                .aliases
+                .iter()
+                .find_map(|alias| obj.get(alias))
+                .or_else(|| {
+                    obj.iter()
+                        .find(|(key, _)| field.named(name, key, self.case_insensitive_fields))
+                        .map(|(_, value)| value)
+                })
+        })
+    }
+
+    /// Returns whether `name` is present in `obj`, under its name or, for a
+    /// declared field, one of its aliases.
+    fn is_present(&self, obj: &Map<String, Value>, name: &str) -> bool {
+        self.present(obj, name).is_some()
+    }
+
+    /// Returns the dependent schemas whose conditions hold for `obj`.
+    fn active_dependents(&self, obj: &Map<String, Value>) -> Vec<&ObjectSchema> {
+        self.dependent_schemas
+            .iter()
+            .filter(|dependent| {
+                self.present(obj, &dependent.trigger)
+                    .is_some_and(|value| dependent.condition.holds(value))
+            })
+            .map(|dependent| &dependent.schema)
+            .collect()
+    }
+
+    /// Adds the output of a dependent schema to `validated`, or its errors
+    /// to `errors`.
+    ///
+    /// Fields this schema declares keep the output of their own schema.
+    fn merge_dependent(
+        &self,
+        result: Validation<Map<String, Value>, SchemaErrors>,
+        validated: &mut Map<String, Value>,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        match result {
+            Validation::Success(output) => {
+                for (key, value) in output {
+                    if !self.declares(&key) {
+                        validated.entry(key).or_insert(value);
+                    }
+                }
+            }
+            Validation::Failure(e) => errors.extend(e),
+        }
+    }
+
+    /// Reports the missing dependents of triggers present in `obj`.
+    ///
+    /// Dependents that are declared as required fields are skipped, as
+    /// their absence is already reported.
+    fn check_dependent_required(
+        &self,
+        obj: &Map<String, Value>,
+        path: &JsonPath,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        for (trigger, dependents) in &self.dependent_required {
+            if !self.is_present(obj, trigger) {
+                continue;
+            }
+            for dependent in dependents {
+                let required = self.fields.get(dependent).is_some_and(|f| f.required);
+                if required || self.is_present(obj, dependent) {
+                    continue;
+                }
+                errors.push(
+                    SchemaError::new(
+                        path.push_field(dependent),
+                        format!(
+                            "field '{}' is required when '{}' is present",
+                            dependent, trigger
+                        ),
+                    )
+                    .with_code("dependent_required")
+                    .with_expected("value"),
+                );
+            }
+        }
+    }
+
+    /// Checks for deprecated fields in `obj`, passing undeclared ones
+    /// through to `validated`.
+    ///
+    /// Returns the deprecation errors, or adds them to `errors` if
+    /// deprecated fields are denied.
+    fn check_deprecated(
+        &self,
+        obj: &Map<String, Value>,
+        path: &JsonPath,
+        validated: &mut Map<String, Value>,
+        errors: &mut Vec<SchemaError>,
+    ) -> Vec<SchemaError> {
+        let mut deprecations = Vec::new();
+        for (name, message) in &self.deprecated {
+            let Some(value) = obj.get(name) else {
+                continue;
+            };
+            if !self.fields.contains_key(name) {
+                validated.insert(name.clone(), value.clone());
+            }
+            deprecations.push(
+                SchemaError::new(
+                    path.push_field(name),
+                    format!("field '{}' is deprecated: {}", name, message),
+                )
+                .with_code("deprecated"),
+            );
+        }
+        if self.deny_deprecated {
+            errors.append(&mut deprecations);
+        }
+        deprecations
+    }
+
+    /// Returns whether the value of a field counts as absent, per
+    /// [`null_as_absent`](Self::null_as_absent).
+    fn counts_as_absent(&self, field_def: &FieldDef, required: bool, value: &Value) -> bool {
+        self.null_as_absent
+            && !required
+            && value.is_null()
+            && field_def
+                .schema
+                .validate_value(value, &JsonPath::root())
+                .is_failure()
+    }
+
+    fn validate_fields(
+        &self,
+        value: &Value,
+        path: &JsonPath,
     ) -> Validation<Map<String, Value>, SchemaErrors> {
         // Check if it's an object
         let obj = match value.as_object() {
@@ -631,12 +2544,15 @@ impl ObjectSchema {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected object".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("object"),
-                ));
+                let error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("object");
+                let error = match self.type_error_message {
+                    Some(_) => render_message(error, &[]),
+                    None => error,
+                };
+                return Validation::Failure(SchemaErrors::single(error));
             }
         };
 
@@ -645,9 +2561,19 @@ impl ObjectSchema {
 
         // Validate defined fields
         for (name, field_def) in &self.fields {
-            let field_path = path.push_field(name);
+            let found = match field_def.find(name, obj, self.case_insensitive_fields) {
+                Ok(found) => found,
+                Err(keys) => {
+                    errors.push(alias_conflict(name, &keys, path));
+                    continue;
+                }
+            };
+            let field_path = path.push_field(found.map_or(name.as_str(), |(key, _)| key));
+            let present = found
+                .map(|(_, value)| value)
+                .filter(|value| !self.counts_as_absent(field_def, field_def.required, value));
 
-            match obj.get(name) {
+            match present {
                 Some(field_value) => {
                     match field_def.schema.validate_value(field_value, &field_path) {
                         Validation::Success(v) => {
@@ -659,28 +2585,63 @@ impl ObjectSchema {
                     }
                 }
                 None if field_def.required => {
-                    errors.push(
-                        SchemaError::new(
-                            field_path,
-                            format!("required field '{}' is missing", name),
-                        )
-                        .with_code("required")
-                        .with_expected("value"),
-                    );
+                    errors.push(field_def.missing(name, field_path));
                 }
                 None => {
                     // Optional field - use default if provided
-                    if let Some(default) = &field_def.default {
-                        validated.insert(name.clone(), default.clone());
+                    match field_def.default_value(&field_path, None) {
+                        Some(Validation::Success(default)) => {
+                            validated.insert(name.clone(), default);
+                        }
+                        Some(Validation::Failure(e)) => errors.extend(e.into_iter()),
+                        None => {}
                     }
                 }
             }
         }
 
-        // Handle additional properties
+        let dependents = self.active_dependents(obj);
+        for dependent in &dependents {
+            let result = dependent.validate(value, path);
+            self.merge_dependent(result, &mut validated, &mut errors);
+        }
+
+        self.check_dependent_required(obj, path, &mut errors);
+
+        // Deprecated fields are only warnings without a context to record them
+        self.check_deprecated(obj, path, &mut validated, &mut errors);
+
+        // Handle pattern and additional properties
+        let mut extra = 0;
         for (key, value) in obj {
-            if !self.fields.contains_key(key) {
+            if !self.declares(key) && !dependents.iter().any(|d| d.declares(key)) {
+                if let Some(error) = self.past_property_limit(&mut extra, path) {
+                    errors.push(error);
+                    break;
+                }
                 let field_path = path.push_field(key);
+                let mut matching = self
+                    .pattern_properties
+                    .iter()
+                    .filter(|(regex, _)| regex.is_match(key))
+                    .peekable();
+                if matching.peek().is_some() {
+                    let mut output = None;
+                    for (_, schema) in matching {
+                        match schema.validate_value(value, &field_path) {
+                            Validation::Success(v) => {
+                                output.get_or_insert(v);
+                            }
+                            Validation::Failure(e) => {
+                                errors.extend(e.into_iter());
+                            }
+                        }
+                    }
+                    if let Some(v) = output {
+                        validated.insert(key.clone(), v);
+                    }
+                    continue;
+                }
                 match &self.additional_properties {
                     AdditionalProperties::Allow => {
                         // Allow and include in output
@@ -688,8 +2649,12 @@ impl ObjectSchema {
                     }
                     AdditionalProperties::Deny => {
                         errors.push(
-                            SchemaError::new(field_path, format!("unknown field '{}'", key))
-                                .with_code("additional_property"),
+                            SchemaError::new(
+                                field_path.clone(),
+                                format!("unknown field '{}'", key),
+                            )
+                            .with_code("additional_property")
+                            .with_suggestion(Repair::remove(RepairKind::RemoveUnknown, field_path)),
                         );
                     }
                     AdditionalProperties::Validate(schema) => {
@@ -707,12 +2672,16 @@ impl ObjectSchema {
         }
 
         // Run cross-field validation if configured
-        if !self.skip_on_field_errors || errors.is_empty() {
+        if !self.cross_field_validators.is_empty()
+            && (!self.skip_on_field_errors || errors.is_empty())
+        {
             let validated_obj = ValidatedObject {
                 fields: validated
                     .iter()
                     .map(|(k, v)| (k.clone(), v.clone()))
                     .collect(),
+                raw: obj.clone(),
+                path: path.clone(),
             };
 
             for validator in &self.cross_field_validators {
@@ -728,31 +2697,13 @@ impl ObjectSchema {
             Validation::Failure(SchemaErrors::from_vec(errors))
         }
     }
-}
-
-impl Default for ObjectSchema {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl SchemaLike for ObjectSchema {
-    type Output = Map<String, Value>;
-
-    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Self::Output, SchemaErrors> {
-        self.validate(value, path)
-    }
-
-    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
-        self.validate(value, path).map(Value::Object)
-    }
 
-    fn validate_with_context(
+    fn validate_fields_with_context(
         &self,
         value: &Value,
         path: &JsonPath,
         context: &crate::validation::ValidationContext,
-    ) -> Validation<Self::Output, SchemaErrors> {
+    ) -> Validation<Map<String, Value>, SchemaErrors> {
         // Check if it's an object
         let obj = match value.as_object() {
             Some(o) => o,
@@ -761,12 +2712,15 @@ impl SchemaLike for ObjectSchema {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected object".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("object"),
-                ));
+                let error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("object");
+                let error = match self.type_error_message {
+                    Some(_) => render_message(error, &[]),
+                    None => error,
+                };
+                return Validation::Failure(SchemaErrors::single(error));
             }
         };
 
@@ -775,9 +2729,33 @@ impl SchemaLike for ObjectSchema {
 
         // Validate defined fields using context
         for (name, field_def) in &self.fields {
-            let field_path = path.push_field(name);
+            let found = match field_def.find(name, obj, self.case_insensitive_fields) {
+                Ok(found) => found,
+                Err(keys) => {
+                    errors.push(alias_conflict(name, &keys, path));
+                    continue;
+                }
+            };
+            let field_path = path.push_field(found.map_or(name.as_str(), |(key, _)| key));
 
-            match obj.get(name) {
+            match (context.mode(), field_def.access) {
+                (Some(ValidationMode::Create), FieldAccess::ReadOnly) => {
+                    if found.is_some() {
+                        errors.push(read_only_set(name, field_path));
+                    }
+                    continue;
+                }
+                (Some(ValidationMode::Read), FieldAccess::WriteOnly) => continue,
+                _ => {}
+            }
+            let required = field_def.required
+                || (context.mode() == Some(ValidationMode::Update)
+                    && field_def.access == FieldAccess::ReadOnly);
+            let present = found
+                .map(|(_, value)| value)
+                .filter(|value| !self.counts_as_absent(field_def, required, value));
+
+            match present {
                 Some(field_value) => {
                     match field_def.schema.validate_value_with_context(
                         field_value,
@@ -792,29 +2770,67 @@ impl SchemaLike for ObjectSchema {
                         }
                     }
                 }
-                None if field_def.required => {
-                    errors.push(
-                        SchemaError::new(
-                            field_path,
-                            format!("required field '{}' is missing", name),
-                        )
-                        .with_code("required")
-                        .with_expected("value"),
-                    );
+                None if required => {
+                    errors.push(field_def.missing(name, field_path));
                 }
                 None => {
                     // Optional field - use default if provided
-                    if let Some(default) = &field_def.default {
-                        validated.insert(name.clone(), default.clone());
+                    match field_def.default_value(&field_path, Some(context)) {
+                        Some(Validation::Success(default)) => {
+                            validated.insert(name.clone(), default);
+                        }
+                        Some(Validation::Failure(e)) => errors.extend(e.into_iter()),
+                        None => {}
                     }
                 }
             }
         }
 
-        // Handle additional properties
+        let dependents = self.active_dependents(obj);
+        for dependent in &dependents {
+            let result = dependent.validate_with_context(value, path, context);
+            self.merge_dependent(result, &mut validated, &mut errors);
+        }
+
+        self.check_dependent_required(obj, path, &mut errors);
+
+        let deprecations = self.check_deprecated(obj, path, &mut validated, &mut errors);
+        context.warn(deprecations.into_iter().map(|error| SuppressedError {
+            error,
+            reason: "deprecated fields are still accepted".to_string(),
+        }));
+
+        // Handle pattern and additional properties
+        let mut extra = 0;
         for (key, value) in obj {
-            if !self.fields.contains_key(key) {
+            if !self.declares(key) && !dependents.iter().any(|d| d.declares(key)) {
+                if let Some(error) = self.past_property_limit(&mut extra, path) {
+                    errors.push(error);
+                    break;
+                }
                 let field_path = path.push_field(key);
+                let mut matching = self
+                    .pattern_properties
+                    .iter()
+                    .filter(|(regex, _)| regex.is_match(key))
+                    .peekable();
+                if matching.peek().is_some() {
+                    let mut output = None;
+                    for (_, schema) in matching {
+                        match schema.validate_value_with_context(value, &field_path, context) {
+                            Validation::Success(v) => {
+                                output.get_or_insert(v);
+                            }
+                            Validation::Failure(e) => {
+                                errors.extend(e.into_iter());
+                            }
+                        }
+                    }
+                    if let Some(v) = output {
+                        validated.insert(key.clone(), v);
+                    }
+                    continue;
+                }
                 match &self.additional_properties {
                     AdditionalProperties::Allow => {
                         // Allow and include in output
@@ -822,8 +2838,12 @@ impl SchemaLike for ObjectSchema {
                     }
                     AdditionalProperties::Deny => {
                         errors.push(
-                            SchemaError::new(field_path, format!("unknown field '{}'", key))
-                                .with_code("additional_property"),
+                            SchemaError::new(
+                                field_path.clone(),
+                                format!("unknown field '{}'", key),
+                            )
+                            .with_code("additional_property")
+                            .with_suggestion(Repair::remove(RepairKind::RemoveUnknown, field_path)),
                         );
                     }
                     AdditionalProperties::Validate(schema) => {
@@ -841,12 +2861,16 @@ impl SchemaLike for ObjectSchema {
         }
 
         // Run cross-field validation if configured
-        if !self.skip_on_field_errors || errors.is_empty() {
+        if !self.cross_field_validators.is_empty()
+            && (!self.skip_on_field_errors || errors.is_empty())
+        {
             let validated_obj = ValidatedObject {
                 fields: validated
                     .iter()
                     .map(|(k, v)| (k.clone(), v.clone()))
                     .collect(),
+                raw: obj.clone(),
+                path: path.clone(),
             };
 
             for validator in &self.cross_field_validators {
@@ -862,6 +2886,34 @@ impl SchemaLike for ObjectSchema {
             Validation::Failure(SchemaErrors::from_vec(errors))
         }
     }
+}
+
+impl Default for ObjectSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaLike for ObjectSchema {
+    type Output = Map<String, Value>;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Self::Output, SchemaErrors> {
+        self.validate(value, path)
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path).map(Value::Object)
+    }
+
+    fn validate_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &crate::validation::ValidationContext,
+    ) -> Validation<Self::Output, SchemaErrors> {
+        let result = self.validate_fields_with_context(value, path, context);
+        self.finish(result, value, path)
+    }
 
     fn validate_to_value_with_context(
         &self,
@@ -879,10 +2931,28 @@ impl SchemaLike for ObjectSchema {
             field_def.schema.collect_refs(refs);
         }
 
+        for (_, schema) in &self.pattern_properties {
+            schema.collect_refs(refs);
+        }
+
         // Collect refs from additional properties schema if present
         if let AdditionalProperties::Validate(schema) = &self.additional_properties {
             schema.collect_refs(refs);
         }
+
+        for dependent in &self.dependent_schemas {
+            dependent.schema.collect_refs(refs);
+        }
+    }
+
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        let (name, rest) = path.split_first()?;
+        let field = self.fields.get(*name)?;
+        if rest.is_empty() {
+            field.doc.clone()
+        } else {
+            field.schema.field_doc(rest)
+        }
     }
 }
 
@@ -924,6 +2994,10 @@ impl<S: SchemaLike + ToJsonSchema> SchemaLike for SchemaWrapper<S> {
     fn collect_refs(&self, refs: &mut Vec<String>) {
         self.0.collect_refs(refs);
     }
+
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        self.0.field_doc(path)
+    }
 }
 
 impl<S: SchemaLike + ToJsonSchema> ToJsonSchema for SchemaWrapper<S> {
@@ -932,6 +3006,65 @@ impl<S: SchemaLike + ToJsonSchema> ToJsonSchema for SchemaWrapper<S> {
     }
 }
 
+/// The condition under which a
+/// [`dependent_schema`](ObjectSchema::dependent_schema) applies, tested on
+/// the value of its trigger field.
+///
+/// Converts from a value the trigger must equal, e.g. `"card"`, or from a
+/// predicate on the trigger's value; [`present`](Self::present) holds
+/// whenever the trigger is present.
+#[derive(Clone)]
+pub struct DependentCondition(Condition);
+
+#[derive(Clone)]
+enum Condition {
+    Present,
+    Equals(Value),
+    Matches(Arc<dyn Fn(&Value) -> bool + Send + Sync>),
+}
+
+impl DependentCondition {
+    /// Holds whenever the trigger field is present, whatever its value.
+    pub fn present() -> Self {
+        DependentCondition(Condition::Present)
+    }
+
+    fn holds(&self, value: &Value) -> bool {
+        match &self.0 {
+            Condition::Present => true,
+            Condition::Equals(expected) => value == expected,
+            Condition::Matches(predicate) => predicate(value),
+        }
+    }
+}
+
+impl From<Value> for DependentCondition {
+    fn from(value: Value) -> Self {
+        DependentCondition(Condition::Equals(value))
+    }
+}
+
+impl From<&str> for DependentCondition {
+    fn from(value: &str) -> Self {
+        DependentCondition(Condition::Equals(Value::from(value)))
+    }
+}
+
+impl From<String> for DependentCondition {
+    fn from(value: String) -> Self {
+        DependentCondition(Condition::Equals(Value::from(value)))
+    }
+}
+
+impl<F> From<F> for DependentCondition
+where
+    F: Fn(&Value) -> bool + Send + Sync + 'static,
+{
+    fn from(predicate: F) -> Self {
+        DependentCondition(Condition::Matches(Arc::new(predicate)))
+    }
+}
+
 /// A type that can be converted into an `AdditionalProperties` setting.
 ///
 /// This allows `additional_properties()` to accept different types:
@@ -951,36 +3084,42 @@ impl From<bool> for AdditionalPropertiesSetting {
 
 impl<S: SchemaLike + ToJsonSchema + 'static> From<S> for AdditionalPropertiesSetting {
     fn from(schema: S) -> Self {
-        AdditionalPropertiesSetting(AdditionalProperties::Validate(Box::new(SchemaWrapper(
+        AdditionalPropertiesSetting(AdditionalProperties::Validate(Arc::new(SchemaWrapper(
             schema,
         ))))
     }
 }
 
-/// Returns the JSON type name for a value.
-fn value_type_name(value: &Value) -> &'static str {
-    match value {
-        Value::Null => "null",
-        Value::Bool(_) => "boolean",
-        Value::Number(_) => "number",
-        Value::String(_) => "string",
-        Value::Array(_) => "array",
-        Value::Object(_) => "object",
-    }
-}
-
 impl ToJsonSchema for ObjectSchema {
     fn to_json_schema(&self) -> Value {
         let mut properties = serde_json::Map::new();
         let mut required = Vec::new();
 
         for (name, field_def) in &self.fields {
-            properties.insert(name.clone(), field_def.schema.to_json_schema());
+            let mut property = field_def.schema.to_json_schema();
+            if let Some(description) = field_def.doc.as_ref().and_then(|d| d.description.as_ref()) {
+                property["description"] = json!(description);
+            }
+            if self.deprecated.contains_key(name) {
+                property["deprecated"] = json!(true);
+            }
+            match field_def.access {
+                FieldAccess::ReadOnly => property["readOnly"] = json!(true),
+                FieldAccess::WriteOnly => property["writeOnly"] = json!(true),
+                FieldAccess::ReadWrite => {}
+            }
+            properties.insert(name.clone(), property);
             if field_def.required {
                 required.push(name.clone());
             }
         }
 
+        for name in self.deprecated.keys() {
+            if !self.fields.contains_key(name) {
+                properties.insert(name.clone(), json!({"deprecated": true}));
+            }
+        }
+
         let mut schema = json!({
             "type": "object",
             "properties": properties,
@@ -990,6 +3129,50 @@ impl ToJsonSchema for ObjectSchema {
             schema["required"] = json!(required);
         }
 
+        if !self.dependent_required.is_empty() {
+            let dependencies: Map<String, Value> = self
+                .dependent_required
+                .iter()
+                .map(|(trigger, dependents)| (trigger.clone(), json!(dependents)))
+                .collect();
+            schema["dependentRequired"] = Value::Object(dependencies);
+        }
+
+        let mut present = Map::new();
+        let mut conditional = Vec::new();
+        for dependent in &self.dependent_schemas {
+            let then = dependent.schema.to_json_schema();
+            match &dependent.condition.0 {
+                Condition::Present => {
+                    present.insert(dependent.trigger.clone(), then);
+                }
+                Condition::Equals(value) => conditional.push(json!({
+                    "if": {
+                        "properties": {dependent.trigger.as_str(): {"const": value}},
+                        "required": [dependent.trigger],
+                    },
+                    "then": then,
+                })),
+                // A predicate has no JSON Schema equivalent
+                Condition::Matches(_) => {}
+            }
+        }
+        if !present.is_empty() {
+            schema["dependentSchemas"] = Value::Object(present);
+        }
+        if !conditional.is_empty() {
+            schema["allOf"] = Value::Array(conditional);
+        }
+
+        if !self.pattern_properties.is_empty() {
+            let patterns: Map<String, Value> = self
+                .pattern_properties
+                .iter()
+                .map(|(regex, schema)| (regex.as_str().to_string(), schema.to_json_schema()))
+                .collect();
+            schema["patternProperties"] = Value::Object(patterns);
+        }
+
         match &self.additional_properties {
             AdditionalProperties::Deny => {
                 schema["additionalProperties"] = json!(false);
@@ -999,6 +3182,11 @@ impl ToJsonSchema for ObjectSchema {
                 schema["additionalProperties"] = s.to_json_schema();
             }
         }
+        // JSON Schema can only limit all properties, so record the limit as
+        // an extension
+        if let Some(max) = self.max_additional_properties {
+            schema["x-maxAdditionalProperties"] = json!(max);
+        }
 
         schema
     }
@@ -1101,6 +3289,34 @@ mod tests {
         assert_eq!(errors.first().code, "invalid_type");
     }
 
+    #[test]
+    fn test_null_for_optional_field() {
+        let schema = ObjectSchema::new()
+            .field("id", IntegerSchema::new())
+            .optional("nickname", StringSchema::new())
+            .optional("manager", crate::SchemaExt::nullable(StringSchema::new()));
+        let value = json!({"id": 1, "nickname": null, "manager": null});
+
+        // Null is a value like any other by default
+        let errors = unwrap_failure(schema.validate(&value, &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().path.to_string(), "nickname");
+
+        let schema = schema.null_as_absent(true);
+        let obj = unwrap_success(schema.validate(&value, &JsonPath::root()));
+        assert!(obj.get("nickname").is_none());
+        assert_eq!(obj.get("manager"), Some(&Value::Null));
+
+        let context = crate::SchemaRegistry::new().context();
+        assert!(schema
+            .validate_with_context(&value, &JsonPath::root(), &context)
+            .is_success());
+
+        // Required fields still reject null
+        let result = schema.validate(&json!({"id": null}), &JsonPath::root());
+        assert_eq!(unwrap_failure(result).first().code, "invalid_type");
+    }
+
     #[test]
     fn test_default_field() {
         let schema = ObjectSchema::new().default("role", StringSchema::new(), json!("user"));
@@ -1118,6 +3334,79 @@ mod tests {
         assert_eq!(obj.get("role"), Some(&json!("admin")));
     }
 
+    #[test]
+    #[should_panic(expected = "default for 'role' does not match its schema")]
+    fn test_default_must_match_schema() {
+        let _ = ObjectSchema::new().default("role", StringSchema::new(), json!(123));
+    }
+
+    #[test]
+    fn test_defaults_checked_when_applied() {
+        // A computed default is checked on every validation
+        let schema =
+            ObjectSchema::new().default_with("count", IntegerSchema::new(), || json!("three"));
+        let errors = unwrap_failure(schema.validate(&json!({}), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_default");
+        assert_eq!(errors.first().path.to_string(), "count");
+        assert!(errors
+            .first()
+            .message
+            .starts_with("default value is invalid: "));
+
+        // A reference is checked against the registry when applied
+        let registry = crate::SchemaRegistry::new();
+        registry
+            .register("Role", StringSchema::new().min_len(3))
+            .unwrap();
+        registry
+            .register(
+                "User",
+                ObjectSchema::new()
+                    .default("role", crate::Schema::ref_("Role"), json!("admin"))
+                    .default("backup_role", crate::Schema::ref_("Role"), json!("x")),
+            )
+            .unwrap();
+        let errors = registry
+            .validate("User", &json!({"role": "owner"}))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_default");
+        assert_eq!(errors.first().path.to_string(), "backup_role");
+    }
+
+    #[test]
+    fn test_default_with_is_computed_per_validation() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let schema = ObjectSchema::new().default_with("seq", IntegerSchema::new(), move || {
+            json!(counter.fetch_add(1, Ordering::SeqCst))
+        });
+
+        let first = unwrap_success(schema.validate(&json!({}), &JsonPath::root()));
+        let second = unwrap_success(schema.validate(&json!({}), &JsonPath::root()));
+        assert_eq!(first.get("seq"), Some(&json!(0)));
+        assert_eq!(second.get("seq"), Some(&json!(1)));
+
+        // Not computed when the field is given
+        let given = unwrap_success(schema.validate(&json!({"seq": 9}), &JsonPath::root()));
+        assert_eq!(given.get("seq"), Some(&json!(9)));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Also computed when validating through a registry
+        let registry = crate::SchemaRegistry::new();
+        registry.register("Counter", schema).unwrap();
+        let value = registry
+            .validate("Counter", &json!({}))
+            .unwrap()
+            .into_result()
+            .unwrap();
+        assert_eq!(value["seq"], 2);
+    }
+
     #[test]
     fn test_additional_properties_allow() {
         let schema = ObjectSchema::new()
@@ -1147,6 +3436,46 @@ mod tests {
         let errors = unwrap_failure(result);
         assert_eq!(errors.first().code, "additional_property");
         assert!(errors.first().message.contains("extra"));
+        assert_eq!(
+            errors.first().suggestion.as_ref().unwrap().to_json_patch(),
+            json!({"op": "remove", "path": "/extra"})
+        );
+    }
+
+    #[test]
+    fn test_max_additional_properties() {
+        let schema = ObjectSchema::new()
+            .field("name", StringSchema::new())
+            .additional_properties(IntegerSchema::new())
+            .max_additional_properties(2);
+
+        // Declared fields do not count
+        let value = json!({"name": "Alice", "a": 1, "b": 2});
+        let output = unwrap_success(schema.validate(&value, &JsonPath::root()));
+        assert_eq!(Value::Object(output), value);
+
+        let value = json!({"name": "Alice", "a": 1, "b": "x", "c": "y", "d": "z"});
+        let errors = unwrap_failure(schema.validate(&value, &JsonPath::root()));
+        let codes: Vec<_> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["invalid_type", "too_many_properties"]);
+        assert_eq!(
+            errors.iter().nth(1).unwrap().message,
+            "more than 2 properties beyond the declared fields"
+        );
+
+        // The limit also caps unknown-field errors
+        let strict = ObjectSchema::new()
+            .additional_properties(false)
+            .max_additional_properties(1);
+        let context = crate::SchemaRegistry::new().context();
+        let errors = unwrap_failure(strict.validate_with_context(
+            &json!({"a": 1, "b": 2, "c": 3}),
+            &JsonPath::root(),
+            &context,
+        ));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.iter().nth(1).unwrap().code, "too_many_properties");
+        assert_eq!(strict.to_json_schema()["x-maxAdditionalProperties"], 1);
     }
 
     #[test]
@@ -1169,6 +3498,343 @@ mod tests {
         assert_eq!(errors.first().code, "invalid_type");
     }
 
+    #[test]
+    fn test_pattern_properties() {
+        let schema = ObjectSchema::new()
+            .field("name", StringSchema::new())
+            .pattern_property("^x-", StringSchema::new().trim())
+            .unwrap()
+            .pattern_property(r"^\d+$", IntegerSchema::new())
+            .unwrap()
+            .additional_properties(false);
+
+        let output = unwrap_success(schema.validate(
+            &json!({"name": "Alice", "x-team": " core ", "7": 3}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(output["x-team"], json!("core"));
+        assert_eq!(output["7"], json!(3));
+
+        // Declared fields are not checked against patterns
+        let schema = schema
+            .pattern_property("^na", IntegerSchema::new())
+            .unwrap();
+        let errors = unwrap_failure(schema.validate(
+            &json!({"name": "Alice", "x-team": 1, "12": "twelve", "extra": true}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors.at_path(&JsonPath::root().push_field("x-team")).len(),
+            1
+        );
+        assert_eq!(errors.at_path(&JsonPath::root().push_field("12")).len(), 1);
+        assert_eq!(errors.with_code("additional_property").len(), 1);
+
+        assert_eq!(
+            schema.to_json_schema()["patternProperties"],
+            json!({
+                "^x-": {"type": "string"},
+                "^\\d+$": {"type": "integer"},
+                "^na": {"type": "integer"}
+            })
+        );
+        assert!(ObjectSchema::new()
+            .pattern_property("(", StringSchema::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_field_aliases() {
+        let schema = ObjectSchema::new()
+            .field_with_aliases("user_name", ["userName", "username"], StringSchema::new())
+            .additional_properties(false);
+
+        for input in [
+            json!({"user_name": "ada"}),
+            json!({"userName": "ada"}),
+            json!({"username": "ada"}),
+        ] {
+            let output = unwrap_success(schema.validate(&input, &JsonPath::root()));
+            assert_eq!(Value::Object(output), json!({"user_name": "ada"}));
+        }
+
+        // Errors point at the key the value was sent under
+        let errors = unwrap_failure(schema.validate(&json!({"userName": 1}), &JsonPath::root()));
+        assert_eq!(errors.first().path.to_string(), "userName");
+        assert_eq!(errors.first().code, "invalid_type");
+
+        let errors = unwrap_failure(schema.validate(
+            &json!({"user_name": "ada", "userName": "ada"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "alias_conflict");
+        assert_eq!(errors.first().path.to_string(), "user_name");
+        assert_eq!(
+            errors.first().message,
+            "field 'user_name' is given more than once, as 'user_name' and 'userName'"
+        );
+
+        let errors = unwrap_failure(schema.validate(&json!({}), &JsonPath::root()));
+        assert_eq!(errors.first().code, "required");
+    }
+
+    #[test]
+    fn test_case_insensitive_fields() {
+        let schema = ObjectSchema::new()
+            .field("contentType", StringSchema::new())
+            .field_with_aliases("userId", ["user_id"], IntegerSchema::new())
+            .optional("Straße", StringSchema::new())
+            .dependent_required("contentType", ["userId"])
+            .additional_properties(false)
+            .case_insensitive_fields(true);
+
+        // `STRASSE` does not lowercase to `straße`, so it stays unknown
+        let errors = unwrap_failure(schema.validate(
+            &json!({"contentType": "text/plain", "userId": 7, "STRASSE": "x"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.first().code, "additional_property");
+        assert_eq!(errors.first().path.to_string(), "STRASSE");
+
+        let output = unwrap_success(schema.validate(
+            &json!({"CONTENTTYPE": "text/plain", "User_ID": 7, "STRAßE": "Hauptstraße"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(
+            Value::Object(output),
+            json!({"contentType": "text/plain", "userId": 7, "Straße": "Hauptstraße"})
+        );
+
+        let errors = unwrap_failure(schema.validate(
+            &json!({"ContentType": 1, "contentType": "text/plain", "userid": 7}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "alias_conflict");
+        assert_eq!(
+            errors.first().message,
+            "field 'contentType' is given more than once, as 'ContentType' and 'contentType'"
+        );
+
+        // Errors point at the input key
+        let errors = unwrap_failure(
+            schema.validate(&json!({"ContentType": 1, "USERID": 7}), &JsonPath::root()),
+        );
+        assert_eq!(errors.first().path.to_string(), "ContentType");
+
+        // Case matters by default
+        let strict = schema.case_insensitive_fields(false);
+        let errors = unwrap_failure(
+            strict.validate(&json!({"ContentType": "text/plain"}), &JsonPath::root()),
+        );
+        assert_eq!(errors.with_code("additional_property").len(), 1);
+    }
+
+    #[test]
+    fn test_rename_keys() {
+        let schema = ObjectSchema::new()
+            .field("firstName", StringSchema::new())
+            .optional("startDate", IntegerSchema::new())
+            .optional("endDate", IntegerSchema::new())
+            .default("isActive", IntegerSchema::new(), json!(1))
+            .field("e-mail", StringSchema::new())
+            .output_name("email")
+            .field_less_than("startDate", "endDate")
+            .rename_keys(KeyCase::SnakeCase);
+
+        let output = unwrap_success(schema.validate(
+            &json!({"firstName": "Ada", "e-mail": "ada@example.com", "startDate": 1, "endDate": 2, "extraInfo": 0}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(
+            Value::Object(output),
+            json!({
+                "first_name": "Ada",
+                "start_date": 1,
+                "end_date": 2,
+                "is_active": 1,
+                "email": "ada@example.com",
+                "extra_info": 0
+            })
+        );
+
+        // Errors and cross-field rules use the input names
+        let errors = unwrap_failure(schema.validate(
+            &json!({"firstName": 1, "e-mail": "a", "startDate": 3, "endDate": 2}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.first().path.to_string(), "firstName");
+        let errors = unwrap_failure(schema.validate(
+            &json!({"firstName": "Ada", "e-mail": "a", "startDate": 3, "endDate": 2}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.len(), 1);
+
+        let errors = unwrap_failure(schema.validate(
+            &json!({"firstName": "Ada", "e-mail": "a", "first_name": "Ada"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.first().code, "key_conflict");
+        assert_eq!(
+            errors.first().message,
+            "keys 'firstName' and 'first_name' would both be renamed to 'first_name'"
+        );
+    }
+
+    #[test]
+    fn test_deprecated_fields() {
+        let schema = ObjectSchema::new()
+            .field("id", IntegerSchema::new())
+            .deprecated_field("legacy_id", "use 'id' instead")
+            .optional("nickname", StringSchema::new())
+            .deprecated_field("nickname", "use 'display_name' instead")
+            .additional_properties(false);
+
+        // Without a context, deprecations are not errors and values pass through
+        let output = unwrap_success(schema.validate(
+            &json!({"id": 1, "legacy_id": "u-1", "nickname": "ada"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(output["legacy_id"], json!("u-1"));
+        assert_eq!(output["nickname"], json!("ada"));
+
+        // Deprecated declared fields are still validated
+        let errors =
+            unwrap_failure(schema.validate(&json!({"id": 1, "nickname": 5}), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_type");
+
+        let exported = schema.to_json_schema();
+        assert_eq!(
+            exported["properties"]["legacy_id"],
+            json!({"deprecated": true})
+        );
+        assert_eq!(
+            exported["properties"]["nickname"]["deprecated"],
+            json!(true)
+        );
+
+        let schema = schema.deny_deprecated(true);
+        let errors = unwrap_failure(schema.validate(
+            &json!({"id": 1, "legacy_id": "u-1", "nickname": "ada"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.with_code("deprecated").len(), 2);
+        assert_eq!(
+            errors.at_path(&JsonPath::root().push_field("nickname"))[0].message,
+            "field 'nickname' is deprecated: use 'display_name' instead"
+        );
+    }
+
+    #[test]
+    fn test_validation_modes() {
+        use crate::validation::ValidationMode;
+        use crate::SchemaRegistry;
+
+        let schema = ObjectSchema::new()
+            .optional("id", IntegerSchema::new())
+            .read_only()
+            .field("name", StringSchema::new())
+            .field("password_hash", StringSchema::new())
+            .write_only()
+            .additional_properties(false);
+        let registry = SchemaRegistry::new();
+        let validate = |value: Value, mode: Option<ValidationMode>| {
+            let context = match mode {
+                Some(mode) => registry.context().with_mode(mode),
+                None => registry.context(),
+            };
+            schema.validate_with_context(&value, &JsonPath::root(), &context)
+        };
+        let user = json!({"id": 7, "name": "Ada", "password_hash": "x1"});
+
+        // Without a mode, the markers change nothing
+        assert_eq!(
+            Value::Object(unwrap_success(validate(user.clone(), None))),
+            user
+        );
+        assert!(validate(json!({"name": "Ada"}), None).is_failure());
+
+        let errors = unwrap_failure(validate(user.clone(), Some(ValidationMode::Create)));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "read_only");
+        assert_eq!(errors.first().path.to_string(), "id");
+        assert_eq!(
+            errors.first().message,
+            "field 'id' is read-only and cannot be set"
+        );
+        let created = json!({"name": "Ada", "password_hash": "x1"});
+        assert!(validate(created.clone(), Some(ValidationMode::Create)).is_success());
+
+        let errors = unwrap_failure(validate(created, Some(ValidationMode::Update)));
+        assert_eq!(errors.first().code, "required");
+        assert_eq!(errors.first().path.to_string(), "id");
+        assert!(validate(user.clone(), Some(ValidationMode::Update)).is_success());
+
+        // Write-only fields are stripped on read, even when invalid
+        let read = json!({"id": 7, "name": "Ada", "password_hash": 5});
+        assert_eq!(
+            Value::Object(unwrap_success(validate(read, Some(ValidationMode::Read)))),
+            json!({"id": 7, "name": "Ada"})
+        );
+        assert!(validate(json!({"name": "Ada"}), Some(ValidationMode::Read)).is_success());
+
+        let exported = schema.to_json_schema();
+        assert_eq!(exported["properties"]["id"]["readOnly"], json!(true));
+        assert_eq!(
+            exported["properties"]["password_hash"]["writeOnly"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_projection() {
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        #[serde(deny_unknown_fields)]
+        struct Item {
+            sku: String,
+            quantity: u8,
+            note: Option<String>,
+        }
+
+        let schema = ObjectSchema::new()
+            .field("sku", StringSchema::new())
+            .field("qty", IntegerSchema::new())
+            .output_name("quantity")
+            .optional("note", StringSchema::new());
+        assert!(schema.undeclared_fields::<Item>().is_empty());
+
+        // Allowed extras are left out of the view
+        let item: Item = unwrap_success(schema.validate_into(
+            &json!({"sku": "A-1", "qty": 2, "color": "red"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(
+            item,
+            Item {
+                sku: "A-1".to_string(),
+                quantity: 2,
+                note: None
+            }
+        );
+
+        // Deserialization errors are reported under the given path
+        let errors = unwrap_failure(schema.validate_into::<Item>(
+            &json!({"sku": "A-1", "qty": 300}),
+            &JsonPath::from_field("items").push_index(0),
+        ));
+        assert_eq!(errors.first().path.to_string(), "items[0].quantity");
+
+        #[allow(dead_code)]
+        #[derive(serde::Deserialize)]
+        struct Drifted {
+            sku: String,
+            price: Option<u32>,
+        }
+        assert_eq!(schema.undeclared_fields::<Drifted>(), ["price"]);
+    }
+
     #[test]
     fn test_multiple_fields() {
         let schema = ObjectSchema::new()
@@ -1286,6 +3952,44 @@ mod tests {
         assert_eq!(errors.first().message, "must be a user object");
     }
 
+    #[test]
+    fn test_custom_required_error_message() {
+        let schema = ObjectSchema::new()
+            .field("name", StringSchema::new())
+            .field("email", StringSchema::new())
+            .required_error("name", "Please provide your full name")
+            .required_error("email", "{field} is needed at {path}");
+
+        let errors = unwrap_failure(schema.validate(
+            &json!({"profile": {}}),
+            &JsonPath::root().push_field("user"),
+        ));
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Please provide your full name",
+                "email is needed at user.email"
+            ]
+        );
+        assert!(errors.iter().all(|e| e.code == "required"));
+
+        // The message only replaces the required error
+        let errors = unwrap_failure(schema.validate(
+            &json!({"name": 5, "email": "ada@example.com"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.first().code, "invalid_type");
+    }
+
+    #[test]
+    #[should_panic(expected = "required_error() names undeclared field 'nmae'")]
+    fn test_required_error_for_undeclared_field_panics() {
+        ObjectSchema::new()
+            .field("name", StringSchema::new())
+            .required_error("nmae", "Please provide your full name");
+    }
+
     #[test]
     fn test_unicode_field_names() {
         let schema = ObjectSchema::new()
@@ -1341,4 +4045,90 @@ mod tests {
             _ => panic!("Expected object"),
         }
     }
+
+    #[test]
+    fn test_error_factory_sees_failed_values() {
+        let schema = ObjectSchema::new()
+            .field("tags", crate::Schema::array(StringSchema::new().min_len(2)))
+            .field("age", IntegerSchema::new().positive())
+            .with_error_factory(|info, value, path| {
+                SchemaError::new(path.clone(), format!("{}={}", info.code, value))
+                    .with_code("custom")
+            });
+
+        let value = json!({"outer": 1, "tags": ["ok", "x"], "age": -1});
+        let path = JsonPath::root().push_field("record");
+        let errors = schema.validate(&value, &path).into_result().unwrap_err();
+
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["min_length=\"x\"", "positive=-1"]);
+        assert!(errors.iter().all(|e| e.code == "custom"));
+        assert_eq!(errors.first().path.to_string(), "record.tags[1]");
+
+        // Missing fields have no value
+        let errors = schema
+            .validate(&json!({"tags": []}), &JsonPath::root())
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().message, "required=null");
+    }
+
+    fn user() -> ObjectSchema {
+        ObjectSchema::new()
+            .field_with_aliases("name", ["userName"], StringSchema::new().min_len(1))
+            .default("role", StringSchema::new(), json!("member"))
+            .optional("card", StringSchema::new())
+            .optional("billing", StringSchema::new())
+            .dependent_required("card", ["billing"])
+            .equal_fields("card", "billing")
+            .additional_properties(false)
+    }
+
+    #[test]
+    fn test_pick_and_omit() {
+        let picked = user().pick(["name", "card"]);
+        let output = unwrap_success(
+            picked.validate(&json!({"userName": "Ada", "card": "x"}), &JsonPath::root()),
+        );
+        // The alias is kept; the dependent and the cross-field rule about
+        // the removed field are dropped
+        assert_eq!(
+            output,
+            *json!({"name": "Ada", "card": "x"}).as_object().unwrap()
+        );
+        let errors = unwrap_failure(picked.validate(&json!({"role": "x"}), &JsonPath::root()));
+        assert_eq!(errors.with_code("additional_property").len(), 1);
+
+        let omitted = user().omit(["role"]);
+        let errors = unwrap_failure(
+            omitted.validate(&json!({"name": "Ada", "card": "x"}), &JsonPath::root()),
+        );
+        assert_eq!(errors.with_code("dependent_required").len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "pick() names a field the schema does not declare: 'email'")]
+    fn test_pick_unknown_field() {
+        user().pick(["email"]);
+    }
+
+    #[test]
+    fn test_partial() {
+        let patch = user().partial();
+        let output = unwrap_success(patch.validate(&json!({"card": "x"}), &JsonPath::root()));
+        assert_eq!(output, *json!({"card": "x"}).as_object().unwrap());
+
+        let errors = unwrap_failure(patch.validate(
+            &json!({"name": "", "card": "x", "billing": "y"}),
+            &JsonPath::root(),
+        ));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "min_length");
+
+        // Cross-field rules still run
+        let errors = unwrap_failure(
+            patch.validate(&json!({"card": "x", "billing": "y"}), &JsonPath::root()),
+        );
+        assert_eq!(errors.first().code, "fields_not_equal");
+    }
 }
@@ -0,0 +1,109 @@
+//! Operators for composing schemas.
+//!
+//! Every schema type implements `|` and `&`: `a | b` builds an
+//! [`any_of`](super::Schema::any_of) and `a & b` an
+//! [`all_of`](super::Schema::all_of). Chains such as `a | b | c` add to the
+//! combinator on the left rather than nesting it.
+
+use std::ops::{BitAnd, BitOr};
+use std::sync::Arc;
+
+use crate::interop::ToJsonSchema;
+
+use super::combinators::validator_fns;
+use super::{
+    ArraySchema, CombinatorSchema, IntegerSchema, LazySchema, ObjectSchema, RefSchema, Schema,
+    SchemaLike, ScopedSchema, StringSchema, ValueValidator,
+};
+
+macro_rules! impl_schema_ops {
+    ($($schema:ty),* $(,)?) => {$(
+        impl<R: ValueValidator + 'static> BitOr<R> for $schema {
+            type Output = CombinatorSchema;
+
+            fn bitor(self, rhs: R) -> CombinatorSchema {
+                Schema::any_of_builder().or(self).or(rhs).build()
+            }
+        }
+
+        impl<R: ValueValidator + 'static> BitAnd<R> for $schema {
+            type Output = CombinatorSchema;
+
+            fn bitand(self, rhs: R) -> CombinatorSchema {
+                Schema::all_of_builder().and(self).and(rhs).build()
+            }
+        }
+    )*};
+}
+
+impl_schema_ops!(
+    StringSchema,
+    IntegerSchema,
+    ObjectSchema,
+    RefSchema,
+    LazySchema,
+    ScopedSchema,
+);
+
+impl<S, R> BitOr<R> for ArraySchema<S>
+where
+    S: SchemaLike + ToJsonSchema + 'static,
+    R: ValueValidator + 'static,
+{
+    type Output = CombinatorSchema;
+
+    fn bitor(self, rhs: R) -> CombinatorSchema {
+        Schema::any_of_builder().or(self).or(rhs).build()
+    }
+}
+
+impl<S, R> BitAnd<R> for ArraySchema<S>
+where
+    S: SchemaLike + ToJsonSchema + 'static,
+    R: ValueValidator + 'static,
+{
+    type Output = CombinatorSchema;
+
+    fn bitand(self, rhs: R) -> CombinatorSchema {
+        Schema::all_of_builder().and(self).and(rhs).build()
+    }
+}
+
+impl<R: ValueValidator + 'static> BitOr<R> for CombinatorSchema {
+    type Output = CombinatorSchema;
+
+    fn bitor(self, rhs: R) -> CombinatorSchema {
+        match self {
+            CombinatorSchema::AnyOf {
+                mut validators,
+                branch_errors,
+                ..
+            } => {
+                validators.push(Arc::new(rhs));
+                CombinatorSchema::AnyOf {
+                    schemas: validator_fns(&validators),
+                    validators,
+                    branch_errors,
+                }
+            }
+            other => Schema::any_of_builder().or(other).or(rhs).build(),
+        }
+    }
+}
+
+impl<R: ValueValidator + 'static> BitAnd<R> for CombinatorSchema {
+    type Output = CombinatorSchema;
+
+    fn bitand(self, rhs: R) -> CombinatorSchema {
+        match self {
+            CombinatorSchema::AllOf { mut validators, .. } => {
+                validators.push(Arc::new(rhs));
+                CombinatorSchema::AllOf {
+                    schemas: validator_fns(&validators),
+                    validators,
+                }
+            }
+            other => Schema::all_of_builder().and(other).and(rhs).build(),
+        }
+    }
+}
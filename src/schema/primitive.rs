@@ -0,0 +1,236 @@
+//! Crate-internal schemas for JSON types without a public schema type.
+//!
+//! Integrations that map foreign type systems onto postmortem (validator
+//! structs, SQL columns) need to accept booleans, floating point numbers, and
+//! arbitrary JSON. These minimal schemas cover those cases without committing
+//! to a public API.
+
+use serde_json::{json, Value};
+use stillwater::Validation;
+
+use crate::error::{describe_type, SchemaError, SchemaErrors};
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+
+use super::traits::SchemaLike;
+
+/// A bound on a floating point number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum NumberBound {
+    Min(f64),
+    Max(f64),
+    ExclusiveMin(f64),
+    ExclusiveMax(f64),
+}
+
+/// Validates that a value is a JSON number within optional bounds.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NumberSchema {
+    bounds: Vec<(NumberBound, Option<String>)>,
+}
+
+impl NumberSchema {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a bound with an optional custom error message.
+    pub(crate) fn bound(mut self, bound: NumberBound, message: Option<String>) -> Self {
+        self.bounds.push((bound, message));
+        self
+    }
+}
+
+impl SchemaLike for NumberSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        let Some(n) = value.as_f64() else {
+            return type_error(value, path, "number");
+        };
+
+        let errors: Vec<SchemaError> = self
+            .bounds
+            .iter()
+            .filter_map(|(bound, message)| {
+                let (ok, code, expected) = match bound {
+                    NumberBound::Min(min) => (n >= *min, "min_value", format!("at least {}", min)),
+                    NumberBound::Max(max) => (n <= *max, "max_value", format!("at most {}", max)),
+                    NumberBound::ExclusiveMin(min) => {
+                        (n > *min, "min_value", format!("greater than {}", min))
+                    }
+                    NumberBound::ExclusiveMax(max) => {
+                        (n < *max, "max_value", format!("less than {}", max))
+                    }
+                };
+                (!ok).then(|| {
+                    let msg = message
+                        .clone()
+                        .unwrap_or_else(|| format!("must be {}, got {}", expected, n));
+                    SchemaError::new(path.clone(), msg)
+                        .with_code(code)
+                        .with_expected(expected)
+                        .with_got(n.to_string())
+                })
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Validation::Success(value.clone())
+        } else {
+            Validation::Failure(SchemaErrors::from_vec(errors))
+        }
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path)
+    }
+}
+
+impl ToJsonSchema for NumberSchema {
+    fn to_json_schema(&self) -> Value {
+        let mut schema = json!({ "type": "number" });
+        for (bound, _) in &self.bounds {
+            match bound {
+                NumberBound::Min(v) => schema["minimum"] = json!(v),
+                NumberBound::Max(v) => schema["maximum"] = json!(v),
+                NumberBound::ExclusiveMin(v) => schema["exclusiveMinimum"] = json!(v),
+                NumberBound::ExclusiveMax(v) => schema["exclusiveMaximum"] = json!(v),
+            }
+        }
+        schema
+    }
+}
+
+/// Validates that a value is a JSON boolean.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct BooleanSchema;
+
+impl SchemaLike for BooleanSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        if value.is_boolean() {
+            Validation::Success(value.clone())
+        } else {
+            type_error(value, path, "boolean")
+        }
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path)
+    }
+}
+
+impl ToJsonSchema for BooleanSchema {
+    fn to_json_schema(&self) -> Value {
+        json!({ "type": "boolean" })
+    }
+}
+
+/// Validates that a value is JSON `null`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct NullSchema;
+
+impl SchemaLike for NullSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        if value.is_null() {
+            Validation::Success(Value::Null)
+        } else {
+            type_error(value, path, "null")
+        }
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path)
+    }
+}
+
+impl ToJsonSchema for NullSchema {
+    fn to_json_schema(&self) -> Value {
+        json!({ "type": "null" })
+    }
+}
+
+/// Accepts any JSON value.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct AnySchema;
+
+impl SchemaLike for AnySchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, _path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        Validation::Success(value.clone())
+    }
+
+    fn validate_to_value(
+        &self,
+        value: &Value,
+        _path: &JsonPath,
+    ) -> Validation<Value, SchemaErrors> {
+        Validation::Success(value.clone())
+    }
+}
+
+impl ToJsonSchema for AnySchema {
+    fn to_json_schema(&self) -> Value {
+        json!({})
+    }
+}
+
+/// Builds the standard `invalid_type` error.
+fn type_error(value: &Value, path: &JsonPath, expected: &str) -> Validation<Value, SchemaErrors> {
+    Validation::Failure(SchemaErrors::single(
+        SchemaError::new(path.clone(), format!("expected {}", expected))
+            .with_code("invalid_type")
+            .with_got(describe_type(value))
+            .with_expected(expected),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_bounds() {
+        let schema = NumberSchema::new()
+            .bound(NumberBound::ExclusiveMin(0.0), None)
+            .bound(NumberBound::Max(1.0), Some("ratio too large".to_string()));
+
+        assert!(schema.validate(&json!(0.5), &JsonPath::root()).is_success());
+
+        let errors = schema
+            .validate(&json!(0), &JsonPath::root())
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().code, "min_value");
+
+        let errors = schema
+            .validate(&json!(1.5), &JsonPath::root())
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().message, "ratio too large");
+
+        let errors = schema
+            .validate(&json!("1"), &JsonPath::root())
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().code, "invalid_type");
+    }
+
+    #[test]
+    fn test_boolean_null_and_any() {
+        assert!(BooleanSchema
+            .validate(&json!(true), &JsonPath::root())
+            .is_success());
+        assert!(BooleanSchema
+            .validate(&json!(1), &JsonPath::root())
+            .is_failure());
+        assert!(AnySchema
+            .validate(&json!(null), &JsonPath::root())
+            .is_success());
+    }
+}
@@ -0,0 +1,91 @@
+//! Introspection of the fields a type deserializes from.
+//!
+//! [`ObjectSchema::undeclared_fields`](super::ObjectSchema::undeclared_fields)
+//! compares a struct's fields with a schema's. Serde does not expose a
+//! struct's fields directly, but a derived `Deserialize` passes them to
+//! `deserialize_struct`, so a deserializer that only records them and then
+//! gives up can read them without any input.
+
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use std::fmt::{self, Display};
+
+/// A deserializer that fails with the fields it was asked for.
+struct FieldCollector;
+
+/// The failure of [`FieldCollector`], carrying the fields if the type is a
+/// struct.
+#[derive(Debug)]
+struct Collected(Option<&'static [&'static str]>);
+
+impl Display for Collected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "field collection")
+    }
+}
+
+impl std::error::Error for Collected {}
+
+impl de::Error for Collected {
+    fn custom<T: Display>(_msg: T) -> Self {
+        Collected(None)
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldCollector {
+    type Error = Collected;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Collected> {
+        Err(Collected(None))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Collected> {
+        Err(Collected(Some(fields)))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Returns the serialized names of the fields of `T`, or `None` if `T` does
+/// not deserialize as a struct (e.g. a map, or a struct with flattened
+/// fields).
+pub(super) fn struct_fields<T: DeserializeOwned>() -> Option<&'static [&'static str]> {
+    match T::deserialize(FieldCollector) {
+        Err(Collected(fields)) => fields,
+        Ok(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct User {
+        id: u64,
+        #[serde(rename = "displayName")]
+        display_name: String,
+        email: Option<String>,
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        assert_eq!(
+            struct_fields::<User>(),
+            Some(&["id", "displayName", "email"][..])
+        );
+        assert_eq!(struct_fields::<HashMap<String, u64>>(), None);
+        assert_eq!(struct_fields::<u64>(), None);
+    }
+}
@@ -106,17 +106,22 @@ impl SchemaLike for RefSchema {
             ));
         }
 
+        // Names that already failed in this context skip the registry lookup
+        if let Some(message) = context.cached_missing_ref(&self.name) {
+            return Validation::Failure(SchemaErrors::single(
+                SchemaError::new(path.clone(), message).with_code("missing_reference"),
+            ));
+        }
+
         // Resolve reference from registry
         let schema = match context.registry().get_schema(&self.name) {
             Some(s) => s,
             None => {
+                let message = missing_reference_message(&self.name, context);
+                context.cache_missing_ref(self.name.clone(), message.clone());
                 return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(
-                        path.clone(),
-                        format!("schema '{}' not found in registry", self.name),
-                    )
-                    .with_code("missing_reference"),
-                ))
+                    SchemaError::new(path.clone(), message).with_code("missing_reference"),
+                ));
             }
         };
 
@@ -138,6 +143,53 @@ impl SchemaLike for RefSchema {
     }
 }
 
+/// Builds the error message for an unresolved reference, suggesting
+/// registered names that are a small edit distance away.
+fn missing_reference_message(name: &str, context: &ValidationContext) -> String {
+    let threshold = (name.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, String)> = context
+        .registry()
+        .schema_names()
+        .into_iter()
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+    candidates.sort();
+
+    let suggestions: Vec<String> = candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| format!("'{}'", candidate))
+        .collect();
+
+    if suggestions.is_empty() {
+        format!("schema '{}' not found in registry", name)
+    } else {
+        format!(
+            "schema '{}' not found in registry; did you mean {}?",
+            name,
+            suggestions.join(" or ")
+        )
+    }
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 impl ToJsonSchema for RefSchema {
     fn to_json_schema(&self) -> Value {
         json!({
@@ -145,3 +197,51 @@ impl ToJsonSchema for RefSchema {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::ValueValidator;
+    use crate::validation::RegistryAccess;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A registry with no schemas that counts lookups.
+    struct CountingRegistry {
+        lookups: AtomicUsize,
+    }
+
+    impl RegistryAccess for CountingRegistry {
+        fn get_schema(&self, _name: &str) -> Option<Arc<dyn ValueValidator>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            None
+        }
+    }
+
+    #[test]
+    fn test_missing_reference_is_cached_per_context() {
+        let registry = Arc::new(CountingRegistry {
+            lookups: AtomicUsize::new(0),
+        });
+        let context = ValidationContext::new(registry.clone(), 10);
+        let schema = RefSchema::new("Missing");
+
+        for i in 0..5 {
+            let path = JsonPath::root().push_index(i);
+            let result = schema.validate_with_context(&json!(1), &path, &context.increment_depth());
+            let errors = result.into_result().unwrap_err();
+            assert_eq!(errors.first().code, "missing_reference");
+            assert_eq!(errors.first().path, path);
+        }
+
+        assert_eq!(registry.lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("User", "User"), 0);
+        assert_eq!(edit_distance("Usr", "User"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,166 @@
+//! Sub-schemas validated with their own context policy.
+//!
+//! This module provides [`ScopedSchema`], created by
+//! [`Schema::scoped`](super::Schema::scoped), which validates a subtree of a
+//! document in a [scope](crate::validation::ValidationContext::scoped) of the
+//! context, e.g. leniently inside an otherwise strict envelope.
+
+use serde_json::Value;
+use std::sync::Arc;
+use stillwater::Validation;
+
+use crate::error::SchemaErrors;
+use crate::interop::ToJsonSchema;
+use crate::path::JsonPath;
+use crate::schema::{FieldDoc, SchemaLike, ValueValidator};
+use crate::validation::ValidationContext;
+
+type Adjust = Arc<dyn Fn(ValidationContext) -> ValidationContext + Send + Sync>;
+
+/// A schema validated in a scope of the validation context.
+///
+/// With a [`ValidationContext`], the inner schema is validated with the
+/// context adjusted for the scope, and the scope ends when it is done:
+/// suppressions set for the scope are applied, and in a
+/// [lenient](ValidationContext::lenient) scope the remaining errors become
+/// warnings. Without a context, the inner schema is validated as is.
+#[derive(Clone)]
+pub struct ScopedSchema {
+    inner: Arc<dyn ValueValidator>,
+    adjust: Adjust,
+}
+
+impl ScopedSchema {
+    pub(crate) fn new<S, F>(inner: S, adjust: F) -> Self
+    where
+        S: ValueValidator + 'static,
+        F: Fn(ValidationContext) -> ValidationContext + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(inner),
+            adjust: Arc::new(adjust),
+        }
+    }
+}
+
+impl SchemaLike for ScopedSchema {
+    type Output = Value;
+
+    fn validate(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.inner.validate_value(value, path)
+    }
+
+    fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
+        self.validate(value, path)
+    }
+
+    fn validate_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        let scope = context.scoped(|context| (self.adjust)(context));
+        let result = self.inner.validate_value_with_context(value, path, &scope);
+        scope.close_scope(result, value)
+    }
+
+    fn validate_to_value_with_context(
+        &self,
+        value: &Value,
+        path: &JsonPath,
+        context: &ValidationContext,
+    ) -> Validation<Value, SchemaErrors> {
+        self.validate_with_context(value, path, context)
+    }
+
+    fn collect_refs(&self, refs: &mut Vec<String>) {
+        self.inner.collect_refs(refs);
+    }
+
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        self.inner.field_doc(path)
+    }
+}
+
+impl ToJsonSchema for ScopedSchema {
+    fn to_json_schema(&self) -> Value {
+        self.inner.to_json_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::suppression::{Suppression, Suppressions};
+    use crate::validation::ValidationMode;
+    use crate::{JsonPath, Schema, SchemaRegistry};
+    use serde_json::json;
+
+    #[test]
+    fn test_scope_applies_to_subtree_only() {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Envelope",
+                Schema::object()
+                    .field("id", Schema::integer().positive())
+                    .field(
+                        "payload",
+                        Schema::scoped(
+                            Schema::object()
+                                .field("id", Schema::integer().positive())
+                                .field("kind", Schema::string()),
+                            |context| {
+                                context.with_suppressions(Suppressions::new().with(
+                                    Suppression::new("payload.kind", "required", "vendor omits it"),
+                                ))
+                            },
+                        ),
+                    ),
+            )
+            .unwrap();
+
+        let context = registry.context();
+        let result = registry
+            .validate_with_context(
+                "Envelope",
+                &json!({"id": -1, "payload": {"id": -2}}),
+                &context,
+            )
+            .unwrap();
+        let errors = result.into_result().unwrap_err();
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["id", "payload.id"]);
+        let warnings = context.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].reason, "vendor omits it");
+    }
+
+    #[test]
+    fn test_scoped_mode_and_plain_validation() {
+        let account = Schema::object()
+            .optional("id", Schema::integer())
+            .read_only()
+            .field("name", Schema::string());
+        let schema = Schema::object().field(
+            "account",
+            Schema::scoped(account, |context| context.with_mode(ValidationMode::Read)),
+        );
+        let registry = SchemaRegistry::new();
+        registry.register("Signup", schema.clone()).unwrap();
+
+        // The nested account is a response, so its id may be set
+        let create = registry.context().with_mode(ValidationMode::Create);
+        let signup = json!({"account": {"id": 3, "name": "Ada"}});
+        assert!(registry
+            .validate_with_context("Signup", &signup, &create)
+            .unwrap()
+            .is_success());
+
+        // Without a context, there is no policy to adjust
+        assert!(schema.validate(&signup, &JsonPath::root()).is_success());
+        assert!(schema
+            .validate(&json!({"account": {"name": 1}}), &JsonPath::root())
+            .is_failure());
+    }
+}
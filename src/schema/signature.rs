@@ -0,0 +1,300 @@
+//! Verification of signatures embedded in documents.
+//!
+//! Webhook payloads often carry an HMAC of their own content, e.g.
+//! `{"event": "order.paid", "amount": 10, "signature": "9f86d0..."}`.
+//! [`ObjectSchema::verify_signature`] checks it as part of validation, so a
+//! forged or tampered payload is rejected with the structural errors.
+//!
+//! The signature covers the canonical form of the document without the
+//! signature field: object keys sorted, and no whitespace. Senders can
+//! produce it with [`SignatureAlgorithm::sign_document`].
+//!
+//! This module is only available when the `signatures` feature is enabled.
+
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value};
+use sha2::{Sha256, Sha512};
+use stillwater::Validation;
+
+use super::ObjectSchema;
+use crate::error::{SchemaError, SchemaErrors};
+
+/// How an embedded signature is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignatureAlgorithm {
+    /// HMAC with SHA-256, hex-encoded.
+    HmacSha256,
+    /// HMAC with SHA-512, hex-encoded.
+    HmacSha512,
+}
+
+impl SignatureAlgorithm {
+    /// Returns the algorithm's name, e.g. `HMAC-SHA256`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::HmacSha256 => "HMAC-SHA256",
+            SignatureAlgorithm::HmacSha512 => "HMAC-SHA512",
+        }
+    }
+
+    /// Returns the signature of `document` without its `field`, as
+    /// [`ObjectSchema::verify_signature`] expects it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::SignatureAlgorithm;
+    /// use serde_json::json;
+    ///
+    /// let document = json!({"b": 2, "a": 1, "signature": "ignored"});
+    /// let signature = SignatureAlgorithm::HmacSha256.sign_document(b"secret", &document, "signature");
+    /// assert_eq!(signature.len(), 64);
+    /// ```
+    pub fn sign_document(&self, key: &[u8], document: &Value, field: &str) -> String {
+        let message = match document {
+            Value::Object(obj) => canonical_without(obj, field),
+            other => canonical_json(other),
+        };
+        let mac = match self {
+            SignatureAlgorithm::HmacSha256 => mac::<Hmac<Sha256>>(key, message.as_bytes()),
+            SignatureAlgorithm::HmacSha512 => mac::<Hmac<Sha512>>(key, message.as_bytes()),
+        };
+        mac.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Checks `signature` against `message` in constant time.
+    fn verify(&self, key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            SignatureAlgorithm::HmacSha256 => verify::<Hmac<Sha256>>(key, message, signature),
+            SignatureAlgorithm::HmacSha512 => verify::<Hmac<Sha512>>(key, message, signature),
+        }
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn verify<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.verify_slice(signature).is_ok()
+}
+
+/// Serializes a value with object keys sorted and no whitespace.
+pub(crate) fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+/// Serializes an object without one of its keys, canonically.
+fn canonical_without(obj: &Map<String, Value>, field: &str) -> String {
+    let rest: Map<String, Value> = obj
+        .iter()
+        .filter(|(key, _)| *key != field)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    canonical_json(&Value::Object(rest))
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Decodes a hex string, in either case.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl ObjectSchema {
+    /// Verifies a signature embedded in the document.
+    ///
+    /// The string in `field` must be the hex-encoded `algorithm` MAC of the
+    /// rest of the document, in canonical form (see
+    /// [`SignatureAlgorithm::sign_document`]), under the key returned by
+    /// `key_provider`. The provider sees the raw document, so the key can
+    /// depend on e.g. a key id or account in it; returning `None` fails
+    /// verification. The signature is checked against the input as given,
+    /// before defaults or renames, and in constant time.
+    ///
+    /// A missing, malformed, or wrong signature, or a missing key, is an
+    /// `invalid_signature` error at `field`. `field` should be declared on
+    /// the schema if it denies additional properties.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Schema, SignatureAlgorithm};
+    /// use serde_json::json;
+    ///
+    /// let webhook = Schema::object()
+    ///     .field("event", Schema::string())
+    ///     .field("signature", Schema::string())
+    ///     .verify_signature("signature", SignatureAlgorithm::HmacSha256, |_| {
+    ///         Some(b"whsec_test".to_vec())
+    ///     });
+    ///
+    /// let mut payload = json!({"event": "order.paid", "signature": ""});
+    /// payload["signature"] = json!(SignatureAlgorithm::HmacSha256
+    ///     .sign_document(b"whsec_test", &payload, "signature"));
+    /// assert!(webhook.validate(&payload, &JsonPath::root()).is_success());
+    ///
+    /// payload["event"] = json!("order.refunded");
+    /// let errors = webhook.validate(&payload, &JsonPath::root()).into_result().unwrap_err();
+    /// assert_eq!(errors.first().code, "invalid_signature");
+    /// ```
+    pub fn verify_signature<K>(
+        self,
+        field: impl Into<String>,
+        algorithm: SignatureAlgorithm,
+        key_provider: K,
+    ) -> Self
+    where
+        K: Fn(&Map<String, Value>) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        let field = field.into();
+        self.custom_raw(move |_, raw, path| {
+            let fail = |message: String| {
+                Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(path.push_field(&field), message)
+                        .with_code("invalid_signature")
+                        .with_expected(format!("hex-encoded {}", algorithm.name())),
+                ))
+            };
+            let Some(signature) = raw.get(&field).and_then(Value::as_str) else {
+                return fail(format!("signature '{}' is missing", field));
+            };
+            let Some(signature) = decode_hex(signature) else {
+                return fail(format!(
+                    "signature '{}' is not a hex-encoded {}",
+                    field,
+                    algorithm.name()
+                ));
+            };
+            let Some(key) = key_provider(raw) else {
+                return fail(format!("no key to verify signature '{}'", field));
+            };
+            let message = canonical_without(raw, &field);
+            if algorithm.verify(&key, message.as_bytes(), &signature) {
+                Validation::Success(())
+            } else {
+                fail(format!("signature '{}' does not match the document", field))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::JsonPath;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_json() {
+        assert_eq!(
+            canonical_json(&json!({"b": [1, {"d": null, "c": "x\"y"}], "a": 1.5})),
+            r#"{"a":1.5,"b":[1,{"c":"x\"y","d":null}]}"#
+        );
+        assert_eq!(decode_hex("0aFf"), Some(vec![0x0a, 0xff]));
+        assert_eq!(decode_hex("0a0"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("+f"), None);
+    }
+
+    #[test]
+    fn test_known_vector() {
+        let mac = mac::<Hmac<Sha256>>(b"key", b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            mac.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let schema = Schema::object()
+            .field("account", Schema::string())
+            .optional("sig", Schema::string())
+            .verify_signature("sig", SignatureAlgorithm::HmacSha512, |doc| {
+                match doc.get("account").and_then(Value::as_str) {
+                    Some("acme") => Some(b"acme-key".to_vec()),
+                    _ => None,
+                }
+            });
+        let sign = |doc: &Value| {
+            let mut doc = doc.clone();
+            let sig = SignatureAlgorithm::HmacSha512.sign_document(b"acme-key", &doc, "sig");
+            doc["sig"] = json!(sig.to_uppercase());
+            doc
+        };
+
+        let signed = sign(&json!({"account": "acme", "n": [1, 2]}));
+        assert!(schema.validate(&signed, &JsonPath::root()).is_success());
+
+        let message = |doc: &Value| {
+            let errors = schema
+                .validate(doc, &JsonPath::root())
+                .into_result()
+                .unwrap_err();
+            assert_eq!(errors.first().code, "invalid_signature");
+            assert_eq!(errors.first().path.to_string(), "sig");
+            errors.first().message.clone()
+        };
+        let mut tampered = signed.clone();
+        tampered["n"] = json!([1, 3]);
+        assert_eq!(
+            message(&tampered),
+            "signature 'sig' does not match the document"
+        );
+        assert_eq!(
+            message(&json!({"account": "acme"})),
+            "signature 'sig' is missing"
+        );
+        assert_eq!(
+            message(&json!({"account": "acme", "sig": "not hex"})),
+            "signature 'sig' is not a hex-encoded HMAC-SHA512"
+        );
+        assert_eq!(
+            message(&sign(&json!({"account": "globex"}))),
+            "no key to verify signature 'sig'"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Standards-compliant format parsers for the `strict-formats` feature.
+//!
+//! The default format checks are quick approximations: any `a@b.c` is an
+//! email, and the IPv6 check is a regex. These parsers follow the RFCs, and
+//! are used by schemas built with
+//! [`StringSchema::strict_formats`](super::StringSchema::strict_formats).
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use email_address::EmailAddress;
+use uuid::{Uuid, Variant};
+
+/// Validates an email address per RFC 5321, with RFC 6531 internationalized
+/// local parts and domains.
+pub(super) fn is_email(s: &str) -> bool {
+    EmailAddress::is_valid(s)
+}
+
+/// Validates a UUID in RFC 9562 hyphenated form.
+///
+/// The variant bits must mark an RFC UUID; the nil and max UUIDs are also
+/// accepted. The braced, URN, and unhyphenated forms are rejected.
+pub(super) fn is_uuid(s: &str) -> bool {
+    if s.len() != 36 {
+        return false;
+    }
+    Uuid::try_parse(s)
+        .is_ok_and(|uuid| uuid.get_variant() == Variant::RFC4122 || uuid.is_nil() || uuid.is_max())
+}
+
+/// Validates an IPv4 address in dotted-decimal form, without leading zeros.
+pub(super) fn is_ipv4(s: &str) -> bool {
+    Ipv4Addr::from_str(s).is_ok()
+}
+
+/// Validates an IPv6 address per RFC 4291, including embedded IPv4.
+pub(super) fn is_ipv6(s: &str) -> bool {
+    Ipv6Addr::from_str(s).is_ok()
+}
+
+/// Validates an IPv4 or IPv6 address.
+pub(super) fn is_ip(s: &str) -> bool {
+    IpAddr::from_str(s).is_ok()
+}
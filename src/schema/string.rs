@@ -3,15 +3,25 @@
 //! This module provides [`StringSchema`] for validating string values with
 //! constraints like minimum/maximum length and regex patterns.
 
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 use stillwater::Validation;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::error::{SchemaError, SchemaErrors};
+use crate::error::{
+    cap, count, describe_type, got_limit, render_message, Provenance, Repair, RepairKind,
+    SchemaError, SchemaErrors,
+};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
+use crate::tunable::Limit;
 
+use super::duration::Duration;
+use super::locale::{is_country_code, is_currency_code, is_language_tag};
 use super::traits::SchemaLike;
 
 /// Type alias for custom string validators.
@@ -28,6 +38,23 @@ enum Format {
     Ip,
     Ipv4,
     Ipv6,
+    Phone,
+    CreditCard,
+    Slug,
+    Identifier,
+    Base64,
+    Base64Url,
+    Hex,
+    Jwt,
+    Duration,
+    HumanDuration,
+    MacAddress,
+    CountryCode,
+    CurrencyCode,
+    LanguageTag,
+    Json,
+    #[cfg(feature = "chrono")]
+    DateTimeWithOffset,
 }
 
 impl Format {
@@ -42,31 +69,261 @@ impl Format {
             Format::Ip => "ipv4", // JSON Schema doesn't have generic ip
             Format::Ipv4 => "ipv4",
             Format::Ipv6 => "ipv6",
+            Format::Phone => "phone",
+            Format::CreditCard => "credit-card",
+            Format::Slug => "slug",
+            Format::Identifier => "identifier",
+            Format::Base64 => "base64",
+            Format::Base64Url => "base64url",
+            Format::Hex => "hex",
+            Format::Jwt => "jwt",
+            Format::Duration => "duration",
+            Format::HumanDuration => "human-duration",
+            Format::MacAddress => "mac",
+            Format::CountryCode => "iso-3166-alpha-2",
+            Format::CurrencyCode => "iso-4217",
+            Format::LanguageTag => "bcp47",
+            Format::Json => "json",
+            #[cfg(feature = "chrono")]
+            Format::DateTimeWithOffset => "date-time",
         }
     }
 }
 
+/// Character classes a string can be restricted to.
+#[derive(Clone, Copy, Debug)]
+enum CharClass {
+    Alphanumeric,
+    Ascii,
+    NoWhitespace,
+    Lowercase,
+    Uppercase,
+}
+
+impl CharClass {
+    /// Returns whether a single character is allowed by this class.
+    fn allows(self, c: char) -> bool {
+        match self {
+            CharClass::Alphanumeric => c.is_alphanumeric(),
+            CharClass::Ascii => c.is_ascii(),
+            CharClass::NoWhitespace => !c.is_whitespace(),
+            CharClass::Lowercase => !c.is_uppercase(),
+            CharClass::Uppercase => !c.is_lowercase(),
+        }
+    }
+
+    /// Returns the error code and a description of the allowed characters.
+    fn describe(self) -> (&'static str, &'static str) {
+        match self {
+            CharClass::Alphanumeric => ("not_alphanumeric", "only letters and digits"),
+            CharClass::Ascii => ("not_ascii", "only ASCII characters"),
+            CharClass::NoWhitespace => ("contains_whitespace", "no whitespace"),
+            CharClass::Lowercase => ("not_lowercase", "no uppercase letters"),
+            CharClass::Uppercase => ("not_uppercase", "no lowercase letters"),
+        }
+    }
+}
+
+/// Unit used when measuring string length.
+///
+/// Length constraints count Unicode scalar values by default, which matches
+/// JSON Schema's `minLength`/`maxLength`. Databases usually limit columns by
+/// bytes, while user interfaces usually count grapheme clusters (so that an
+/// emoji with modifiers counts as one).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LengthUnit {
+    /// Unicode scalar values (`str::chars`).
+    #[default]
+    Chars,
+    /// UTF-8 encoded bytes (`str::len`).
+    Bytes,
+    /// Extended grapheme clusters.
+    Graphemes,
+}
+
+impl LengthUnit {
+    /// Measures the length of a string in this unit.
+    fn measure(self, s: &str) -> usize {
+        match self {
+            LengthUnit::Chars => s.chars().count(),
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Graphemes => s.graphemes(true).count(),
+        }
+    }
+
+    /// Returns the longest prefix of a string at most `max` long in this unit.
+    fn truncate(self, s: &str, max: usize) -> String {
+        match self {
+            LengthUnit::Chars => s.chars().take(max).collect(),
+            LengthUnit::Bytes => {
+                let mut end = max.min(s.len());
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                s[..end].to_string()
+            }
+            LengthUnit::Graphemes => s.graphemes(true).take(max).collect(),
+        }
+    }
+
+    /// Renders a length with this unit's noun, e.g. `1 character`.
+    fn count(self, len: usize) -> String {
+        match self {
+            LengthUnit::Chars => count(len, "character", "characters"),
+            LengthUnit::Bytes => count(len, "byte", "bytes"),
+            LengthUnit::Graphemes => count(len, "grapheme", "graphemes"),
+        }
+    }
+
+    /// Returns the name used for this unit in message templates.
+    fn name(self) -> &'static str {
+        match self {
+            LengthUnit::Chars => "chars",
+            LengthUnit::Bytes => "bytes",
+            LengthUnit::Graphemes => "graphemes",
+        }
+    }
+}
+
+/// Limits applied when compiling and matching a regex pattern.
+///
+/// The regex crate matches in time linear in the input, so a pattern cannot
+/// backtrack catastrophically. It can still be expensive: counted
+/// repetitions such as `(\w{1,100}){1,100}` compile to large programs, and
+/// matching time grows with both the program and the input. These limits
+/// bound both, for patterns read from files that may not be trusted.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{JsonPath, PatternLimits, Schema};
+/// use serde_json::json;
+///
+/// let limits = PatternLimits::untrusted();
+///
+/// // Compiles to far more than the size limit
+/// assert!(Schema::string()
+///     .pattern_with_limits(r"((\w{1,100}){1,100}){1,100}", limits)
+///     .is_err());
+///
+/// let schema = Schema::string()
+///     .pattern_with_limits(r"^[a-z]+$", limits.max_input(8))
+///     .unwrap();
+/// let errors = schema
+///     .validate(&json!("abcdefghijk"), &JsonPath::root())
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.first().code, "pattern_timeout");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatternLimits {
+    size_limit: usize,
+    nest_limit: u32,
+    max_input: Option<usize>,
+}
+
+impl Default for PatternLimits {
+    /// The regex crate's own limits, with no limit on input length.
+    fn default() -> Self {
+        PatternLimits {
+            size_limit: 10 * (1 << 20),
+            nest_limit: 250,
+            max_input: None,
+        }
+    }
+}
+
+impl PatternLimits {
+    /// Limits suited to patterns from untrusted sources: a 256 KiB compiled
+    /// program, 32 levels of nesting, and inputs of at most 64 KiB.
+    pub fn untrusted() -> Self {
+        PatternLimits {
+            size_limit: 256 * (1 << 10),
+            nest_limit: 32,
+            max_input: Some(64 * (1 << 10)),
+        }
+    }
+
+    /// Sets the largest compiled program allowed, in bytes.
+    pub fn size_limit(mut self, bytes: usize) -> Self {
+        self.size_limit = bytes;
+        self
+    }
+
+    /// Sets how deeply groups and repetitions may nest.
+    pub fn nest_limit(mut self, depth: u32) -> Self {
+        self.nest_limit = depth;
+        self
+    }
+
+    /// Sets the longest string, in bytes, the pattern is matched against.
+    ///
+    /// Since matching time is linear in the input, this bounds the time
+    /// spent on each value. Longer strings fail with a `pattern_timeout`
+    /// error instead of being matched.
+    pub fn max_input(mut self, bytes: usize) -> Self {
+        self.max_input = Some(bytes);
+        self
+    }
+
+    /// Compiles a pattern within these limits.
+    fn compile(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(pattern)
+            .size_limit(self.size_limit)
+            .nest_limit(self.nest_limit)
+            .build()
+    }
+}
+
 /// String transformation types.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 enum Transform {
     Trim,
     Lowercase,
+    Uppercase,
+    NormalizeNfc,
+    CollapseWhitespace,
+    Map(Arc<dyn Fn(String) -> String + Send + Sync>),
+}
+
+impl Transform {
+    fn apply(&self, s: String) -> String {
+        match self {
+            Transform::Trim => s.trim().to_string(),
+            Transform::Lowercase => s.to_lowercase(),
+            Transform::Uppercase => s.to_uppercase(),
+            Transform::NormalizeNfc => s.nfc().collect(),
+            Transform::CollapseWhitespace => collapse_whitespace(&s),
+            Transform::Map(transform) => transform(s),
+        }
+    }
+}
+
+/// One step of the validation pipeline, as an index into the schema's
+/// transforms, constraints, or custom validators.
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Transform(usize),
+    Constraint(usize),
+    Custom(usize),
 }
 
 /// A constraint applied to string values.
 #[derive(Clone)]
 enum StringConstraint {
     MinLength {
-        min: usize,
+        min: Limit<usize>,
         message: Option<String>,
     },
     MaxLength {
-        max: usize,
+        max: Limit<usize>,
         message: Option<String>,
     },
     Pattern {
         regex: Regex,
         pattern_str: String,
+        max_input: Option<usize>,
         message: Option<String>,
     },
     Format {
@@ -77,6 +334,11 @@ enum StringConstraint {
         values: Vec<String>,
         message: Option<String>,
     },
+    NotOneOf {
+        values: Vec<String>,
+        ignore_case: bool,
+        message: Option<String>,
+    },
     StartsWith {
         prefix: String,
         message: Option<String>,
@@ -89,6 +351,105 @@ enum StringConstraint {
         substring: String,
         message: Option<String>,
     },
+    CharClass {
+        class: CharClass,
+        message: Option<String>,
+    },
+    #[cfg(feature = "chrono")]
+    DateMin {
+        min: chrono::NaiveDate,
+        message: Option<String>,
+    },
+    #[cfg(feature = "chrono")]
+    DateMax {
+        max: chrono::NaiveDate,
+        message: Option<String>,
+    },
+    #[cfg(feature = "chrono")]
+    BeforeNow { message: Option<String> },
+    #[cfg(feature = "chrono")]
+    BusinessHours {
+        zone: super::calendar::Zone,
+        start: u32,
+        end: u32,
+        message: Option<String>,
+    },
+    #[cfg(feature = "chrono")]
+    WeekdayOnly {
+        zone: super::calendar::Zone,
+        message: Option<String>,
+    },
+    #[cfg(feature = "chrono")]
+    NotHoliday {
+        calendar: Arc<dyn super::calendar::HolidayCalendar>,
+        message: Option<String>,
+    },
+}
+
+impl StringConstraint {
+    /// Returns the custom message set with `.error(...)`, if any.
+    fn message(&self) -> Option<&str> {
+        match self {
+            StringConstraint::MinLength { message, .. }
+            | StringConstraint::MaxLength { message, .. }
+            | StringConstraint::Pattern { message, .. }
+            | StringConstraint::Format { message, .. }
+            | StringConstraint::OneOf { message, .. }
+            | StringConstraint::NotOneOf { message, .. }
+            | StringConstraint::StartsWith { message, .. }
+            | StringConstraint::EndsWith { message, .. }
+            | StringConstraint::Contains { message, .. }
+            | StringConstraint::CharClass { message, .. } => message.as_deref(),
+            #[cfg(feature = "chrono")]
+            StringConstraint::DateMin { message, .. }
+            | StringConstraint::DateMax { message, .. }
+            | StringConstraint::BeforeNow { message }
+            | StringConstraint::BusinessHours { message, .. }
+            | StringConstraint::WeekdayOnly { message, .. }
+            | StringConstraint::NotHoliday { message, .. } => message.as_deref(),
+        }
+    }
+
+    /// Returns the parameters available as message placeholders.
+    fn template_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            StringConstraint::MinLength { min, .. } => vec![("min", min.describe())],
+            StringConstraint::MaxLength { max, .. } => vec![("max", max.describe())],
+            StringConstraint::Pattern { pattern_str, .. } => {
+                vec![("pattern", pattern_str.clone())]
+            }
+            StringConstraint::Format { format, .. } => {
+                vec![("format", format.to_json_schema_format().to_string())]
+            }
+            StringConstraint::OneOf { values, .. } | StringConstraint::NotOneOf { values, .. } => {
+                vec![("values", values.join(", "))]
+            }
+            StringConstraint::StartsWith { prefix, .. } => vec![("prefix", prefix.clone())],
+            StringConstraint::EndsWith { suffix, .. } => vec![("suffix", suffix.clone())],
+            StringConstraint::Contains { substring, .. } => {
+                vec![("substring", substring.clone())]
+            }
+            StringConstraint::CharClass { class, .. } => {
+                vec![("allowed", class.describe().1.to_string())]
+            }
+            #[cfg(feature = "chrono")]
+            StringConstraint::DateMin { min, .. } => vec![("min", min.to_string())],
+            #[cfg(feature = "chrono")]
+            StringConstraint::DateMax { max, .. } => vec![("max", max.to_string())],
+            #[cfg(feature = "chrono")]
+            StringConstraint::BeforeNow { .. } | StringConstraint::NotHoliday { .. } => Vec::new(),
+            #[cfg(feature = "chrono")]
+            StringConstraint::BusinessHours {
+                zone, start, end, ..
+            } => vec![
+                ("start", format!("{:02}:00", start)),
+                ("end", format!("{:02}:00", end)),
+                ("zone", zone.name().to_string()),
+            ],
+            #[cfg(feature = "chrono")]
+            StringConstraint::WeekdayOnly { zone, .. } => vec![("zone", zone.name().to_string())],
+        }
+    }
 }
 
 /// A schema for validating string values.
@@ -120,7 +481,14 @@ pub struct StringSchema {
     constraints: Vec<StringConstraint>,
     transforms: Vec<Transform>,
     custom_validators: Vec<CustomValidator>,
+    steps: Vec<Step>,
+    transforms_first: bool,
+    embedded_schema: Option<Arc<dyn super::traits::ValueValidator>>,
     type_error_message: Option<String>,
+    length_unit: LengthUnit,
+    strict_formats: bool,
+    type_provenance: Option<Provenance>,
+    constraint_provenance: HashMap<usize, Provenance>,
 }
 
 impl StringSchema {
@@ -130,13 +498,22 @@ impl StringSchema {
             constraints: Vec::new(),
             transforms: Vec::new(),
             custom_validators: Vec::new(),
+            steps: Vec::new(),
+            transforms_first: false,
+            embedded_schema: None,
             type_error_message: None,
+            length_unit: LengthUnit::Chars,
+            strict_formats: false,
+            type_provenance: None,
+            constraint_provenance: HashMap::new(),
         }
     }
 
     /// Adds a minimum length constraint.
     ///
-    /// The string must have at least `min` characters (Unicode scalar values).
+    /// The string must have at least `min` characters (Unicode scalar values),
+    /// or `min` of the unit configured with [`len_unit`](Self::len_unit).
+    /// `min` may be a [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -152,15 +529,19 @@ impl StringSchema {
     /// let result = schema.validate(&json!("hi"), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn min_len(mut self, min: usize) -> Self {
-        self.constraints
-            .push(StringConstraint::MinLength { min, message: None });
+    pub fn min_len(mut self, min: impl Into<Limit<usize>>) -> Self {
+        self.push_constraint(StringConstraint::MinLength {
+            min: min.into(),
+            message: None,
+        });
         self
     }
 
     /// Adds a maximum length constraint.
     ///
-    /// The string must have at most `max` characters (Unicode scalar values).
+    /// The string must have at most `max` characters (Unicode scalar values),
+    /// or `max` of the unit configured with [`len_unit`](Self::len_unit).
+    /// `max` may be a [`Tunable`](crate::Tunable).
     ///
     /// # Example
     ///
@@ -176,9 +557,36 @@ impl StringSchema {
     /// let result = schema.validate(&json!("this is too long"), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn max_len(mut self, max: usize) -> Self {
-        self.constraints
-            .push(StringConstraint::MaxLength { max, message: None });
+    pub fn max_len(mut self, max: impl Into<Limit<usize>>) -> Self {
+        self.push_constraint(StringConstraint::MaxLength {
+            max: max.into(),
+            message: None,
+        });
+        self
+    }
+
+    /// Sets the unit used by `min_len` and `max_len`.
+    ///
+    /// Defaults to [`LengthUnit::Chars`]. The unit applies to all length
+    /// constraints on this schema, regardless of the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, LengthUnit, Schema};
+    /// use serde_json::json;
+    ///
+    /// // A VARCHAR column limited to 8 bytes
+    /// let schema = Schema::string().max_len(8).len_unit(LengthUnit::Bytes);
+    ///
+    /// let result = schema.validate(&json!("héllo"), &JsonPath::root());
+    /// assert!(result.is_success()); // 6 bytes
+    ///
+    /// let result = schema.validate(&json!("日本語"), &JsonPath::root());
+    /// assert!(result.is_failure()); // 9 bytes
+    /// ```
+    pub fn len_unit(mut self, unit: LengthUnit) -> Self {
+        self.length_unit = unit;
         self
     }
 
@@ -203,19 +611,67 @@ impl StringSchema {
     /// let result = schema.validate(&json!("abc"), &JsonPath::root());
     /// assert!(result.is_failure());
     /// ```
-    pub fn pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
-        let regex = Regex::new(pattern)?;
-        self.constraints.push(StringConstraint::Pattern {
+    pub fn pattern(self, pattern: &str) -> Result<Self, regex::Error> {
+        self.pattern_with_limits(pattern, PatternLimits::default())
+    }
+
+    /// Adds a regex pattern constraint, compiled and matched within `limits`.
+    ///
+    /// Returns an error if the pattern is invalid or exceeds the size or
+    /// nesting limits. Strings longer than the input limit fail with a
+    /// `pattern_timeout` error. See [`PatternLimits`] for an example.
+    pub fn pattern_with_limits(
+        mut self,
+        pattern: &str,
+        limits: PatternLimits,
+    ) -> Result<Self, regex::Error> {
+        let regex = limits.compile(pattern)?;
+        self.push_constraint(StringConstraint::Pattern {
             regex,
             pattern_str: pattern.to_string(),
+            max_input: limits.max_input,
             message: None,
         });
         Ok(self)
     }
 
+    /// Checks email, UUID, and IP formats with standards-compliant parsers.
+    ///
+    /// By default these formats are checked with quick approximations. In
+    /// strict mode, emails are parsed per RFC 5321/6531, UUIDs must be
+    /// hyphenated RFC 9562 UUIDs, and IP addresses are parsed with
+    /// [`std::net`]. Error codes are the same in both modes.
+    ///
+    /// Requires the `strict-formats` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let lenient = Schema::string().ip();
+    /// let strict = Schema::string().ip().strict_formats();
+    ///
+    /// // Leading zeros are ambiguous (octal in some parsers)
+    /// let value = json!("192.168.001.010");
+    /// assert!(lenient.validate(&value, &JsonPath::root()).is_success());
+    /// assert!(strict.validate(&value, &JsonPath::root()).is_failure());
+    ///
+    /// // IPv4-mapped IPv6 addresses are valid
+    /// let value = json!("::ffff:192.0.2.1");
+    /// assert!(lenient.validate(&value, &JsonPath::root()).is_failure());
+    /// assert!(strict.validate(&value, &JsonPath::root()).is_success());
+    /// ```
+    #[cfg(feature = "strict-formats")]
+    pub fn strict_formats(mut self) -> Self {
+        self.strict_formats = true;
+        self
+    }
+
     /// Adds an email format constraint.
     pub fn email(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
+        self.push_constraint(StringConstraint::Format {
             format: Format::Email,
             message: None,
         });
@@ -224,7 +680,7 @@ impl StringSchema {
 
     /// Adds a URL format constraint (http/https).
     pub fn url(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
+        self.push_constraint(StringConstraint::Format {
             format: Format::Url,
             message: None,
         });
@@ -233,7 +689,7 @@ impl StringSchema {
 
     /// Adds a UUID format constraint.
     pub fn uuid(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
+        self.push_constraint(StringConstraint::Format {
             format: Format::Uuid,
             message: None,
         });
@@ -241,8 +697,12 @@ impl StringSchema {
     }
 
     /// Adds a date format constraint (YYYY-MM-DD).
+    ///
+    /// With the `chrono` feature the date must exist in the calendar, so
+    /// `2025-02-31` is rejected. Without it, only the shape and the field
+    /// ranges are checked.
     pub fn date(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
+        self.push_constraint(StringConstraint::Format {
             format: Format::Date,
             message: None,
         });
@@ -250,92 +710,1021 @@ impl StringSchema {
     }
 
     /// Adds a datetime format constraint (ISO 8601).
+    ///
+    /// The timezone offset is optional. With the `chrono` feature the whole
+    /// string is parsed, with a real calendar date, a valid time of day, and
+    /// nothing after the optional `Z` or `±HH:MM` offset.
     pub fn datetime(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
+        self.push_constraint(StringConstraint::Format {
             format: Format::DateTime,
             message: None,
         });
         self
     }
 
-    /// Adds an IP address format constraint (IPv4 or IPv6).
-    pub fn ip(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
-            format: Format::Ip,
+    /// Adds an RFC 3339 datetime constraint that requires a timezone offset.
+    ///
+    /// Datetimes without an offset are ambiguous about which instant they
+    /// name; this rejects them with the same `invalid_datetime` code as
+    /// [`datetime`](Self::datetime).
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().datetime_with_offset();
+    ///
+    /// assert!(schema.validate(&json!("2025-11-28T14:30:00Z"), &JsonPath::root()).is_success());
+    /// assert!(schema.validate(&json!("2025-11-28T14:30:00+01:00"), &JsonPath::root()).is_success());
+    /// assert!(schema.validate(&json!("2025-11-28T14:30:00"), &JsonPath::root()).is_failure());
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn datetime_with_offset(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::DateTimeWithOffset,
             message: None,
         });
         self
     }
 
-    /// Adds an IPv4 format constraint.
-    pub fn ipv4(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
-            format: Format::Ipv4,
-            message: None,
-        });
+    /// Requires the date to be on or after `min` (YYYY-MM-DD).
+    ///
+    /// Applies to dates and to the date part of datetimes, as written in
+    /// the value's own offset. Values that are not dates are left to the
+    /// format constraint, so combine this with [`date`](Self::date) or
+    /// [`datetime`](Self::datetime).
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is not a valid date.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string()
+    ///     .date()
+    ///     .date_min("2020-01-01")
+    ///     .date_max("2029-12-31");
+    ///
+    /// assert!(schema.validate(&json!("2024-02-29"), &JsonPath::root()).is_success());
+    /// assert!(schema.validate(&json!("2019-12-31"), &JsonPath::root()).is_failure());
+    /// assert!(schema.validate(&json!("2030-01-01"), &JsonPath::root()).is_failure());
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn date_min(mut self, min: &str) -> Self {
+        let min = super::calendar::parse_date(min)
+            .unwrap_or_else(|| panic!("date_min expects a YYYY-MM-DD date, got '{}'", min));
+        self.push_constraint(StringConstraint::DateMin { min, message: None });
         self
     }
 
-    /// Adds an IPv6 format constraint.
-    pub fn ipv6(mut self) -> Self {
-        self.constraints.push(StringConstraint::Format {
-            format: Format::Ipv6,
+    /// Requires the date to be on or before `max` (YYYY-MM-DD).
+    ///
+    /// See [`date_min`](Self::date_min) for how values are compared.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is not a valid date.
+    #[cfg(feature = "chrono")]
+    pub fn date_max(mut self, max: &str) -> Self {
+        let max = super::calendar::parse_date(max)
+            .unwrap_or_else(|| panic!("date_max expects a YYYY-MM-DD date, got '{}'", max));
+        self.push_constraint(StringConstraint::DateMax { max, message: None });
+        self
+    }
+
+    /// Requires a datetime to be strictly in the past.
+    ///
+    /// The value is compared with the system clock when it is validated.
+    /// Datetimes without an offset are taken to be in UTC. Values that are
+    /// not datetimes are left to the format constraint.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().datetime_with_offset().datetime_before_now();
+    ///
+    /// assert!(schema.validate(&json!("2001-09-09T01:46:40Z"), &JsonPath::root()).is_success());
+    /// assert!(schema.validate(&json!("9999-01-01T00:00:00Z"), &JsonPath::root()).is_failure());
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn datetime_before_now(mut self) -> Self {
+        self.push_constraint(StringConstraint::BeforeNow { message: None });
+        self
+    }
+
+    /// Requires a datetime to fall within business hours in a time zone.
+    ///
+    /// The datetime is converted to `tz` and its local time must be at or
+    /// after `hours.start` o'clock and before `hours.end` o'clock, so
+    /// `9..17` accepts 09:00 up to 16:59:59. `tz` is any chrono
+    /// [`TimeZone`](chrono::TimeZone), e.g. [`chrono::Utc`], a
+    /// [`FixedOffset`](chrono::FixedOffset), or a `chrono_tz::Tz` for zones
+    /// with daylight saving time. Datetimes without an offset are taken to
+    /// be local time in `tz`. Values that are not datetimes are left to the
+    /// format constraint.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hours` is empty or ends after 24.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::FixedOffset;
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+    /// let schema = Schema::string().datetime().business_hours(new_york, 9..17);
+    ///
+    /// // 14:30 UTC is 09:30 in New York
+    /// assert!(schema.validate(&json!("2025-11-28T14:30:00Z"), &JsonPath::root()).is_success());
+    ///
+    /// let errors = schema
+    ///     .validate(&json!("2025-11-28T23:00:00Z"), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "outside_business_hours");
+    /// assert_eq!(errors.first().message, "must be between 09:00 and 17:00 in -05:00, got 18:00");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn business_hours<Tz>(mut self, tz: Tz, hours: std::ops::Range<u32>) -> Self
+    where
+        Tz: chrono::TimeZone + std::fmt::Display + Send + Sync + 'static,
+    {
+        assert!(
+            hours.start < hours.end && hours.end <= 24,
+            "business_hours expects a non-empty range of hours within 0..24, got {:?}",
+            hours
+        );
+        self.push_constraint(StringConstraint::BusinessHours {
+            zone: super::calendar::Zone::new(tz),
+            start: hours.start,
+            end: hours.end,
             message: None,
         });
         self
     }
 
-    /// Adds an enumeration constraint.
-    pub fn one_of<I, S>(mut self, values: I) -> Self
+    /// Requires a date or datetime to fall on a weekday (Monday to Friday)
+    /// in a time zone.
+    ///
+    /// Datetimes are converted to `tz` first, as in
+    /// [`business_hours`](Self::business_hours); dates and datetimes
+    /// without an offset are taken as written. Values that are not dates
+    /// are left to the format constraint.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::FixedOffset;
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+    /// let schema = Schema::string().datetime().weekday_only(tokyo);
+    ///
+    /// // Friday evening in UTC is already Saturday in Tokyo
+    /// let errors = schema
+    ///     .validate(&json!("2025-11-28T20:00:00Z"), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "not_weekday");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn weekday_only<Tz>(mut self, tz: Tz) -> Self
     where
-        I: IntoIterator<Item = S>,
-        S: Into<String>,
+        Tz: chrono::TimeZone + std::fmt::Display + Send + Sync + 'static,
     {
-        let values: Vec<String> = values.into_iter().map(Into::into).collect();
-        self.constraints.push(StringConstraint::OneOf {
-            values,
+        self.push_constraint(StringConstraint::WeekdayOnly {
+            zone: super::calendar::Zone::new(tz),
             message: None,
         });
         self
     }
 
-    /// Adds a starts-with constraint.
-    pub fn starts_with(mut self, prefix: impl Into<String>) -> Self {
-        self.constraints.push(StringConstraint::StartsWith {
-            prefix: prefix.into(),
+    /// Requires a date or datetime not to fall on a holiday.
+    ///
+    /// Applies to dates and to the date part of datetimes, as written in
+    /// the value's own offset, like [`date_min`](Self::date_min). Combine
+    /// with [`weekday_only`](Self::weekday_only) to accept business days
+    /// only. Values that are not dates are left to the format constraint.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::{Datelike, NaiveDate};
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string()
+    ///     .date()
+    ///     .not_holiday(|date: NaiveDate| date.month() == 12 && date.day() == 25);
+    ///
+    /// assert!(schema.validate(&json!("2025-12-24"), &JsonPath::root()).is_success());
+    /// let errors = schema
+    ///     .validate(&json!("2025-12-25"), &JsonPath::root())
+    ///     .into_result()
+    ///     .unwrap_err();
+    /// assert_eq!(errors.first().code, "holiday");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn not_holiday(
+        mut self,
+        calendar: impl super::calendar::HolidayCalendar + 'static,
+    ) -> Self {
+        self.push_constraint(StringConstraint::NotHoliday {
+            calendar: Arc::new(calendar),
             message: None,
         });
         self
     }
 
-    /// Adds an ends-with constraint.
-    pub fn ends_with(mut self, suffix: impl Into<String>) -> Self {
-        self.constraints.push(StringConstraint::EndsWith {
-            suffix: suffix.into(),
+    /// Adds an IP address format constraint (IPv4 or IPv6).
+    pub fn ip(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Ip,
             message: None,
         });
         self
     }
 
-    /// Adds a contains constraint.
-    pub fn contains(mut self, substring: impl Into<String>) -> Self {
-        self.constraints.push(StringConstraint::Contains {
-            substring: substring.into(),
-            message: None,
-        });
+    /// Adds an IPv4 format constraint.
+    pub fn ipv4(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Ipv4,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an IPv6 format constraint.
+    pub fn ipv6(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Ipv6,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an E.164 phone number format constraint.
+    ///
+    /// The value must be a `+` followed by 7 to 15 digits with no spaces or
+    /// punctuation, starting with a plausible country code. Normalize user
+    /// input (e.g. strip spaces and dashes) before validating.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().phone();
+    ///
+    /// let result = schema.validate(&json!("+14155552671"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("415-555-2671"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn phone(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Phone,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a credit card number format constraint.
+    ///
+    /// Spaces and dashes are ignored; the remaining 12 to 19 characters must
+    /// be digits that pass the Luhn checksum. The validated value is returned
+    /// as given, so add a transform if separators should be stripped. Error
+    /// reports only include the last four digits of a rejected number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().credit_card();
+    ///
+    /// let result = schema.validate(&json!("4111 1111 1111 1111"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("4111 1111 1111 1112"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn credit_card(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::CreditCard,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a URL slug format constraint.
+    ///
+    /// The value must be one or more groups of lowercase ASCII letters and
+    /// digits separated by single dashes, with no leading or trailing dash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().slug();
+    ///
+    /// let result = schema.validate(&json!("hello-world-2024"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("Hello World"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn slug(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Slug,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a programming identifier format constraint.
+    ///
+    /// The value must start with an ASCII letter or underscore, followed by
+    /// ASCII letters, digits, or underscores, as accepted by most languages
+    /// for variable names and by databases for unquoted machine names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().identifier();
+    ///
+    /// let result = schema.validate(&json!("user_id"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("2fa-enabled"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn identifier(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Identifier,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a standard base64 format constraint (RFC 4648 section 4).
+    ///
+    /// The value must use the `A-Z`, `a-z`, `0-9`, `+`, `/` alphabet, have a
+    /// length that is a multiple of 4, and only use `=` as trailing padding.
+    /// The value is not decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().base64();
+    ///
+    /// let result = schema.validate(&json!("aGVsbG8="), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("aGVsbG8"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn base64(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Base64,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a URL-safe base64 format constraint (RFC 4648 section 5).
+    ///
+    /// The value must use the `A-Z`, `a-z`, `0-9`, `-`, `_` alphabet. Padding
+    /// is optional, but if present it must be correct. The value is not
+    /// decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().base64url();
+    ///
+    /// let result = schema.validate(&json!("PDw_Pz4-"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("PDw/Pz4+"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn base64url(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Base64Url,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a hexadecimal format constraint.
+    ///
+    /// The value must be an even number of hex digits (either case), i.e. a
+    /// hex encoding of whole bytes, without a `0x` prefix. The value is not
+    /// decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().hex();
+    ///
+    /// let result = schema.validate(&json!("deadBEEF"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("0xdeadbeef"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn hex(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Hex,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a JSON Web Token structural format constraint (RFC 7519).
+    ///
+    /// The value must be three dot-separated, unpadded base64url segments
+    /// whose header and payload decode to JSON objects. The signature segment
+    /// may be empty, as in unsecured tokens. Only the structure is checked:
+    /// the signature is not verified and claims such as `exp` are not
+    /// inspected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().jwt();
+    ///
+    /// // {"alg":"none"}.{"sub":"1"}.
+    /// let result = schema.validate(&json!("eyJhbGciOiJub25lIn0.eyJzdWIiOiIxIn0."), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("not.a.jwt"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn jwt(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Jwt,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an ISO 8601 duration format constraint, e.g. `P3DT4H` or `PT30M`.
+    ///
+    /// Use [`Duration::parse_iso8601`](super::Duration::parse_iso8601) in a
+    /// [`custom`](Self::custom) validator to check the parsed components.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::schema::Duration;
+    /// use postmortem::{JsonPath, Schema, SchemaError, SchemaErrors};
+    /// use serde_json::json;
+    /// use stillwater::Validation;
+    ///
+    /// let retention = Schema::string().duration().custom(|s, path| {
+    ///     match Duration::parse_iso8601(s).and_then(|d| d.to_std()) {
+    ///         Some(d) if d.as_secs() > 90 * 86_400 => Validation::Failure(SchemaErrors::single(
+    ///             SchemaError::new(path.clone(), "retention must be at most 90 days"),
+    ///         )),
+    ///         _ => Validation::Success(()),
+    ///     }
+    /// });
+    ///
+    /// assert!(retention.validate(&json!("P30D"), &JsonPath::root()).is_success());
+    /// assert!(retention.validate(&json!("P120D"), &JsonPath::root()).is_failure());
+    /// assert!(retention.validate(&json!("30 days"), &JsonPath::root()).is_failure());
+    /// ```
+    pub fn duration(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Duration,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a human-readable duration format constraint, e.g. `90s` or
+    /// `1h 30min`.
+    ///
+    /// See [`Duration::parse_human`](super::Duration::parse_human) for the
+    /// accepted units. Failures use the same `invalid_duration` code as
+    /// [`duration`](Self::duration).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().human_duration();
+    ///
+    /// let result = schema.validate(&json!("1h 30min"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("PT1H30M"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn human_duration(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::HumanDuration,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a MAC address format constraint.
+    ///
+    /// The value must be six pairs of hex digits (either case) separated
+    /// consistently by colons or dashes, e.g. `00:1a:2b:3c:4d:5e` or
+    /// `00-1A-2B-3C-4D-5E`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().mac_address();
+    ///
+    /// let result = schema.validate(&json!("00-1A-2B-3C-4D-5E"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("00:1a-2b:3c:4d:5e"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn mac_address(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::MacAddress,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an ISO 3166-1 alpha-2 country code constraint.
+    ///
+    /// The value must be an officially assigned two-letter code in upper
+    /// case, e.g. `US` or `DE`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().country_code();
+    ///
+    /// let result = schema.validate(&json!("DE"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("XX"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn country_code(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::CountryCode,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an ISO 4217 currency code constraint.
+    ///
+    /// The value must be an active three-letter code in upper case, e.g.
+    /// `USD` or `EUR`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().currency_code();
+    ///
+    /// let result = schema.validate(&json!("EUR"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("eur"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn currency_code(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::CurrencyCode,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a BCP 47 language tag constraint.
+    ///
+    /// The value must follow the RFC 5646 tag syntax, e.g. `en`, `en-US`, or
+    /// `zh-Hant-TW`. Subtags are checked for shape, not looked up in the
+    /// IANA registry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().language_tag();
+    ///
+    /// let result = schema.validate(&json!("zh-Hant-TW"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("en_US"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn language_tag(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::LanguageTag,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an embedded JSON constraint.
+    ///
+    /// The value must be a string that parses as JSON, such as a payload
+    /// stored in a text column.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().json();
+    ///
+    /// let result = schema.validate(&json!(r#"{"retries": 3}"#), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("{retries: 3}"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn json(mut self) -> Self {
+        self.push_constraint(StringConstraint::Format {
+            format: Format::Json,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an embedded JSON constraint whose parsed value must match a schema.
+    ///
+    /// The string must parse as JSON, and the parsed value is then validated
+    /// against `schema`. Errors from the embedded document are reported
+    /// under the string's path, so a bad field inside the `config` string
+    /// shows up at `config.retries`. The validated output is still the
+    /// original string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object().field(
+    ///     "config",
+    ///     Schema::string().json_matching(
+    ///         Schema::object().field("retries", Schema::integer().non_negative()),
+    ///     ),
+    /// );
+    ///
+    /// let result = schema.validate(&json!({"config": r#"{"retries": -1}"#}), &JsonPath::root());
+    /// let errors = result.into_result().unwrap_err();
+    /// assert_eq!(errors.first().path.to_string(), "config.retries");
+    /// ```
+    pub fn json_matching<S>(mut self, schema: S) -> Self
+    where
+        S: SchemaLike + ToJsonSchema + 'static,
+    {
+        self.embedded_schema = Some(Arc::new(schema));
+        self.json()
+    }
+
+    /// Adds an enumeration constraint.
+    pub fn one_of<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values: Vec<String> = values.into_iter().map(Into::into).collect();
+        self.push_constraint(StringConstraint::OneOf {
+            values,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a denylist constraint.
+    ///
+    /// The string must not equal any of the given values. Matching is exact;
+    /// use [`not_one_of_ignore_case`](Self::not_one_of_ignore_case) to ignore
+    /// letter case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().not_one_of(["admin", "root"]);
+    ///
+    /// let result = schema.validate(&json!("alice"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("admin"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn not_one_of<I, S>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.push_not_one_of(values, false)
+    }
+
+    /// Adds a case-insensitive denylist constraint.
+    ///
+    /// Like [`not_one_of`](Self::not_one_of), but `"Admin"` and `"ADMIN"` are
+    /// rejected by a denylist containing `"admin"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().not_one_of_ignore_case(["admin", "root"]);
+    ///
+    /// let result = schema.validate(&json!("Root"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn not_one_of_ignore_case<I, S>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.push_not_one_of(values, true)
+    }
+
+    fn push_not_one_of<I, S>(mut self, values: I, ignore_case: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values: Vec<String> = values.into_iter().map(Into::into).collect();
+        self.push_constraint(StringConstraint::NotOneOf {
+            values,
+            ignore_case,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a starts-with constraint.
+    pub fn starts_with(mut self, prefix: impl Into<String>) -> Self {
+        self.push_constraint(StringConstraint::StartsWith {
+            prefix: prefix.into(),
+            message: None,
+        });
+        self
+    }
+
+    /// Adds an ends-with constraint.
+    pub fn ends_with(mut self, suffix: impl Into<String>) -> Self {
+        self.push_constraint(StringConstraint::EndsWith {
+            suffix: suffix.into(),
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a contains constraint.
+    pub fn contains(mut self, substring: impl Into<String>) -> Self {
+        self.push_constraint(StringConstraint::Contains {
+            substring: substring.into(),
+            message: None,
+        });
+        self
+    }
+
+    /// Requires every character to be a letter or digit.
+    ///
+    /// Letters and digits from any script are accepted; combine with
+    /// [`ascii_only`](Self::ascii_only) to restrict to `[A-Za-z0-9]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string().alphanumeric();
+    ///
+    /// let result = schema.validate(&json!("abc123"), &JsonPath::root());
+    /// assert!(result.is_success());
+    ///
+    /// let result = schema.validate(&json!("abc-123"), &JsonPath::root());
+    /// assert!(result.is_failure());
+    /// ```
+    pub fn alphanumeric(self) -> Self {
+        self.char_class(CharClass::Alphanumeric)
+    }
+
+    /// Requires every character to be ASCII.
+    pub fn ascii_only(self) -> Self {
+        self.char_class(CharClass::Ascii)
+    }
+
+    /// Rejects strings containing any whitespace character.
+    pub fn no_whitespace(self) -> Self {
+        self.char_class(CharClass::NoWhitespace)
+    }
+
+    /// Rejects strings containing uppercase letters.
+    ///
+    /// Digits, punctuation and caseless characters are allowed. Unlike
+    /// [`lowercase`](Self::lowercase), this reports an error instead of
+    /// transforming the value.
+    pub fn lowercase_only(self) -> Self {
+        self.char_class(CharClass::Lowercase)
+    }
+
+    /// Rejects strings containing lowercase letters.
+    ///
+    /// Digits, punctuation and caseless characters are allowed.
+    pub fn uppercase_only(self) -> Self {
+        self.char_class(CharClass::Uppercase)
+    }
+
+    fn char_class(mut self, class: CharClass) -> Self {
+        self.push_constraint(StringConstraint::CharClass {
+            class,
+            message: None,
+        });
+        self
+    }
+
+    /// Adds a trim transformation.
+    pub fn trim(mut self) -> Self {
+        self.push_transform(Transform::Trim);
+        self
+    }
+
+    /// Adds a lowercase transformation.
+    pub fn lowercase(mut self) -> Self {
+        self.push_transform(Transform::Lowercase);
+        self
+    }
+
+    /// Adds an uppercase transformation.
+    pub fn uppercase(mut self) -> Self {
+        self.push_transform(Transform::Uppercase);
+        self
+    }
+
+    /// Adds a Unicode NFC normalization transformation.
+    ///
+    /// Composes characters such as `e` followed by a combining acute accent
+    /// into `é`, so visually identical strings compare and measure equal.
+    pub fn normalize_nfc(mut self) -> Self {
+        self.push_transform(Transform::NormalizeNfc);
+        self
+    }
+
+    /// Adds a transformation that replaces each run of whitespace with a
+    /// single space.
+    ///
+    /// Leading and trailing whitespace is collapsed too, not removed;
+    /// combine with [`trim`](Self::trim) to remove it.
+    pub fn collapse_whitespace(mut self) -> Self {
+        self.push_transform(Transform::CollapseWhitespace);
+        self
+    }
+
+    /// Adds a custom transformation.
+    ///
+    /// Transforms run in the order they were added, before any constraint
+    /// is checked, and the result is the validated output. Like the
+    /// built-in transforms, the function should be idempotent, so that
+    /// validating an already-validated value leaves it unchanged; check
+    /// this in tests with
+    /// [`assert_normalization_idempotent`](crate::testing::assert_normalization_idempotent).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string()
+    ///     .trim()
+    ///     .map(|s| s.replace('-', ""))
+    ///     .collapse_whitespace()
+    ///     .uppercase()
+    ///     .max_len(8);
+    ///
+    /// let result = schema.validate(&json!("  sw1a -  1aa "), &JsonPath::root());
+    /// assert_eq!(result.into_result().unwrap(), "SW1A 1AA");
+    /// ```
+    pub fn map<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(String) -> String + Send + Sync + 'static,
+    {
+        self.push_transform(Transform::Map(Arc::new(transform)));
+        self
+    }
+
+    /// Runs every transform before every constraint, regardless of the
+    /// order they were declared in.
+    ///
+    /// By default steps run in builder order, so a constraint only sees the
+    /// transforms declared before it. This restores the behavior of earlier
+    /// versions, in which constraints always saw the fully transformed
+    /// value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, JsonPath};
+    /// use serde_json::json;
+    ///
+    /// let value = json!("  hi  ");
+    ///
+    /// // The length is checked before trimming
+    /// let ordered = Schema::string().min_len(5).trim();
+    /// assert!(ordered.validate(&value, &JsonPath::root()).is_success());
+    ///
+    /// let legacy = Schema::string().min_len(5).trim().transforms_first();
+    /// assert!(legacy.validate(&value, &JsonPath::root()).is_failure());
+    /// ```
+    pub fn transforms_first(mut self) -> Self {
+        self.transforms_first = true;
         self
     }
 
-    /// Adds a trim transformation.
-    pub fn trim(mut self) -> Self {
-        self.transforms.push(Transform::Trim);
-        self
+    fn push_transform(&mut self, transform: Transform) {
+        self.steps.push(Step::Transform(self.transforms.len()));
+        self.transforms.push(transform);
     }
 
-    /// Adds a lowercase transformation.
-    pub fn lowercase(mut self) -> Self {
-        self.transforms.push(Transform::Lowercase);
-        self
+    fn push_constraint(&mut self, constraint: StringConstraint) {
+        self.steps.push(Step::Constraint(self.constraints.len()));
+        self.constraints.push(constraint);
+    }
+
+    fn push_custom(&mut self, validator: CustomValidator) {
+        self.steps.push(Step::Custom(self.custom_validators.len()));
+        self.custom_validators.push(validator);
     }
 
     /// Adds a custom validator.
@@ -343,7 +1732,7 @@ impl StringSchema {
     where
         F: Fn(&str, &JsonPath) -> Validation<(), SchemaErrors> + Send + Sync + 'static,
     {
-        self.custom_validators.push(Arc::new(validator));
+        self.push_custom(Arc::new(validator));
         self
     }
 
@@ -352,6 +1741,16 @@ impl StringSchema {
     /// If no constraints have been added yet, this sets the type error message
     /// (used when the value is not a string).
     ///
+    /// The message may contain placeholders, filled in when the error is
+    /// reported: `{field}`, `{path}`, `{got}`, `{expected}`, and `{value}`,
+    /// plus the constraint parameters `{min}`, `{max}`, `{pattern}`,
+    /// `{format}`, `{values}`, `{prefix}`, `{suffix}`, `{substring}`, and
+    /// `{allowed}`. `{min}` and `{max}` come from the length bounds of the
+    /// whole schema, so either bound's message can mention both. Length
+    /// errors also have `{len}` and `{unit}` (`chars`, `bytes`, or
+    /// `graphemes`), which work well with plural and select placeholders:
+    /// `{min, plural, one {# character} other {# characters}}`.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -364,6 +1763,22 @@ impl StringSchema {
     ///
     /// let result = schema.validate(&json!("hi"), &JsonPath::root());
     /// // Error message will be "username must be at least 5 characters"
+    ///
+    /// let schema = Schema::string()
+    ///     .min_len(3)
+    ///     .max_len(8)
+    ///     .error("{field} must be {min} to {max} characters, got '{value}'");
+    ///
+    /// let path = JsonPath::root().push_field("username");
+    /// let errors = schema.validate(&json!("much too long"), &path).into_result().unwrap_err();
+    /// assert_eq!(errors.first().message, "username must be 3 to 8 characters, got 'much too long'");
+    ///
+    /// let schema = Schema::string()
+    ///     .min_len(1)
+    ///     .error("{field} needs at least {min, plural, one {# character} other {# characters}}");
+    ///
+    /// let errors = schema.validate(&json!(""), &path).into_result().unwrap_err();
+    /// assert_eq!(errors.first().message, "username needs at least 1 character");
     /// ```
     pub fn error(mut self, message: impl Into<String>) -> Self {
         if let Some(last) = self.constraints.last_mut() {
@@ -373,9 +1788,23 @@ impl StringSchema {
                 StringConstraint::Pattern { message: m, .. } => *m = Some(message.into()),
                 StringConstraint::Format { message: m, .. } => *m = Some(message.into()),
                 StringConstraint::OneOf { message: m, .. } => *m = Some(message.into()),
+                StringConstraint::NotOneOf { message: m, .. } => *m = Some(message.into()),
                 StringConstraint::StartsWith { message: m, .. } => *m = Some(message.into()),
                 StringConstraint::EndsWith { message: m, .. } => *m = Some(message.into()),
                 StringConstraint::Contains { message: m, .. } => *m = Some(message.into()),
+                StringConstraint::CharClass { message: m, .. } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::DateMin { message: m, .. } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::DateMax { message: m, .. } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::BeforeNow { message: m } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::BusinessHours { message: m, .. } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::WeekdayOnly { message: m, .. } => *m = Some(message.into()),
+                #[cfg(feature = "chrono")]
+                StringConstraint::NotHoliday { message: m, .. } => *m = Some(message.into()),
             }
         } else {
             self.type_error_message = Some(message.into());
@@ -383,6 +1812,40 @@ impl StringSchema {
         self
     }
 
+    /// Records where the most recent constraint was defined.
+    ///
+    /// Errors from that constraint carry the [`Provenance`] and mention it
+    /// when displayed. Like [`error`](Self::error), if no constraints have
+    /// been added yet this applies to the type check instead. Schema loaders
+    /// call this for each rule they read from a file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{JsonPath, Provenance, Schema};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::string()
+    ///     .defined_at(Provenance::new("schemas/user.json", 3))
+    ///     .min_len(3)
+    ///     .defined_at(Provenance::new("schemas/user.json", 4));
+    ///
+    /// let errors = schema.validate(&json!("ab"), &JsonPath::root()).into_result().unwrap_err();
+    /// assert!(errors.first().to_string().ends_with("(rule defined in schemas/user.json:4)"));
+    ///
+    /// let errors = schema.validate(&json!(null), &JsonPath::root()).into_result().unwrap_err();
+    /// assert!(errors.first().to_string().ends_with("(rule defined in schemas/user.json:3)"));
+    /// ```
+    pub fn defined_at(mut self, provenance: Provenance) -> Self {
+        if self.constraints.is_empty() {
+            self.type_provenance = Some(provenance);
+        } else {
+            self.constraint_provenance
+                .insert(self.constraints.len() - 1, provenance);
+        }
+        self
+    }
+
     /// Validates a value against this schema.
     ///
     /// Returns `Validation::Success` with the validated string if all
@@ -415,36 +1878,74 @@ impl StringSchema {
                     .type_error_message
                     .clone()
                     .unwrap_or_else(|| "expected string".to_string());
-                return Validation::Failure(SchemaErrors::single(
-                    SchemaError::new(path.clone(), message)
-                        .with_code("invalid_type")
-                        .with_got(value_type_name(value))
-                        .with_expected("string"),
-                ));
+                let mut error = SchemaError::new(path.clone(), message)
+                    .with_code("invalid_type")
+                    .with_got(describe_type(value))
+                    .with_expected("string");
+                if self.type_error_message.is_some() {
+                    error = render_message(error, &[]);
+                }
+                if value.is_number() || value.is_boolean() {
+                    error = error.with_suggestion(Repair::replace(
+                        RepairKind::Coerce,
+                        path.clone(),
+                        value.to_string(),
+                    ));
+                }
+                if let Some(provenance) = &self.type_provenance {
+                    error = error.with_provenance(provenance.clone());
+                }
+                return Validation::Failure(SchemaErrors::single(error));
             }
         };
 
-        // Apply transformations
+        // Each constraint sees the value as transformed by the steps that
+        // run before it
         let mut transformed = s.to_string();
-        for transform in &self.transforms {
-            transformed = match transform {
-                Transform::Trim => transformed.trim().to_string(),
-                Transform::Lowercase => transformed.to_lowercase(),
-            };
+        let mut errors: Vec<SchemaError> = Vec::new();
+        for step in self.pipeline().iter() {
+            match *step {
+                Step::Transform(i) => transformed = self.transforms[i].apply(transformed),
+                Step::Constraint(i) => {
+                    let constraint = &self.constraints[i];
+                    let unit = self.length_unit;
+                    if let Some(mut error) =
+                        check_constraint(constraint, &transformed, unit, self.strict_formats, path)
+                    {
+                        if constraint.message().is_some() {
+                            let params = self.template_params(i, &transformed);
+                            error = render_message(error, &params);
+                        }
+                        errors.push(match self.constraint_provenance.get(&i) {
+                            Some(provenance) => error.with_provenance(provenance.clone()),
+                            None => error,
+                        });
+                    }
+                }
+                Step::Custom(i) => {
+                    if let Validation::Failure(errs) = self.custom_validators[i](&transformed, path)
+                    {
+                        errors.extend(errs.into_vec());
+                    }
+                }
+            }
+        }
+        // Surrounding whitespace is the likeliest cause of any failure
+        // except being too short, which trimming can only make worse
+        if transformed.trim() != transformed {
+            for error in &mut errors {
+                if error.suggestion.is_none() && error.code != "min_length" {
+                    error.suggestion =
+                        Some(Repair::replace(RepairKind::Trim, path.clone(), s.trim()));
+                }
+            }
         }
 
-        // Collect all constraint violations
-        let mut errors: Vec<SchemaError> = self
-            .constraints
-            .iter()
-            .filter_map(|c| check_constraint(c, &transformed, path))
-            .collect();
-
-        // Run custom validators
-        for validator in &self.custom_validators {
-            match validator(&transformed, path) {
-                Validation::Success(_) => {}
-                Validation::Failure(errs) => {
+        // Errors inside an embedded document sit below this string's path,
+        // so they are added after the trim suggestions above
+        if let Some(schema) = &self.embedded_schema {
+            if let Ok(parsed) = serde_json::from_str::<Value>(&transformed) {
+                if let Validation::Failure(errs) = schema.validate_value(&parsed, path) {
                     errors.extend(errs.into_vec());
                 }
             }
@@ -456,6 +1957,40 @@ impl StringSchema {
             Validation::Failure(SchemaErrors::from_vec(errors))
         }
     }
+
+    /// Returns the message placeholders for the constraint at `index`:
+    /// the parameters of every constraint, then those of the failing one,
+    /// so that `{min}` and `{max}` work in a message on either bound.
+    fn template_params(&self, index: usize, value: &str) -> Vec<(&'static str, String)> {
+        let mut params: Vec<_> = self
+            .constraints
+            .iter()
+            .flat_map(StringConstraint::template_params)
+            .collect();
+        params.extend(self.constraints[index].template_params());
+        if let StringConstraint::MinLength { .. } | StringConstraint::MaxLength { .. } =
+            self.constraints[index]
+        {
+            params.push(("len", self.length_unit.measure(value).to_string()));
+            params.push(("unit", self.length_unit.name().to_string()));
+        }
+        if let Some(value) = cap(value, got_limit()) {
+            params.push(("value", value));
+        }
+        params
+    }
+
+    /// Returns the steps in the order they run.
+    fn pipeline(&self) -> Cow<'_, [Step]> {
+        if !self.transforms_first {
+            return Cow::Borrowed(&self.steps);
+        }
+        (0..self.transforms.len())
+            .map(Step::Transform)
+            .chain((0..self.constraints.len()).map(Step::Constraint))
+            .chain((0..self.custom_validators.len()).map(Step::Custom))
+            .collect()
+    }
 }
 
 impl Default for StringSchema {
@@ -474,6 +2009,12 @@ impl SchemaLike for StringSchema {
     fn validate_to_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors> {
         self.validate(value, path).map(Value::String)
     }
+
+    fn collect_refs(&self, refs: &mut Vec<String>) {
+        if let Some(schema) = &self.embedded_schema {
+            schema.collect_refs(refs);
+        }
+    }
 }
 
 impl ToJsonSchema for StringSchema {
@@ -482,21 +2023,78 @@ impl ToJsonSchema for StringSchema {
 
         for constraint in &self.constraints {
             match constraint {
-                StringConstraint::MinLength { min, .. } => {
-                    schema["minLength"] = json!(min);
+                // JSON Schema lengths count characters, so byte and grapheme
+                // limits have no faithful equivalent
+                StringConstraint::MinLength { min, .. }
+                    if self.length_unit == LengthUnit::Chars =>
+                {
+                    if let Some(min) = min.current() {
+                        schema["minLength"] = json!(min);
+                    }
                 }
-                StringConstraint::MaxLength { max, .. } => {
-                    schema["maxLength"] = json!(max);
+                StringConstraint::MaxLength { max, .. }
+                    if self.length_unit == LengthUnit::Chars =>
+                {
+                    if let Some(max) = max.current() {
+                        schema["maxLength"] = json!(max);
+                    }
                 }
                 StringConstraint::Pattern { pattern_str, .. } => {
                     schema["pattern"] = json!(pattern_str);
                 }
+                // Standard encodings are described by contentEncoding (RFC 4648 names)
+                StringConstraint::Format {
+                    format: Format::Base64,
+                    ..
+                } => {
+                    schema["contentEncoding"] = json!("base64");
+                }
+                StringConstraint::Format {
+                    format: Format::Hex,
+                    ..
+                } => {
+                    schema["contentEncoding"] = json!("base16");
+                }
+                StringConstraint::Format {
+                    format: Format::Json,
+                    ..
+                } => {
+                    schema["contentMediaType"] = json!("application/json");
+                    if let Some(embedded) = &self.embedded_schema {
+                        schema["contentSchema"] = embedded.to_json_schema();
+                    }
+                }
                 StringConstraint::Format { format, .. } => {
                     schema["format"] = json!(format.to_json_schema_format());
                 }
                 StringConstraint::OneOf { values, .. } => {
                     schema["enum"] = json!(values);
                 }
+                #[cfg(feature = "chrono")]
+                StringConstraint::DateMin { min, .. } => {
+                    schema["formatMinimum"] = json!(min.to_string());
+                }
+                #[cfg(feature = "chrono")]
+                StringConstraint::DateMax { max, .. } => {
+                    schema["formatMaximum"] = json!(max.to_string());
+                }
+                // A case-insensitive denylist cannot be expressed as an enum
+                StringConstraint::NotOneOf {
+                    values,
+                    ignore_case: false,
+                    ..
+                } => match schema["not"]["enum"].as_array_mut() {
+                    // Several denylists reject the union of their values
+                    Some(denied) => {
+                        for value in values {
+                            let value = json!(value);
+                            if !denied.contains(&value) {
+                                denied.push(value);
+                            }
+                        }
+                    }
+                    None => schema["not"] = json!({ "enum": values }),
+                },
                 // StartsWith, EndsWith, Contains don't have direct JSON Schema equivalents
                 // They could be represented as patterns, but we'll skip them for now
                 _ => {}
@@ -507,6 +2105,24 @@ impl ToJsonSchema for StringSchema {
     }
 }
 
+/// Replaces each run of whitespace with a single space.
+fn collapse_whitespace(s: &str) -> String {
+    let mut collapsed = String::with_capacity(s.len());
+    let mut in_whitespace = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
+
 /// Validates email format using a basic regex.
 fn validate_email(s: &str) -> bool {
     let re = Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap();
@@ -528,22 +2144,13 @@ fn validate_uuid(s: &str) -> bool {
 }
 
 /// Validates date format (YYYY-MM-DD).
+#[cfg(not(feature = "chrono"))]
 fn validate_date(s: &str) -> bool {
-    let re = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
-    if !re.is_match(s) {
-        return false;
-    }
-    let parts: Vec<&str> = s.split('-').collect();
-    if parts.len() != 3 {
-        return false;
-    }
-    let year: i32 = parts[0].parse().unwrap_or(0);
-    let month: u32 = parts[1].parse().unwrap_or(0);
-    let day: u32 = parts[2].parse().unwrap_or(0);
-    (1000..=9999).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day)
+    super::civil::Date::parse(s).is_some_and(|date| date.year >= 1000)
 }
 
 /// Validates datetime format (ISO 8601).
+#[cfg(not(feature = "chrono"))]
 fn validate_datetime(s: &str) -> bool {
     let re = Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}").unwrap();
     re.is_match(s)
@@ -569,49 +2176,238 @@ fn validate_ip(s: &str) -> bool {
     validate_ipv4(s) || validate_ipv6(s)
 }
 
+/// Validates E.164 phone number format (`+` and 7 to 15 digits).
+fn validate_phone(s: &str) -> bool {
+    let Some(digits) = s.strip_prefix('+') else {
+        return false;
+    };
+    if !(7..=15).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    // Country codes never start with 0, and these prefixes are spare or
+    // reserved in the ITU-T E.164 assignment list
+    let spare = [
+        "0", "28", "801", "802", "803", "804", "805", "806", "807", "83", "89",
+    ];
+    !spare.iter().any(|prefix| digits.starts_with(prefix))
+}
+
+/// Validates a credit card number: 12 to 19 digits passing the Luhn check.
+fn validate_credit_card(s: &str) -> bool {
+    let digits: Vec<u32> = match s
+        .chars()
+        .filter(|c| *c != ' ' && *c != '-')
+        .map(|c| c.to_digit(10))
+        .collect()
+    {
+        Some(digits) => digits,
+        None => return false,
+    };
+    if !(12..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => *d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Validates a URL slug: lowercase alphanumeric groups joined by single dashes.
+fn validate_slug(s: &str) -> bool {
+    s.split('-').all(|part| {
+        !part.is_empty()
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+    })
+}
+
+/// Validates an identifier: a letter or underscore, then letters, digits, or
+/// underscores.
+fn validate_identifier(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    matches!(bytes.next(), Some(b) if b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Validates base64 with the given two non-alphanumeric alphabet characters.
+///
+/// Padding is required for standard base64 and optional for base64url.
+fn validate_base64(s: &str, extra: [u8; 2], padding_required: bool) -> bool {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+    let alphabet_ok = data
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || extra.contains(&b));
+    if !alphabet_ok || padding > 2 || data.len() % 4 == 1 {
+        return false;
+    }
+    if padding == 0 && !padding_required {
+        return true;
+    }
+    s.len() % 4 == 0
+}
+
+/// Validates the structure of a JSON Web Token: a base64url header and
+/// payload that decode to JSON objects, and a base64url signature.
+fn validate_jwt(s: &str) -> bool {
+    let mut segments = s.split('.');
+    let (Some(header), Some(payload), Some(signature), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return false;
+    };
+    let is_object = |segment: &str| {
+        decode_base64url(segment)
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            .is_some_and(|value| value.is_object())
+    };
+    is_object(header) && is_object(payload) && decode_base64url(signature).is_some()
+}
+
+/// Decodes unpadded base64url, returning `None` for invalid input.
+fn decode_base64url(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for b in s.bytes() {
+        let sextet = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Validates a MAC address: six hex pairs separated by all colons or all
+/// dashes.
+fn validate_mac_address(s: &str) -> bool {
+    let Some(separator) = s.chars().find(|c| *c == ':' || *c == '-') else {
+        return false;
+    };
+    let groups: Vec<&str> = s.split(separator).collect();
+    groups.len() == 6
+        && groups
+            .iter()
+            .all(|g| g.len() == 2 && g.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Validates an even-length string of hex digits.
+fn validate_hex(s: &str) -> bool {
+    s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Masks all but the last four characters of a card number.
+fn mask_card_number(s: &str) -> String {
+    let count = s.chars().count();
+    s.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i + 4 < count && c.is_ascii_digit() {
+                '*'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
 /// Checks a single constraint and returns an error if it fails.
+#[cfg_attr(not(feature = "strict-formats"), allow(unused_variables))]
 fn check_constraint(
     constraint: &StringConstraint,
     value: &str,
+    unit: LengthUnit,
+    strict: bool,
     path: &JsonPath,
 ) -> Option<SchemaError> {
     match constraint {
         StringConstraint::MinLength { min, message } => {
-            let len = value.chars().count();
-            if len < *min {
+            let Some(min) = min.current() else {
+                return Some(min.unresolved(path));
+            };
+            let len = unit.measure(value);
+            if len < min {
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("length must be at least {}, got {}", min, len));
                 Some(
                     SchemaError::new(path.clone(), msg)
                         .with_code("min_length")
-                        .with_expected(format!("at least {} characters", min))
-                        .with_got(format!("{} characters", len)),
+                        .with_expected(format!("at least {}", unit.count(min)))
+                        .with_got(unit.count(len)),
                 )
             } else {
                 None
             }
         }
         StringConstraint::MaxLength { max, message } => {
-            let len = value.chars().count();
-            if len > *max {
+            let Some(max) = max.current() else {
+                return Some(max.unresolved(path));
+            };
+            let len = unit.measure(value);
+            if len > max {
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("length must be at most {}, got {}", max, len));
                 Some(
                     SchemaError::new(path.clone(), msg)
                         .with_code("max_length")
-                        .with_expected(format!("at most {} characters", max))
-                        .with_got(format!("{} characters", len)),
+                        .with_expected(format!("at most {}", unit.count(max)))
+                        .with_got(unit.count(len))
+                        .with_suggestion(Repair::replace(
+                            RepairKind::Truncate,
+                            path.clone(),
+                            unit.truncate(value, max),
+                        )),
                 )
             } else {
                 None
             }
         }
+        StringConstraint::Pattern {
+            pattern_str,
+            max_input: Some(max),
+            message,
+            ..
+        } if value.len() > *max => {
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| format!("is too long to match pattern '{}'", pattern_str));
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("pattern_timeout")
+                    .with_expected(format!("at most {}", count(*max, "byte", "bytes")))
+                    .with_got(count(value.len(), "byte", "bytes")),
+            )
+        }
         StringConstraint::Pattern {
             regex,
             pattern_str,
             message,
+            ..
         } => {
             if !regex.is_match(value) {
                 let msg = message
@@ -621,7 +2417,7 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("pattern")
                         .with_expected(format!("string matching '{}'", pattern_str))
-                        .with_got(value.to_string()),
+                        .with_got(value),
                 )
             } else {
                 None
@@ -629,32 +2425,155 @@ fn check_constraint(
         }
         StringConstraint::Format { format, message } => {
             let (is_valid, format_name, code) = match format {
+                #[cfg(feature = "strict-formats")]
+                Format::Email if strict => (
+                    super::strict::is_email(value),
+                    "valid email",
+                    "invalid_email",
+                ),
+                #[cfg(feature = "strict-formats")]
+                Format::Uuid if strict => {
+                    (super::strict::is_uuid(value), "valid UUID", "invalid_uuid")
+                }
+                #[cfg(feature = "strict-formats")]
+                Format::Ip if strict => (
+                    super::strict::is_ip(value),
+                    "valid IP address",
+                    "invalid_ip",
+                ),
+                #[cfg(feature = "strict-formats")]
+                Format::Ipv4 if strict => (
+                    super::strict::is_ipv4(value),
+                    "valid IPv4 address",
+                    "invalid_ipv4",
+                ),
+                #[cfg(feature = "strict-formats")]
+                Format::Ipv6 if strict => (
+                    super::strict::is_ipv6(value),
+                    "valid IPv6 address",
+                    "invalid_ipv6",
+                ),
                 Format::Email => (validate_email(value), "valid email", "invalid_email"),
                 Format::Url => (validate_url(value), "valid URL", "invalid_url"),
                 Format::Uuid => (validate_uuid(value), "valid UUID", "invalid_uuid"),
+                #[cfg(feature = "chrono")]
+                Format::Date => (
+                    super::calendar::parse_date(value).is_some(),
+                    "valid date (YYYY-MM-DD)",
+                    "invalid_date",
+                ),
+                #[cfg(not(feature = "chrono"))]
                 Format::Date => (
                     validate_date(value),
                     "valid date (YYYY-MM-DD)",
                     "invalid_date",
                 ),
+                #[cfg(feature = "chrono")]
+                Format::DateTime => (
+                    super::calendar::parse_datetime(value).is_some(),
+                    "valid ISO 8601 datetime",
+                    "invalid_datetime",
+                ),
+                #[cfg(not(feature = "chrono"))]
                 Format::DateTime => (
                     validate_datetime(value),
                     "valid ISO 8601 datetime",
                     "invalid_datetime",
                 ),
+                #[cfg(feature = "chrono")]
+                Format::DateTimeWithOffset => (
+                    matches!(
+                        super::calendar::parse_datetime(value),
+                        Some(super::calendar::ParsedDateTime::Offset(_))
+                    ),
+                    "valid RFC 3339 datetime with timezone offset",
+                    "invalid_datetime",
+                ),
                 Format::Ip => (validate_ip(value), "valid IP address", "invalid_ip"),
                 Format::Ipv4 => (validate_ipv4(value), "valid IPv4 address", "invalid_ipv4"),
                 Format::Ipv6 => (validate_ipv6(value), "valid IPv6 address", "invalid_ipv6"),
+                Format::Phone => (
+                    validate_phone(value),
+                    "valid E.164 phone number",
+                    "invalid_phone",
+                ),
+                Format::CreditCard => (
+                    validate_credit_card(value),
+                    "valid credit card number",
+                    "invalid_credit_card",
+                ),
+                Format::Slug => (
+                    validate_slug(value),
+                    "valid slug (lowercase letters, digits, and dashes)",
+                    "invalid_slug",
+                ),
+                Format::Identifier => (
+                    validate_identifier(value),
+                    "valid identifier",
+                    "invalid_identifier",
+                ),
+                Format::Base64 => (
+                    validate_base64(value, [b'+', b'/'], true),
+                    "valid base64",
+                    "invalid_base64",
+                ),
+                Format::Base64Url => (
+                    validate_base64(value, [b'-', b'_'], false),
+                    "valid base64url",
+                    "invalid_base64url",
+                ),
+                Format::Hex => (validate_hex(value), "valid hex string", "invalid_hex"),
+                Format::Jwt => (validate_jwt(value), "valid JWT", "invalid_jwt"),
+                Format::MacAddress => (
+                    validate_mac_address(value),
+                    "valid MAC address",
+                    "invalid_mac",
+                ),
+                Format::CountryCode => (
+                    is_country_code(value),
+                    "valid ISO 3166-1 alpha-2 country code",
+                    "invalid_country_code",
+                ),
+                Format::CurrencyCode => (
+                    is_currency_code(value),
+                    "valid ISO 4217 currency code",
+                    "invalid_currency_code",
+                ),
+                Format::LanguageTag => (
+                    is_language_tag(value),
+                    "valid BCP 47 language tag",
+                    "invalid_language_tag",
+                ),
+                Format::Json => (
+                    serde_json::from_str::<Value>(value).is_ok(),
+                    "valid JSON",
+                    "invalid_json",
+                ),
+                Format::Duration => (
+                    Duration::parse_iso8601(value).is_some(),
+                    "valid ISO 8601 duration",
+                    "invalid_duration",
+                ),
+                Format::HumanDuration => (
+                    Duration::parse_human(value).is_some(),
+                    "valid duration",
+                    "invalid_duration",
+                ),
             };
             if !is_valid {
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("must be {}", format_name));
+                // Never echo full card numbers into error reports
+                let got = match format {
+                    Format::CreditCard => Cow::Owned(mask_card_number(value)),
+                    _ => Cow::Borrowed(value),
+                };
                 Some(
                     SchemaError::new(path.clone(), msg)
                         .with_code(code)
                         .with_expected(format_name)
-                        .with_got(value.to_string()),
+                        .with_got(got),
                 )
             } else {
                 None
@@ -665,11 +2584,44 @@ fn check_constraint(
                 let msg = message
                     .clone()
                     .unwrap_or_else(|| format!("must be one of: {}", values.join(", ")));
+                let mut error = SchemaError::new(path.clone(), msg)
+                    .with_code("invalid_enum")
+                    .with_expected(format!("one of: {}", values.join(", ")))
+                    .with_got(value);
+                // Suggest the allowed value this one matches ignoring case
+                let lower = value.to_lowercase();
+                if let Some(canonical) = values.iter().find(|v| v.to_lowercase() == lower) {
+                    error = error.with_suggestion(Repair::replace(
+                        RepairKind::Canonicalize,
+                        path.clone(),
+                        canonical.clone(),
+                    ));
+                }
+                Some(error)
+            } else {
+                None
+            }
+        }
+        StringConstraint::NotOneOf {
+            values,
+            ignore_case,
+            message,
+        } => {
+            let forbidden = if *ignore_case {
+                let lowered = value.to_lowercase();
+                values.iter().any(|v| v.to_lowercase() == lowered)
+            } else {
+                values.iter().any(|v| v == value)
+            };
+            if forbidden {
+                let msg = message
+                    .clone()
+                    .unwrap_or_else(|| format!("must not be one of: {}", values.join(", ")));
                 Some(
                     SchemaError::new(path.clone(), msg)
-                        .with_code("invalid_enum")
-                        .with_expected(format!("one of: {}", values.join(", ")))
-                        .with_got(value.to_string()),
+                        .with_code("forbidden_value")
+                        .with_expected(format!("none of: {}", values.join(", ")))
+                        .with_got(value),
                 )
             } else {
                 None
@@ -684,7 +2636,7 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("invalid_prefix")
                         .with_expected(format!("string starting with '{}'", prefix))
-                        .with_got(value.to_string()),
+                        .with_got(value),
                 )
             } else {
                 None
@@ -699,7 +2651,7 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("invalid_suffix")
                         .with_expected(format!("string ending with '{}'", suffix))
-                        .with_got(value.to_string()),
+                        .with_got(value),
                 )
             } else {
                 None
@@ -714,24 +2666,143 @@ fn check_constraint(
                     SchemaError::new(path.clone(), msg)
                         .with_code("invalid_substring")
                         .with_expected(format!("string containing '{}'", substring))
-                        .with_got(value.to_string()),
+                        .with_got(value),
                 )
             } else {
                 None
             }
         }
-    }
-}
+        StringConstraint::CharClass { class, message } => {
+            let (position, found) = value.chars().enumerate().find(|(_, c)| !class.allows(*c))?;
+            let (code, allowed) = class.describe();
+            let msg = message.clone().unwrap_or_else(|| {
+                format!(
+                    "must contain {}, found {:?} at position {}",
+                    allowed, found, position
+                )
+            });
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code(code)
+                    .with_expected(format!("string with {}", allowed))
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::DateMin { min, message } => {
+            let date = super::calendar::date_of(value)?;
+            if date >= *min {
+                return None;
+            }
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| format!("must be on or after {}", min));
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("min_date")
+                    .with_expected(format!("date on or after {}", min))
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::DateMax { max, message } => {
+            let date = super::calendar::date_of(value)?;
+            if date <= *max {
+                return None;
+            }
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| format!("must be on or before {}", max));
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("max_date")
+                    .with_expected(format!("date on or before {}", max))
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::BeforeNow { message } => {
+            if super::calendar::is_before_now(value)? {
+                return None;
+            }
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| "must be in the past".to_string());
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("future_datetime")
+                    .with_expected("datetime in the past")
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::BusinessHours {
+            zone,
+            start,
+            end,
+            message,
+        } => {
+            use chrono::Timelike;
+
+            let local = zone.local_datetime(value)?;
+            if (*start..*end).contains(&local.hour()) {
+                return None;
+            }
+            let msg = message.clone().unwrap_or_else(|| {
+                format!(
+                    "must be between {:02}:00 and {:02}:00 in {}, got {}",
+                    start,
+                    end,
+                    zone.name(),
+                    local.format("%H:%M")
+                )
+            });
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("outside_business_hours")
+                    .with_expected(format!(
+                        "time between {:02}:00 and {:02}:00 in {}",
+                        start,
+                        end,
+                        zone.name()
+                    ))
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::WeekdayOnly { zone, message } => {
+            use chrono::{Datelike, Weekday};
 
-/// Returns the JSON type name for a value.
-fn value_type_name(value: &Value) -> &'static str {
-    match value {
-        Value::Null => "null",
-        Value::Bool(_) => "boolean",
-        Value::Number(_) => "number",
-        Value::String(_) => "string",
-        Value::Array(_) => "array",
-        Value::Object(_) => "object",
+            let weekday = zone.local_date(value)?.weekday();
+            if !matches!(weekday, Weekday::Sat | Weekday::Sun) {
+                return None;
+            }
+            let msg = message.clone().unwrap_or_else(|| {
+                format!("must fall on a weekday in {}, got {}", zone.name(), weekday)
+            });
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("not_weekday")
+                    .with_expected(format!("Monday to Friday in {}", zone.name()))
+                    .with_got(value),
+            )
+        }
+        #[cfg(feature = "chrono")]
+        StringConstraint::NotHoliday { calendar, message } => {
+            let date = super::calendar::date_of(value)?;
+            if !calendar.is_holiday(date) {
+                return None;
+            }
+            let msg = message
+                .clone()
+                .unwrap_or_else(|| format!("must not fall on a holiday, got {}", date));
+            Some(
+                SchemaError::new(path.clone(), msg)
+                    .with_code("holiday")
+                    .with_expected("date that is not a holiday")
+                    .with_got(value),
+            )
+        }
     }
 }
 
@@ -811,6 +2882,54 @@ mod tests {
         assert_eq!(errors.first().code, "max_length");
     }
 
+    #[test]
+    fn test_max_len_truncation_suggestion() {
+        let truncated = |schema: StringSchema, value: &str| {
+            let errors = unwrap_failure(schema.validate(&json!(value), &JsonPath::root()));
+            let repair = errors.first().suggestion.clone().unwrap();
+            assert_eq!(repair.kind, RepairKind::Truncate);
+            repair.value.unwrap()
+        };
+
+        assert_eq!(truncated(StringSchema::new().max_len(3), "héllo"), "hél");
+        assert_eq!(
+            truncated(
+                StringSchema::new().max_len(2).len_unit(LengthUnit::Bytes),
+                "héllo"
+            ),
+            "h"
+        );
+        assert_eq!(
+            truncated(
+                StringSchema::new()
+                    .max_len(1)
+                    .len_unit(LengthUnit::Graphemes),
+                "e\u{301}x"
+            ),
+            "e\u{301}"
+        );
+    }
+
+    #[test]
+    fn test_type_and_enum_suggestions() {
+        let errors = unwrap_failure(StringSchema::new().validate(&json!(42), &JsonPath::root()));
+        let repair = errors.first().suggestion.as_ref().unwrap();
+        assert_eq!(repair.kind, RepairKind::Coerce);
+        assert_eq!(repair.value, Some(json!("42")));
+
+        let errors = unwrap_failure(StringSchema::new().validate(&json!(null), &JsonPath::root()));
+        assert!(errors.first().suggestion.is_none());
+
+        let schema = StringSchema::new().one_of(["pending", "active"]);
+        let errors = unwrap_failure(schema.validate(&json!("Active"), &JsonPath::root()));
+        let repair = errors.first().suggestion.as_ref().unwrap();
+        assert_eq!(repair.kind, RepairKind::Canonicalize);
+        assert_eq!(repair.value, Some(json!("active")));
+
+        let errors = unwrap_failure(schema.validate(&json!("done"), &JsonPath::root()));
+        assert!(errors.first().suggestion.is_none());
+    }
+
     #[test]
     fn test_combined_length_constraints() {
         let schema = StringSchema::new().min_len(5).max_len(10);
@@ -859,6 +2978,35 @@ mod tests {
         assert!(errors.first().message.contains(r"^\d+$"));
     }
 
+    #[test]
+    fn test_pattern_limits() {
+        let limits = PatternLimits::default().size_limit(10_000);
+        assert!(StringSchema::new()
+            .pattern_with_limits(r"\w{1,1000}", limits)
+            .is_err());
+        assert!(StringSchema::new()
+            .pattern_with_limits(r"^[a-z]{1,10}$", limits)
+            .is_ok());
+
+        let nested = PatternLimits::default().nest_limit(3);
+        assert!(StringSchema::new()
+            .pattern_with_limits(r"(a)", nested)
+            .is_ok());
+        assert!(StringSchema::new()
+            .pattern_with_limits(r"((((((a))))))", nested)
+            .is_err());
+
+        let schema = StringSchema::new()
+            .pattern_with_limits(r"^\d+$", limits.max_input(5))
+            .unwrap();
+        assert!(schema
+            .validate(&json!("12345"), &JsonPath::root())
+            .is_success());
+        let errors = unwrap_failure(schema.validate(&json!("123456"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "pattern_timeout");
+        assert_eq!(errors.first().got.as_deref(), Some("6 bytes"));
+    }
+
     #[test]
     fn test_custom_error_message() {
         let schema = StringSchema::new().min_len(5).error("username too short");
@@ -879,6 +3027,33 @@ mod tests {
         assert_eq!(errors.first().message, "must be a string");
     }
 
+    #[test]
+    fn test_error_message_placeholders() {
+        let schema = StringSchema::new()
+            .min_len(2)
+            .error("{field} needs {min}-{max} chars, got '{value}'")
+            .max_len(4)
+            .pattern(r"^[a-z]{2,}$")
+            .unwrap()
+            .error("{field} must match {pattern}: '{value}'");
+        let path = JsonPath::root().push_field("code");
+
+        let errors = unwrap_failure(schema.validate(&json!("A"), &path));
+        assert_eq!(errors.first().message, "code needs 2-4 chars, got 'A'");
+        assert_eq!(
+            errors.iter().nth(1).unwrap().message,
+            "code must match ^[a-z]{2,}$: 'A'"
+        );
+
+        // Constraints without a custom message keep the default
+        let errors = unwrap_failure(schema.validate(&json!("abcdef"), &path));
+        assert_eq!(errors.first().message, "length must be at most 4, got 6");
+
+        let schema = StringSchema::new().error("{path} must be text, not {got}");
+        let errors = unwrap_failure(schema.validate(&json!(1), &JsonPath::root()));
+        assert_eq!(errors.first().message, "(root) must be text, not number");
+    }
+
     #[test]
     fn test_error_accumulation() {
         let schema = StringSchema::new().min_len(10).pattern(r"^\d+$").unwrap();
@@ -907,24 +3082,69 @@ mod tests {
     fn test_empty_string() {
         let schema = StringSchema::new().min_len(1);
 
-        let result = schema.validate(&json!(""), &JsonPath::root());
+        let result = schema.validate(&json!(""), &JsonPath::root());
+        assert!(result.is_failure());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().code, "min_length");
+    }
+
+    #[test]
+    fn test_unicode_length() {
+        // Unicode strings should count characters, not bytes
+        let schema = StringSchema::new().min_len(3).max_len(5);
+
+        // "日本語" is 3 characters
+        let result = schema.validate(&json!("日本語"), &JsonPath::root());
+        assert!(result.is_success());
+
+        // "🎉🎊" is 2 characters
+        let result = schema.validate(&json!("🎉🎊"), &JsonPath::root());
+        assert!(result.is_failure());
+    }
+
+    #[test]
+    fn test_byte_length_unit() {
+        let schema = StringSchema::new().max_len(4).len_unit(LengthUnit::Bytes);
+
+        let result = schema.validate(&json!("abcd"), &JsonPath::root());
+        assert!(result.is_success());
+
+        // "日本" is 2 characters but 6 bytes
+        let result = schema.validate(&json!("日本"), &JsonPath::root());
+        assert!(result.is_failure());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().code, "max_length");
+        assert_eq!(errors.first().got, Some("6 bytes".to_string()));
+    }
+
+    #[test]
+    fn test_grapheme_length_unit() {
+        let schema = StringSchema::new()
+            .min_len(2)
+            .max_len(2)
+            .len_unit(LengthUnit::Graphemes);
+
+        // Family emoji is 7 scalar values joined with ZWJ, but one grapheme
+        let result = schema.validate(&json!("👨‍👩‍👧‍👦a"), &JsonPath::root());
+        assert!(result.is_success());
+
+        let result = schema.validate(&json!("e\u{301}"), &JsonPath::root());
         assert!(result.is_failure());
         let errors = unwrap_failure(result);
         assert_eq!(errors.first().code, "min_length");
+        assert_eq!(errors.first().got, Some("1 grapheme".to_string()));
     }
 
     #[test]
-    fn test_unicode_length() {
-        // Unicode strings should count characters, not bytes
-        let schema = StringSchema::new().min_len(3).max_len(5);
-
-        // "日本語" is 3 characters
-        let result = schema.validate(&json!("日本語"), &JsonPath::root());
-        assert!(result.is_success());
+    fn test_non_char_length_unit_omitted_from_json_schema() {
+        let schema = StringSchema::new()
+            .max_len(10)
+            .len_unit(LengthUnit::Bytes)
+            .to_json_schema();
+        assert!(schema.get("maxLength").is_none());
 
-        // "🎉🎊" is 2 characters
-        let result = schema.validate(&json!("🎉🎊"), &JsonPath::root());
-        assert!(result.is_failure());
+        let schema = StringSchema::new().max_len(10).to_json_schema();
+        assert_eq!(schema["maxLength"], json!(10));
     }
 
     #[test]
@@ -997,6 +3217,15 @@ mod tests {
         let result = schema.validate(&json!("2025-13-01"), &JsonPath::root());
         assert!(result.is_failure());
 
+        let result = schema.validate(&json!("2025-02-31"), &JsonPath::root());
+        assert!(result.is_failure());
+
+        let result = schema.validate(&json!("2024-02-29"), &JsonPath::root());
+        assert!(result.is_success());
+
+        let result = schema.validate(&json!("2023-02-29"), &JsonPath::root());
+        assert!(result.is_failure());
+
         let result = schema.validate(&json!("invalid-date"), &JsonPath::root());
         assert!(result.is_failure());
         let errors = unwrap_failure(result);
@@ -1067,6 +3296,534 @@ mod tests {
         assert_eq!(errors.first().code, "invalid_ip");
     }
 
+    #[test]
+    fn test_phone_format() {
+        let schema = StringSchema::new().phone();
+
+        for valid in ["+14155552671", "+442071838750", "+6831234", "+80012345678"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "14155552671",       // missing +
+            "+1 415 555 2671",   // separators
+            "+123456",           // too short
+            "+1234567890123456", // too long
+            "+04155552671",      // country code starts with 0
+            "+2851234567",       // spare country code
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_phone", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_credit_card_format() {
+        let schema = StringSchema::new().credit_card();
+
+        for valid in [
+            "4111111111111111",
+            "4111-1111-1111-1111",
+            "5500 0000 0000 0004",
+            "378282246310005",
+        ] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "4111111111111112",     // Luhn failure
+            "4111 1111 1111 111a",  // non-digit
+            "42424242424",          // too short
+            "41111111111111111111", // too long
+            "",
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_credit_card", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_slug_format() {
+        let schema = StringSchema::new().slug();
+
+        for valid in ["hello", "hello-world", "2024-recap", "a1-b2-c3"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "",
+            "Hello",
+            "hello_world",
+            "hello world",
+            "-hello",
+            "hello-",
+            "hello--world",
+            "héllo",
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_slug", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_identifier_format() {
+        let schema = StringSchema::new().identifier();
+
+        for valid in ["x", "_private", "userId", "user_id_2", "__init__"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in ["", "2fa", "user-id", "user id", "naïve", "a.b"] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_identifier", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_base64_format() {
+        let schema = StringSchema::new().base64();
+
+        for valid in ["", "aGVsbG8=", "aGk=", "aGVsbG8gd29ybGQh", "+/+/"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "aGVsbG8",   // missing padding
+            "aGk===",    // too much padding
+            "a=Vs",      // padding inside
+            "aGVs bG8=", // whitespace
+            "-_-_",      // url-safe alphabet
+            "a",
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_base64", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_base64url_format() {
+        let schema = StringSchema::new().base64url();
+
+        for valid in ["", "aGVsbG8", "aGVsbG8=", "aGk", "-_-_"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in ["+/+/", "aGk==", "aGVsb", "aGVsbG8==="] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_base64url", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_jwt_format() {
+        let schema = StringSchema::new().jwt();
+
+        // Header {"alg":"HS256","typ":"JWT"}, payload {"sub":"1234567890"}
+        let header = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
+        let payload = "eyJzdWIiOiIxMjM0NTY3ODkwIn0";
+        let signed = format!(
+            "{}.{}.SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c",
+            header, payload
+        );
+        let unsecured = format!("{}.{}.", header, payload);
+
+        for valid in [signed.as_str(), unsecured.as_str()] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        // "WzFd" is [1], "bnVsbA" is null, and "e30=" is padded {}
+        let array_payload = format!("{}.WzFd.", header);
+        let null_header = format!("bnVsbA.{}.", payload);
+        let padded = format!("{}.e30=.", header);
+        let bad_signature = format!("{}.{}.a+b", header, payload);
+        let too_many = format!("{}.{}..", header, payload);
+        for invalid in [
+            "",
+            "abc",
+            header,
+            array_payload.as_str(),
+            null_header.as_str(),
+            padded.as_str(),
+            bad_signature.as_str(),
+            too_many.as_str(),
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_jwt", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_duration_formats() {
+        let iso = StringSchema::new().duration();
+        let human = StringSchema::new().human_duration();
+
+        for valid in ["P3DT4H", "PT30M", "P1Y", "PT0.5S"] {
+            assert!(iso.validate(&json!(valid), &JsonPath::root()).is_success());
+        }
+        for valid in ["90s", "1h 30min", "2 days"] {
+            assert!(human
+                .validate(&json!(valid), &JsonPath::root())
+                .is_success());
+        }
+
+        let errors = unwrap_failure(iso.validate(&json!("3 days"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_duration");
+        assert_eq!(
+            errors.first().expected.as_deref(),
+            Some("valid ISO 8601 duration")
+        );
+
+        let errors = unwrap_failure(human.validate(&json!("P3D"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_duration");
+    }
+
+    #[test]
+    fn test_mac_address_format() {
+        let schema = StringSchema::new().mac_address();
+
+        for valid in [
+            "00:1a:2b:3c:4d:5e",
+            "00-1A-2B-3C-4D-5E",
+            "FF:ff:Ff:fF:00:00",
+        ] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in [
+            "",
+            "001a2b3c4d5e",
+            "00:1a-2b:3c:4d:5e",
+            "00:1a:2b:3c:4d",
+            "00:1a:2b:3c:4d:5e:6f",
+            "0:1a:2b:3c:4d:5e",
+            "00:1a:2b:3c:4d:5g",
+            "001a.2b3c.4d5e",
+        ] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_mac", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_country_and_currency_code_formats() {
+        let country = StringSchema::new().country_code();
+        for valid in ["US", "DE", "JP", "ZW"] {
+            let result = country.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+        for invalid in ["", "us", "USA", "XX", "U1"] {
+            let errors = unwrap_failure(country.validate(&json!(invalid), &JsonPath::root()));
+            assert_eq!(errors.first().code, "invalid_country_code", "{}", invalid);
+        }
+
+        let currency = StringSchema::new().currency_code();
+        for valid in ["USD", "EUR", "JPY", "XAU"] {
+            let result = currency.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+        for invalid in ["", "usd", "US", "ABC", "DEM"] {
+            let errors = unwrap_failure(currency.validate(&json!(invalid), &JsonPath::root()));
+            assert_eq!(errors.first().code, "invalid_currency_code", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_language_tag_format() {
+        let schema = StringSchema::new().language_tag();
+
+        let result = schema.validate(&json!("en-US"), &JsonPath::root());
+        assert!(result.is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!("en_US"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_language_tag");
+        assert_eq!(errors.first().message, "must be valid BCP 47 language tag");
+    }
+
+    #[test]
+    fn test_json_format() {
+        let schema = StringSchema::new().json();
+
+        for valid in [r#"{"a": [1, 2]}"#, "null", "42", r#""text""#] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in ["", "{", "{a: 1}", "[1, 2,]", "undefined"] {
+            let errors = unwrap_failure(schema.validate(&json!(invalid), &JsonPath::root()));
+            assert_eq!(errors.first().code, "invalid_json", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_json_matching_reroots_nested_errors() {
+        use crate::schema::{IntegerSchema, ObjectSchema};
+
+        let schema = StringSchema::new().json_matching(
+            ObjectSchema::new()
+                .field("retries", IntegerSchema::new().non_negative())
+                .field("name", StringSchema::new().min_len(1)),
+        );
+        let path = JsonPath::root().push_field("config");
+
+        let result = schema.validate(&json!(r#"{"retries": 2, "name": "x"}"#), &path);
+        assert_eq!(unwrap_success(result), r#"{"retries": 2, "name": "x"}"#);
+
+        let errors = unwrap_failure(schema.validate(&json!(r#"{"retries": -1}"#), &path));
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, vec!["config.retries", "config.name"]);
+
+        // A string that does not parse reports only the parse failure
+        let errors = unwrap_failure(schema.validate(&json!("{"), &path));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_json");
+        assert_eq!(errors.first().path.to_string(), "config");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_calendar_datetime() {
+        let schema = StringSchema::new().datetime();
+        assert!(schema
+            .validate(&json!("2025-11-28T14:30:00.5-08:00"), &JsonPath::root())
+            .is_success());
+        for invalid in [
+            "2025-02-30T10:00:00Z",
+            "2025-11-28T25:00:00Z",
+            "2025-11-28T14:30:00 trailing",
+        ] {
+            let errors = unwrap_failure(schema.validate(&json!(invalid), &JsonPath::root()));
+            assert_eq!(errors.first().code, "invalid_datetime", "{}", invalid);
+        }
+
+        let schema = StringSchema::new().datetime_with_offset();
+        assert!(schema
+            .validate(&json!("2025-11-28T14:30:00Z"), &JsonPath::root())
+            .is_success());
+        let errors =
+            unwrap_failure(schema.validate(&json!("2025-11-28T14:30:00"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "invalid_datetime");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_date_range() {
+        let schema = StringSchema::new()
+            .datetime()
+            .date_min("2020-01-01")
+            .date_max("2020-12-31");
+
+        assert!(schema
+            .validate(&json!("2020-06-15T12:00:00Z"), &JsonPath::root())
+            .is_success());
+
+        let errors =
+            unwrap_failure(schema.validate(&json!("2019-12-31T23:59:59Z"), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "min_date");
+        assert_eq!(errors.first().message, "must be on or after 2020-01-01");
+
+        let errors =
+            unwrap_failure(schema.validate(&json!("2021-01-01T00:00:00Z"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "max_date");
+
+        // Unparseable values are only reported by the format constraint
+        let errors = unwrap_failure(schema.validate(&json!("soon"), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "invalid_datetime");
+
+        let json_schema = schema.to_json_schema();
+        assert_eq!(json_schema["formatMinimum"], "2020-01-01");
+        assert_eq!(json_schema["formatMaximum"], "2020-12-31");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    #[should_panic(expected = "date_min expects a YYYY-MM-DD date")]
+    fn test_date_min_rejects_invalid_bound() {
+        let _ = StringSchema::new().date_min("2020-02-30");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_datetime_before_now() {
+        let schema = StringSchema::new()
+            .datetime()
+            .datetime_before_now()
+            .error("cannot be in the future");
+
+        assert!(schema
+            .validate(&json!("2020-01-01T00:00:00Z"), &JsonPath::root())
+            .is_success());
+
+        let errors =
+            unwrap_failure(schema.validate(&json!("9999-12-31T23:59:59Z"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "future_datetime");
+        assert_eq!(errors.first().message, "cannot be in the future");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_business_rules() {
+        use chrono::{FixedOffset, NaiveDate};
+        use std::collections::HashSet;
+
+        let berlin_winter = FixedOffset::east_opt(3600).unwrap();
+        let christmas: HashSet<NaiveDate> = [NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()].into();
+        let schema = StringSchema::new()
+            .datetime()
+            .business_hours(berlin_winter, 9..17)
+            .weekday_only(berlin_winter)
+            .not_holiday(christmas);
+
+        let codes = |value: &str| match schema.validate(&json!(value), &JsonPath::root()) {
+            Validation::Success(_) => Vec::new(),
+            Validation::Failure(errors) => errors.iter().map(|e| e.code.clone()).collect(),
+        };
+
+        // Tuesday 09:00 and 16:59 in Berlin
+        assert!(codes("2025-12-02T08:00:00Z").is_empty());
+        assert!(codes("2025-12-02T15:59:59Z").is_empty());
+        assert_eq!(codes("2025-12-02T16:00:00Z"), ["outside_business_hours"]);
+        assert_eq!(codes("2025-12-02T07:59:00Z"), ["outside_business_hours"]);
+        // Datetimes without an offset are Berlin time
+        assert!(codes("2025-12-02T16:30:00").is_empty());
+        // Friday 23:30 UTC is Saturday in Berlin
+        assert_eq!(
+            codes("2025-12-05T23:30:00Z"),
+            ["outside_business_hours", "not_weekday"]
+        );
+        assert_eq!(codes("2025-12-25T10:00:00+01:00"), ["holiday"]);
+        // Other values are left to the format check
+        assert_eq!(codes("not a datetime"), ["invalid_datetime"]);
+
+        let errors = unwrap_failure(
+            StringSchema::new()
+                .business_hours(berlin_winter, 9..17)
+                .error("book between {start} and {end} ({zone})")
+                .validate(&json!("2025-12-02T20:00:00Z"), &JsonPath::root()),
+        );
+        assert_eq!(
+            errors.first().message,
+            "book between 09:00 and 17:00 (+01:00)"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    #[should_panic(expected = "business_hours expects a non-empty range")]
+    fn test_business_hours_rejects_empty_range() {
+        StringSchema::new().business_hours(chrono::Utc, 9..25);
+    }
+
+    #[cfg(feature = "strict-formats")]
+    #[test]
+    fn test_strict_formats() {
+        let cases: [(StringSchema, &str, &[&str], &[&str]); 4] = [
+            (
+                StringSchema::new().email(),
+                "invalid_email",
+                &[
+                    "user@example.com",
+                    "first.last+tag@sub.example.org",
+                    "用户@例子.广告",
+                ],
+                &[
+                    "user@@example.com",
+                    "a b@example.com",
+                    ".user@example.com",
+                    "user@",
+                ],
+            ),
+            (
+                StringSchema::new().uuid(),
+                "invalid_uuid",
+                &[
+                    "550e8400-e29b-41d4-a716-446655440000",
+                    "00000000-0000-0000-0000-000000000000",
+                ],
+                &[
+                    "550e8400e29b41d4a716446655440000",
+                    "{550e8400-e29b-41d4-a716-446655440000}",
+                    "550e8400-e29b-41d4-c716-446655440000",
+                ],
+            ),
+            (
+                StringSchema::new().ipv4(),
+                "invalid_ipv4",
+                &["192.168.1.1", "0.0.0.0"],
+                &["192.168.01.1", "256.1.1.1", "1.2.3"],
+            ),
+            (
+                StringSchema::new().ipv6(),
+                "invalid_ipv6",
+                &["::1", "2001:db8::8a2e:370:7334", "::ffff:192.0.2.1"],
+                &["2001:db8::8a2e::7334", "12345::", "1:2:3:4:5:6:7:8:9"],
+            ),
+        ];
+
+        for (schema, code, valid, invalid) in cases {
+            let schema = schema.strict_formats();
+            for value in valid {
+                let result = schema.validate(&json!(value), &JsonPath::root());
+                assert!(result.is_success(), "{} should be valid", value);
+            }
+            for value in invalid {
+                let errors = unwrap_failure(schema.validate(&json!(value), &JsonPath::root()));
+                assert_eq!(errors.first().code, code, "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_format() {
+        let schema = StringSchema::new().hex();
+
+        for valid in ["", "00", "deadbeef", "DEADbeef01"] {
+            let result = schema.validate(&json!(valid), &JsonPath::root());
+            assert!(result.is_success(), "{} should be valid", valid);
+        }
+
+        for invalid in ["abc", "0xff", "zz", "de ad"] {
+            let result = schema.validate(&json!(invalid), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "invalid_hex", "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_slug_custom_error() {
+        let schema = StringSchema::new().slug().error("bad slug");
+
+        let result = schema.validate(&json!("Not A Slug"), &JsonPath::root());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().message, "bad slug");
+    }
+
+    #[test]
+    fn test_credit_card_error_masks_number() {
+        let schema = StringSchema::new().credit_card();
+
+        let result = schema.validate(&json!("4111-1111-1111-1112"), &JsonPath::root());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().got.as_deref(), Some("****-****-****-1112"));
+    }
+
     #[test]
     fn test_one_of_constraint() {
         let schema = StringSchema::new().one_of(["pending", "active", "completed"]);
@@ -1081,6 +3838,57 @@ mod tests {
         assert!(errors.first().message.contains("pending"));
     }
 
+    #[test]
+    fn test_not_one_of_constraint() {
+        let schema = StringSchema::new().not_one_of(["admin", "root"]);
+
+        let result = schema.validate(&json!("alice"), &JsonPath::root());
+        assert!(result.is_success());
+
+        // Matching is case-sensitive by default
+        let result = schema.validate(&json!("Admin"), &JsonPath::root());
+        assert!(result.is_success());
+
+        let result = schema.validate(&json!("root"), &JsonPath::root());
+        let errors = unwrap_failure(result);
+        assert_eq!(errors.first().code, "forbidden_value");
+        assert!(errors.first().message.contains("admin"));
+    }
+
+    #[test]
+    fn test_not_one_of_ignore_case() {
+        let schema = StringSchema::new()
+            .not_one_of_ignore_case(["Admin", "root"])
+            .error("that username is reserved");
+
+        let result = schema.validate(&json!("alice"), &JsonPath::root());
+        assert!(result.is_success());
+
+        for name in ["admin", "ADMIN", "Root"] {
+            let result = schema.validate(&json!(name), &JsonPath::root());
+            let errors = unwrap_failure(result);
+            assert_eq!(errors.first().code, "forbidden_value");
+            assert_eq!(errors.first().message, "that username is reserved");
+        }
+    }
+
+    #[test]
+    fn test_not_one_of_json_schema() {
+        let schema = StringSchema::new().not_one_of(["admin"]).to_json_schema();
+        assert_eq!(schema["not"], json!({ "enum": ["admin"] }));
+
+        let schema = StringSchema::new()
+            .not_one_of(["admin", "root"])
+            .not_one_of(["root", "guest"])
+            .to_json_schema();
+        assert_eq!(schema["not"], json!({ "enum": ["admin", "root", "guest"] }));
+
+        let schema = StringSchema::new()
+            .not_one_of_ignore_case(["admin"])
+            .to_json_schema();
+        assert!(schema.get("not").is_none());
+    }
+
     #[test]
     fn test_starts_with_constraint() {
         let schema = StringSchema::new().starts_with("http");
@@ -1120,6 +3928,83 @@ mod tests {
         assert_eq!(errors.first().code, "invalid_substring");
     }
 
+    #[test]
+    fn test_alphanumeric_constraint() {
+        let schema = StringSchema::new().alphanumeric();
+
+        assert!(schema
+            .validate(&json!("abc123"), &JsonPath::root())
+            .is_success());
+        assert!(schema
+            .validate(&json!("café"), &JsonPath::root())
+            .is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!("ab_c"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "not_alphanumeric");
+        assert!(errors.first().message.contains("'_' at position 2"));
+    }
+
+    #[test]
+    fn test_ascii_only_constraint() {
+        let schema = StringSchema::new().ascii_only();
+
+        assert!(schema
+            .validate(&json!("hello!"), &JsonPath::root())
+            .is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!("héllo"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "not_ascii");
+    }
+
+    #[test]
+    fn test_no_whitespace_constraint() {
+        let schema = StringSchema::new().no_whitespace();
+
+        assert!(schema
+            .validate(&json!("user-name"), &JsonPath::root())
+            .is_success());
+
+        let errors = unwrap_failure(schema.validate(&json!("user\tname"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "contains_whitespace");
+    }
+
+    #[test]
+    fn test_case_only_constraints() {
+        let lower = StringSchema::new().lowercase_only();
+        assert!(lower
+            .validate(&json!("user_42"), &JsonPath::root())
+            .is_success());
+        let errors = unwrap_failure(lower.validate(&json!("User"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "not_lowercase");
+
+        let upper = StringSchema::new().uppercase_only();
+        assert!(upper
+            .validate(&json!("SKU-42"), &JsonPath::root())
+            .is_success());
+        let errors = unwrap_failure(upper.validate(&json!("Sku"), &JsonPath::root()));
+        assert_eq!(errors.first().code, "not_uppercase");
+    }
+
+    #[test]
+    fn test_char_classes_accumulate() {
+        let schema = StringSchema::new()
+            .alphanumeric()
+            .ascii_only()
+            .lowercase_only()
+            .error("must be a lowercase ASCII identifier");
+
+        let errors = unwrap_failure(schema.validate(&json!("Ñame 1"), &JsonPath::root()));
+        let codes: Vec<&str> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            vec!["not_alphanumeric", "not_ascii", "not_lowercase"]
+        );
+        assert_eq!(
+            errors.iter().last().unwrap().message,
+            "must be a lowercase ASCII identifier"
+        );
+    }
+
     #[test]
     fn test_trim_transformation() {
         let schema = StringSchema::new().trim().min_len(5);
@@ -1153,6 +4038,97 @@ mod tests {
         assert_eq!(unwrap_success(result), "hello");
     }
 
+    #[test]
+    fn test_steps_run_in_builder_order() {
+        let schema = StringSchema::new()
+            .max_len(8)
+            .trim()
+            .max_len(4)
+            .uppercase()
+            .custom(|s, path| {
+                if s.chars().any(|c| c.is_lowercase()) {
+                    Validation::Failure(SchemaErrors::single(
+                        SchemaError::new(path.clone(), "saw lowercase").with_code("lowercase"),
+                    ))
+                } else {
+                    Validation::Success(())
+                }
+            });
+
+        // The first max_len sees the untrimmed value
+        let errors = unwrap_failure(schema.validate(&json!("   abc   "), &JsonPath::root()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.first().code, "max_length");
+        assert_eq!(errors.first().got.as_deref(), Some("9 characters"));
+
+        // The second max_len sees the trimmed value, the custom validator
+        // the uppercased one
+        let result = schema.validate(&json!(" abc "), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "ABC");
+    }
+
+    #[test]
+    fn test_transforms_first_compat() {
+        let schema = StringSchema::new().max_len(3).trim();
+        assert!(schema
+            .validate(&json!("  abc  "), &JsonPath::root())
+            .is_failure());
+
+        let schema = schema.transforms_first();
+        let result = schema.validate(&json!("  abc  "), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "abc");
+    }
+
+    #[test]
+    fn test_uppercase_transformation() {
+        let schema = StringSchema::new().uppercase().uppercase_only();
+
+        let result = schema.validate(&json!("gb-123"), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "GB-123");
+    }
+
+    #[test]
+    fn test_normalize_nfc_transformation() {
+        let schema = StringSchema::new().normalize_nfc().max_len(4);
+
+        // "café" with a combining acute accent is five chars before NFC
+        let result = schema.validate(&json!("cafe\u{301}"), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_collapse_whitespace_transformation() {
+        let schema = StringSchema::new().collapse_whitespace();
+
+        let result = schema.validate(&json!(" a \t\n b  c "), &JsonPath::root());
+        assert_eq!(unwrap_success(result), " a b c ");
+
+        let schema = StringSchema::new().trim().collapse_whitespace();
+        let result = schema.validate(&json!("  Jane   Doe "), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "Jane Doe");
+    }
+
+    #[test]
+    fn test_map_transformation() {
+        let schema = StringSchema::new()
+            .map(|s| s.replace([' ', '-'], ""))
+            .pattern(r"^\d{16}$")
+            .unwrap();
+
+        let result = schema.validate(&json!("4111 1111-1111 1111"), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "4111111111111111");
+
+        let result = schema.validate_to_value(&json!("4111 1111"), &JsonPath::root());
+        assert!(result.is_failure());
+    }
+
+    #[test]
+    fn test_non_idempotent_map_does_not_panic() {
+        let schema = StringSchema::new().map(|s| format!("{}!", s));
+        let result = schema.validate(&json!("hi"), &JsonPath::root());
+        assert_eq!(unwrap_success(result), "hi!");
+    }
+
     #[test]
     fn test_custom_validator() {
         let schema = StringSchema::new().custom(|s, path| {
@@ -2,14 +2,23 @@
 //!
 //! This module provides the [`SchemaLike`] trait that enables different schema types
 //! (string, integer, object, etc.) to be composed together for nested validation.
+//!
+//! `SchemaLike` is the extension point for custom schemas; methods added to it
+//! always come with a default implementation. [`ValueValidator`] is sealed: it
+//! is implemented for every `SchemaLike` type and cannot be implemented
+//! directly. Conveniences built on top of schemas live in [`SchemaExt`], which
+//! every schema implements, so they can be added without breaking downstream
+//! code.
 
 use serde_json::Value;
 use stillwater::Validation;
 
-use crate::error::SchemaErrors;
+use crate::error::{repair_with, RepairOutcome, RepairPolicy, SchemaErrors};
 use crate::interop::ToJsonSchema;
 use crate::path::JsonPath;
 
+use super::{CombinatorSchema, FieldDoc};
+
 /// A trait for schema types that can validate JSON values.
 ///
 /// `SchemaLike` enables schema polymorphism, allowing different schema types
@@ -118,13 +127,57 @@ pub trait SchemaLike: Send + Sync {
     fn collect_refs(&self, _refs: &mut Vec<String>) {
         // Default: no references to collect
     }
+
+    /// Returns the documentation of the field at `path` below this schema,
+    /// given as a list of field names.
+    ///
+    /// The default implementation returns `None`. Object schemas return the
+    /// documentation attached with [`ObjectSchema::doc`](crate::ObjectSchema::doc),
+    /// and container schemas look inside their children.
+    fn field_doc(&self, _path: &[&str]) -> Option<FieldDoc> {
+        None
+    }
+
+    /// Validates a value, applying the repairs a policy allows.
+    ///
+    /// Errors with a [`suggestion`](crate::SchemaError::suggestion) whose
+    /// kind the policy allows are fixed and the document is validated
+    /// again, until it is valid or no allowed repair remains. The outcome
+    /// holds the repaired document, the schema's output for it, the repairs
+    /// applied, and any errors left.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{RepairPolicy, Schema, SchemaLike};
+    /// use serde_json::json;
+    ///
+    /// let schema = Schema::object()
+    ///     .field("port", Schema::integer().min(1).max(65535))
+    ///     .additional_properties(false);
+    ///
+    /// let outcome = schema.validate_and_repair(
+    ///     &json!({"port": "8080", "debug": true}),
+    ///     RepairPolicy::safe(),
+    /// );
+    ///
+    /// assert!(outcome.is_valid());
+    /// assert_eq!(outcome.value, json!({"port": 8080}));
+    /// assert_eq!(outcome.applied.len(), 2);
+    /// ```
+    fn validate_and_repair(&self, value: &Value, policy: RepairPolicy) -> RepairOutcome {
+        repair_with(value, policy, |v| {
+            self.validate_to_value(v, &JsonPath::root())
+        })
+    }
 }
 
 /// A type-erased trait for schemas that validate to JSON values.
 ///
 /// `ValueValidator` provides type erasure for schemas with different output types,
 /// allowing them to be used together in combinators. Any type that implements
-/// `SchemaLike` automatically implements `ValueValidator`.
+/// `SchemaLike` and `ToJsonSchema` automatically implements `ValueValidator`;
+/// the trait is sealed, so it cannot be implemented otherwise.
 ///
 /// This trait is primarily used by schema combinators like `one_of`, `any_of`,
 /// and `all_of` which need to work with heterogeneous collections of schemas.
@@ -141,7 +194,7 @@ pub trait SchemaLike: Send + Sync {
 ///     Box::new(Schema::integer().positive()),
 /// ];
 /// ```
-pub trait ValueValidator: Send + Sync {
+pub trait ValueValidator: Send + Sync + sealed::Sealed {
     /// Validates a value and returns the result as a `serde_json::Value`.
     fn validate_value(&self, value: &Value, path: &JsonPath) -> Validation<Value, SchemaErrors>;
 
@@ -164,6 +217,13 @@ pub trait ValueValidator: Send + Sync {
         // Most schemas have no references
     }
 
+    /// Returns the documentation of the field at `path` below this schema.
+    ///
+    /// Default implementation returns `None`.
+    fn field_doc(&self, _path: &[&str]) -> Option<FieldDoc> {
+        None
+    }
+
     /// Converts this schema to JSON Schema format.
     ///
     /// This enables ObjectSchema and other container schemas to export their
@@ -192,7 +252,88 @@ impl<S: SchemaLike + ToJsonSchema> ValueValidator for S {
         SchemaLike::collect_refs(self, refs);
     }
 
+    fn field_doc(&self, path: &[&str]) -> Option<FieldDoc> {
+        SchemaLike::field_doc(self, path)
+    }
+
     fn to_json_schema(&self) -> Value {
         ToJsonSchema::to_json_schema(self)
     }
 }
+
+mod sealed {
+    use super::SchemaLike;
+    use crate::interop::ToJsonSchema;
+
+    /// Keeps [`ValueValidator`](super::ValueValidator) implemented through
+    /// [`SchemaLike`] only.
+    pub trait Sealed {}
+
+    impl<S: SchemaLike + ToJsonSchema> Sealed for S {}
+}
+
+/// Conveniences for every schema.
+///
+/// Implemented for all [`SchemaLike`] types and cannot be implemented
+/// otherwise, so new methods can be added in minor releases. Bring it into
+/// scope with `use postmortem::SchemaExt`.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Schema, SchemaExt, ValueValidator};
+/// use serde_json::json;
+///
+/// let schema = Schema::string().min_len(1);
+/// assert!(schema.is_valid(&json!("hello")));
+/// assert!(schema.validate_root(&json!("")).is_failure());
+///
+/// let schemas: Vec<Box<dyn ValueValidator>> =
+///     vec![schema.boxed(), Schema::integer().boxed()];
+/// assert_eq!(schemas.len(), 2);
+/// ```
+pub trait SchemaExt: SchemaLike {
+    /// Validates a value at the root path.
+    fn validate_root(&self, value: &Value) -> Validation<Self::Output, SchemaErrors> {
+        self.validate(value, &JsonPath::root())
+    }
+
+    /// Returns whether a value is valid.
+    fn is_valid(&self, value: &Value) -> bool {
+        self.validate_root(value).is_success()
+    }
+
+    /// Allows `null` as well as the values the schema accepts.
+    ///
+    /// Equivalent to [`Schema::optional`](crate::Schema::optional) with the
+    /// schema boxed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaExt};
+    /// use serde_json::json;
+    ///
+    /// let middle_name = Schema::string().min_len(1).nullable();
+    /// assert!(middle_name.is_valid(&json!(null)));
+    /// assert!(middle_name.is_valid(&json!("Ann")));
+    /// assert!(!middle_name.is_valid(&json!("")));
+    /// ```
+    fn nullable(self) -> CombinatorSchema
+    where
+        Self: Sized + ToJsonSchema + 'static,
+    {
+        crate::Schema::optional(Box::new(self))
+    }
+
+    /// Boxes the schema for use with heterogeneous collections, e.g. in
+    /// [`Schema::one_of`](crate::Schema::one_of).
+    fn boxed(self) -> Box<dyn ValueValidator>
+    where
+        Self: Sized + ToJsonSchema + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+impl<S: SchemaLike + ?Sized> SchemaExt for S {}
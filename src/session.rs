@@ -0,0 +1,1160 @@
+//! Validation sessions spanning multiple documents.
+//!
+//! A [`ValidationSession`] validates many documents against a registry while
+//! sharing state between them. Keys collected from one stream of documents
+//! (all warehouse IDs, say) are kept in named [`LookupSet`]s, and references
+//! declared on a schema are checked against them when documents of a second
+//! stream are validated. For large batch jobs,
+//! [`validate_bulk`](ValidationSession::validate_bulk) keeps a compact
+//! [`BulkSummary`] of the errors instead of every error.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use stillwater::prelude::*;
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::path::{JsonPath, PathSegment};
+use crate::registry::{RegistryError, SchemaRegistry};
+use crate::validation::ValidationContext;
+
+/// A set of keys that references are checked against.
+///
+/// [`exact`](Self::exact) sets store every key. [`bloom`](Self::bloom) sets
+/// use a fixed amount of memory for very large key sets, at the cost of
+/// occasionally accepting an unknown key (a false positive); they never
+/// reject a key that was inserted.
+///
+/// Keys are compared by their JSON representation, so `"1"` and `1` are
+/// different keys.
+#[derive(Debug, Clone)]
+pub struct LookupSet {
+    inner: LookupInner,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+enum LookupInner {
+    Exact(HashSet<String>),
+    Bloom { bits: Vec<u64>, hashes: u32 },
+}
+
+impl LookupSet {
+    /// Creates a set that stores every key.
+    pub fn exact() -> Self {
+        Self {
+            inner: LookupInner::Exact(HashSet::new()),
+            len: 0,
+        }
+    }
+
+    /// Creates a bloom filter sized for `expected_items` keys with the given
+    /// false positive rate (e.g. `0.001` for 0.1%).
+    ///
+    /// Memory use is fixed at creation: about 1.8 bytes per expected key at a
+    /// 0.1% false positive rate. Inserting more keys than expected raises the
+    /// false positive rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `false_positive_rate` is not strictly between 0 and 1.
+    pub fn bloom(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false positive rate must be between 0 and 1"
+        );
+        let n = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0);
+        let hashes = ((bits / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        Self {
+            inner: LookupInner::Bloom {
+                bits: vec![0; (bits as usize).div_ceil(64)],
+                hashes,
+            },
+            len: 0,
+        }
+    }
+
+    /// Adds a key to the set.
+    pub fn insert(&mut self, key: &Value) {
+        let key = lookup_key(key);
+        match &mut self.inner {
+            LookupInner::Exact(keys) => {
+                if keys.insert(key) {
+                    self.len += 1;
+                }
+            }
+            LookupInner::Bloom { bits, hashes } => {
+                let positions = bloom_positions(&key, *hashes, bits.len() * 64);
+                for bit in positions {
+                    bits[bit / 64] |= 1 << (bit % 64);
+                }
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Returns whether the set contains a key.
+    ///
+    /// For bloom sets this may return `true` for a key that was never
+    /// inserted, but never `false` for one that was.
+    pub fn contains(&self, key: &Value) -> bool {
+        let key = lookup_key(key);
+        match &self.inner {
+            LookupInner::Exact(keys) => keys.contains(&key),
+            LookupInner::Bloom { bits, hashes } => bloom_positions(&key, *hashes, bits.len() * 64)
+                .all(|bit| bits[bit / 64] & (1 << (bit % 64)) != 0),
+        }
+    }
+
+    /// Returns the number of keys inserted.
+    ///
+    /// Exact sets count distinct keys; bloom sets count insertions.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no keys have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Default for LookupSet {
+    fn default() -> Self {
+        Self::exact()
+    }
+}
+
+/// Errors that documents had when a stricter schema was adopted.
+///
+/// A baseline records, for each schema and document id, the errors already
+/// known. A [`ValidationSession`] given a baseline with
+/// [`with_baseline`](ValidationSession::with_baseline) reports only the
+/// errors a document did not have before, as [`SchemaErrors::diff`] does.
+/// Baselines can be stored with [`to_json`](Self::to_json) and loaded with
+/// [`from_json`](Self::from_json).
+#[derive(Debug, Clone, Default)]
+pub struct ErrorBaseline {
+    documents: HashMap<String, HashMap<String, SchemaErrors>>,
+}
+
+impl ErrorBaseline {
+    /// Creates an empty baseline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the errors of the document with id `document_id`, adding to
+    /// any already recorded for it.
+    pub fn record(
+        &mut self,
+        schema_name: &str,
+        document_id: &str,
+        errors: &SchemaErrors,
+    ) -> &mut Self {
+        let documents = self.documents.entry(schema_name.to_string()).or_default();
+        let recorded = match documents.remove(document_id) {
+            Some(known) => known.combine(errors.clone()),
+            None => errors.clone(),
+        };
+        documents.insert(document_id.to_string(), recorded);
+        self
+    }
+
+    /// Returns the errors recorded for a document.
+    pub fn get(&self, schema_name: &str, document_id: &str) -> Option<&SchemaErrors> {
+        self.documents.get(schema_name)?.get(document_id)
+    }
+
+    /// Returns the number of documents with recorded errors.
+    pub fn len(&self) -> usize {
+        self.documents.values().map(HashMap::len).sum()
+    }
+
+    /// Returns whether no errors have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Serializes the baseline, keeping the path and code of each error.
+    ///
+    /// The result maps schema names to document ids to errors, e.g.
+    /// `{"Order": {"O1": [{"path": ["lines", 0, "sku"], "code": "pattern"}]}}`.
+    pub fn to_json(&self) -> Value {
+        let schemas = self
+            .documents
+            .iter()
+            .map(|(schema, documents)| {
+                let documents = documents
+                    .iter()
+                    .map(|(id, errors)| {
+                        let errors = errors
+                            .iter()
+                            .map(|error| {
+                                let path: Vec<Value> = error
+                                    .path
+                                    .segments()
+                                    .map(|segment| match segment {
+                                        PathSegment::Field(name) => Value::from(name.as_str()),
+                                        PathSegment::Index(index) => Value::from(*index),
+                                    })
+                                    .collect();
+                                json!({"path": path, "code": error.code})
+                            })
+                            .collect();
+                        (id.clone(), Value::Array(errors))
+                    })
+                    .collect();
+                (schema.clone(), Value::Object(documents))
+            })
+            .collect();
+        Value::Object(schemas)
+    }
+
+    /// Loads a baseline written by [`to_json`](Self::to_json), or returns
+    /// `None` if the value is not in that format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{ErrorBaseline, JsonPath, SchemaError, SchemaErrors};
+    ///
+    /// let mut baseline = ErrorBaseline::new();
+    /// baseline.record("Order", "O1", &SchemaErrors::single(
+    ///     SchemaError::new(JsonPath::root().push_field("sku"), "bad sku").with_code("pattern"),
+    /// ));
+    ///
+    /// let stored = baseline.to_json().to_string();
+    /// let loaded = ErrorBaseline::from_json(&serde_json::from_str(&stored).unwrap()).unwrap();
+    /// assert_eq!(loaded.get("Order", "O1").unwrap().first().code, "pattern");
+    /// ```
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let mut baseline = Self::new();
+        for (schema, documents) in value.as_object()? {
+            for (id, errors) in documents.as_object()? {
+                let errors = errors
+                    .as_array()?
+                    .iter()
+                    .map(|error| {
+                        let path = error.get("path")?.as_array()?.iter().try_fold(
+                            JsonPath::root(),
+                            |path, segment| match segment {
+                                Value::String(name) => Some(path.push_field(name.as_str())),
+                                Value::Number(n) => Some(path.push_index(n.as_u64()? as usize)),
+                                _ => None,
+                            },
+                        )?;
+                        let code = error.get("code")?.as_str()?;
+                        Some(SchemaError::new(path, "").with_code(code))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                if !errors.is_empty() {
+                    baseline.record(schema, id, &SchemaErrors::from_vec(errors));
+                }
+            }
+        }
+        Some(baseline)
+    }
+}
+
+/// A compact summary of the errors of many documents.
+///
+/// Errors are grouped by path pattern, the error's path with every array
+/// index replaced by `[]` (e.g. `lines[].sku`), and code. Every group keeps
+/// a full count but only the first `sample_limit` errors, each with the
+/// index of its document, so a batch job with millions of errors holds a
+/// bounded amount of memory. Groups are kept in the order they were first
+/// seen.
+///
+/// Summaries are built by [`ValidationSession::validate_bulk`], or by
+/// [`record`](Self::record)ing outcomes as they arrive.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{BulkSummary, JsonPath, SchemaError, SchemaErrors};
+///
+/// let mut summary = BulkSummary::new(1);
+/// for i in 0..3 {
+///     let path = JsonPath::root().push_field("lines").push_index(i).push_field("sku");
+///     summary.record(Some(&SchemaErrors::single(
+///         SchemaError::new(path, "bad sku").with_code("pattern"),
+///     )));
+/// }
+/// summary.record(None);
+///
+/// assert_eq!(summary.documents(), 4);
+/// assert_eq!(summary.invalid_documents(), 3);
+///
+/// let group = summary.group("lines[].sku", "pattern").unwrap();
+/// assert_eq!(group.count, 3);
+/// assert_eq!(group.samples.len(), 1);
+/// assert_eq!(group.samples[0].error.path.to_string(), "lines[0].sku");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BulkSummary {
+    sample_limit: usize,
+    documents: usize,
+    invalid_documents: usize,
+    groups: IndexMap<(String, String), ErrorGroup>,
+}
+
+/// The errors of a [`BulkSummary`] sharing a path pattern and code.
+#[derive(Debug, Clone)]
+pub struct ErrorGroup {
+    /// The path pattern, e.g. `lines[].sku`.
+    pub path: String,
+    /// The error code.
+    pub code: String,
+    /// The number of errors in the group.
+    pub count: usize,
+    /// The first errors of the group.
+    pub samples: Vec<ErrorSample>,
+}
+
+/// An error kept as a sample of an [`ErrorGroup`].
+#[derive(Debug, Clone)]
+pub struct ErrorSample {
+    /// The index of the document, in the order documents were recorded.
+    pub document: usize,
+    /// The error.
+    pub error: SchemaError,
+}
+
+impl BulkSummary {
+    /// Creates an empty summary keeping at most `sample_limit` errors per
+    /// group.
+    pub fn new(sample_limit: usize) -> Self {
+        Self {
+            sample_limit,
+            documents: 0,
+            invalid_documents: 0,
+            groups: IndexMap::new(),
+        }
+    }
+
+    /// Records the outcome of the next document: its errors, or `None` if
+    /// it is valid.
+    pub fn record(&mut self, errors: Option<&SchemaErrors>) -> &mut Self {
+        let document = self.documents;
+        self.documents += 1;
+        let Some(errors) = errors else {
+            return self;
+        };
+        self.invalid_documents += 1;
+        for error in errors.iter() {
+            let path = path_pattern(&error.path);
+            let group = self
+                .groups
+                .entry((path.clone(), error.code.clone()))
+                .or_insert_with(|| ErrorGroup {
+                    path,
+                    code: error.code.clone(),
+                    count: 0,
+                    samples: Vec::new(),
+                });
+            group.count += 1;
+            if group.samples.len() < self.sample_limit {
+                group.samples.push(ErrorSample {
+                    document,
+                    error: error.clone(),
+                });
+            }
+        }
+        self
+    }
+
+    /// Returns the number of documents recorded.
+    pub fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// Returns the number of documents recorded with errors.
+    pub fn invalid_documents(&self) -> usize {
+        self.invalid_documents
+    }
+
+    /// Returns the total number of errors, including those not sampled.
+    pub fn error_count(&self) -> usize {
+        self.groups.values().map(|group| group.count).sum()
+    }
+
+    /// Returns the groups in the order they were first seen.
+    pub fn groups(&self) -> impl Iterator<Item = &ErrorGroup> {
+        self.groups.values()
+    }
+
+    /// Returns the group of errors at a path pattern with a code.
+    pub fn group(&self, path: &str, code: &str) -> Option<&ErrorGroup> {
+        self.groups.get(&(path.to_string(), code.to_string()))
+    }
+
+    /// Serializes the summary for dashboards and retry jobs.
+    ///
+    /// The result has the document counts and a `groups` array; each group
+    /// has its `path` pattern, `code`, `count`, and `samples`, each sample
+    /// the `document` index and the error's concrete `path` and `message`.
+    pub fn to_json(&self) -> Value {
+        let groups: Vec<Value> = self
+            .groups()
+            .map(|group| {
+                let samples: Vec<Value> = group
+                    .samples
+                    .iter()
+                    .map(|sample| {
+                        json!({
+                            "document": sample.document,
+                            "path": sample.error.path.to_string(),
+                            "message": sample.error.message,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "path": group.path,
+                    "code": group.code,
+                    "count": group.count,
+                    "samples": samples,
+                })
+            })
+            .collect();
+        json!({
+            "documents": self.documents,
+            "invalid_documents": self.invalid_documents,
+            "errors": self.error_count(),
+            "groups": groups,
+        })
+    }
+}
+
+/// A reference from a field of a schema's documents to a lookup set.
+#[derive(Debug, Clone)]
+struct Reference {
+    pattern: Vec<KeySegment>,
+    lookup: String,
+}
+
+/// A segment of a key pattern such as `lines[].warehouse_id`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum KeySegment {
+    Field(String),
+    Index(usize),
+    Each,
+}
+
+/// Validates documents against a registry, sharing lookup sets and
+/// reference-resolution state across documents.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Schema, SchemaRegistry, ValidationSession};
+/// use serde_json::json;
+///
+/// let registry = SchemaRegistry::new();
+/// registry.register("Order", Schema::object()
+///     .field("id", Schema::string())
+///     .field("lines", Schema::array(Schema::object()
+///         .field("warehouse_id", Schema::string()))))
+///     .unwrap();
+///
+/// let mut session = ValidationSession::new(&registry);
+///
+/// // First stream: collect every warehouse ID
+/// let warehouses = vec![json!({"id": "W1"}), json!({"id": "W2"})];
+/// session.collect_keys("warehouses", &warehouses, "id");
+///
+/// // Second stream: orders must reference known warehouses
+/// session.reference("Order", "lines[].warehouse_id", "warehouses");
+///
+/// let order = json!({"id": "O1", "lines": [{"warehouse_id": "W1"}, {"warehouse_id": "W9"}]});
+/// let errors = session.validate("Order", &order).unwrap().into_result().unwrap_err();
+///
+/// assert_eq!(errors.first().code, "unknown_reference");
+/// assert_eq!(errors.first().path.to_string(), "lines[1].warehouse_id");
+/// ```
+pub struct ValidationSession {
+    registry: SchemaRegistry,
+    context: ValidationContext,
+    lookups: HashMap<String, LookupSet>,
+    references: HashMap<String, Vec<Reference>>,
+    baseline: Option<(ErrorBaseline, Vec<KeySegment>)>,
+}
+
+impl ValidationSession {
+    /// Creates a session validating against `registry`.
+    ///
+    /// The registry is shared, not copied: schemas registered later are
+    /// visible to the session.
+    pub fn new(registry: &SchemaRegistry) -> Self {
+        Self {
+            registry: registry.clone(),
+            context: registry.context(),
+            lookups: HashMap::new(),
+            references: HashMap::new(),
+            baseline: None,
+        }
+    }
+
+    /// Replaces the lookup set named `name`, e.g. with a
+    /// [`LookupSet::bloom`] filter before collecting a very large key set.
+    ///
+    /// Sets created implicitly by [`insert_key`](Self::insert_key) or
+    /// [`collect_keys`](Self::collect_keys) are exact.
+    pub fn add_lookup(&mut self, name: impl Into<String>, set: LookupSet) -> &mut Self {
+        self.lookups.insert(name.into(), set);
+        self
+    }
+
+    /// Returns the lookup set named `name`.
+    pub fn lookup(&self, name: &str) -> Option<&LookupSet> {
+        self.lookups.get(name)
+    }
+
+    /// Adds a single key to the lookup set named `name`.
+    pub fn insert_key(&mut self, name: &str, key: &Value) -> &mut Self {
+        self.lookups
+            .entry(name.to_string())
+            .or_default()
+            .insert(key);
+        self
+    }
+
+    /// Adds the keys found at `key` in each document to the lookup set
+    /// named `name`.
+    ///
+    /// `key` is a dotted field path where `[]` matches every array item and
+    /// `[n]` a single one, e.g. `id` or `warehouses[].code`. Missing and
+    /// `null` keys are skipped.
+    pub fn collect_keys<'a, I>(&mut self, name: &str, documents: I, key: &str) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        let pattern = parse_pattern(key);
+        let set = self.lookups.entry(name.to_string()).or_default();
+        for document in documents {
+            for (_, value) in resolve_pattern(document, &pattern, JsonPath::root()) {
+                set.insert(value);
+            }
+        }
+        self
+    }
+
+    /// Declares that values at `key` in documents validated against
+    /// `schema_name` must be present in the lookup set named `lookup`.
+    ///
+    /// `key` uses the same syntax as [`collect_keys`](Self::collect_keys).
+    /// Missing and `null` values are not checked; use the schema to require
+    /// them.
+    pub fn reference(
+        &mut self,
+        schema_name: impl Into<String>,
+        key: &str,
+        lookup: impl Into<String>,
+    ) -> &mut Self {
+        self.references
+            .entry(schema_name.into())
+            .or_default()
+            .push(Reference {
+                pattern: parse_pattern(key),
+                lookup: lookup.into(),
+            });
+        self
+    }
+
+    /// Reports only errors that are not in `baseline`.
+    ///
+    /// Each document is identified by the value at `id_key`, which uses the
+    /// same syntax as [`collect_keys`](Self::collect_keys). Errors recorded
+    /// in the baseline for that document are dropped, and a document whose
+    /// errors were all recorded validates successfully. Documents without
+    /// an id are reported in full.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{ErrorBaseline, Schema, SchemaRegistry, ValidationSession};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Product", Schema::object()
+    ///     .field("id", Schema::string())
+    ///     .field("sku", Schema::string().pattern(r"^[A-Z]{3}-\d+$").unwrap())
+    ///     .field("price", Schema::integer().non_negative()))
+    ///     .unwrap();
+    ///
+    /// // Record the violations of the stricter schema in existing data
+    /// let existing = json!({"id": "P1", "sku": "legacy", "price": 5});
+    /// let mut baseline = ErrorBaseline::new();
+    /// let session = ValidationSession::new(&registry);
+    /// if let Some(errors) = session.validate("Product", &existing).unwrap().into_result().err() {
+    ///     baseline.record("Product", "P1", &errors);
+    /// }
+    ///
+    /// let mut session = ValidationSession::new(&registry);
+    /// session.with_baseline(baseline, "id");
+    ///
+    /// // The known bad SKU is tolerated...
+    /// assert!(session.validate("Product", &existing).unwrap().is_success());
+    ///
+    /// // ...but a new violation is reported
+    /// let changed = json!({"id": "P1", "sku": "legacy", "price": -1});
+    /// let errors = session.validate("Product", &changed).unwrap().into_result().unwrap_err();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors.first().code, "non_negative");
+    /// ```
+    pub fn with_baseline(&mut self, baseline: ErrorBaseline, id_key: &str) -> &mut Self {
+        self.baseline = Some((baseline, parse_pattern(id_key)));
+        self
+    }
+
+    /// Validates a document against a named schema and its declared
+    /// references.
+    ///
+    /// Schema errors and `unknown_reference` errors are accumulated. A
+    /// reference to a lookup set that was never created reports every
+    /// value as unknown. As with
+    /// [`SchemaRegistry::validate`], the schema's transform is applied
+    /// first, a valid document yields the schema's output, and errors are
+    /// rebuilt by the registry's error factory and linked to their
+    /// fields' documentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::SchemaNotFound` if the schema name doesn't exist.
+    pub fn validate(
+        &self,
+        schema_name: &str,
+        value: &Value,
+    ) -> Result<Validation<Value, SchemaErrors>, RegistryError> {
+        let schema = self
+            .registry
+            .get(schema_name)
+            .ok_or_else(|| RegistryError::SchemaNotFound(schema_name.to_string()))?;
+
+        let value = self.registry.transformed(schema_name, value);
+        let result = self
+            .registry
+            .run(schema_name, schema.as_ref(), &value, &self.context);
+        let unknown = NonEmptyVec::from_vec(self.check_references(schema_name, &value))
+            .map(SchemaErrors::from_non_empty);
+        let result = match (result, unknown) {
+            (result, None) => result,
+            (Validation::Success(_), Some(unknown)) => Validation::Failure(unknown),
+            (Validation::Failure(errors), Some(unknown)) => {
+                Validation::Failure(errors.combine(unknown))
+            }
+        };
+        let result = self
+            .registry
+            .finish(schema.as_ref(), result, &value, &self.context);
+
+        let Some(known) = self.known_errors(schema_name, &value) else {
+            return Ok(result);
+        };
+        Ok(match result {
+            Validation::Failure(errors) => match errors.diff(known) {
+                Some(errors) => Validation::Failure(errors),
+                None => Validation::Success(value.into_owned()),
+            },
+            success => success,
+        })
+    }
+
+    /// Validates many documents against a named schema, keeping only a
+    /// [`BulkSummary`] of their errors.
+    ///
+    /// Documents are validated one at a time as the iterator yields them,
+    /// so they can be read lazily from a file or queue. Each group of
+    /// errors keeps at most `sample_limit` samples; everything else is only
+    /// counted. References and the baseline apply as in
+    /// [`validate`](Self::validate).
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::SchemaNotFound` if the schema name doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry, ValidationSession};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Reading", Schema::object()
+    ///     .field("sensor", Schema::string())
+    ///     .field("values", Schema::array(Schema::integer().non_negative())))
+    ///     .unwrap();
+    ///
+    /// let session = ValidationSession::new(&registry);
+    /// let readings = (0..1000).map(|i| json!({"sensor": "s1", "values": [i % 7, -1, -2]}));
+    /// let summary = session.validate_bulk("Reading", readings, 5).unwrap();
+    ///
+    /// assert_eq!(summary.invalid_documents(), 1000);
+    /// let group = summary.group("values[]", "non_negative").unwrap();
+    /// assert_eq!(group.count, 2000);
+    /// assert_eq!(group.samples.len(), 5);
+    /// ```
+    pub fn validate_bulk<I>(
+        &self,
+        schema_name: &str,
+        documents: I,
+        sample_limit: usize,
+    ) -> Result<BulkSummary, RegistryError>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Value>,
+    {
+        if self.registry.get(schema_name).is_none() {
+            return Err(RegistryError::SchemaNotFound(schema_name.to_string()));
+        }
+        let mut summary = BulkSummary::new(sample_limit);
+        for document in documents {
+            match self.validate(schema_name, document.borrow())? {
+                Validation::Success(_) => summary.record(None),
+                Validation::Failure(errors) => summary.record(Some(&errors)),
+            };
+        }
+        Ok(summary)
+    }
+
+    /// Returns the baseline errors of a document, if there is a baseline
+    /// and the document's id is in it.
+    fn known_errors(&self, schema_name: &str, value: &Value) -> Option<&SchemaErrors> {
+        let (baseline, id_key) = self.baseline.as_ref()?;
+        let (_, id) = resolve_pattern(value, id_key, JsonPath::root())
+            .into_iter()
+            .next()?;
+        baseline.get(schema_name, &key_string(id))
+    }
+
+    /// Returns an error for each declared reference of `schema_name` in
+    /// `value` whose key is not in its lookup set.
+    fn check_references(&self, schema_name: &str, value: &Value) -> Vec<SchemaError> {
+        let empty = LookupSet::exact();
+        let mut errors = Vec::new();
+        for reference in self.references.get(schema_name).into_iter().flatten() {
+            let set = self.lookups.get(&reference.lookup).unwrap_or(&empty);
+            for (path, key) in resolve_pattern(value, &reference.pattern, JsonPath::root()) {
+                if !set.contains(key) {
+                    errors.push(
+                        SchemaError::new(path, format!("unknown {} key {}", reference.lookup, key))
+                            .with_code("unknown_reference")
+                            .with_expected(format!("a key in {}", reference.lookup))
+                            .with_got(key_string(key)),
+                    );
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Parses a key pattern such as `lines[].warehouse_id` or `[0].id`.
+pub(crate) fn parse_pattern(pattern: &str) -> Vec<KeySegment> {
+    let mut segments = Vec::new();
+    for part in pattern.split('.').filter(|p| !p.is_empty()) {
+        let (field, mut rest) = match part.find('[') {
+            Some(i) => (&part[..i], &part[i..]),
+            None => (part, ""),
+        };
+        if !field.is_empty() {
+            segments.push(KeySegment::Field(field.to_string()));
+        }
+        while let Some(close) = rest.find(']') {
+            let index = rest[1..close].trim();
+            segments.push(match index.parse() {
+                Ok(i) => KeySegment::Index(i),
+                Err(_) => KeySegment::Each,
+            });
+            rest = &rest[close + 1..];
+        }
+    }
+    segments
+}
+
+/// Returns the non-null values matching a pattern, with their paths.
+pub(crate) fn resolve_pattern<'a>(
+    value: &'a Value,
+    pattern: &[KeySegment],
+    path: JsonPath,
+) -> Vec<(JsonPath, &'a Value)> {
+    let Some((segment, rest)) = pattern.split_first() else {
+        return if value.is_null() {
+            Vec::new()
+        } else {
+            vec![(path, value)]
+        };
+    };
+    match (segment, value) {
+        (KeySegment::Field(name), Value::Object(map)) => match map.get(name) {
+            Some(v) => resolve_pattern(v, rest, path.push_field(name.as_str())),
+            None => Vec::new(),
+        },
+        (KeySegment::Index(i), Value::Array(items)) => match items.get(*i) {
+            Some(v) => resolve_pattern(v, rest, path.push_index(*i)),
+            None => Vec::new(),
+        },
+        (KeySegment::Each, Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .flat_map(|(i, v)| resolve_pattern(v, rest, path.push_index(i)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns a path with every array index replaced by `[]`.
+fn path_pattern(path: &JsonPath) -> String {
+    let mut pattern = String::new();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::Field(name) => {
+                if !pattern.is_empty() {
+                    pattern.push('.');
+                }
+                pattern.push_str(name);
+            }
+            PathSegment::Index(_) => pattern.push_str("[]"),
+        }
+    }
+    pattern
+}
+
+/// Returns the string a [`LookupSet`] compares a key by: its JSON
+/// representation, which quotes strings.
+fn lookup_key(key: &Value) -> String {
+    serde_json::to_string(key).expect("a JSON value serializes")
+}
+
+/// Returns a key as text: strings unquoted, other values as JSON.
+fn key_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Returns the bloom filter bits for a key using double hashing.
+fn bloom_positions(key: &str, hashes: u32, bits: usize) -> impl Iterator<Item = usize> {
+    let hash = |seed: u64| {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        key.hash(&mut hasher);
+        hasher.finish()
+    };
+    let (h1, h2) = (hash(0), hash(1) | 1);
+    (0..hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits as u64) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use serde_json::json;
+
+    fn registry() -> SchemaRegistry {
+        let registry = SchemaRegistry::new();
+        registry
+            .register(
+                "Order",
+                Schema::object()
+                    .field("id", Schema::string())
+                    .optional("warehouse_id", Schema::string())
+                    .field(
+                        "lines",
+                        Schema::array(Schema::object().field("sku", Schema::integer())),
+                    ),
+            )
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_pattern("lines[].sku"),
+            [
+                KeySegment::Field("lines".to_string()),
+                KeySegment::Each,
+                KeySegment::Field("sku".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_pattern("[2].id"),
+            [KeySegment::Index(2), KeySegment::Field("id".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_references_accumulate_with_schema_errors() {
+        let registry = registry();
+        let mut session = ValidationSession::new(&registry);
+        session
+            .collect_keys("skus", &[json!([{"sku": 1}, {"sku": 2}])], "[].sku")
+            .insert_key("warehouses", &json!("W1"))
+            .reference("Order", "lines[].sku", "skus")
+            .reference("Order", "warehouse_id", "warehouses");
+
+        let order = json!({"lines": [{"sku": 1}, {"sku": 3}], "warehouse_id": "W2"});
+        let errors = session
+            .validate("Order", &order)
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+
+        let found: Vec<(String, &str)> = errors
+            .iter()
+            .map(|e| (e.path.to_string(), e.code.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("id".to_string(), "required"),
+                ("lines[1].sku".to_string(), "unknown_reference"),
+                ("warehouse_id".to_string(), "unknown_reference"),
+            ]
+        );
+        assert_eq!(errors.iter().nth(1).unwrap().got.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_missing_and_null_references_are_skipped() {
+        let registry = registry();
+        let mut session = ValidationSession::new(&registry);
+        session.reference("Order", "warehouse_id", "warehouses");
+
+        let result = session
+            .validate("Order", &json!({"id": "O1", "lines": []}))
+            .unwrap();
+        assert!(result.is_success());
+    }
+
+    #[test]
+    fn test_unknown_lookup_set_rejects_values() {
+        let registry = registry();
+        let mut session = ValidationSession::new(&registry);
+        session.reference("Order", "id", "orders");
+
+        let result = session
+            .validate("Order", &json!({"id": "O1", "lines": []}))
+            .unwrap();
+        assert!(result.is_failure());
+        assert!(session.validate("Missing", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_key_types_are_distinct() {
+        let mut set = LookupSet::exact();
+        set.insert(&json!(1));
+        set.insert(&json!(1));
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&json!(1)));
+        assert!(!set.contains(&json!("1")));
+        assert!(!set.contains(&json!(2)));
+
+        set.insert(&json!("1"));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&json!("1")));
+        assert!(!set.contains(&json!("\"1\"")));
+
+        let mut bloom = LookupSet::bloom(100, 0.001);
+        bloom.insert(&json!("1"));
+        assert!(!bloom.contains(&json!(1)));
+    }
+
+    #[test]
+    fn test_bloom_set() {
+        let mut set = LookupSet::bloom(10_000, 0.01);
+        for i in 0..10_000 {
+            set.insert(&json!(format!("key-{}", i)));
+        }
+        assert_eq!(set.len(), 10_000);
+        assert!((0..10_000).all(|i| set.contains(&json!(format!("key-{}", i)))));
+
+        let false_positives = (0..10_000)
+            .filter(|i| set.contains(&json!(format!("other-{}", i))))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_bloom_lookup_in_session() {
+        let registry = registry();
+        let mut session = ValidationSession::new(&registry);
+        session
+            .add_lookup("orders", LookupSet::bloom(100, 0.001))
+            .collect_keys("orders", &[json!({"id": "O1"})], "id")
+            .reference("Order", "id", "orders");
+
+        let ok = session
+            .validate("Order", &json!({"id": "O1", "lines": []}))
+            .unwrap();
+        assert!(ok.is_success());
+        assert_eq!(session.lookup("orders").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_baseline_reports_only_regressions() {
+        let registry = registry();
+        let legacy = json!({"id": "O1", "lines": [{"sku": "A"}, {"sku": 2}]});
+        let errors = ValidationSession::new(&registry)
+            .validate("Order", &legacy)
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        let mut baseline = ErrorBaseline::new();
+        baseline.record("Order", "O1", &errors);
+
+        // The baseline survives being stored and loaded
+        let baseline = ErrorBaseline::from_json(&baseline.to_json()).unwrap();
+        assert_eq!(baseline.len(), 1);
+
+        let mut session = ValidationSession::new(&registry);
+        session
+            .with_baseline(baseline, "id")
+            .reference("Order", "warehouse_id", "warehouses");
+        assert!(session.validate("Order", &legacy).unwrap().is_success());
+
+        let regressed = json!({
+            "id": "O1",
+            "warehouse_id": "W9",
+            "lines": [{"sku": "A"}, {"sku": "B"}]
+        });
+        let errors = session
+            .validate("Order", &regressed)
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        let paths: Vec<_> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["lines[1].sku", "warehouse_id"]);
+
+        // Other documents are not covered by O1's baseline
+        let other = json!({"id": "O2", "lines": [{"sku": "A"}]});
+        assert!(session.validate("Order", &other).unwrap().is_failure());
+
+        assert!(ErrorBaseline::from_json(&json!({"Order": {"O1": [{"code": "x"}]}})).is_none());
+    }
+
+    #[test]
+    fn test_validate_bulk_summarizes_errors() {
+        let registry = registry();
+        let mut session = ValidationSession::new(&registry);
+        session.insert_key("warehouses", &json!("W1")).reference(
+            "Order",
+            "warehouse_id",
+            "warehouses",
+        );
+
+        let orders = (0..10).map(|i| {
+            json!({
+                "id": format!("O{}", i),
+                "warehouse_id": if i % 2 == 0 { "W1" } else { "W9" },
+                "lines": [{"sku": 1}, {"sku": "A"}, {"sku": "B"}]
+            })
+        });
+        let summary = session.validate_bulk("Order", orders, 2).unwrap();
+
+        assert_eq!(summary.documents(), 10);
+        assert_eq!(summary.invalid_documents(), 10);
+        assert_eq!(summary.error_count(), 25);
+
+        let groups: Vec<_> = summary
+            .groups()
+            .map(|g| (g.path.as_str(), g.code.as_str(), g.count))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("lines[].sku", "invalid_type", 20),
+                ("warehouse_id", "unknown_reference", 5)
+            ]
+        );
+
+        let sku = summary.group("lines[].sku", "invalid_type").unwrap();
+        let samples: Vec<_> = sku
+            .samples
+            .iter()
+            .map(|s| (s.document, s.error.path.to_string()))
+            .collect();
+        assert_eq!(
+            samples,
+            [
+                (0, "lines[1].sku".to_string()),
+                (0, "lines[2].sku".to_string())
+            ]
+        );
+        let reference = summary.group("warehouse_id", "unknown_reference").unwrap();
+        assert_eq!(reference.samples[0].document, 1);
+
+        let json = summary.to_json();
+        assert_eq!(json["errors"], 25);
+        assert_eq!(json["groups"][1]["samples"][1]["document"], 3);
+
+        assert!(session
+            .validate_bulk("Missing", Vec::<Value>::new(), 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_matches_registry_validation() {
+        let registry = SchemaRegistry::new().with_error_factory(|info, _, path| {
+            SchemaError::new(path.clone(), info.message).with_code(format!("acme.{}", info.code))
+        });
+        registry
+            .register(
+                "User",
+                Schema::object()
+                    .field("name", Schema::string().trim())
+                    .field("age", Schema::integer().non_negative()),
+            )
+            .unwrap();
+        registry
+            .set_transform("User", crate::Transform::new().rename("fullName", "name"))
+            .unwrap();
+        let session = ValidationSession::new(&registry);
+
+        let user = json!({"fullName": "  Ada  ", "age": 36});
+        let output = session
+            .validate("User", &user)
+            .unwrap()
+            .into_result()
+            .unwrap();
+        assert_eq!(output, json!({"name": "Ada", "age": 36}));
+        assert_eq!(
+            output,
+            registry
+                .validate("User", &user)
+                .unwrap()
+                .into_result()
+                .unwrap()
+        );
+
+        let errors = session
+            .validate("User", &json!({"fullName": "Ada", "age": -1}))
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().code, "acme.non_negative");
+    }
+
+    #[test]
+    fn test_path_pattern() {
+        let path = JsonPath::root()
+            .push_index(3)
+            .push_field("lines")
+            .push_index(0)
+            .push_index(1)
+            .push_field("sku");
+        assert_eq!(path_pattern(&path), "[].lines[][].sku");
+        assert_eq!(path_pattern(&JsonPath::root()), "");
+    }
+}
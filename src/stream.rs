@@ -0,0 +1,218 @@
+//! Validation of values arriving from async streams.
+//!
+//! Async pipelines, like a queue consumer or a websocket feed, receive
+//! documents one at a time. [`validate_stream`] validates each item of a
+//! [`Stream`] as it arrives and yields its outcome, in order, without first
+//! collecting a batch. Items are treated as elements of one array: item `i`
+//! is validated at path `[i]`, so errors read the same as if the whole array
+//! had been validated at once.
+//!
+//! [`StreamValidator`] validates several items at once on tokio's blocking
+//! thread pool. Only as many items as it validates at once are taken from
+//! the source ahead of the consumer, so a slow consumer applies
+//! backpressure to the source.
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `tokio` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures_util::{stream, StreamExt};
+//! use postmortem::stream::validate_stream;
+//! use postmortem::Schema;
+//! use serde_json::json;
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let items = stream::iter(vec![json!(30), json!(-1)]);
+//! let outcomes: Vec<_> = validate_stream(Schema::integer().non_negative(), items)
+//!     .collect()
+//!     .await;
+//!
+//! assert!(outcomes[0].is_success());
+//! let errors = outcomes[1].clone().into_result().unwrap_err();
+//! assert_eq!(errors.first().path.to_string(), "[1]");
+//! # });
+//! ```
+
+use futures_util::stream::{Stream, StreamExt};
+use serde_json::Value;
+use std::panic::resume_unwind;
+use std::sync::Arc;
+use stillwater::Validation;
+
+use crate::error::SchemaErrors;
+use crate::path::JsonPath;
+use crate::schema::ValueValidator;
+
+/// Validates each item of a stream against `schema`, one at a time.
+///
+/// Yields the outcome of each item in order. Equivalent to
+/// `StreamValidator::new(schema).validate(items)`.
+pub fn validate_stream<V, S>(
+    schema: V,
+    items: S,
+) -> impl Stream<Item = Validation<Value, SchemaErrors>> + Send + 'static
+where
+    V: ValueValidator + 'static,
+    S: Stream<Item = Value> + Send + 'static,
+{
+    StreamValidator::new(schema).validate(items)
+}
+
+/// Validates the items of streams against a schema, optionally several at
+/// once.
+///
+/// # Example
+///
+/// ```rust
+/// use futures_util::{stream, StreamExt};
+/// use postmortem::stream::StreamValidator;
+/// use postmortem::Schema;
+/// use serde_json::json;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let validator = StreamValidator::new(Schema::string().min_len(3)).with_concurrency(4);
+///
+/// let items = stream::iter((0..100).map(|i| json!("x".repeat(i % 5))));
+/// let invalid = validator
+///     .validate(items)
+///     .filter(|outcome| std::future::ready(outcome.is_failure()))
+///     .count()
+///     .await;
+///
+/// assert_eq!(invalid, 60);
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct StreamValidator {
+    schema: Arc<dyn ValueValidator>,
+    concurrency: usize,
+}
+
+impl StreamValidator {
+    /// Creates a validator that validates one item at a time.
+    pub fn new(schema: impl ValueValidator + 'static) -> Self {
+        Self {
+            schema: Arc::new(schema),
+            concurrency: 1,
+        }
+    }
+
+    /// Validates up to `concurrency` items at once.
+    ///
+    /// With a concurrency above 1, each item is validated on tokio's
+    /// blocking thread pool, so the returned stream must be polled within a
+    /// tokio runtime. Outcomes are still yielded in the order of the items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is 0.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "concurrency must be at least 1");
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Validates each item of `items`, yielding the outcomes in order.
+    ///
+    /// The returned stream ends when `items` ends. A panic while validating
+    /// an item is resumed when its outcome is polled.
+    pub fn validate<S>(
+        &self,
+        items: S,
+    ) -> impl Stream<Item = Validation<Value, SchemaErrors>> + Send + 'static
+    where
+        S: Stream<Item = Value> + Send + 'static,
+    {
+        let schema = Arc::clone(&self.schema);
+        let concurrency = self.concurrency;
+        items
+            .enumerate()
+            .map(move |(index, item)| {
+                let schema = Arc::clone(&schema);
+                async move {
+                    let path = JsonPath::root().push_index(index);
+                    if concurrency == 1 {
+                        return schema.validate_value(&item, &path);
+                    }
+                    let task =
+                        tokio::task::spawn_blocking(move || schema.validate_value(&item, &path));
+                    match task.await {
+                        Ok(outcome) => outcome,
+                        Err(e) if e.is_panic() => resume_unwind(e.into_panic()),
+                        Err(e) => panic!("validation of item {} was cancelled: {}", index, e),
+                    }
+                }
+            })
+            .buffered(concurrency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use futures_util::stream;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn outcomes(
+        validator: &StreamValidator,
+        items: Vec<Value>,
+    ) -> Vec<Validation<Value, SchemaErrors>> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .build()
+            .unwrap();
+        runtime.block_on(validator.validate(stream::iter(items)).collect())
+    }
+
+    #[test]
+    fn test_outcomes_keep_item_order() {
+        let schema = Schema::object().field("id", Schema::integer().positive());
+        let items: Vec<Value> = (0..50).map(|i| json!({"id": i % 3})).collect();
+
+        for concurrency in [1, 8] {
+            let validator = StreamValidator::new(schema.clone()).with_concurrency(concurrency);
+            let outcomes = outcomes(&validator, items.clone());
+            assert_eq!(outcomes.len(), 50);
+            for (i, outcome) in outcomes.into_iter().enumerate() {
+                match outcome {
+                    Validation::Success(value) => assert_eq!(value, items[i]),
+                    Validation::Failure(errors) => {
+                        assert_eq!(i % 3, 0);
+                        assert_eq!(errors.first().path.to_string(), format!("[{}].id", i));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_source_is_not_read_ahead() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&pulled);
+        let items = stream::iter(0..100).map(move |i| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            json!(i)
+        });
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let validator = StreamValidator::new(Schema::integer()).with_concurrency(4);
+        let first = runtime.block_on(async {
+            let mut outcomes = Box::pin(validator.validate(items));
+            outcomes.next().await
+        });
+
+        assert!(first.unwrap().is_success());
+        assert!(pulled.load(Ordering::SeqCst) <= 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "concurrency must be at least 1")]
+    fn test_zero_concurrency_panics() {
+        StreamValidator::new(Schema::integer()).with_concurrency(0);
+    }
+}
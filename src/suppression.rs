@@ -0,0 +1,373 @@
+//! Suppressions of known validation errors.
+//!
+//! A suppressions file lists errors that are known and accepted for now, each
+//! with the reason and an optional expiry date, like lint suppressions for
+//! data quality. Loaded into a [`ValidationContext`] with
+//! [`with_suppressions`](ValidationContext::with_suppressions), suppressed
+//! errors are downgraded to warnings, collected by the context. Once a
+//! suppression expires, the errors it matched fail again, together with a
+//! `suppression_expired` error naming the suppression, so that a forgotten
+//! exception cannot stay hidden.
+//!
+//! This complements [`ErrorBaseline`](crate::ErrorBaseline): a baseline
+//! accepts every error a document had at one point in time, while
+//! suppressions accept specific errors, for a stated reason, for a while.
+
+use serde_json::Value;
+use stillwater::Validation;
+
+use crate::error::{SchemaError, SchemaErrors};
+use crate::path::JsonPath;
+use crate::schema::civil::Date;
+use crate::schema::Schema;
+#[cfg(doc)]
+use crate::validation::ValidationContext;
+
+/// An accepted error: the path and code it occurs at, why it is accepted,
+/// and until when.
+///
+/// The path is written as errors display it, e.g. `items[0].sku`; `[*]`
+/// matches any array index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    path: String,
+    code: String,
+    reason: String,
+    expires: Option<Date>,
+}
+
+impl Suppression {
+    /// Suppresses errors with `code` at `path`, for `reason`.
+    pub fn new(
+        path: impl Into<String>,
+        code: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            code: code.into(),
+            reason: reason.into(),
+            expires: None,
+        }
+    }
+
+    /// Sets the last day the suppression applies, as `YYYY-MM-DD`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `date` is not a valid `YYYY-MM-DD` date.
+    pub fn expires(mut self, date: &str) -> Self {
+        self.expires = Some(Date::parse(date).expect("expires() takes a YYYY-MM-DD date"));
+        self
+    }
+
+    /// Returns the path of the suppressed errors.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the code of the suppressed errors.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns why the errors are accepted.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Returns the expiry date as `YYYY-MM-DD`, if there is one.
+    pub fn expiry(&self) -> Option<String> {
+        self.expires.map(|date| date.to_string())
+    }
+
+    fn matches(&self, error: &SchemaError) -> bool {
+        error.code == self.code && path_matches(&self.path, &error.path.to_string())
+    }
+
+    fn is_expired(&self, today: Date) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+}
+
+/// Matches a displayed path against a pattern in which `[*]` matches any
+/// array index.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut rest = path;
+    let mut pieces = pattern.split("[*]");
+    let Some(first) = pieces.next() else {
+        return rest.is_empty();
+    };
+    let Some(after) = rest.strip_prefix(first) else {
+        return false;
+    };
+    rest = after;
+    for piece in pieces {
+        let Some(index) = rest.strip_prefix('[') else {
+            return false;
+        };
+        let digits = index.len() - index.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let Some(after) = index[digits..].strip_prefix(']') else {
+            return false;
+        };
+        let Some(after) = after.strip_prefix(piece).filter(|_| digits > 0) else {
+            return false;
+        };
+        rest = after;
+    }
+    rest.is_empty()
+}
+
+/// An error that was downgraded to a warning: by a [`Suppression`], by
+/// being outvoted in a [consensus](crate::Schema::consensus), or for a
+/// [deprecated field](crate::ObjectSchema::deprecated_field).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuppressedError {
+    /// The suppressed error.
+    pub error: SchemaError,
+    /// Why the error was downgraded, e.g. the reason given by the
+    /// suppression or how the consensus was reached.
+    pub reason: String,
+}
+
+/// A set of [`Suppression`]s, usually loaded from a file.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Schema, SchemaRegistry, Suppressions};
+/// use serde_json::json;
+///
+/// let registry = SchemaRegistry::new();
+/// registry.register("Order", Schema::object()
+///     .field("sku", Schema::string().pattern("^[A-Z]{3}-\\d+$").unwrap())
+///     .field("qty", Schema::integer().positive())).unwrap();
+///
+/// let suppressions = Suppressions::from_json(&json!([
+///     {"path": "sku", "code": "pattern", "reason": "legacy SKUs, migrating in Q3", "expires": "2099-09-30"},
+///     {"path": "qty", "code": "positive", "reason": "zero-quantity test orders", "expires": "2020-01-01"}
+/// ])).into_result().unwrap();
+/// let context = registry.context().with_suppressions(suppressions);
+///
+/// // The SKU error is only a warning now
+/// let order = json!({"sku": "abc", "qty": 1});
+/// assert!(registry.validate_with_context("Order", &order, &context).unwrap().is_success());
+/// let warnings = context.take_warnings();
+/// assert_eq!(warnings[0].reason, "legacy SKUs, migrating in Q3");
+///
+/// // The quantity suppression has expired, so its error fails again
+/// let order = json!({"sku": "ABC-1", "qty": 0});
+/// let errors = registry
+///     .validate_with_context("Order", &order, &context)
+///     .unwrap()
+///     .into_result()
+///     .unwrap_err();
+/// assert_eq!(errors.with_code("positive").len(), 1);
+/// assert_eq!(errors.with_code("suppression_expired").len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    entries: Vec<Suppression>,
+    today: Option<Date>,
+}
+
+impl Suppressions {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a suppression.
+    pub fn with(mut self, suppression: Suppression) -> Self {
+        self.entries.push(suppression);
+        self
+    }
+
+    /// Loads suppressions from a JSON array of objects with `path`, `code`,
+    /// `reason`, and an optional `expires` date.
+    ///
+    /// Fails with the validation errors if the document is malformed.
+    /// Every suppression must give a non-empty reason.
+    pub fn from_json(value: &Value) -> Validation<Self, SchemaErrors> {
+        let schema = Schema::array(
+            Schema::object()
+                .field("path", Schema::string())
+                .field("code", Schema::string().min_len(1))
+                .field("reason", Schema::string().min_len(1))
+                .optional("expires", Schema::string().date())
+                .additional_properties(false),
+        );
+        schema.validate(value, &JsonPath::root()).map(|entries| {
+            let entries = entries
+                .iter()
+                .map(|entry| {
+                    let text = |key: &str| entry[key].as_str().unwrap_or_default().to_string();
+                    Suppression {
+                        path: text("path"),
+                        code: text("code"),
+                        reason: text("reason"),
+                        expires: entry["expires"].as_str().and_then(Date::parse),
+                    }
+                })
+                .collect();
+            Self {
+                entries,
+                today: None,
+            }
+        })
+    }
+
+    /// Checks expiry as of `date` (`YYYY-MM-DD`) rather than today.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `date` is not a valid `YYYY-MM-DD` date.
+    pub fn as_of(mut self, date: &str) -> Self {
+        self.today = Some(Date::parse(date).expect("as_of() takes a YYYY-MM-DD date"));
+        self
+    }
+
+    /// Returns the suppressions.
+    pub fn iter(&self) -> impl Iterator<Item = &Suppression> {
+        self.entries.iter()
+    }
+
+    /// Returns the suppressions that have expired, e.g. for a CI check that
+    /// the file is kept up to date.
+    pub fn expired(&self) -> Vec<&Suppression> {
+        let today = self.today.unwrap_or_else(Date::today);
+        self.entries
+            .iter()
+            .filter(|suppression| suppression.is_expired(today))
+            .collect()
+    }
+
+    /// Returns the number of suppressions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no suppressions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Splits errors into those that still fail and those downgraded to
+    /// warnings.
+    ///
+    /// An error matched by an expired suppression still fails, followed by
+    /// a `suppression_expired` error.
+    pub(crate) fn apply(&self, errors: SchemaErrors) -> (Vec<SchemaError>, Vec<SuppressedError>) {
+        let today = self.today.unwrap_or_else(Date::today);
+        let mut failing = Vec::new();
+        let mut warnings = Vec::new();
+        for error in errors {
+            match self.entries.iter().find(|s| s.matches(&error)) {
+                None => failing.push(error),
+                Some(suppression) if suppression.is_expired(today) => {
+                    let expired = SchemaError::new(
+                        error.path.clone(),
+                        format!(
+                            "suppression of '{}' expired on {}: {}",
+                            suppression.code,
+                            suppression
+                                .expires
+                                .map_or_else(String::new, |d| d.to_string()),
+                            suppression.reason
+                        ),
+                    )
+                    .with_code("suppression_expired");
+                    failing.push(error);
+                    failing.push(expired);
+                }
+                Some(suppression) => warnings.push(SuppressedError {
+                    error,
+                    reason: suppression.reason.clone(),
+                }),
+            }
+        }
+        (failing, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("items[0].sku", "items[0].sku"));
+        assert!(path_matches("items[*].sku", "items[12].sku"));
+        assert!(path_matches("[*]", "[3]"));
+        assert!(path_matches("", ""));
+        assert!(!path_matches("items[*].sku", "items[].sku"));
+        assert!(!path_matches("items[*].sku", "items[0].skus"));
+        assert!(!path_matches("items[*]", "items.x"));
+        assert!(!path_matches("sku", "items[0].sku"));
+    }
+
+    #[test]
+    fn test_apply_as_of() {
+        let suppressions = Suppressions::new()
+            .with(Suppression::new("a", "required", "pending backfill").expires("2026-03-31"))
+            .with(Suppression::new("items[*]", "invalid_type", "vendor bug"));
+        let errors = || {
+            SchemaErrors::from_vec(vec![
+                SchemaError::new(JsonPath::from_field("a"), "missing").with_code("required"),
+                SchemaError::new(JsonPath::from_field("items").push_index(4), "bad")
+                    .with_code("invalid_type"),
+                SchemaError::new(JsonPath::from_field("b"), "missing").with_code("required"),
+            ])
+        };
+
+        let (failing, warnings) = suppressions.clone().as_of("2026-03-31").apply(errors());
+        assert_eq!(failing.len(), 1);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].reason, "vendor bug");
+
+        let suppressions = suppressions.as_of("2026-04-01");
+        assert_eq!(suppressions.expired().len(), 1);
+        let (failing, warnings) = suppressions.apply(errors());
+        let codes: Vec<_> = failing.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["required", "suppression_expired", "required"]);
+        assert_eq!(
+            failing[1].message,
+            "suppression of 'required' expired on 2026-03-31: pending backfill"
+        );
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_from_json_requires_reasons() {
+        let errors = Suppressions::from_json(&json!([
+            {"path": "a", "code": "required", "reason": ""},
+            {"path": "b", "code": "required", "reason": "ok", "expires": "next week"}
+        ]))
+        .into_result()
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+
+        let loaded = Suppressions::from_json(&json!([
+            {"path": "a", "code": "required", "reason": "ok", "expires": "2026-01-31"}
+        ]))
+        .into_result()
+        .unwrap();
+        let suppression = loaded.iter().next().unwrap();
+        assert_eq!(suppression.expiry().as_deref(), Some("2026-01-31"));
+        assert_eq!((suppression.path(), suppression.code()), ("a", "required"));
+
+        let errors = Suppressions::from_json(&json!([
+            {"path": "a", "code": "required", "reason": "ok", "expires": "+999-01-01"}
+        ]))
+        .into_result()
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "YYYY-MM-DD")]
+    fn test_expires_rejects_signed_years() {
+        let _ = Suppression::new("a", "required", "ok").expires("+999-01-01");
+    }
+}
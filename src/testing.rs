@@ -0,0 +1,464 @@
+//! Helpers for testing schemas and triaging failures.
+//!
+//! Schemas that normalize their input (trimming, lowercasing, coercion) must
+//! produce output that validates again unchanged. Otherwise a document saved
+//! after validation can fail the next time it is loaded.
+//!
+//! When a large production payload fails, [`minimize_failure`] shrinks it to
+//! a small reproducer that fails the same way.
+//!
+//! Translated [message catalogs](crate::MessageCatalog) are easy to break
+//! in ways a reviewer who does not read the language will miss: a
+//! placeholder typed with full-width braces by a CJK input method, a
+//! plural form the locale never selects, or a stray directional mark in
+//! right-to-left text. [`assert_catalog_renders`] renders every message to
+//! catch them.
+
+use serde_json::Value;
+use stillwater::Validation;
+
+use crate::error::{
+    placeholder_names, MessageCatalog, SchemaError, FIRST_STRONG_ISOLATE, POP_DIRECTIONAL_ISOLATE,
+};
+use crate::path::{JsonPath, PathSegment};
+use crate::schema::SchemaLike;
+
+/// Asserts that validating a schema's normalized output is a no-op.
+///
+/// The value is validated once, and the output is validated again. The
+/// assertion panics if either validation fails or if the second pass
+/// changes the output. Returns the normalized value.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::testing::assert_normalization_idempotent;
+/// use postmortem::Schema;
+/// use serde_json::json;
+///
+/// let schema = Schema::object().field(
+///     "email",
+///     Schema::string().trim().lowercase().email(),
+/// );
+///
+/// let normalized = assert_normalization_idempotent(
+///     &schema,
+///     &json!({"email": "  Alice@Example.COM "}),
+/// );
+/// assert_eq!(normalized, json!({"email": "alice@example.com"}));
+/// ```
+#[track_caller]
+pub fn assert_normalization_idempotent<S>(schema: &S, value: &Value) -> Value
+where
+    S: SchemaLike + ?Sized,
+{
+    let root = JsonPath::root();
+    let normalized = match schema.validate_to_value(value, &root) {
+        Validation::Success(normalized) => normalized,
+        Validation::Failure(errors) => {
+            panic!("value failed validation: {}", errors)
+        }
+    };
+
+    match schema.validate_to_value(&normalized, &root) {
+        Validation::Success(renormalized) => {
+            assert_eq!(
+                renormalized, normalized,
+                "normalizing an already-normalized value changed it"
+            );
+        }
+        Validation::Failure(errors) => {
+            panic!(
+                "normalized value {} failed re-validation: {}",
+                normalized, errors
+            )
+        }
+    }
+
+    normalized
+}
+
+/// Shrinks a failing value to a minimal one that fails with the same error
+/// codes.
+///
+/// Object fields and array items are removed one at a time, largest first,
+/// keeping each removal after which the value still fails with the same
+/// codes, each as many times as before, until no more can be removed. What remains is a
+/// reproducer in which every field and item is needed for the failure,
+/// e.g. to attach to a bug report instead of a payload with hundreds of
+/// fields. Scalar values are kept as they are.
+///
+/// # Panics
+///
+/// Panics if `value` is valid.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::testing::minimize_failure;
+/// use postmortem::Schema;
+/// use serde_json::json;
+///
+/// let schema = Schema::object()
+///     .field("id", Schema::integer())
+///     .optional("items", Schema::array(Schema::object().field("sku", Schema::string())));
+///
+/// let payload = json!({
+///     "id": 7,
+///     "note": "gift",
+///     "items": [{"sku": "A-1", "qty": 1}, {"qty": 2}, {"sku": "B-2"}]
+/// });
+/// // The second item is missing its sku; `id` is required, so it stays
+/// assert_eq!(minimize_failure(&schema, &payload), json!({"id": 7, "items": [{}]}));
+/// ```
+#[track_caller]
+pub fn minimize_failure<S>(schema: &S, value: &Value) -> Value
+where
+    S: SchemaLike + ?Sized,
+{
+    let Some(codes) = failure_codes(schema, value) else {
+        panic!("value is valid; there is no failure to minimize");
+    };
+
+    let mut current = value.clone();
+    loop {
+        let mut removed = false;
+        let mut paths = removable_paths(&current);
+        // Paths are in preorder, so removing the one at `i` leaves the
+        // earlier ones unchanged and the next one at `i`
+        let mut i = 0;
+        while i < paths.len() {
+            let mut candidate = current.clone();
+            remove(&mut candidate, &paths[i]);
+            if failure_codes(schema, &candidate).as_ref() == Some(&codes) {
+                current = candidate;
+                paths = removable_paths(&current);
+                removed = true;
+            } else {
+                i += 1;
+            }
+        }
+        // A removal can make earlier ones possible, e.g. once an item no
+        // longer makes its array too long
+        if !removed {
+            return current;
+        }
+    }
+}
+
+/// Returns the sorted error codes of validating `value`, with repeats, or
+/// `None` if it is valid.
+fn failure_codes<S>(schema: &S, value: &Value) -> Option<Vec<String>>
+where
+    S: SchemaLike + ?Sized,
+{
+    match schema.validate_to_value(value, &JsonPath::root()) {
+        Validation::Success(_) => None,
+        Validation::Failure(errors) => {
+            let mut codes: Vec<String> = errors.iter().map(|e| e.code.clone()).collect();
+            codes.sort();
+            Some(codes)
+        }
+    }
+}
+
+/// Returns the paths of every object field and array item, in preorder.
+fn removable_paths(value: &Value) -> Vec<JsonPath> {
+    fn collect(value: &Value, path: &JsonPath, paths: &mut Vec<JsonPath>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let child_path = path.push_field(key);
+                    paths.push(child_path.clone());
+                    collect(child, &child_path, paths);
+                }
+            }
+            Value::Array(items) => {
+                for (index, child) in items.iter().enumerate() {
+                    let child_path = path.push_index(index);
+                    paths.push(child_path.clone());
+                    collect(child, &child_path, paths);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut paths = Vec::new();
+    collect(value, &JsonPath::root(), &mut paths);
+    paths
+}
+
+/// Removes the field or item at `path`.
+fn remove(value: &mut Value, path: &JsonPath) {
+    let Some(parent) = path.parent() else {
+        return;
+    };
+    match (parent.resolve_mut(value), path.last()) {
+        (Some(Value::Object(map)), Some(PathSegment::Field(key))) => {
+            map.remove(key);
+        }
+        (Some(Value::Array(items)), Some(PathSegment::Index(index))) if *index < items.len() => {
+            items.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+/// Asserts that every message of a catalog renders cleanly.
+///
+/// Panics with every problem [`catalog_problems`] finds.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::testing::assert_catalog_renders;
+/// use postmortem::MessageCatalog;
+///
+/// let catalog = MessageCatalog::new("ja")
+///     .message("required", "「{field}」は必須項目です")
+///     .message("unique", "{field}に重複した要素があります");
+/// assert_catalog_renders(&catalog);
+/// ```
+#[track_caller]
+pub fn assert_catalog_renders(catalog: &MessageCatalog) {
+    let problems = catalog_problems(catalog);
+    if !problems.is_empty() {
+        panic!(
+            "catalog '{}' has {} problem(s):\n  {}",
+            catalog.locale(),
+            problems.len(),
+            problems.join("\n  ")
+        );
+    }
+}
+
+/// Returns the problems that would make messages of a catalog render
+/// badly, one line each.
+///
+/// Each message is checked for:
+///
+/// - unbalanced braces, and braces typed full-width (`｛｝`);
+/// - placeholders other than `{field}`, `{path}`, `{got}`, and
+///   `{expected}`, which would be left in the message as written;
+/// - placeholders left unrendered when `{got}` is 0, 1, 2, 5, or 21, such
+///   as a plural without an `other` form, the only form of the plural rules
+///   of Chinese and Japanese;
+/// - directional isolates and embeddings that are not closed, which in
+///   right-to-left text reorder whatever follows the message.
+pub fn catalog_problems(catalog: &MessageCatalog) -> Vec<String> {
+    const KNOWN: &[&str] = &["field", "path", "got", "expected"];
+
+    let mut problems = Vec::new();
+    for code in catalog.codes() {
+        let template = catalog.get(code).unwrap_or_default();
+        if template.contains(['｛', '｝']) {
+            problems.push(format!(
+                "{}: full-width brace; placeholders need ASCII braces",
+                code
+            ));
+        }
+        let Some(names) = placeholder_names(template) else {
+            problems.push(format!("{}: unbalanced braces", code));
+            continue;
+        };
+        let unknown: Vec<&String> = names
+            .iter()
+            .filter(|name| !KNOWN.contains(&name.as_str()))
+            .collect();
+        if !unknown.is_empty() {
+            for name in unknown {
+                problems.push(format!("{}: unknown placeholder {{{}}}", code, name));
+            }
+            continue;
+        }
+        for count in [0, 1, 2, 5, 21] {
+            let sample = SchemaError::new(
+                JsonPath::root()
+                    .push_field("order")
+                    .push_field("lines")
+                    .push_index(0)
+                    .push_field("sku"),
+                "",
+            )
+            .with_code(code)
+            .with_got(count.to_string())
+            .with_expected("sample");
+            let (rendered, complete) = catalog.render(sample);
+            let message = rendered.message;
+            if !complete {
+                problems.push(format!(
+                    "{}: placeholder left unrendered when got is {}: {}",
+                    code, count, message
+                ));
+                break;
+            }
+            if let Some(problem) = unbalanced_bidi(&message) {
+                problems.push(format!("{}: {}", code, problem));
+                break;
+            }
+        }
+    }
+    problems
+}
+
+/// Describes the first directional isolate or embedding in `message` that
+/// is not closed, or closed without being opened.
+fn unbalanced_bidi(message: &str) -> Option<&'static str> {
+    let (mut isolates, mut embeddings) = (0usize, 0usize);
+    for c in message.chars() {
+        match c {
+            '\u{2066}' | '\u{2067}' | FIRST_STRONG_ISOLATE => isolates += 1,
+            POP_DIRECTIONAL_ISOLATE if isolates == 0 => {
+                return Some("directional isolate closed without being opened")
+            }
+            POP_DIRECTIONAL_ISOLATE => isolates -= 1,
+            '\u{202A}' | '\u{202B}' | '\u{202D}' | '\u{202E}' => embeddings += 1,
+            '\u{202C}' if embeddings == 0 => {
+                return Some("directional embedding closed without being opened")
+            }
+            '\u{202C}' => embeddings -= 1,
+            _ => {}
+        }
+    }
+    if isolates > 0 {
+        Some("directional isolate is not closed")
+    } else if embeddings > 0 {
+        Some("directional embedding is not closed")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SchemaErrors;
+    use crate::Schema;
+    use serde_json::json;
+
+    /// Appends a marker on every pass, so its output never settles.
+    struct Appending;
+
+    impl SchemaLike for Appending {
+        type Output = String;
+
+        fn validate(&self, value: &Value, _path: &JsonPath) -> Validation<String, SchemaErrors> {
+            Validation::Success(format!("{}!", value.as_str().unwrap_or_default()))
+        }
+
+        fn validate_to_value(
+            &self,
+            value: &Value,
+            path: &JsonPath,
+        ) -> Validation<Value, SchemaErrors> {
+            self.validate(value, path).map(Value::String)
+        }
+    }
+
+    #[test]
+    fn test_idempotent_normalization_passes() {
+        let schema = Schema::array(Schema::string().trim().lowercase().min_len(1));
+        let normalized = assert_normalization_idempotent(&schema, &json!([" A ", "b  "]));
+        assert_eq!(normalized, json!(["a", "b"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "changed it")]
+    fn test_non_idempotent_normalization_panics() {
+        assert_normalization_idempotent(&Appending, &json!("x"));
+    }
+
+    #[test]
+    #[should_panic(expected = "value failed validation")]
+    fn test_invalid_input_panics() {
+        assert_normalization_idempotent(&Schema::string().min_len(5), &json!("abc"));
+    }
+
+    #[test]
+    fn test_minimize_failure_keeps_every_code() {
+        let schema = Schema::object()
+            .field("name", Schema::string().min_len(1))
+            .optional(
+                "tags",
+                Schema::array(Schema::string().max_len(3)).max_len(2),
+            )
+            .optional("meta", Schema::object());
+
+        let payload = json!({
+            "name": "",
+            "meta": {"a": {"b": [1, 2, 3]}},
+            "tags": ["ok", "too long", "fine", "x"]
+        });
+        // Every error is kept: the long tags, and enough tags to be too many
+        assert_eq!(
+            minimize_failure(&schema, &payload),
+            json!({"name": "", "tags": ["too long", "fine", "x"]})
+        );
+
+        // Removing a required field would add an error
+        let payload = json!({"name": "", "tags": ["a", "b"]});
+        assert_eq!(minimize_failure(&schema, &payload), json!({"name": ""}));
+    }
+
+    #[test]
+    #[should_panic(expected = "value is valid")]
+    fn test_minimize_valid_value_panics() {
+        minimize_failure(&Schema::string(), &json!("ok"));
+    }
+
+    #[test]
+    fn test_shipped_catalogs_render() {
+        for (locale, json) in [
+            ("de", include_str!("../catalogs/de.json")),
+            ("fr", include_str!("../catalogs/fr.json")),
+            ("ja", include_str!("../catalogs/ja.json")),
+            ("zh", include_str!("../catalogs/zh.json")),
+        ] {
+            let catalog = MessageCatalog::from_json(locale, json).unwrap();
+            assert!(catalog.get("required").is_some(), "{}", locale);
+            assert_catalog_renders(&catalog);
+        }
+    }
+
+    #[test]
+    fn test_catalog_problems() {
+        let catalog = MessageCatalog::new("zh")
+            .message("required", "｛field｝为必填项")
+            .message("min_length", "{field}太短，至少{min}")
+            .message("max_length", "{field, plural, one {太长}")
+            .message("unique", "{got, plural, one {# 个重复项}}")
+            .message("pattern", "{{field}} stays literal");
+        assert_eq!(
+            catalog_problems(&catalog),
+            [
+                "max_length: unbalanced braces",
+                "min_length: unknown placeholder {min}",
+                "required: full-width brace; placeholders need ASCII braces",
+                "unique: placeholder left unrendered when got is 0: {got, plural, one {# 个重复项}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rtl_catalog_problems() {
+        let catalog = MessageCatalog::new("he")
+            .message("required", "השדה {path} חובה")
+            .message("unique", "\u{202B}{field} מכיל כפילויות")
+            .message("deprecated", "{field}\u{2069} הוצא משימוש");
+        assert_eq!(
+            catalog_problems(&catalog),
+            [
+                "deprecated: directional isolate closed without being opened",
+                "unique: directional embedding is not closed",
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "catalog 'ja' has 1 problem(s)")]
+    fn test_assert_catalog_renders_panics() {
+        assert_catalog_renders(
+            &MessageCatalog::new("ja").message("required", "「{name}」は必須です"),
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Declarative transforms applied to payloads before validation.
+//!
+//! Partners send payloads with quirks: a key spelled differently, a value
+//! nested one level too deep, a full name where first and last names are
+//! expected. A [`Transform`] describes the fixes as a list of steps on
+//! paths, and [`SchemaRegistry::set_transform`](crate::SchemaRegistry::set_transform)
+//! attaches it to a registered schema, so the adapter lives next to the
+//! schema instead of in handler code.
+
+use serde_json::{Map, Value};
+
+use crate::path::{JsonPath, PathSegment};
+
+/// A step of a [`Transform`].
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// Moves the value at `from` to `to`.
+    Move { from: JsonPath, to: JsonPath },
+    /// Splits the string at `path` on `separator` into the values at `into`.
+    Split {
+        path: JsonPath,
+        separator: String,
+        into: Vec<JsonPath>,
+    },
+}
+
+/// A list of steps that reshape a payload before it is validated.
+///
+/// Paths are written the way errors display them, e.g. `customer.address`
+/// or `items[0].sku`; see [`JsonPath::parse`]. Steps run in order, each on
+/// the output of the previous one. A step whose source is missing, or whose
+/// destination already holds a value, does nothing, so a transform can be
+/// applied to payloads that do not have the quirk it fixes.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::Transform;
+/// use serde_json::json;
+///
+/// let transform = Transform::new()
+///     .rename("userName", "user_name")
+///     .move_value("customer.address.zip", "zip")
+///     .split("fullName", " ", ["first_name", "last_name"]);
+///
+/// let mut payload = json!({
+///     "userName": "ada",
+///     "customer": {"address": {"zip": "02134"}},
+///     "fullName": "Ada Lovelace"
+/// });
+/// transform.apply(&mut payload);
+///
+/// assert_eq!(payload, json!({
+///     "user_name": "ada",
+///     "customer": {"address": {}},
+///     "zip": "02134",
+///     "first_name": "Ada",
+///     "last_name": "Lovelace"
+/// }));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transform {
+    steps: Vec<Step>,
+}
+
+impl Transform {
+    /// Creates a transform with no steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renames the key at `path`, keeping it in the same object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid path to an object field.
+    pub fn rename(mut self, path: &str, new_name: impl Into<String>) -> Self {
+        let from = parse(path);
+        let to = match (from.parent(), from.last()) {
+            (Some(parent), Some(PathSegment::Field(_))) => parent.push_field(new_name),
+            _ => panic!("rename() takes the path of an object field, got '{}'", path),
+        };
+        self.steps.push(Step::Move { from, to });
+        self
+    }
+
+    /// Moves the value at `from` to `to`, e.g. to lift a nested value to
+    /// the top level.
+    ///
+    /// Objects on the way to `to` are created as needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either path is invalid, or `from` is not an object field.
+    pub fn move_value(mut self, from: &str, to: &str) -> Self {
+        let (from_path, to) = (parse(from), parse(to));
+        assert!(
+            matches!(from_path.last(), Some(PathSegment::Field(_))),
+            "move_value() takes the path of an object field, got '{}'",
+            from
+        );
+        self.steps.push(Step::Move {
+            from: from_path,
+            to,
+        });
+        self
+    }
+
+    /// Splits the string at `path` on `separator` into the paths `into`.
+    ///
+    /// The string is split into at most as many parts as there are paths,
+    /// so the last path receives the remainder: splitting `"Ada King
+    /// Lovelace"` on a space into two paths gives `"Ada"` and `"King
+    /// Lovelace"`. The source is removed. A value that is not a string is
+    /// left alone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a path is invalid, or `path` is not an object field.
+    pub fn split<I>(mut self, path: &str, separator: impl Into<String>, into: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let source = parse(path);
+        assert!(
+            matches!(source.last(), Some(PathSegment::Field(_))),
+            "split() takes the path of an object field, got '{}'",
+            path
+        );
+        self.steps.push(Step::Split {
+            path: source,
+            separator: separator.into(),
+            into: into.into_iter().map(|p| parse(p.as_ref())).collect(),
+        });
+        self
+    }
+
+    /// Returns `true` if the transform has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Applies the steps to `value` in order.
+    pub fn apply(&self, value: &mut Value) {
+        for step in &self.steps {
+            match step {
+                Step::Move { from, to } => {
+                    if from != to
+                        && from.resolve(value).is_some()
+                        && to.resolve(value).is_none()
+                        && can_insert(value, to)
+                    {
+                        if let Some(moved) = remove(value, from) {
+                            insert(value, to, moved);
+                        }
+                    }
+                }
+                Step::Split {
+                    path,
+                    separator,
+                    into,
+                } => {
+                    let Some(Value::String(text)) = path.resolve(value) else {
+                        continue;
+                    };
+                    if into
+                        .iter()
+                        .any(|target| target.resolve(value).is_some() || !can_insert(value, target))
+                    {
+                        continue;
+                    }
+                    let parts: Vec<String> = text
+                        .splitn(into.len(), separator.as_str())
+                        .map(str::to_string)
+                        .collect();
+                    remove(value, path);
+                    for (target, part) in into.iter().zip(parts) {
+                        insert(value, target, Value::String(part));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parses a path given to a builder method.
+fn parse(path: &str) -> JsonPath {
+    JsonPath::parse(path).unwrap_or_else(|| panic!("invalid path '{}'", path))
+}
+
+/// Removes and returns the object field at `path`.
+fn remove(value: &mut Value, path: &JsonPath) -> Option<Value> {
+    let Some(PathSegment::Field(name)) = path.last() else {
+        return None;
+    };
+    let parent = path.parent()?;
+    parent.resolve_mut(value)?.as_object_mut()?.remove(name)
+}
+
+/// Returns whether [`insert`] can set the value at `path`: the way must not
+/// be blocked by a value that is not an object, or an array index that does
+/// not exist.
+fn can_insert(value: &Value, path: &JsonPath) -> bool {
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+    // `None` stands for an object that insert() will create
+    let mut current = Some(value);
+    for segment in parent.segments() {
+        current = match (segment, current) {
+            (PathSegment::Field(name), Some(Value::Object(map))) => map.get(name),
+            (PathSegment::Field(_), None) => None,
+            (PathSegment::Index(idx), Some(value)) => match value.get(*idx) {
+                Some(item) => Some(item),
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+    match (path.last(), current) {
+        (Some(PathSegment::Field(_)), None | Some(Value::Object(_))) => true,
+        (Some(PathSegment::Index(idx)), Some(Value::Array(items))) => *idx < items.len(),
+        _ => false,
+    }
+}
+
+/// Sets the value at `path`, creating objects on the way as needed.
+///
+/// Does nothing unless [`can_insert`] allows it.
+fn insert(value: &mut Value, path: &JsonPath, new: Value) {
+    let Some(parent) = path.parent() else {
+        *value = new;
+        return;
+    };
+    let mut current = value;
+    for segment in parent.segments() {
+        current = match segment {
+            PathSegment::Field(name) => match current {
+                Value::Object(map) => map
+                    .entry(name.clone())
+                    .or_insert_with(|| Value::Object(Map::new())),
+                _ => return,
+            },
+            PathSegment::Index(idx) => match current.get_mut(*idx) {
+                Some(item) => item,
+                None => return,
+            },
+        };
+    }
+    match (path.last(), current) {
+        (Some(PathSegment::Field(name)), Value::Object(map)) => {
+            map.insert(name.clone(), new);
+        }
+        (Some(PathSegment::Index(idx)), Value::Array(items)) if *idx < items.len() => {
+            items[*idx] = new;
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn applied(transform: &Transform, mut value: Value) -> Value {
+        transform.apply(&mut value);
+        value
+    }
+
+    #[test]
+    fn test_steps_skip_missing_sources_and_taken_destinations() {
+        let transform = Transform::new()
+            .rename("userName", "user_name")
+            .move_value("meta.id", "id");
+
+        // Nothing to fix
+        let payload = json!({"user_name": "ada", "id": 1});
+        assert_eq!(applied(&transform, payload.clone()), payload);
+
+        // Both spellings present: the canonical one is kept, and the quirk
+        // is left for validation to reject
+        let payload = json!({"userName": "x", "user_name": "ada", "meta": {"id": 2}, "id": 1});
+        assert_eq!(applied(&transform, payload.clone()), payload);
+    }
+
+    #[test]
+    fn test_move_into_nested_and_indexed_paths() {
+        let transform = Transform::new()
+            .move_value("zip", "address.postal.code")
+            .move_value("first", "items[0].sku")
+            .move_value("second", "items[5].sku");
+
+        let value = applied(
+            &transform,
+            json!({"zip": "02134", "first": "A-1", "second": "B-2", "items": [{}]}),
+        );
+        assert_eq!(
+            value,
+            json!({
+                "address": {"postal": {"code": "02134"}},
+                "items": [{"sku": "A-1"}],
+                "second": "B-2"
+            })
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let transform = Transform::new().split("name", " ", ["first", "last"]);
+        assert_eq!(
+            applied(&transform, json!({"name": "Ada King Lovelace"})),
+            json!({"first": "Ada", "last": "King Lovelace"})
+        );
+        assert_eq!(
+            applied(&transform, json!({"name": "Ada"})),
+            json!({"first": "Ada"})
+        );
+        assert_eq!(applied(&transform, json!({"name": 7})), json!({"name": 7}));
+    }
+
+    #[test]
+    #[should_panic(expected = "rename() takes the path of an object field, got 'items[0]'")]
+    fn test_rename_requires_a_field() {
+        Transform::new().rename("items[0]", "first");
+    }
+}
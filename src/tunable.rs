@@ -0,0 +1,528 @@
+//! Constraint limits that can be changed without a rebuild.
+//!
+//! During an incident, operators may need to tighten a limit, e.g. cap
+//! usernames at 16 characters instead of 64, faster than a deploy allows. A
+//! constraint given a [`Tunable`] instead of a number looks its limit up by
+//! name each time it is checked:
+//!
+//! ```rust
+//! use postmortem::{set_tunable_source, JsonPath, Schema, Tunable, Tunables};
+//! use serde_json::json;
+//! use std::sync::Arc;
+//!
+//! let tunables = Arc::new(Tunables::new());
+//! tunables.set("username_max", 8.0);
+//! set_tunable_source(tunables.clone());
+//!
+//! let username = Schema::string().min_len(1).max_len(Tunable("username_max"));
+//! assert!(username.validate(&json!("ada_lovelace"), &JsonPath::root()).is_failure());
+//!
+//! tunables.set("username_max", 32.0);
+//! assert!(username.validate(&json!("ada_lovelace"), &JsonPath::root()).is_success());
+//! ```
+//!
+//! Limits come from the [`TunableSource`] installed with
+//! [`set_tunable_source`]: [`Tunables`] holds values set in code, and
+//! [`FileTunables`] reads a JSON file and reloads it when it changes. A
+//! constraint whose tunable has no value, or a value out of range, fails with
+//! an `unresolved_tunable` error rather than letting values through unchecked.
+//!
+//! String and array lengths and integer bounds accept tunables.
+//!
+//! Outcomes cached by an [`OutcomeCache`](crate::OutcomeCache) are keyed by
+//! the source's [generation](TunableSource::generation), so a changed limit
+//! applies to retried payloads too.
+
+use parking_lot::{Mutex, RwLock};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::error::SchemaError;
+use crate::path::JsonPath;
+
+static SOURCE: RwLock<Option<Arc<dyn TunableSource>>> = RwLock::new(None);
+
+/// How many times a source has been installed, so replacing the source
+/// changes the [`generation`].
+static INSTALLS: AtomicU64 = AtomicU64::new(0);
+
+/// How often [`FileTunables`] checks its file for changes by default.
+const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A limit looked up by name when it is checked.
+///
+/// See the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tunable(pub &'static str);
+
+/// The limit of a constraint: a fixed value or a [`Tunable`].
+///
+/// Constraints that accept tunables take `impl Into<Limit<T>>`, so either
+/// can be passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit<T> {
+    /// A value fixed when the schema is built.
+    Fixed(T),
+    /// A value looked up by name when the constraint is checked.
+    Tunable(&'static str),
+}
+
+impl From<usize> for Limit<usize> {
+    fn from(value: usize) -> Self {
+        Limit::Fixed(value)
+    }
+}
+
+impl From<i64> for Limit<i64> {
+    fn from(value: i64) -> Self {
+        Limit::Fixed(value)
+    }
+}
+
+impl<T> From<Tunable> for Limit<T> {
+    fn from(tunable: Tunable) -> Self {
+        Limit::Tunable(tunable.0)
+    }
+}
+
+/// A type of limit that can be given as a [`Tunable`].
+pub trait LimitValue: Copy + ToString {
+    /// Converts `value`, or returns `None` if it is out of range.
+    fn from_tunable(value: f64) -> Option<Self>;
+}
+
+impl LimitValue for usize {
+    fn from_tunable(value: f64) -> Option<Self> {
+        (value.fract() == 0.0 && value >= 0.0 && value <= usize::MAX as f64)
+            .then_some(value as usize)
+    }
+}
+
+impl LimitValue for i64 {
+    fn from_tunable(value: f64) -> Option<Self> {
+        (value.fract() == 0.0 && value >= i64::MIN as f64 && value <= i64::MAX as f64)
+            .then_some(value as i64)
+    }
+}
+
+impl<T: LimitValue> Limit<T> {
+    /// Returns the current value, if it has one.
+    pub(crate) fn current(&self) -> Option<T> {
+        match self {
+            Limit::Fixed(value) => Some(*value),
+            Limit::Tunable(name) => T::from_tunable(tunable_value(name)?),
+        }
+    }
+
+    /// Returns the error for a limit without a current value.
+    pub(crate) fn unresolved(&self, path: &JsonPath) -> SchemaError {
+        let name = match self {
+            Limit::Tunable(name) => name.to_string(),
+            Limit::Fixed(value) => value.to_string(),
+        };
+        SchemaError::new(path.clone(), format!("limit '{}' is not configured", name))
+            .with_code("unresolved_tunable")
+            .with_expected(format!("a value for tunable '{}'", name))
+    }
+
+    /// Returns the current value as text for messages, or the tunable's
+    /// name if it has none.
+    pub(crate) fn describe(&self) -> String {
+        match (self.current(), self) {
+            (Some(value), _) => value.to_string(),
+            (None, Limit::Tunable(name)) => name.to_string(),
+            (None, Limit::Fixed(_)) => unreachable!("fixed limits always resolve"),
+        }
+    }
+}
+
+/// Provides the values of tunables by name.
+pub trait TunableSource: Send + Sync {
+    /// Returns the value of `name`, or `None` if it is not set.
+    fn get(&self, name: &str) -> Option<f64>;
+
+    /// Returns a number that changes whenever any value changes.
+    ///
+    /// Cached validation outcomes are only reused within one generation.
+    /// The default never changes, which suits sources whose values are
+    /// fixed; sources whose values can change should override it.
+    fn generation(&self) -> u64 {
+        0
+    }
+}
+
+/// Sets where every [`Tunable`] is looked up.
+///
+/// The source is process-wide and replaces any set before. Without one,
+/// constraints with tunable limits fail with `unresolved_tunable`.
+pub fn set_tunable_source(source: Arc<dyn TunableSource>) {
+    let mut installed = SOURCE.write();
+    *installed = Some(source);
+    INSTALLS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the generation of the installed source, which changes whenever
+/// a tunable's value may have changed.
+pub(crate) fn generation() -> (u64, u64) {
+    let source = SOURCE.read();
+    (
+        INSTALLS.load(Ordering::Relaxed),
+        source.as_ref().map_or(0, |source| source.generation()),
+    )
+}
+
+/// Returns the current value of the tunable `name`.
+fn tunable_value(name: &str) -> Option<f64> {
+    SOURCE.read().as_ref()?.get(name)
+}
+
+/// Tunable values set in code, e.g. from an admin endpoint.
+///
+/// # Example
+///
+/// ```rust
+/// use postmortem::{Tunables, TunableSource};
+///
+/// let tunables = Tunables::new();
+/// tunables.set("page_size_max", 100.0);
+/// assert_eq!(tunables.get("page_size_max"), Some(100.0));
+/// ```
+#[derive(Debug, Default)]
+pub struct Tunables {
+    values: RwLock<HashMap<String, f64>>,
+    generation: AtomicU64,
+}
+
+impl Tunables {
+    /// Creates an empty set of tunables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of `name`.
+    pub fn set(&self, name: impl Into<String>, value: f64) {
+        self.values.write().insert(name.into(), value);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Removes the value of `name`.
+    pub fn remove(&self, name: &str) {
+        self.values.write().remove(name);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl TunableSource for Tunables {
+    fn get(&self, name: &str) -> Option<f64> {
+        self.values.read().get(name).copied()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+}
+
+/// Tunable values read from a JSON file, reloaded when the file changes.
+///
+/// The file is an object of numbers, e.g. `{"username_max": 32}`. Lookups
+/// check whether it has changed at most once per
+/// [`reload_interval`](Self::reload_interval). If a changed file cannot be
+/// read, the previous values are kept, so a bad edit does not unset every
+/// limit.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use postmortem::{set_tunable_source, FileTunables};
+/// use std::sync::Arc;
+///
+/// let tunables = FileTunables::open("/etc/myapp/limits.json")?;
+/// set_tunable_source(Arc::new(tunables));
+/// # Ok::<(), postmortem::TunableError>(())
+/// ```
+#[derive(Debug)]
+pub struct FileTunables {
+    path: PathBuf,
+    reload_interval: Duration,
+    state: Mutex<FileState>,
+}
+
+#[derive(Debug)]
+struct FileState {
+    values: HashMap<String, f64>,
+    version: Option<(SystemTime, u64)>,
+    checked: Instant,
+    generation: u64,
+}
+
+impl FileState {
+    /// Replaces the values, starting a new generation.
+    fn update(&mut self, values: HashMap<String, f64>) {
+        self.values = values;
+        self.generation += 1;
+    }
+}
+
+impl FileTunables {
+    /// Reads the tunables in the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not an object of
+    /// numbers.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TunableError> {
+        let path = path.as_ref().to_path_buf();
+        let version = file_version(&path);
+        let values = read_tunables(&path)?;
+        Ok(Self {
+            path,
+            reload_interval: DEFAULT_RELOAD_INTERVAL,
+            state: Mutex::new(FileState {
+                values,
+                version,
+                checked: Instant::now(),
+                generation: 0,
+            }),
+        })
+    }
+
+    /// Sets how often lookups check the file for changes. The default is
+    /// one second.
+    pub fn reload_interval(mut self, interval: Duration) -> Self {
+        self.reload_interval = interval;
+        self
+    }
+
+    /// Reads the file again now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and keeps the previous values, if the file cannot
+    /// be read or is not an object of numbers.
+    pub fn reload(&self) -> Result<(), TunableError> {
+        let mut state = self.state.lock();
+        state.checked = Instant::now();
+        state.version = file_version(&self.path);
+        let values = read_tunables(&self.path)?;
+        state.update(values);
+        Ok(())
+    }
+
+    /// Reloads the file if it has changed and the reload interval has
+    /// passed since it was last checked.
+    fn refresh(&self, state: &mut FileState) {
+        if state.checked.elapsed() < self.reload_interval {
+            return;
+        }
+        state.checked = Instant::now();
+        let version = file_version(&self.path);
+        if version != state.version {
+            state.version = version;
+            if let Ok(values) = read_tunables(&self.path) {
+                state.update(values);
+            }
+        }
+    }
+}
+
+impl TunableSource for FileTunables {
+    fn get(&self, name: &str) -> Option<f64> {
+        let mut state = self.state.lock();
+        self.refresh(&mut state);
+        state.values.get(name).copied()
+    }
+
+    fn generation(&self) -> u64 {
+        let mut state = self.state.lock();
+        self.refresh(&mut state);
+        state.generation
+    }
+}
+
+/// Returns when the file was last modified, and its size.
+fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn read_tunables(path: &Path) -> Result<HashMap<String, f64>, TunableError> {
+    let content = fs::read_to_string(path)?;
+    let document: Value = serde_json::from_str(&content).map_err(TunableError::Parse)?;
+    let Value::Object(object) = document else {
+        return Err(TunableError::NotAnObject);
+    };
+    object
+        .into_iter()
+        .map(|(name, value)| match value.as_f64() {
+            Some(number) => Ok((name, number)),
+            None => Err(TunableError::NotANumber(name)),
+        })
+        .collect()
+}
+
+/// Errors that can occur reading a tunables file.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TunableError {
+    /// IO error reading the file
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The file is not valid JSON.
+    #[error("invalid JSON: {0}")]
+    Parse(serde_json::Error),
+
+    /// The file is not a JSON object.
+    #[error("tunables file must contain a JSON object")]
+    NotAnObject,
+
+    /// A tunable's value is not a number.
+    #[error("tunable '{0}' must be a number")]
+    NotANumber(String),
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::OnceLock;
+
+    /// Returns the tunables installed for every test in the crate, which
+    /// share the process-wide source and so must use distinct names.
+    pub(crate) fn test_tunables() -> &'static Arc<Tunables> {
+        static TUNABLES: OnceLock<Arc<Tunables>> = OnceLock::new();
+        TUNABLES.get_or_init(|| {
+            let tunables = Arc::new(Tunables::new());
+            set_tunable_source(tunables.clone());
+            tunables
+        })
+    }
+
+    #[test]
+    fn test_limit_resolution() {
+        test_tunables().set("tunable_test_len", 12.0);
+        test_tunables().set("tunable_test_fraction", 1.5);
+        test_tunables().set("tunable_test_negative", -3.0);
+
+        assert_eq!(Limit::<usize>::from(4).current(), Some(4));
+        assert_eq!(
+            Limit::<usize>::from(Tunable("tunable_test_len")).current(),
+            Some(12)
+        );
+        assert_eq!(
+            Limit::<usize>::from(Tunable("tunable_test_fraction")).current(),
+            None
+        );
+        assert_eq!(
+            Limit::<usize>::from(Tunable("tunable_test_negative")).current(),
+            None
+        );
+        assert_eq!(
+            Limit::<i64>::from(Tunable("tunable_test_negative")).current(),
+            Some(-3)
+        );
+
+        let unset = Limit::<usize>::from(Tunable("tunable_test_unset"));
+        assert_eq!(unset.describe(), "tunable_test_unset");
+        let error = unset.unresolved(&JsonPath::from_field("name"));
+        assert_eq!(error.code, "unresolved_tunable");
+        assert_eq!(
+            error.message,
+            "limit 'tunable_test_unset' is not configured"
+        );
+    }
+
+    #[test]
+    fn test_tunable_constraints() {
+        use crate::schema::Schema;
+        use serde_json::json;
+
+        let tunables = test_tunables();
+        tunables.set("tunable_test_name_max", 4.0);
+        tunables.set("tunable_test_tags_min", 1.0);
+        tunables.set("tunable_test_page_max", 50.0);
+        let root = JsonPath::root();
+
+        let name = Schema::string()
+            .max_len(Tunable("tunable_test_name_max"))
+            .error("at most {max} characters");
+        let errors = name
+            .validate(&json!("grace"), &root)
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().message, "at most 4 characters");
+        tunables.set("tunable_test_name_max", 8.0);
+        assert!(name.validate(&json!("grace"), &root).is_success());
+
+        let tags = Schema::array(Schema::string()).min_len(Tunable("tunable_test_tags_min"));
+        assert!(tags.validate(&json!([]), &root).is_failure());
+        assert_eq!(
+            crate::ToJsonSchema::to_json_schema(&tags)["minItems"],
+            json!(1)
+        );
+
+        let page = Schema::integer()
+            .min(1)
+            .max(Tunable("tunable_test_page_max"));
+        assert!(page.validate(&json!(50), &root).is_success());
+        assert!(page.validate(&json!(51), &root).is_failure());
+
+        tunables.remove("tunable_test_page_max");
+        let errors = page.validate(&json!(10), &root).into_result().unwrap_err();
+        assert_eq!(errors.first().code, "unresolved_tunable");
+    }
+
+    #[test]
+    fn test_cached_outcomes_follow_tunable_changes() {
+        use crate::schema::Schema;
+        use crate::{OutcomeCache, SchemaRegistry};
+        use serde_json::json;
+
+        let tunables = test_tunables();
+        tunables.set("tunable_test_username_max", 64.0);
+        let registry = SchemaRegistry::new().with_outcome_cache(OutcomeCache::new(10));
+        registry
+            .register(
+                "User",
+                Schema::string().max_len(Tunable("tunable_test_username_max")),
+            )
+            .unwrap();
+
+        let username = json!("ada_lovelace");
+        assert!(registry.validate("User", &username).unwrap().is_success());
+        tunables.set("tunable_test_username_max", 8.0);
+        assert!(registry.validate("User", &username).unwrap().is_failure());
+    }
+
+    #[test]
+    fn test_file_tunables_reload() {
+        let path =
+            std::env::temp_dir().join(format!("postmortem-tunables-{}.json", std::process::id()));
+        fs::write(&path, r#"{"name_max": 8}"#).unwrap();
+        let tunables = FileTunables::open(&path)
+            .unwrap()
+            .reload_interval(Duration::ZERO);
+        assert_eq!(tunables.get("name_max"), Some(8.0));
+
+        let generation = tunables.generation();
+        fs::write(&path, r#"{"name_max": 16, "page_max": 100}"#).unwrap();
+        assert_ne!(tunables.generation(), generation);
+        assert_eq!(tunables.get("name_max"), Some(16.0));
+        assert_eq!(tunables.get("page_max"), Some(100.0));
+
+        // A bad edit keeps the previous values
+        fs::write(&path, r#"{"name_max": "sixteen", "page_max": 1}"#).unwrap();
+        assert_eq!(tunables.get("name_max"), Some(16.0));
+        assert!(matches!(
+            tunables.reload(),
+            Err(TunableError::NotANumber(name)) if name == "name_max"
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,9 +2,45 @@
 //!
 //! This module provides the [`ValidationContext`] type that carries registry information
 //! and depth tracking during validation. It enables schema references to be resolved
-//! and prevents infinite loops in circular references.
+//! and prevents infinite loops in circular references. A context can also
+//! carry a correlation id and a failure hook, so failures can be joined with
+//! request traces; see [`FailureReport`]. Errors listed in a context's
+//! [`Suppressions`] are downgraded to warnings. A [`ValidationMode`] lets
+//! one object schema validate documents being created, updated, or read.
+//! [`ValidationContext::scoped`] adjusts any of these for one subtree of a
+//! document, such as an embedded third-party payload validated leniently.
 
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use stillwater::Validation;
+
+use crate::error::{ErrorSettings, GotLimit, SchemaErrors};
+use crate::suppression::{SuppressedError, Suppressions};
+
+/// What a document is validated for.
+///
+/// Set on a context with [`ValidationContext::with_mode`], so a single
+/// object schema can describe a resource in its requests and responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValidationMode {
+    /// The document creates a resource: read-only fields, such as a
+    /// server-assigned id, must not be given.
+    Create,
+    /// The document updates a resource: read-only fields are required, to
+    /// identify it.
+    Update,
+    /// The document is a resource being returned: write-only fields, such
+    /// as a password hash, are removed from the output.
+    Read,
+}
+
+/// Type alias for hooks called with each failed validation.
+type FailureHook = Arc<dyn Fn(&FailureReport<'_>) + Send + Sync>;
 
 /// Validation context carries registry and depth tracking information.
 ///
@@ -12,6 +48,12 @@ use std::sync::Arc;
 /// - Schema reference resolution via registry lookup
 /// - Depth tracking to prevent infinite loops in circular references
 /// - Thread-safe access to shared registry
+/// - Negative caching of reference names that failed to resolve
+/// - A correlation id and failure hook for tracing failed validations
+/// - Suppressions of known errors, and the warnings they produce
+/// - The [`ValidationMode`] of read-only and write-only fields
+/// - Whether errors are only [warnings](Self::lenient)
+/// - How much of a failing value errors [record](Self::with_got_limit)
 ///
 /// The context uses Arc for the registry to avoid lifetime constraints
 /// and enable flexible ownership patterns during validation.
@@ -20,6 +62,15 @@ pub struct ValidationContext {
     registry: Arc<dyn RegistryAccess>,
     depth: usize,
     max_depth: usize,
+    missing_refs: Arc<Mutex<HashMap<String, String>>>,
+    correlation_id: Option<Arc<str>>,
+    failure_hook: Option<FailureHook>,
+    suppressions: Option<Arc<Suppressions>>,
+    warnings: Arc<Mutex<Vec<SuppressedError>>>,
+    computed: Arc<AtomicBool>,
+    mode: Option<ValidationMode>,
+    lenient: bool,
+    errors: ErrorSettings,
 }
 
 impl ValidationContext {
@@ -29,6 +80,317 @@ impl ValidationContext {
             registry,
             depth: 0,
             max_depth,
+            missing_refs: Arc::new(Mutex::new(HashMap::new())),
+            correlation_id: None,
+            failure_hook: None,
+            suppressions: None,
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            computed: Arc::new(AtomicBool::new(false)),
+            mode: None,
+            lenient: false,
+            errors: ErrorSettings::DEFAULT,
+        }
+    }
+
+    /// Tags validations run with this context with a correlation id, such
+    /// as the trace id of the request being validated.
+    ///
+    /// The id is included in every [`FailureReport`] passed to the
+    /// [failure hook](Self::on_failure).
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(Arc::from(id.into()));
+        self
+    }
+
+    /// Returns the correlation id, if one was set.
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    /// Sets a hook called with a [`FailureReport`] for each failed
+    /// validation run with this context, e.g. to log it or export it to a
+    /// tracing system.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{Schema, SchemaRegistry};
+    /// use serde_json::json;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Age", Schema::integer().non_negative()).unwrap();
+    ///
+    /// let logged = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&logged);
+    /// let context = registry
+    ///     .context()
+    ///     .with_correlation_id("trace-7f3a")
+    ///     .on_failure(move |report| sink.lock().unwrap().push(report.to_json()));
+    ///
+    /// let result = registry.validate_with_context("Age", &json!(-1), &context).unwrap();
+    /// assert!(result.is_failure());
+    ///
+    /// let logged = logged.lock().unwrap();
+    /// assert_eq!(logged[0]["correlation_id"], "trace-7f3a");
+    /// assert_eq!(logged[0]["errors"][0]["code"], "non_negative");
+    /// ```
+    pub fn on_failure<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&FailureReport<'_>) + Send + Sync + 'static,
+    {
+        self.failure_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Calls the failure hook, if any, for a failed validation of `schema`.
+    pub(crate) fn report_failure(&self, schema: &str, errors: &SchemaErrors) {
+        if let Some(hook) = &self.failure_hook {
+            hook(&FailureReport {
+                schema,
+                correlation_id: self.correlation_id(),
+                errors,
+            });
+        }
+    }
+
+    /// Downgrades errors matched by `suppressions` to warnings in
+    /// validations run with this context.
+    ///
+    /// Suppressed errors are collected by the context; see
+    /// [`take_warnings`](Self::take_warnings). A value whose errors are all
+    /// suppressed passes, with the input value as its output. See
+    /// [`Suppressions`] for an example.
+    pub fn with_suppressions(mut self, suppressions: Suppressions) -> Self {
+        self.suppressions = Some(Arc::new(suppressions));
+        self
+    }
+
+    /// Returns and clears the warnings recorded so far: errors downgraded
+    /// by suppressions, outvoted in a [consensus](crate::Schema::consensus),
+    /// or for [deprecated fields](crate::ObjectSchema::deprecated_field).
+    ///
+    /// Warnings are shared by all clones of a context, so one context used
+    /// for a batch collects the warnings of the whole batch. Warnings raised
+    /// by a `one_of`, `any_of`, or consensus schema that did not accept the
+    /// value are dropped.
+    pub fn take_warnings(&self) -> Vec<SuppressedError> {
+        std::mem::take(&mut *self.warnings.lock())
+    }
+
+    /// Records warnings for errors that did not fail validation.
+    pub(crate) fn warn(&self, warnings: impl IntoIterator<Item = SuppressedError>) {
+        self.warnings.lock().extend(warnings);
+    }
+
+    /// Records that a validation computed part of its output, such as a
+    /// [computed default](crate::ObjectSchema::default_with), so its outcome
+    /// must not be cached.
+    pub(crate) fn mark_computed(&self) {
+        self.computed.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether a validation with this context computed part of its
+    /// output.
+    pub(crate) fn has_computed(&self) -> bool {
+        self.computed.load(Ordering::Relaxed)
+    }
+
+    /// Returns a copy of this context that records its warnings and
+    /// computed outputs apart from this one.
+    ///
+    /// Used to validate a branch that may be discarded, such as a losing
+    /// `one_of` schema; [`adopt`](Self::adopt) keeps what it recorded.
+    pub(crate) fn detached(&self) -> Self {
+        Self {
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            computed: Arc::new(AtomicBool::new(false)),
+            ..self.clone()
+        }
+    }
+
+    /// Keeps the warnings and computed outputs recorded by a
+    /// [detached](Self::detached) copy of this context.
+    pub(crate) fn adopt(&self, detached: &Self) {
+        self.warn(detached.take_warnings());
+        if detached.has_computed() {
+            self.mark_computed();
+        }
+    }
+
+    /// Applies the context's suppressions, if any, to the result of
+    /// validating `value`.
+    pub(crate) fn suppress(
+        &self,
+        result: Validation<Value, SchemaErrors>,
+        value: &Value,
+    ) -> Validation<Value, SchemaErrors> {
+        let Some(suppressions) = &self.suppressions else {
+            return result;
+        };
+        match result {
+            Validation::Failure(errors) => {
+                let (failing, warnings) = suppressions.apply(errors);
+                self.warn(warnings);
+                if failing.is_empty() {
+                    Validation::Success(value.clone())
+                } else {
+                    Validation::Failure(SchemaErrors::from_vec(failing))
+                }
+            }
+            success => success,
+        }
+    }
+
+    /// Validates documents for `mode`, which decides how
+    /// [read-only](crate::ObjectSchema::read_only) and
+    /// [write-only](crate::ObjectSchema::write_only) fields are treated.
+    ///
+    /// Without a mode, those fields are validated like any other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::validation::ValidationMode;
+    /// use postmortem::{Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("User", Schema::object()
+    ///     .optional("id", Schema::integer()).read_only()
+    ///     .field("name", Schema::string())
+    ///     .optional("password", Schema::string()).write_only()).unwrap();
+    ///
+    /// let create = registry.context().with_mode(ValidationMode::Create);
+    /// let result = registry
+    ///     .validate_with_context("User", &json!({"id": 7, "name": "Ada"}), &create)
+    ///     .unwrap();
+    /// assert_eq!(result.into_result().unwrap_err().first().code, "read_only");
+    ///
+    /// let update = registry.context().with_mode(ValidationMode::Update);
+    /// let result = registry
+    ///     .validate_with_context("User", &json!({"name": "Ada"}), &update)
+    ///     .unwrap();
+    /// assert_eq!(result.into_result().unwrap_err().first().code, "required");
+    ///
+    /// let read = registry.context().with_mode(ValidationMode::Read);
+    /// let user = json!({"id": 7, "name": "Ada", "password": "hunter2"});
+    /// let output = registry.validate_with_context("User", &user, &read).unwrap();
+    /// assert_eq!(output.into_result().unwrap(), json!({"id": 7, "name": "Ada"}));
+    /// ```
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Returns the validation mode, if one was set.
+    pub fn mode(&self) -> Option<ValidationMode> {
+        self.mode
+    }
+
+    /// Downgrades every error to a warning in validations run with this
+    /// context, so values always pass.
+    ///
+    /// This is meant for a [scope](Self::scoped) holding data the
+    /// application does not control; the errors are still collected by
+    /// [`take_warnings`](Self::take_warnings).
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Returns whether errors are downgraded to warnings.
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Sets how much of a failing value the errors of validations run with
+    /// this context record.
+    ///
+    /// The limit applies to every error built while a
+    /// [`SchemaRegistry`](crate::SchemaRegistry) validates with this
+    /// context, including those of [`SchemaError::with_got`](crate::SchemaError::with_got)
+    /// in custom validators and error factories. The default is
+    /// [`GotLimit::default`], or the registry's
+    /// [limit](crate::SchemaRegistry::with_got_limit) for its
+    /// [contexts](crate::SchemaRegistry::context).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::{GotLimit, Schema, SchemaRegistry};
+    /// use serde_json::json;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// registry.register("Token", Schema::string().pattern("^tok_").unwrap()).unwrap();
+    ///
+    /// let context = registry.context().with_got_limit(GotLimit::Omit);
+    /// let result = registry
+    ///     .validate_with_context("Token", &json!("secret-value"), &context)
+    ///     .unwrap();
+    /// assert_eq!(result.into_result().unwrap_err().first().got, None);
+    /// ```
+    pub fn with_got_limit(mut self, limit: GotLimit) -> Self {
+        self.errors.got_limit = limit;
+        self
+    }
+
+    /// Returns the settings errors are built with.
+    pub(crate) fn error_settings(&self) -> ErrorSettings {
+        self.errors
+    }
+
+    /// Replaces the settings errors are built with.
+    pub(crate) fn with_error_settings(mut self, settings: ErrorSettings) -> Self {
+        self.errors = settings;
+        self
+    }
+
+    /// Returns a context for a nested region of a document, with the
+    /// policy changed by `adjust`.
+    ///
+    /// The scope shares the registry, depth, reference cache, and warnings
+    /// of this context, so warnings raised inside it are collected with the
+    /// others. Changes made by `adjust`, such as
+    /// [`lenient`](Self::lenient), [`with_mode`](Self::with_mode), or
+    /// [`with_suppressions`](Self::with_suppressions), apply only to the
+    /// scope. [`Schema::scoped`](crate::Schema::scoped) validates a
+    /// sub-schema in a scope, and ends it when the sub-schema is done.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use postmortem::SchemaRegistry;
+    ///
+    /// let registry = SchemaRegistry::new();
+    /// let context = registry.context();
+    /// let scope = context.scoped(|c| c.lenient());
+    /// assert!(scope.is_lenient());
+    /// assert!(!context.is_lenient());
+    /// ```
+    pub fn scoped<F>(&self, adjust: F) -> Self
+    where
+        F: FnOnce(Self) -> Self,
+    {
+        adjust(self.clone())
+    }
+
+    /// Ends a scope: applies its suppressions and, if it is lenient,
+    /// downgrades the remaining errors of `result` to warnings.
+    pub(crate) fn close_scope(
+        &self,
+        result: Validation<Value, SchemaErrors>,
+        value: &Value,
+    ) -> Validation<Value, SchemaErrors> {
+        match self.suppress(result, value) {
+            Validation::Failure(errors) if self.lenient => {
+                self.warn(errors.into_iter().map(|error| SuppressedError {
+                    error,
+                    reason: "validated leniently".to_string(),
+                }));
+                Validation::Success(value.clone())
+            }
+            result => result,
         }
     }
 
@@ -41,6 +403,15 @@ impl ValidationContext {
             registry: Arc::clone(&self.registry),
             depth: self.depth + 1,
             max_depth: self.max_depth,
+            missing_refs: Arc::clone(&self.missing_refs),
+            correlation_id: self.correlation_id.clone(),
+            failure_hook: self.failure_hook.clone(),
+            suppressions: self.suppressions.clone(),
+            warnings: Arc::clone(&self.warnings),
+            computed: Arc::clone(&self.computed),
+            mode: self.mode,
+            lenient: self.lenient,
+            errors: self.errors,
         }
     }
 
@@ -58,6 +429,83 @@ impl ValidationContext {
     pub fn registry(&self) -> &dyn RegistryAccess {
         &*self.registry
     }
+
+    /// Returns the cached error message for a reference that previously
+    /// failed to resolve in this context.
+    ///
+    /// The cache is shared by all contexts derived from the same root via
+    /// [`increment_depth`](Self::increment_depth), so a batch validated with
+    /// one context only looks up and diagnoses each unknown name once.
+    pub fn cached_missing_ref(&self, name: &str) -> Option<String> {
+        self.missing_refs.lock().get(name).cloned()
+    }
+
+    /// Records the error message for a reference that failed to resolve.
+    pub fn cache_missing_ref(&self, name: impl Into<String>, message: impl Into<String>) {
+        self.missing_refs.lock().insert(name.into(), message.into());
+    }
+}
+
+/// A failed validation, as passed to a context's
+/// [failure hook](ValidationContext::on_failure).
+///
+/// `Display` renders a single log line prefixed with the correlation id,
+/// e.g. `[trace-7f3a] User failed validation: age: must be at least 0`,
+/// and [`to_json`](Self::to_json) a structured record for log pipelines.
+#[derive(Debug, Clone, Copy)]
+pub struct FailureReport<'a> {
+    /// The name of the schema the value was validated against.
+    pub schema: &'a str,
+    /// The correlation id of the context, if one was set.
+    pub correlation_id: Option<&'a str>,
+    /// The validation errors.
+    pub errors: &'a SchemaErrors,
+}
+
+impl FailureReport<'_> {
+    /// Serializes the report as JSON.
+    ///
+    /// Each error becomes an object with `path`, `code`, and `message`, plus
+    /// `expected`, `got`, and `params` when present. The correlation id is `null` if
+    /// none was set.
+    pub fn to_json(&self) -> Value {
+        let errors: Vec<Value> = self
+            .errors
+            .iter()
+            .map(|error| {
+                let mut entry = json!({
+                    "path": error.path.to_string(),
+                    "code": error.code,
+                    "message": error.message,
+                });
+                if let Some(expected) = &error.expected {
+                    entry["expected"] = json!(expected);
+                }
+                if let Some(got) = &error.got {
+                    entry["got"] = json!(got);
+                }
+                if !error.params.is_empty() {
+                    entry["params"] = Value::Object(error.params.clone());
+                }
+                entry
+            })
+            .collect();
+
+        json!({
+            "schema": self.schema,
+            "correlation_id": self.correlation_id,
+            "errors": errors,
+        })
+    }
+}
+
+impl Display for FailureReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(id) = self.correlation_id {
+            write!(f, "[{}] ", id)?;
+        }
+        write!(f, "{} failed validation: {}", self.schema, self.errors)
+    }
 }
 
 /// Trait for accessing schemas from a registry.
@@ -67,4 +515,12 @@ impl ValidationContext {
 pub trait RegistryAccess: Send + Sync {
     /// Gets a schema by name from the registry.
     fn get_schema(&self, name: &str) -> Option<Arc<dyn crate::schema::ValueValidator>>;
+
+    /// Returns the names of all schemas in the registry.
+    ///
+    /// Used to suggest close matches when a reference cannot be resolved.
+    /// The default implementation returns no names.
+    fn schema_names(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
@@ -1,4 +1,5 @@
-use postmortem::{JsonPath, Schema, SchemaLike, ValueValidator};
+use postmortem::validation::ValidationMode;
+use postmortem::{JsonPath, KeyCase, Quorum, Schema, SchemaLike, SchemaRegistry, ValueValidator};
 use serde_json::json;
 use stillwater::Validation;
 
@@ -43,6 +44,47 @@ fn test_one_of_no_matches() {
     }
 }
 
+#[test]
+fn test_one_of_branch_errors() {
+    let schema = Schema::one_of(vec![
+        boxed(Schema::object().field("radius", Schema::integer().positive())),
+        boxed(Schema::object().field("width", Schema::integer())),
+    ])
+    .with_branch_errors(true);
+
+    let errors = schema
+        .validate(&json!({"radius": 0}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    let reported: Vec<_> = errors
+        .iter()
+        .map(|e| (e.path.to_string(), e.code.as_str()))
+        .collect();
+    assert_eq!(
+        reported,
+        [
+            ("".to_string(), "one_of_none_matched"),
+            ("radius".to_string(), "positive"),
+            ("width".to_string(), "required"),
+        ]
+    );
+    assert!(errors
+        .iter()
+        .nth(2)
+        .unwrap()
+        .message
+        .starts_with("branch 1: "));
+
+    let context = postmortem::SchemaRegistry::new().context();
+    let result = schema.validate_with_context(&json!({"radius": 0}), &JsonPath::root(), &context);
+    assert_eq!(result.into_result().unwrap_err().len(), 3);
+
+    // Only the summary by default
+    let schema = schema.with_branch_errors(false);
+    let result = schema.validate(&json!({"radius": 0}), &JsonPath::root());
+    assert_eq!(result.into_result().unwrap_err().len(), 1);
+}
+
 #[test]
 fn test_one_of_multiple_matches() {
     // Both schemas accept strings
@@ -185,6 +227,102 @@ fn test_all_of_all_passing() {
     assert!(result.is_success());
 }
 
+#[test]
+fn test_all_of_merges_object_outputs() {
+    let schema = Schema::all_of(vec![
+        boxed(
+            Schema::object()
+                .field("name", Schema::string().trim())
+                .field("address", Schema::object().field("city", Schema::string())),
+        ),
+        boxed(
+            Schema::object()
+                .default("role", Schema::string(), json!("user"))
+                .field(
+                    "address",
+                    Schema::object().default("country", Schema::string(), json!("NZ")),
+                ),
+        ),
+    ]);
+
+    let output = schema
+        .validate(
+            &json!({"name": " Ada ", "address": {"city": "Wellington"}}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap();
+    // The trimmed name wins over the second schema's pass-through
+    assert_eq!(
+        output,
+        json!({
+            "name": "Ada",
+            "address": {"city": "Wellington", "country": "NZ"},
+            "role": "user"
+        })
+    );
+}
+
+#[test]
+fn test_all_of_keeps_renamed_keys_renamed() {
+    let schema = Schema::all_of(vec![
+        boxed(
+            Schema::object()
+                .field("userName", Schema::string())
+                .rename_keys(KeyCase::SnakeCase),
+        ),
+        boxed(Schema::object().additional_properties(true)),
+    ]);
+
+    let output = schema
+        .validate(&json!({"userName": "ada"}), &JsonPath::root())
+        .into_result()
+        .unwrap();
+    assert_eq!(output, json!({"user_name": "ada"}));
+}
+
+#[test]
+fn test_all_of_keeps_stripped_keys_stripped() {
+    let schema = Schema::all_of(vec![
+        boxed(Schema::object().additional_properties(true)),
+        boxed(
+            Schema::object()
+                .field("name", Schema::string())
+                .optional("password", Schema::string())
+                .write_only(),
+        ),
+        boxed(Schema::object().field("name", Schema::string().trim())),
+    ]);
+    let context = SchemaRegistry::new()
+        .context()
+        .with_mode(ValidationMode::Read);
+
+    let output = schema
+        .validate_value_with_context(
+            &json!({"name": " Ada ", "password": "hunter2"}),
+            &JsonPath::root(),
+            &context,
+        )
+        .into_result()
+        .unwrap();
+    assert_eq!(output, json!({"name": "Ada"}));
+}
+
+#[test]
+fn test_all_of_later_transformation_wins() {
+    let schema = Schema::all_of(vec![
+        boxed(Schema::string().trim()),
+        boxed(Schema::string()),
+        boxed(Schema::string().lowercase()),
+    ]);
+    // Two schemas change the value, so the later one wins
+    let output = schema
+        .validate(&json!(" ADA "), &JsonPath::root())
+        .into_result()
+        .unwrap();
+    assert_eq!(output, json!(" ada "));
+}
+
 #[test]
 fn test_all_of_some_failing() {
     let schema = Schema::all_of(vec![
@@ -444,3 +582,421 @@ fn test_combinator_error_paths() {
         assert_eq!(error.path.to_string(), "id");
     }
 }
+
+// ====== consensus Tests ======
+
+fn max_len_votes(quorum: Quorum) -> postmortem::CombinatorSchema {
+    Schema::consensus(
+        vec![
+            boxed(Schema::string().max_len(3)),
+            boxed(Schema::string().max_len(5)),
+            boxed(Schema::string().max_len(7)),
+        ],
+        quorum,
+    )
+}
+
+#[test]
+fn test_consensus_quorum() {
+    let schema = max_len_votes(Quorum::MinAgree(2));
+    assert!(schema
+        .validate(&json!("abcd"), &JsonPath::root())
+        .is_success());
+
+    let result = schema.validate(&json!("abcdef"), &JsonPath::root());
+    let Validation::Failure(errors) = result else {
+        panic!("expected failure");
+    };
+    // A summary, then the errors of both dissenting schemas
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors.first().code, "consensus_not_reached");
+    assert_eq!(
+        errors.first().message,
+        "1 of 3 schemas accepted the value, at least 2 required"
+    );
+    assert_eq!(errors.with_code("max_length").len(), 2);
+}
+
+#[test]
+fn test_consensus_majority() {
+    let schema = max_len_votes(Quorum::Majority);
+    assert!(schema
+        .validate(&json!("abcd"), &JsonPath::root())
+        .is_success());
+    assert!(schema
+        .validate(&json!("abcdef"), &JsonPath::root())
+        .is_failure());
+
+    let all = max_len_votes(Quorum::MinAgree(3));
+    assert!(all.validate(&json!("abcd"), &JsonPath::root()).is_failure());
+}
+
+#[test]
+fn test_consensus_records_dissent_as_warnings() {
+    let registry = postmortem::SchemaRegistry::new();
+    registry
+        .register("Code", max_len_votes(Quorum::MinAgree(1)))
+        .unwrap();
+    let context = registry.context();
+
+    let result = registry
+        .validate_with_context("Code", &json!("abcdef"), &context)
+        .unwrap();
+    assert!(result.is_success());
+
+    let warnings = context.take_warnings();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(
+        warnings[0].reason,
+        "schema 0 dissented; 1 of 3 accepted the value"
+    );
+    assert!(context.take_warnings().is_empty());
+}
+
+#[test]
+fn test_only_the_accepted_branch_records_warnings() {
+    let registry = SchemaRegistry::new();
+    let card = || {
+        Schema::object()
+            .field("card", Schema::string())
+            .deprecated_field("legacy", "use 'card' instead")
+    };
+    let iban = || Schema::object().field("iban", Schema::string());
+    registry
+        .register("OneOf", Schema::one_of(vec![boxed(card()), boxed(iban())]))
+        .unwrap();
+    registry
+        .register("AnyOf", Schema::any_of(vec![boxed(card()), boxed(iban())]))
+        .unwrap();
+    let context = registry.context();
+
+    let payment = json!({"iban": "x", "legacy": 1});
+    for name in ["OneOf", "AnyOf"] {
+        let result = registry.validate_with_context(name, &payment, &context);
+        assert!(result.unwrap().is_success());
+        assert!(context.take_warnings().is_empty());
+    }
+
+    // The accepted branch keeps its warnings
+    let payment = json!({"card": "x", "legacy": 1});
+    let result = registry.validate_with_context("OneOf", &payment, &context);
+    assert!(result.unwrap().is_success());
+    let warnings = context.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].error.code, "deprecated");
+}
+
+#[test]
+#[should_panic(expected = "consensus quorum of 4 exceeds the 3 schemas")]
+fn test_consensus_quorum_too_large() {
+    max_len_votes(Quorum::MinAgree(4));
+}
+
+// ====== discriminated_union Tests ======
+
+fn shape() -> postmortem::CombinatorSchema {
+    Schema::discriminated_union(
+        "type",
+        [
+            (
+                "circle",
+                boxed(
+                    Schema::object()
+                        .field("type", Schema::string())
+                        .field("radius", Schema::integer().positive()),
+                ),
+            ),
+            (
+                "rect",
+                boxed(
+                    Schema::object()
+                        .field("type", Schema::string())
+                        .field("width", Schema::integer().positive())
+                        .field("height", Schema::integer().positive()),
+                ),
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_discriminated_union_reports_selected_branch() {
+    let schema = shape();
+    let result = schema.validate(&json!({"type": "circle", "radius": 2}), &JsonPath::root());
+    assert_eq!(
+        result.into_result().unwrap(),
+        json!({"type": "circle", "radius": 2})
+    );
+
+    let errors = schema
+        .validate(
+            &json!({"type": "circle", "radius": -1, "width": 3}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().path.to_string(), "radius");
+}
+
+#[test]
+fn test_discriminated_union_tag_errors() {
+    let schema = shape();
+    let errors = schema
+        .validate(&json!({"type": "hexagon"}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    let error = errors.first();
+    assert_eq!(error.code, "unknown_discriminator");
+    assert_eq!(error.path.to_string(), "type");
+    assert_eq!(
+        error.message,
+        "unknown type \"hexagon\", must be one of: circle, rect"
+    );
+
+    let errors = schema
+        .validate(&json!({"radius": 2}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "required");
+    assert_eq!(errors.first().path.to_string(), "type");
+
+    let errors = schema
+        .validate(&json!("circle"), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "invalid_type");
+}
+
+#[test]
+fn test_discriminated_union_json_schema() {
+    let exported = postmortem::ToJsonSchema::to_json_schema(&shape());
+    assert_eq!(exported["discriminator"], json!({"propertyName": "type"}));
+    assert_eq!(
+        exported["oneOf"][1]["properties"]["type"],
+        json!({"const": "rect"})
+    );
+}
+
+#[test]
+#[should_panic(expected = "discriminated_union() has two branches tagged 'circle'")]
+fn test_discriminated_union_duplicate_tag() {
+    Schema::discriminated_union(
+        "type",
+        [
+            ("circle", boxed(Schema::object())),
+            ("circle", boxed(Schema::object())),
+        ],
+    );
+}
+
+// ====== if_then_else Tests ======
+
+fn payment(otherwise: Option<Box<dyn ValueValidator>>) -> postmortem::CombinatorSchema {
+    Schema::if_then_else(
+        boxed(Schema::object().field("type", Schema::string().one_of(["card"]))),
+        boxed(Schema::object().field("card_number", Schema::string().min_len(12))),
+        otherwise,
+    )
+}
+
+#[test]
+fn test_if_then_else_selects_branch() {
+    let schema = payment(Some(boxed(
+        Schema::object().field("iban", Schema::string()),
+    )));
+
+    let errors = schema
+        .validate(&json!({"type": "card"}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().path.to_string(), "card_number");
+
+    let errors = schema
+        .validate(&json!({"type": "transfer"}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().path.to_string(), "iban");
+
+    let result = schema.validate(
+        &json!({"type": "transfer", "iban": "DE89"}),
+        &JsonPath::root(),
+    );
+    assert!(result.is_success());
+}
+
+#[test]
+fn test_if_then_without_else() {
+    let schema = payment(None);
+    let result = schema.validate(&json!({"type": "invoice"}), &JsonPath::root());
+    assert!(result.is_success());
+
+    let context = postmortem::SchemaRegistry::new().context();
+    let result = schema.validate_with_context(
+        &json!({"type": "card", "card_number": "4111"}),
+        &JsonPath::root(),
+        &context,
+    );
+    assert!(result.is_failure());
+}
+
+#[test]
+fn test_if_then_else_json_schema() {
+    let exported = postmortem::ToJsonSchema::to_json_schema(&payment(None));
+    assert_eq!(
+        exported["if"]["properties"]["type"],
+        json!({"type": "string", "enum": ["card"]})
+    );
+    assert_eq!(exported["then"]["required"], json!(["card_number"]));
+    assert!(exported.get("else").is_none());
+}
+
+// ====== one_of_named Tests ======
+
+fn named_shape() -> postmortem::CombinatorSchema {
+    Schema::one_of_named([
+        (
+            "circle",
+            boxed(Schema::object().field("radius", Schema::integer().positive())),
+        ),
+        (
+            "rect",
+            boxed(Schema::object().field("width", Schema::integer().positive())),
+        ),
+    ])
+}
+
+#[test]
+fn test_one_of_named_labels_branches() {
+    let schema = named_shape();
+    assert!(schema
+        .validate(&json!({"radius": 2}), &JsonPath::root())
+        .is_success());
+
+    let errors = schema
+        .validate(&json!({"radius": 2, "width": 3}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "one_of_multiple_matched");
+    assert!(errors.first().message.contains("branches 'circle', 'rect'"));
+
+    let errors = schema
+        .with_branch_errors(true)
+        .validate(&json!({"radius": -2}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "value did not match any of 2 schemas",
+            "branch 'circle': must be positive, got -2",
+            "branch 'rect': required field 'width' is missing",
+        ]
+    );
+}
+
+#[test]
+fn test_one_of_named_json_schema() {
+    let exported = postmortem::ToJsonSchema::to_json_schema(&named_shape());
+    assert_eq!(exported["oneOf"][0]["title"], json!("circle"));
+    assert_eq!(exported["oneOf"][1]["title"], json!("rect"));
+}
+
+#[test]
+#[should_panic(expected = "one_of_named() has two branches named 'circle'")]
+fn test_one_of_named_duplicate_name() {
+    Schema::one_of_named([
+        ("circle", boxed(Schema::object())),
+        ("circle", boxed(Schema::object())),
+    ]);
+}
+
+// ====== Builder Tests ======
+
+#[test]
+fn test_one_of_builder() {
+    let schema = Schema::one_of_builder()
+        .or(Schema::string())
+        .or(Schema::integer())
+        .build();
+    assert!(schema.validate(&json!("a"), &JsonPath::root()).is_success());
+    assert!(schema.validate(&json!(1), &JsonPath::root()).is_success());
+    assert_eq!(
+        postmortem::ToJsonSchema::to_json_schema(&schema),
+        json!({"oneOf": [{"type": "string"}, {"type": "integer"}]})
+    );
+}
+
+#[test]
+fn test_any_of_and_all_of_builders() {
+    let any = Schema::any_of_builder()
+        .or(Schema::string().min_len(5))
+        .or(Schema::integer())
+        .build();
+    let errors = any
+        .validate(&json!("abc"), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "any_of_none_matched");
+
+    let all = Schema::all_of_builder()
+        .and(Schema::string().min_len(2))
+        .and(Schema::string().max_len(4))
+        .build();
+    assert!(all.validate(&json!("abc"), &JsonPath::root()).is_success());
+    assert!(all
+        .validate(&json!("abcde"), &JsonPath::root())
+        .is_failure());
+}
+
+// ====== Operator Tests ======
+
+#[test]
+fn test_bitor_builds_flat_any_of() {
+    let schema = Schema::string() | Schema::integer() | Schema::object();
+    assert!(schema.validate(&json!("a"), &JsonPath::root()).is_success());
+    assert!(schema.validate(&json!({}), &JsonPath::root()).is_success());
+    assert!(schema.validate(&json!([]), &JsonPath::root()).is_failure());
+    assert_eq!(
+        postmortem::ToJsonSchema::to_json_schema(&schema)["anyOf"]
+            .as_array()
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_bitand_builds_flat_all_of() {
+    let schema = Schema::object().field("name", Schema::string())
+        & Schema::object().field("age", Schema::integer())
+        & Schema::object().field("email", Schema::string());
+    let errors = schema
+        .validate(&json!({"name": "Alice"}), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        postmortem::ToJsonSchema::to_json_schema(&schema)["allOf"]
+            .as_array()
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_operators_mix() {
+    let tags = Schema::array(Schema::string()) | Schema::string();
+    let schema = (Schema::string().min_len(2) & Schema::string().max_len(3)) | tags;
+    assert!(schema
+        .validate(&json!("abcdef"), &JsonPath::root())
+        .is_success());
+    assert!(schema
+        .validate(&json!(["x"]), &JsonPath::root())
+        .is_success());
+    assert!(schema.validate(&json!(1), &JsonPath::root()).is_failure());
+}
@@ -1,4 +1,4 @@
-use postmortem::{JsonPath, Schema, SchemaErrors};
+use postmortem::{DependentCondition, JsonPath, Schema, SchemaError, SchemaErrors, ToJsonSchema};
 use serde_json::json;
 use stillwater::Validation;
 
@@ -133,6 +133,144 @@ fn test_require_if_with_required_field() {
     assert!(result.is_success());
 }
 
+#[test]
+fn test_require_unless() {
+    let schema = Schema::object()
+        .optional("sso_provider", Schema::string())
+        .optional("password", Schema::string())
+        .require_unless("sso_provider", |v| v != &json!(""), "password");
+
+    assert!(schema
+        .validate(&json!({"sso_provider": "okta"}), &JsonPath::root())
+        .is_success());
+    assert!(schema
+        .validate(&json!({"password": "hunter2"}), &JsonPath::root())
+        .is_success());
+
+    // Missing or not matching the condition
+    for value in [json!({}), json!({"sso_provider": ""})] {
+        let errors = unwrap_failure(schema.validate(&value, &JsonPath::root()));
+        assert_eq!(errors.first().code, "conditional_required");
+        assert_eq!(errors.first().path.to_string(), "password");
+    }
+}
+
+#[test]
+fn test_forbid_if() {
+    let schema = Schema::object()
+        .field("role", Schema::string())
+        .optional("permissions", Schema::array(Schema::string()))
+        .forbid_if("role", |v| v == &json!("guest"), "permissions");
+
+    assert!(schema
+        .validate(&json!({"role": "guest"}), &JsonPath::root())
+        .is_success());
+    assert!(schema
+        .validate(
+            &json!({"role": "admin", "permissions": ["write"]}),
+            &JsonPath::root()
+        )
+        .is_success());
+
+    let errors = unwrap_failure(schema.validate(
+        &json!({"role": "guest", "permissions": []}),
+        &JsonPath::root(),
+    ));
+    assert_eq!(errors.first().code, "conditional_forbidden");
+    assert_eq!(
+        errors.first().message,
+        "'permissions' is not allowed when 'role' matches condition"
+    );
+}
+
+#[test]
+fn test_dependent_required() {
+    let schema = Schema::object()
+        .field("name", Schema::string())
+        .optional("credit_card", Schema::string().min_len(12))
+        .optional("billing_address", Schema::string())
+        .optional("cvv", Schema::string())
+        .dependent_required("credit_card", ["billing_address", "cvv"]);
+
+    assert!(schema
+        .validate(&json!({"name": "Ada"}), &JsonPath::root())
+        .is_success());
+
+    // One error per missing dependent, even when other fields are invalid
+    let errors = unwrap_failure(schema.validate(
+        &json!({"name": "Ada", "credit_card": "4111", "cvv": "123"}),
+        &JsonPath::root(),
+    ));
+    assert_eq!(errors.len(), 2);
+    let missing = errors.with_code("dependent_required");
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].path.to_string(), "billing_address");
+
+    assert_eq!(
+        schema.to_json_schema()["dependentRequired"],
+        json!({"credit_card": ["billing_address", "cvv"]})
+    );
+}
+
+#[test]
+fn test_dependent_schema() {
+    let schema = Schema::object()
+        .field("kind", Schema::string())
+        .optional("version", Schema::integer())
+        .additional_properties(false)
+        .dependent_schema(
+            "kind",
+            "card",
+            Schema::object().field("card_number", Schema::string().min_len(12)),
+        )
+        .dependent_schema(
+            "version",
+            |v: &serde_json::Value| v.as_i64().is_some_and(|n| n >= 2),
+            Schema::object().field("checksum", Schema::string()),
+        )
+        .dependent_schema(
+            "coupon",
+            DependentCondition::present(),
+            Schema::object().optional("coupon", Schema::string().max_len(8)),
+        );
+
+    let output = schema
+        .validate(
+            &json!({"kind": "card", "card_number": "4111111111111111", "version": 1}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap();
+    assert_eq!(output["card_number"], "4111111111111111");
+
+    // Inactive dependents' fields are unknown; active ones are checked
+    let errors = unwrap_failure(schema.validate(
+        &json!({"kind": "bank", "card_number": "4111", "version": 2, "coupon": "TOO-LONG-CODE"}),
+        &JsonPath::root(),
+    ));
+    let mut codes: Vec<_> = errors.iter().map(|e| e.code.as_str()).collect();
+    codes.sort_unstable();
+    assert_eq!(codes, ["additional_property", "max_length", "required"]);
+    assert_eq!(errors.with_code("required")[0].path.to_string(), "checksum");
+
+    let exported = schema.to_json_schema();
+    assert_eq!(
+        exported["allOf"],
+        json!([{
+            "if": {"properties": {"kind": {"const": "card"}}, "required": ["kind"]},
+            "then": {
+                "type": "object",
+                "properties": {"card_number": {"type": "string", "minLength": 12}},
+                "required": ["card_number"]
+            }
+        }])
+    );
+    assert_eq!(
+        exported["dependentSchemas"]["coupon"]["properties"]["coupon"]["maxLength"],
+        8
+    );
+}
+
 #[test]
 fn test_mutually_exclusive_both_present() {
     let schema = Schema::object()
@@ -222,6 +360,52 @@ fn test_mutually_exclusive_with_null() {
     assert!(result.is_success());
 }
 
+#[test]
+fn test_exactly_one_of() {
+    let schema = Schema::object()
+        .optional("email", Schema::string())
+        .optional("phone", Schema::string())
+        .optional("telegram", Schema::string())
+        .exactly_one_of(["email", "phone", "telegram"]);
+
+    let result = schema.validate(&json!({"telegram": "@ada"}), &JsonPath::root());
+    assert!(result.is_success());
+
+    let errors = unwrap_failure(schema.validate(&json!({}), &JsonPath::root()));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().code, "exactly_one_required");
+
+    let errors = unwrap_failure(schema.validate(
+        &json!({"email": "user@example.com", "telegram": "@ada"}),
+        &JsonPath::root(),
+    ));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().code, "exactly_one_required");
+    assert!(errors
+        .first()
+        .message
+        .contains(r#"got ["email", "telegram"]"#));
+}
+
+#[test]
+fn test_all_or_none() {
+    let schema = Schema::object()
+        .optional("lat", Schema::integer())
+        .optional("lng", Schema::integer())
+        .optional("alt", Schema::integer())
+        .all_or_none(["lat", "lng", "alt"]);
+
+    assert!(schema.validate(&json!({}), &JsonPath::root()).is_success());
+    assert!(schema
+        .validate(&json!({"lat": 52, "lng": 13, "alt": 34}), &JsonPath::root())
+        .is_success());
+
+    let errors = unwrap_failure(schema.validate(&json!({"lat": 52}), &JsonPath::root()));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().code, "all_or_none");
+    assert!(errors.first().message.contains(r#"missing ["lng", "alt"]"#));
+}
+
 #[test]
 fn test_at_least_one_of_none_present() {
     let schema = Schema::object()
@@ -546,6 +730,32 @@ fn test_skip_cross_field_on_errors_disabled() {
     assert!(!errors.with_code("min_length").is_empty());
 }
 
+#[test]
+fn test_custom_raw_sees_invalid_fields() {
+    let schema = Schema::object()
+        .field("start", Schema::integer())
+        .field("end", Schema::integer())
+        .skip_cross_field_on_errors(false)
+        .custom_raw(|obj, raw, path| {
+            assert!(obj.get("start").is_none());
+            assert_eq!(obj.get("end"), Some(&json!(5)));
+            if raw.get("start") == Some(&json!("later")) {
+                Validation::Failure(SchemaErrors::single(
+                    SchemaError::new(path.push_field("start"), "start must be a time")
+                        .with_code("start_not_a_time"),
+                ))
+            } else {
+                Validation::Success(())
+            }
+        });
+
+    let errors =
+        unwrap_failure(schema.validate(&json!({"start": "later", "end": 5}), &JsonPath::root()));
+    assert_eq!(errors.len(), 2);
+    assert!(!errors.with_code("invalid_type").is_empty());
+    assert_eq!(errors.with_code("start_not_a_time").len(), 1);
+}
+
 #[test]
 fn test_cross_field_error_accumulation() {
     let schema = Schema::object()
@@ -573,6 +783,34 @@ fn test_cross_field_error_accumulation() {
     assert!(!errors.with_code("field_not_less_than").is_empty());
 }
 
+#[test]
+fn test_validated_object_typed_getters() {
+    let schema = Schema::object()
+        .field("qty", Schema::integer())
+        .field("sku", Schema::string())
+        .field("tags", Schema::array(Schema::string()))
+        .additional_properties(true)
+        .custom(|obj, _path| {
+            assert_eq!(obj.get_i64("qty"), Some(3));
+            assert_eq!(obj.get_f64("qty"), Some(3.0));
+            assert_eq!(obj.get_str("sku"), Some("A-1"));
+            assert_eq!(obj.get_array("tags").map(Vec::len), Some(2));
+            assert_eq!(obj.get_bool("express"), Some(true));
+            // Wrong types are None rather than a default
+            assert_eq!(obj.get_i64("sku"), None);
+            assert_eq!(obj.get_str("missing"), None);
+            obj.require("gift").map(|_| ())
+        });
+
+    let errors = unwrap_failure(schema.validate(
+        &json!({"qty": 3, "sku": "A-1", "tags": ["a", "b"], "express": true}),
+        &JsonPath::root().push_index(2),
+    ));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().code, "required");
+    assert_eq!(errors.first().path.to_string(), "[2].gift");
+}
+
 #[test]
 fn test_validated_object_has_method() {
     let schema = Schema::object()
@@ -589,3 +827,81 @@ fn test_validated_object_has_method() {
     let result = schema.validate(&json!({}), &JsonPath::root());
     assert!(result.is_success());
 }
+
+fn order_schema() -> postmortem::ObjectSchema {
+    Schema::object()
+        .field(
+            "items",
+            Schema::array(Schema::object().additional_properties(true)),
+        )
+        .additional_properties(true)
+        .amounts_sum_to("items[*].amount", "total")
+        .currency_consistent("items[*].currency", "currency")
+}
+
+#[test]
+fn test_amounts_sum_to_mixes_numbers_and_strings() {
+    let schema = order_schema();
+
+    let order = json!({
+        "items": [{"amount": 0.1}, {"amount": "0.2"}, {"amount": 12}],
+        "total": "12.30"
+    });
+    assert!(schema.validate(&order, &JsonPath::root()).is_success());
+
+    let order = json!({
+        "items": [{"amount": "19.99"}, {"amount": "5"}],
+        "total": 25
+    });
+    let errors = unwrap_failure(schema.validate(&order, &JsonPath::root()));
+    assert_eq!(errors.len(), 1);
+    let error = errors.first();
+    assert_eq!(error.code, "sum_mismatch");
+    assert_eq!(error.path.to_string(), "total");
+    assert_eq!(error.expected.as_deref(), Some("24.99"));
+    assert_eq!(
+        error.message,
+        "'total' is 25 but the amounts add up to 24.99"
+    );
+}
+
+#[test]
+fn test_amounts_sum_to_skips_unparseable_values() {
+    let schema = order_schema();
+
+    for order in [
+        json!({"items": [{"amount": "1"}]}),
+        json!({"items": [{"amount": "1"}], "total": "n/a"}),
+        json!({"items": [{"amount": "1,5"}], "total": "3"}),
+    ] {
+        assert!(schema.validate(&order, &JsonPath::root()).is_success());
+    }
+    // Items without an amount add nothing
+    let order = json!({"items": [{"amount": "4.5"}, {}], "total": "4.50"});
+    assert!(schema.validate(&order, &JsonPath::root()).is_success());
+}
+
+#[test]
+fn test_currency_consistent() {
+    let schema = order_schema();
+
+    let order = json!({
+        "items": [{"currency": "usd"}, {"currency": "EUR"}, {"currency": "GBP"}],
+        "currency": "USD"
+    });
+    let errors = unwrap_failure(schema.validate(&order, &JsonPath::root()));
+    let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+    assert_eq!(paths, ["items[1].currency", "items[2].currency"]);
+    assert!(errors.iter().all(|e| e.code == "currency_mismatch"));
+    assert_eq!(errors.first().got.as_deref(), Some("EUR"));
+
+    // Without a top-level currency, items must agree with the first
+    let order = json!({"items": [{"currency": "EUR"}, {"currency": "eur"}, {"currency": "USD"}]});
+    let errors = unwrap_failure(schema.validate(&order, &JsonPath::root()));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.first().path.to_string(), "items[2].currency");
+    assert_eq!(
+        errors.first().message,
+        "currency 'USD' does not match 'EUR' from 'items[0].currency'"
+    );
+}
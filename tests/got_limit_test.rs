@@ -0,0 +1,115 @@
+//! Tests for the settings for recorded values.
+
+use postmortem::{
+    set_value_previews, GotLimit, JsonPath, Schema, SchemaError, SchemaLike, SchemaRegistry,
+    ValueValidator,
+};
+use serde_json::json;
+
+#[test]
+fn test_got_limit_applies_per_validation() {
+    let long = "a".repeat(10_000);
+    let registry = SchemaRegistry::new();
+    registry
+        .register("Code", Schema::string().max_len(10).pattern("^b").unwrap())
+        .unwrap();
+    let got = |context: &postmortem::validation::ValidationContext, value: &str| {
+        registry
+            .validate_with_context("Code", &json!(value), context)
+            .unwrap()
+            .into_result()
+            .unwrap_err()
+            .iter()
+            .map(|error| error.got.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Truncated to the default of 256 characters
+    let pattern_got = got(&registry.context(), &long)[1].clone().unwrap();
+    assert_eq!(pattern_got.chars().count(), 257);
+    assert!(pattern_got.ends_with('…'));
+
+    // Short values are recorded unchanged
+    assert_eq!(
+        got(&registry.context(), "aaaaaaaaaaaa")[1].as_deref(),
+        Some("aaaaaaaaaaaa")
+    );
+
+    let truncated = registry.context().with_got_limit(GotLimit::Truncate(4));
+    assert_eq!(got(&truncated, &long)[1].as_deref(), Some("aaaa…"));
+    let omitted = registry.context().with_got_limit(GotLimit::Omit);
+    assert_eq!(got(&omitted, &long), vec![None, None]);
+    let unlimited = registry.context().with_got_limit(GotLimit::Unlimited);
+    assert_eq!(got(&unlimited, &long)[1].as_deref(), Some(long.as_str()));
+
+    // The registry's limit applies to its contexts, and custom errors too
+    let registry = SchemaRegistry::new().with_got_limit(GotLimit::Truncate(4));
+    registry
+        .register(
+            "Custom",
+            Schema::string().custom(|_, path| {
+                stillwater::Validation::Failure(postmortem::SchemaErrors::single(
+                    SchemaError::new(path.clone(), "custom").with_got("abcdef"),
+                ))
+            }),
+        )
+        .unwrap();
+    let errors = registry
+        .validate("Custom", &json!("x"))
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().got.as_deref(), Some("abcd…"));
+
+    // Schemas validated directly keep the default
+    assert_eq!(
+        SchemaError::new(JsonPath::root(), "custom")
+            .with_got(&long)
+            .got
+            .map(|got| got.chars().count()),
+        Some(257)
+    );
+}
+
+#[test]
+fn test_value_previews_apply_to_type_errors() {
+    // Type errors record a preview of objects and arrays once enabled
+    let shape = Schema::one_of(vec![
+        Box::new(Schema::object().field("radius", Schema::integer())) as Box<dyn ValueValidator>,
+        Box::new(Schema::object().field("width", Schema::integer())) as Box<dyn ValueValidator>,
+    ]);
+    let value = json!({"kind": "circle", "r": 5});
+    let type_got = |value: &serde_json::Value| {
+        Schema::integer()
+            .validate(value, &JsonPath::root())
+            .into_result()
+            .unwrap_err()
+            .first()
+            .got
+            .clone()
+    };
+    let union_got = |value: &serde_json::Value| {
+        shape
+            .validate(value, &JsonPath::root())
+            .into_result()
+            .unwrap_err()
+            .first()
+            .got
+            .clone()
+    };
+
+    assert_eq!(type_got(&value).as_deref(), Some("object"));
+    assert_eq!(union_got(&value), None);
+
+    set_value_previews(true);
+    let preview = r#"{"kind":"circle","r":5} (2 keys)"#;
+    assert_eq!(type_got(&value).as_deref(), Some(preview));
+    assert_eq!(union_got(&value).as_deref(), Some(preview));
+    assert_eq!(
+        type_got(&json!([[1], 2])).as_deref(),
+        Some("[[...],2] (2 items)")
+    );
+    assert_eq!(type_got(&json!("x")).as_deref(), Some("string"));
+
+    set_value_previews(false);
+}
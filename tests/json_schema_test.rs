@@ -65,6 +65,99 @@ fn test_string_schema_with_datetime_format() {
     assert_eq!(json_schema["format"], "date-time");
 }
 
+#[test]
+fn test_string_schema_with_phone_format() {
+    let schema = Schema::string().phone();
+    let json_schema = schema.to_json_schema();
+
+    assert_eq!(json_schema["type"], "string");
+    assert_eq!(json_schema["format"], "phone");
+}
+
+#[test]
+fn test_string_schema_with_slug_and_identifier_formats() {
+    assert_eq!(Schema::string().slug().to_json_schema()["format"], "slug");
+    assert_eq!(
+        Schema::string().identifier().to_json_schema()["format"],
+        "identifier"
+    );
+}
+
+#[test]
+fn test_string_schema_with_binary_encodings() {
+    let base64 = Schema::string().base64().to_json_schema();
+    assert_eq!(base64["contentEncoding"], "base64");
+    assert!(base64.get("format").is_none());
+
+    let hex = Schema::string().hex().to_json_schema();
+    assert_eq!(hex["contentEncoding"], "base16");
+
+    let base64url = Schema::string().base64url().to_json_schema();
+    assert_eq!(base64url["format"], "base64url");
+}
+
+#[test]
+fn test_string_schema_with_jwt_format() {
+    let schema = Schema::string().jwt();
+    let json_schema = schema.to_json_schema();
+
+    assert_eq!(json_schema["type"], "string");
+    assert_eq!(json_schema["format"], "jwt");
+}
+
+#[test]
+fn test_string_schema_with_duration_formats() {
+    assert_eq!(
+        Schema::string().duration().to_json_schema()["format"],
+        "duration"
+    );
+    assert_eq!(
+        Schema::string().human_duration().to_json_schema()["format"],
+        "human-duration"
+    );
+}
+
+#[test]
+fn test_string_schema_with_mac_address_format() {
+    assert_eq!(
+        Schema::string().mac_address().to_json_schema()["format"],
+        "mac"
+    );
+}
+
+#[test]
+fn test_string_schema_with_locale_formats() {
+    assert_eq!(
+        Schema::string().country_code().to_json_schema()["format"],
+        "iso-3166-alpha-2"
+    );
+    assert_eq!(
+        Schema::string().currency_code().to_json_schema()["format"],
+        "iso-4217"
+    );
+    assert_eq!(
+        Schema::string().language_tag().to_json_schema()["format"],
+        "bcp47"
+    );
+}
+
+#[test]
+fn test_string_schema_with_embedded_json() {
+    let schema = Schema::string().json().to_json_schema();
+    assert_eq!(schema["contentMediaType"], "application/json");
+    assert!(schema.get("contentSchema").is_none());
+
+    let schema = Schema::string()
+        .json_matching(Schema::object().field("id", Schema::integer()))
+        .to_json_schema();
+    assert_eq!(schema["contentMediaType"], "application/json");
+    assert_eq!(schema["contentSchema"]["type"], "object");
+    assert_eq!(
+        schema["contentSchema"]["properties"]["id"]["type"],
+        "integer"
+    );
+}
+
 #[test]
 fn test_string_schema_with_enum() {
     let schema = Schema::string().one_of(["pending", "active", "completed"]);
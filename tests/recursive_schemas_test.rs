@@ -384,3 +384,134 @@ fn test_invalid_data_in_recursive_structure() {
 
     assert!(result.is_failure());
 }
+
+fn comment() -> postmortem::LazySchema {
+    Schema::recursive(|comment| {
+        Schema::object()
+            .field("text", Schema::string().min_len(1))
+            .optional("replies", Schema::array(comment))
+    })
+}
+
+fn nested(levels: usize) -> serde_json::Value {
+    (0..levels).fold(
+        json!({"text": "leaf"}),
+        |reply, _| json!({"text": "re", "replies": [reply]}),
+    )
+}
+
+#[test]
+fn test_recursive_without_registry() {
+    use postmortem::{JsonPath, SchemaLike};
+
+    let schema = comment();
+    assert!(schema.validate(&nested(20), &JsonPath::root()).is_success());
+
+    let errors = schema
+        .validate(
+            &json!({"text": "top", "replies": [{"text": "ok"}, {"text": "", "replies": [{}]}]}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap_err();
+    let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+    assert_eq!(paths, ["replies[1].text", "replies[1].replies[0].text"]);
+}
+
+#[test]
+fn test_recursive_depth_limit() {
+    use postmortem::{JsonPath, SchemaLike};
+
+    let schema = comment().max_depth(10);
+    assert!(schema.validate(&nested(9), &JsonPath::root()).is_success());
+    let errors = schema
+        .validate(&nested(10), &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "max_depth_exceeded");
+
+    // Through a registry, the registry's limit applies
+    let registry = SchemaRegistry::new().with_max_depth(5);
+    registry.register("Comment", comment()).unwrap();
+    let result = registry.validate("Comment", &nested(5)).unwrap();
+    assert_eq!(
+        result.into_result().unwrap_err().first().code,
+        "max_depth_exceeded"
+    );
+    assert!(registry
+        .validate("Comment", &nested(3))
+        .unwrap()
+        .is_success());
+}
+
+fn category() -> impl ValueValidator {
+    Schema::object()
+        .field("name", Schema::string())
+        .optional("children", Schema::array(Schema::lazy(category)))
+}
+
+#[test]
+fn test_lazy_self_reference() {
+    use postmortem::{JsonPath, SchemaLike};
+
+    let schema = Schema::lazy(category);
+    let tree = json!({"name": "a", "children": [{"name": "b", "children": [{"name": 3}]}]});
+    let errors = schema
+        .validate(&tree, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(
+        errors.first().path.to_string(),
+        "children[0].children[0].name"
+    );
+}
+
+#[test]
+fn test_recursive_json_schema_export() {
+    let exported = postmortem::ToJsonSchema::to_json_schema(&comment());
+    let anchor = exported["$anchor"].as_str().unwrap();
+    assert_eq!(
+        exported["properties"]["replies"]["items"],
+        json!({"$ref": format!("#{}", anchor)})
+    );
+
+    let exported = postmortem::ToJsonSchema::to_json_schema(&Schema::lazy(category));
+    let anchor = exported["$anchor"].as_str().unwrap();
+    assert_eq!(
+        exported["properties"]["children"]["items"],
+        json!({"$ref": format!("#{}", anchor)})
+    );
+}
+
+fn level(n: i64) -> postmortem::ObjectSchema {
+    let schema = Schema::object().field("depth", Schema::integer().min(n).max(n));
+    if n == 0 {
+        schema.field("leaf", Schema::ref_("Leaf"))
+    } else {
+        schema.optional("child", Schema::lazy(move || level(n - 1)))
+    }
+}
+
+#[test]
+fn test_lazy_closures_with_different_captures_are_distinct() {
+    use postmortem::{JsonPath, SchemaLike, ToJsonSchema};
+
+    let schema = Schema::lazy(|| level(2));
+    let exported = ToJsonSchema::to_json_schema(&schema);
+    assert!(!exported.to_string().contains("#lazy-"));
+    assert_eq!(
+        exported["properties"]["child"]["properties"]["child"]["properties"]["depth"]["minimum"],
+        0
+    );
+
+    let mut refs = Vec::new();
+    SchemaLike::collect_refs(&schema, &mut refs);
+    assert_eq!(refs, ["Leaf"]);
+
+    let tree = json!({"depth": 2, "child": {"depth": 1, "child": {"depth": 5}}});
+    let errors = schema
+        .validate(&tree, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().path.to_string(), "child.child.depth");
+}
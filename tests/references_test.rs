@@ -296,3 +296,61 @@ fn test_ref_resolution_error() {
 
     assert!(result.is_failure());
 }
+
+#[test]
+fn test_missing_reference_suggests_close_names() {
+    let registry = SchemaRegistry::new();
+    registry.register("User", Schema::string()).unwrap();
+    registry.register("Order", Schema::string()).unwrap();
+    registry
+        .register(
+            "Wrapper",
+            Schema::object().field("user", Schema::ref_("Usr")),
+        )
+        .unwrap();
+
+    let result = registry
+        .validate("Wrapper", &json!({"user": "alice"}))
+        .unwrap();
+    let errors = result.into_result().unwrap_err();
+    let error = errors.first();
+    assert_eq!(error.code, "missing_reference");
+    assert_eq!(error.path.to_string(), "user");
+    assert!(error.message.contains("did you mean 'User'"));
+    assert!(!error.message.contains("Order"));
+}
+
+#[test]
+fn test_missing_reference_without_close_names() {
+    let registry = SchemaRegistry::new();
+    registry.register("User", Schema::string()).unwrap();
+    registry.register("Root", Schema::ref_("Invoice")).unwrap();
+
+    let result = registry.validate("Root", &json!("x")).unwrap();
+    let errors = result.into_result().unwrap_err();
+    assert_eq!(
+        errors.first().message,
+        "schema 'Invoice' not found in registry"
+    );
+}
+
+#[test]
+fn test_validate_batch_reports_missing_reference_per_value() {
+    let registry = SchemaRegistry::new();
+    registry.register("User", Schema::string()).unwrap();
+    registry
+        .register("Row", Schema::object().field("owner", Schema::ref_("Usr")))
+        .unwrap();
+
+    let values: Vec<_> = (0..3)
+        .map(|i| json!({"owner": format!("u{}", i)}))
+        .collect();
+    let results = registry.validate_batch("Row", &values).unwrap();
+
+    assert_eq!(results.len(), 3);
+    for result in results {
+        let errors = result.into_result().unwrap_err();
+        assert_eq!(errors.first().code, "missing_reference");
+        assert!(errors.first().message.contains("did you mean 'User'"));
+    }
+}
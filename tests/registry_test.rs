@@ -1,7 +1,13 @@
 //! Tests for schema registry operations.
 
-use postmortem::{Schema, SchemaRegistry};
+use postmortem::validation::ValidationMode;
+use postmortem::{
+    FieldDoc, MetadataField, OutcomeCache, RegistryError, Schema, SchemaError, SchemaMetadata,
+    SchemaRegistry, Transform,
+};
 use serde_json::json;
+use std::sync::{Arc, Mutex};
+use stillwater::Validation;
 
 #[test]
 fn test_register_and_get() {
@@ -220,3 +226,469 @@ fn test_registry_with_array_of_refs() {
     let result = registry.validate("UserList", &json!([1, -2, 3])).unwrap();
     assert!(result.is_failure());
 }
+
+#[test]
+fn test_failure_hook_receives_correlation_id() {
+    let registry = SchemaRegistry::new();
+    registry
+        .register("UserId", Schema::integer().positive())
+        .unwrap();
+    registry
+        .register(
+            "User",
+            Schema::object()
+                .field("id", Schema::ref_("UserId"))
+                .field("name", Schema::string().min_len(1)),
+        )
+        .unwrap();
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let (line_sink, report_sink) = (Arc::clone(&lines), Arc::clone(&reports));
+    let context = registry
+        .context()
+        .with_correlation_id("req-42")
+        .on_failure(move |report| {
+            line_sink.lock().unwrap().push(report.to_string());
+            report_sink.lock().unwrap().push(report.to_json());
+        });
+    assert_eq!(context.increment_depth().correlation_id(), Some("req-42"));
+
+    let valid = registry
+        .validate_with_context("User", &json!({"id": 1, "name": "Ann"}), &context)
+        .unwrap();
+    assert!(valid.is_success());
+    assert!(reports.lock().unwrap().is_empty());
+
+    let invalid = registry
+        .validate_with_context("User", &json!({"id": 0, "name": ""}), &context)
+        .unwrap();
+    assert!(invalid.is_failure());
+
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("[req-42] User failed validation: "));
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports[0]["schema"], "User");
+    assert_eq!(reports[0]["correlation_id"], "req-42");
+    assert_eq!(reports[0]["errors"][0]["path"], "id");
+    assert_eq!(reports[0]["errors"][1]["path"], "name");
+    assert_eq!(reports[0]["errors"][1]["code"], "min_length");
+}
+
+#[test]
+fn test_error_factory_applies_to_all_validation_methods() {
+    let registry = SchemaRegistry::new().with_error_factory(|info, _, path| {
+        SchemaError::new(path.clone(), info.message.to_uppercase())
+            .with_code(format!("acme.{}", info.code))
+    });
+    registry
+        .register("Email", Schema::string().email())
+        .unwrap();
+    registry
+        .register(
+            "User",
+            Schema::object().field("email", Schema::ref_("Email")),
+        )
+        .unwrap();
+
+    let invalid = json!({"email": "nope"});
+    let codes = |result: Validation<serde_json::Value, postmortem::SchemaErrors>| {
+        let errors = result.into_result().unwrap_err();
+        errors.iter().map(|e| e.code.clone()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        codes(registry.validate("User", &invalid).unwrap()),
+        vec!["acme.invalid_email"]
+    );
+    let batch = registry.validate_batch("User", [&invalid]).unwrap();
+    assert_eq!(
+        codes(batch.into_iter().next().unwrap()),
+        vec!["acme.invalid_email"]
+    );
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    let context = registry.context().on_failure(move |report| {
+        sink.lock()
+            .unwrap()
+            .push(report.errors.first().code.clone());
+    });
+    registry
+        .validate_with_context("User", &invalid, &context)
+        .unwrap();
+    assert_eq!(*seen.lock().unwrap(), vec!["acme.invalid_email"]);
+}
+
+#[test]
+fn test_doc_links_on_nested_fields() {
+    let registry = SchemaRegistry::new().with_doc_links();
+    registry
+        .register(
+            "Order",
+            Schema::object()
+                .field("id", Schema::integer())
+                .field(
+                    "lines",
+                    Schema::array(
+                        Schema::object()
+                            .field("sku", Schema::string().min_len(3))
+                            .doc(FieldDoc::default().link("https://docs.example.com/sku")),
+                    ),
+                )
+                .doc(FieldDoc::new("Items ordered")),
+        )
+        .unwrap();
+
+    let errors = registry
+        .validate(
+            "Order",
+            &json!({"id": "x", "lines": [{"sku": "ok1"}, {"sku": "a"}]}),
+        )
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages.len(), 2);
+    assert!(!messages[0].contains("see docs"));
+    assert!(messages[1].ends_with("(see docs: https://docs.example.com/sku)"));
+
+    assert_eq!(
+        registry
+            .doc_for_path("Order", "lines")
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("Items ordered")
+    );
+    assert!(registry.doc_for_path("Order", "lines.sku").is_some());
+    assert!(registry.doc_for_path("Order", "id").is_none());
+    assert!(registry.doc_for_path("Missing", "id").is_none());
+}
+
+#[test]
+fn test_outcome_cache_reuses_outcomes_until_registration() {
+    let cache = OutcomeCache::new(100);
+    let registry = SchemaRegistry::new()
+        .with_outcome_cache(cache.clone())
+        .with_error_factory(|info, _, path| {
+            SchemaError::new(path.clone(), info.message).with_code(format!("acme.{}", info.code))
+        });
+    registry
+        .register(
+            "User",
+            Schema::object().field("address", Schema::ref_("Address")),
+        )
+        .unwrap();
+
+    let user = json!({"address": {"city": "Oslo"}});
+    for _ in 0..2 {
+        let errors = registry
+            .validate("User", &user)
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.first().code, "acme.missing_reference");
+    }
+    let batch = registry.validate_batch("User", [&user]).unwrap();
+    assert!(batch[0].is_failure());
+    assert_eq!((cache.stats().hits, cache.stats().misses), (2, 1));
+
+    // Registering the missing schema invalidates the cached failure
+    registry
+        .register("Address", Schema::object().field("city", Schema::string()))
+        .unwrap();
+    assert_eq!(cache.stats().entries, 0);
+    assert!(registry.validate("User", &user).unwrap().is_success());
+    assert_eq!(cache.stats().misses, 2);
+}
+
+#[test]
+fn test_outcome_cache_separates_validation_modes() {
+    let registry = SchemaRegistry::new().with_outcome_cache(OutcomeCache::new(100));
+    registry
+        .register(
+            "User",
+            Schema::object()
+                .optional("id", Schema::integer())
+                .read_only()
+                .field("name", Schema::string()),
+        )
+        .unwrap();
+
+    let user = json!({"id": 7, "name": "Ada"});
+    let update = registry.context().with_mode(ValidationMode::Update);
+    let result = registry.validate_with_context("User", &user, &update);
+    assert!(result.unwrap().is_success());
+
+    let create = registry.context().with_mode(ValidationMode::Create);
+    let errors = registry
+        .validate_with_context("User", &user, &create)
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().code, "read_only");
+}
+
+#[test]
+fn test_outcome_cache_shared_by_registries() {
+    let cache = OutcomeCache::new(100);
+    let strict = SchemaRegistry::new().with_outcome_cache(cache.clone());
+    strict
+        .register("Age", Schema::integer().non_negative())
+        .unwrap();
+    let loose = SchemaRegistry::new().with_outcome_cache(cache.clone());
+    loose.register("Age", Schema::integer()).unwrap();
+
+    assert!(loose.validate("Age", &json!(-1)).unwrap().is_success());
+    assert!(strict.validate("Age", &json!(-1)).unwrap().is_failure());
+    assert_eq!(cache.stats().hits, 0);
+}
+
+#[test]
+fn test_outcome_cache_replays_warnings() {
+    let cache = OutcomeCache::new(100);
+    let registry = SchemaRegistry::new().with_outcome_cache(cache.clone());
+    registry
+        .register(
+            "User",
+            Schema::object()
+                .field("id", Schema::integer())
+                .deprecated_field("legacy_id", "use 'id' instead"),
+        )
+        .unwrap();
+
+    let user = json!({"id": 7, "legacy_id": 7});
+    for _ in 0..2 {
+        let context = registry.context();
+        let result = registry.validate_with_context("User", &user, &context);
+        assert!(result.unwrap().is_success());
+        let warnings = context.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].error.code, "deprecated");
+    }
+    assert_eq!(cache.stats().hits, 1);
+}
+
+#[test]
+fn test_outcome_cache_skips_computed_defaults() {
+    let cache = OutcomeCache::new(100);
+    let registry = SchemaRegistry::new().with_outcome_cache(cache.clone());
+    let next = std::sync::atomic::AtomicU64::new(1);
+    registry
+        .register(
+            "Request",
+            Schema::object().default_with("id", Schema::integer(), move || {
+                json!(next.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+            }),
+        )
+        .unwrap();
+
+    let ids: Vec<_> = (0..2)
+        .map(|_| {
+            registry
+                .validate("Request", &json!({}))
+                .unwrap()
+                .into_result()
+                .unwrap()["id"]
+                .clone()
+        })
+        .collect();
+    assert_eq!(ids, [json!(1), json!(2)]);
+    assert_eq!((cache.stats().hits, cache.stats().entries), (0, 0));
+
+    // A given value computes nothing, so its outcome is cached
+    for _ in 0..2 {
+        assert!(registry
+            .validate("Request", &json!({"id": 9}))
+            .unwrap()
+            .is_success());
+    }
+    assert_eq!(cache.stats().hits, 1);
+}
+
+#[test]
+fn test_transforms_apply_to_values_validated_by_name() {
+    let registry = SchemaRegistry::new();
+    let name = Schema::object()
+        .field("first_name", Schema::string())
+        .field("last_name", Schema::string())
+        .additional_properties(false);
+    registry.register("Name", name).unwrap();
+    registry
+        .register(
+            "Customer",
+            Schema::object().field("name", Schema::ref_("Name")),
+        )
+        .unwrap();
+
+    assert!(registry.set_transform("Missing", Transform::new()).is_err());
+    registry
+        .set_transform(
+            "Name",
+            Transform::new().split("full_name", " ", ["first_name", "last_name"]),
+        )
+        .unwrap();
+
+    let results = registry
+        .validate_batch(
+            "Name",
+            [
+                &json!({"full_name": "Ada Lovelace"}),
+                &json!({"first_name": "Ada", "last_name": "Lovelace"}),
+                &json!({"full_name": "Ada"}),
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        results[0].clone().into_result().unwrap(),
+        json!({"first_name": "Ada", "last_name": "Lovelace"})
+    );
+    assert!(results[1].is_success());
+    assert!(results[2].is_failure());
+
+    // References are validated as sent
+    let customer = json!({"name": {"full_name": "Ada Lovelace"}});
+    assert!(registry
+        .validate("Customer", &customer)
+        .unwrap()
+        .is_failure());
+}
+
+#[test]
+fn test_schema_metadata() {
+    let registry = SchemaRegistry::new();
+    let metadata = SchemaMetadata::new()
+        .owner("ada@example.com")
+        .team("billing")
+        .version("1.2.0");
+    registry
+        .register_with_metadata("Invoice", Schema::object(), metadata.clone())
+        .unwrap();
+    registry.register("Scratch", Schema::string()).unwrap();
+
+    // Clones share the metadata with the registry
+    let clone = registry.clone();
+    assert_eq!(clone.metadata("Invoice"), Some(metadata));
+    assert_eq!(clone.metadata("Scratch"), Some(SchemaMetadata::new()));
+    assert_eq!(clone.metadata("Missing"), None);
+}
+
+#[test]
+fn test_registration_hooks_reject_schemas() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let registry = SchemaRegistry::new()
+        .require_metadata([MetadataField::Owner, MetadataField::Reviewers])
+        .with_registration_hook(move |name, _| {
+            log.lock().unwrap().push(name.to_string());
+            if name.starts_with("Tmp") {
+                Err("temporary schemas are not allowed".to_string())
+            } else {
+                Ok(())
+            }
+        });
+
+    match registry.register("User", Schema::object()) {
+        Err(RegistryError::Rejected { name, reason }) => {
+            assert_eq!(name, "User");
+            assert_eq!(reason, "missing required metadata: owner, reviewers");
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+    assert!(registry.get("User").is_none());
+    // The first rejection stops the remaining hooks
+    assert!(seen.lock().unwrap().is_empty());
+
+    let reviewed = SchemaMetadata::new()
+        .owner("identity")
+        .reviewer("grace@example.com");
+    let error = registry
+        .register_with_metadata("TmpUser", Schema::object(), reviewed.clone())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "schema 'TmpUser' was rejected: temporary schemas are not allowed"
+    );
+
+    registry
+        .register_with_metadata("User", Schema::object(), reviewed)
+        .unwrap();
+    assert!(registry.get("User").is_some());
+    assert_eq!(*seen.lock().unwrap(), ["TmpUser", "User"]);
+}
+
+#[test]
+fn test_transaction_commits_batch() {
+    let registry = SchemaRegistry::new();
+    registry.register("Email", Schema::string()).unwrap();
+    registry
+        .transaction(|tx| {
+            // References may point forward within the batch
+            tx.register(
+                "Order",
+                Schema::object().field("customer", Schema::ref_("Customer")),
+            );
+            tx.register_with_metadata(
+                "Customer",
+                Schema::object().field("email", Schema::ref_("Email")),
+                SchemaMetadata::new().team("crm"),
+            );
+        })
+        .unwrap();
+
+    assert_eq!(registry.names(), ["Customer", "Email", "Order"]);
+    assert_eq!(
+        registry.metadata("Customer").unwrap().team.as_deref(),
+        Some("crm")
+    );
+    assert!(registry.validate_refs().is_empty());
+    assert!(registry
+        .validate("Order", &json!({"customer": {"email": 7}}))
+        .unwrap()
+        .is_failure());
+}
+
+#[test]
+fn test_transaction_rolls_back() {
+    let registry = SchemaRegistry::new().with_registration_hook(|name, _| {
+        if name.starts_with("Tmp") {
+            Err("temporary schemas are not allowed".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    registry.register("Email", Schema::string()).unwrap();
+
+    let error = registry
+        .transaction(|tx| {
+            tx.register("User", Schema::object())
+                .register("Email", Schema::string())
+                .register("TmpUser", Schema::object())
+                .register("User", Schema::object())
+                .register(
+                    "Order",
+                    Schema::object()
+                        .field("customer", Schema::ref_("Customer"))
+                        .field("items", Schema::array(Schema::ref_("Item"))),
+                );
+        })
+        .unwrap_err();
+
+    let RegistryError::Multiple(errors) = &error else {
+        panic!("expected several errors, got {:?}", error);
+    };
+    assert_eq!(errors.len(), 4);
+    assert!(matches!(&errors[0], RegistryError::Rejected { name, .. } if name == "TmpUser"));
+    assert!(matches!(&errors[1], RegistryError::DuplicateName(name) if name == "Email"));
+    assert!(matches!(&errors[2], RegistryError::DuplicateName(name) if name == "User"));
+    assert_eq!(
+        errors[3].to_string(),
+        "schema 'Order' references unregistered schemas: Customer, Item"
+    );
+
+    // Nothing from the batch was registered
+    assert_eq!(registry.names(), ["Email"]);
+}
@@ -0,0 +1,310 @@
+//! Tests for building schemas from `validator` crate attributes.
+
+use std::sync::LazyLock;
+
+use postmortem::compat::validator::{CompatType, FieldRules, StructRules};
+use postmortem::{validator_schema, JsonPath, Schema, ToJsonSchema};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use validator::Validate;
+
+static USERNAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-z0-9_]+$").unwrap());
+
+validator_schema! {
+    #[derive(Debug, Deserialize, Validate)]
+    pub struct Address {
+        #[validate(length(min = 1, message = "city is required"))]
+        pub city: String,
+        #[validate(length(equal = 5))]
+        pub zip: String,
+    }
+}
+
+validator_schema! {
+    #[derive(Debug, Deserialize, Validate)]
+    pub struct SignupForm {
+        #[validate(length(min = 3, max = 20), regex(path = *USERNAME))]
+        pub username: String,
+        #[validate(email(message = "invalid email"))]
+        pub email: String,
+        #[validate(range(min = 18, max = 150))]
+        pub age: u32,
+        #[validate(range(exclusive_min = 0.0, max = 1.0))]
+        pub ratio: f64,
+        #[validate(url)]
+        pub homepage: Option<String>,
+        #[validate(does_not_contain(pattern = "admin"))]
+        pub display_name: String,
+        #[validate(contains(pattern = "@"))]
+        #[serde(rename = "contactHandle")]
+        pub contact: String,
+        pub password: String,
+        #[validate(must_match(other = "password"))]
+        pub password_confirm: String,
+        #[validate(length(max = 3))]
+        pub tags: Vec<String>,
+        pub newsletter: bool,
+        #[validate(nested)]
+        pub address: Address,
+    }
+}
+
+fn valid_signup() -> serde_json::Value {
+    json!({
+        "username": "alice_1",
+        "email": "alice@example.com",
+        "age": 30,
+        "ratio": 0.5,
+        "homepage": null,
+        "display_name": "Alice",
+        "contactHandle": "@alice",
+        "password": "hunter22",
+        "password_confirm": "hunter22",
+        "tags": ["a", "b"],
+        "newsletter": true,
+        "address": {"city": "Berlin", "zip": "10115"}
+    })
+}
+
+#[test]
+fn test_valid_payload_passes() {
+    let schema = SignupForm::postmortem_schema().unwrap();
+    let result = schema.validate(&valid_signup(), &JsonPath::root());
+    assert!(result.is_success());
+}
+
+#[test]
+fn test_accumulates_all_rule_violations() {
+    let schema = SignupForm::postmortem_schema().unwrap();
+    let mut value = valid_signup();
+    value["username"] = json!("Al");
+    value["email"] = json!("nope");
+    value["age"] = json!(12);
+    value["ratio"] = json!(0.0);
+    value["homepage"] = json!("not a url");
+    value["display_name"] = json!("the admin");
+    value["contactHandle"] = json!("alice");
+    value["tags"] = json!(["a", "b", "c", "d"]);
+    value["newsletter"] = json!("yes");
+    value["address"] = json!({"city": "", "zip": "123"});
+
+    let errors = schema
+        .validate(&value, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+
+    let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+    for expected in [
+        "username",
+        "email",
+        "age",
+        "ratio",
+        "homepage",
+        "display_name",
+        "contactHandle",
+        "tags",
+        "newsletter",
+        "address.city",
+        "address.zip",
+    ] {
+        assert!(paths.iter().any(|p| p == expected), "missing {}", expected);
+    }
+
+    let email = errors.with_code("invalid_email");
+    assert_eq!(email.len(), 1);
+    assert_eq!(email[0].message, "invalid email");
+
+    assert_eq!(errors.with_code("forbidden_substring").len(), 1);
+    assert_eq!(
+        errors.at_path(&JsonPath::root().push_field("address").push_field("city"))[0].message,
+        "city is required"
+    );
+}
+
+#[test]
+fn test_must_match_runs_once_fields_are_valid() {
+    let schema = SignupForm::postmortem_schema().unwrap();
+    let mut value = valid_signup();
+    value["password_confirm"] = json!("hunter23");
+
+    let errors = schema
+        .validate(&value, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors.first().message.contains("password"));
+}
+
+#[test]
+fn test_type_mismatches_are_reported() {
+    let schema = SignupForm::postmortem_schema().unwrap();
+    let mut value = valid_signup();
+    value["age"] = json!("thirty");
+    value["username"] = json!(42);
+
+    let errors = schema
+        .validate(&value, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.with_code("invalid_type").len(), 2);
+}
+
+#[test]
+fn test_optional_field_may_be_absent() {
+    let schema = SignupForm::postmortem_schema().unwrap();
+    let mut value = valid_signup();
+    value.as_object_mut().unwrap().remove("homepage");
+
+    assert!(schema.validate(&value, &JsonPath::root()).is_success());
+}
+
+#[test]
+fn test_valid_payload_deserializes_into_struct() {
+    let form: SignupForm = serde_json::from_value(valid_signup()).unwrap();
+    assert_eq!(form.contact, "@alice");
+    assert!(form.validate().is_ok());
+}
+
+#[test]
+fn test_struct_still_validates_with_validator() {
+    let address = Address {
+        city: String::new(),
+        zip: "10115".to_string(),
+    };
+    assert!(address.validate().is_err());
+}
+
+#[test]
+fn test_json_schema_output() {
+    let schema = Address::postmortem_schema().unwrap().to_json_schema();
+    assert_eq!(schema["properties"]["zip"]["minLength"], 5);
+    assert_eq!(schema["properties"]["zip"]["maxLength"], 5);
+    assert_eq!(schema["required"], json!(["city", "zip"]));
+}
+
+validator_schema! {
+    #[derive(Debug, Deserialize, Validate)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    pub struct Profile {
+        #[validate(length(min = 2, code = "name_too_short"))]
+        pub display_name: String,
+        #[serde(rename = "mail")]
+        #[validate(email(code = "bad_email", message = "not an email"))]
+        pub email_address: String,
+        #[serde(default)]
+        pub bio: String,
+        #[serde(skip)]
+        pub cached_score: u32,
+    }
+}
+
+#[test]
+fn test_codes_replace_constraint_codes() {
+    let schema = Profile::postmortem_schema().unwrap();
+    let errors = schema
+        .validate(
+            &json!({"displayName": "A", "mail": "nope"}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.with_code("name_too_short").len(), 1);
+    let email = errors.with_code("bad_email");
+    assert_eq!(email.len(), 1);
+    assert_eq!(email[0].message, "not an email");
+}
+
+#[test]
+fn test_serde_attributes_shape_the_schema() {
+    let schema = Profile::postmortem_schema().unwrap();
+    let profile = json!({"displayName": "Ada", "mail": "ada@example.com"});
+    assert!(schema.validate(&profile, &JsonPath::root()).is_success());
+    let parsed: Profile = serde_json::from_value(profile).unwrap();
+    assert_eq!(parsed.bio, "");
+
+    let errors = schema
+        .validate(
+            &json!({"displayName": "Ada", "mail": "ada@example.com", "cachedScore": 3}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.first().path.to_string(), "cachedScore");
+}
+
+#[test]
+fn test_rules_that_do_not_fit_the_type_are_errors() {
+    let fields = StructRules::new();
+    let error =
+        match <u32 as CompatType>::rules()
+            .begin()
+            .email()
+            .apply(Schema::object(), "age", &fields)
+        {
+            Ok(_) => panic!("email on an integer was accepted"),
+            Err(error) => error,
+        };
+    assert_eq!(
+        error.to_string(),
+        "validator rule `email` on field 'age' is not supported for integer fields"
+    );
+
+    // A nested struct whose schema failed fails its parent too
+    let nested = FieldRules::object(Err(error.clone()));
+    match nested.apply(Schema::object(), "inner", &fields) {
+        Ok(_) => panic!("the nested error was dropped"),
+        Err(nested) => assert_eq!(nested, error),
+    }
+}
+
+validator_schema! {
+    #[derive(Debug, Deserialize, Validate)]
+    pub struct PinChange {
+        #[serde(rename = "newPin")]
+        pub new_pin: String,
+        #[validate(must_match(other = "new_pin"))]
+        pub confirm_pin: String,
+    }
+}
+
+#[test]
+fn test_must_match_reads_renamed_fields() {
+    let schema = PinChange::postmortem_schema().unwrap();
+    let matching = json!({"newPin": "1234", "confirm_pin": "1234"});
+    assert!(schema.validate(&matching, &JsonPath::root()).is_success());
+
+    let errors = schema
+        .validate(
+            &json!({"newPin": "1234", "confirm_pin": "4321"}),
+            &JsonPath::root(),
+        )
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_exclusive_bounds_at_integer_limits() {
+    let fields = StructRules::new();
+    let schema = <i64 as CompatType>::rules()
+        .begin()
+        .exclusive_min(i64::MAX as f64)
+        .apply(Schema::object(), "above", &fields)
+        .unwrap();
+    let schema = <i64 as CompatType>::rules()
+        .begin()
+        .exclusive_max(i64::MIN as f64)
+        .apply(schema, "below", &fields)
+        .unwrap();
+
+    let limits = json!({"above": i64::MAX, "below": i64::MIN});
+    assert!(schema.validate(&limits, &JsonPath::root()).is_success());
+
+    let inside = json!({"above": 0, "below": 0});
+    let errors = schema
+        .validate(&inside, &JsonPath::root())
+        .into_result()
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+}